@@ -0,0 +1,252 @@
+/*
+	Copyright 2021 Integritee AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Declarative routing of parentchain events to trusted calls.
+//!
+//! An [`EventRoute`] matches a parentchain event by pallet and event name, plus a set of
+//! predicates on the decoded event fields, and maps it to a trusted call. The [`EventRouter`]
+//! holds the routing table of a parentchain, signs the resulting calls with the enclave's
+//! key and submits them to the top pool.
+
+use codec::{Decode, Encode};
+use ita_stf::{Getter, TrustedCall, TrustedCallSigned};
+use itc_parentchain_indirect_calls_executor::error::Error;
+use itp_api_client_types::StaticEvent;
+use itp_stf_primitives::{traits::IndirectExecutor, types::TrustedOperation};
+use itp_types::parentchain::{AccountId, ParentchainId, RawEvent};
+use log::*;
+use std::{boxed::Box, vec::Vec};
+
+/// Information available to predicates and call constructors when routing an event.
+pub struct RoutingContext<'a> {
+	pub parentchain_id: ParentchainId,
+	pub enclave_account: &'a AccountId,
+	pub vault_account: &'a AccountId,
+}
+
+/// Predicate on the fields of a decoded event.
+pub type FieldPredicate<E> = fn(&E, &RoutingContext) -> bool;
+
+/// Maps a matching event to the trusted call that will be signed by the enclave.
+pub type IntoTrustedCall<E> = fn(&E, &RoutingContext) -> TrustedCall;
+
+/// Type erased [`EventRoute`], such that routes of different events fit into one table.
+pub trait RouteEvent {
+	/// Returns the trusted call if the event matches the route.
+	fn route(&self, event: &RawEvent, context: &RoutingContext) -> Option<TrustedCall>;
+}
+
+/// Route for the parentchain event `E`, which is identified by `E::PALLET` and `E::EVENT`.
+pub struct EventRoute<E> {
+	predicates: Vec<FieldPredicate<E>>,
+	into_call: IntoTrustedCall<E>,
+}
+
+impl<E: StaticEvent + Decode> EventRoute<E> {
+	pub fn new(into_call: IntoTrustedCall<E>) -> Self {
+		Self { predicates: Vec::new(), into_call }
+	}
+
+	/// Only route events for which `predicate` holds. All predicates must hold.
+	pub fn when(mut self, predicate: FieldPredicate<E>) -> Self {
+		self.predicates.push(predicate);
+		self
+	}
+}
+
+impl<E: StaticEvent + Decode> RouteEvent for EventRoute<E> {
+	fn route(&self, event: &RawEvent, context: &RoutingContext) -> Option<TrustedCall> {
+		let event = match event.as_event::<E>()? {
+			Ok(event) => event,
+			Err(e) => {
+				warn!(
+					"[{}] Could not decode {}.{} event: {:?}",
+					context.parentchain_id,
+					E::PALLET,
+					E::EVENT,
+					e
+				);
+				return None
+			},
+		};
+
+		if !self.predicates.iter().all(|predicate| predicate(&event, context)) {
+			return None
+		}
+		Some((self.into_call)(&event, context))
+	}
+}
+
+/// Routing table of a parentchain.
+#[derive(Default)]
+pub struct EventRouter {
+	routes: Vec<Box<dyn RouteEvent>>,
+}
+
+impl EventRouter {
+	pub fn with_route<E: StaticEvent + Decode + 'static>(mut self, route: EventRoute<E>) -> Self {
+		self.routes.push(Box::new(route));
+		self
+	}
+
+	/// Returns the trusted calls of all routes matching the events, in the order of the events.
+	pub fn route_events(&self, events: &[RawEvent], context: &RoutingContext) -> Vec<TrustedCall> {
+		events
+			.iter()
+			.flat_map(|event| {
+				self.routes.iter().filter_map(move |route| route.route(event, context))
+			})
+			.collect()
+	}
+
+	/// Signs the trusted calls of all matching routes with the enclave's key and submits them.
+	pub fn dispatch<Executor: IndirectExecutor<TrustedCallSigned, Error>>(
+		&self,
+		executor: &Executor,
+		events: &[RawEvent],
+		parentchain_id: ParentchainId,
+		vault_account: &AccountId,
+	) -> Result<(), Error> {
+		let enclave_account = executor.get_enclave_account()?;
+		let context =
+			RoutingContext { parentchain_id, enclave_account: &enclave_account, vault_account };
+
+		self.route_events(events, &context).into_iter().try_for_each(|trusted_call| {
+			info!(
+				"[{}] routing parentchain event to trusted call {:?}",
+				parentchain_id, trusted_call
+			);
+			submit_signed_by_self(executor, &trusted_call)
+		})
+	}
+}
+
+fn submit_signed_by_self<Executor: IndirectExecutor<TrustedCallSigned, Error>>(
+	executor: &Executor,
+	trusted_call: &TrustedCall,
+) -> Result<(), Error> {
+	let shard = executor.get_default_shard();
+	let signed_trusted_call = executor.sign_call_with_self(trusted_call, &shard)?;
+	let trusted_operation =
+		TrustedOperation::<TrustedCallSigned, Getter>::indirect_call(signed_trusted_call);
+
+	let encrypted_trusted_call = executor.encrypt(&trusted_operation.encode())?;
+	executor.submit_trusted_call(shard, encrypted_trusted_call);
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use itp_types::parentchain::{AssetTransfer, BalanceTransfer};
+
+	fn shield_transfers_to_vault() -> EventRouter {
+		EventRouter::default().with_route(
+			EventRoute::<BalanceTransfer>::new(|event, context| {
+				TrustedCall::balance_shield(
+					context.enclave_account.clone(),
+					event.from.clone(),
+					event.amount,
+					context.parentchain_id,
+				)
+			})
+			.when(|event, context| event.to == *context.vault_account),
+		)
+	}
+
+	fn transfer(from: u8, to: u8, amount: u128) -> RawEvent {
+		RawEvent::new(&BalanceTransfer { from: [from; 32].into(), to: [to; 32].into(), amount })
+	}
+
+	fn context<'a>(enclave: &'a AccountId, vault: &'a AccountId) -> RoutingContext<'a> {
+		RoutingContext {
+			parentchain_id: ParentchainId::Integritee,
+			enclave_account: enclave,
+			vault_account: vault,
+		}
+	}
+
+	#[test]
+	fn matching_event_is_routed_to_trusted_call() {
+		let (enclave, vault) = (AccountId::from([1u8; 32]), AccountId::from([2u8; 32]));
+
+		let calls = shield_transfers_to_vault()
+			.route_events(&[transfer(3, 2, 42)], &context(&enclave, &vault));
+
+		assert_eq!(
+			calls,
+			vec![TrustedCall::balance_shield(
+				enclave.clone(),
+				[3u8; 32].into(),
+				42,
+				ParentchainId::Integritee
+			)]
+		);
+	}
+
+	#[test]
+	fn event_failing_predicate_is_not_routed() {
+		let (enclave, vault) = (AccountId::from([1u8; 32]), AccountId::from([2u8; 32]));
+
+		let calls = shield_transfers_to_vault()
+			.route_events(&[transfer(3, 4, 42)], &context(&enclave, &vault));
+
+		assert!(calls.is_empty());
+	}
+
+	#[test]
+	fn events_of_other_pallets_are_not_routed() {
+		let (enclave, vault) = (AccountId::from([1u8; 32]), AccountId::from([2u8; 32]));
+		let asset_transfer = RawEvent::new(&AssetTransfer {
+			asset_id: 1,
+			from: [3u8; 32].into(),
+			to: vault.clone(),
+			amount: 42,
+		});
+
+		let calls =
+			shield_transfers_to_vault().route_events(&[asset_transfer], &context(&enclave, &vault));
+
+		assert!(calls.is_empty());
+	}
+
+	#[test]
+	fn events_are_routed_in_order() {
+		let (enclave, vault) = (AccountId::from([1u8; 32]), AccountId::from([2u8; 32]));
+		let events = [transfer(3, 2, 1), transfer(3, 4, 2), transfer(5, 2, 3)];
+
+		let calls = shield_transfers_to_vault().route_events(&events, &context(&enclave, &vault));
+
+		assert_eq!(
+			calls,
+			vec![
+				TrustedCall::balance_shield(
+					enclave.clone(),
+					[3u8; 32].into(),
+					1,
+					ParentchainId::Integritee
+				),
+				TrustedCall::balance_shield(
+					enclave.clone(),
+					[5u8; 32].into(),
+					3,
+					ParentchainId::Integritee
+				),
+			]
+		);
+	}
+}
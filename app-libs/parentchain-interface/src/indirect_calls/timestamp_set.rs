@@ -15,39 +15,28 @@
 
 */
 
-use crate::{Integritee, ParentchainInstance, TargetA, TargetB};
 use codec::{Compact, Decode, Encode};
-use core::{any::TypeId, marker::PhantomData};
 use ita_stf::{Getter, TrustedCall, TrustedCallSigned};
 use itc_parentchain_indirect_calls_executor::{
 	error::{Error, Result},
-	IndirectDispatch,
+	GetParentchainId, IndirectDispatch,
 };
 use itp_stf_primitives::{traits::IndirectExecutor, types::TrustedOperation};
-use itp_types::{parentchain::ParentchainId, Moment};
+use itp_types::Moment;
 use log::info;
 
 #[derive(Debug, Clone, Encode, Decode, Eq, PartialEq)]
-pub struct TimestampSetArgs<I: ParentchainInstance> {
+pub struct TimestampSetArgs {
 	now: Compact<Moment>,
-	_phantom: PhantomData<I>,
 }
 
-impl<Executor: IndirectExecutor<TrustedCallSigned, Error>, I: ParentchainInstance + 'static>
-	IndirectDispatch<Executor, TrustedCallSigned> for TimestampSetArgs<I>
+impl<Executor: IndirectExecutor<TrustedCallSigned, Error> + GetParentchainId>
+	IndirectDispatch<Executor, TrustedCallSigned> for TimestampSetArgs
 {
 	fn dispatch(&self, executor: &Executor) -> Result<()> {
 		info!("Found TimestampSet extrinsic in block: now = {:?}", self.now);
 		let enclave_account_id = executor.get_enclave_account()?;
-		let parentchain_id = if TypeId::of::<I>() == TypeId::of::<Integritee>() {
-			ParentchainId::Integritee
-		} else if TypeId::of::<I>() == TypeId::of::<TargetA>() {
-			ParentchainId::TargetA
-		} else if TypeId::of::<I>() == TypeId::of::<TargetB>() {
-			ParentchainId::TargetB
-		} else {
-			return Err(Error::Other("unknown parentchain instance".into()))
-		};
+		let parentchain_id = executor.parentchain_id();
 		let trusted_call =
 			TrustedCall::timestamp_set(enclave_account_id, self.now.0, parentchain_id);
 		let shard = executor.get_default_shard();
@@ -15,14 +15,16 @@
 
 */
 
+use crate::event_routing::{EventRoute, EventRouter};
 use codec::Encode;
 
 pub use ita_sgx_runtime::{Balance, Index};
-use ita_stf::{Getter, TrustedCall, TrustedCallSigned};
+use ita_stf::{TrustedCall, TrustedCallSigned};
 use itc_parentchain_indirect_calls_executor::error::Error;
-use itp_stf_primitives::{traits::IndirectExecutor, types::TrustedOperation};
+use itp_stf_primitives::traits::IndirectExecutor;
 use itp_types::parentchain::{
-	AccountId, FilterEvents, HandleParentchainEvents, ParentchainError, ParentchainId,
+	AccountId, BalanceTransfer, FilterEvents, HandleParentchainEvents, ParentchainError,
+	ParentchainId,
 };
 use itp_utils::hex::hex_encode;
 use log::*;
@@ -30,28 +32,20 @@ use log::*;
 pub struct ParentchainEventHandler {}
 
 impl ParentchainEventHandler {
-	fn shield_funds<Executor: IndirectExecutor<TrustedCallSigned, Error>>(
-		executor: &Executor,
-		account: &AccountId,
-		amount: Balance,
-	) -> Result<(), Error> {
-		log::info!("shielding for {:?} amount {}", account, amount,);
-		let shard = executor.get_default_shard();
-		// todo: ensure this parentchain is assigned for the shard vault!
-		let trusted_call = TrustedCall::balance_shield(
-			executor.get_enclave_account()?,
-			account.clone(),
-			amount,
-			ParentchainId::Integritee,
-		);
-		let signed_trusted_call = executor.sign_call_with_self(&trusted_call, &shard)?;
-		let trusted_operation =
-			TrustedOperation::<TrustedCallSigned, Getter>::indirect_call(signed_trusted_call);
-
-		let encrypted_trusted_call = executor.encrypt(&trusted_operation.encode())?;
-		executor.submit_trusted_call(shard, encrypted_trusted_call);
-
-		Ok(())
+	/// Routing table for the events of the Integritee parentchain.
+	fn event_router() -> EventRouter {
+		EventRouter::default().with_route(
+			EventRoute::<BalanceTransfer>::new(|event, context| {
+				// todo: ensure this parentchain is assigned for the shard vault!
+				TrustedCall::balance_shield(
+					context.enclave_account.clone(),
+					event.from.clone(),
+					event.amount,
+					context.parentchain_id,
+				)
+			})
+			.when(|event, context| event.to == *context.vault_account),
+		)
 	}
 }
 
@@ -65,21 +59,13 @@ where
 		events: impl FilterEvents,
 		vault_account: &AccountId,
 	) -> Result<(), Error> {
-		let filter_events = events.get_transfer_events();
 		trace!(
-			"filtering transfer events to shard vault account: {}",
+			"routing events with shard vault account: {}",
 			hex_encode(vault_account.encode().as_slice())
 		);
-		if let Ok(events) = filter_events {
-			events
-				.iter()
-				.filter(|&event| event.to == *vault_account)
-				.try_for_each(|event| {
-					info!("found transfer_event to vault account: {}", event);
-					//debug!("shielding from Integritee suppressed");
-					Self::shield_funds(executor, &event.from, event.amount)
-					//Err(ParentchainError::FunctionalityDisabled)
-				})
+		if let Ok(events) = events.get_raw_events() {
+			Self::event_router()
+				.dispatch(executor, &events, ParentchainId::Integritee, vault_account)
 				.map_err(|_| ParentchainError::ShieldFundsFailure)?;
 		}
 		Ok(())
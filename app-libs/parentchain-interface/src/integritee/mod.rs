@@ -24,7 +24,6 @@ use crate::{
 	indirect_calls::{
 		invoke::InvokeArgs, shield_funds::ShieldFundsArgs, timestamp_set::TimestampSetArgs,
 	},
-	Integritee,
 };
 use codec::{Decode, Encode};
 pub use event_filter::FilterableEvents;
@@ -33,7 +32,7 @@ use ita_stf::TrustedCallSigned;
 use itc_parentchain_indirect_calls_executor::{
 	error::{Error, Result},
 	filter_metadata::FilterIntoDataFrom,
-	IndirectDispatch,
+	GetParentchainId, IndirectDispatch,
 };
 use itp_api_client_types::ParentchainSignedExtra;
 use itp_node_api::metadata::{
@@ -58,10 +57,10 @@ pub type ParentchainExtrinsicParser = ExtrinsicParser<ParentchainSignedExtra>;
 pub enum IndirectCall {
 	ShieldFunds(ShieldFundsArgs),
 	Invoke(InvokeArgs),
-	TimestampSet(TimestampSetArgs<Integritee>),
+	TimestampSet(TimestampSetArgs),
 }
 
-impl<Executor: IndirectExecutor<TrustedCallSigned, Error>>
+impl<Executor: IndirectExecutor<TrustedCallSigned, Error> + GetParentchainId>
 	IndirectDispatch<Executor, TrustedCallSigned> for IndirectCall
 {
 	fn dispatch(&self, executor: &Executor) -> Result<()> {
@@ -111,7 +110,7 @@ impl<NodeMetadata: EnclaveBridgeCallIndexes + TimestampCallIndexes> FilterIntoDa
 			Some(IndirectCall::Invoke(args))
 		} else if index == metadata.timestamp_set_call_indexes().ok()? {
 			debug!("ExtrinsicFilter: found timestamp set extrinsic");
-			let args = decode_and_log_error::<TimestampSetArgs<Integritee>>(call_args)?;
+			let args = decode_and_log_error::<TimestampSetArgs>(call_args)?;
 			Some(IndirectCall::TimestampSet(args))
 		} else {
 			None
@@ -21,29 +21,18 @@
 #[cfg(all(not(feature = "std"), feature = "sgx"))]
 extern crate sgx_tstd as std;
 
-use codec::{Decode, Encode};
+use codec::Decode;
+use itp_api_client_types::Events;
+use itp_types::{parentchain::AssetTransfer, H256};
+use std::vec::Vec;
 
+pub mod event_routing;
 #[cfg(feature = "std")]
 pub mod event_subscriber;
 pub mod extrinsic_parser;
 pub mod indirect_calls;
 pub mod integritee;
-pub mod target_a;
-pub mod target_b;
-
-pub trait ParentchainInstance {}
-
-#[derive(Debug, Clone, Encode, Decode, Eq, PartialEq)]
-pub struct Integritee;
-impl ParentchainInstance for Integritee {}
-
-#[derive(Debug, Clone, Encode, Decode, Eq, PartialEq)]
-pub struct TargetA;
-impl ParentchainInstance for TargetA {}
-
-#[derive(Debug, Clone, Encode, Decode, Eq, PartialEq)]
-pub struct TargetB;
-impl ParentchainInstance for TargetB {}
+pub mod target;
 
 pub fn decode_and_log_error<V: Decode>(encoded: &mut &[u8]) -> Option<V> {
 	match V::decode(encoded) {
@@ -54,3 +43,18 @@ pub fn decode_and_log_error<V: Decode>(encoded: &mut &[u8]) -> Option<V> {
 		},
 	}
 }
+
+/// Asset transfers among the events of a parentchain block. Events that fail to decode are logged.
+pub fn asset_transfer_events(events: &Events<H256>) -> Vec<AssetTransfer> {
+	events
+		.iter()
+		.flatten() // flatten filters out the nones
+		.filter_map(|ev| match ev.as_event::<AssetTransfer>() {
+			Ok(maybe_event) => maybe_event,
+			Err(e) => {
+				log::error!("Could not decode event: {:?}", e);
+				None
+			},
+		})
+		.collect()
+}
@@ -16,12 +16,14 @@
 */
 //! Various way to filter Parentchain events
 
+use crate::asset_transfer_events;
 use itc_parentchain_indirect_calls_executor::event_filter::ToEvents;
 use itp_api_client_types::Events;
 
 use itp_types::{
 	parentchain::{
-		BalanceTransfer, ExtrinsicFailed, ExtrinsicStatus, ExtrinsicSuccess, FilterEvents,
+		AssetTransfer, BalanceTransfer, ExtrinsicFailed, ExtrinsicStatus, ExtrinsicSuccess,
+		FilterEvents, RawEvent,
 	},
 	H256,
 };
@@ -81,4 +83,21 @@ impl FilterEvents for FilterableEvents {
 			})
 			.collect())
 	}
+
+	fn get_asset_transfer_events(&self) -> core::result::Result<Vec<AssetTransfer>, Self::Error> {
+		Ok(asset_transfer_events(self.to_events()))
+	}
+
+	fn get_raw_events(&self) -> core::result::Result<Vec<RawEvent>, Self::Error> {
+		Ok(self
+			.to_events()
+			.iter()
+			.flatten() // flatten filters out the nones
+			.map(|ev| RawEvent {
+				pallet: ev.pallet_name().into(),
+				variant: ev.variant_name().into(),
+				fields: ev.field_bytes().to_vec(),
+			})
+			.collect())
+	}
 }
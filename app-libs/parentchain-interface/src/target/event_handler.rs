@@ -0,0 +1,88 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+use codec::Encode;
+pub use ita_sgx_runtime::{AssetId, Balance, Index};
+
+use crate::event_routing::{EventRoute, EventRouter};
+use ita_stf::{TrustedCall, TrustedCallSigned};
+use itc_parentchain_indirect_calls_executor::{error::Error, GetParentchainId};
+use itp_stf_primitives::traits::IndirectExecutor;
+use itp_types::parentchain::{
+	AccountId, AssetTransfer, BalanceTransfer, FilterEvents, HandleParentchainEvents,
+	ParentchainError,
+};
+use itp_utils::hex::hex_encode;
+use log::*;
+
+pub struct ParentchainEventHandler {}
+
+impl ParentchainEventHandler {
+	/// Routing table for the events of a target parentchain.
+	fn event_router() -> EventRouter {
+		EventRouter::default()
+			.with_route(
+				EventRoute::<BalanceTransfer>::new(|event, context| {
+					// todo: ensure this parentchain is assigned for the shard vault!
+					TrustedCall::balance_shield(
+						context.enclave_account.clone(),
+						event.from.clone(),
+						event.amount,
+						context.parentchain_id,
+					)
+				})
+				.when(|event, context| event.to == *context.vault_account),
+			)
+			.with_route(
+				EventRoute::<AssetTransfer>::new(|event, context| {
+					TrustedCall::assets_shield(
+						context.enclave_account.clone(),
+						event.from.clone(),
+						event.asset_id,
+						event.amount,
+						context.parentchain_id,
+					)
+				})
+				.when(|event, context| event.to == *context.vault_account),
+			)
+	}
+}
+
+impl<Executor> HandleParentchainEvents<Executor, TrustedCallSigned, Error>
+	for ParentchainEventHandler
+where
+	Executor: IndirectExecutor<TrustedCallSigned, Error> + GetParentchainId,
+{
+	fn handle_events(
+		executor: &Executor,
+		events: impl FilterEvents,
+		vault_account: &AccountId,
+	) -> Result<(), Error> {
+		let parentchain_id = executor.parentchain_id();
+		trace!(
+			"[{}] routing events with shard vault account: {}",
+			parentchain_id,
+			hex_encode(vault_account.encode().as_slice())
+		);
+		if let Ok(events) = events.get_raw_events() {
+			Self::event_router()
+				.dispatch(executor, &events, parentchain_id, vault_account)
+				.map_err(|_| ParentchainError::ShieldFundsFailure)?;
+		}
+		Ok(())
+	}
+}
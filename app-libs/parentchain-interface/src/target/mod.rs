@@ -14,7 +14,6 @@
 	limitations under the License.
 
 */
-
 mod event_filter;
 mod event_handler;
 
@@ -22,7 +21,6 @@ use crate::{
 	decode_and_log_error,
 	extrinsic_parser::{ExtrinsicParser, ParseExtrinsic},
 	indirect_calls::timestamp_set::TimestampSetArgs,
-	TargetB,
 };
 use codec::{Decode, Encode};
 pub use event_filter::FilterableEvents;
@@ -31,7 +29,7 @@ use ita_stf::TrustedCallSigned;
 use itc_parentchain_indirect_calls_executor::{
 	error::{Error, Result},
 	filter_metadata::FilterIntoDataFrom,
-	IndirectDispatch,
+	GetParentchainId, IndirectDispatch,
 };
 use itp_api_client_types::ParentchainSignedExtra;
 use itp_node_api::metadata::pallet_timestamp::TimestampCallIndexes;
@@ -41,13 +39,13 @@ use log::*;
 /// Parses the extrinsics corresponding to the parentchain.
 pub type ParentchainExtrinsicParser = ExtrinsicParser<ParentchainSignedExtra>;
 
-/// The default indirect call (extrinsic-triggered) of the Target-A-Parachain.
+/// The default indirect call (extrinsic-triggered) of a target parentchain.
 #[derive(Debug, Clone, Encode, Decode, Eq, PartialEq)]
 pub enum IndirectCall {
-	TimestampSet(TimestampSetArgs<TargetB>),
+	TimestampSet(TimestampSetArgs),
 }
 
-impl<Executor: IndirectExecutor<TrustedCallSigned, Error>>
+impl<Executor: IndirectExecutor<TrustedCallSigned, Error> + GetParentchainId>
 	IndirectDispatch<Executor, TrustedCallSigned> for IndirectCall
 {
 	fn dispatch(&self, executor: &Executor) -> Result<()> {
@@ -84,7 +82,7 @@ impl<NodeMetadata: TimestampCallIndexes> FilterIntoDataFrom<NodeMetadata> for Ex
 		trace!("ExtrinsicFilter: attempting to execute indirect call with index {:?}", index);
 		if index == metadata.timestamp_set_call_indexes().ok()? {
 			debug!("ExtrinsicFilter: found timestamp set extrinsic");
-			let args = decode_and_log_error::<TimestampSetArgs<TargetB>>(call_args)?;
+			let args = decode_and_log_error::<TimestampSetArgs>(call_args)?;
 			Some(IndirectCall::TimestampSet(args))
 		} else {
 			None
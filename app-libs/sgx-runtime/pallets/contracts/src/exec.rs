@@ -0,0 +1,369 @@
+//! Execution of contracts with wasmi.
+//!
+//! A contract has to export its `memory` as well as a `deploy` function, which is called once
+//! when the contract is instantiated, and a `call` function, which is called by every call of the
+//! contract. Both take no arguments and return nothing, everything else goes through these host
+//! functions, imported from the `env` module:
+//!
+//! * `input_len() -> i32`: length of the input of the call.
+//! * `input_read(ptr: i32)`: copy the input to `ptr`.
+//! * `caller(ptr: i32) -> i32`: copy the encoded account of the caller to `ptr`, returns its length.
+//! * `storage_read(key_ptr: i32, key_len: i32, value_ptr: i32, value_max_len: i32) -> i32`: copy
+//!   up to `value_max_len` bytes of the value stored under the key to `value_ptr`. Returns the full
+//!   length of the value, or [`NO_VALUE`] if there is none.
+//! * `storage_write(key_ptr: i32, key_len: i32, value_ptr: i32, value_len: i32)`
+//! * `storage_clear(key_ptr: i32, key_len: i32)`
+//! * `return_value(ptr: i32, len: i32)`: set the output of the call.
+//! * `deposit_event(ptr: i32, len: i32)`: emit a `ContractEmitted` event.
+//!
+//! Each call of a host function consumes [`HOST_FUNCTION_FUEL`] and [`FUEL_PER_BYTE`] for each
+//! byte it copies or stores.
+//!
+//! Pointers and lengths are unsigned. A contract which traps, runs out of gas or accesses memory
+//! out of bounds fails the call. Its storage changes and events are buffered and only applied once
+//! the execution succeeded, as the state of the enclave can't roll back a transaction. Applying
+//! them fails if the caller can't reserve the storage deposit.
+//!
+//! The memory of a contract is limited to `MaxMemoryPages`, its table to [`MAX_TABLE_ELEMENTS`]
+//! and the stack of the interpreter to [`MAX_VALUE_STACK_HEIGHT`] values and
+//! [`MAX_RECURSION_DEPTH`] nested calls. Growing beyond these limits fails like running out of
+//! memory on the host would, instead of aborting the enclave.
+
+use crate::{pallet::ContractStorage, Config, Error, Event, Pallet};
+use codec::Encode;
+use frame_support::pallet_prelude::Get;
+use log::*;
+use sp_std::{collections::btree_map::BTreeMap, vec, vec::Vec};
+use wasmi::{
+	core::{Pages, Trap, TrapCode},
+	Caller, Engine, Extern, ExternType, Linker, Memory, Module, StackLimits, Store, StoreLimits,
+	StoreLimitsBuilder,
+};
+
+pub const ENV_MODULE: &str = "env";
+pub const MEMORY_EXPORT: &str = "memory";
+pub const DEPLOY_EXPORT: &str = "deploy";
+pub const CALL_EXPORT: &str = "call";
+
+/// Returned by `storage_read` if there is no value stored under the key.
+pub const NO_VALUE: u32 = u32::MAX;
+
+/// Size of a page of wasm memory in bytes.
+pub const WASM_PAGE_SIZE: usize = 64 * 1024;
+
+/// Max number of elements of the table of a contract.
+pub const MAX_TABLE_ELEMENTS: u32 = 1024;
+
+/// Number of values (of 8 bytes) the stack of the interpreter is initialized with.
+pub const INITIAL_VALUE_STACK_HEIGHT: usize = 1024;
+
+/// Max number of values (of 8 bytes) on the stack of the interpreter.
+pub const MAX_VALUE_STACK_HEIGHT: usize = 64 * 1024;
+
+/// Max number of nested function calls.
+pub const MAX_RECURSION_DEPTH: usize = 256;
+
+/// Fuel consumed by each call of a host function.
+pub const HOST_FUNCTION_FUEL: u64 = 1_000;
+
+/// Fuel consumed for each byte a host function copies or stores.
+pub const FUEL_PER_BYTE: u64 = 10;
+
+/// Context of an execution, accessible by the host functions.
+pub struct HostState<T: Config> {
+	contract: T::AccountId,
+	caller: T::AccountId,
+	input: Vec<u8>,
+	output: Vec<u8>,
+	/// changes of the storage of the contract, `None` removes the value
+	storage_changes: BTreeMap<Vec<u8>, Option<Vec<u8>>>,
+	/// data of the events emitted by the contract
+	events: Vec<Vec<u8>>,
+	limits: StoreLimits,
+}
+
+impl<T: Config> HostState<T> {
+	pub fn new(contract: T::AccountId, caller: T::AccountId, input: Vec<u8>) -> Self {
+		let limits = StoreLimitsBuilder::new()
+			.memory_size(T::MaxMemoryPages::get() as usize * WASM_PAGE_SIZE)
+			.table_elements(MAX_TABLE_ELEMENTS)
+			.instances(1)
+			.memories(1)
+			.tables(1)
+			.build();
+		HostState {
+			contract,
+			caller,
+			input,
+			output: Vec::new(),
+			storage_changes: BTreeMap::new(),
+			events: Vec::new(),
+			limits,
+		}
+	}
+
+	/// The value stored under the key, including the changes of this execution.
+	fn storage_value(&self, key: &[u8]) -> Option<Vec<u8>> {
+		match self.storage_changes.get(key) {
+			Some(change) => change.clone(),
+			None => <ContractStorage<T>>::get(&self.contract, key),
+		}
+	}
+
+	/// Settle the storage deposit, apply the storage changes and deposit the events of a
+	/// successful execution.
+	///
+	/// Returns the output of the execution.
+	fn commit(self) -> Result<Vec<u8>, Error<T>> {
+		let (mut bytes_added, mut bytes_removed) = (0u64, 0u64);
+		for (key, change) in &self.storage_changes {
+			let stored_len = <ContractStorage<T>>::decode_len(&self.contract, key)
+				.map_or(0, |len| key.len() + len);
+			let changed_len = change.as_ref().map_or(0, |value| key.len() + value.len());
+			if changed_len > stored_len {
+				bytes_added += (changed_len - stored_len) as u64;
+			} else {
+				bytes_removed += (stored_len - changed_len) as u64;
+			}
+		}
+		Pallet::<T>::settle_storage_deposit(
+			&self.contract,
+			&self.caller,
+			bytes_added,
+			bytes_removed,
+		)?;
+
+		for (key, change) in self.storage_changes {
+			match change {
+				Some(value) => <ContractStorage<T>>::insert(&self.contract, key, value),
+				None => <ContractStorage<T>>::remove(&self.contract, key),
+			}
+		}
+		for data in self.events {
+			let contract = self.contract.clone();
+			Pallet::<T>::deposit_event(Event::ContractEmitted { contract, data });
+		}
+		Ok(self.output)
+	}
+}
+
+pub struct ExecReturn {
+	pub output: Vec<u8>,
+	pub gas_used: u64,
+}
+
+fn engine() -> Engine {
+	let stack_limits =
+		StackLimits::new(INITIAL_VALUE_STACK_HEIGHT, MAX_VALUE_STACK_HEIGHT, MAX_RECURSION_DEPTH)
+			.expect("initial stack height does not exceed the max stack height; qed");
+	let mut config = wasmi::Config::default();
+	config.consume_fuel(true).set_stack_limits(stack_limits);
+	Engine::new(&config)
+}
+
+/// Check that the code is a valid wasm module, whose memory does not exceed `MaxMemoryPages`.
+///
+/// A memory which is not exported is only limited when the contract is instantiated.
+pub fn validate<T: Config>(code: &[u8]) -> Result<(), Error<T>> {
+	let module = Module::new(&engine(), code).map_err(|e| {
+		debug!("Invalid contract code: {:?}", e);
+		Error::<T>::InvalidCode
+	})?;
+
+	if let Some(ExternType::Memory(memory)) = module.get_export(MEMORY_EXPORT) {
+		let max_pages = T::MaxMemoryPages::get();
+		let exceeds_max_pages = |pages: Pages| u32::from(pages) > max_pages;
+		if exceeds_max_pages(memory.initial_pages())
+			|| memory.maximum_pages().map_or(false, exceeds_max_pages)
+		{
+			return Err(Error::<T>::MemoryTooLarge)
+		}
+	}
+	Ok(())
+}
+
+/// Instantiate the code and run its export `entry_point`, with at most `gas_limit` fuel.
+///
+/// Nothing is written to the state unless the execution succeeds and the caller can reserve the
+/// storage deposit.
+pub fn execute<T: Config>(
+	code: &[u8],
+	entry_point: &str,
+	state: HostState<T>,
+	gas_limit: u64,
+) -> Result<ExecReturn, Error<T>> {
+	let engine = engine();
+	let module = Module::new(&engine, code).map_err(|_| Error::<T>::InvalidCode)?;
+	let mut store = Store::new(&engine, state);
+	store.limiter(|state| &mut state.limits);
+	store.add_fuel(gas_limit).map_err(|_| Error::<T>::ContractTrapped)?;
+
+	let instance = linker::<T>(&engine)
+		.and_then(|linker| linker.instantiate(&mut store, &module))
+		.and_then(|instance| instance.start(&mut store))
+		.map_err(|e| {
+			debug!("Contract instantiation failed: {:?}", e);
+			Error::<T>::ContractTrapped
+		})?;
+	let function = instance
+		.get_typed_func::<(), ()>(&store, entry_point)
+		.map_err(|_| Error::<T>::MissingExport)?;
+	function.call(&mut store, ()).map_err(|e| {
+		debug!("Contract execution of {} failed: {:?}", entry_point, e);
+		Error::<T>::ContractTrapped
+	})?;
+
+	let gas_used = store.fuel_consumed().unwrap_or_default();
+	let output = store.into_data().commit()?;
+	Ok(ExecReturn { output, gas_used })
+}
+
+fn linker<T: Config>(engine: &Engine) -> Result<Linker<HostState<T>>, wasmi::Error> {
+	let mut linker = Linker::new(engine);
+	linker.func_wrap(
+		ENV_MODULE,
+		"input_len",
+		|mut caller: Caller<'_, HostState<T>>| -> Result<u32, Trap> {
+			consume_fuel(&mut caller, 0)?;
+			Ok(caller.data().input.len() as u32)
+		},
+	)?;
+	linker.func_wrap(
+		ENV_MODULE,
+		"input_read",
+		|mut caller: Caller<'_, HostState<T>>, ptr: u32| -> Result<(), Trap> {
+			let input = caller.data().input.clone();
+			consume_fuel(&mut caller, input.len() as u64)?;
+			write_memory(&mut caller, ptr, &input)
+		},
+	)?;
+	linker.func_wrap(
+		ENV_MODULE,
+		"caller",
+		|mut caller: Caller<'_, HostState<T>>, ptr: u32| -> Result<u32, Trap> {
+			let account = caller.data().caller.encode();
+			consume_fuel(&mut caller, account.len() as u64)?;
+			write_memory(&mut caller, ptr, &account)?;
+			Ok(account.len() as u32)
+		},
+	)?;
+	linker.func_wrap(
+		ENV_MODULE,
+		"storage_read",
+		|mut caller: Caller<'_, HostState<T>>,
+		 key_ptr: u32,
+		 key_len: u32,
+		 value_ptr: u32,
+		 value_max_len: u32|
+		 -> Result<u32, Trap> {
+			consume_fuel(&mut caller, key_len.into())?;
+			let key = read_memory(&caller, key_ptr, key_len)?;
+			match caller.data().storage_value(&key) {
+				Some(value) => {
+					consume_fuel_for_bytes(&mut caller, value.len() as u64)?;
+					let len = value.len().min(value_max_len as usize);
+					write_memory(&mut caller, value_ptr, &value[..len])?;
+					Ok(value.len() as u32)
+				},
+				None => Ok(NO_VALUE),
+			}
+		},
+	)?;
+	linker.func_wrap(
+		ENV_MODULE,
+		"storage_write",
+		|mut caller: Caller<'_, HostState<T>>,
+		 key_ptr: u32,
+		 key_len: u32,
+		 value_ptr: u32,
+		 value_len: u32|
+		 -> Result<(), Trap> {
+			consume_fuel(&mut caller, u64::from(key_len) + u64::from(value_len))?;
+			let key = read_memory(&caller, key_ptr, key_len)?;
+			let value = read_memory(&caller, value_ptr, value_len)?;
+			caller.data_mut().storage_changes.insert(key, Some(value));
+			Ok(())
+		},
+	)?;
+	linker.func_wrap(
+		ENV_MODULE,
+		"storage_clear",
+		|mut caller: Caller<'_, HostState<T>>, key_ptr: u32, key_len: u32| -> Result<(), Trap> {
+			consume_fuel(&mut caller, key_len.into())?;
+			let key = read_memory(&caller, key_ptr, key_len)?;
+			caller.data_mut().storage_changes.insert(key, None);
+			Ok(())
+		},
+	)?;
+	linker.func_wrap(
+		ENV_MODULE,
+		"return_value",
+		|mut caller: Caller<'_, HostState<T>>, ptr: u32, len: u32| -> Result<(), Trap> {
+			consume_fuel(&mut caller, len.into())?;
+			let output = read_memory(&caller, ptr, len)?;
+			caller.data_mut().output = output;
+			Ok(())
+		},
+	)?;
+	linker.func_wrap(
+		ENV_MODULE,
+		"deposit_event",
+		|mut caller: Caller<'_, HostState<T>>, ptr: u32, len: u32| -> Result<(), Trap> {
+			consume_fuel(&mut caller, len.into())?;
+			let data = read_memory(&caller, ptr, len)?;
+			caller.data_mut().events.push(data);
+			Ok(())
+		},
+	)?;
+	Ok(linker)
+}
+
+/// Consume the fuel of a host function call which copies or stores `bytes`.
+fn consume_fuel<T: Config>(caller: &mut Caller<'_, HostState<T>>, bytes: u64) -> Result<(), Trap> {
+	consume_fuel_for_bytes(caller, bytes)?;
+	caller.consume_fuel(HOST_FUNCTION_FUEL).map_err(|_| TrapCode::OutOfFuel)?;
+	Ok(())
+}
+
+/// Consume the fuel for `bytes` a host function copies on top of those it was charged for.
+fn consume_fuel_for_bytes<T: Config>(
+	caller: &mut Caller<'_, HostState<T>>,
+	bytes: u64,
+) -> Result<(), Trap> {
+	caller
+		.consume_fuel(FUEL_PER_BYTE.saturating_mul(bytes))
+		.map_err(|_| TrapCode::OutOfFuel)?;
+	Ok(())
+}
+
+fn memory<T: Config>(caller: &Caller<'_, HostState<T>>) -> Result<Memory, Trap> {
+	caller
+		.get_export(MEMORY_EXPORT)
+		.and_then(Extern::into_memory)
+		.ok_or_else(|| Trap::new("contract does not export its memory"))
+}
+
+fn read_memory<T: Config>(
+	caller: &Caller<'_, HostState<T>>,
+	ptr: u32,
+	len: u32,
+) -> Result<Vec<u8>, Trap> {
+	if len > T::MaxValueSize::get() {
+		return Err(Trap::new("value too large"))
+	}
+	let mut buffer = vec![0u8; len as usize];
+	memory(caller)?
+		.read(caller, ptr as usize, &mut buffer)
+		.map_err(|_| Trap::new("memory access out of bounds"))?;
+	Ok(buffer)
+}
+
+fn write_memory<T: Config>(
+	caller: &mut Caller<'_, HostState<T>>,
+	ptr: u32,
+	data: &[u8],
+) -> Result<(), Trap> {
+	memory(caller)?
+		.write(caller, ptr as usize, data)
+		.map_err(|_| Trap::new("memory access out of bounds"))
+}
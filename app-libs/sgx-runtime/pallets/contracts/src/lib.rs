@@ -0,0 +1,295 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Confidential smart contracts in WebAssembly.
+//!
+//! Code is uploaded once and can then be instantiated by anyone. Each instance gets its own
+//! account and key-value storage. Contracts are executed with wasmi and metered with its fuel,
+//! see [`exec`] for the host functions available to them.
+//!
+//! The caller of a contract reserves a deposit of `DepositPerByte` for each byte the call adds to
+//! the storage of the contract, keys included. A call which removes bytes returns the deposit for
+//! them to its caller, as far as the caller has deposited for the contract.
+//!
+//! All of this happens inside the enclave, so neither the code, nor the inputs, nor the storage of
+//! a contract are visible outside of the shard.
+
+use codec::{Decode, Encode};
+use frame_support::{
+	ensure,
+	traits::{Currency, ReservableCurrency},
+};
+pub use pallet::*;
+use scale_info::TypeInfo;
+use sp_io::hashing::blake2_256;
+use sp_runtime::{
+	traits::{Hash, Saturating, TrailingZeroInput, Zero},
+	SaturatedConversion,
+};
+use sp_std::vec::Vec;
+
+pub mod exec;
+
+pub type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+#[derive(Encode, Decode, Clone, PartialEq, Eq, sp_core::RuntimeDebug, TypeInfo)]
+pub struct ContractInfo<AccountId, CodeHash> {
+	/// the code run by the contract
+	pub code_hash: CodeHash,
+	/// the account which instantiated the contract. only they can read its storage with a getter
+	pub deployer: AccountId,
+}
+
+/// Derives the account of a contract. Instantiating the same code twice requires a different salt.
+pub fn contract_address<AccountId: Encode + Decode, CodeHash: Encode>(
+	deployer: &AccountId,
+	code_hash: &CodeHash,
+	salt: &[u8],
+) -> AccountId {
+	let entropy = (b"contract", deployer, code_hash, salt).using_encoded(blake2_256);
+	Decode::decode(&mut TrailingZeroInput::new(entropy.as_ref()))
+		.expect("infinite length input; no invalid inputs for type; qed")
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
+	#[pallet::without_storage_info]
+	pub struct Pallet<T>(PhantomData<T>);
+
+	/// Configuration trait.
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// max size of uploaded code in bytes
+		#[pallet::constant]
+		type MaxCodeSize: Get<u32>;
+
+		/// max size of inputs, outputs, storage keys, storage values and events of contracts
+		#[pallet::constant]
+		type MaxValueSize: Get<u32>;
+
+		/// max fuel a single execution of a contract may consume
+		#[pallet::constant]
+		type MaxGas: Get<u64>;
+
+		/// max number of 64 KiB pages of the memory of a contract
+		#[pallet::constant]
+		type MaxMemoryPages: Get<u32>;
+
+		type Currency: ReservableCurrency<Self::AccountId>;
+
+		/// deposit reserved from the caller for each byte a call adds to the storage of a contract
+		#[pallet::constant]
+		type DepositPerByte: Get<BalanceOf<Self>>;
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		CodeStored {
+			code_hash: T::Hash,
+		},
+		Instantiated {
+			deployer: T::AccountId,
+			contract: T::AccountId,
+			code_hash: T::Hash,
+		},
+		Called {
+			caller: T::AccountId,
+			contract: T::AccountId,
+			output: Vec<u8>,
+			gas_used: u64,
+		},
+		/// emitted by the contract itself
+		ContractEmitted {
+			contract: T::AccountId,
+			data: Vec<u8>,
+		},
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		CodeTooLarge,
+		InvalidCode,
+		CodeNotFound,
+		ContractExists,
+		ContractNotFound,
+		InputTooLarge,
+		GasLimitTooHigh,
+		MissingExport,
+		ContractTrapped,
+		MemoryTooLarge,
+		StorageDepositNotAvailable,
+	}
+
+	#[pallet::storage]
+	#[pallet::getter(fn code)]
+	pub(super) type Code<T: Config> = StorageMap<_, Identity, T::Hash, Vec<u8>, OptionQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn contract_info)]
+	pub(super) type ContractInfoOf<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		ContractInfo<T::AccountId, T::Hash>,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn contract_storage)]
+	pub(super) type ContractStorage<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		Vec<u8>,
+		Vec<u8>,
+		OptionQuery,
+	>;
+
+	/// the storage deposits of a contract, by depositor
+	#[pallet::storage]
+	#[pallet::getter(fn storage_deposit)]
+	pub(super) type StorageDepositOf<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		T::AccountId,
+		BalanceOf<T>,
+		ValueQuery,
+	>;
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Store code which can then be instantiated by anyone.
+		#[pallet::call_index(0)]
+		#[pallet::weight((10_000, DispatchClass::Normal, Pays::Yes))]
+		pub fn upload_code(origin: OriginFor<T>, code: Vec<u8>) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+			ensure!(code.len() <= T::MaxCodeSize::get() as usize, Error::<T>::CodeTooLarge);
+			exec::validate::<T>(&code)?;
+
+			let code_hash = T::Hashing::hash(&code);
+			<Code<T>>::insert(code_hash, code);
+			Self::deposit_event(Event::CodeStored { code_hash });
+			Ok(().into())
+		}
+
+		/// Create a new contract running the code and call its `deploy` export with `input`.
+		///
+		/// The contract is only created if `deploy` succeeds. The actual weight is the fuel consumed
+		/// by the contract.
+		#[pallet::call_index(1)]
+		#[pallet::weight((Weight::from_parts(*gas_limit, 0), DispatchClass::Normal, Pays::Yes))]
+		pub fn instantiate(
+			origin: OriginFor<T>,
+			code_hash: T::Hash,
+			salt: Vec<u8>,
+			input: Vec<u8>,
+			gas_limit: u64,
+		) -> DispatchResultWithPostInfo {
+			let deployer = ensure_signed(origin)?;
+			Self::ensure_limits(&input, gas_limit)?;
+			let code = Self::code(code_hash).ok_or(Error::<T>::CodeNotFound)?;
+			let contract = contract_address(&deployer, &code_hash, &salt);
+			ensure!(!<ContractInfoOf<T>>::contains_key(&contract), Error::<T>::ContractExists);
+
+			let exec::ExecReturn { gas_used, .. } = exec::execute::<T>(
+				&code,
+				exec::DEPLOY_EXPORT,
+				exec::HostState::new(contract.clone(), deployer.clone(), input),
+				gas_limit,
+			)?;
+			<ContractInfoOf<T>>::insert(
+				&contract,
+				ContractInfo { code_hash, deployer: deployer.clone() },
+			);
+			Self::deposit_event(Event::Instantiated { deployer, contract, code_hash });
+			Ok(Some(Weight::from_parts(gas_used, 0)).into())
+		}
+
+		/// Call the `call` export of the contract with `input`.
+		///
+		/// The actual weight is the fuel consumed by the contract.
+		#[pallet::call_index(2)]
+		#[pallet::weight((Weight::from_parts(*gas_limit, 0), DispatchClass::Normal, Pays::Yes))]
+		pub fn call(
+			origin: OriginFor<T>,
+			contract: T::AccountId,
+			input: Vec<u8>,
+			gas_limit: u64,
+		) -> DispatchResultWithPostInfo {
+			let caller = ensure_signed(origin)?;
+			Self::ensure_limits(&input, gas_limit)?;
+			let info = Self::contract_info(&contract).ok_or(Error::<T>::ContractNotFound)?;
+			let code = Self::code(info.code_hash).ok_or(Error::<T>::CodeNotFound)?;
+
+			let exec::ExecReturn { output, gas_used } = exec::execute::<T>(
+				&code,
+				exec::CALL_EXPORT,
+				exec::HostState::new(contract.clone(), caller.clone(), input),
+				gas_limit,
+			)?;
+			Self::deposit_event(Event::Called { caller, contract, output, gas_used });
+			Ok(Some(Weight::from_parts(gas_used, 0)).into())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	fn ensure_limits(input: &[u8], gas_limit: u64) -> Result<(), Error<T>> {
+		ensure!(input.len() <= T::MaxValueSize::get() as usize, Error::<T>::InputTooLarge);
+		ensure!(gas_limit <= T::MaxGas::get(), Error::<T>::GasLimitTooHigh);
+		Ok(())
+	}
+
+	/// Reserve the deposit for the net bytes an execution added to the storage of the contract from
+	/// the depositor, or return the deposit for the net bytes it removed, up to the deposit of the
+	/// depositor for the contract.
+	pub(crate) fn settle_storage_deposit(
+		contract: &T::AccountId,
+		depositor: &T::AccountId,
+		bytes_added: u64,
+		bytes_removed: u64,
+	) -> Result<(), Error<T>> {
+		if bytes_added == bytes_removed {
+			return Ok(())
+		}
+		let deposit_of =
+			|bytes: u64| T::DepositPerByte::get().saturating_mul(bytes.saturated_into());
+		let deposited = Self::storage_deposit(contract, depositor);
+
+		let deposited = if bytes_added >= bytes_removed {
+			let deposit = deposit_of(bytes_added - bytes_removed);
+			T::Currency::reserve(depositor, deposit)
+				.map_err(|_| Error::<T>::StorageDepositNotAvailable)?;
+			deposited.saturating_add(deposit)
+		} else {
+			let refund = deposit_of(bytes_removed - bytes_added).min(deposited);
+			T::Currency::unreserve(depositor, refund);
+			deposited - refund
+		};
+
+		if deposited.is_zero() {
+			<StorageDepositOf<T>>::remove(contract, depositor);
+		} else {
+			<StorageDepositOf<T>>::insert(contract, depositor, deposited);
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
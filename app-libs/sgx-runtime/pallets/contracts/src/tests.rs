@@ -0,0 +1,471 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+use crate::{
+	contract_address,
+	exec::{FUEL_PER_BYTE, HOST_FUNCTION_FUEL, NO_VALUE},
+	mock::*,
+	ContractInfo, Error, Event,
+};
+use codec::Encode;
+use frame_support::{assert_err, assert_noop, assert_ok, weights::Weight};
+use sp_core::H256;
+use sp_keyring::AccountKeyring;
+use sp_runtime::traits::{BlakeTwo256, Hash};
+
+const GAS_LIMIT: u64 = 10_000;
+
+/// Keeps a counter under the key "count", initialized with the input of `deploy`.
+/// Each call increments the counter, emits an event and returns the new count.
+const COUNTER: &str = r#"
+(module
+	(import "env" "input_read" (func $input_read (param i32)))
+	(import "env" "storage_read" (func $storage_read (param i32 i32 i32 i32) (result i32)))
+	(import "env" "storage_write" (func $storage_write (param i32 i32 i32 i32)))
+	(import "env" "return_value" (func $return_value (param i32 i32)))
+	(import "env" "deposit_event" (func $deposit_event (param i32 i32)))
+	(memory (export "memory") 1)
+	(data (i32.const 0) "count")
+
+	(func (export "deploy")
+		(call $input_read (i32.const 16))
+		(call $storage_write (i32.const 0) (i32.const 5) (i32.const 16) (i32.const 4)))
+
+	(func (export "call")
+		(drop (call $storage_read (i32.const 0) (i32.const 5) (i32.const 16) (i32.const 4)))
+		(i32.store (i32.const 16) (i32.add (i32.load (i32.const 16)) (i32.const 1)))
+		(call $storage_write (i32.const 0) (i32.const 5) (i32.const 16) (i32.const 4))
+		(call $deposit_event (i32.const 0) (i32.const 5))
+		(call $return_value (i32.const 16) (i32.const 4)))
+)
+"#;
+
+/// Stores the result of reading the missing key "missing" under the key "result" on deploy.
+/// Each call writes to "missing" and then traps.
+const TRAPPING: &str = r#"
+(module
+	(import "env" "storage_read" (func $storage_read (param i32 i32 i32 i32) (result i32)))
+	(import "env" "storage_write" (func $storage_write (param i32 i32 i32 i32)))
+	(memory (export "memory") 1)
+	(data (i32.const 0) "missing")
+	(data (i32.const 8) "result")
+
+	(func (export "deploy")
+		(i32.store (i32.const 16)
+			(call $storage_read (i32.const 0) (i32.const 7) (i32.const 32) (i32.const 4)))
+		(call $storage_write (i32.const 8) (i32.const 6) (i32.const 16) (i32.const 4)))
+
+	(func (export "call")
+		(call $storage_write (i32.const 0) (i32.const 7) (i32.const 0) (i32.const 7))
+		unreachable)
+)
+"#;
+
+/// Stores its input under the key "key" on every call, or clears the key if the input is empty.
+const STORAGE: &str = r#"
+(module
+	(import "env" "input_len" (func $input_len (result i32)))
+	(import "env" "input_read" (func $input_read (param i32)))
+	(import "env" "storage_write" (func $storage_write (param i32 i32 i32 i32)))
+	(import "env" "storage_clear" (func $storage_clear (param i32 i32)))
+	(memory (export "memory") 1)
+	(data (i32.const 0) "key")
+
+	(func (export "deploy"))
+
+	(func (export "call")
+		(if (call $input_len)
+			(then
+				(call $input_read (i32.const 16))
+				(call $storage_write (i32.const 0) (i32.const 3) (i32.const 16) (call $input_len)))
+			(else
+				(call $storage_clear (i32.const 0) (i32.const 3)))))
+)
+"#;
+
+const ENDLESS_LOOP: &str = r#"
+(module
+	(memory (export "memory") 1)
+	(func (export "deploy"))
+	(func (export "call") (loop $forever (br $forever)))
+)
+"#;
+
+/// Tries to grow its memory beyond `MaxMemoryPages` on every call, and traps if that fails.
+const GROWING_MEMORY: &str = r#"
+(module
+	(memory (export "memory") 1)
+	(func (export "deploy"))
+	(func (export "call")
+		(if (i32.eq (memory.grow (i32.const 2)) (i32.const -1)) (then unreachable)))
+)
+"#;
+
+fn code(wat: &str) -> Vec<u8> {
+	wat::parse_str(wat).unwrap()
+}
+
+fn upload(wat: &str) -> H256 {
+	let code = code(wat);
+	let code_hash = BlakeTwo256::hash(&code);
+	assert_ok!(Contracts::upload_code(RuntimeOrigin::signed(AccountKeyring::Alice.into()), code));
+	code_hash
+}
+
+fn instantiate(wat: &str, input: Vec<u8>) -> AccountId {
+	let alice: AccountId = AccountKeyring::Alice.into();
+	let code_hash = upload(wat);
+	assert_ok!(Contracts::instantiate(
+		RuntimeOrigin::signed(alice.clone()),
+		code_hash,
+		vec![],
+		input,
+		GAS_LIMIT
+	));
+	contract_address(&alice, &code_hash, &[])
+}
+
+#[test]
+fn contract_address_depends_on_deployer_and_salt() {
+	let alice: AccountId = AccountKeyring::Alice.into();
+	let bob: AccountId = AccountKeyring::Bob.into();
+	let code_hash = H256::from_low_u64_be(1);
+
+	assert_eq!(
+		contract_address(&alice, &code_hash, &[1]),
+		contract_address(&alice, &code_hash, &[1])
+	);
+	assert_ne!(
+		contract_address(&alice, &code_hash, &[1]),
+		contract_address(&bob, &code_hash, &[1])
+	);
+	assert_ne!(
+		contract_address(&alice, &code_hash, &[1]),
+		contract_address(&alice, &code_hash, &[2])
+	);
+}
+
+#[test]
+fn upload_code_works() {
+	new_test_ext().execute_with(|| {
+		let code_hash = upload(COUNTER);
+
+		assert_eq!(Contracts::code(code_hash), Some(code(COUNTER)));
+		System::assert_last_event(Event::CodeStored { code_hash }.into());
+	})
+}
+
+#[test]
+fn upload_of_invalid_code_fails() {
+	new_test_ext().execute_with(|| {
+		let origin = RuntimeOrigin::signed(AccountKeyring::Alice.into());
+
+		assert_err!(
+			Contracts::upload_code(origin.clone(), b"not wasm".to_vec()),
+			Error::<Test>::InvalidCode
+		);
+		assert_err!(Contracts::upload_code(origin, vec![0; 4097]), Error::<Test>::CodeTooLarge);
+	})
+}
+
+#[test]
+fn instantiate_and_call_works() {
+	new_test_ext().execute_with(|| {
+		let alice: AccountId = AccountKeyring::Alice.into();
+		let bob: AccountId = AccountKeyring::Bob.into();
+		let contract = instantiate(COUNTER, 41u32.encode());
+
+		assert_eq!(
+			Contracts::contract_info(&contract),
+			Some(ContractInfo { code_hash: BlakeTwo256::hash(&code(COUNTER)), deployer: alice })
+		);
+		assert_eq!(Contracts::contract_storage(&contract, b"count".to_vec()), Some(41u32.encode()));
+
+		assert_ok!(Contracts::call(
+			RuntimeOrigin::signed(bob.clone()),
+			contract.clone(),
+			vec![],
+			GAS_LIMIT
+		));
+
+		assert_eq!(Contracts::contract_storage(&contract, b"count".to_vec()), Some(42u32.encode()));
+		System::assert_has_event(
+			Event::ContractEmitted { contract: contract.clone(), data: b"count".to_vec() }.into(),
+		);
+		match System::events().last().map(|record| record.event.clone()) {
+			Some(RuntimeEvent::Contracts(Event::Called { caller, output, gas_used, .. })) => {
+				assert_eq!(caller, bob);
+				assert_eq!(output, 42u32.encode());
+				assert!(gas_used > 0 && gas_used <= GAS_LIMIT);
+			},
+			event => panic!("unexpected event {:?}", event),
+		}
+	})
+}
+
+#[test]
+fn instantiate_twice_with_same_salt_fails() {
+	new_test_ext().execute_with(|| {
+		let origin = RuntimeOrigin::signed(AccountKeyring::Alice.into());
+		let code_hash = upload(COUNTER);
+
+		assert_ok!(Contracts::instantiate(origin.clone(), code_hash, vec![1], vec![], GAS_LIMIT));
+		assert_err!(
+			Contracts::instantiate(origin.clone(), code_hash, vec![1], vec![], GAS_LIMIT),
+			Error::<Test>::ContractExists
+		);
+		assert_ok!(Contracts::instantiate(origin, code_hash, vec![2], vec![], GAS_LIMIT));
+	})
+}
+
+#[test]
+fn instantiate_of_unknown_code_fails() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Contracts::instantiate(
+				RuntimeOrigin::signed(AccountKeyring::Alice.into()),
+				H256::from_low_u64_be(1),
+				vec![],
+				vec![],
+				GAS_LIMIT
+			),
+			Error::<Test>::CodeNotFound
+		);
+	})
+}
+
+#[test]
+fn call_of_unknown_contract_fails() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Contracts::call(
+				RuntimeOrigin::signed(AccountKeyring::Alice.into()),
+				AccountKeyring::Bob.into(),
+				vec![],
+				GAS_LIMIT
+			),
+			Error::<Test>::ContractNotFound
+		);
+	})
+}
+
+#[test]
+fn reading_missing_storage_value_returns_no_value() {
+	new_test_ext().execute_with(|| {
+		let contract = instantiate(TRAPPING, vec![]);
+
+		assert_eq!(
+			Contracts::contract_storage(&contract, b"result".to_vec()),
+			Some(NO_VALUE.encode())
+		);
+	})
+}
+
+#[test]
+fn trapping_call_reverts_storage_changes() {
+	new_test_ext().execute_with(|| {
+		let contract = instantiate(TRAPPING, vec![]);
+
+		assert_noop!(
+			Contracts::call(
+				RuntimeOrigin::signed(AccountKeyring::Alice.into()),
+				contract,
+				vec![],
+				GAS_LIMIT
+			),
+			Error::<Test>::ContractTrapped
+		);
+	})
+}
+
+#[test]
+fn endless_loop_runs_out_of_gas() {
+	new_test_ext().execute_with(|| {
+		let contract = instantiate(ENDLESS_LOOP, vec![]);
+
+		assert_noop!(
+			Contracts::call(
+				RuntimeOrigin::signed(AccountKeyring::Alice.into()),
+				contract,
+				vec![],
+				GAS_LIMIT
+			),
+			Error::<Test>::ContractTrapped
+		);
+	})
+}
+
+#[test]
+fn limits_are_enforced() {
+	new_test_ext().execute_with(|| {
+		let contract = instantiate(COUNTER, vec![]);
+		let origin = RuntimeOrigin::signed(AccountKeyring::Alice.into());
+
+		assert_noop!(
+			Contracts::call(origin.clone(), contract.clone(), vec![], MaxGas::get() + 1),
+			Error::<Test>::GasLimitTooHigh
+		);
+		assert_noop!(
+			Contracts::call(origin, contract, vec![0; 65], GAS_LIMIT),
+			Error::<Test>::InputTooLarge
+		);
+	})
+}
+
+#[test]
+fn call_reports_consumed_gas_as_actual_weight() {
+	new_test_ext().execute_with(|| {
+		let contract = instantiate(COUNTER, vec![]);
+
+		let post_info = Contracts::call(
+			RuntimeOrigin::signed(AccountKeyring::Alice.into()),
+			contract,
+			vec![],
+			GAS_LIMIT,
+		)
+		.unwrap();
+
+		match System::events().last().map(|record| record.event.clone()) {
+			Some(RuntimeEvent::Contracts(Event::Called { gas_used, .. })) =>
+				assert_eq!(post_info.actual_weight, Some(Weight::from_parts(gas_used, 0))),
+			event => panic!("unexpected event {:?}", event),
+		}
+	})
+}
+
+#[test]
+fn upload_of_code_with_too_large_memory_fails() {
+	new_test_ext().execute_with(|| {
+		let origin = RuntimeOrigin::signed(AccountKeyring::Alice.into());
+
+		assert_err!(
+			Contracts::upload_code(
+				origin.clone(),
+				code(r#"(module (memory (export "memory") 3))"#)
+			),
+			Error::<Test>::MemoryTooLarge
+		);
+		assert_err!(
+			Contracts::upload_code(origin, code(r#"(module (memory (export "memory") 1 65536))"#)),
+			Error::<Test>::MemoryTooLarge
+		);
+	})
+}
+
+#[test]
+fn instantiate_with_too_large_internal_memory_fails() {
+	new_test_ext().execute_with(|| {
+		let code_hash = upload(r#"(module (memory 3) (func (export "deploy")))"#);
+
+		assert_noop!(
+			Contracts::instantiate(
+				RuntimeOrigin::signed(AccountKeyring::Alice.into()),
+				code_hash,
+				vec![],
+				vec![],
+				GAS_LIMIT
+			),
+			Error::<Test>::ContractTrapped
+		);
+	})
+}
+
+#[test]
+fn growing_memory_beyond_limit_fails() {
+	new_test_ext().execute_with(|| {
+		let contract = instantiate(GROWING_MEMORY, vec![]);
+
+		assert_noop!(
+			Contracts::call(
+				RuntimeOrigin::signed(AccountKeyring::Alice.into()),
+				contract,
+				vec![],
+				GAS_LIMIT
+			),
+			Error::<Test>::ContractTrapped
+		);
+	})
+}
+
+#[test]
+fn storage_deposit_is_reserved_for_added_bytes_and_returned_for_removed_bytes() {
+	new_test_ext().execute_with(|| {
+		let alice: AccountId = AccountKeyring::Alice.into();
+		let bob: AccountId = AccountKeyring::Bob.into();
+		let contract = instantiate(STORAGE, vec![]);
+		let call = |who: &AccountId, input: Vec<u8>| {
+			Contracts::call(RuntimeOrigin::signed(who.clone()), contract.clone(), input, GAS_LIMIT)
+		};
+
+		// key and value
+		assert_ok!(call(&bob, vec![1; 10]));
+		assert_eq!(Balances::reserved_balance(&bob), 13 * DepositPerByte::get());
+		assert_eq!(Contracts::storage_deposit(&contract, &bob), 13 * DepositPerByte::get());
+
+		assert_ok!(call(&bob, vec![1; 4]));
+		assert_eq!(Balances::reserved_balance(&bob), 7 * DepositPerByte::get());
+		assert_eq!(Contracts::storage_deposit(&contract, &bob), 7 * DepositPerByte::get());
+
+		// alice didn't deposit anything for the contract
+		assert_ok!(call(&alice, vec![]));
+		assert_eq!(Contracts::contract_storage(&contract, b"key".to_vec()), None);
+		assert_eq!(Balances::reserved_balance(&alice), 0);
+		assert_eq!(Balances::reserved_balance(&bob), 7 * DepositPerByte::get());
+	})
+}
+
+#[test]
+fn storage_write_fails_if_deposit_can_not_be_reserved() {
+	new_test_ext().execute_with(|| {
+		let contract = instantiate(STORAGE, vec![]);
+
+		// charlie has no funds
+		assert_noop!(
+			Contracts::call(
+				RuntimeOrigin::signed(AccountKeyring::Charlie.into()),
+				contract,
+				vec![1; 10],
+				GAS_LIMIT
+			),
+			Error::<Test>::StorageDepositNotAvailable
+		);
+	})
+}
+
+#[test]
+fn host_functions_consume_fuel_per_call_and_per_byte() {
+	new_test_ext().execute_with(|| {
+		let contract = instantiate(STORAGE, vec![]);
+		let gas_used = |input: Vec<u8>| {
+			let post_info = Contracts::call(
+				RuntimeOrigin::signed(AccountKeyring::Alice.into()),
+				contract.clone(),
+				input,
+				GAS_LIMIT,
+			)
+			.unwrap();
+			post_info.actual_weight.unwrap().ref_time()
+		};
+
+		let gas_used_for_one_byte = gas_used(vec![1]);
+		let gas_used_for_ten_bytes = gas_used(vec![1; 10]);
+
+		// input_len twice, input_read and storage_write
+		assert!(gas_used_for_one_byte > 4 * HOST_FUNCTION_FUEL);
+		// the input is copied by input_read and stored by storage_write
+		assert_eq!(gas_used_for_ten_bytes - gas_used_for_one_byte, 2 * 9 * FUEL_PER_BYTE);
+	})
+}
@@ -0,0 +1,257 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, Encode};
+use frame_support::ensure;
+pub use pallet::*;
+use scale_info::TypeInfo;
+use sp_io::hashing::blake2_256;
+use sp_runtime::traits::TrailingZeroInput;
+use sp_std::{vec, vec::Vec};
+
+pub type CallHash = [u8; 32];
+
+#[derive(Encode, Decode, Clone, PartialEq, Eq, sp_core::RuntimeDebug, TypeInfo)]
+pub struct Proposal<AccountId> {
+	/// all signatories of the multisig account, sorted
+	pub signatories: Vec<AccountId>,
+	pub threshold: u16,
+	/// the signatory who made the first approval. only they can cancel the proposal
+	pub proposer: AccountId,
+	/// signatories who approved the call so far, including the proposer
+	pub approvals: Vec<AccountId>,
+	/// opaque call. it's up to the caller of `take_approved` to decode and execute it on behalf of
+	/// the multisig account
+	pub call: Vec<u8>,
+}
+
+/// Derives the account controlled by `threshold` out of the given `signatories`. The order of the
+/// signatories doesn't matter.
+pub fn multi_account_id<AccountId: Encode + Decode + Ord + Clone>(
+	signatories: &[AccountId],
+	threshold: u16,
+) -> AccountId {
+	let mut signatories = signatories.to_vec();
+	signatories.sort();
+	let entropy = (b"shardmultisig", signatories, threshold).using_encoded(blake2_256);
+	Decode::decode(&mut TrailingZeroInput::new(entropy.as_ref()))
+		.expect("infinite length input; no invalid inputs for type; qed")
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
+	#[pallet::without_storage_info]
+	pub struct Pallet<T>(PhantomData<T>);
+
+	/// Configuration trait.
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// max encoded length of a proposed call
+		#[pallet::constant]
+		type MaxCallSize: Get<u32>;
+
+		/// max number of signatories of a multisig account
+		#[pallet::constant]
+		type MaxSignatories: Get<u32>;
+
+		/// max number of pending proposals per multisig account
+		#[pallet::constant]
+		type MaxPendingProposals: Get<u32>;
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		Proposed { multisig: T::AccountId, call_hash: CallHash, proposer: T::AccountId },
+		Approved { multisig: T::AccountId, call_hash: CallHash, approving: T::AccountId },
+		Cancelled { multisig: T::AccountId, call_hash: CallHash },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		CallTooLong,
+		ZeroThreshold,
+		TooFewSignatories,
+		TooManySignatories,
+		DuplicateSignatories,
+		TooManyPendingProposals,
+		AlreadyApproved,
+		UnknownProposal,
+		NotProposer,
+	}
+
+	#[pallet::storage]
+	#[pallet::getter(fn proposals)]
+	pub(super) type Proposals<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Identity,
+		CallHash,
+		Proposal<T::AccountId>,
+		OptionQuery,
+	>;
+
+	/// index of the pending proposals by each of their signatories
+	#[pallet::storage]
+	pub(super) type ProposalsOfSignatory<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		(T::AccountId, CallHash),
+		(),
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn pending_proposals_count)]
+	pub(super) type PendingProposalsCount<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Approves `call` on behalf of the multisig account of the sender, `other_signatories`
+		/// and `threshold`. The first approval creates the proposal.
+		#[pallet::call_index(0)]
+		#[pallet::weight((10_000, DispatchClass::Normal, Pays::Yes))]
+		pub fn approve(
+			origin: OriginFor<T>,
+			other_signatories: Vec<T::AccountId>,
+			threshold: u16,
+			call: Vec<u8>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			ensure!(call.len() <= T::MaxCallSize::get() as usize, Error::<T>::CallTooLong);
+			let signatories = Self::checked_signatories(&who, other_signatories, threshold)?;
+			let multisig = multi_account_id(&signatories, threshold);
+			let call_hash = blake2_256(&call);
+
+			match Self::proposals(&multisig, call_hash) {
+				Some(mut proposal) => {
+					ensure!(!proposal.approvals.contains(&who), Error::<T>::AlreadyApproved);
+					proposal.approvals.push(who.clone());
+					<Proposals<T>>::insert(&multisig, call_hash, proposal);
+					Self::deposit_event(Event::Approved { multisig, call_hash, approving: who });
+				},
+				None => {
+					ensure!(
+						Self::pending_proposals_count(&multisig) < T::MaxPendingProposals::get(),
+						Error::<T>::TooManyPendingProposals
+					);
+					for signatory in signatories.iter() {
+						<ProposalsOfSignatory<T>>::insert(signatory, (&multisig, call_hash), ());
+					}
+					<Proposals<T>>::insert(
+						&multisig,
+						call_hash,
+						Proposal {
+							signatories,
+							threshold,
+							proposer: who.clone(),
+							approvals: vec![who.clone()],
+							call,
+						},
+					);
+					<PendingProposalsCount<T>>::mutate(&multisig, |count| *count += 1);
+					Self::deposit_event(Event::Proposed { multisig, call_hash, proposer: who });
+				},
+			}
+			Ok(().into())
+		}
+
+		/// Cancels a pending proposal. Only the proposer can do that.
+		#[pallet::call_index(1)]
+		#[pallet::weight((10_000, DispatchClass::Normal, Pays::Yes))]
+		pub fn cancel(
+			origin: OriginFor<T>,
+			other_signatories: Vec<T::AccountId>,
+			threshold: u16,
+			call_hash: CallHash,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let signatories = Self::checked_signatories(&who, other_signatories, threshold)?;
+			let multisig = multi_account_id(&signatories, threshold);
+			let proposal =
+				Self::proposals(&multisig, call_hash).ok_or(Error::<T>::UnknownProposal)?;
+			ensure!(proposal.proposer == who, Error::<T>::NotProposer);
+
+			Self::remove_proposal(&multisig, call_hash, &proposal.signatories);
+			Self::deposit_event(Event::Cancelled { multisig, call_hash });
+			Ok(().into())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Removes the proposal and returns it for execution if it has reached its threshold.
+	pub fn take_approved(
+		multisig: &T::AccountId,
+		call_hash: CallHash,
+	) -> Option<Proposal<T::AccountId>> {
+		let proposal = Self::proposals(multisig, call_hash)?;
+		if proposal.approvals.len() < proposal.threshold as usize {
+			return None
+		}
+		Self::remove_proposal(multisig, call_hash, &proposal.signatories);
+		Some(proposal)
+	}
+
+	/// All pending proposals the given account is a signatory of.
+	pub fn proposals_for_signatory(
+		who: &T::AccountId,
+	) -> Vec<(T::AccountId, CallHash, Proposal<T::AccountId>)> {
+		<ProposalsOfSignatory<T>>::iter_key_prefix(who)
+			.filter_map(|(multisig, call_hash)| {
+				Self::proposals(&multisig, call_hash)
+					.map(|proposal| (multisig, call_hash, proposal))
+			})
+			.collect()
+	}
+
+	fn checked_signatories(
+		who: &T::AccountId,
+		other_signatories: Vec<T::AccountId>,
+		threshold: u16,
+	) -> Result<Vec<T::AccountId>, Error<T>> {
+		ensure!(threshold > 0, Error::<T>::ZeroThreshold);
+		let mut signatories = other_signatories;
+		signatories.push(who.clone());
+		ensure!(
+			signatories.len() <= T::MaxSignatories::get() as usize,
+			Error::<T>::TooManySignatories
+		);
+		ensure!(
+			signatories.len() >= 2 && signatories.len() >= threshold as usize,
+			Error::<T>::TooFewSignatories
+		);
+		signatories.sort();
+		let len = signatories.len();
+		signatories.dedup();
+		ensure!(signatories.len() == len, Error::<T>::DuplicateSignatories);
+		Ok(signatories)
+	}
+
+	fn remove_proposal(multisig: &T::AccountId, call_hash: CallHash, signatories: &[T::AccountId]) {
+		<Proposals<T>>::remove(multisig, call_hash);
+		for signatory in signatories {
+			<ProposalsOfSignatory<T>>::remove(signatory, (multisig, call_hash));
+		}
+		<PendingProposalsCount<T>>::mutate_exists(multisig, |count| {
+			*count = count.map(|c| c.saturating_sub(1)).filter(|c| *c > 0)
+		});
+	}
+}
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
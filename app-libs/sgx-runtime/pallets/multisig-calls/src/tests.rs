@@ -0,0 +1,193 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+use crate::{mock::*, multi_account_id, Error, Event, Proposal};
+use frame_support::{assert_err, assert_ok};
+use sp_io::hashing::blake2_256;
+use sp_keyring::AccountKeyring;
+
+fn signatories() -> (AccountId, AccountId, AccountId) {
+	(
+		AccountKeyring::Alice.to_account_id(),
+		AccountKeyring::Bob.to_account_id(),
+		AccountKeyring::Charlie.to_account_id(),
+	)
+}
+
+#[test]
+fn multi_account_id_is_independent_of_signatory_order() {
+	let (alice, bob, charlie) = signatories();
+	assert_eq!(
+		multi_account_id(&[alice.clone(), bob.clone(), charlie.clone()], 2),
+		multi_account_id(&[charlie.clone(), alice.clone(), bob.clone()], 2)
+	);
+	assert_ne!(
+		multi_account_id(&[alice.clone(), bob.clone(), charlie.clone()], 2),
+		multi_account_id(&[alice, bob, charlie], 3)
+	);
+}
+
+#[test]
+fn approve_until_threshold_works() {
+	new_test_ext().execute_with(|| {
+		let (alice, bob, charlie) = signatories();
+		let multisig = multi_account_id(&[alice.clone(), bob.clone(), charlie.clone()], 2);
+		let call = vec![1, 2, 3];
+		let call_hash = blake2_256(&call);
+
+		assert_ok!(MultisigCalls::approve(
+			RuntimeOrigin::signed(alice.clone()),
+			vec![bob.clone(), charlie.clone()],
+			2,
+			call.clone()
+		));
+		System::assert_last_event(
+			Event::Proposed { multisig: multisig.clone(), call_hash, proposer: alice.clone() }
+				.into(),
+		);
+		assert_eq!(MultisigCalls::pending_proposals_count(&multisig), 1);
+		assert_eq!(MultisigCalls::take_approved(&multisig, call_hash), None);
+		assert_eq!(MultisigCalls::proposals_for_signatory(&charlie).len(), 1);
+
+		assert_err!(
+			MultisigCalls::approve(
+				RuntimeOrigin::signed(alice.clone()),
+				vec![charlie.clone(), bob.clone()],
+				2,
+				call.clone()
+			),
+			Error::<Test>::AlreadyApproved
+		);
+		assert_ok!(MultisigCalls::approve(
+			RuntimeOrigin::signed(bob.clone()),
+			vec![charlie.clone(), alice.clone()],
+			2,
+			call.clone()
+		));
+
+		let mut signatories = vec![alice.clone(), bob.clone(), charlie];
+		signatories.sort();
+		assert_eq!(
+			MultisigCalls::take_approved(&multisig, call_hash),
+			Some(Proposal {
+				signatories,
+				threshold: 2,
+				proposer: alice.clone(),
+				approvals: vec![alice.clone(), bob],
+				call
+			})
+		);
+		assert_eq!(MultisigCalls::proposals(&multisig, call_hash), None);
+		assert_eq!(MultisigCalls::pending_proposals_count(&multisig), 0);
+		assert!(MultisigCalls::proposals_for_signatory(&alice).is_empty());
+	})
+}
+
+#[test]
+fn approve_with_invalid_signatories_fails() {
+	new_test_ext().execute_with(|| {
+		let (alice, bob, charlie) = signatories();
+		let dave = AccountKeyring::Dave.to_account_id();
+		let eve = AccountKeyring::Eve.to_account_id();
+		let origin = || RuntimeOrigin::signed(alice.clone());
+
+		assert_err!(
+			MultisigCalls::approve(origin(), vec![bob.clone()], 0, vec![]),
+			Error::<Test>::ZeroThreshold
+		);
+		assert_err!(
+			MultisigCalls::approve(origin(), vec![], 1, vec![]),
+			Error::<Test>::TooFewSignatories
+		);
+		assert_err!(
+			MultisigCalls::approve(origin(), vec![bob.clone()], 3, vec![]),
+			Error::<Test>::TooFewSignatories
+		);
+		assert_err!(
+			MultisigCalls::approve(origin(), vec![bob.clone(), alice.clone()], 2, vec![]),
+			Error::<Test>::DuplicateSignatories
+		);
+		assert_err!(
+			MultisigCalls::approve(origin(), vec![bob, charlie, dave, eve], 2, vec![]),
+			Error::<Test>::TooManySignatories
+		);
+	})
+}
+
+#[test]
+fn too_many_pending_proposals_fails() {
+	new_test_ext().execute_with(|| {
+		let (alice, bob, _) = signatories();
+		for call in [vec![1], vec![2]] {
+			assert_ok!(MultisigCalls::approve(
+				RuntimeOrigin::signed(alice.clone()),
+				vec![bob.clone()],
+				2,
+				call
+			));
+		}
+		assert_err!(
+			MultisigCalls::approve(RuntimeOrigin::signed(bob), vec![alice], 2, vec![3]),
+			Error::<Test>::TooManyPendingProposals
+		);
+	})
+}
+
+#[test]
+fn cancel_works_for_proposer_only() {
+	new_test_ext().execute_with(|| {
+		let (alice, bob, _) = signatories();
+		let multisig = multi_account_id(&[alice.clone(), bob.clone()], 2);
+		let call_hash = blake2_256(&[1]);
+		assert_ok!(MultisigCalls::approve(
+			RuntimeOrigin::signed(alice.clone()),
+			vec![bob.clone()],
+			2,
+			vec![1]
+		));
+
+		assert_eq!(MultisigCalls::proposals_for_signatory(&bob).len(), 1);
+		assert_err!(
+			MultisigCalls::cancel(
+				RuntimeOrigin::signed(bob.clone()),
+				vec![alice.clone()],
+				2,
+				call_hash
+			),
+			Error::<Test>::NotProposer
+		);
+		assert_err!(
+			MultisigCalls::cancel(
+				RuntimeOrigin::signed(alice.clone()),
+				vec![AccountKeyring::Bob.to_account_id()],
+				2,
+				[0u8; 32]
+			),
+			Error::<Test>::UnknownProposal
+		);
+		assert_ok!(MultisigCalls::cancel(
+			RuntimeOrigin::signed(alice),
+			vec![AccountKeyring::Bob.to_account_id()],
+			2,
+			call_hash
+		));
+		System::assert_last_event(
+			Event::Cancelled { multisig: multisig.clone(), call_hash }.into(),
+		);
+		assert_eq!(MultisigCalls::pending_proposals_count(&multisig), 0);
+		assert!(MultisigCalls::proposals_for_signatory(&bob).is_empty());
+	})
+}
@@ -81,6 +81,10 @@ pub mod pallet {
 		/// max size of all persisted buckets in bytes
 		#[pallet::constant]
 		type MaxTotalSize: Get<u32>;
+
+		/// max number of accounts a note can be linked to (e.g. sender and recipients of a message)
+		#[pallet::constant]
+		type MaxLinkedAccounts: Get<u32>;
 	}
 
 	#[pallet::error]
@@ -151,7 +155,10 @@ pub mod pallet {
 			payload: Vec<u8>,
 		) -> DispatchResultWithPostInfo {
 			ensure_signed(origin)?;
-			ensure!(link_to.len() < 3, Error::<T>::TooManyLinkedAccounts);
+			ensure!(
+				link_to.len() <= T::MaxLinkedAccounts::get() as usize,
+				Error::<T>::TooManyLinkedAccounts
+			);
 			let note = TimestampedTrustedNote::<T::Moment> {
 				timestamp: Timestamp::<T>::get(),
 				version: NOTE_VERSION,
@@ -174,7 +181,10 @@ pub mod pallet {
 			payload: Vec<u8>,
 		) -> DispatchResultWithPostInfo {
 			ensure_signed(origin)?;
-			ensure!(link_to.len() < 3, Error::<T>::TooManyLinkedAccounts);
+			ensure!(
+				link_to.len() <= T::MaxLinkedAccounts::get() as usize,
+				Error::<T>::TooManyLinkedAccounts
+			);
 			let note = TimestampedTrustedNote::<T::Moment> {
 				timestamp: Timestamp::<T>::get(),
 				version: NOTE_VERSION,
@@ -191,6 +201,39 @@ pub mod pallet {
 }
 
 impl<T: Config> Pallet<T> {
+	/// Returns up to `max` notes linked to `who`, across all buckets and newest first.
+	///
+	/// Only notes with an index lower than `before` are returned, if given. So the next page
+	/// is obtained by passing the index of the last note of the current page.
+	pub fn linked_notes_page(
+		who: &T::AccountId,
+		before: Option<NoteIndex>,
+		max: u32,
+	) -> Vec<(NoteIndex, TimestampedTrustedNote<T::Moment>)> {
+		let mut page = Vec::new();
+		let (first_bucket_index, last_bucket_index) =
+			match (Self::first_bucket_index(), Self::last_bucket_index()) {
+				(Some(first), Some(last)) => (first, last),
+				_ => return page,
+			};
+
+		for bucket_index in (first_bucket_index..=last_bucket_index).rev() {
+			// note indices are increasing within a bucket, as well as across buckets
+			for note_index in Self::notes_lookup(bucket_index, who).into_iter().rev() {
+				if before.map_or(false, |before| note_index >= before) {
+					continue
+				}
+				if page.len() >= max as usize {
+					return page
+				}
+				if let Some(note) = Self::notes(bucket_index, note_index) {
+					page.push((note_index, note));
+				}
+			}
+		}
+		page
+	}
+
 	fn store_note(
 		note: TimestampedTrustedNote<T::Moment>,
 	) -> Result<(BucketIndex, NoteIndex), Error<T>> {
@@ -77,6 +77,7 @@ parameter_types! {
 	pub const MaxNoteSize: u32 = 512;
 	pub const MaxBucketSize: u32 = 5120;
 	pub const MaxTotalSize: u32 = 51200;
+	pub const MaxLinkedAccounts: u32 = 2;
 }
 
 impl dut::Config for Test {
@@ -85,6 +86,7 @@ impl dut::Config for Test {
 	type MaxNoteSize = MaxNoteSize;
 	type MaxBucketSize = MaxBucketSize;
 	type MaxTotalSize = MaxTotalSize;
+	type MaxLinkedAccounts = MaxLinkedAccounts;
 }
 
 parameter_types! {
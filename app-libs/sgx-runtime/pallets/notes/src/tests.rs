@@ -260,3 +260,61 @@ fn note_string_works() {
 		assert_eq!(bucket.bytes, expected_note.encoded_size() as u32);
 	})
 }
+
+#[test]
+fn note_string_with_too_many_linked_accounts_fails() {
+	new_test_ext().execute_with(|| {
+		let alice = AccountKeyring::Alice.to_account_id();
+		let bob = AccountKeyring::Bob.to_account_id();
+		let charlie = AccountKeyring::Charlie.to_account_id();
+		assert_err!(
+			Notes::note_string(
+				RuntimeOrigin::signed(bob.clone()),
+				[bob, alice, charlie].into(),
+				"hi all".to_string().encode()
+			),
+			Error::<Test>::TooManyLinkedAccounts
+		);
+	})
+}
+
+#[test]
+fn linked_notes_page_works_across_buckets() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(0);
+		set_timestamp(234);
+		let alice = AccountKeyring::Alice.to_account_id();
+		let bob = AccountKeyring::Bob.to_account_id();
+		let charlie = AccountKeyring::Charlie.to_account_id();
+		let msg = vec![0u8; 400];
+		for i in 0..16u64 {
+			// every third note is not linked to alice
+			let recipient = if i % 3 == 2 { charlie.clone() } else { alice.clone() };
+			assert_ok!(Notes::note_string(
+				RuntimeOrigin::signed(bob.clone()),
+				[bob.clone(), recipient].into(),
+				msg.clone()
+			));
+		}
+		assert_eq!(Notes::last_bucket_index(), Some(1));
+
+		let note_indices = |page: Vec<(u64, TimestampedTrustedNote<Moment>)>| {
+			page.into_iter().map(|(index, _)| index).collect::<Vec<_>>()
+		};
+		let first_page = Notes::linked_notes_page(&alice, None, 4);
+		assert_eq!(note_indices(first_page), vec![15, 13, 12, 10]);
+
+		let second_page = Notes::linked_notes_page(&alice, Some(10), 4);
+		assert_eq!(note_indices(second_page), vec![9, 7, 6, 4]);
+
+		let last_page = Notes::linked_notes_page(&alice, Some(4), 4);
+		assert_eq!(note_indices(last_page), vec![3, 1, 0]);
+
+		assert!(Notes::linked_notes_page(&alice, Some(0), 4).is_empty());
+		assert_eq!(Notes::linked_notes_page(&bob, None, 100).len(), 16);
+		assert_eq!(
+			Notes::linked_notes_page(&charlie, None, 100)[0],
+			(14, Notes::notes(Notes::last_bucket_index().unwrap(), 14).unwrap())
+		);
+	})
+}
@@ -2,6 +2,8 @@
 
 pub use pallet::*;
 
+pub mod migrations;
+
 /// Index/Nonce type for parentchain runtime
 type ParentchainIndex = u32;
 /// Balance type for parentchain runtime
@@ -16,17 +18,16 @@ pub mod pallet {
 	use frame_system::{pallet_prelude::*, AccountInfo};
 	use sp_runtime::traits::{AtLeast32Bit, Scale};
 
-	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+	pub(crate) const STORAGE_VERSION: StorageVersion = StorageVersion::new(2);
 	#[pallet::pallet]
 	#[pallet::storage_version(STORAGE_VERSION)]
 	#[pallet::without_storage_info]
-	pub struct Pallet<T, I = ()>(PhantomData<(T, I)>);
+	pub struct Pallet<T>(PhantomData<T>);
 
 	/// Configuration trait.
 	#[pallet::config]
-	pub trait Config<I: 'static = ()>: frame_system::Config {
-		type RuntimeEvent: From<Event<Self, I>>
-			+ IsType<<Self as frame_system::Config>::RuntimeEvent>;
+	pub trait Config: frame_system::Config {
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
 		type WeightInfo: WeightInfo;
 
 		/// Type used for expressing timestamp.
@@ -37,34 +38,42 @@ pub mod pallet {
 			+ Copy
 			+ MaxEncodedLen
 			+ scale_info::StaticTypeInfo;
+
+		/// Identifies the parentchain that a storage entry or call refers to.
+		type ParentchainId: Parameter + Copy;
 	}
 
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
-	pub enum Event<T: Config<I>, I: 'static = ()> {
+	pub enum Event<T: Config> {
 		/// a parentchain block has been registered
 		SetBlock {
+			parentchain_id: T::ParentchainId,
 			block_number: T::BlockNumber,
 			parent_hash: T::Hash,
 			block_hash: T::Hash,
 		},
 		SetCreationBlock {
+			parentchain_id: T::ParentchainId,
 			block_number: T::BlockNumber,
 			block_hash: T::Hash,
 		},
 		ShardVaultInitialized {
+			parentchain_id: T::ParentchainId,
 			account: T::AccountId,
 		},
 		AccountInfoForcedFor {
+			parentchain_id: T::ParentchainId,
 			account: T::AccountId,
 		},
 		ParentchainGenesisInitialized {
+			parentchain_id: T::ParentchainId,
 			hash: T::Hash,
 		},
 	}
 
 	#[pallet::error]
-	pub enum Error<T, I = ()> {
+	pub enum Error<T> {
 		/// Sahrd vault has been previously initialized and can't be overwritten
 		ShardVaultAlreadyInitialized,
 		/// Parentchain genesis hash has already been initialized and can^t be overwritten
@@ -74,80 +83,88 @@ pub mod pallet {
 	/// The parentchain mirror of full account information for a particular account ID.
 	#[pallet::storage]
 	#[pallet::getter(fn account)]
-	pub type Account<T: Config<I>, I: 'static = ()> = StorageMap<
+	pub type Account<T: Config> = StorageDoubleMap<
 		_,
 		Blake2_128Concat,
+		T::ParentchainId,
+		Blake2_128Concat,
 		T::AccountId,
 		AccountInfo<ParentchainIndex, ParentchainAccountData>,
 		ValueQuery,
 	>;
 
-	/// The current block number being processed. Set by `set_block`.
+	/// The shard vault account and the parentchain it lives on. Set by `init_shard_vault`.
 	#[pallet::storage]
 	#[pallet::getter(fn shard_vault)]
-	pub(super) type ShardVault<T: Config<I>, I: 'static = ()> =
-		StorageValue<_, T::AccountId, OptionQuery>;
+	pub(super) type ShardVault<T: Config> =
+		StorageValue<_, (T::AccountId, T::ParentchainId), OptionQuery>;
 
 	#[pallet::storage]
 	#[pallet::getter(fn parentchain_genesis_hash)]
-	pub(super) type ParentchainGenesisHash<T: Config<I>, I: 'static = ()> =
-		StorageValue<_, T::Hash, OptionQuery>;
+	pub(super) type ParentchainGenesisHash<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::ParentchainId, T::Hash, OptionQuery>;
 
 	/// The current block number being processed. Set by `set_block`.
 	#[pallet::storage]
 	#[pallet::getter(fn block_number)]
-	pub(super) type Number<T: Config<I>, I: 'static = ()> =
-		StorageValue<_, T::BlockNumber, OptionQuery>;
+	pub(super) type Number<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::ParentchainId, T::BlockNumber, OptionQuery>;
 
 	/// The current block timestamp. Set by `set_now`.
 	/// this is not guaranteed by the pallet to be consistent with block_number or hash
 	#[pallet::storage]
 	#[pallet::getter(fn now)]
-	pub(super) type Now<T: Config<I>, I: 'static = ()> = StorageValue<_, T::Moment, OptionQuery>;
+	pub(super) type Now<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::ParentchainId, T::Moment, OptionQuery>;
 
 	/// Hash of the previous block. Set by `set_block`.
 	#[pallet::storage]
 	#[pallet::getter(fn parent_hash)]
-	pub(super) type ParentHash<T: Config<I>, I: 'static = ()> =
-		StorageValue<_, T::Hash, OptionQuery>;
+	pub(super) type ParentHash<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::ParentchainId, T::Hash, OptionQuery>;
 
 	/// Hash of the last block. Set by `set_block`.
 	#[pallet::storage]
 	#[pallet::getter(fn block_hash)]
-	pub(super) type BlockHash<T: Config<I>, I: 'static = ()> =
-		StorageValue<_, T::Hash, OptionQuery>;
+	pub(super) type BlockHash<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::ParentchainId, T::Hash, OptionQuery>;
 
 	/// Hash of the shard creation block. Set by `set_creation_block`.
 	#[pallet::storage]
 	#[pallet::getter(fn creation_block_hash)]
-	pub(super) type CreationBlockHash<T: Config<I>, I: 'static = ()> =
-		StorageValue<_, T::Hash, OptionQuery>;
+	pub(super) type CreationBlockHash<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::ParentchainId, T::Hash, OptionQuery>;
 
 	/// The creation block number. Set by `set_creation_block`.
 	#[pallet::storage]
 	#[pallet::getter(fn creation_block_number)]
-	pub(super) type CreationBlockNumber<T: Config<I>, I: 'static = ()> =
-		StorageValue<_, T::BlockNumber, OptionQuery>;
+	pub(super) type CreationBlockNumber<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::ParentchainId, T::BlockNumber, OptionQuery>;
 
 	/// The creation block timestamp. Set by `set_creation_timestamp`.
 	#[pallet::storage]
 	#[pallet::getter(fn creation_timestamp)]
-	pub(super) type CreationTimestamp<T: Config<I>, I: 'static = ()> =
-		StorageValue<_, T::Moment, OptionQuery>;
+	pub(super) type CreationTimestamp<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::ParentchainId, T::Moment, OptionQuery>;
 
 	#[pallet::hooks]
-	impl<T: Config<I>, I: 'static> Hooks<BlockNumberFor<T>> for Pallet<T, I> {}
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
 
 	#[pallet::call]
-	impl<T: Config<I>, I: 'static> Pallet<T, I> {
+	impl<T: Config> Pallet<T> {
 		#[pallet::call_index(0)]
 		#[pallet::weight(T::WeightInfo::set_block())]
-		pub fn set_block(origin: OriginFor<T>, header: T::Header) -> DispatchResult {
+		pub fn set_block(
+			origin: OriginFor<T>,
+			parentchain_id: T::ParentchainId,
+			header: T::Header,
+		) -> DispatchResult {
 			ensure_root(origin)?;
-			<Number<T, I>>::put(header.number());
-			<ParentHash<T, I>>::put(header.parent_hash());
-			<BlockHash<T, I>>::put(header.hash());
+			<Number<T>>::insert(parentchain_id, header.number());
+			<ParentHash<T>>::insert(parentchain_id, header.parent_hash());
+			<BlockHash<T>>::insert(parentchain_id, header.hash());
 			Self::deposit_event(Event::SetBlock {
+				parentchain_id,
 				block_number: *header.number(),
 				parent_hash: *header.parent_hash(),
 				block_hash: header.hash(),
@@ -157,11 +174,15 @@ pub mod pallet {
 
 		#[pallet::call_index(1)]
 		#[pallet::weight(T::WeightInfo::init_shard_vault())]
-		pub fn init_shard_vault(origin: OriginFor<T>, account: T::AccountId) -> DispatchResult {
+		pub fn init_shard_vault(
+			origin: OriginFor<T>,
+			parentchain_id: T::ParentchainId,
+			account: T::AccountId,
+		) -> DispatchResult {
 			ensure_root(origin)?;
-			ensure!(Self::shard_vault().is_none(), Error::<T, I>::ShardVaultAlreadyInitialized);
-			<ShardVault<T, I>>::put(account.clone());
-			Self::deposit_event(Event::ShardVaultInitialized { account });
+			ensure!(Self::shard_vault().is_none(), Error::<T>::ShardVaultAlreadyInitialized);
+			<ShardVault<T>>::put((account.clone(), parentchain_id));
+			Self::deposit_event(Event::ShardVaultInitialized { parentchain_id, account });
 			Ok(())
 		}
 
@@ -169,15 +190,19 @@ pub mod pallet {
 		#[pallet::weight(T::WeightInfo::init_parentchain_genesis_hash())]
 		pub fn init_parentchain_genesis_hash(
 			origin: OriginFor<T>,
+			parentchain_id: T::ParentchainId,
 			genesis: T::Hash,
 		) -> DispatchResult {
 			ensure_root(origin)?;
 			ensure!(
-				Self::parentchain_genesis_hash().is_none(),
-				Error::<T, I>::GenesisAlreadyInitialized
+				Self::parentchain_genesis_hash(parentchain_id).is_none(),
+				Error::<T>::GenesisAlreadyInitialized
 			);
-			<ParentchainGenesisHash<T, I>>::put(genesis);
-			Self::deposit_event(Event::ParentchainGenesisInitialized { hash: genesis });
+			<ParentchainGenesisHash<T>>::insert(parentchain_id, genesis);
+			Self::deposit_event(Event::ParentchainGenesisInitialized {
+				parentchain_id,
+				hash: genesis,
+			});
 			Ok(())
 		}
 
@@ -185,29 +210,42 @@ pub mod pallet {
 		#[pallet::weight(T::WeightInfo::force_account_info())]
 		pub fn force_account_info(
 			origin: OriginFor<T>,
+			parentchain_id: T::ParentchainId,
 			account: T::AccountId,
 			account_info: AccountInfo<ParentchainIndex, ParentchainAccountData>,
 		) -> DispatchResult {
 			ensure_root(origin)?;
-			<crate::pallet::Account<T, I>>::insert(&account, account_info);
-			Self::deposit_event(crate::pallet::Event::AccountInfoForcedFor { account });
+			<crate::pallet::Account<T>>::insert(parentchain_id, &account, account_info);
+			Self::deposit_event(crate::pallet::Event::AccountInfoForcedFor {
+				parentchain_id,
+				account,
+			});
 			Ok(())
 		}
 
 		#[pallet::call_index(4)]
 		#[pallet::weight(T::WeightInfo::set_now())]
-		pub fn set_now(origin: OriginFor<T>, now: T::Moment) -> DispatchResult {
+		pub fn set_now(
+			origin: OriginFor<T>,
+			parentchain_id: T::ParentchainId,
+			now: T::Moment,
+		) -> DispatchResult {
 			ensure_root(origin)?;
-			<Now<T, I>>::put(now);
+			<Now<T>>::insert(parentchain_id, now);
 			Ok(())
 		}
 		#[pallet::call_index(5)]
 		#[pallet::weight(T::WeightInfo::set_creation_block())]
-		pub fn set_creation_block(origin: OriginFor<T>, header: T::Header) -> DispatchResult {
+		pub fn set_creation_block(
+			origin: OriginFor<T>,
+			parentchain_id: T::ParentchainId,
+			header: T::Header,
+		) -> DispatchResult {
 			ensure_root(origin)?;
-			<CreationBlockNumber<T, I>>::put(header.number());
-			<CreationBlockHash<T, I>>::put(header.hash());
+			<CreationBlockNumber<T>>::insert(parentchain_id, header.number());
+			<CreationBlockHash<T>>::insert(parentchain_id, header.hash());
 			Self::deposit_event(Event::SetCreationBlock {
+				parentchain_id,
 				block_number: *header.number(),
 				block_hash: header.hash(),
 			});
@@ -216,12 +254,24 @@ pub mod pallet {
 
 		#[pallet::call_index(6)]
 		#[pallet::weight(T::WeightInfo::set_creation_timestamp())]
-		pub fn set_creation_timestamp(origin: OriginFor<T>, creation: T::Moment) -> DispatchResult {
+		pub fn set_creation_timestamp(
+			origin: OriginFor<T>,
+			parentchain_id: T::ParentchainId,
+			creation: T::Moment,
+		) -> DispatchResult {
 			ensure_root(origin)?;
-			<CreationTimestamp<T, I>>::put(creation);
+			<CreationTimestamp<T>>::insert(parentchain_id, creation);
 			Ok(())
 		}
 	}
+
+	impl<T: Config> Pallet<T> {
+		/// All parentchains that have been registered in this pallet, i.e. whose genesis hash
+		/// has been initialized.
+		pub fn parentchain_ids() -> impl Iterator<Item = T::ParentchainId> {
+			<ParentchainGenesisHash<T>>::iter_keys()
+		}
+	}
 }
 
 #[cfg(test)]
@@ -0,0 +1,98 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Storage migrations of the parentchain pallet.
+
+/// Migrates from one pallet instance per parentchain to storage keyed by the parentchain id.
+pub mod v2 {
+	use crate::{
+		pallet::{
+			Account, BlockHash, CreationBlockHash, CreationBlockNumber, CreationTimestamp, Now,
+			Number, ParentHash, ParentchainGenesisHash, ShardVault, STORAGE_VERSION,
+		},
+		Config, Pallet, ParentchainAccountData, ParentchainIndex,
+	};
+	use codec::Decode;
+	use frame_support::{
+		storage::migration::{storage_key_iter, take_storage_value},
+		traits::{Get, GetStorageVersion},
+		weights::Weight,
+		Blake2_128Concat,
+	};
+	use frame_system::AccountInfo;
+
+	/// Moves the storage of the former per-parentchain pallet instances into the maps keyed by
+	/// the parentchain id.
+	///
+	/// `legacy_instances` maps the pallet name of each former instance to the parentchain its
+	/// storage belongs to. Does nothing if the storage has already been migrated.
+	pub fn migrate<T: Config>(legacy_instances: &[(&[u8], T::ParentchainId)]) -> Weight {
+		if Pallet::<T>::on_chain_storage_version() >= STORAGE_VERSION {
+			return T::DbWeight::get().reads(1)
+		}
+
+		let mut moved_items = 0u64;
+		for (pallet_name, parentchain_id) in legacy_instances.iter().copied() {
+			let accounts = storage_key_iter::<
+				T::AccountId,
+				AccountInfo<ParentchainIndex, ParentchainAccountData>,
+				Blake2_128Concat,
+			>(pallet_name, b"Account")
+			.drain();
+			for (account, account_info) in accounts {
+				Account::<T>::insert(parentchain_id, account, account_info);
+				moved_items += 1;
+			}
+
+			if let Some(account) = take_value::<T::AccountId>(pallet_name, b"ShardVault") {
+				if ShardVault::<T>::get().is_none() {
+					ShardVault::<T>::put((account, parentchain_id));
+				}
+				moved_items += 1;
+			}
+
+			// The legacy storage items are named like their keyed successors.
+			macro_rules! move_value {
+				($storage:ident, $value:ty) => {
+					if let Some(value) =
+						take_value::<$value>(pallet_name, stringify!($storage).as_bytes())
+					{
+						$storage::<T>::insert(parentchain_id, value);
+						moved_items += 1;
+					}
+				};
+			}
+			move_value!(ParentchainGenesisHash, T::Hash);
+			move_value!(Number, T::BlockNumber);
+			move_value!(Now, T::Moment);
+			move_value!(ParentHash, T::Hash);
+			move_value!(BlockHash, T::Hash);
+			move_value!(CreationBlockHash, T::Hash);
+			move_value!(CreationBlockNumber, T::BlockNumber);
+			move_value!(CreationTimestamp, T::Moment);
+		}
+
+		STORAGE_VERSION.put::<Pallet<T>>();
+		log::info!("migrated {} parentchain storage items to storage version 2", moved_items);
+
+		T::DbWeight::get().reads_writes(moved_items + 1, 2 * moved_items + 1)
+	}
+
+	fn take_value<V: Decode + Sized>(pallet_name: &[u8], item: &[u8]) -> Option<V> {
+		take_storage_value(pallet_name, item, &[])
+	}
+}
@@ -52,23 +52,19 @@ frame_support::construct_runtime!(
 	{
 		System: frame_system::{Pallet, Call, Config, Event<T>},
 		Balances: pallet_balances::{Pallet, Call, Config<T>, Event<T>},
-		ParentchainIntegritee: pallet_parentchain::<Instance1>::{Pallet, Call, Event<T>},
-		ParentchainTargetA: pallet_parentchain::<Instance2>::{Pallet, Call, Event<T>},
+		Parentchain: pallet_parentchain::{Pallet, Call, Event<T>},
 	}
 );
 
-pub type ParentchainInstanceIntegritee = pallet_parentchain::Instance1;
-impl Config<ParentchainInstanceIntegritee> for Test {
-	type RuntimeEvent = RuntimeEvent;
-	type WeightInfo = ();
-	type Moment = u64;
-}
+pub type ParentchainId = u32;
+pub const INTEGRITEE: ParentchainId = 0;
+pub const TARGET_A: ParentchainId = 1;
 
-pub type ParentchainInstanceTargetA = pallet_parentchain::Instance2;
-impl Config<crate::mock::ParentchainInstanceTargetA> for Test {
+impl Config for Test {
 	type RuntimeEvent = RuntimeEvent;
 	type WeightInfo = ();
 	type Moment = u64;
+	type ParentchainId = ParentchainId;
 }
 
 parameter_types! {
@@ -14,8 +14,14 @@
 	limitations under the License.
 
 */
-use crate::{mock::*, Error, Event as ParentchainEvent};
-use frame_support::{assert_err, assert_noop, assert_ok};
+use crate::{migrations::v2::migrate, mock::*, Error, Event as ParentchainEvent};
+use codec::Encode;
+use frame_support::{
+	assert_err, assert_noop, assert_ok,
+	storage::migration::{have_storage_value, put_storage_value},
+	traits::{GetStorageVersion, StorageVersion},
+	Blake2_128Concat, StorageHasher,
+};
 use frame_system::AccountInfo;
 use pallet_balances::AccountData;
 use sp_core::H256;
@@ -43,19 +49,22 @@ fn verify_storage_works() {
 	let hash = header.hash();
 
 	new_test_ext().execute_with(|| {
-		assert_ok!(ParentchainIntegritee::set_block(RuntimeOrigin::root(), header));
-		assert_eq!(ParentchainIntegritee::block_number().unwrap(), block_number);
-		assert_eq!(ParentchainIntegritee::parent_hash().unwrap(), parent_hash);
-		assert_eq!(ParentchainIntegritee::block_hash().unwrap(), hash);
+		assert_ok!(Parentchain::set_block(RuntimeOrigin::root(), INTEGRITEE, header));
+		assert_eq!(Parentchain::block_number(INTEGRITEE).unwrap(), block_number);
+		assert_eq!(Parentchain::parent_hash(INTEGRITEE).unwrap(), parent_hash);
+		assert_eq!(Parentchain::block_hash(INTEGRITEE).unwrap(), hash);
 
-		System::assert_last_event(RuntimeEvent::ParentchainIntegritee(
-			ParentchainEvent::SetBlock { block_number, parent_hash, block_hash: hash },
-		));
+		System::assert_last_event(RuntimeEvent::Parentchain(ParentchainEvent::SetBlock {
+			parentchain_id: INTEGRITEE,
+			block_number,
+			parent_hash,
+			block_hash: hash,
+		}));
 	})
 }
 
 #[test]
-fn multi_pallet_instance_storage_works() {
+fn multi_parentchain_storage_works() {
 	let block_number = 3;
 	let parent_hash = H256::from_low_u64_be(420);
 
@@ -81,29 +90,33 @@ fn multi_pallet_instance_storage_works() {
 	let hash_a = header_a.hash();
 
 	new_test_ext().execute_with(|| {
-		assert_ok!(ParentchainIntegritee::set_block(RuntimeOrigin::root(), header));
-		assert_eq!(ParentchainIntegritee::block_number().unwrap(), block_number);
-		assert_eq!(ParentchainIntegritee::parent_hash().unwrap(), parent_hash);
-		assert_eq!(ParentchainIntegritee::block_hash().unwrap(), hash);
+		assert_ok!(Parentchain::set_block(RuntimeOrigin::root(), INTEGRITEE, header));
+		assert_eq!(Parentchain::block_number(INTEGRITEE).unwrap(), block_number);
+		assert_eq!(Parentchain::parent_hash(INTEGRITEE).unwrap(), parent_hash);
+		assert_eq!(Parentchain::block_hash(INTEGRITEE).unwrap(), hash);
 
-		System::assert_last_event(RuntimeEvent::ParentchainIntegritee(
-			ParentchainEvent::SetBlock { block_number, parent_hash, block_hash: hash },
-		));
+		System::assert_last_event(RuntimeEvent::Parentchain(ParentchainEvent::SetBlock {
+			parentchain_id: INTEGRITEE,
+			block_number,
+			parent_hash,
+			block_hash: hash,
+		}));
 
-		assert_ok!(ParentchainTargetA::set_block(RuntimeOrigin::root(), header_a));
-		assert_eq!(ParentchainTargetA::block_number().unwrap(), block_number_a);
-		assert_eq!(ParentchainTargetA::parent_hash().unwrap(), parent_hash_a);
-		assert_eq!(ParentchainTargetA::block_hash().unwrap(), hash_a);
+		assert_ok!(Parentchain::set_block(RuntimeOrigin::root(), TARGET_A, header_a));
+		assert_eq!(Parentchain::block_number(TARGET_A).unwrap(), block_number_a);
+		assert_eq!(Parentchain::parent_hash(TARGET_A).unwrap(), parent_hash_a);
+		assert_eq!(Parentchain::block_hash(TARGET_A).unwrap(), hash_a);
 
-		System::assert_last_event(RuntimeEvent::ParentchainTargetA(ParentchainEvent::SetBlock {
+		System::assert_last_event(RuntimeEvent::Parentchain(ParentchainEvent::SetBlock {
+			parentchain_id: TARGET_A,
 			block_number: block_number_a,
 			parent_hash: parent_hash_a,
 			block_hash: hash_a,
 		}));
 
 		// double check previous storage
-		assert_eq!(ParentchainIntegritee::block_number().unwrap(), block_number);
-		assert_eq!(ParentchainIntegritee::block_hash().unwrap(), hash);
+		assert_eq!(Parentchain::block_number(INTEGRITEE).unwrap(), block_number);
+		assert_eq!(Parentchain::block_hash(INTEGRITEE).unwrap(), hash);
 	})
 }
 
@@ -120,7 +133,7 @@ fn non_root_account_errs() {
 	new_test_ext().execute_with(|| {
 		let root = AccountKeyring::Ferdie.to_account_id();
 		assert_err!(
-			ParentchainIntegritee::set_block(RuntimeOrigin::signed(root), header),
+			Parentchain::set_block(RuntimeOrigin::signed(root), INTEGRITEE, header),
 			BadOrigin
 		);
 	})
@@ -130,15 +143,18 @@ fn non_root_account_errs() {
 fn init_shard_vault_works() {
 	new_test_ext().execute_with(|| {
 		let vault = AccountKeyring::Alice.to_account_id();
-		assert_ok!(ParentchainIntegritee::init_shard_vault(RuntimeOrigin::root(), vault.clone()));
-		assert_eq!(ParentchainIntegritee::shard_vault().unwrap(), vault);
+		assert_ok!(Parentchain::init_shard_vault(RuntimeOrigin::root(), TARGET_A, vault.clone()));
+		assert_eq!(Parentchain::shard_vault().unwrap(), (vault.clone(), TARGET_A));
 
-		System::assert_last_event(RuntimeEvent::ParentchainIntegritee(
-			ParentchainEvent::ShardVaultInitialized { account: vault.clone() },
+		System::assert_last_event(RuntimeEvent::Parentchain(
+			ParentchainEvent::ShardVaultInitialized {
+				parentchain_id: TARGET_A,
+				account: vault.clone(),
+			},
 		));
 		assert_noop!(
-			ParentchainIntegritee::init_shard_vault(RuntimeOrigin::root(), vault.clone()),
-			Error::<Test, ParentchainInstanceIntegritee>::ShardVaultAlreadyInitialized
+			Parentchain::init_shard_vault(RuntimeOrigin::root(), INTEGRITEE, vault.clone()),
+			Error::<Test>::ShardVaultAlreadyInitialized
 		);
 	})
 }
@@ -146,19 +162,32 @@ fn init_shard_vault_works() {
 fn init_parentchain_genesis_hash_works() {
 	new_test_ext().execute_with(|| {
 		let genesis = H256::default();
-		assert_ok!(ParentchainIntegritee::init_parentchain_genesis_hash(
+		assert_ok!(Parentchain::init_parentchain_genesis_hash(
 			RuntimeOrigin::root(),
+			INTEGRITEE,
 			genesis
 		));
-		assert_eq!(ParentchainIntegritee::parentchain_genesis_hash().unwrap(), genesis);
+		assert_eq!(Parentchain::parentchain_genesis_hash(INTEGRITEE).unwrap(), genesis);
+		assert_eq!(Parentchain::parentchain_genesis_hash(TARGET_A), None);
 
-		System::assert_last_event(RuntimeEvent::ParentchainIntegritee(
-			ParentchainEvent::ParentchainGenesisInitialized { hash: genesis },
+		System::assert_last_event(RuntimeEvent::Parentchain(
+			ParentchainEvent::ParentchainGenesisInitialized {
+				parentchain_id: INTEGRITEE,
+				hash: genesis,
+			},
 		));
 		assert_noop!(
-			ParentchainIntegritee::init_parentchain_genesis_hash(RuntimeOrigin::root(), genesis),
-			Error::<Test, ParentchainInstanceIntegritee>::GenesisAlreadyInitialized
+			Parentchain::init_parentchain_genesis_hash(RuntimeOrigin::root(), INTEGRITEE, genesis),
+			Error::<Test>::GenesisAlreadyInitialized
 		);
+		assert_ok!(Parentchain::init_parentchain_genesis_hash(
+			RuntimeOrigin::root(),
+			TARGET_A,
+			genesis
+		));
+		let mut parentchain_ids: Vec<_> = Parentchain::parentchain_ids().collect();
+		parentchain_ids.sort();
+		assert_eq!(parentchain_ids, vec![INTEGRITEE, TARGET_A]);
 	})
 }
 #[test]
@@ -177,15 +206,20 @@ fn force_account_info_works() {
 				flags: Default::default(),
 			},
 		};
-		assert_ok!(ParentchainIntegritee::force_account_info(
+		assert_ok!(Parentchain::force_account_info(
 			RuntimeOrigin::root(),
+			INTEGRITEE,
 			vault.clone(),
 			account_info.clone()
 		));
-		assert_eq!(ParentchainIntegritee::account(&vault), account_info);
+		assert_eq!(Parentchain::account(INTEGRITEE, &vault), account_info);
+		assert_eq!(Parentchain::account(TARGET_A, &vault), Default::default());
 
-		System::assert_last_event(RuntimeEvent::ParentchainIntegritee(
-			ParentchainEvent::AccountInfoForcedFor { account: vault.clone() },
+		System::assert_last_event(RuntimeEvent::Parentchain(
+			ParentchainEvent::AccountInfoForcedFor {
+				parentchain_id: INTEGRITEE,
+				account: vault.clone(),
+			},
 		));
 	})
 }
@@ -194,8 +228,8 @@ fn force_account_info_works() {
 fn set_now_works() {
 	new_test_ext().execute_with(|| {
 		let now = 111u64;
-		assert_ok!(ParentchainIntegritee::set_now(RuntimeOrigin::root(), now));
-		assert_eq!(ParentchainIntegritee::now(), Some(now));
+		assert_ok!(Parentchain::set_now(RuntimeOrigin::root(), INTEGRITEE, now));
+		assert_eq!(Parentchain::now(INTEGRITEE), Some(now));
 	})
 }
 
@@ -203,8 +237,8 @@ fn set_now_works() {
 fn set_creation_timestamp_works() {
 	new_test_ext().execute_with(|| {
 		let now = 111u64;
-		assert_ok!(ParentchainIntegritee::set_creation_timestamp(RuntimeOrigin::root(), now));
-		assert_eq!(ParentchainIntegritee::creation_timestamp(), Some(now));
+		assert_ok!(Parentchain::set_creation_timestamp(RuntimeOrigin::root(), INTEGRITEE, now));
+		assert_eq!(Parentchain::creation_timestamp(INTEGRITEE), Some(now));
 	})
 }
 
@@ -215,8 +249,57 @@ fn set_creation_block_works() {
 		Header::new(1, Default::default(), Default::default(), parent_hash, Default::default());
 	let hash = header.hash();
 	new_test_ext().execute_with(|| {
-		assert_ok!(ParentchainIntegritee::set_creation_block(RuntimeOrigin::root(), header));
-		assert_eq!(ParentchainIntegritee::creation_block_hash(), Some(hash));
-		assert_eq!(ParentchainIntegritee::creation_block_number(), Some(1));
+		assert_ok!(Parentchain::set_creation_block(RuntimeOrigin::root(), INTEGRITEE, header));
+		assert_eq!(Parentchain::creation_block_hash(INTEGRITEE), Some(hash));
+		assert_eq!(Parentchain::creation_block_number(INTEGRITEE), Some(1));
+	})
+}
+
+#[test]
+fn migrate_legacy_pallet_instances_works() {
+	let vault = AccountKeyring::Alice.to_account_id();
+	let account_info = AccountInfo {
+		nonce: 42,
+		consumers: 1,
+		providers: 1,
+		sufficients: 1,
+		data: AccountData { free: 1000, ..Default::default() },
+	};
+	let block_hash = H256::from_low_u64_be(42);
+	let target_genesis = H256::from_low_u64_be(43);
+
+	new_test_ext().execute_with(|| {
+		put_storage_value(b"ParentchainIntegritee", b"Number", &[], 7u32);
+		put_storage_value(b"ParentchainIntegritee", b"BlockHash", &[], block_hash);
+		put_storage_value(
+			b"ParentchainIntegritee",
+			b"Account",
+			&Blake2_128Concat::hash(&vault.encode()),
+			account_info.clone(),
+		);
+		put_storage_value(b"ParentchainTargetA", b"ShardVault", &[], vault.clone());
+		put_storage_value(b"ParentchainTargetA", b"ParentchainGenesisHash", &[], target_genesis);
+		put_storage_value(b"ParentchainTargetA", b"Now", &[], 1234u64);
+
+		let legacy_instances: [(&[u8], ParentchainId); 2] =
+			[(b"ParentchainIntegritee", INTEGRITEE), (b"ParentchainTargetA", TARGET_A)];
+		migrate::<Test>(&legacy_instances);
+
+		assert_eq!(Parentchain::block_number(INTEGRITEE), Some(7));
+		assert_eq!(Parentchain::block_hash(INTEGRITEE), Some(block_hash));
+		assert_eq!(Parentchain::account(INTEGRITEE, &vault), account_info);
+		assert_eq!(Parentchain::shard_vault(), Some((vault.clone(), TARGET_A)));
+		assert_eq!(Parentchain::parentchain_genesis_hash(TARGET_A), Some(target_genesis));
+		assert_eq!(Parentchain::now(TARGET_A), Some(1234));
+		assert_eq!(Parentchain::block_number(TARGET_A), None);
+
+		assert!(!have_storage_value(b"ParentchainIntegritee", b"Number", &[]));
+		assert!(!have_storage_value(b"ParentchainTargetA", b"ShardVault", &[]));
+		assert_eq!(Parentchain::on_chain_storage_version(), StorageVersion::new(2));
+
+		// A second run must not touch the migrated storage.
+		put_storage_value(b"ParentchainIntegritee", b"Number", &[], 8u32);
+		migrate::<Test>(&legacy_instances);
+		assert_eq!(Parentchain::block_number(INTEGRITEE), Some(7));
 	})
 }
@@ -0,0 +1,260 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, Encode};
+pub use pallet::*;
+use scale_info::TypeInfo;
+use sp_runtime::traits::{One, Saturating, UniqueSaturatedInto, Zero};
+use sp_std::vec::Vec;
+
+pub type TaskId = u64;
+
+/// Key of the agenda: the due time as big-endian bytes. With the identity hasher, the agenda is
+/// hence iterated in the order of due times.
+pub type DueKey = [u8; 8];
+
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, sp_core::RuntimeDebug, TypeInfo)]
+pub struct Recurrence<Moment> {
+	/// time between two executions
+	pub period: Moment,
+	/// how many times the call will be executed after the next execution
+	pub remaining: u32,
+}
+
+#[derive(Encode, Decode, Clone, PartialEq, Eq, sp_core::RuntimeDebug, TypeInfo)]
+pub struct ScheduledCall<AccountId, Moment> {
+	pub owner: AccountId,
+	/// the call will be executed as soon as the time has reached this moment
+	pub due: Moment,
+	pub recurrence: Option<Recurrence<Moment>>,
+	/// opaque call. it's up to the caller of `take_due` to decode and execute it on behalf of the owner
+	pub call: Vec<u8>,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
+	#[pallet::without_storage_info]
+	pub struct Pallet<T>(PhantomData<T>);
+
+	/// Configuration trait.
+	#[pallet::config]
+	pub trait Config: frame_system::Config + pallet_timestamp::Config {
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// max encoded length of a scheduled call
+		#[pallet::constant]
+		type MaxCallSize: Get<u32>;
+
+		/// max number of calls which can be scheduled at the same time, across all accounts
+		#[pallet::constant]
+		type MaxScheduledCalls: Get<u32>;
+
+		/// max number of calls which can be scheduled at the same time by a single account
+		#[pallet::constant]
+		type MaxScheduledCallsPerAccount: Get<u32>;
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		Scheduled { task_id: TaskId, owner: T::AccountId, due: T::Moment },
+		Cancelled { task_id: TaskId },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		CallTooLong,
+		TooManyScheduledCalls,
+		TooManyScheduledCallsForAccount,
+		ZeroPeriod,
+		DueInThePast,
+		UnknownTask,
+		NotOwner,
+		Overflow,
+	}
+
+	#[pallet::storage]
+	#[pallet::getter(fn next_task_id)]
+	pub(super) type NextTaskId<T: Config> = StorageValue<_, TaskId, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn scheduled_calls)]
+	pub(super) type ScheduledCalls<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		TaskId,
+		ScheduledCall<T::AccountId, T::Moment>,
+		OptionQuery,
+	>;
+
+	/// ids of the pending tasks by their due time, see [`DueKey`]
+	#[pallet::storage]
+	pub(super) type Agenda<T: Config> = StorageMap<_, Identity, DueKey, Vec<TaskId>, ValueQuery>;
+
+	/// number of pending tasks, across all accounts
+	#[pallet::storage]
+	#[pallet::getter(fn scheduled_call_count)]
+	pub(super) type ScheduledCallCount<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn tasks_of)]
+	pub(super) type TasksOf<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, Vec<TaskId>, ValueQuery>;
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		#[pallet::call_index(0)]
+		#[pallet::weight((10_000, DispatchClass::Normal, Pays::Yes))]
+		pub fn schedule(
+			origin: OriginFor<T>,
+			due: T::Moment,
+			recurrence: Option<Recurrence<T::Moment>>,
+			call: Vec<u8>,
+		) -> DispatchResultWithPostInfo {
+			let owner = ensure_signed(origin)?;
+			ensure!(call.len() <= T::MaxCallSize::get() as usize, Error::<T>::CallTooLong);
+			if let Some(recurrence) = recurrence {
+				ensure!(!recurrence.period.is_zero(), Error::<T>::ZeroPeriod);
+			}
+			ensure!(due >= pallet_timestamp::Pallet::<T>::get(), Error::<T>::DueInThePast);
+			ensure!(
+				Self::scheduled_call_count() < T::MaxScheduledCalls::get(),
+				Error::<T>::TooManyScheduledCalls
+			);
+			ensure!(
+				Self::tasks_of(&owner).len() < T::MaxScheduledCallsPerAccount::get() as usize,
+				Error::<T>::TooManyScheduledCallsForAccount
+			);
+
+			let task_id = Self::next_task_id();
+			<NextTaskId<T>>::put(task_id.checked_add(1).ok_or(Error::<T>::Overflow)?);
+			<ScheduledCalls<T>>::insert(
+				task_id,
+				ScheduledCall { owner: owner.clone(), due, recurrence, call },
+			);
+			<TasksOf<T>>::mutate(&owner, |tasks| tasks.push(task_id));
+			<ScheduledCallCount<T>>::mutate(|count| *count = count.saturating_add(1));
+			Self::insert_into_agenda(due, task_id);
+
+			Self::deposit_event(Event::Scheduled { task_id, owner, due });
+			Ok(().into())
+		}
+
+		#[pallet::call_index(1)]
+		#[pallet::weight((10_000, DispatchClass::Normal, Pays::Yes))]
+		pub fn cancel(origin: OriginFor<T>, task_id: TaskId) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let task = Self::scheduled_calls(task_id).ok_or(Error::<T>::UnknownTask)?;
+			ensure!(task.owner == who, Error::<T>::NotOwner);
+
+			<Agenda<T>>::mutate_exists(Self::due_key(task.due), |task_ids| {
+				if let Some(ids) = task_ids {
+					ids.retain(|id| *id != task_id);
+					if ids.is_empty() {
+						*task_ids = None;
+					}
+				}
+			});
+			Self::remove_task(task_id, &task.owner);
+
+			Self::deposit_event(Event::Cancelled { task_id });
+			Ok(().into())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Removes up to `max` calls from the agenda which are due at `now`, in the order of their due
+	/// time, and returns them for execution.
+	///
+	/// Recurring calls are scheduled again for their next period before they are returned, so the
+	/// caller doesn't need to care whether the execution succeeds. In case `now` is behind by more
+	/// than a period, the missed executions are skipped, and count towards the remaining ones.
+	pub fn take_due(
+		now: T::Moment,
+		max: u32,
+	) -> Vec<(TaskId, ScheduledCall<T::AccountId, T::Moment>)> {
+		let now_key = Self::due_key(now);
+		let max = max as usize;
+
+		let mut due_tasks: Vec<(DueKey, Vec<TaskId>)> = Vec::new();
+		let mut due_count = 0;
+		for (due, task_ids) in <Agenda<T>>::iter() {
+			if due > now_key || due_count >= max {
+				break
+			}
+			due_count += task_ids.len();
+			due_tasks.push((due, task_ids));
+		}
+
+		let mut due_calls = Vec::with_capacity(due_count.min(max));
+		for (due, mut task_ids) in due_tasks {
+			let remaining_task_ids = task_ids.split_off(task_ids.len().min(max - due_calls.len()));
+			if remaining_task_ids.is_empty() {
+				<Agenda<T>>::remove(due);
+			} else {
+				<Agenda<T>>::insert(due, remaining_task_ids);
+			}
+
+			for task_id in task_ids {
+				let task = match Self::scheduled_calls(task_id) {
+					Some(task) => task,
+					None => continue,
+				};
+				match task.recurrence {
+					Some(recurrence) => {
+						let missed_periods = now.saturating_sub(task.due) / recurrence.period;
+						let skipped: u32 = missed_periods.unique_saturated_into();
+						if recurrence.remaining > skipped {
+							let next_due = task.due.saturating_add(
+								recurrence.period.saturating_mul(missed_periods + One::one()),
+							);
+							<ScheduledCalls<T>>::insert(
+								task_id,
+								ScheduledCall {
+									due: next_due,
+									recurrence: Some(Recurrence {
+										remaining: recurrence.remaining - skipped - 1,
+										..recurrence
+									}),
+									..task.clone()
+								},
+							);
+							Self::insert_into_agenda(next_due, task_id);
+						} else {
+							Self::remove_task(task_id, &task.owner);
+						}
+					},
+					None => Self::remove_task(task_id, &task.owner),
+				}
+				due_calls.push((task_id, task));
+			}
+		}
+		due_calls
+	}
+
+	fn due_key(due: T::Moment) -> DueKey {
+		UniqueSaturatedInto::<u64>::unique_saturated_into(due).to_be_bytes()
+	}
+
+	fn insert_into_agenda(due: T::Moment, task_id: TaskId) {
+		<Agenda<T>>::append(Self::due_key(due), task_id);
+	}
+
+	fn remove_task(task_id: TaskId, owner: &T::AccountId) {
+		<ScheduledCalls<T>>::remove(task_id);
+		<TasksOf<T>>::mutate(owner, |tasks| tasks.retain(|id| *id != task_id));
+		<ScheduledCallCount<T>>::mutate(|count| *count = count.saturating_sub(1));
+	}
+}
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
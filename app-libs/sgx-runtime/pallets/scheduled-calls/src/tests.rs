@@ -0,0 +1,243 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+use crate::{mock::*, Agenda, Error, Event, Recurrence, ScheduledCall};
+use frame_support::{assert_err, assert_ok};
+use sp_keyring::AccountKeyring;
+
+const NOW: u64 = 1_585_058_843_000;
+const ONE_DAY: u64 = 86_400_000;
+
+pub fn set_timestamp(t: u64) {
+	let _ = pallet_timestamp::Pallet::<Test>::set(RuntimeOrigin::none(), t);
+}
+
+fn agenda() -> Vec<(u64, u64)> {
+	Agenda::<Test>::iter()
+		.flat_map(|(due, task_ids)| {
+			task_ids.into_iter().map(move |task_id| (u64::from_be_bytes(due), task_id))
+		})
+		.collect()
+}
+
+fn task_ids(due_calls: Vec<(u64, ScheduledCall<AccountId, Moment>)>) -> Vec<u64> {
+	due_calls.into_iter().map(|(task_id, _)| task_id).collect()
+}
+
+#[test]
+fn schedule_works() {
+	new_test_ext().execute_with(|| {
+		set_timestamp(NOW);
+		let alice = AccountKeyring::Alice.to_account_id();
+		assert_ok!(ScheduledCalls::schedule(
+			RuntimeOrigin::signed(alice.clone()),
+			NOW + ONE_DAY,
+			None,
+			vec![1, 2, 3]
+		));
+		assert_eq!(
+			ScheduledCalls::scheduled_calls(0),
+			Some(ScheduledCall {
+				owner: alice.clone(),
+				due: NOW + ONE_DAY,
+				recurrence: None,
+				call: vec![1, 2, 3]
+			})
+		);
+		assert_eq!(agenda(), vec![(NOW + ONE_DAY, 0)]);
+		assert_eq!(ScheduledCalls::tasks_of(&alice), vec![0]);
+		assert_eq!(ScheduledCalls::next_task_id(), 1);
+		assert_eq!(ScheduledCalls::scheduled_call_count(), 1);
+		System::assert_last_event(
+			Event::Scheduled { task_id: 0, owner: alice, due: NOW + ONE_DAY }.into(),
+		);
+	})
+}
+
+#[test]
+fn schedule_enforces_limits() {
+	new_test_ext().execute_with(|| {
+		set_timestamp(NOW);
+		let alice = AccountKeyring::Alice.to_account_id();
+		let bob = AccountKeyring::Bob.to_account_id();
+		assert_err!(
+			ScheduledCalls::schedule(
+				RuntimeOrigin::signed(alice.clone()),
+				NOW + 1,
+				None,
+				vec![0u8; 513]
+			),
+			Error::<Test>::CallTooLong
+		);
+		assert_err!(
+			ScheduledCalls::schedule(
+				RuntimeOrigin::signed(alice.clone()),
+				NOW + 1,
+				Some(Recurrence { period: 0, remaining: 1 }),
+				vec![]
+			),
+			Error::<Test>::ZeroPeriod
+		);
+		assert_err!(
+			ScheduledCalls::schedule(RuntimeOrigin::signed(alice.clone()), NOW - 1, None, vec![]),
+			Error::<Test>::DueInThePast
+		);
+		for _ in 0..3 {
+			assert_ok!(ScheduledCalls::schedule(
+				RuntimeOrigin::signed(alice.clone()),
+				NOW + 1,
+				None,
+				vec![]
+			));
+		}
+		assert_err!(
+			ScheduledCalls::schedule(RuntimeOrigin::signed(alice), NOW + 1, None, vec![]),
+			Error::<Test>::TooManyScheduledCallsForAccount
+		);
+		for _ in 0..2 {
+			assert_ok!(ScheduledCalls::schedule(
+				RuntimeOrigin::signed(bob.clone()),
+				NOW + 1,
+				None,
+				vec![]
+			));
+		}
+		assert_err!(
+			ScheduledCalls::schedule(RuntimeOrigin::signed(bob), NOW + 1, None, vec![]),
+			Error::<Test>::TooManyScheduledCalls
+		);
+	})
+}
+
+#[test]
+fn cancel_works_for_owner_only() {
+	new_test_ext().execute_with(|| {
+		set_timestamp(NOW);
+		let alice = AccountKeyring::Alice.to_account_id();
+		let bob = AccountKeyring::Bob.to_account_id();
+		assert_ok!(ScheduledCalls::schedule(
+			RuntimeOrigin::signed(alice.clone()),
+			NOW + ONE_DAY,
+			None,
+			vec![]
+		));
+		assert_err!(ScheduledCalls::cancel(RuntimeOrigin::signed(bob), 0), Error::<Test>::NotOwner);
+		assert_ok!(ScheduledCalls::cancel(RuntimeOrigin::signed(alice.clone()), 0));
+		assert_eq!(ScheduledCalls::scheduled_calls(0), None);
+		assert!(agenda().is_empty());
+		assert!(ScheduledCalls::tasks_of(&alice).is_empty());
+		assert_eq!(ScheduledCalls::scheduled_call_count(), 0);
+		assert_err!(
+			ScheduledCalls::cancel(RuntimeOrigin::signed(alice), 0),
+			Error::<Test>::UnknownTask
+		);
+	})
+}
+
+#[test]
+fn take_due_returns_due_calls_in_order() {
+	new_test_ext().execute_with(|| {
+		set_timestamp(NOW);
+		let alice = AccountKeyring::Alice.to_account_id();
+		for due in [NOW + 3, NOW + 1, NOW + 2] {
+			assert_ok!(ScheduledCalls::schedule(
+				RuntimeOrigin::signed(alice.clone()),
+				due,
+				None,
+				vec![]
+			));
+		}
+
+		assert!(ScheduledCalls::take_due(NOW, 10).is_empty());
+		assert_eq!(task_ids(ScheduledCalls::take_due(NOW + 2, 10)), vec![1, 2]);
+		assert_eq!(agenda(), vec![(NOW + 3, 0)]);
+		assert_eq!(ScheduledCalls::tasks_of(&alice), vec![0]);
+		assert_eq!(ScheduledCalls::scheduled_calls(1), None);
+	})
+}
+
+#[test]
+fn take_due_respects_max() {
+	new_test_ext().execute_with(|| {
+		set_timestamp(NOW);
+		let alice = AccountKeyring::Alice.to_account_id();
+		for _ in 0..3 {
+			assert_ok!(ScheduledCalls::schedule(
+				RuntimeOrigin::signed(alice.clone()),
+				NOW + 1,
+				None,
+				vec![]
+			));
+		}
+
+		assert_eq!(task_ids(ScheduledCalls::take_due(NOW + 1, 2)), vec![0, 1]);
+		assert_eq!(task_ids(ScheduledCalls::take_due(NOW + 1, 2)), vec![2]);
+	})
+}
+
+#[test]
+fn take_due_reschedules_recurring_calls() {
+	new_test_ext().execute_with(|| {
+		set_timestamp(NOW);
+		let alice = AccountKeyring::Alice.to_account_id();
+		assert_ok!(ScheduledCalls::schedule(
+			RuntimeOrigin::signed(alice.clone()),
+			NOW + ONE_DAY,
+			Some(Recurrence { period: ONE_DAY, remaining: 1 }),
+			vec![]
+		));
+
+		let due_calls = ScheduledCalls::take_due(NOW + ONE_DAY, 10);
+		assert_eq!(due_calls[0].1.due, NOW + ONE_DAY);
+		assert_eq!(agenda(), vec![(NOW + 2 * ONE_DAY, 0)]);
+		assert_eq!(
+			ScheduledCalls::scheduled_calls(0).unwrap().recurrence,
+			Some(Recurrence { period: ONE_DAY, remaining: 0 })
+		);
+
+		assert_eq!(task_ids(ScheduledCalls::take_due(NOW + 2 * ONE_DAY, 10)), vec![0]);
+		assert!(agenda().is_empty());
+		assert_eq!(ScheduledCalls::scheduled_calls(0), None);
+		assert!(ScheduledCalls::tasks_of(&alice).is_empty());
+	})
+}
+
+#[test]
+fn take_due_skips_missed_periods_of_recurring_calls() {
+	new_test_ext().execute_with(|| {
+		set_timestamp(NOW);
+		let alice = AccountKeyring::Alice.to_account_id();
+		assert_ok!(ScheduledCalls::schedule(
+			RuntimeOrigin::signed(alice.clone()),
+			NOW + ONE_DAY,
+			Some(Recurrence { period: ONE_DAY, remaining: 5 }),
+			vec![]
+		));
+
+		assert_eq!(task_ids(ScheduledCalls::take_due(NOW + 3 * ONE_DAY + 1, 10)), vec![0]);
+		assert_eq!(agenda(), vec![(NOW + 4 * ONE_DAY, 0)]);
+		assert_eq!(
+			ScheduledCalls::scheduled_calls(0).unwrap().recurrence,
+			Some(Recurrence { period: ONE_DAY, remaining: 2 })
+		);
+		assert!(ScheduledCalls::take_due(NOW + 3 * ONE_DAY + 2, 10).is_empty());
+
+		assert_eq!(task_ids(ScheduledCalls::take_due(NOW + 10 * ONE_DAY, 10)), vec![0]);
+		assert!(agenda().is_empty());
+		assert!(ScheduledCalls::tasks_of(&alice).is_empty());
+		assert_eq!(ScheduledCalls::scheduled_call_count(), 0);
+	})
+}
@@ -0,0 +1,102 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+*/
+
+//! Accounts that are affected by the runtime events, e.g. to notify their subscribers.
+
+use crate::{AccountId, RuntimeEvent};
+use sp_std::{vec, vec::Vec};
+
+/// The accounts that are named in the fields of the event, e.g. both sides of a transfer.
+///
+/// Events without any account field, or whose accounts are not substrate accounts (e.g. the EVM
+/// addresses), affect no account.
+pub fn accounts_of_event(event: &RuntimeEvent) -> Vec<AccountId> {
+	use frame_system::Event as System;
+	use pallet_assets::Event as Assets;
+	use pallet_balances::Event as Balances;
+	use pallet_contracts::Event as Contracts;
+	use pallet_multisig_calls::Event as MultisigCalls;
+	use pallet_parentchain::Event as Parentchain;
+	use pallet_scheduled_calls::Event as ScheduledCalls;
+	use pallet_sudo::Event as Sudo;
+	use pallet_transaction_payment::Event as TransactionPayment;
+
+	let accounts: Vec<&AccountId> = match event {
+		RuntimeEvent::System(e) => match e {
+			System::NewAccount { account } | System::KilledAccount { account } => vec![account],
+			System::Remarked { sender, .. } => vec![sender],
+			_ => vec![],
+		},
+		RuntimeEvent::Balances(e) => match e {
+			Balances::Endowed { account, .. } | Balances::DustLost { account, .. } => vec![account],
+			Balances::Transfer { from, to, .. } | Balances::ReserveRepatriated { from, to, .. } =>
+				vec![from, to],
+			Balances::BalanceSet { who, .. }
+			| Balances::Reserved { who, .. }
+			| Balances::Unreserved { who, .. }
+			| Balances::Deposit { who, .. }
+			| Balances::Withdraw { who, .. }
+			| Balances::Slashed { who, .. } => vec![who],
+			_ => vec![],
+		},
+		RuntimeEvent::TransactionPayment(e) => match e {
+			TransactionPayment::TransactionFeePaid { who, .. } => vec![who],
+			_ => vec![],
+		},
+		RuntimeEvent::Sudo(e) => match e {
+			Sudo::KeyChanged { old_sudoer } => old_sudoer.iter().collect(),
+			_ => vec![],
+		},
+		RuntimeEvent::Assets(e) => match e {
+			Assets::Created { creator, owner, .. } => vec![creator, owner],
+			Assets::Issued { owner, .. }
+			| Assets::Burned { owner, .. }
+			| Assets::OwnerChanged { owner, .. }
+			| Assets::ForceCreated { owner, .. } => vec![owner],
+			Assets::Transferred { from, to, .. } => vec![from, to],
+			Assets::TeamChanged { issuer, admin, freezer, .. } => vec![issuer, admin, freezer],
+			Assets::Frozen { who, .. } | Assets::Thawed { who, .. } => vec![who],
+			Assets::ApprovedTransfer { source, delegate, .. } => vec![source, delegate],
+			Assets::ApprovalCancelled { owner, delegate, .. } => vec![owner, delegate],
+			Assets::TransferredApproved { owner, delegate, destination, .. } =>
+				vec![owner, delegate, destination],
+			_ => vec![],
+		},
+		RuntimeEvent::Parentchain(e) => match e {
+			Parentchain::ShardVaultInitialized { account, .. }
+			| Parentchain::AccountInfoForcedFor { account, .. } => vec![account],
+			_ => vec![],
+		},
+		RuntimeEvent::ScheduledCalls(e) => match e {
+			ScheduledCalls::Scheduled { owner, .. } => vec![owner],
+			_ => vec![],
+		},
+		RuntimeEvent::MultisigCalls(e) => match e {
+			MultisigCalls::Proposed { multisig, proposer, .. } => vec![multisig, proposer],
+			MultisigCalls::Approved { multisig, approving, .. } => vec![multisig, approving],
+			MultisigCalls::Cancelled { multisig, .. } => vec![multisig],
+			_ => vec![],
+		},
+		RuntimeEvent::Contracts(e) => match e {
+			Contracts::Instantiated { deployer, contract, .. } => vec![deployer, contract],
+			Contracts::Called { caller, contract, .. } => vec![caller, contract],
+			Contracts::ContractEmitted { contract, .. } => vec![contract],
+			_ => vec![],
+		},
+		_ => vec![],
+	};
+	accounts.into_iter().cloned().collect()
+}
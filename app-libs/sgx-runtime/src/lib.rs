@@ -28,9 +28,12 @@
 // `construct_runtime!` does a lot of recursion and requires us to increase the limit to 256.
 #![recursion_limit = "256"]
 
+mod events;
 #[cfg(feature = "evm")]
 mod evm;
 
+pub use events::accounts_of_event;
+
 #[cfg(feature = "evm")]
 pub use evm::{
 	AddressMapping, EnsureAddressTruncated, EvmCall, FeeCalculator, FixedGasPrice,
@@ -54,7 +57,8 @@ use sp_version::RuntimeVersion;
 pub use itp_sgx_runtime_primitives::{
 	constants::SLOT_DURATION,
 	types::{
-		AccountData, AccountId, Address, Balance, BlockNumber, Hash, Header, Index, Signature,
+		AccountData, AccountId, Address, AssetId, Balance, BlockNumber, Hash, Header, Index,
+		Signature,
 	},
 };
 
@@ -71,16 +75,21 @@ pub use frame_support::{
 	StorageValue,
 };
 use frame_support::{
-	traits::{ConstU8, EitherOfDiverse},
+	traits::{AsEnsureOriginWithArg, ConstU128, ConstU8, EitherOfDiverse, NeverEnsureOrigin},
 	PalletId,
 };
 use frame_system::{EnsureRoot, EnsureSignedBy};
 use itp_randomness::SgxRandomness;
 use itp_sgx_runtime_primitives::types::Moment;
+use itp_types::parentchain::ParentchainId;
+pub use pallet_assets::Call as AssetsCall;
 pub use pallet_balances::Call as BalancesCall;
+pub use pallet_contracts::{Call as ContractsCall, ContractInfo};
 pub use pallet_guess_the_number::{Call as GuessTheNumberCall, GuessType};
+pub use pallet_multisig_calls::Call as MultisigCallsCall;
 pub use pallet_notes::Call as NotesCall;
 pub use pallet_parentchain::Call as ParentchainPalletCall;
+pub use pallet_scheduled_calls::Call as ScheduledCallsCall;
 pub use pallet_timestamp::Call as TimestampCall;
 use sp_core::crypto::AccountId32;
 #[cfg(any(feature = "std", test))]
@@ -249,6 +258,29 @@ impl pallet_balances::Config for Runtime {
 	type MaxFreezes = ConstU32<0>;
 }
 
+impl pallet_assets::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Balance = Balance;
+	/// Assets mirror the ones of the parentchain holding the shard vault, with the same id.
+	type AssetId = AssetId;
+	type AssetIdParameter = AssetId;
+	type Currency = Balances;
+	/// Assets are only created by the enclave upon shielding, with root origin.
+	type CreateOrigin = AsEnsureOriginWithArg<NeverEnsureOrigin<AccountId>>;
+	type ForceOrigin = EnsureRoot<AccountId>;
+	type AssetDeposit = ConstU128<0>;
+	type AssetAccountDeposit = ConstU128<0>;
+	type MetadataDepositBase = ConstU128<0>;
+	type MetadataDepositPerByte = ConstU128<0>;
+	type ApprovalDeposit = ConstU128<0>;
+	type StringLimit = ConstU32<50>;
+	type Freezer = ();
+	type Extra = ();
+	type CallbackHandle = ();
+	type WeightInfo = ();
+	type RemoveItemsLimit = ConstU32<1000>;
+}
+
 parameter_types! {
 	pub const TransactionByteFee: Balance = 1;
 	pub const OperationalFeeMultiplier: u8 = 5;
@@ -268,26 +300,20 @@ impl pallet_sudo::Config for Runtime {
 	type RuntimeCall = RuntimeCall;
 }
 
-pub type ParentchainInstanceIntegritee = pallet_parentchain::Instance1;
-impl pallet_parentchain::Config<ParentchainInstanceIntegritee> for Runtime {
-	type WeightInfo = ();
-	type RuntimeEvent = RuntimeEvent;
-	type Moment = Moment;
-}
-
-pub type ParentchainInstanceTargetA = pallet_parentchain::Instance2;
-impl pallet_parentchain::Config<crate::ParentchainInstanceTargetA> for Runtime {
+impl pallet_parentchain::Config for Runtime {
 	type WeightInfo = ();
 	type RuntimeEvent = RuntimeEvent;
 	type Moment = Moment;
+	type ParentchainId = ParentchainId;
 }
 
-pub type ParentchainInstanceTargetB = pallet_parentchain::Instance3;
-impl pallet_parentchain::Config<crate::ParentchainInstanceTargetB> for Runtime {
-	type WeightInfo = ();
-	type RuntimeEvent = RuntimeEvent;
-	type Moment = Moment;
-}
+/// Pallet names of the former per-parentchain `pallet_parentchain` instances, and the
+/// parentchain their storage is migrated to.
+pub const LEGACY_PARENTCHAIN_PALLET_INSTANCES: [(&[u8], ParentchainId); 3] = [
+	(b"ParentchainIntegritee", ParentchainId::Integritee),
+	(b"ParentchainTargetA", ParentchainId::Target(0)),
+	(b"ParentchainTargetB", ParentchainId::Target(1)),
+];
 
 ord_parameter_types! {
 	pub const GameMaster: AccountId32 = AccountId32::new([148, 117, 87, 242, 252, 96, 167, 29, 118, 69, 87, 119, 15, 57, 142, 82, 216, 8, 210, 102, 12, 213, 46, 76, 214, 5, 144, 153, 148, 113, 89, 95]);
@@ -316,6 +342,7 @@ parameter_types! {
 	pub const MaxNoteSize: u32 = 512;
 	pub const MaxBucketSize: u32 = 51_200;
 	pub const MaxTotalSize: u32 = 5_120_000;
+	pub const MaxLinkedAccounts: u32 = 10;
 }
 
 impl pallet_notes::Config for Runtime {
@@ -324,6 +351,54 @@ impl pallet_notes::Config for Runtime {
 	type MaxNoteSize = MaxNoteSize;
 	type MaxBucketSize = MaxBucketSize;
 	type MaxTotalSize = MaxTotalSize;
+	type MaxLinkedAccounts = MaxLinkedAccounts;
+}
+
+parameter_types! {
+	pub const MaxCallSize: u32 = 1024;
+	pub const MaxScheduledCalls: u32 = 10_000;
+	pub const MaxScheduledCallsPerAccount: u32 = 100;
+}
+
+impl pallet_scheduled_calls::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type MaxCallSize = MaxCallSize;
+	type MaxScheduledCalls = MaxScheduledCalls;
+	type MaxScheduledCallsPerAccount = MaxScheduledCallsPerAccount;
+}
+
+parameter_types! {
+	// all signatories are linked to the notes of multisig calls
+	pub const MaxSignatories: u32 = 10;
+	pub const MaxPendingProposals: u32 = 100;
+}
+
+impl pallet_multisig_calls::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type MaxCallSize = MaxCallSize;
+	type MaxSignatories = MaxSignatories;
+	type MaxPendingProposals = MaxPendingProposals;
+}
+
+parameter_types! {
+	pub const MaxCodeSize: u32 = 256 * 1024;
+	pub const MaxValueSize: u32 = 16 * 1024;
+	// a contract call has to fit into a sidechain block, roughly one unit of gas per instruction
+	pub const MaxGas: u64 = 100_000_000;
+	// 1 MiB, the memory is allocated on the heap of the enclave
+	pub const MaxMemoryPages: u32 = 16;
+	// roughly one unit of a parentchain with 12 decimals per MiB of contract storage
+	pub const ContractDepositPerByte: Balance = 1_000_000;
+}
+
+impl pallet_contracts::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type MaxCodeSize = MaxCodeSize;
+	type MaxValueSize = MaxValueSize;
+	type MaxGas = MaxGas;
+	type MaxMemoryPages = MaxMemoryPages;
+	type Currency = Balances;
+	type DepositPerByte = ContractDepositPerByte;
 }
 
 // The plain sgx-runtime without the `evm-pallet`
@@ -339,14 +414,18 @@ construct_runtime!(
 		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>} = 2,
 		TransactionPayment: pallet_transaction_payment::{Pallet, Storage, Event<T>} = 3,
 		Sudo: pallet_sudo::{Pallet, Call, Config<T>, Storage, Event<T>} = 4,
+		Assets: pallet_assets::{Pallet, Call, Storage, Event<T>} = 5,
 
-		ParentchainIntegritee: pallet_parentchain::<Instance1>::{Pallet, Call, Event<T>} = 10,
-		ParentchainTargetA: pallet_parentchain::<Instance2>::{Pallet, Call, Event<T>} = 11,
-		ParentchainTargetB: pallet_parentchain::<Instance3>::{Pallet, Call, Event<T>} = 12,
+		Parentchain: pallet_parentchain::{Pallet, Call, Event<T>} = 10,
 
 		GuessTheNumber: pallet_guess_the_number::{Pallet, Call, Storage, Event<T>} = 30,
 
 		Notes: pallet_notes::{Pallet, Call, Storage} = 40,
+
+		ScheduledCalls: pallet_scheduled_calls::{Pallet, Call, Storage, Event<T>} = 50,
+		MultisigCalls: pallet_multisig_calls::{Pallet, Call, Storage, Event<T>} = 51,
+
+		Contracts: pallet_contracts::{Pallet, Call, Storage, Event<T>} = 60,
 	}
 );
 
@@ -366,16 +445,20 @@ construct_runtime!(
 		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>} = 2,
 		TransactionPayment: pallet_transaction_payment::{Pallet, Storage, Event<T>} = 3,
 		Sudo: pallet_sudo::{Pallet, Call, Config<T>, Storage, Event<T>} = 4,
+		Assets: pallet_assets::{Pallet, Call, Storage, Event<T>} = 5,
 
-		ParentchainIntegritee: pallet_parentchain::<Instance1>::{Pallet, Call, Event<T>} = 10,
-		ParentchainTargetA: pallet_parentchain::<Instance2>::{Pallet, Call, Event<T>} = 11,
-		ParentchainTargetB: pallet_parentchain::<Instance3>::{Pallet, Call, Event<T>} = 12,
+		Parentchain: pallet_parentchain::{Pallet, Call, Event<T>} = 10,
 
 		Evm: pallet_evm::{Pallet, Call, Storage, Config, Event<T>} = 20,
 
 		GuessTheNumber: pallet_guess_the_number::{Pallet, Call, Storage, Event<T>} = 30,
 
 		Notes: pallet_notes::{Pallet, Call, Storage} = 40,
+
+		ScheduledCalls: pallet_scheduled_calls::{Pallet, Call, Storage, Event<T>} = 50,
+		MultisigCalls: pallet_multisig_calls::{Pallet, Call, Storage, Event<T>} = 51,
+
+		Contracts: pallet_contracts::{Pallet, Call, Storage, Event<T>} = 60,
 	}
 );
 
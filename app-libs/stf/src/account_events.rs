@@ -0,0 +1,138 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Subscription of a client to everything that touches its account, i.e. the status of the
+//! trusted operations it signed and the runtime events (e.g. incoming transfers).
+
+use crate::helpers::wrap_bytes;
+use codec::{Decode, Encode};
+use itp_stf_primitives::types::{AccountId, KeyPair, ShardIdentifier, Signature};
+use sp_core::{blake2_256, H256};
+use sp_runtime::traits::Verify;
+use std::vec::Vec;
+
+/// Max time from now until a subscription expires, so that a leaked request can only be
+/// replayed for a limited time.
+pub const MAX_ACCOUNT_EVENTS_SUBSCRIPTION_LIFETIME_MILLIS: u64 = 24 * 60 * 60 * 1000;
+
+/// Subscription request for the events of an account. Has to be signed by the account
+/// (i.e. its incognito key), as the events reveal the private activity of the account.
+#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq)]
+pub struct AccountEventsSubscription {
+	pub account: AccountId,
+	pub shard: ShardIdentifier,
+	/// Unix timestamp in milliseconds, after which no more updates are sent.
+	pub expires_at: u64,
+	/// Chosen by the client to distinguish multiple subscriptions of the same account.
+	pub nonce: u32,
+}
+
+impl AccountEventsSubscription {
+	pub fn new(account: AccountId, shard: ShardIdentifier, expires_at: u64, nonce: u32) -> Self {
+		AccountEventsSubscription { account, shard, expires_at, nonce }
+	}
+
+	/// Signs the subscription for the enclave with `mrenclave`, like a trusted call.
+	pub fn sign(&self, pair: &KeyPair, mrenclave: &[u8; 32]) -> AccountEventsSubscriptionSigned {
+		let signature = pair.sign(self.signature_payload(mrenclave).as_slice());
+		AccountEventsSubscriptionSigned { subscription: self.clone(), signature }
+	}
+
+	/// Whether the subscription has not expired yet and expires within the max lifetime.
+	pub fn is_valid_at(&self, now: u64) -> bool {
+		now < self.expires_at
+			&& self.expires_at
+				<= now.saturating_add(MAX_ACCOUNT_EVENTS_SUBSCRIPTION_LIFETIME_MILLIS)
+	}
+
+	fn signature_payload(&self, mrenclave: &[u8; 32]) -> Vec<u8> {
+		let mut payload = self.encode();
+		payload.append(&mut mrenclave.encode());
+		payload
+	}
+}
+
+#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq)]
+pub struct AccountEventsSubscriptionSigned {
+	pub subscription: AccountEventsSubscription,
+	pub signature: Signature,
+}
+
+impl AccountEventsSubscriptionSigned {
+	pub fn verify_signature(&self, mrenclave: &[u8; 32]) -> bool {
+		let payload = self.subscription.signature_payload(mrenclave);
+
+		if self.signature.verify(payload.as_slice(), &self.subscription.account) {
+			return true
+		};
+
+		// check if the signature is from an extension-dapp signer.
+		self.signature
+			.verify(wrap_bytes(&payload).as_slice(), &self.subscription.account)
+	}
+
+	/// Id of the subscription, under which the updates are sent to the client.
+	///
+	/// Does not depend on the signature, such that a request can't be used again with another
+	/// valid signature of the same subscription.
+	pub fn subscription_id(&self) -> H256 {
+		blake2_256(&self.subscription.encode()).into()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_keyring::AccountKeyring;
+
+	#[test]
+	fn signature_is_bound_to_mrenclave() {
+		let mrenclave = [1u8; 32];
+		let subscription = AccountEventsSubscription::new(
+			AccountKeyring::Alice.public().into(),
+			ShardIdentifier::default(),
+			1000,
+			0,
+		);
+
+		let signed = subscription
+			.sign(&KeyPair::Sr25519(Box::new(AccountKeyring::Alice.pair())), &mrenclave);
+
+		assert!(signed.verify_signature(&mrenclave));
+		assert!(!signed.verify_signature(&[2u8; 32]));
+	}
+
+	#[test]
+	fn subscription_is_only_valid_within_its_lifetime() {
+		let subscription = AccountEventsSubscription::new(
+			AccountKeyring::Alice.public().into(),
+			ShardIdentifier::default(),
+			1000,
+			0,
+		);
+
+		assert!(subscription.is_valid_at(999));
+		assert!(!subscription.is_valid_at(1000));
+
+		let long_lived = AccountEventsSubscription {
+			expires_at: 1000 + MAX_ACCOUNT_EVENTS_SUBSCRIPTION_LIFETIME_MILLIS,
+			..subscription
+		};
+		assert!(long_lived.is_valid_at(1000));
+		assert!(!long_lived.is_valid_at(999));
+	}
+}
@@ -0,0 +1,232 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+// TrustedCalls and Getters for the confidential wasm contracts
+
+use crate::{
+	helpers::{shielding_target_genesis_hash, store_note},
+	trusted_call::refund_fee,
+	TrustedCall,
+};
+#[cfg(not(feature = "std"))]
+use alloc::format;
+use codec::{Decode, Encode};
+use frame_support::{dispatch::UnfilteredDispatchable, weights::Weight};
+use ita_parentchain_specs::MinimalChainSpec;
+use ita_sgx_runtime::{Contracts, Hash, Runtime};
+use itp_node_api::metadata::provider::AccessNodeMetadata;
+use itp_node_api_metadata::NodeMetadataTrait;
+use itp_sgx_runtime_primitives::types::Balance;
+use itp_stf_interface::{ExecuteCall, ExecuteGetter};
+use itp_stf_primitives::error::StfError;
+use itp_types::{parentchain::ParentchainCall, AccountId};
+use itp_utils::stringify::account_id_to_string;
+use log::*;
+pub use pallet_contracts::{contract_address, ContractInfo};
+use sp_std::{sync::Arc, vec, vec::Vec};
+
+/// Gas covered by one fee unit (`one / STF_TX_FEE_UNIT_DIVIDER`). The fee covers the whole gas
+/// limit, the gas that the contract did not consume is refunded after a successful execution.
+pub const GAS_PER_FEE_UNIT: Balance = 10_000_000;
+
+/// Bytes of code covered by one fee unit, as the code is compiled on every execution.
+pub const CODE_BYTES_PER_FEE_UNIT: Balance = 16 * 1024;
+
+#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq)]
+#[allow(non_camel_case_types)]
+#[repr(u8)]
+#[allow(clippy::unnecessary_cast)]
+pub enum ContractsTrustedCall {
+	upload_code(AccountId, Vec<u8>) = 0, // (Origin, Code)
+	instantiate(AccountId, Hash, Vec<u8>, Vec<u8>, u64) = 1, // (Origin, CodeHash, Salt, Input, Gas limit)
+	call(AccountId, AccountId, Vec<u8>, u64) = 2,            // (Origin, Contract, Input, Gas limit)
+}
+
+impl ContractsTrustedCall {
+	pub fn sender_account(&self) -> &AccountId {
+		match self {
+			Self::upload_code(sender_account, ..) => sender_account,
+			Self::instantiate(sender_account, ..) => sender_account,
+			Self::call(sender_account, ..) => sender_account,
+		}
+	}
+}
+
+impl<NodeMetadataRepository> ExecuteCall<NodeMetadataRepository> for ContractsTrustedCall
+where
+	NodeMetadataRepository: AccessNodeMetadata,
+	NodeMetadataRepository::MetadataType: NodeMetadataTrait,
+{
+	type Error = StfError;
+
+	fn execute(
+		self,
+		_calls: &mut Vec<ParentchainCall>,
+		_node_metadata_repo: Arc<NodeMetadataRepository>,
+	) -> Result<(), Self::Error> {
+		match self.clone() {
+			Self::upload_code(sender, code) => {
+				let origin = ita_sgx_runtime::RuntimeOrigin::signed(sender);
+				std::println!("⣿STF⣿ contracts: upload code of {} bytes", code.len());
+				ita_sgx_runtime::ContractsCall::<Runtime>::upload_code { code }
+					.dispatch_bypass_filter(origin)
+					.map_err(|e| {
+						Self::Error::Dispatch(format!("Contracts upload code error: {:?}", e.error))
+					})?;
+				// no note, the code doesn't fit into one. the sender gets a `CodeStored` event
+				Ok::<(), Self::Error>(())
+			},
+			Self::instantiate(sender, code_hash, salt, input, gas_limit) => {
+				let origin = ita_sgx_runtime::RuntimeOrigin::signed(sender.clone());
+				let contract = contract_address(&sender, &code_hash, &salt);
+				std::println!(
+					"⣿STF⣿ contracts: instantiate contract {}",
+					account_id_to_string(&contract)
+				);
+				let post_info = ita_sgx_runtime::ContractsCall::<Runtime>::instantiate {
+					code_hash,
+					salt,
+					input,
+					gas_limit,
+				}
+				.dispatch_bypass_filter(origin)
+				.map_err(|e| {
+					Self::Error::Dispatch(format!("Contracts instantiate error: {:?}", e.error))
+				})?;
+				refund_unused_gas(&sender, gas_limit, post_info.actual_weight)?;
+				store_note(&sender, TrustedCall::contracts(self), vec![sender.clone()])?;
+				Ok::<(), Self::Error>(())
+			},
+			Self::call(sender, contract, input, gas_limit) => {
+				let origin = ita_sgx_runtime::RuntimeOrigin::signed(sender.clone());
+				std::println!("⣿STF⣿ contracts: call contract {}", account_id_to_string(&contract));
+				let post_info =
+					ita_sgx_runtime::ContractsCall::<Runtime>::call { contract, input, gas_limit }
+						.dispatch_bypass_filter(origin)
+						.map_err(|e| {
+							Self::Error::Dispatch(format!("Contracts call error: {:?}", e.error))
+						})?;
+				refund_unused_gas(&sender, gas_limit, post_info.actual_weight)?;
+				store_note(&sender, TrustedCall::contracts(self), vec![sender.clone()])?;
+				Ok::<(), Self::Error>(())
+			},
+		}?;
+		Ok(())
+	}
+
+	fn get_storage_hashes_to_update(self) -> Vec<Vec<u8>> {
+		debug!("No storage updates needed...");
+		Vec::new()
+	}
+}
+
+/// A base fee plus the size of the compiled code plus the gas limit.
+pub fn get_fee_for(tc: &ContractsTrustedCall) -> Balance {
+	let fee_unit = fee_unit();
+	match tc {
+		ContractsTrustedCall::upload_code(_, code) =>
+			fee_unit * 10 + code_fee(fee_unit, code.len()),
+		ContractsTrustedCall::instantiate(_, code_hash, _, _, gas_limit) =>
+			fee_unit * 3 + code_fee(fee_unit, code_len(code_hash)) + gas_fee(fee_unit, *gas_limit),
+		ContractsTrustedCall::call(_, contract, _, gas_limit) => {
+			let code_len =
+				Contracts::contract_info(contract).map_or(0, |info| code_len(&info.code_hash));
+			fee_unit + code_fee(fee_unit, code_len) + gas_fee(fee_unit, *gas_limit)
+		},
+	}
+}
+
+fn fee_unit() -> Balance {
+	MinimalChainSpec::one_unit(shielding_target_genesis_hash().unwrap_or_default())
+		/ crate::STF_TX_FEE_UNIT_DIVIDER
+}
+
+fn gas_fee(fee_unit: Balance, gas: u64) -> Balance {
+	fee_unit.saturating_mul(gas.into()) / GAS_PER_FEE_UNIT
+}
+
+fn code_fee(fee_unit: Balance, code_len: usize) -> Balance {
+	fee_unit.saturating_mul(code_len as Balance) / CODE_BYTES_PER_FEE_UNIT
+}
+
+fn code_len(code_hash: &Hash) -> usize {
+	Contracts::code(code_hash).map_or(0, |code| code.len())
+}
+
+/// The actual weight of a contract execution is the gas it consumed.
+fn refund_unused_gas(
+	sender: &AccountId,
+	gas_limit: u64,
+	actual_weight: Option<Weight>,
+) -> Result<(), StfError> {
+	let gas_used = actual_weight.map_or(gas_limit, |weight| weight.ref_time());
+	let refund = gas_fee(fee_unit(), gas_limit.saturating_sub(gas_used));
+	if refund > 0 {
+		refund_fee(refund, sender)?;
+	}
+	Ok(())
+}
+
+/// Only the deployer of a contract can query its info and storage.
+#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq)]
+#[allow(non_camel_case_types)]
+pub enum ContractsTrustedGetter {
+	contract_info { origin: AccountId, contract: AccountId },
+	storage { origin: AccountId, contract: AccountId, key: Vec<u8> },
+}
+
+impl ContractsTrustedGetter {
+	pub fn sender_account(&self) -> &AccountId {
+		match self {
+			Self::contract_info { origin, .. } => origin,
+			Self::storage { origin, .. } => origin,
+		}
+	}
+}
+
+impl ExecuteGetter for ContractsTrustedGetter {
+	fn execute(self) -> Option<Vec<u8>> {
+		match self {
+			Self::contract_info { origin, contract } =>
+				deployed_contract_info(&origin, &contract).map(|info| info.encode()),
+			Self::storage { origin, contract, key } => {
+				deployed_contract_info(&origin, &contract)?;
+				std::println!("⣿STF⣿ 🔍 TrustedGetter query: contract storage");
+				Contracts::contract_storage(&contract, key).map(|value| value.encode())
+			},
+		}
+	}
+
+	fn get_storage_hashes_to_update(self) -> Vec<Vec<u8>> {
+		Vec::new()
+	}
+}
+
+fn deployed_contract_info(
+	deployer: &AccountId,
+	contract: &AccountId,
+) -> Option<ContractInfo<AccountId, Hash>> {
+	let info = Contracts::contract_info(contract).filter(|info| &info.deployer == deployer);
+	if info.is_none() {
+		debug!(
+			"{} is not a contract deployed by {}",
+			account_id_to_string(contract),
+			account_id_to_string(deployer)
+		);
+	}
+	info
+}
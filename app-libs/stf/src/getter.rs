@@ -16,9 +16,7 @@
 */
 
 use codec::{Decode, Encode};
-use ita_sgx_runtime::{
-	Balances, Notes, ParentchainIntegritee, ParentchainTargetA, ParentchainTargetB, System,
-};
+use ita_sgx_runtime::{AssetId, Assets, Balances, MultisigCalls, Notes, Parentchain, System};
 use itp_stf_interface::ExecuteGetter;
 use itp_stf_primitives::{
 	traits::GetterAuthorization,
@@ -37,14 +35,16 @@ use ita_sgx_runtime::{AddressMapping, HashedAddressMapping};
 use crate::evm_helpers::{get_evm_account, get_evm_account_codes, get_evm_account_storages};
 
 use crate::{
+	contracts::ContractsTrustedGetter,
 	guess_the_number::{GuessTheNumberPublicGetter, GuessTheNumberTrustedGetter},
 	helpers::{shielding_target, shielding_target_genesis_hash, wrap_bytes},
+	MAX_NOTES_PER_PAGE,
 };
 use ita_parentchain_specs::MinimalChainSpec;
 use itp_sgx_runtime_primitives::types::Moment;
 use itp_stf_primitives::traits::{GetDecimals, PoolTransactionValidation};
 use itp_types::parentchain::{BlockNumber, Hash, ParentchainId};
-use pallet_notes::{BucketIndex, BucketRange};
+use pallet_notes::{BucketIndex, BucketRange, NoteIndex};
 #[cfg(feature = "evm")]
 use sp_core::{H160, H256};
 use sp_runtime::transaction_validity::{
@@ -124,8 +124,12 @@ pub enum PublicGetter {
 #[allow(clippy::unnecessary_cast)]
 pub enum TrustedGetter {
 	account_info(AccountId) = 0,
+	asset_balance(AccountId, AssetId) = 1,
 	notes_for(AccountId, BucketIndex) = 10,
+	notes_page_for(AccountId, Option<NoteIndex>, u32) = 11, // (Who, Before, Max)
+	multisig_proposals_for(AccountId) = 20,
 	guess_the_number(GuessTheNumberTrustedGetter) = 50,
+	contracts(ContractsTrustedGetter) = 60,
 	#[cfg(feature = "evm")]
 	evm_nonce(AccountId) = 90,
 	#[cfg(feature = "evm")]
@@ -138,8 +142,12 @@ impl TrustedGetter {
 	pub fn sender_account(&self) -> &AccountId {
 		match self {
 			TrustedGetter::account_info(sender_account) => sender_account,
+			TrustedGetter::asset_balance(sender_account, ..) => sender_account,
 			TrustedGetter::notes_for(sender_account, ..) => sender_account,
+			TrustedGetter::notes_page_for(sender_account, ..) => sender_account,
+			TrustedGetter::multisig_proposals_for(sender_account) => sender_account,
 			TrustedGetter::guess_the_number(getter) => getter.sender_account(),
+			TrustedGetter::contracts(getter) => getter.sender_account(),
 			#[cfg(feature = "evm")]
 			TrustedGetter::evm_nonce(sender_account) => sender_account,
 			#[cfg(feature = "evm")]
@@ -205,6 +213,18 @@ impl ExecuteGetter for TrustedGetterSigned {
 				std::println!("⣿STF⣿ 🔍 TrustedGetter query: account info for ⣿⣿⣿ is ⣿⣿⣿",);
 				Some(info.encode())
 			},
+			TrustedGetter::asset_balance(who, asset_id) => {
+				let balance = Assets::balance(asset_id, &who);
+				debug!("TrustedGetter asset_balance");
+				debug!(
+					"Balance of asset {} for {} is {}",
+					asset_id,
+					account_id_to_string(&who),
+					balance
+				);
+				std::println!("⣿STF⣿ 🔍 TrustedGetter query: asset balance for ⣿⣿⣿ is ⣿⣿⣿",);
+				Some(balance.encode())
+			},
 			TrustedGetter::notes_for(who, bucket_index) => {
 				debug!("TrustedGetter notes_for");
 				let note_indices = Notes::notes_lookup(bucket_index, &who);
@@ -219,7 +239,31 @@ impl ExecuteGetter for TrustedGetterSigned {
 				std::println!("⣿STF⣿ 🔍 TrustedGetter query: notes for ⣿⣿⣿",);
 				Some(notes.encode())
 			},
+			TrustedGetter::notes_page_for(who, before, max) => {
+				debug!("TrustedGetter notes_page_for");
+				let notes = Notes::linked_notes_page(&who, before, max.min(MAX_NOTES_PER_PAGE));
+				debug!(
+					"Returning {} notes for {} before {:?}",
+					notes.len(),
+					account_id_to_string(&who),
+					before
+				);
+				std::println!("⣿STF⣿ 🔍 TrustedGetter query: notes page for ⣿⣿⣿",);
+				Some(notes.encode())
+			},
+			TrustedGetter::multisig_proposals_for(who) => {
+				debug!("TrustedGetter multisig_proposals_for");
+				let proposals = MultisigCalls::proposals_for_signatory(&who);
+				debug!(
+					"{} pending multisig proposals for {}",
+					proposals.len(),
+					account_id_to_string(&who)
+				);
+				std::println!("⣿STF⣿ 🔍 TrustedGetter query: multisig proposals for ⣿⣿⣿",);
+				Some(proposals.encode())
+			},
 			TrustedGetter::guess_the_number(getter) => getter.execute(),
+			TrustedGetter::contracts(getter) => getter.execute(),
 			#[cfg(feature = "evm")]
 			TrustedGetter::evm_nonce(who) => {
 				let evm_account = get_evm_account(&who);
@@ -257,6 +301,8 @@ impl ExecuteGetter for TrustedGetterSigned {
 		match self.getter {
 			TrustedGetter::guess_the_number(getter) =>
 				key_hashes.append(&mut getter.get_storage_hashes_to_update()),
+			TrustedGetter::contracts(getter) =>
+				key_hashes.append(&mut getter.get_storage_hashes_to_update()),
 			_ => debug!("No storage updates needed..."),
 		};
 		key_hashes
@@ -269,36 +315,15 @@ impl ExecuteGetter for PublicGetter {
 			PublicGetter::some_value => Some(42u32.encode()),
 			PublicGetter::total_issuance => Some(Balances::total_issuance().encode()),
 			PublicGetter::parentchains_info => {
-				let integritee = ParentchainInfo {
-					id: ParentchainId::Integritee,
-					genesis_hash: ParentchainIntegritee::parentchain_genesis_hash(),
-					block_number: ParentchainIntegritee::block_number(),
-					now: ParentchainIntegritee::now(),
-					creation_block_number: ParentchainIntegritee::creation_block_number(),
-					creation_timestamp: ParentchainIntegritee::creation_timestamp(),
-				};
-				let target_a = ParentchainInfo {
-					id: ParentchainId::TargetA,
-					genesis_hash: ParentchainTargetA::parentchain_genesis_hash(),
-					block_number: ParentchainTargetA::block_number(),
-					now: ParentchainTargetA::now(),
-					creation_block_number: ParentchainTargetA::creation_block_number(),
-					creation_timestamp: ParentchainTargetA::creation_timestamp(),
-				};
-				let target_b = ParentchainInfo {
-					id: ParentchainId::TargetB,
-					genesis_hash: ParentchainTargetB::parentchain_genesis_hash(),
-					block_number: ParentchainTargetB::block_number(),
-					now: ParentchainTargetB::now(),
-					creation_block_number: ParentchainTargetB::creation_block_number(),
-					creation_timestamp: ParentchainTargetB::creation_timestamp(),
-				};
-				let parentchains_info = ParentchainsInfo {
-					integritee,
-					target_a,
-					target_b,
-					shielding_target: shielding_target(),
-				};
+				let parentchains: Vec<ParentchainInfo> = Parentchain::parentchain_ids()
+					.into_iter()
+					.chain(core::iter::once(ParentchainId::Integritee))
+					.collect::<sp_std::collections::btree_set::BTreeSet<_>>()
+					.into_iter()
+					.map(ParentchainInfo::from_state)
+					.collect();
+				let parentchains_info =
+					ParentchainsInfo { parentchains, shielding_target: shielding_target() };
 				Some(parentchains_info.encode())
 			},
 			PublicGetter::note_buckets_info => {
@@ -324,12 +349,8 @@ impl ExecuteGetter for PublicGetter {
 /// General public information about the sync status of all parentchains
 #[derive(Encode, Decode, Debug, Clone, PartialEq, Eq)]
 pub struct ParentchainsInfo {
-	/// info for the integritee network parentchain
-	pub integritee: ParentchainInfo,
-	/// info for the target A parentchain
-	pub target_a: ParentchainInfo,
-	/// info for the target B parentchain
-	pub target_b: ParentchainInfo,
+	/// info for the integritee network parentchain followed by all target parentchains
+	pub parentchains: Vec<ParentchainInfo>,
 	/// which of the parentchains is used as a shielding target?
 	pub shielding_target: ParentchainId,
 }
@@ -350,6 +371,19 @@ pub struct ParentchainInfo {
 	/// the timestamp of creation for this shard
 	creation_timestamp: Option<Moment>,
 }
+
+impl ParentchainInfo {
+	fn from_state(id: ParentchainId) -> Self {
+		ParentchainInfo {
+			id,
+			genesis_hash: Parentchain::parentchain_genesis_hash(id),
+			block_number: Parentchain::block_number(id),
+			now: Parentchain::now(id),
+			creation_block_number: Parentchain::creation_block_number(id),
+			creation_timestamp: Parentchain::creation_timestamp(id),
+		}
+	}
+}
 mod tests {
 	use super::*;
 
@@ -14,17 +14,19 @@
 	limitations under the License.
 
 */
-use crate::{TrustedCall, ENCLAVE_ACCOUNT_KEY};
+use crate::{TrustedCall, ENCLAVE_ACCOUNT_KEY, PENDING_ENCLAVE_ACCOUNT_ROTATION_KEY};
 use codec::{Decode, Encode};
 use frame_support::dispatch::UnfilteredDispatchable;
-use ita_sgx_runtime::{ParentchainIntegritee, ParentchainTargetA, ParentchainTargetB, Runtime};
+use ita_sgx_runtime::{Parentchain, Runtime};
 use itp_stf_interface::{BlockMetadata, ShardCreationInfo};
 use itp_stf_primitives::{
 	error::{StfError, StfResult},
 	types::AccountId,
 };
 use itp_storage::{storage_double_map_key, storage_map_key, storage_value_key, StorageHasher};
-use itp_types::parentchain::{BlockNumber, GenericMortality, Hash, ParentchainId};
+use itp_types::parentchain::{
+	BlockNumber, GenericMortality, Hash, ParentchainId, TargetParentchainIndex,
+};
 use itp_utils::stringify::account_id_to_string;
 use log::*;
 use sp_runtime::generic::Era;
@@ -91,6 +93,29 @@ pub fn enclave_signer_account<AccountId: Decode>() -> AccountId {
 	get_storage_value("Sudo", ENCLAVE_ACCOUNT_KEY).expect("No enclave account")
 }
 
+pub fn set_enclave_signer_account<AccountId: Encode>(account: &AccountId) {
+	sp_io::storage::set(&storage_value_key("Sudo", ENCLAVE_ACCOUNT_KEY), &account.encode());
+}
+
+/// The enclave account that replaces the current one, and the sidechain block number from
+/// which on it does.
+pub fn pending_enclave_account_rotation<AccountId: Decode>() -> Option<(AccountId, u64)> {
+	get_storage_value("Sudo", PENDING_ENCLAVE_ACCOUNT_ROTATION_KEY)
+}
+
+pub fn set_pending_enclave_account_rotation<AccountId: Encode>(rotation: Option<(AccountId, u64)>) {
+	let key = storage_value_key("Sudo", PENDING_ENCLAVE_ACCOUNT_ROTATION_KEY);
+	match rotation {
+		Some(rotation) => sp_io::storage::set(&key, &rotation.encode()),
+		None => sp_io::storage::clear(&key),
+	}
+}
+
+/// Number of the sidechain block that is being executed, as set by the block proposer.
+pub fn sidechain_block_number() -> u64 {
+	get_storage_value("System", "Number").unwrap_or_default()
+}
+
 /// Ensures an account is a registered enclave account.
 pub fn ensure_enclave_signer_account<AccountId: Encode + Decode + PartialEq>(
 	account: &AccountId,
@@ -131,10 +156,9 @@ pub fn set_block_number(block_number: u32) {
 	sp_io::storage::set(&storage_value_key("System", "Number"), &block_number.encode());
 }
 
-/// get shard vault from any of the parentchain interfaces
-/// We assume it has been ensured elsewhere that there can't be multiple shard vaults on multiple parentchains
+/// get shard vault and the parentchain it lives on from the parentchain pallet
 pub fn shard_vault() -> Option<(AccountId, ParentchainId)> {
-	get_shard_vaults().into_iter().next()
+	Parentchain::shard_vault()
 }
 
 /// get shielding target from parentchain pallets
@@ -144,56 +168,29 @@ pub fn shielding_target() -> ParentchainId {
 
 /// get genesis hash of shielding target parentchain, if available
 pub fn shielding_target_genesis_hash() -> Option<Hash> {
-	match shielding_target() {
-		ParentchainId::Integritee => ParentchainIntegritee::parentchain_genesis_hash(),
-		ParentchainId::TargetA => ParentchainTargetA::parentchain_genesis_hash(),
-		ParentchainId::TargetB => ParentchainTargetB::parentchain_genesis_hash(),
-	}
+	Parentchain::parentchain_genesis_hash(shielding_target())
 }
 
-/// We assume it has been ensured elsewhere that there can't be multiple shard vaults on multiple parentchains
-pub fn get_shard_vaults() -> Vec<(AccountId, ParentchainId)> {
-	[
-		(ParentchainIntegritee::shard_vault(), ParentchainId::Integritee),
-		(ParentchainTargetA::shard_vault(), ParentchainId::TargetA),
-		(ParentchainTargetB::shard_vault(), ParentchainId::TargetB),
-	]
-	.into_iter()
-	.filter_map(|vp| vp.0.map(|v| (v, vp.1)))
-	.collect()
+pub fn creation_block_metadata(parentchain_id: ParentchainId) -> Option<BlockMetadata> {
+	Parentchain::creation_block_number(parentchain_id).and_then(|number| {
+		Parentchain::creation_block_hash(parentchain_id).map(|hash| BlockMetadata {
+			number,
+			hash,
+			timestamp: Parentchain::creation_timestamp(parentchain_id),
+		})
+	})
 }
 
 pub fn shard_creation_info() -> ShardCreationInfo {
-	let maybe_integritee_info: Option<BlockMetadata> =
-		ParentchainIntegritee::creation_block_number().and_then(|number| {
-			ParentchainIntegritee::creation_block_hash().map(|hash| BlockMetadata {
-				number,
-				hash,
-				timestamp: ParentchainIntegritee::creation_timestamp(),
-			})
-		});
-	let maybe_target_a_info: Option<BlockMetadata> = ParentchainTargetA::creation_block_number()
-		.and_then(|number| {
-			ParentchainTargetA::creation_block_hash().map(|hash| BlockMetadata {
-				number,
-				hash,
-				timestamp: ParentchainTargetA::creation_timestamp(),
-			})
-		});
-	let maybe_target_b_info: Option<BlockMetadata> = ParentchainTargetB::creation_block_number()
-		.and_then(|number| {
-			ParentchainTargetB::creation_block_hash().map(|hash| BlockMetadata {
-				number,
-				hash,
-				timestamp: ParentchainTargetB::creation_timestamp(),
-			})
-		});
+	let mut targets: Vec<(TargetParentchainIndex, BlockMetadata)> = Parentchain::parentchain_ids()
+		.filter_map(|id| {
+			let index = id.target_index()?;
+			creation_block_metadata(id).map(|metadata| (index, metadata))
+		})
+		.collect();
+	targets.sort_by_key(|(index, _)| *index);
 
-	ShardCreationInfo {
-		integritee: maybe_integritee_info,
-		target_a: maybe_target_a_info,
-		target_b: maybe_target_b_info,
-	}
+	ShardCreationInfo { integritee: creation_block_metadata(ParentchainId::Integritee), targets }
 }
 
 const PREFIX: &[u8] = b"<Bytes>";
@@ -219,14 +216,8 @@ pub fn get_mortality(
 	parentchain_id: ParentchainId,
 	blocks_to_live: BlockNumber,
 ) -> Option<GenericMortality> {
-	let (maybe_number, maybe_hash) = match parentchain_id {
-		ParentchainId::Integritee =>
-			(ParentchainIntegritee::block_number(), ParentchainIntegritee::block_hash()),
-		ParentchainId::TargetA =>
-			(ParentchainTargetA::block_number(), ParentchainTargetA::block_hash()),
-		ParentchainId::TargetB =>
-			(ParentchainTargetB::block_number(), ParentchainTargetB::block_hash()),
-	};
+	let (maybe_number, maybe_hash) =
+		(Parentchain::block_number(parentchain_id), Parentchain::block_hash(parentchain_id));
 	if let Some(number) = maybe_number {
 		if let Some(hash) = maybe_hash {
 			return Some(GenericMortality {
@@ -28,10 +28,13 @@ extern crate alloc;
 #[cfg(all(not(feature = "std"), feature = "sgx"))]
 extern crate sgx_tstd as std;
 
+pub use account_events::*;
 pub use getter::*;
 pub use stf_sgx_primitives::{types::*, Stf};
 pub use trusted_call::*;
 
+pub mod account_events;
+pub mod contracts;
 #[cfg(feature = "evm")]
 pub mod evm_helpers;
 pub mod getter;
@@ -46,7 +49,10 @@ pub mod stf_sgx_tests;
 pub mod test_genesis;
 pub mod trusted_call;
 
-pub(crate) const ENCLAVE_ACCOUNT_KEY: &str = "Enclave_Account_Key";
+pub const ENCLAVE_ACCOUNT_KEY: &str = "Enclave_Account_Key";
+pub(crate) const PENDING_ENCLAVE_ACCOUNT_ROTATION_KEY: &str = "Pending_Enclave_Account_Rotation";
 pub const STF_SHIELDING_FEE_AMOUNT_DIVIDER: Balance = 571; // approx 0.175%
 pub const STF_TX_FEE_UNIT_DIVIDER: Balance = 100; // 0.01 tokens
 pub const STF_GUESS_FEE_UNIT_DIVIDER: Balance = 10; // 0.1 tokens
+pub const MAX_NOTES_PER_PAGE: u32 = 100;
+pub const MAX_SCHEDULED_CALLS_PER_TIMESTAMP: u32 = 100;
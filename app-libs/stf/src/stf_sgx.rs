@@ -18,23 +18,24 @@
 #[cfg(feature = "test")]
 use crate::test_genesis::test_genesis_setup;
 use crate::{
-	helpers::{enclave_signer_account, get_shard_vaults, shard_creation_info, shard_vault},
-	Stf, ENCLAVE_ACCOUNT_KEY,
+	helpers::{
+		enclave_signer_account, set_enclave_signer_account, shard_creation_info, shard_vault,
+	},
+	trusted_call::rotate_enclave_account_if_due,
+	Stf,
 };
 use codec::{Decode, Encode};
 use frame_support::traits::{OnTimestampSet, OriginTrait, UnfilteredDispatchable};
-use ita_sgx_runtime::{
-	ParentchainInstanceIntegritee, ParentchainInstanceTargetA, ParentchainInstanceTargetB,
-};
+use ita_sgx_runtime::LEGACY_PARENTCHAIN_PALLET_INSTANCES;
 use itp_node_api::metadata::{provider::AccessNodeMetadata, NodeMetadataTrait};
 use itp_sgx_externalities::SgxExternalitiesTrait;
 use itp_sgx_runtime_primitives::types::Moment;
 use itp_stf_interface::{
-	parentchain_pallet::ParentchainPalletInstancesInterface,
+	parentchain_pallet::ParentchainPalletInterface,
 	sudo_pallet::SudoPalletInterface,
 	system_pallet::{SystemPalletAccountInterface, SystemPalletEventInterface},
 	ExecuteCall, ExecuteGetter, InitState, ShardCreationInfo, ShardCreationQuery, ShardVaultQuery,
-	StateCallInterface, StateGetterInterface, UpdateState,
+	StateCallInterface, StateGetterInterface, UpdateEnclaveAccount, UpdateState,
 };
 use itp_stf_primitives::{error::StfError, traits::TrustedCallVerification};
 use itp_storage::storage_value_key;
@@ -81,19 +82,31 @@ where
 		#[cfg(feature = "test")]
 		test_genesis_setup(&mut state);
 
+		Self::update_enclave_account(&mut state, enclave_account);
+
+		trace!("Returning updated state: {:?}", state);
+		state
+	}
+}
+
+impl<TCS, G, State, Runtime, AccountId> UpdateEnclaveAccount<State, AccountId>
+	for Stf<TCS, G, State, Runtime>
+where
+	State: SgxExternalitiesTrait,
+	Runtime: frame_system::Config<AccountId = AccountId> + pallet_balances::Config,
+	<<Runtime as frame_system::Config>::Lookup as StaticLookup>::Source:
+		std::convert::From<AccountId>,
+	AccountId: Encode,
+{
+	fn update_enclave_account(state: &mut State, enclave_account: AccountId) {
+		debug!("setting enclave account {}", account_id_to_string(&enclave_account));
 		state.execute_with(|| {
-			sp_io::storage::set(
-				&storage_value_key("Sudo", ENCLAVE_ACCOUNT_KEY),
-				&enclave_account.encode(),
-			);
+			set_enclave_signer_account(&enclave_account);
 
 			if let Err(e) = create_enclave_self_account::<Runtime, AccountId>(enclave_account) {
 				error!("Failed to initialize the enclave signer account: {:?}", e);
 			}
 		});
-
-		trace!("Returning updated state: {:?}", state);
-		state
 	}
 }
 
@@ -120,13 +133,9 @@ where
 		});
 	}
 
-	fn storage_hashes_to_update_on_block(parentchain_id: &ParentchainId) -> Vec<Vec<u8>> {
-		// Get all shards that are currently registered.
-		match parentchain_id {
-			ParentchainId::Integritee => vec![], // shards_key_hash() moved to stf_executor and is currently unused
-			ParentchainId::TargetA => vec![],
-			ParentchainId::TargetB => vec![],
-		}
+	fn storage_hashes_to_update_on_block(_parentchain_id: &ParentchainId) -> Vec<Vec<u8>> {
+		// shards_key_hash() moved to stf_executor and is currently unused
+		vec![]
 	}
 }
 
@@ -166,6 +175,9 @@ where
 			sp_io::storage::set(&storage_value_key("Timestamp", "Now"), &now.encode());
 			sp_io::storage::set(&storage_value_key("Timestamp", "DidUpdate"), &true.encode());
 			<Runtime::OnTimestampSet as OnTimestampSet<_>>::on_timestamp_set(now.into());
+			if let Err(e) = rotate_enclave_account_if_due() {
+				error!("Failed to rotate the enclave account: {:?}", e);
+			}
 		});
 		Ok(())
 	}
@@ -276,67 +288,35 @@ where
 	}
 }
 
-impl<TCS, G, State, Runtime, ParentchainHeader>
-	ParentchainPalletInstancesInterface<State, ParentchainHeader> for Stf<TCS, G, State, Runtime>
+impl<TCS, G, State, Runtime, ParentchainHeader> ParentchainPalletInterface<State, ParentchainHeader>
+	for Stf<TCS, G, State, Runtime>
 where
 	State: SgxExternalitiesTrait,
 	Runtime: frame_system::Config<Header = ParentchainHeader, AccountId = AccountId, Hash = Hash>
-		+ pallet_parentchain::Config<ParentchainInstanceIntegritee>
-		+ pallet_parentchain::Config<ParentchainInstanceTargetA>
-		+ pallet_parentchain::Config<ParentchainInstanceTargetB>,
+		+ pallet_parentchain::Config<ParentchainId = ParentchainId>,
 	<<Runtime as frame_system::Config>::Lookup as StaticLookup>::Source: From<AccountId>,
 	ParentchainHeader: Debug,
 {
 	type Error = StfError;
 
-	fn update_parentchain_integritee_block(
-		state: &mut State,
-		header: ParentchainHeader,
-	) -> Result<(), Self::Error> {
-		trace!("updating integritee parentchain block : {:?}", header);
-		state.execute_with(|| {
-			pallet_parentchain::Call::<Runtime, ParentchainInstanceIntegritee>::set_block { header }
-				.dispatch_bypass_filter(Runtime::RuntimeOrigin::root())
-				.map_err(|e| {
-					Self::Error::Dispatch(format!(
-						"Update parentchain integritee block error: {:?}",
-						e.error
-					))
-				})
-		})?;
-		Ok(())
-	}
-
-	fn update_parentchain_target_a_block(
-		state: &mut State,
-		header: ParentchainHeader,
-	) -> Result<(), Self::Error> {
-		trace!("updating target_a parentchain block: {:?}", header);
-		state.execute_with(|| {
-			pallet_parentchain::Call::<Runtime, ParentchainInstanceTargetA>::set_block { header }
-				.dispatch_bypass_filter(Runtime::RuntimeOrigin::root())
-				.map_err(|e| {
-					Self::Error::Dispatch(format!(
-						"Update parentchain target_a block error: {:?}",
-						e.error
-					))
-				})
-		})?;
-		Ok(())
-	}
-
-	fn update_parentchain_target_b_block(
+	fn update_parentchain_block(
 		state: &mut State,
 		header: ParentchainHeader,
+		parentchain_id: ParentchainId,
 	) -> Result<(), Self::Error> {
-		trace!("updating target_b parentchain block: {:?}", header);
+		trace!("updating {} parentchain block: {:?}", parentchain_id, header);
 		state.execute_with(|| {
-			pallet_parentchain::Call::<Runtime, ParentchainInstanceTargetB>::set_block { header }
+			// The parentchain block is the first thing to be updated on every block import, so
+			// this is where states persisted before the storage migration are migrated.
+			pallet_parentchain::migrations::v2::migrate::<Runtime>(
+				&LEGACY_PARENTCHAIN_PALLET_INSTANCES,
+			);
+			pallet_parentchain::Call::<Runtime>::set_block { parentchain_id, header }
 				.dispatch_bypass_filter(Runtime::RuntimeOrigin::root())
 				.map_err(|e| {
 					Self::Error::Dispatch(format!(
-						"Update parentchain target_b block error: {:?}",
-						e.error
+						"Update parentchain {} block error: {:?}",
+						parentchain_id, e.error
 					))
 				})
 		})?;
@@ -360,33 +340,12 @@ where
 			warn!("attempting to init shard vault which has already been initialized");
 			return Ok(())
 		}
-		state.execute_with(|| match parentchain_id {
-			ParentchainId::Integritee => pallet_parentchain::Call::<
-				Runtime,
-				ParentchainInstanceIntegritee,
-			>::init_shard_vault {
-				account: vault,
-			}
-			.dispatch_bypass_filter(Runtime::RuntimeOrigin::root())
-			.map_err(|e| {
-				Self::Error::Dispatch(format!("Init shard vault account error: {:?}", e.error))
-			}),
-			ParentchainId::TargetA =>
-				pallet_parentchain::Call::<Runtime, ParentchainInstanceTargetA>::init_shard_vault {
-					account: vault,
-				}
-				.dispatch_bypass_filter(Runtime::RuntimeOrigin::root())
-				.map_err(|e| {
-					Self::Error::Dispatch(format!("Init shard vault account error: {:?}", e.error))
-				}),
-			ParentchainId::TargetB =>
-				pallet_parentchain::Call::<Runtime, ParentchainInstanceTargetB>::init_shard_vault {
-					account: vault,
-				}
+		state.execute_with(|| {
+			pallet_parentchain::Call::<Runtime>::init_shard_vault { parentchain_id, account: vault }
 				.dispatch_bypass_filter(Runtime::RuntimeOrigin::root())
 				.map_err(|e| {
 					Self::Error::Dispatch(format!("Init shard vault account error: {:?}", e.error))
-				}),
+				})
 		})?;
 		Ok(())
 	}
@@ -396,37 +355,12 @@ where
 		header: ParentchainHeader,
 		parentchain_id: ParentchainId,
 	) -> Result<(), Self::Error> {
-		state.execute_with(|| match parentchain_id {
-			ParentchainId::Integritee => pallet_parentchain::Call::<
-				Runtime,
-				ParentchainInstanceIntegritee,
-			>::set_creation_block {
-				header,
-			}
-			.dispatch_bypass_filter(Runtime::RuntimeOrigin::root())
-			.map_err(|e| {
-				Self::Error::Dispatch(format!("Init shard vault account error: {:?}", e.error))
-			}),
-			ParentchainId::TargetA => pallet_parentchain::Call::<
-				Runtime,
-				ParentchainInstanceTargetA,
-			>::set_creation_block {
-				header,
-			}
-			.dispatch_bypass_filter(Runtime::RuntimeOrigin::root())
-			.map_err(|e| {
-				Self::Error::Dispatch(format!("Init shard vault account error: {:?}", e.error))
-			}),
-			ParentchainId::TargetB => pallet_parentchain::Call::<
-				Runtime,
-				ParentchainInstanceTargetB,
-			>::set_creation_block {
-				header,
-			}
-			.dispatch_bypass_filter(Runtime::RuntimeOrigin::root())
-			.map_err(|e| {
-				Self::Error::Dispatch(format!("Init shard vault account error: {:?}", e.error))
-			}),
+		state.execute_with(|| {
+			pallet_parentchain::Call::<Runtime>::set_creation_block { parentchain_id, header }
+				.dispatch_bypass_filter(Runtime::RuntimeOrigin::root())
+				.map_err(|e| {
+					Self::Error::Dispatch(format!("Set creation block error: {:?}", e.error))
+				})
 		})?;
 		Ok(())
 	}
@@ -436,31 +370,13 @@ where
 		genesis_hash: Hash,
 		parentchain_id: ParentchainId,
 	) -> Result<(), Self::Error> {
-		state.execute_with(|| match parentchain_id {
-			ParentchainId::Integritee => pallet_parentchain::Call::<
-				Runtime,
-				ParentchainInstanceIntegritee,
-			>::init_parentchain_genesis_hash {
-				genesis: genesis_hash,
-			}
-			.dispatch_bypass_filter(Runtime::RuntimeOrigin::root())
-			.map_err(|e| Self::Error::Dispatch(format!("Init genesis hash error: {:?}", e.error))),
-			ParentchainId::TargetA => pallet_parentchain::Call::<
-				Runtime,
-				ParentchainInstanceTargetA,
-			>::init_parentchain_genesis_hash {
-				genesis: genesis_hash,
-			}
-			.dispatch_bypass_filter(Runtime::RuntimeOrigin::root())
-			.map_err(|e| Self::Error::Dispatch(format!("Init genesis hash error: {:?}", e.error))),
-			ParentchainId::TargetB => pallet_parentchain::Call::<
-				Runtime,
-				ParentchainInstanceTargetB,
-			>::init_parentchain_genesis_hash {
+		state.execute_with(|| {
+			pallet_parentchain::Call::<Runtime>::init_parentchain_genesis_hash {
+				parentchain_id,
 				genesis: genesis_hash,
 			}
 			.dispatch_bypass_filter(Runtime::RuntimeOrigin::root())
-			.map_err(|e| Self::Error::Dispatch(format!("Init genesis hash error: {:?}", e.error))),
+			.map_err(|e| Self::Error::Dispatch(format!("Init genesis hash error: {:?}", e.error)))
 		})?;
 		Ok(())
 	}
@@ -468,17 +384,9 @@ where
 	fn get_shard_vault_ensure_single_parentchain(
 		state: &mut State,
 	) -> Result<Option<(AccountId, ParentchainId)>, Self::Error> {
-		state.execute_with(|| {
-			let vaults = get_shard_vaults();
-			match vaults.len() {
-				0 => Ok(None),
-				1 => Ok(Some(vaults[0].clone())),
-				_ => Err(Self::Error::Dispatch(format!(
-					"shard vault assigned to more than one parentchain: {:?}",
-					vaults
-				))),
-			}
-		})
+		// The pallet stores a single shard vault together with its parentchain, so there can't
+		// be vaults on multiple parentchains.
+		Ok(state.execute_with(shard_vault))
 	}
 }
 
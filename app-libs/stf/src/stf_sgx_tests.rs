@@ -15,20 +15,29 @@
 
 */
 
-use crate::{Getter, State, Stf, TrustedCall, TrustedCallSigned};
-use ita_sgx_runtime::Runtime;
+use crate::{
+	contracts::contract_address, multi_account_id, Getter, State, Stf, TrustedCall,
+	TrustedCallSigned,
+};
+use codec::Encode;
+use frame_support::traits::fungibles;
+use ita_sgx_runtime::{Assets, Contracts, Runtime, RuntimeEvent, RuntimeOrigin, System};
 use itp_node_api::metadata::{metadata_mocks::NodeMetadataMock, provider::NodeMetadataRepository};
+use itp_sgx_externalities::SgxExternalitiesTrait;
 use itp_stf_interface::{
-	parentchain_pallet::ParentchainPalletInstancesInterface, sudo_pallet::SudoPalletInterface,
+	parentchain_pallet::ParentchainPalletInterface, sudo_pallet::SudoPalletInterface,
 	system_pallet::SystemPalletAccountInterface, InitState, StateCallInterface,
+	UpdateEnclaveAccount,
 };
 use itp_stf_primitives::types::{AccountId, Signature};
+use itp_storage::storage_value_key;
 use itp_types::parentchain::ParentchainId;
 use sp_core::{
 	ed25519::{Pair as Ed25519Pair, Signature as Ed25519Signature},
 	Pair,
 };
-use std::{sync::Arc, vec::Vec};
+use sp_runtime::traits::{BlakeTwo256, Hash};
+use std::{boxed::Box, sync::Arc, vec, vec::Vec};
 
 pub type StfState = Stf<TrustedCallSigned, Getter, State, Runtime>;
 
@@ -43,6 +52,78 @@ pub fn enclave_account_initialization_works() {
 	assert_eq!(1000, account_data.free);
 }
 
+pub fn enclave_account_update_works() {
+	let mut state = StfState::init_state(AccountId::new([2u8; 32]));
+	let rotated_enclave_account = AccountId::new([3u8; 32]);
+
+	StfState::update_enclave_account(&mut state, rotated_enclave_account.clone());
+
+	assert_eq!(rotated_enclave_account, StfState::get_enclave_account(&mut state));
+	assert_eq!(1000, StfState::get_account_data(&mut state, &rotated_enclave_account).free);
+}
+
+pub fn rotate_enclave_account_migrates_funds_and_assets() {
+	let enclave_call_signer = Ed25519Pair::from_seed(b"14672678901234567890123456789012");
+	let enclave_signer_account_id: AccountId = enclave_call_signer.public().into();
+	let mut state = StfState::init_state(enclave_signer_account_id.clone());
+	let vault = AccountId::new([2u8; 32]);
+	StfState::init_shard_vault_account(&mut state, vault, ParentchainId::Target(0)).unwrap();
+	let repo = Arc::new(NodeMetadataRepository::new(NodeMetadataMock::new()));
+	let signature = Signature::Ed25519(Ed25519Signature([0u8; 64]));
+
+	let shield_assets_call = TrustedCallSigned::new(
+		TrustedCall::assets_shield(
+			enclave_signer_account_id.clone(),
+			AccountId::new([1u8; 32]),
+			1984,
+			500u128,
+			ParentchainId::Target(0),
+		),
+		0,
+		signature.clone(),
+	);
+	StfState::execute_call(&mut state, shield_assets_call, &mut Vec::new(), repo.clone()).unwrap();
+	let funds = StfState::get_account_data(&mut state, &enclave_signer_account_id).free;
+
+	let rotated_enclave_account = AccountId::new([3u8; 32]);
+	let rotate_call = |activation_block, nonce| {
+		TrustedCallSigned::new(
+			TrustedCall::rotate_enclave_account(
+				enclave_signer_account_id.clone(),
+				rotated_enclave_account.clone(),
+				activation_block,
+			),
+			nonce,
+			signature.clone(),
+		)
+	};
+	set_sidechain_block_number(&mut state, 5);
+	assert!(StfState::execute_call(&mut state, rotate_call(5, 1), &mut Vec::new(), repo.clone())
+		.is_err());
+	StfState::execute_call(&mut state, rotate_call(7, 2), &mut Vec::new(), repo).unwrap();
+	assert_eq!(enclave_signer_account_id, StfState::get_enclave_account(&mut state));
+
+	set_sidechain_block_number(&mut state, 6);
+	StfState::on_initialize(&mut state, 1).unwrap();
+	assert_eq!(enclave_signer_account_id, StfState::get_enclave_account(&mut state));
+
+	set_sidechain_block_number(&mut state, 7);
+	StfState::on_initialize(&mut state, 2).unwrap();
+	assert_eq!(rotated_enclave_account, StfState::get_enclave_account(&mut state));
+	assert_eq!(0, StfState::get_account_data(&mut state, &enclave_signer_account_id).free);
+	assert_eq!(funds, StfState::get_account_data(&mut state, &rotated_enclave_account).free);
+	assert_eq!(
+		Some(rotated_enclave_account),
+		state.execute_with(|| <Assets as fungibles::roles::Inspect<AccountId>>::owner(1984))
+	);
+}
+
+fn set_sidechain_block_number(state: &mut State, block_number: u64) {
+	state.execute_with(|| {
+		sp_io::storage::set(&storage_value_key("System", "Number"), &block_number.encode())
+	});
+}
+
 pub fn shield_funds_increments_signer_account_nonce() {
 	let enclave_call_signer = Ed25519Pair::from_seed(b"14672678901234567890123456789012");
 	let enclave_signer_account_id: AccountId = enclave_call_signer.public().into();
@@ -66,6 +147,271 @@ pub fn shield_funds_increments_signer_account_nonce() {
 	assert_eq!(1, StfState::get_account_nonce(&mut state, &enclave_signer_account_id));
 }
 
+pub fn shield_assets_creates_asset_and_mints_to_beneficiary() {
+	let enclave_call_signer = Ed25519Pair::from_seed(b"14672678901234567890123456789012");
+	let enclave_signer_account_id: AccountId = enclave_call_signer.public().into();
+	let mut state = StfState::init_state(enclave_signer_account_id);
+	let vault = AccountId::new([2u8; 32]);
+	StfState::init_shard_vault_account(&mut state, vault, ParentchainId::Target(0)).unwrap();
+	let beneficiary = AccountId::new([1u8; 32]);
+	let repo = Arc::new(NodeMetadataRepository::new(NodeMetadataMock::new()));
+
+	for nonce in 0..2 {
+		let shield_assets_call = TrustedCallSigned::new(
+			TrustedCall::assets_shield(
+				enclave_call_signer.public().into(),
+				beneficiary.clone(),
+				1984,
+				500u128,
+				ParentchainId::Target(0),
+			),
+			nonce,
+			Signature::Ed25519(Ed25519Signature([0u8; 64])),
+		);
+		StfState::execute_call(&mut state, shield_assets_call, &mut Vec::new(), repo.clone())
+			.unwrap();
+	}
+
+	assert_eq!(state.execute_with(|| Assets::balance(1984, &beneficiary)), 1000);
+}
+
+pub fn shield_assets_fails_for_other_parentchain_than_shard_vault() {
+	let enclave_call_signer = Ed25519Pair::from_seed(b"14672678901234567890123456789012");
+	let enclave_signer_account_id: AccountId = enclave_call_signer.public().into();
+	let mut state = StfState::init_state(enclave_signer_account_id);
+	let vault = AccountId::new([2u8; 32]);
+	StfState::init_shard_vault_account(&mut state, vault, ParentchainId::Target(0)).unwrap();
+	let beneficiary = AccountId::new([1u8; 32]);
+
+	let shield_assets_call = TrustedCallSigned::new(
+		TrustedCall::assets_shield(
+			enclave_call_signer.public().into(),
+			beneficiary.clone(),
+			1984,
+			500u128,
+			ParentchainId::Target(1),
+		),
+		0,
+		Signature::Ed25519(Ed25519Signature([0u8; 64])),
+	);
+
+	let repo = Arc::new(NodeMetadataRepository::new(NodeMetadataMock::new()));
+	assert!(StfState::execute_call(&mut state, shield_assets_call, &mut Vec::new(), repo).is_err());
+	assert_eq!(state.execute_with(|| Assets::balance(1984, &beneficiary)), 0);
+}
+
+pub fn scheduled_call_is_executed_when_due() {
+	let enclave_call_signer = Ed25519Pair::from_seed(b"14672678901234567890123456789012");
+	let enclave_signer_account_id: AccountId = enclave_call_signer.public().into();
+	let mut state = StfState::init_state(enclave_signer_account_id);
+	let vault = AccountId::new([2u8; 32]);
+	StfState::init_shard_vault_account(&mut state, vault, ParentchainId::Integritee).unwrap();
+	let owner = AccountId::new([1u8; 32]);
+	let beneficiary = AccountId::new([3u8; 32]);
+	let repo = Arc::new(NodeMetadataRepository::new(NodeMetadataMock::new()));
+	let signature = Signature::Ed25519(Ed25519Signature([0u8; 64]));
+
+	let calls = [
+		TrustedCallSigned::new(
+			TrustedCall::balance_shield(
+				enclave_call_signer.public().into(),
+				owner.clone(),
+				10u128.pow(14),
+				ParentchainId::Integritee,
+			),
+			0,
+			signature.clone(),
+		),
+		TrustedCallSigned::new(
+			TrustedCall::schedule_call(
+				owner.clone(),
+				1000,
+				None,
+				Box::new(TrustedCall::balance_transfer(owner.clone(), beneficiary.clone(), 1000)),
+			),
+			0,
+			signature.clone(),
+		),
+		TrustedCallSigned::new(
+			TrustedCall::timestamp_set(
+				enclave_call_signer.public().into(),
+				999,
+				ParentchainId::Integritee,
+			),
+			1,
+			signature.clone(),
+		),
+	];
+	for call in calls {
+		StfState::execute_call(&mut state, call, &mut Vec::new(), repo.clone()).unwrap();
+	}
+	assert_eq!(StfState::get_account_data(&mut state, &beneficiary).free, 0);
+
+	let timestamp_set_call = TrustedCallSigned::new(
+		TrustedCall::timestamp_set(
+			enclave_call_signer.public().into(),
+			1000,
+			ParentchainId::Integritee,
+		),
+		2,
+		signature,
+	);
+	StfState::execute_call(&mut state, timestamp_set_call, &mut Vec::new(), repo).unwrap();
+
+	assert_eq!(StfState::get_account_data(&mut state, &beneficiary).free, 1000);
+	// executing the scheduled call doesn't consume a nonce of the owner
+	assert_eq!(StfState::get_account_nonce(&mut state, &owner), 1);
+}
+
+pub fn multisig_call_is_executed_when_threshold_is_reached() {
+	let enclave_call_signer = Ed25519Pair::from_seed(b"14672678901234567890123456789012");
+	let enclave_signer_account_id: AccountId = enclave_call_signer.public().into();
+	let mut state = StfState::init_state(enclave_signer_account_id);
+	let vault = AccountId::new([2u8; 32]);
+	StfState::init_shard_vault_account(&mut state, vault, ParentchainId::Integritee).unwrap();
+	let alice = AccountId::new([1u8; 32]);
+	let bob = AccountId::new([3u8; 32]);
+	let charlie = AccountId::new([4u8; 32]);
+	let beneficiary = AccountId::new([5u8; 32]);
+	let multisig = multi_account_id(&[alice.clone(), bob.clone(), charlie.clone()], 2);
+	let repo = Arc::new(NodeMetadataRepository::new(NodeMetadataMock::new()));
+	let signature = Signature::Ed25519(Ed25519Signature([0u8; 64]));
+
+	for (nonce, who) in [alice.clone(), bob.clone(), multisig.clone()].into_iter().enumerate() {
+		let shield_funds_call = TrustedCallSigned::new(
+			TrustedCall::balance_shield(
+				enclave_call_signer.public().into(),
+				who,
+				10u128.pow(14),
+				ParentchainId::Integritee,
+			),
+			nonce as u32,
+			signature.clone(),
+		);
+		StfState::execute_call(&mut state, shield_funds_call, &mut Vec::new(), repo.clone())
+			.unwrap();
+	}
+
+	let transfer = TrustedCall::balance_transfer(multisig.clone(), beneficiary.clone(), 1000);
+	let alice_approval = TrustedCallSigned::new(
+		TrustedCall::multisig_approve(
+			alice.clone(),
+			vec![bob.clone(), charlie.clone()],
+			2,
+			Box::new(transfer.clone()),
+		),
+		0,
+		signature.clone(),
+	);
+	StfState::execute_call(&mut state, alice_approval, &mut Vec::new(), repo.clone()).unwrap();
+	assert_eq!(StfState::get_account_data(&mut state, &beneficiary).free, 0);
+
+	let bob_approval = TrustedCallSigned::new(
+		TrustedCall::multisig_approve(bob, vec![charlie, alice], 2, Box::new(transfer)),
+		0,
+		signature,
+	);
+	StfState::execute_call(&mut state, bob_approval, &mut Vec::new(), repo).unwrap();
+
+	assert_eq!(StfState::get_account_data(&mut state, &beneficiary).free, 1000);
+	assert_eq!(StfState::get_account_nonce(&mut state, &multisig), 0);
+}
+
+/// Writes "dep" under the key "key" on deploy. Every call writes "cal" under the key and emits an
+/// event. Both trap after that if the input is not empty.
+///
+/// (module
+/// 	(import "env" "input_len" (func $input_len (result i32)))
+/// 	(import "env" "storage_write" (func $storage_write (param i32 i32 i32 i32)))
+/// 	(import "env" "deposit_event" (func $deposit_event (param i32 i32)))
+/// 	(memory (export "memory") 1)
+/// 	(data (i32.const 0) "keydepcal")
+/// 	(func (export "deploy")
+/// 		(call $storage_write (i32.const 0) (i32.const 3) (i32.const 3) (i32.const 3))
+/// 		(call $trap_on_input))
+/// 	(func (export "call")
+/// 		(call $storage_write (i32.const 0) (i32.const 3) (i32.const 6) (i32.const 3))
+/// 		(call $deposit_event (i32.const 0) (i32.const 3))
+/// 		(call $trap_on_input))
+/// 	(func $trap_on_input (if (call $input_len) (then unreachable)))
+/// )
+const TRAPPING_CONTRACT: [u8; 193] = [
+	0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x14, 0x04, 0x60, 0x00, 0x01, 0x7f, 0x60,
+	0x04, 0x7f, 0x7f, 0x7f, 0x7f, 0x00, 0x60, 0x02, 0x7f, 0x7f, 0x00, 0x60, 0x00, 0x00, 0x02, 0x39,
+	0x03, 0x03, 0x65, 0x6e, 0x76, 0x09, 0x69, 0x6e, 0x70, 0x75, 0x74, 0x5f, 0x6c, 0x65, 0x6e, 0x00,
+	0x00, 0x03, 0x65, 0x6e, 0x76, 0x0d, 0x73, 0x74, 0x6f, 0x72, 0x61, 0x67, 0x65, 0x5f, 0x77, 0x72,
+	0x69, 0x74, 0x65, 0x00, 0x01, 0x03, 0x65, 0x6e, 0x76, 0x0d, 0x64, 0x65, 0x70, 0x6f, 0x73, 0x69,
+	0x74, 0x5f, 0x65, 0x76, 0x65, 0x6e, 0x74, 0x00, 0x02, 0x03, 0x04, 0x03, 0x03, 0x03, 0x03, 0x05,
+	0x03, 0x01, 0x00, 0x01, 0x07, 0x1a, 0x03, 0x06, 0x6d, 0x65, 0x6d, 0x6f, 0x72, 0x79, 0x02, 0x00,
+	0x06, 0x64, 0x65, 0x70, 0x6c, 0x6f, 0x79, 0x00, 0x03, 0x04, 0x63, 0x61, 0x6c, 0x6c, 0x00, 0x04,
+	0x0a, 0x2e, 0x03, 0x0e, 0x00, 0x41, 0x00, 0x41, 0x03, 0x41, 0x03, 0x41, 0x03, 0x10, 0x01, 0x10,
+	0x05, 0x0b, 0x14, 0x00, 0x41, 0x00, 0x41, 0x03, 0x41, 0x06, 0x41, 0x03, 0x10, 0x01, 0x41, 0x00,
+	0x41, 0x03, 0x10, 0x02, 0x10, 0x05, 0x0b, 0x08, 0x00, 0x10, 0x00, 0x04, 0x40, 0x00, 0x0b, 0x0b,
+	0x0b, 0x0f, 0x01, 0x00, 0x41, 0x00, 0x0b, 0x09, 0x6b, 0x65, 0x79, 0x64, 0x65, 0x70, 0x63, 0x61,
+	0x6c,
+];
+
+pub fn trapping_contract_does_not_change_state() {
+	let enclave_call_signer = Ed25519Pair::from_seed(b"14672678901234567890123456789012");
+	let enclave_signer_account_id: AccountId = enclave_call_signer.public().into();
+	let mut state = StfState::init_state(enclave_signer_account_id);
+	let vault = AccountId::new([2u8; 32]);
+	StfState::init_shard_vault_account(&mut state, vault, ParentchainId::Integritee).unwrap();
+	let deployer = AccountId::new([1u8; 32]);
+	let repo = Arc::new(NodeMetadataRepository::new(NodeMetadataMock::new()));
+
+	let shield_funds_call = TrustedCallSigned::new(
+		TrustedCall::balance_shield(
+			enclave_call_signer.public().into(),
+			deployer.clone(),
+			10u128.pow(14),
+			ParentchainId::Integritee,
+		),
+		0,
+		Signature::Ed25519(Ed25519Signature([0u8; 64])),
+	);
+	StfState::execute_call(&mut state, shield_funds_call, &mut Vec::new(), repo).unwrap();
+	// events are only deposited after genesis
+	set_sidechain_block_number(&mut state, 1);
+
+	state.execute_with(|| {
+		let origin = || RuntimeOrigin::signed(deployer.clone());
+		let gas_limit = 1_000_000;
+		let stored_value =
+			|contract: &AccountId| Contracts::contract_storage(contract, b"key".to_vec());
+		let emitted_events = || {
+			System::events()
+				.into_iter()
+				.filter(|record| {
+					matches!(
+						record.event,
+						RuntimeEvent::Contracts(pallet_contracts::Event::ContractEmitted { .. })
+					)
+				})
+				.count()
+		};
+
+		Contracts::upload_code(origin(), TRAPPING_CONTRACT.to_vec()).unwrap();
+		let code_hash = BlakeTwo256::hash(&TRAPPING_CONTRACT);
+		let contract = contract_address(&deployer, &code_hash, &[]);
+
+		assert!(Contracts::instantiate(origin(), code_hash, vec![], vec![1], gas_limit).is_err());
+		assert_eq!(Contracts::contract_info(&contract), None);
+		assert_eq!(stored_value(&contract), None);
+
+		Contracts::instantiate(origin(), code_hash, vec![], vec![], gas_limit).unwrap();
+		assert_eq!(stored_value(&contract), Some(b"dep".to_vec()));
+
+		assert!(Contracts::call(origin(), contract.clone(), vec![1], gas_limit).is_err());
+		assert_eq!(stored_value(&contract), Some(b"dep".to_vec()));
+		assert_eq!(emitted_events(), 0);
+
+		Contracts::call(origin(), contract.clone(), vec![], gas_limit).unwrap();
+		assert_eq!(stored_value(&contract), Some(b"cal".to_vec()));
+		assert_eq!(emitted_events(), 1);
+	});
+}
+
 pub fn test_root_account_exists_after_initialization() {
 	let enclave_account = AccountId::new([2u8; 32]);
 	let mut state = StfState::init_state(enclave_account);
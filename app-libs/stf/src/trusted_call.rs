@@ -24,27 +24,30 @@ use std::vec::Vec;
 #[cfg(feature = "evm")]
 use crate::evm_helpers::{create_code_hash, evm_create2_address, evm_create_address};
 use crate::{
+	contracts::ContractsTrustedCall,
 	guess_the_number::GuessTheNumberTrustedCall,
 	helpers::{
 		enclave_signer_account, ensure_enclave_signer_account, ensure_maintainer_account,
-		get_mortality, shard_vault, shielding_target_genesis_hash, store_note, wrap_bytes,
+		get_mortality, pending_enclave_account_rotation, set_enclave_signer_account,
+		set_pending_enclave_account_rotation, shard_vault, shielding_target_genesis_hash,
+		sidechain_block_number, store_note, wrap_bytes,
 	},
-	Getter, STF_SHIELDING_FEE_AMOUNT_DIVIDER,
+	Getter, MAX_SCHEDULED_CALLS_PER_TIMESTAMP, STF_SHIELDING_FEE_AMOUNT_DIVIDER,
 };
 use codec::{Compact, Decode, Encode};
-use frame_support::{ensure, traits::UnfilteredDispatchable};
+use frame_support::{
+	ensure,
+	traits::{fungibles, UnfilteredDispatchable},
+};
 use ita_parentchain_specs::MinimalChainSpec;
 #[cfg(feature = "evm")]
 use ita_sgx_runtime::{AddressMapping, HashedAddressMapping};
-pub use ita_sgx_runtime::{Balance, Index};
-use ita_sgx_runtime::{
-	ParentchainInstanceIntegritee, ParentchainInstanceTargetA, ParentchainInstanceTargetB,
-	ParentchainIntegritee, Runtime, System,
-};
+pub use ita_sgx_runtime::{AssetId, Balance, Index};
+use ita_sgx_runtime::{Assets, MultisigCalls, Parentchain, Runtime, ScheduledCalls, System};
 use itp_node_api::metadata::{provider::AccessNodeMetadata, NodeMetadataTrait};
 use itp_node_api_metadata::{
-	pallet_balances::BalancesCallIndexes, pallet_enclave_bridge::EnclaveBridgeCallIndexes,
-	pallet_proxy::ProxyCallIndexes,
+	pallet_assets::AssetsCallIndexes, pallet_balances::BalancesCallIndexes,
+	pallet_enclave_bridge::EnclaveBridgeCallIndexes, pallet_proxy::ProxyCallIndexes,
 };
 use itp_stf_interface::ExecuteCall;
 use itp_stf_primitives::{
@@ -58,7 +61,9 @@ use itp_types::{
 };
 use itp_utils::stringify::account_id_to_string;
 use log::*;
+pub use pallet_multisig_calls::{multi_account_id, CallHash, Proposal};
 use pallet_notes::{TimestampedTrustedNote, TrustedNote};
+pub use pallet_scheduled_calls::{Recurrence, TaskId};
 use sp_core::{
 	crypto::{AccountId32, UncheckedFrom},
 	ed25519,
@@ -78,9 +83,19 @@ pub enum TrustedCall {
 	balance_unshield(AccountId, AccountId, Balance, ShardIdentifier) = 3, // (AccountIncognito, BeneficiaryPublicAccount, Amount, Shard)
 	balance_shield(AccountId, AccountId, Balance, ParentchainId) = 4, // (Root, AccountIncognito, Amount, origin parentchain)
 	balance_transfer_with_note(AccountId, AccountId, Balance, Vec<u8>) = 5,
+	send_note(AccountId, Vec<AccountId>, Vec<u8>) = 6, // (Sender, Recipients, Message)
+	rotate_enclave_account(AccountId, AccountId, u64) = 7, // (Root, new enclave account, sidechain block number of the rotation)
+	assets_transfer(AccountId, AccountId, AssetId, Balance) = 20,
+	assets_unshield(AccountId, AccountId, AssetId, Balance, ShardIdentifier) = 21, // (AccountIncognito, BeneficiaryPublicAccount, AssetId, Amount, Shard)
+	assets_shield(AccountId, AccountId, AssetId, Balance, ParentchainId) = 22, // (Root, AccountIncognito, AssetId, Amount, origin parentchain)
+	schedule_call(AccountId, Moment, Option<Recurrence<Moment>>, Box<TrustedCall>) = 30, // (Owner, Due, Recurrence, Call)
+	cancel_scheduled_call(AccountId, TaskId) = 31,
+	multisig_approve(AccountId, Vec<AccountId>, u16, Box<TrustedCall>) = 40, // (Signatory, OtherSignatories, Threshold, Call)
+	multisig_cancel(AccountId, Vec<AccountId>, u16, CallHash) = 41, // (Proposer, OtherSignatories, Threshold, CallHash)
 	note_bloat(AccountId, u32) = 10,
 	waste_time(AccountId, u32) = 11,
 	guess_the_number(GuessTheNumberTrustedCall) = 50,
+	contracts(ContractsTrustedCall) = 60,
 	#[cfg(feature = "evm")]
 	evm_withdraw(AccountId, H160, Balance) = 90, // (Origin, Address EVM Account, Value)
 	// (Origin, Source, Target, Input, Value, Gas limit, Max fee per gas, Max priority fee per gas, Nonce, Access list)
@@ -138,6 +153,15 @@ impl TrustedCall {
 			Self::balance_unshield(sender_account, ..) => sender_account,
 			Self::balance_shield(sender_account, ..) => sender_account,
 			Self::balance_transfer_with_note(sender_account, ..) => sender_account,
+			Self::send_note(sender_account, ..) => sender_account,
+			Self::rotate_enclave_account(sender_account, ..) => sender_account,
+			Self::assets_transfer(sender_account, ..) => sender_account,
+			Self::assets_unshield(sender_account, ..) => sender_account,
+			Self::assets_shield(sender_account, ..) => sender_account,
+			Self::schedule_call(sender_account, ..) => sender_account,
+			Self::cancel_scheduled_call(sender_account, ..) => sender_account,
+			Self::multisig_approve(sender_account, ..) => sender_account,
+			Self::multisig_cancel(sender_account, ..) => sender_account,
 			Self::timestamp_set(sender_account, ..) => sender_account,
 			Self::note_bloat(sender_account, ..) => sender_account,
 			Self::waste_time(sender_account, ..) => sender_account,
@@ -150,6 +174,7 @@ impl TrustedCall {
 			#[cfg(feature = "evm")]
 			Self::evm_create2(sender_account, ..) => sender_account,
 			Self::guess_the_number(call) => call.sender_account(),
+			Self::contracts(call) => call.sender_account(),
 		}
 	}
 }
@@ -251,7 +276,6 @@ where
 		node_metadata_repo: Arc<NodeMetadataRepository>,
 	) -> Result<(), Self::Error> {
 		let sender = self.call.sender_account().clone();
-		let call_hash = blake2_256(&self.call.encode());
 		let system_nonce = System::account_nonce(&sender);
 		ensure!(self.nonce == system_nonce, Self::Error::InvalidNonce(self.nonce, system_nonce));
 
@@ -264,14 +288,49 @@ where
 		// so it should be considered as valid
 		System::inc_account_nonce(&sender);
 
+		self.dispatch(fee, calls, node_metadata_repo)
+	}
+
+	fn get_storage_hashes_to_update(self) -> Vec<Vec<u8>> {
+		let mut key_hashes = Vec::new();
+		match self.call {
+			TrustedCall::noop(..) => debug!("No storage updates needed..."),
+			TrustedCall::guess_the_number(call) =>
+				key_hashes.append(&mut <GuessTheNumberTrustedCall as ExecuteCall<
+					NodeMetadataRepository,
+				>>::get_storage_hashes_to_update(call)),
+			TrustedCall::contracts(call) =>
+				key_hashes.append(&mut <ContractsTrustedCall as ExecuteCall<
+					NodeMetadataRepository,
+				>>::get_storage_hashes_to_update(call)),
+			_ => debug!("No storage updates needed..."),
+		};
+		key_hashes
+	}
+}
+
+impl TrustedCallSigned {
+	/// Executes the call, assuming that the nonce has been checked and the fee has been charged.
+	fn dispatch<NodeMetadataRepository>(
+		self,
+		fee: Balance,
+		calls: &mut Vec<ParentchainCall>,
+		node_metadata_repo: Arc<NodeMetadataRepository>,
+	) -> Result<(), StfError>
+	where
+		NodeMetadataRepository: AccessNodeMetadata,
+		NodeMetadataRepository::MetadataType: NodeMetadataTrait,
+	{
+		let call_hash = blake2_256(&self.call.encode());
+
 		match self.call.clone() {
 			TrustedCall::noop(who) => {
 				debug!("noop called by {}", account_id_to_string(&who),);
-				Ok::<(), Self::Error>(())
+				Ok::<(), StfError>(())
 			},
 			#[cfg(any(feature = "test", test))]
 			TrustedCall::balance_set_balance(root, who, free_balance, reserved_balance) => {
-				ensure!(is_root::<Runtime, AccountId>(&root), Self::Error::MissingPrivileges(root));
+				ensure!(is_root::<Runtime, AccountId>(&root), StfError::MissingPrivileges(root));
 				debug!(
 					"balance_set_balance({}, {}, {})",
 					account_id_to_string(&who),
@@ -284,7 +343,7 @@ where
 				}
 				.dispatch_bypass_filter(ita_sgx_runtime::RuntimeOrigin::root())
 				.map_err(|e| {
-					Self::Error::Dispatch(format!("Balance Set Balance error: {:?}", e.error))
+					StfError::Dispatch(format!("Balance Set Balance error: {:?}", e.error))
 				})?;
 				store_note(&root, self.call, vec![who])?;
 				// This explicit Error type is somehow still needed, otherwise the compiler complains
@@ -294,7 +353,7 @@ where
 				//
 				// Alternatively, removing the customised "impl From<..> for StfError" and use map_err directly
 				// would also work
-				Ok::<(), Self::Error>(())
+				Ok::<(), StfError>(())
 			},
 			TrustedCall::balance_transfer(from, to, value) => {
 				let origin = ita_sgx_runtime::RuntimeOrigin::signed(from.clone());
@@ -305,7 +364,7 @@ where
 				}
 				.dispatch_bypass_filter(origin)
 				.map_err(|e| {
-					Self::Error::Dispatch(format!("Balance Transfer error: {:?}", e.error))
+					StfError::Dispatch(format!("Balance Transfer error: {:?}", e.error))
 				})?;
 				store_note(&from, self.call, vec![from.clone(), to])?;
 				Ok(())
@@ -319,11 +378,23 @@ where
 				}
 				.dispatch_bypass_filter(origin)
 				.map_err(|e| {
-					Self::Error::Dispatch(format!("Balance Transfer error: {:?}", e.error))
+					StfError::Dispatch(format!("Balance Transfer error: {:?}", e.error))
 				})?;
 				store_note(&from, self.call, vec![from.clone(), to])?;
 				Ok(())
 			},
+			TrustedCall::send_note(from, to, _msg) => {
+				std::println!("⣿STF⣿ ✉ send_note from ⣿⣿⣿ to ⣿⣿⣿ with note ⣿⣿⣿");
+				if to.is_empty() {
+					return Err(StfError::Dispatch(
+						"note must have at least one recipient".to_string(),
+					))
+				}
+				let mut link_to = to;
+				link_to.push(from.clone());
+				store_note(&from, self.call, link_to)?;
+				Ok(())
+			},
 			TrustedCall::balance_unshield(account_incognito, beneficiary, value, shard) => {
 				std::println!(
 					"⣿STF⣿ 🛡👐 balance_unshield from ⣿⣿⣿ to {}, amount {}",
@@ -349,7 +420,7 @@ where
 				let (vault, parentchain_id) = shard_vault().ok_or_else(|| {
 					StfError::Dispatch("shard vault key hasn't been set".to_string())
 				})?;
-				let vault_address = Address::from(vault);
+				let vault_address = Address::from(vault.clone());
 				let vault_transfer_call = OpaqueCall::from_tuple(&(
 					node_metadata_repo
 						.get_from_metadata(|m| m.transfer_keep_alive_call_indexes())
@@ -365,17 +436,18 @@ where
 						.map_err(|_| StfError::InvalidMetadata)?,
 					vault_address,
 					None::<ProxyType>,
-					vault_transfer_call,
+					vault_transfer_call.clone(),
 				));
 				let mortality =
 					get_mortality(parentchain_id, 32).unwrap_or_else(GenericMortality::immortal);
 
-				let parentchain_call = match parentchain_id {
-					ParentchainId::Integritee => ParentchainCall::Integritee { call, mortality },
-					ParentchainId::TargetA => ParentchainCall::TargetA { call, mortality },
-					ParentchainId::TargetB => ParentchainCall::TargetB { call, mortality },
-				};
-				calls.push(parentchain_call);
+				calls.push(ParentchainCall::VaultWithdrawal {
+					parentchain_id,
+					vault,
+					transfer: vault_transfer_call,
+					call,
+					mortality,
+				});
 				Ok(())
 			},
 			TrustedCall::balance_shield(enclave_account, who, value, parentchain_id) => {
@@ -413,61 +485,277 @@ where
 				});
 				Ok(())
 			},
+			TrustedCall::assets_transfer(from, to, asset_id, value) => {
+				let origin = ita_sgx_runtime::RuntimeOrigin::signed(from.clone());
+				std::println!("⣿STF⣿ 🔄 assets_transfer from ⣿⣿⣿ to ⣿⣿⣿ asset ⣿⣿⣿ amount ⣿⣿⣿");
+				ita_sgx_runtime::AssetsCall::<Runtime>::transfer {
+					id: asset_id,
+					target: MultiAddress::Id(to.clone()),
+					amount: value,
+				}
+				.dispatch_bypass_filter(origin)
+				.map_err(|e| StfError::Dispatch(format!("Assets Transfer error: {:?}", e.error)))?;
+				store_note(&from, self.call, vec![from.clone(), to])?;
+				Ok(())
+			},
+			TrustedCall::assets_unshield(
+				account_incognito,
+				beneficiary,
+				asset_id,
+				value,
+				shard,
+			) => {
+				std::println!(
+					"⣿STF⣿ 🛡👐 assets_unshield from ⣿⣿⣿ to {}, asset {}, amount {}",
+					account_id_to_string(&beneficiary),
+					asset_id,
+					value
+				);
+				info!(
+					"assets_unshield(from (L2): {}, to (L1): {}, asset {}, amount {} (+fee: {}), shard {})",
+					account_id_to_string(&account_incognito),
+					account_id_to_string(&beneficiary),
+					asset_id,
+					value,
+					fee,
+					shard
+				);
+
+				burn_assets(&account_incognito, asset_id, value)?;
+				store_note(
+					&account_incognito,
+					self.call,
+					vec![account_incognito.clone(), beneficiary.clone()],
+				)?;
+
+				let (vault, parentchain_id) = shard_vault().ok_or_else(|| {
+					StfError::Dispatch("shard vault key hasn't been set".to_string())
+				})?;
+				let vault_address = Address::from(vault.clone());
+				let vault_transfer_call = OpaqueCall::from_tuple(&(
+					node_metadata_repo
+						.get_from_metadata(|m| m.assets_transfer_keep_alive_call_indexes())
+						.map_err(|_| StfError::InvalidMetadata)?
+						.map_err(|_| StfError::InvalidMetadata)?,
+					Compact(asset_id),
+					Address::from(beneficiary),
+					Compact(value),
+				));
+				let call = OpaqueCall::from_tuple(&(
+					node_metadata_repo
+						.get_from_metadata(|m| m.proxy_call_indexes())
+						.map_err(|_| StfError::InvalidMetadata)?
+						.map_err(|_| StfError::InvalidMetadata)?,
+					vault_address,
+					None::<ProxyType>,
+					vault_transfer_call.clone(),
+				));
+				let mortality =
+					get_mortality(parentchain_id, 32).unwrap_or_else(GenericMortality::immortal);
+
+				calls.push(ParentchainCall::VaultWithdrawal {
+					parentchain_id,
+					vault,
+					transfer: vault_transfer_call,
+					call,
+					mortality,
+				});
+				Ok(())
+			},
+			TrustedCall::assets_shield(enclave_account, who, asset_id, value, parentchain_id) => {
+				ensure_enclave_signer_account(&enclave_account)?;
+				debug!(
+					"assets_shield({}, {}, {}, {:?})",
+					account_id_to_string(&who),
+					asset_id,
+					value,
+					parentchain_id
+				);
+				let (_vault_account, vault_parentchain_id) =
+					shard_vault().ok_or(StfError::NoShardVaultAssigned)?;
+				// Asset ids are only unique per parentchain, so we can only accept the assets of
+				// the parentchain which holds the vault.
+				ensure!(
+					parentchain_id == vault_parentchain_id,
+					StfError::WrongParentchainIdForShardVault
+				);
+				std::println!(
+					"⣿STF⣿ 🛡 will shield asset {} to {}",
+					asset_id,
+					account_id_to_string(&who)
+				);
+				mint_assets(&enclave_account, &who, asset_id, value)?;
+				store_note(&enclave_account, self.call, vec![who])?;
+
+				// Send proof of execution on chain.
+				let mortality =
+					get_mortality(parentchain_id, 32).unwrap_or_else(GenericMortality::immortal);
+				calls.push(ParentchainCall::Integritee {
+					call: OpaqueCall::from_tuple(&(
+						node_metadata_repo
+							.get_from_metadata(|m| m.publish_hash_call_indexes())
+							.map_err(|_| StfError::InvalidMetadata)?
+							.map_err(|_| StfError::InvalidMetadata)?,
+						call_hash,
+						Vec::<itp_types::H256>::new(),
+						b"shielded some assets!".to_vec(),
+					)),
+					mortality,
+				});
+				Ok(())
+			},
+			TrustedCall::rotate_enclave_account(
+				enclave_account,
+				new_enclave_account,
+				activation_block,
+			) => {
+				ensure_enclave_signer_account(&enclave_account)?;
+				debug!(
+					"rotate_enclave_account({}, {}, {})",
+					account_id_to_string(&enclave_account),
+					account_id_to_string(&new_enclave_account),
+					activation_block
+				);
+				// All validateers switch to the rotated keys once the enclave account has been
+				// rotated, so they need to be given time to obtain the keys.
+				ensure!(
+					activation_block > sidechain_block_number(),
+					StfError::Dispatch(
+						"enclave account rotation must be in the future".to_string()
+					)
+				);
+				set_pending_enclave_account_rotation(Some((new_enclave_account, activation_block)));
+				Ok(())
+			},
 			TrustedCall::timestamp_set(enclave_account, now, parentchain_id) => {
 				ensure_enclave_signer_account(&enclave_account)?;
 				debug!("timestamp_set({}, {:?})", now, parentchain_id);
-				match parentchain_id {
-					ParentchainId::Integritee => {
-						if ParentchainIntegritee::creation_timestamp().is_none() {
-							debug!(
-								"initializing creation timestamp({}, {:?})",
-								now, parentchain_id
-							);
-							ita_sgx_runtime::ParentchainPalletCall::<
-								Runtime,
-								ParentchainInstanceIntegritee,
-							>::set_creation_timestamp {
-								creation: now,
-							}
-							.dispatch_bypass_filter(ita_sgx_runtime::RuntimeOrigin::root())
-							.map_err(|e| {
-								Self::Error::Dispatch(format!("Timestamp Set error: {:?}", e.error))
-							})?;
-						};
-						ita_sgx_runtime::ParentchainPalletCall::<
-							Runtime,
-							ParentchainInstanceIntegritee,
-						>::set_now {
-							now,
-						}
-						.dispatch_bypass_filter(ita_sgx_runtime::RuntimeOrigin::root())
-						.map_err(|e| {
-							Self::Error::Dispatch(format!("Timestamp Set error: {:?}", e.error))
-						})?
-					},
-					ParentchainId::TargetA => ita_sgx_runtime::ParentchainPalletCall::<
-						Runtime,
-						ParentchainInstanceTargetA,
-					>::set_now {
-						now,
+				if parentchain_id == ParentchainId::Integritee
+					&& Parentchain::creation_timestamp(parentchain_id).is_none()
+				{
+					debug!("initializing creation timestamp({}, {:?})", now, parentchain_id);
+					ita_sgx_runtime::ParentchainPalletCall::<Runtime>::set_creation_timestamp {
+						parentchain_id,
+						creation: now,
 					}
 					.dispatch_bypass_filter(ita_sgx_runtime::RuntimeOrigin::root())
 					.map_err(|e| {
-						Self::Error::Dispatch(format!("Timestamp Set error: {:?}", e.error))
-					})?,
-					ParentchainId::TargetB => ita_sgx_runtime::ParentchainPalletCall::<
-						Runtime,
-						ParentchainInstanceTargetB,
-					>::set_now {
-						now,
-					}
+						StfError::Dispatch(format!("Timestamp Set error: {:?}", e.error))
+					})?;
+				};
+				ita_sgx_runtime::ParentchainPalletCall::<Runtime>::set_now { parentchain_id, now }
 					.dispatch_bypass_filter(ita_sgx_runtime::RuntimeOrigin::root())
 					.map_err(|e| {
-						Self::Error::Dispatch(format!("Timestamp Set error: {:?}", e.error))
-					})?,
+						StfError::Dispatch(format!("Timestamp Set error: {:?}", e.error))
+					})?;
+				if parentchain_id == ParentchainId::Integritee {
+					// The Integritee parentchain is the clock for scheduled calls, as every
+					// shard is synced with it.
+					execute_due_calls(now, calls, node_metadata_repo)
 				};
 				Ok(())
 			},
+			TrustedCall::schedule_call(owner, due, recurrence, call) => {
+				debug!(
+					"schedule_call({}, {}, {:?}, {:?})",
+					account_id_to_string(&owner),
+					due,
+					recurrence,
+					call
+				);
+				// the signature of the scheduling call authorizes the scheduled call
+				ensure!(
+					call.sender_account() == &owner,
+					StfError::Dispatch("scheduled call must be sent by its owner".to_string())
+				);
+				ensure!(
+					!matches!(
+						*call,
+						TrustedCall::schedule_call(..) | TrustedCall::cancel_scheduled_call(..)
+					),
+					StfError::Dispatch("scheduling calls can't be scheduled".to_string())
+				);
+				std::println!("⣿STF⣿ ⏰ schedule_call by ⣿⣿⣿ due at {}", due);
+				ita_sgx_runtime::ScheduledCallsCall::<Runtime>::schedule {
+					due,
+					recurrence,
+					call: call.encode(),
+				}
+				.dispatch_bypass_filter(ita_sgx_runtime::RuntimeOrigin::signed(owner.clone()))
+				.map_err(|e| StfError::Dispatch(format!("Schedule call error: {:?}", e.error)))?;
+				store_note(&owner, self.call, vec![owner.clone()])?;
+				Ok(())
+			},
+			TrustedCall::cancel_scheduled_call(owner, task_id) => {
+				debug!("cancel_scheduled_call({}, {})", account_id_to_string(&owner), task_id);
+				ita_sgx_runtime::ScheduledCallsCall::<Runtime>::cancel { task_id }
+					.dispatch_bypass_filter(ita_sgx_runtime::RuntimeOrigin::signed(owner.clone()))
+					.map_err(|e| {
+						StfError::Dispatch(format!("Cancel scheduled call error: {:?}", e.error))
+					})?;
+				store_note(&owner, self.call, vec![owner.clone()])?;
+				Ok(())
+			},
+			TrustedCall::multisig_approve(signatory, other_signatories, threshold, call) => {
+				debug!(
+					"multisig_approve({}, {} others, {}, {:?})",
+					account_id_to_string(&signatory),
+					other_signatories.len(),
+					threshold,
+					call
+				);
+				let mut signatories = other_signatories.clone();
+				signatories.push(signatory.clone());
+				let multisig = multi_account_id(&signatories, threshold);
+				ensure!(
+					call.sender_account() == &multisig,
+					StfError::Dispatch(
+						"call must be sent by the multisig account of the signatories".to_string()
+					)
+				);
+				let encoded_call = call.encode();
+				let call_hash = blake2_256(&encoded_call);
+				std::println!("⣿STF⣿ 🔐 multisig_approve by ⣿⣿⣿ for ⣿⣿⣿");
+				ita_sgx_runtime::MultisigCallsCall::<Runtime>::approve {
+					other_signatories,
+					threshold,
+					call: encoded_call,
+				}
+				.dispatch_bypass_filter(ita_sgx_runtime::RuntimeOrigin::signed(signatory.clone()))
+				.map_err(|e| {
+					StfError::Dispatch(format!("Multisig approve error: {:?}", e.error))
+				})?;
+				store_note(&signatory, self.call, signatories)?;
+				if MultisigCalls::take_approved(&multisig, call_hash).is_some() {
+					std::println!("⣿STF⣿ 🔐 executing multisig call");
+					// the approvals are consumed regardless of the outcome, like on any other
+					// failing call
+					if let Err(e) = dispatch_authorized_call(*call, calls, node_metadata_repo) {
+						warn!("multisig call {:?} failed: {:?}", call_hash, e)
+					}
+				}
+				Ok(())
+			},
+			TrustedCall::multisig_cancel(proposer, other_signatories, threshold, call_hash) => {
+				debug!(
+					"multisig_cancel({}, {} others, {}, {:?})",
+					account_id_to_string(&proposer),
+					other_signatories.len(),
+					threshold,
+					call_hash
+				);
+				let mut signatories = other_signatories.clone();
+				signatories.push(proposer.clone());
+				ita_sgx_runtime::MultisigCallsCall::<Runtime>::cancel {
+					other_signatories,
+					threshold,
+					call_hash,
+				}
+				.dispatch_bypass_filter(ita_sgx_runtime::RuntimeOrigin::signed(proposer.clone()))
+				.map_err(|e| StfError::Dispatch(format!("Multisig cancel error: {:?}", e.error)))?;
+				store_note(&proposer, self.call, signatories)?;
+				Ok(())
+			},
 			TrustedCall::note_bloat(sender, kilobytes) => {
 				ensure_maintainer_account(&sender)?;
 				if kilobytes >= 1_100 {
@@ -507,7 +795,7 @@ where
 				ita_sgx_runtime::EvmCall::<Runtime>::withdraw { address, value }
 					.dispatch_bypass_filter(ita_sgx_runtime::RuntimeOrigin::signed(from))
 					.map_err(|e| {
-						Self::Error::Dispatch(format!("Evm Withdraw error: {:?}", e.error))
+						StfError::Dispatch(format!("Evm Withdraw error: {:?}", e.error))
 					})?;
 				Ok(())
 			},
@@ -542,7 +830,7 @@ where
 					access_list,
 				}
 				.dispatch_bypass_filter(ita_sgx_runtime::RuntimeOrigin::signed(from))
-				.map_err(|e| Self::Error::Dispatch(format!("Evm Call error: {:?}", e.error)))?;
+				.map_err(|e| StfError::Dispatch(format!("Evm Call error: {:?}", e.error)))?;
 				Ok(())
 			},
 			#[cfg(feature = "evm")]
@@ -576,7 +864,7 @@ where
 					access_list,
 				}
 				.dispatch_bypass_filter(ita_sgx_runtime::RuntimeOrigin::signed(from))
-				.map_err(|e| Self::Error::Dispatch(format!("Evm Create error: {:?}", e.error)))?;
+				.map_err(|e| StfError::Dispatch(format!("Evm Create error: {:?}", e.error)))?;
 				let contract_address = evm_create_address(source, nonce_evm_account);
 				info!("Trying to create evm contract with address {:?}", contract_address);
 				Ok(())
@@ -613,36 +901,89 @@ where
 					access_list,
 				}
 				.dispatch_bypass_filter(ita_sgx_runtime::RuntimeOrigin::signed(from))
-				.map_err(|e| Self::Error::Dispatch(format!("Evm Create2 error: {:?}", e.error)))?;
+				.map_err(|e| StfError::Dispatch(format!("Evm Create2 error: {:?}", e.error)))?;
 				let contract_address = evm_create2_address(source, salt, code_hash);
 				info!("Trying to create evm contract with address {:?}", contract_address);
 				Ok(())
 			},
 			TrustedCall::guess_the_number(call) => call.execute(calls, node_metadata_repo),
+			TrustedCall::contracts(call) => call.execute(calls, node_metadata_repo),
 		}?;
 		Ok(())
 	}
+}
 
-	fn get_storage_hashes_to_update(self) -> Vec<Vec<u8>> {
-		let mut key_hashes = Vec::new();
-		match self.call {
-			TrustedCall::noop(..) => debug!("No storage updates needed..."),
-			TrustedCall::guess_the_number(call) =>
-				key_hashes.append(&mut <GuessTheNumberTrustedCall as ExecuteCall<
-					NodeMetadataRepository,
-				>>::get_storage_hashes_to_update(call)),
-			_ => debug!("No storage updates needed..."),
+/// Executes the scheduled calls which are due at `now` on behalf of their owners, who pay the fee
+/// as if they sent the calls themselves. A failing call doesn't affect the others.
+///
+/// Note that the state changes a call has made before it failed are kept, as the STF doesn't
+/// support storage transactions. This is the same as for calls that are sent directly.
+fn execute_due_calls<NodeMetadataRepository>(
+	now: Moment,
+	calls: &mut Vec<ParentchainCall>,
+	node_metadata_repo: Arc<NodeMetadataRepository>,
+) where
+	NodeMetadataRepository: AccessNodeMetadata,
+	NodeMetadataRepository::MetadataType: NodeMetadataTrait,
+{
+	for (task_id, scheduled_call) in
+		ScheduledCalls::take_due(now, MAX_SCHEDULED_CALLS_PER_TIMESTAMP)
+	{
+		let call = match TrustedCall::decode(&mut scheduled_call.call.as_slice()) {
+			Ok(call) => call,
+			Err(e) => {
+				error!("could not decode scheduled call {}: {:?}", task_id, e);
+				continue
+			},
 		};
-		key_hashes
+		std::println!("⣿STF⣿ ⏰ executing scheduled call {}", task_id);
+		if let Err(e) = dispatch_authorized_call(call, calls, node_metadata_repo.clone()) {
+			warn!("scheduled call {} failed: {:?}", task_id, e)
+		}
 	}
 }
 
+/// Executes a call which has been authorized by other means than the signature of its sender, like
+/// a scheduled or a multisig call. The sender pays the fee, but no nonce is consumed, so the call
+/// can't invalidate pending operations of the sender. Parentchain calls are only emitted if the
+/// call succeeds.
+fn dispatch_authorized_call<NodeMetadataRepository>(
+	call: TrustedCall,
+	calls: &mut Vec<ParentchainCall>,
+	node_metadata_repo: Arc<NodeMetadataRepository>,
+) -> Result<(), StfError>
+where
+	NodeMetadataRepository: AccessNodeMetadata,
+	NodeMetadataRepository::MetadataType: NodeMetadataTrait,
+{
+	let sender = call.sender_account().clone();
+	let trusted_call = TrustedCallSigned::new(
+		call,
+		System::account_nonce(&sender),
+		MultiSignature::Ed25519(ed25519::Signature([0u8; 64])),
+	);
+	let fee = get_fee_for(&trusted_call);
+	charge_fee(fee, &sender)?;
+	let mut authorized_parentchain_calls = Vec::new();
+	trusted_call.dispatch(fee, &mut authorized_parentchain_calls, node_metadata_repo)?;
+	calls.append(&mut authorized_parentchain_calls);
+	Ok(())
+}
+
 fn get_fee_for(tc: &TrustedCallSigned) -> Balance {
 	let one = MinimalChainSpec::one_unit(shielding_target_genesis_hash().unwrap_or_default());
 	match &tc.call {
 		TrustedCall::balance_transfer(..) => one / crate::STF_TX_FEE_UNIT_DIVIDER,
 		TrustedCall::balance_unshield(..) => one / crate::STF_TX_FEE_UNIT_DIVIDER * 3,
+		TrustedCall::send_note(..) => one / crate::STF_TX_FEE_UNIT_DIVIDER,
+		TrustedCall::assets_transfer(..) => one / crate::STF_TX_FEE_UNIT_DIVIDER,
+		TrustedCall::assets_unshield(..) => one / crate::STF_TX_FEE_UNIT_DIVIDER * 3,
+		TrustedCall::schedule_call(..) => one / crate::STF_TX_FEE_UNIT_DIVIDER,
+		TrustedCall::cancel_scheduled_call(..) => one / crate::STF_TX_FEE_UNIT_DIVIDER,
+		TrustedCall::multisig_approve(..) => one / crate::STF_TX_FEE_UNIT_DIVIDER,
+		TrustedCall::multisig_cancel(..) => one / crate::STF_TX_FEE_UNIT_DIVIDER,
 		TrustedCall::guess_the_number(call) => crate::guess_the_number::get_fee_for(call),
+		TrustedCall::contracts(call) => crate::contracts::get_fee_for(call),
 		_ => Balance::from(0u32),
 	}
 }
@@ -660,6 +1001,19 @@ fn charge_fee(fee: Balance, payer: &AccountId) -> Result<(), StfError> {
 	Ok(())
 }
 
+/// Pays back part of a fee that has been charged in advance, e.g. for unused gas.
+pub(crate) fn refund_fee(amount: Balance, payee: &AccountId) -> Result<(), StfError> {
+	debug!("attempting to refund {} of the fee for TrustedCall", amount);
+	let origin = ita_sgx_runtime::RuntimeOrigin::signed(enclave_signer_account());
+	ita_sgx_runtime::BalancesCall::<Runtime>::transfer {
+		dest: MultiAddress::Id(payee.clone()),
+		value: amount,
+	}
+	.dispatch_bypass_filter(origin)
+	.map_err(|e| StfError::Dispatch(format!("Fee Refund Error: {:?}", e.error)))?;
+	Ok(())
+}
+
 fn burn_funds(account: &AccountId, amount: u128) -> Result<(), StfError> {
 	let account_info = System::account(&account);
 	if account_info.data.free < amount {
@@ -675,6 +1029,113 @@ fn burn_funds(account: &AccountId, amount: u128) -> Result<(), StfError> {
 	Ok(())
 }
 
+fn burn_assets(account: &AccountId, asset_id: AssetId, amount: Balance) -> Result<(), StfError> {
+	// `burn` would silently reduce the balance by less than `amount`.
+	if Assets::balance(asset_id, account) < amount {
+		return Err(StfError::MissingFunds)
+	}
+
+	ita_sgx_runtime::AssetsCall::<Runtime>::burn {
+		id: asset_id,
+		who: MultiAddress::Id(account.clone()),
+		amount,
+	}
+	.dispatch_bypass_filter(ita_sgx_runtime::RuntimeOrigin::signed(enclave_signer_account()))
+	.map_err(|e| StfError::Dispatch(format!("Burn assets error: {:?}", e.error)))?;
+	Ok(())
+}
+
+fn mint_assets(
+	enclave_account: &AccountId,
+	beneficiary: &AccountId,
+	asset_id: AssetId,
+	amount: Balance,
+) -> Result<(), StfError> {
+	// Assets are created lazily upon first shielding, owned by the enclave. They are sufficient,
+	// so shielding doesn't require the beneficiary to hold native funds on L2.
+	if !<Assets as fungibles::Inspect<AccountId>>::asset_exists(asset_id) {
+		ita_sgx_runtime::AssetsCall::<Runtime>::force_create {
+			id: asset_id,
+			owner: MultiAddress::Id(enclave_account.clone()),
+			is_sufficient: true,
+			min_balance: 1,
+		}
+		.dispatch_bypass_filter(ita_sgx_runtime::RuntimeOrigin::root())
+		.map_err(|e| StfError::Dispatch(format!("Create asset error: {:?}", e.error)))?;
+	}
+
+	ita_sgx_runtime::AssetsCall::<Runtime>::mint {
+		id: asset_id,
+		beneficiary: MultiAddress::Id(beneficiary.clone()),
+		amount,
+	}
+	.dispatch_bypass_filter(ita_sgx_runtime::RuntimeOrigin::signed(enclave_account.clone()))
+	.map_err(|e| StfError::Dispatch(format!("Mint assets error: {:?}", e.error)))?;
+	Ok(())
+}
+
+/// Moves the funds of the enclave account, i.e. the collected fees, and the ownership of the
+/// assets it created to the account derived from the rotated shielding key.
+/// Rotates the enclave account if a rotation is due in the current sidechain block.
+///
+/// The funds and assets of the previous enclave account are moved to the new one.
+pub(crate) fn rotate_enclave_account_if_due() -> Result<(), StfError> {
+	let (new_enclave_account, activation_block) =
+		match pending_enclave_account_rotation::<AccountId>() {
+			Some(rotation) => rotation,
+			None => return Ok(()),
+		};
+	if sidechain_block_number() < activation_block {
+		return Ok(())
+	}
+	set_pending_enclave_account_rotation::<AccountId>(None);
+
+	let enclave_account: AccountId = enclave_signer_account();
+	info!(
+		"rotating enclave account {} to {}",
+		account_id_to_string(&enclave_account),
+		account_id_to_string(&new_enclave_account)
+	);
+	migrate_enclave_account(&enclave_account, &new_enclave_account)?;
+	set_enclave_signer_account(&new_enclave_account);
+	Ok(())
+}
+
+fn migrate_enclave_account(from: &AccountId, to: &AccountId) -> Result<(), StfError> {
+	let origin = ita_sgx_runtime::RuntimeOrigin::signed(from.clone());
+	let owned_assets: Vec<AssetId> =
+		<Assets as fungibles::InspectEnumerable<AccountId>>::asset_ids()
+			.filter(|id| {
+				<Assets as fungibles::roles::Inspect<AccountId>>::owner(*id).as_ref() == Some(from)
+			})
+			.collect();
+	for id in owned_assets {
+		ita_sgx_runtime::AssetsCall::<Runtime>::set_team {
+			id,
+			issuer: MultiAddress::Id(to.clone()),
+			admin: MultiAddress::Id(to.clone()),
+			freezer: MultiAddress::Id(to.clone()),
+		}
+		.dispatch_bypass_filter(origin.clone())
+		.and_then(|_| {
+			ita_sgx_runtime::AssetsCall::<Runtime>::transfer_ownership {
+				id,
+				owner: MultiAddress::Id(to.clone()),
+			}
+			.dispatch_bypass_filter(origin.clone())
+		})
+		.map_err(|e| StfError::Dispatch(format!("Migrate enclave assets error: {:?}", e.error)))?;
+	}
+
+	ita_sgx_runtime::BalancesCall::<Runtime>::transfer_all {
+		dest: MultiAddress::Id(to.clone()),
+		keep_alive: false,
+	}
+	.dispatch_bypass_filter(origin)
+	.map_err(|e| StfError::Dispatch(format!("Migrate enclave funds error: {:?}", e.error)))?;
+	Ok(())
+}
+
 fn shield_funds(account: &AccountId, amount: u128) -> Result<(), StfError> {
 	//fixme: make fee configurable and send fee to vault account on L2
 	let fee = amount / STF_SHIELDING_FEE_AMOUNT_DIVIDER;
@@ -0,0 +1,304 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+use crate::{
+	error::{Error, Result},
+	nonce_cache::NonceCache,
+	response::{
+		decode_account_event_update, decode_return_value, decode_status_update,
+		decode_subscription_id, is_final_status,
+	},
+	subscription::{AccountEventStream, StatusStream, Subscription},
+};
+use base58::ToBase58;
+use codec::{Decode, Encode};
+use ita_stf::{AccountEventsSubscription, Getter, TrustedCall, TrustedCallSigned, TrustedGetter};
+use itc_rpc_client::direct_client::{DirectApi, DirectClient};
+use itp_rpc::{RpcRequest, RpcReturnValue};
+use itp_sgx_crypto::ShieldingCryptoEncrypt;
+use itp_stf_primitives::{
+	traits::TrustedCallSigning,
+	types::{AccountId, KeyPair, ShardIdentifier, TrustedOperation},
+};
+use itp_types::{AccountInfo, Index, MrEnclave, Request};
+use itp_utils::ToHexPrefixed;
+use log::*;
+use sgx_crypto_helper::rsa3072::Rsa3072PubKey;
+use std::{
+	sync::{
+		mpsc::{channel, Receiver, RecvTimeoutError, TryRecvError},
+		Arc, RwLock,
+	},
+	thread,
+	time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::{
+	sync::{mpsc::unbounded_channel, oneshot},
+	task,
+};
+
+pub const SUBMIT_AND_WATCH_METHOD: &str = "author_submitAndWatchExtrinsic";
+pub const PENDING_TRUSTED_CALLS_FOR_METHOD: &str = "author_pendingTrustedCallsFor";
+pub const SUBSCRIBE_ACCOUNT_EVENTS_METHOD: &str = "author_subscribeAccountEvents";
+pub const EXECUTE_GETTER_METHOD: &str = "state_executeGetter";
+
+/// How often the thread of a subscription checks if the subscription has been dropped.
+const CLOSE_SIGNAL_CHECK_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Client of the trusted RPC of a worker, for a single shard.
+pub struct TrustedRpcClient {
+	url: String,
+	mrenclave: MrEnclave,
+	shard: ShardIdentifier,
+	shielding_key: RwLock<Option<Arc<Rsa3072PubKey>>>,
+	nonce_cache: NonceCache,
+}
+
+impl TrustedRpcClient {
+	/// Create a client for the worker listening on `url` (e.g. `wss://localhost:2000`).
+	pub fn new(url: impl Into<String>, mrenclave: MrEnclave, shard: ShardIdentifier) -> Self {
+		TrustedRpcClient {
+			url: url.into(),
+			mrenclave,
+			shard,
+			shielding_key: Default::default(),
+			nonce_cache: NonceCache::new(),
+		}
+	}
+
+	pub fn mrenclave(&self) -> MrEnclave {
+		self.mrenclave
+	}
+
+	pub fn shard(&self) -> ShardIdentifier {
+		self.shard
+	}
+
+	/// Shielding key of the worker, only fetched on first use.
+	pub async fn shielding_key(&self) -> Result<Arc<Rsa3072PubKey>> {
+		if let Some(shielding_key) = self.shielding_key.read().expect("Lock poisoning").as_ref() {
+			return Ok(shielding_key.clone())
+		}
+
+		let url = self.url.clone();
+		let shielding_key =
+			Arc::new(task::spawn_blocking(move || DirectClient::new(url).get_rsa_pubkey()).await??);
+		*self.shielding_key.write().expect("Lock poisoning") = Some(shielding_key.clone());
+		Ok(shielding_key)
+	}
+
+	/// Execute a getter and decode its result, `None` if the value is not present in the state.
+	pub async fn get<T: Decode>(&self, getter: &Getter) -> Result<Option<T>> {
+		let request = Request { shard: self.shard, cyphertext: getter.encode() };
+		let return_value = self.request(EXECUTE_GETTER_METHOD, vec![request.to_hex()]).await?;
+
+		let maybe_value = Option::<Vec<u8>>::decode(&mut return_value.value.as_slice())?;
+		Ok(maybe_value.map(|value| T::decode(&mut value.as_slice())).transpose()?)
+	}
+
+	pub async fn account_info(&self, pair: &KeyPair) -> Result<AccountInfo> {
+		let getter = Getter::trusted(TrustedGetter::account_info(pair.account_id()).sign(pair));
+		Ok(self.get(&getter).await?.unwrap_or_default())
+	}
+
+	pub async fn pending_trusted_calls_for(
+		&self,
+		account: &AccountId,
+	) -> Result<Vec<TrustedOperation<TrustedCallSigned, Getter>>> {
+		let params = vec![self.shard.encode().to_base58(), account.to_hex()];
+		let return_value = self.request(PENDING_TRUSTED_CALLS_FOR_METHOD, params).await?;
+		Ok(Decode::decode(&mut return_value.value.as_slice())?)
+	}
+
+	/// Reserve the next nonce of the account.
+	///
+	/// The nonce is fetched from the worker (including the calls still pending in its pool)
+	/// on first use and counted up locally afterwards.
+	pub async fn next_nonce(&self, pair: &KeyPair) -> Result<Index> {
+		let account = pair.account_id();
+		if let Some(nonce) = self.nonce_cache.reserve(&account) {
+			return Ok(nonce)
+		}
+
+		let nonce = self.account_info(pair).await?.nonce;
+		let pending_calls = self.pending_trusted_calls_for(&account).await?.len();
+		let fetched_nonce = nonce + Index::try_from(pending_calls).unwrap_or(Index::MAX);
+		debug!("Fetched nonce {} of {:?}", fetched_nonce, account);
+		Ok(self.nonce_cache.reserve_fetched(account, fetched_nonce))
+	}
+
+	/// Fetch the nonce of the account from the worker again on its next call,
+	/// e.g. after a call turned out to be invalid.
+	pub fn reset_nonce(&self, account: &AccountId) {
+		self.nonce_cache.invalidate(account)
+	}
+
+	/// Sign the call with the next nonce of the signer, submit it and watch its status.
+	pub async fn submit_and_watch(
+		&self,
+		call: &TrustedCall,
+		pair: &KeyPair,
+	) -> Result<StatusStream> {
+		let nonce = self.next_nonce(pair).await?;
+		let signed_call = call.sign(pair, nonce, &self.mrenclave, &self.shard);
+
+		self.submit_and_watch_signed(signed_call).await.map_err(|e| {
+			self.reset_nonce(&pair.account_id());
+			e
+		})
+	}
+
+	/// Submit an already signed call as direct call and watch its status.
+	pub async fn submit_and_watch_signed(&self, call: TrustedCallSigned) -> Result<StatusStream> {
+		let top = call.into_trusted_operation(true);
+		let cyphertext = self
+			.shielding_key()
+			.await?
+			.encrypt(&top.encode())
+			.map_err(|e| Error::Encryption(format!("{:?}", e)))?;
+		let request = Request { shard: self.shard, cyphertext };
+
+		let jsonrpc_call = RpcRequest::compose_jsonrpc_call(
+			SUBMIT_AND_WATCH_METHOD.to_owned(),
+			vec![request.to_hex()],
+		)?;
+		self.watch(jsonrpc_call, decode_status_update, is_final_status).await
+	}
+
+	/// Subscribe to the statuses of the calls of the account and the runtime events touching it.
+	///
+	/// The `nonce` distinguishes several subscriptions of the same account. The worker stops
+	/// sending updates after `lifetime`, which must not exceed
+	/// `MAX_ACCOUNT_EVENTS_SUBSCRIPTION_LIFETIME_MILLIS`.
+	pub async fn subscribe_account_events(
+		&self,
+		pair: &KeyPair,
+		nonce: u32,
+		lifetime: Duration,
+	) -> Result<AccountEventStream> {
+		let expires_at = (SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default()
+			+ lifetime)
+			.as_millis() as u64;
+		let subscription =
+			AccountEventsSubscription::new(pair.account_id(), self.shard, expires_at, nonce)
+				.sign(pair, &self.mrenclave);
+		let jsonrpc_call = RpcRequest::compose_jsonrpc_call(
+			SUBSCRIBE_ACCOUNT_EVENTS_METHOD.to_owned(),
+			vec![subscription.to_hex()],
+		)?;
+		self.watch(jsonrpc_call, decode_account_event_update, |_| false).await
+	}
+
+	async fn request(&self, method: &str, params: Vec<String>) -> Result<RpcReturnValue> {
+		let jsonrpc_call = RpcRequest::compose_jsonrpc_call(method.to_owned(), params)?;
+		let url = self.url.clone();
+
+		let response =
+			task::spawn_blocking(move || DirectClient::new(url).get(&jsonrpc_call)).await??;
+		decode_return_value(&response)
+	}
+
+	/// Send a subscription request and forward its updates until the last one.
+	///
+	/// The connection lives as long as the subscription, so it is driven by a dedicated thread
+	/// instead of occupying a thread of the blocking pool of the runtime. The thread closes the
+	/// connection once the subscription (or this future) is dropped.
+	async fn watch<Update: Send + 'static>(
+		&self,
+		jsonrpc_call: String,
+		decode_update: fn(&str) -> Result<Update>,
+		is_last_update: fn(&Update) -> bool,
+	) -> Result<Subscription<Update>> {
+		let url = self.url.clone();
+		let (id_sender, id_receiver) = oneshot::channel();
+		let (update_sender, update_receiver) = unbounded_channel();
+		let (close_sender, close_receiver) = channel::<()>();
+
+		thread::spawn(move || {
+			// The connection is closed when the client is dropped.
+			let direct_client = DirectClient::new(url);
+			let (sender, receiver) = channel();
+			direct_client.watch(jsonrpc_call, sender);
+
+			let subscription_id = next_message(&receiver, &close_receiver)
+				.ok_or(Error::ConnectionClosed)
+				.and_then(|response| decode_subscription_id(&response));
+			let is_subscribed = subscription_id.is_ok();
+			if id_sender.send(subscription_id).is_err() || !is_subscribed {
+				return
+			}
+
+			while let Some(message) = next_message(&receiver, &close_receiver) {
+				let update = decode_update(&message);
+				let is_last = update.as_ref().map_or(true, is_last_update);
+				if update_sender.send(update).is_err() || is_last {
+					break
+				}
+			}
+		});
+
+		let subscription_id = id_receiver.await.map_err(|_| Error::ConnectionClosed)??;
+		Ok(Subscription::new(subscription_id, update_receiver, close_sender))
+	}
+}
+
+/// Wait for the next message of a subscription, `None` once the connection has been closed or
+/// the sender of the close signal has been dropped.
+fn next_message(receiver: &Receiver<String>, close_receiver: &Receiver<()>) -> Option<String> {
+	loop {
+		match receiver.recv_timeout(CLOSE_SIGNAL_CHECK_INTERVAL) {
+			Ok(message) => return Some(message),
+			Err(RecvTimeoutError::Timeout)
+				if !matches!(close_receiver.try_recv(), Err(TryRecvError::Disconnected)) =>
+				continue,
+			Err(_) => return None,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn next_message_returns_pending_message_after_close_signal() {
+		let (sender, receiver) = channel();
+		let (close_sender, close_receiver) = channel::<()>();
+		sender.send("update".to_string()).unwrap();
+		drop(close_sender);
+
+		assert_eq!(Some("update".to_string()), next_message(&receiver, &close_receiver));
+	}
+
+	#[test]
+	fn next_message_returns_none_once_subscription_is_dropped() {
+		let (_sender, receiver) = channel::<String>();
+		let (close_sender, close_receiver) = channel::<()>();
+		drop(close_sender);
+
+		assert_eq!(None, next_message(&receiver, &close_receiver));
+	}
+
+	#[test]
+	fn next_message_returns_none_once_connection_is_closed() {
+		let (sender, receiver) = channel::<String>();
+		let (_close_sender, close_receiver) = channel::<()>();
+		drop(sender);
+
+		assert_eq!(None, next_message(&receiver, &close_receiver));
+	}
+}
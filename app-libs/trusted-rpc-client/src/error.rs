@@ -0,0 +1,42 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+use codec::Error as CodecError;
+use itc_rpc_client::error::Error as RpcClientError;
+use serde_json::Error as JsonError;
+use tokio::task::JoinError;
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	#[error("RPC client error: {0}")]
+	RpcClient(#[from] RpcClientError),
+	#[error("{0}")]
+	Codec(#[from] CodecError),
+	#[error("{0}")]
+	SerdeJson(#[from] JsonError),
+	#[error("Worker returned the following error message: {0}")]
+	Status(String),
+	#[error("Could not decode hex value: {0}")]
+	Hex(String),
+	#[error("Could not encrypt trusted operation: {0}")]
+	Encryption(String),
+	#[error("Blocking RPC task failed: {0}")]
+	Task(#[from] JoinError),
+	#[error("Connection to the worker has been closed")]
+	ConnectionClosed,
+}
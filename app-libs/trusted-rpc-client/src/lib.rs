@@ -0,0 +1,37 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Async client for the trusted RPC of a worker.
+//!
+//! Takes care of everything a client of the STF needs to interact with a worker: fetching the
+//! shielding key, managing the nonces of the signing accounts, signing and encrypting trusted
+//! calls, streaming the status of submitted calls and decoding the results of getters.
+//!
+//! The connections are handled by the blocking [`itc_rpc_client::direct_client::DirectClient`],
+//! which is driven outside of the async runtime.
+
+#![cfg_attr(test, feature(assert_matches))]
+
+pub mod client;
+pub mod error;
+pub mod nonce_cache;
+mod response;
+pub mod subscription;
+
+pub use client::TrustedRpcClient;
+pub use error::{Error, Result};
+pub use subscription::{AccountEventStream, StatusStream, Subscription};
@@ -0,0 +1,105 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Local nonce bookkeeping, so several calls of the same account can be submitted
+//! without waiting for the previous ones to be executed.
+
+use itp_stf_primitives::types::AccountId;
+use itp_types::Index;
+use std::{collections::HashMap, sync::Mutex};
+
+#[derive(Default)]
+pub struct NonceCache {
+	next_nonces: Mutex<HashMap<AccountId, Index>>,
+}
+
+impl NonceCache {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Reserve the next nonce of the account, if it is known.
+	pub fn reserve(&self, account: &AccountId) -> Option<Index> {
+		let mut next_nonces = self.next_nonces.lock().expect("Lock poisoning");
+		let next_nonce = next_nonces.get_mut(account)?;
+		let nonce = *next_nonce;
+		*next_nonce += 1;
+		Some(nonce)
+	}
+
+	/// Reserve the next nonce of the account, given the nonce fetched from the worker.
+	///
+	/// Nonces that have been reserved in the meantime are not handed out again.
+	pub fn reserve_fetched(&self, account: AccountId, fetched_nonce: Index) -> Index {
+		let mut next_nonces = self.next_nonces.lock().expect("Lock poisoning");
+		let next_nonce = next_nonces.entry(account).or_insert(fetched_nonce);
+		let nonce = fetched_nonce.max(*next_nonce);
+		*next_nonce = nonce + 1;
+		nonce
+	}
+
+	/// Forget the nonce of the account, so it is fetched from the worker again.
+	///
+	/// Has to be done whenever a call has not been accepted by the worker.
+	pub fn invalidate(&self, account: &AccountId) {
+		self.next_nonces.lock().expect("Lock poisoning").remove(account);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_keyring::AccountKeyring;
+
+	#[test]
+	fn unknown_nonce_is_not_reserved() {
+		let cache = NonceCache::new();
+		assert_eq!(cache.reserve(&AccountKeyring::Alice.to_account_id()), None);
+	}
+
+	#[test]
+	fn nonces_are_reserved_consecutively() {
+		let cache = NonceCache::new();
+		let alice = AccountKeyring::Alice.to_account_id();
+
+		assert_eq!(cache.reserve_fetched(alice.clone(), 3), 3);
+		assert_eq!(cache.reserve(&alice), Some(4));
+		assert_eq!(cache.reserve(&alice), Some(5));
+		assert_eq!(cache.reserve(&AccountKeyring::Bob.to_account_id()), None);
+	}
+
+	#[test]
+	fn outdated_fetched_nonce_does_not_hand_out_reserved_nonces_again() {
+		let cache = NonceCache::new();
+		let alice = AccountKeyring::Alice.to_account_id();
+		cache.reserve_fetched(alice.clone(), 3);
+
+		assert_eq!(cache.reserve_fetched(alice, 3), 4);
+	}
+
+	#[test]
+	fn invalidated_nonce_is_fetched_again() {
+		let cache = NonceCache::new();
+		let alice = AccountKeyring::Alice.to_account_id();
+		cache.reserve_fetched(alice.clone(), 3);
+
+		cache.invalidate(&alice);
+
+		assert_eq!(cache.reserve(&alice), None);
+		assert_eq!(cache.reserve_fetched(alice, 3), 3);
+	}
+}
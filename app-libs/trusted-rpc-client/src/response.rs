@@ -0,0 +1,150 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Decoding of the responses and subscription updates of the trusted RPC.
+
+use crate::error::{Error, Result};
+use codec::Decode;
+use itp_rpc::{RpcResponse, RpcReturnValue, RpcSubscriptionUpdate};
+use itp_types::{AccountEventUpdate, DirectRequestStatus, TrustedOperationStatus, H256};
+use itp_utils::FromHexPrefixed;
+
+/// Decode the return value of a request, mapping an error status to [`Error::Status`].
+pub(crate) fn decode_return_value(response: &str) -> Result<RpcReturnValue> {
+	let rpc_response: RpcResponse = serde_json::from_str(response)?;
+	let return_value = RpcReturnValue::from_hex(&rpc_response.result)
+		.map_err(|e| Error::Hex(format!("{:?}", e)))?;
+
+	match return_value.status {
+		DirectRequestStatus::Error =>
+			Err(Error::Status(String::decode(&mut return_value.value.as_slice())?)),
+		_ => Ok(return_value),
+	}
+}
+
+/// Decode the first response of a subscription, which is the plain subscription id.
+pub(crate) fn decode_subscription_id(response: &str) -> Result<H256> {
+	let rpc_response: RpcResponse = serde_json::from_str(response)?;
+	match H256::from_hex(&rpc_response.result) {
+		Ok(subscription_id) => Ok(subscription_id),
+		// A failed subscription is answered with an error return value instead.
+		Err(e) => match decode_return_value(response) {
+			Err(Error::Status(message)) => Err(Error::Status(message)),
+			_ => Err(Error::Hex(format!("{:?}", e))),
+		},
+	}
+}
+
+pub(crate) fn decode_status_update(update: &str) -> Result<TrustedOperationStatus> {
+	let subscription_update: RpcSubscriptionUpdate = serde_json::from_str(update)?;
+	let direct_request_status = DirectRequestStatus::from_hex(&subscription_update.params.result)
+		.map_err(|e| Error::Hex(format!("{:?}", e)))?;
+
+	match direct_request_status {
+		DirectRequestStatus::TrustedOperationStatus(status) => Ok(status),
+		DirectRequestStatus::Error =>
+			Err(Error::Status(subscription_update.params.error.unwrap_or_default())),
+		DirectRequestStatus::Ok => Err(Error::Status("Unexpected status update".into())),
+	}
+}
+
+pub(crate) fn decode_account_event_update(update: &str) -> Result<AccountEventUpdate> {
+	let subscription_update: RpcSubscriptionUpdate = serde_json::from_str(update)?;
+	AccountEventUpdate::from_hex(&subscription_update.params.result)
+		.map_err(|e| Error::Hex(format!("{:?}", e)))
+}
+
+/// The worker stops sending updates for an operation once it has one of these statuses.
+pub(crate) fn is_final_status(status: &TrustedOperationStatus) -> bool {
+	matches!(
+		status,
+		TrustedOperationStatus::Invalid
+			| TrustedOperationStatus::InSidechainBlock(_)
+			| TrustedOperationStatus::Finalized
+			| TrustedOperationStatus::Usurped
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use codec::Encode;
+	use itp_rpc::{Id, SubscriptionParams};
+	use itp_utils::ToHexPrefixed;
+	use std::assert_matches::assert_matches;
+
+	fn rpc_response(result: String) -> String {
+		serde_json::to_string(&RpcResponse { jsonrpc: "2.0".into(), result, id: Id::Number(1) })
+			.unwrap()
+	}
+
+	fn subscription_update(result: String, error: Option<String>) -> String {
+		serde_json::to_string(&RpcSubscriptionUpdate::new(
+			"author_submitAndWatchExtrinsic".into(),
+			SubscriptionParams { error, result, subscription: H256::default().to_hex() },
+		))
+		.unwrap()
+	}
+
+	#[test]
+	fn error_return_value_is_decoded_as_status_error() {
+		let response = rpc_response(RpcReturnValue::from_error_message("bad request").to_hex());
+
+		assert_matches!(decode_return_value(&response), Err(Error::Status(m)) if m == "bad request");
+	}
+
+	#[test]
+	fn return_value_is_decoded() {
+		let return_value = RpcReturnValue::new(42u32.encode(), false, DirectRequestStatus::Ok);
+		let response = rpc_response(return_value.to_hex());
+
+		assert_eq!(decode_return_value(&response).unwrap().value, 42u32.encode());
+	}
+
+	#[test]
+	fn subscription_id_is_decoded() {
+		let subscription_id = H256::from_low_u64_be(7);
+
+		assert_eq!(
+			decode_subscription_id(&rpc_response(subscription_id.to_hex())).unwrap(),
+			subscription_id
+		);
+	}
+
+	#[test]
+	fn rejected_subscription_is_decoded_as_status_error() {
+		let response = rpc_response(RpcReturnValue::from_error_message("invalid call").to_hex());
+
+		assert_matches!(decode_subscription_id(&response), Err(Error::Status(m)) if m == "invalid call");
+	}
+
+	#[test]
+	fn status_update_is_decoded() {
+		let status = DirectRequestStatus::TrustedOperationStatus(TrustedOperationStatus::Ready);
+		let update = subscription_update(status.to_hex(), None);
+
+		assert_eq!(decode_status_update(&update).unwrap(), TrustedOperationStatus::Ready);
+	}
+
+	#[test]
+	fn account_event_update_is_decoded() {
+		let event = AccountEventUpdate::RuntimeEvent(H256::from_low_u64_be(1), vec![1, 2, 3]);
+		let update = subscription_update(event.to_hex(), None);
+
+		assert_eq!(decode_account_event_update(&update).unwrap(), event);
+	}
+}
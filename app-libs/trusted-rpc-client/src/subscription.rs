@@ -0,0 +1,114 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+use crate::error::{Error, Result};
+use futures::Stream;
+use itp_types::{AccountEventUpdate, TrustedOperationStatus, H256};
+use std::{
+	pin::Pin,
+	sync::mpsc::Sender,
+	task::{Context, Poll},
+};
+use tokio::sync::mpsc::UnboundedReceiver;
+
+/// Status updates of a submitted trusted operation.
+pub type StatusStream = Subscription<TrustedOperationStatus>;
+
+/// Updates of an `author_subscribeAccountEvents` subscription.
+pub type AccountEventStream = Subscription<AccountEventUpdate>;
+
+/// Stream of the updates of a subscription, ends when the worker closes the connection.
+///
+/// Dropping the subscription closes the connection.
+pub struct Subscription<Update> {
+	id: H256,
+	receiver: UnboundedReceiver<Result<Update>>,
+	/// Signals the thread of the subscription to close the connection when dropped.
+	_close_sender: Sender<()>,
+}
+
+impl<Update> Subscription<Update> {
+	pub(crate) fn new(
+		id: H256,
+		receiver: UnboundedReceiver<Result<Update>>,
+		close_sender: Sender<()>,
+	) -> Self {
+		Subscription { id, receiver, _close_sender: close_sender }
+	}
+
+	/// Id of the subscription, the hash of the trusted operation for a [`StatusStream`].
+	pub fn id(&self) -> H256 {
+		self.id
+	}
+
+	pub async fn next_update(&mut self) -> Option<Result<Update>> {
+		self.receiver.recv().await
+	}
+}
+
+impl StatusStream {
+	/// Wait for the last status the worker sends for the operation.
+	pub async fn final_status(mut self) -> Result<TrustedOperationStatus> {
+		let mut last_status = None;
+		while let Some(status) = self.next_update().await {
+			last_status = Some(status?);
+		}
+		last_status.ok_or(Error::ConnectionClosed)
+	}
+}
+
+impl<Update> Stream for Subscription<Update> {
+	type Item = Result<Update>;
+
+	fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+		self.receiver.poll_recv(cx)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::{assert_matches::assert_matches, sync::mpsc::channel};
+	use tokio::sync::mpsc::unbounded_channel;
+
+	#[tokio::test]
+	async fn final_status_is_the_last_update() {
+		let (sender, receiver) = unbounded_channel();
+		sender.send(Ok(TrustedOperationStatus::Ready)).unwrap();
+		sender
+			.send(Ok(TrustedOperationStatus::InSidechainBlock(H256::default())))
+			.unwrap();
+		drop(sender);
+
+		let status_stream = StatusStream::new(H256::default(), receiver, channel().0);
+
+		assert_eq!(
+			status_stream.final_status().await.unwrap(),
+			TrustedOperationStatus::InSidechainBlock(H256::default())
+		);
+	}
+
+	#[tokio::test]
+	async fn final_status_without_updates_is_an_error() {
+		let (sender, receiver) = unbounded_channel();
+		drop(sender);
+
+		let status_stream = StatusStream::new(H256::default(), receiver, channel().0);
+
+		assert_matches!(status_stream.final_status().await, Err(Error::ConnectionClosed));
+	}
+}
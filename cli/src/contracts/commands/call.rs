@@ -0,0 +1,71 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+use crate::{
+	contracts::DEFAULT_GAS_LIMIT,
+	get_layer_two_nonce,
+	trusted_cli::TrustedCli,
+	trusted_command_utils::{get_accountid_from_str, get_identifiers, get_pair_from_str},
+	trusted_operation::{perform_trusted_operation, send_direct_request},
+	Cli, CliResult, CliResultOk,
+};
+use ita_stf::{contracts::ContractsTrustedCall, Getter, Index, TrustedCall, TrustedCallSigned};
+use itp_stf_primitives::{
+	traits::TrustedCallSigning,
+	types::{KeyPair, TrustedOperation},
+};
+use log::*;
+use sp_core::Pair;
+use std::boxed::Box;
+
+#[derive(Parser)]
+pub struct CallCommand {
+	/// sender's AccountId in ss58check format, mnemonic or hex seed
+	sender: String,
+	/// AccountId of the contract in ss58check format
+	contract: String,
+	/// input of the call function, hex encoded
+	#[clap(long, default_value = "")]
+	input: String,
+	#[clap(long, default_value_t = DEFAULT_GAS_LIMIT)]
+	gas_limit: u64,
+}
+
+impl CallCommand {
+	pub(crate) fn run(&self, cli: &Cli, trusted_args: &TrustedCli) -> CliResult {
+		let signer = get_pair_from_str(trusted_args, &self.sender);
+		let contract = get_accountid_from_str(&self.contract);
+		let input = array_bytes::hex2bytes(&self.input).unwrap();
+
+		println!("send trusted call call ({}, {})", signer.public(), self.contract);
+
+		let (mrenclave, shard) = get_identifiers(trusted_args);
+		let nonce = get_layer_two_nonce!(signer, cli, trusted_args);
+		let top: TrustedOperation<TrustedCallSigned, Getter> = TrustedCall::contracts(
+			ContractsTrustedCall::call(signer.public().into(), contract, input, self.gas_limit),
+		)
+		.sign(&KeyPair::Sr25519(Box::new(signer)), nonce, &mrenclave, &shard)
+		.into_trusted_operation(trusted_args.direct);
+
+		if trusted_args.direct {
+			Ok(send_direct_request(cli, trusted_args, &top).map(|_| CliResultOk::None)?)
+		} else {
+			Ok(perform_trusted_operation::<()>(cli, trusted_args, &top)
+				.map(|_| CliResultOk::None)?)
+		}
+	}
+}
@@ -0,0 +1,54 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+use crate::{
+	trusted_cli::TrustedCli,
+	trusted_command_utils::{get_accountid_from_str, get_pair_from_str},
+	trusted_operation::perform_trusted_operation,
+	Cli, CliResult, CliResultOk,
+};
+use ita_stf::{contracts::ContractsTrustedGetter, Getter, TrustedCallSigned, TrustedGetter};
+use itp_stf_primitives::types::{KeyPair, TrustedOperation};
+use sp_core::Pair;
+
+#[derive(Parser)]
+pub struct GetStorageCommand {
+	/// deployer's AccountId in ss58check format, mnemonic or hex seed
+	deployer: String,
+	/// AccountId of the contract in ss58check format
+	contract: String,
+	/// storage key, hex encoded
+	key: String,
+}
+
+impl GetStorageCommand {
+	pub(crate) fn run(&self, cli: &Cli, trusted_args: &TrustedCli) -> CliResult {
+		let who = get_pair_from_str(trusted_args, &self.deployer);
+		let top = TrustedOperation::<TrustedCallSigned, Getter>::get(Getter::trusted(
+			TrustedGetter::contracts(ContractsTrustedGetter::storage {
+				origin: who.public().into(),
+				contract: get_accountid_from_str(&self.contract),
+				key: array_bytes::hex2bytes(&self.key).unwrap(),
+			})
+			.sign(&KeyPair::Sr25519(Box::new(who))),
+		));
+		let value = perform_trusted_operation::<Vec<u8>>(cli, trusted_args, &top)?;
+		let value = array_bytes::bytes2hex("0x", value);
+		println!("{}", value);
+		Ok(CliResultOk::String { value })
+	}
+}
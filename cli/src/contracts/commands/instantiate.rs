@@ -0,0 +1,82 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+use crate::{
+	contracts::DEFAULT_GAS_LIMIT,
+	get_layer_two_nonce,
+	trusted_cli::TrustedCli,
+	trusted_command_utils::{get_identifiers, get_pair_from_str},
+	trusted_operation::{perform_trusted_operation, send_direct_request},
+	Cli, CliResult, CliResultOk,
+};
+use ita_stf::{
+	contracts::{contract_address, ContractsTrustedCall},
+	Getter, Index, TrustedCall, TrustedCallSigned,
+};
+use itp_stf_primitives::{
+	traits::TrustedCallSigning,
+	types::{AccountId, KeyPair, TrustedOperation},
+};
+use log::*;
+use sp_core::{crypto::Ss58Codec, Pair, H256};
+use std::boxed::Box;
+
+#[derive(Parser)]
+pub struct InstantiateCommand {
+	/// deployer's AccountId in ss58check format, mnemonic or hex seed
+	deployer: String,
+	/// hash of the uploaded code, hex encoded
+	code_hash: String,
+	/// input of the deploy function, hex encoded
+	#[clap(long, default_value = "")]
+	input: String,
+	/// salt to instantiate the same code more than once, hex encoded
+	#[clap(long, default_value = "")]
+	salt: String,
+	#[clap(long, default_value_t = DEFAULT_GAS_LIMIT)]
+	gas_limit: u64,
+}
+
+impl InstantiateCommand {
+	pub(crate) fn run(&self, cli: &Cli, trusted_args: &TrustedCli) -> CliResult {
+		let signer = get_pair_from_str(trusted_args, &self.deployer);
+		let deployer: AccountId = signer.public().into();
+		let code_hash = H256::from_slice(&array_bytes::hex2bytes(&self.code_hash).unwrap());
+		let input = array_bytes::hex2bytes(&self.input).unwrap();
+		let salt = array_bytes::hex2bytes(&self.salt).unwrap();
+		let contract = contract_address(&deployer, &code_hash, &salt);
+
+		println!("send trusted call instantiate ({}, {:?})", signer.public(), code_hash);
+
+		let (mrenclave, shard) = get_identifiers(trusted_args);
+		let nonce = get_layer_two_nonce!(signer, cli, trusted_args);
+		let top: TrustedOperation<TrustedCallSigned, Getter> = TrustedCall::contracts(
+			ContractsTrustedCall::instantiate(deployer, code_hash, salt, input, self.gas_limit),
+		)
+		.sign(&KeyPair::Sr25519(Box::new(signer)), nonce, &mrenclave, &shard)
+		.into_trusted_operation(trusted_args.direct);
+
+		if trusted_args.direct {
+			send_direct_request(cli, trusted_args, &top)?;
+		} else {
+			perform_trusted_operation::<()>(cli, trusted_args, &top)?;
+		}
+		info!("trusted call instantiate executed");
+		println!("{}", contract.to_ss58check());
+		Ok(CliResultOk::String { value: contract.to_ss58check() })
+	}
+}
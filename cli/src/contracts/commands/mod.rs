@@ -0,0 +1,4 @@
+pub mod call;
+pub mod get_storage;
+pub mod instantiate;
+pub mod upload_code;
@@ -0,0 +1,66 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+use crate::{
+	get_layer_two_nonce,
+	trusted_cli::TrustedCli,
+	trusted_command_utils::{get_identifiers, get_pair_from_str},
+	trusted_operation::{perform_trusted_operation, send_direct_request},
+	Cli, CliResult, CliResultOk,
+};
+use ita_stf::{contracts::ContractsTrustedCall, Getter, Index, TrustedCall, TrustedCallSigned};
+use itp_stf_primitives::{
+	traits::TrustedCallSigning,
+	types::{KeyPair, TrustedOperation},
+};
+use log::*;
+use sp_core::Pair;
+use sp_runtime::traits::{BlakeTwo256, Hash};
+use std::{boxed::Box, fs};
+
+#[derive(Parser)]
+pub struct UploadCodeCommand {
+	/// sender's AccountId in ss58check format, mnemonic or hex seed
+	sender: String,
+	/// path to the wasm file of the contract
+	path: String,
+}
+
+impl UploadCodeCommand {
+	pub(crate) fn run(&self, cli: &Cli, trusted_args: &TrustedCli) -> CliResult {
+		let signer = get_pair_from_str(trusted_args, &self.sender);
+		let code = fs::read(&self.path).expect("contract code can't be read");
+		let code_hash = BlakeTwo256::hash(&code);
+
+		println!("send trusted call upload-code ({}, {} bytes)", signer.public(), code.len());
+
+		let (mrenclave, shard) = get_identifiers(trusted_args);
+		let nonce = get_layer_two_nonce!(signer, cli, trusted_args);
+		let top: TrustedOperation<TrustedCallSigned, Getter> =
+			TrustedCall::contracts(ContractsTrustedCall::upload_code(signer.public().into(), code))
+				.sign(&KeyPair::Sr25519(Box::new(signer)), nonce, &mrenclave, &shard)
+				.into_trusted_operation(trusted_args.direct);
+
+		if trusted_args.direct {
+			send_direct_request(cli, trusted_args, &top)?;
+		} else {
+			perform_trusted_operation::<()>(cli, trusted_args, &top)?;
+		}
+		println!("{:?}", code_hash);
+		Ok(CliResultOk::H256 { hash: code_hash })
+	}
+}
@@ -0,0 +1,50 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+use crate::{trusted_cli::TrustedCli, Cli, CliResult};
+use commands::{
+	call::CallCommand, get_storage::GetStorageCommand, instantiate::InstantiateCommand,
+	upload_code::UploadCodeCommand,
+};
+
+mod commands;
+
+/// Gas limit of instantiations and calls, if not given.
+pub(crate) const DEFAULT_GAS_LIMIT: u64 = 10_000_000;
+
+#[derive(Subcommand)]
+pub enum ContractsCommand {
+	/// upload the code of a wasm contract
+	UploadCode(UploadCodeCommand),
+	/// instantiate a contract from uploaded code
+	Instantiate(InstantiateCommand),
+	/// call a contract
+	Call(CallCommand),
+	/// read a value from the storage of a contract (must be its deployer)
+	GetStorage(GetStorageCommand),
+}
+
+impl ContractsCommand {
+	pub fn run(&self, cli: &Cli, trusted_cli: &TrustedCli) -> CliResult {
+		match self {
+			ContractsCommand::UploadCode(cmd) => cmd.run(cli, trusted_cli),
+			ContractsCommand::Instantiate(cmd) => cmd.run(cli, trusted_cli),
+			ContractsCommand::Call(cmd) => cmd.run(cli, trusted_cli),
+			ContractsCommand::GetStorage(cmd) => cmd.run(cli, trusted_cli),
+		}
+	}
+}
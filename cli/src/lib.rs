@@ -32,6 +32,7 @@ mod attesteer;
 mod base_cli;
 mod benchmark;
 mod command_utils;
+mod contracts;
 #[cfg(feature = "evm")]
 mod evm;
 mod guess_the_number;
@@ -0,0 +1,60 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+use crate::{
+	trusted_cli::TrustedCli, trusted_command_utils::get_pair_from_str,
+	trusted_operation::perform_trusted_operation, Cli, CliResult, CliResultOk,
+};
+use codec::Decode;
+use ita_stf::{CallHash, Getter, Proposal, TrustedCall, TrustedCallSigned, TrustedGetter};
+use itp_stf_primitives::types::{AccountId, KeyPair, TrustedOperation};
+use sp_core::{crypto::Ss58Codec, Pair, H256};
+
+#[derive(Parser)]
+pub struct GetMultisigProposalsCommand {
+	/// signatory's AccountId in ss58check format, mnemonic or hex seed
+	account: String,
+}
+
+impl GetMultisigProposalsCommand {
+	pub(crate) fn run(&self, cli: &Cli, trusted_args: &TrustedCli) -> CliResult {
+		let who = get_pair_from_str(trusted_args, self.account.as_str());
+		let top = TrustedOperation::<TrustedCallSigned, Getter>::get(Getter::trusted(
+			TrustedGetter::multisig_proposals_for(who.public().into())
+				.sign(&KeyPair::Sr25519(Box::new(who))),
+		));
+		let proposals = perform_trusted_operation::<Vec<(AccountId, CallHash, Proposal<AccountId>)>>(
+			cli,
+			trusted_args,
+			&top,
+		)?;
+		for (multisig, call_hash, proposal) in proposals {
+			println!(
+				"multisig {} call hash {:?}: {}/{} approvals, proposed by {}",
+				multisig.to_ss58check(),
+				H256::from(call_hash),
+				proposal.approvals.len(),
+				proposal.threshold,
+				proposal.proposer.to_ss58check()
+			);
+			match TrustedCall::decode(&mut proposal.call.as_slice()) {
+				Ok(call) => println!("   {:?}", call),
+				Err(_) => println!("   <undecodable call>"),
+			}
+		}
+		Ok(CliResultOk::None)
+	}
+}
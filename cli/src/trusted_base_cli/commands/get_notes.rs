@@ -52,67 +52,79 @@ impl GetNotesCommand {
 			&top,
 		)
 		.unwrap();
-		for tnote in notes.clone() {
-			let datetime_str = format_moment(tnote.timestamp);
-			match tnote.note {
-				TrustedNote::SuccessfulTrustedCall(encoded_call) => {
-					if let Ok(call) = TrustedCall::decode(&mut encoded_call.as_slice()) {
-						match call {
-							TrustedCall::balance_transfer_with_note(from, to, amount, msg) => {
-								println!(
-									"[{}] TrustedCall::balance_transfer_with_note from: {:?}, to: {:?}, amount: {}  msg: {}",
-									datetime_str,
-									from,
-									to,
-									amount,
-									String::from_utf8_lossy(msg.as_ref())
-								);
-							},
-							TrustedCall::balance_transfer(from, to, amount) => {
-								println!(
-									"[{}] TrustedCall::balance_transfer from: {:?}, to: {:?}, amount: {}",
-									datetime_str,
-									from,
-									to,
-									amount
-								);
-							},
-							TrustedCall::balance_unshield(from, to, amount, shard) => {
-								println!(
-									"[{}] TrustedCall::balance_unshield from: {:?}, to: {:?}, amount: {}, shard: {}",
-									datetime_str,
-									from,
-									to,
-									amount,
-									shard
-								);
-							},
-							TrustedCall::balance_shield(_, to, amount, parentchain_id) => {
-								println!(
-									"[{}] TrustedCall::balance_shield from: {:?}, to: {:?}, amount: {}",
-									datetime_str,
-									parentchain_id, to, amount
-								);
-							},
-							TrustedCall::guess_the_number(GuessTheNumberTrustedCall::guess(
-								sender,
-								guess,
-							)) => {
-								println!(
-									"[{}] TrustedCall::guess_the_number::guess sender: {:?}, guess: {}",
-									datetime_str,
-									sender, guess,
-								);
-							},
-							_ => println!("[{}] {:?}", datetime_str, call),
-						}
-					} else {
-						error!("failed to decode note. check version")
+		print_notes(&notes);
+		Ok(CliResultOk::Notes { notes })
+	}
+}
+
+/// Prints the notes, decoding the trusted calls we know about.
+pub(crate) fn print_notes(notes: &[TimestampedTrustedNote<Moment>]) {
+	for tnote in notes {
+		let datetime_str = format_moment(tnote.timestamp);
+		match &tnote.note {
+			TrustedNote::SuccessfulTrustedCall(encoded_call) => {
+				if let Ok(call) = TrustedCall::decode(&mut encoded_call.as_slice()) {
+					match call {
+						TrustedCall::balance_transfer_with_note(from, to, amount, msg) => {
+							println!(
+								"[{}] TrustedCall::balance_transfer_with_note from: {:?}, to: {:?}, amount: {}  msg: {}",
+								datetime_str,
+								from,
+								to,
+								amount,
+								String::from_utf8_lossy(msg.as_ref())
+							);
+						},
+						TrustedCall::send_note(from, to, msg) => {
+							println!(
+								"[{}] TrustedCall::send_note from: {:?}, to: {:?}, msg: {}",
+								datetime_str,
+								from,
+								to,
+								String::from_utf8_lossy(msg.as_ref())
+							);
+						},
+						TrustedCall::balance_transfer(from, to, amount) => {
+							println!(
+								"[{}] TrustedCall::balance_transfer from: {:?}, to: {:?}, amount: {}",
+								datetime_str,
+								from,
+								to,
+								amount
+							);
+						},
+						TrustedCall::balance_unshield(from, to, amount, shard) => {
+							println!(
+								"[{}] TrustedCall::balance_unshield from: {:?}, to: {:?}, amount: {}, shard: {}",
+								datetime_str,
+								from,
+								to,
+								amount,
+								shard
+							);
+						},
+						TrustedCall::balance_shield(_, to, amount, parentchain_id) => {
+							println!(
+								"[{}] TrustedCall::balance_shield from: {:?}, to: {:?}, amount: {}",
+								datetime_str, parentchain_id, to, amount
+							);
+						},
+						TrustedCall::guess_the_number(GuessTheNumberTrustedCall::guess(
+							sender,
+							guess,
+						)) => {
+							println!(
+								"[{}] TrustedCall::guess_the_number::guess sender: {:?}, guess: {}",
+								datetime_str, sender, guess,
+							);
+						},
+						_ => println!("[{}] {:?}", datetime_str, call),
 					}
-				},
-				_ => println!("{:?}", tnote.note),
-			}
+				} else {
+					error!("failed to decode note. check version")
+				}
+			},
+			_ => println!("{:?}", tnote.note),
 		}
-		Ok(CliResultOk::Notes { notes })
 	}
 }
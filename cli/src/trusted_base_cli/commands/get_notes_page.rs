@@ -0,0 +1,61 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+use crate::{
+	trusted_base_cli::commands::get_notes::print_notes, trusted_cli::TrustedCli,
+	trusted_command_utils::get_pair_from_str, trusted_operation::perform_trusted_operation, Cli,
+	CliResult, CliResultOk,
+};
+use ita_stf::{Getter, TrustedCallSigned, TrustedGetter};
+use itp_stf_primitives::types::{KeyPair, TrustedOperation};
+use itp_types::Moment;
+use pallet_notes::{NoteIndex, TimestampedTrustedNote};
+use sp_core::Pair;
+
+#[derive(Parser)]
+pub struct GetNotesPageCommand {
+	/// AccountId in ss58check format, mnemonic or hex seed
+	account: String,
+
+	/// only return notes older than the note with this index
+	#[clap(long)]
+	before: Option<NoteIndex>,
+
+	/// max number of notes to return, newest first
+	#[clap(long, default_value_t = 20)]
+	max: u32,
+}
+
+impl GetNotesPageCommand {
+	pub(crate) fn run(&self, cli: &Cli, trusted_args: &TrustedCli) -> CliResult {
+		let who = get_pair_from_str(trusted_args, self.account.as_str());
+		let top = TrustedOperation::<TrustedCallSigned, Getter>::get(Getter::trusted(
+			TrustedGetter::notes_page_for(who.public().into(), self.before, self.max)
+				.sign(&KeyPair::Sr25519(Box::new(who))),
+		));
+		let page = perform_trusted_operation::<Vec<(NoteIndex, TimestampedTrustedNote<Moment>)>>(
+			cli,
+			trusted_args,
+			&top,
+		)?;
+		let (note_indices, notes): (Vec<_>, Vec<_>) = page.into_iter().unzip();
+		print_notes(&notes);
+		if let Some(last_index) = note_indices.last() {
+			println!("for older notes, pass: --before {}", last_index);
+		}
+		Ok(CliResultOk::Notes { notes })
+	}
+}
@@ -1,16 +1,27 @@
+pub mod asset_balance;
 pub mod balance;
+pub mod cancel_scheduled_call;
 pub mod get_fingerprint;
 pub mod get_header;
+pub mod get_multisig_proposals;
 pub mod get_note_buckets_info;
 pub mod get_notes;
+pub mod get_notes_page;
 pub mod get_parentchains_info;
 pub mod get_shard;
 pub mod get_shard_vault;
 pub mod get_total_issuance;
 
+pub mod multisig_account;
+pub mod multisig_cancel;
+pub mod multisig_transfer;
 pub mod nonce;
 pub mod note_bloat;
+pub mod schedule_transfer;
+pub mod send_note;
 pub mod transfer;
+pub mod transfer_asset;
+pub mod unshield_asset;
 pub mod unshield_funds;
 pub mod version;
 pub mod waste_time;
@@ -0,0 +1,43 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+use crate::{
+	trusted_cli::TrustedCli, trusted_command_utils::get_accountid_from_str, Cli, CliResult,
+	CliResultOk,
+};
+use ita_stf::multi_account_id;
+use itp_stf_primitives::types::AccountId;
+use sp_core::crypto::Ss58Codec;
+
+#[derive(Parser)]
+pub struct MultisigAccountCommand {
+	/// number of approvals needed to execute a call on behalf of the multisig account
+	threshold: u16,
+
+	/// all signatories' AccountIds in ss58check format
+	#[clap(required = true)]
+	signatories: Vec<String>,
+}
+
+impl MultisigAccountCommand {
+	pub(crate) fn run(&self, _cli: &Cli, _trusted_args: &TrustedCli) -> CliResult {
+		let signatories: Vec<AccountId> =
+			self.signatories.iter().map(|s| get_accountid_from_str(s)).collect();
+		let multisig = multi_account_id(&signatories, self.threshold).to_ss58check();
+		println!("{}", multisig);
+		Ok(CliResultOk::String { value: multisig })
+	}
+}
@@ -0,0 +1,83 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+use crate::{
+	get_layer_two_nonce,
+	trusted_cli::TrustedCli,
+	trusted_command_utils::{get_accountid_from_str, get_identifiers, get_pair_from_str},
+	trusted_operation::{perform_trusted_operation, send_direct_request},
+	Cli, CliResult, CliResultOk,
+};
+use base58::ToBase58;
+use ita_stf::{Getter, Index, TrustedCall, TrustedCallSigned};
+use itp_stf_primitives::{
+	traits::TrustedCallSigning,
+	types::{AccountId, KeyPair, TrustedOperation},
+};
+use log::*;
+use sp_core::{crypto::Ss58Codec, Pair, H256};
+use std::boxed::Box;
+
+#[derive(Parser)]
+pub struct MultisigCancelCommand {
+	/// proposer's AccountId in ss58check format, mnemonic or hex seed
+	proposer: String,
+
+	/// number of approvals needed to execute a call on behalf of the multisig account
+	threshold: u16,
+
+	/// hash of the proposed call, hex encoded
+	call_hash: H256,
+
+	/// the other signatories' AccountIds in ss58check format
+	#[clap(required = true)]
+	other_signatories: Vec<String>,
+}
+
+impl MultisigCancelCommand {
+	pub(crate) fn run(&self, cli: &Cli, trusted_args: &TrustedCli) -> CliResult {
+		let proposer = get_pair_from_str(trusted_args, &self.proposer);
+		let other_signatories: Vec<AccountId> =
+			self.other_signatories.iter().map(|s| get_accountid_from_str(s)).collect();
+		info!("proposer ss58 is {}", proposer.public().to_ss58check());
+
+		let (mrenclave, shard) = get_identifiers(trusted_args);
+		let nonce = get_layer_two_nonce!(proposer, cli, trusted_args);
+		println!(
+			"send trusted call multisig_cancel by {} for call hash {:?}, nonce: {}, signing using mrenclave: {} and shard: {}",
+			proposer.public(),
+			self.call_hash,
+			nonce,
+			mrenclave.to_base58(),
+			shard.0.to_base58()
+		);
+		let top: TrustedOperation<TrustedCallSigned, Getter> = TrustedCall::multisig_cancel(
+			proposer.public().into(),
+			other_signatories,
+			self.threshold,
+			self.call_hash.0,
+		)
+		.sign(&KeyPair::Sr25519(Box::new(proposer)), nonce, &mrenclave, &shard)
+		.into_trusted_operation(trusted_args.direct);
+
+		if trusted_args.direct {
+			Ok(send_direct_request(cli, trusted_args, &top).map(|_| CliResultOk::None)?)
+		} else {
+			Ok(perform_trusted_operation::<()>(cli, trusted_args, &top)
+				.map(|_| CliResultOk::None)?)
+		}
+	}
+}
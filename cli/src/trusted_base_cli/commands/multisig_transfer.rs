@@ -0,0 +1,97 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+use crate::{
+	get_layer_two_nonce,
+	trusted_cli::TrustedCli,
+	trusted_command_utils::{get_accountid_from_str, get_identifiers, get_pair_from_str},
+	trusted_operation::{perform_trusted_operation, send_direct_request},
+	Cli, CliResult, CliResultOk,
+};
+use base58::ToBase58;
+use codec::Encode;
+use ita_parentchain_interface::integritee::Balance;
+use ita_stf::{multi_account_id, Getter, Index, TrustedCall, TrustedCallSigned};
+use itp_stf_primitives::{
+	traits::TrustedCallSigning,
+	types::{AccountId, KeyPair, TrustedOperation},
+};
+use log::*;
+use sp_core::{blake2_256, crypto::Ss58Codec, Pair, H256};
+use std::boxed::Box;
+
+#[derive(Parser)]
+pub struct MultisigTransferCommand {
+	/// approving signatory's AccountId in ss58check format, mnemonic or hex seed
+	signatory: String,
+
+	/// number of approvals needed to execute the transfer
+	threshold: u16,
+
+	/// recipient's AccountId in ss58check format
+	to: String,
+
+	/// amount to be transferred from the multisig account
+	amount: Balance,
+
+	/// the other signatories' AccountIds in ss58check format
+	#[clap(required = true)]
+	other_signatories: Vec<String>,
+}
+
+impl MultisigTransferCommand {
+	pub(crate) fn run(&self, cli: &Cli, trusted_args: &TrustedCli) -> CliResult {
+		let signatory = get_pair_from_str(trusted_args, &self.signatory);
+		let to = get_accountid_from_str(&self.to);
+		let other_signatories: Vec<AccountId> =
+			self.other_signatories.iter().map(|s| get_accountid_from_str(s)).collect();
+		let mut signatories = other_signatories.clone();
+		signatories.push(signatory.public().into());
+		let multisig = multi_account_id(&signatories, self.threshold);
+		info!("signatory ss58 is {}", signatory.public().to_ss58check());
+		info!("multisig ss58 is {}", multisig.to_ss58check());
+
+		let transfer = TrustedCall::balance_transfer(multisig.clone(), to, self.amount);
+		let call_hash = H256::from(blake2_256(&transfer.encode()));
+		let (mrenclave, shard) = get_identifiers(trusted_args);
+		let nonce = get_layer_two_nonce!(signatory, cli, trusted_args);
+		println!(
+			"send trusted call multisig_approve by {} for transfer from {}: {}, call hash: {:?}, nonce: {}, signing using mrenclave: {} and shard: {}",
+			signatory.public(),
+			multisig,
+			self.amount,
+			call_hash,
+			nonce,
+			mrenclave.to_base58(),
+			shard.0.to_base58()
+		);
+		let top: TrustedOperation<TrustedCallSigned, Getter> = TrustedCall::multisig_approve(
+			signatory.public().into(),
+			other_signatories,
+			self.threshold,
+			Box::new(transfer),
+		)
+		.sign(&KeyPair::Sr25519(Box::new(signatory)), nonce, &mrenclave, &shard)
+		.into_trusted_operation(trusted_args.direct);
+
+		if trusted_args.direct {
+			Ok(send_direct_request(cli, trusted_args, &top).map(|_| CliResultOk::None)?)
+		} else {
+			Ok(perform_trusted_operation::<()>(cli, trusted_args, &top)
+				.map(|_| CliResultOk::None)?)
+		}
+	}
+}
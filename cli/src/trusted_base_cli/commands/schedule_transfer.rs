@@ -0,0 +1,99 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+use crate::{
+	get_layer_two_nonce,
+	trusted_cli::TrustedCli,
+	trusted_command_utils::{get_accountid_from_str, get_identifiers, get_pair_from_str},
+	trusted_operation::{perform_trusted_operation, send_direct_request},
+	Cli, CliResult, CliResultOk,
+};
+use base58::ToBase58;
+use ita_parentchain_interface::integritee::Balance;
+use ita_stf::{Getter, Index, Recurrence, TrustedCall, TrustedCallSigned};
+use itp_stf_primitives::{
+	traits::TrustedCallSigning,
+	types::{KeyPair, TrustedOperation},
+};
+use itp_types::Moment;
+use log::*;
+use sp_core::{crypto::Ss58Codec, Pair};
+use std::boxed::Box;
+
+#[derive(Parser)]
+pub struct ScheduleTransferCommand {
+	/// sender's AccountId in ss58check format, mnemonic or hex seed
+	from: String,
+
+	/// recipient's AccountId in ss58check format
+	to: String,
+
+	/// amount to be transferred
+	amount: Balance,
+
+	/// unix timestamp in milliseconds (integritee parentchain time) of the first execution
+	due: Moment,
+
+	/// repeat the transfer with this period in milliseconds
+	#[clap(long, requires = "repeat")]
+	period: Option<Moment>,
+
+	/// how many times the transfer is repeated after the first execution
+	#[clap(long, requires = "period")]
+	repeat: Option<u32>,
+}
+
+impl ScheduleTransferCommand {
+	pub(crate) fn run(&self, cli: &Cli, trusted_args: &TrustedCli) -> CliResult {
+		let from = get_pair_from_str(trusted_args, &self.from);
+		let to = get_accountid_from_str(&self.to);
+		info!("from ss58 is {}", from.public().to_ss58check());
+		info!("to ss58 is {}", to.to_ss58check());
+		let recurrence = self
+			.period
+			.zip(self.repeat)
+			.map(|(period, remaining)| Recurrence { period, remaining });
+
+		let (mrenclave, shard) = get_identifiers(trusted_args);
+		let nonce = get_layer_two_nonce!(from, cli, trusted_args);
+		println!(
+			"send trusted call schedule_call for transfer from {} to {}: {} due at {}, nonce: {}, signing using mrenclave: {} and shard: {}",
+			from.public(),
+			to,
+			self.amount,
+			self.due,
+			nonce,
+			mrenclave.to_base58(),
+			shard.0.to_base58()
+		);
+		let transfer = TrustedCall::balance_transfer(from.public().into(), to, self.amount);
+		let top: TrustedOperation<TrustedCallSigned, Getter> = TrustedCall::schedule_call(
+			from.public().into(),
+			self.due,
+			recurrence,
+			Box::new(transfer),
+		)
+		.sign(&KeyPair::Sr25519(Box::new(from)), nonce, &mrenclave, &shard)
+		.into_trusted_operation(trusted_args.direct);
+
+		if trusted_args.direct {
+			Ok(send_direct_request(cli, trusted_args, &top).map(|_| CliResultOk::None)?)
+		} else {
+			Ok(perform_trusted_operation::<()>(cli, trusted_args, &top)
+				.map(|_| CliResultOk::None)?)
+		}
+	}
+}
@@ -19,14 +19,19 @@
 use crate::trusted_base_cli::commands::set_balance::SetBalanceCommand;
 use crate::{
 	trusted_base_cli::commands::{
-		balance::BalanceCommand, get_fingerprint::GetFingerprintCommand,
-		get_header::GetSidechainHeaderCommand, get_note_buckets_info::GetNoteBucketsInfoCommand,
-		get_notes::GetNotesCommand, get_parentchains_info::GetParentchainsInfoCommand,
+		asset_balance::AssetBalanceCommand, balance::BalanceCommand,
+		cancel_scheduled_call::CancelScheduledCallCommand, get_fingerprint::GetFingerprintCommand,
+		get_header::GetSidechainHeaderCommand, get_multisig_proposals::GetMultisigProposalsCommand,
+		get_note_buckets_info::GetNoteBucketsInfoCommand, get_notes::GetNotesCommand,
+		get_notes_page::GetNotesPageCommand, get_parentchains_info::GetParentchainsInfoCommand,
 		get_shard::GetShardCommand, get_shard_vault::GetShardVaultCommand,
-		get_total_issuance::GetTotalIssuanceCommand, nonce::NonceCommand,
-		note_bloat::NoteBloatCommand, transfer::TransferCommand,
-		unshield_funds::UnshieldFundsCommand, version::VersionCommand,
-		waste_time::WasteTimeCommand,
+		get_total_issuance::GetTotalIssuanceCommand, multisig_account::MultisigAccountCommand,
+		multisig_cancel::MultisigCancelCommand, multisig_transfer::MultisigTransferCommand,
+		nonce::NonceCommand, note_bloat::NoteBloatCommand,
+		schedule_transfer::ScheduleTransferCommand, send_note::SendNoteCommand,
+		transfer::TransferCommand, transfer_asset::TransferAssetCommand,
+		unshield_asset::UnshieldAssetCommand, unshield_funds::UnshieldFundsCommand,
+		version::VersionCommand, waste_time::WasteTimeCommand,
 	},
 	trusted_cli::TrustedCli,
 	trusted_command_utils::get_keystore_path,
@@ -60,6 +65,33 @@ pub enum TrustedBaseCommand {
 	/// Transfer funds from an incognito account to an parentchain account
 	UnshieldFunds(UnshieldFundsCommand),
 
+	/// send shielded assets from one incognito account to another
+	TransferAsset(TransferAssetCommand),
+
+	/// query the balance of a shielded asset for incognito account in keystore
+	AssetBalance(AssetBalanceCommand),
+
+	/// Transfer assets from an incognito account to an parentchain account
+	UnshieldAsset(UnshieldAssetCommand),
+
+	/// schedule a transfer to be executed by the enclave at a later time, optionally repeatedly
+	ScheduleTransfer(ScheduleTransferCommand),
+
+	/// cancel a scheduled call before its next execution
+	CancelScheduledCall(CancelScheduledCallCommand),
+
+	/// derive the incognito account controlled by a threshold of signatories
+	MultisigAccount(MultisigAccountCommand),
+
+	/// approve a transfer from a multisig account. it is executed once the threshold is reached
+	MultisigTransfer(MultisigTransferCommand),
+
+	/// cancel a pending multisig proposal
+	MultisigCancel(MultisigCancelCommand),
+
+	/// get the pending multisig proposals an account is a signatory of
+	GetMultisigProposals(GetMultisigProposalsCommand),
+
 	/// gets the nonce of a given account, taking the pending trusted calls
 	/// in top pool in consideration
 	Nonce(NonceCommand),
@@ -88,6 +120,12 @@ pub enum TrustedBaseCommand {
 	/// get notes for account
 	GetNotes(GetNotesCommand),
 
+	/// get notes for account across all buckets, newest first
+	GetNotesPage(GetNotesPageCommand),
+
+	/// send a private message to one or more incognito accounts
+	SendNote(SendNoteCommand),
+
 	/// waste time for benchmarking
 	WasteTime(WasteTimeCommand),
 
@@ -108,11 +146,22 @@ impl TrustedBaseCommand {
 			TrustedBaseCommand::SetBalance(cmd) => cmd.run(cli, trusted_cli),
 			TrustedBaseCommand::Balance(cmd) => cmd.run(cli, trusted_cli),
 			TrustedBaseCommand::UnshieldFunds(cmd) => cmd.run(cli, trusted_cli),
+			TrustedBaseCommand::TransferAsset(cmd) => cmd.run(cli, trusted_cli),
+			TrustedBaseCommand::AssetBalance(cmd) => cmd.run(cli, trusted_cli),
+			TrustedBaseCommand::UnshieldAsset(cmd) => cmd.run(cli, trusted_cli),
+			TrustedBaseCommand::ScheduleTransfer(cmd) => cmd.run(cli, trusted_cli),
+			TrustedBaseCommand::CancelScheduledCall(cmd) => cmd.run(cli, trusted_cli),
+			TrustedBaseCommand::MultisigAccount(cmd) => cmd.run(cli, trusted_cli),
+			TrustedBaseCommand::MultisigTransfer(cmd) => cmd.run(cli, trusted_cli),
+			TrustedBaseCommand::MultisigCancel(cmd) => cmd.run(cli, trusted_cli),
+			TrustedBaseCommand::GetMultisigProposals(cmd) => cmd.run(cli, trusted_cli),
 			TrustedBaseCommand::Nonce(cmd) => cmd.run(cli, trusted_cli),
 			TrustedBaseCommand::GetFingerprint(cmd) => cmd.run(cli, trusted_cli),
 			TrustedBaseCommand::GetParentchainsInfo(cmd) => cmd.run(cli, trusted_cli),
 			TrustedBaseCommand::GetNoteBucketsInfo(cmd) => cmd.run(cli, trusted_cli),
 			TrustedBaseCommand::GetNotes(cmd) => cmd.run(cli, trusted_cli),
+			TrustedBaseCommand::GetNotesPage(cmd) => cmd.run(cli, trusted_cli),
+			TrustedBaseCommand::SendNote(cmd) => cmd.run(cli, trusted_cli),
 			TrustedBaseCommand::GetShard(cmd) => cmd.run(cli, trusted_cli),
 			TrustedBaseCommand::GetShardVault(cmd) => cmd.run(cli, trusted_cli),
 			TrustedBaseCommand::GetSidechainHeader(cmd) => cmd.run(cli, trusted_cli),
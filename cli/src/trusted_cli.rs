@@ -19,7 +19,10 @@ use crate::{benchmark::BenchmarkCommand, Cli, CliResult};
 
 #[cfg(feature = "evm")]
 use crate::evm::EvmCommand;
-use crate::{guess_the_number::GuessTheNumberCommand, trusted_base_cli::TrustedBaseCommand};
+use crate::{
+	contracts::ContractsCommand, guess_the_number::GuessTheNumberCommand,
+	trusted_base_cli::TrustedBaseCommand,
+};
 
 #[derive(Args)]
 pub struct TrustedCli {
@@ -56,6 +59,10 @@ pub enum TrustedCommand {
 	#[clap(subcommand)]
 	GuessTheNumber(GuessTheNumberCommand),
 
+	/// commands for confidential wasm contracts
+	#[clap(subcommand)]
+	Contracts(ContractsCommand),
+
 	/// Run Benchmark
 	Benchmark(BenchmarkCommand),
 }
@@ -68,6 +75,7 @@ impl TrustedCli {
 			#[cfg(feature = "evm")]
 			TrustedCommand::EvmCommands(cmd) => cmd.run(cli, self),
 			TrustedCommand::GuessTheNumber(cmd) => cmd.run(cli, self),
+			TrustedCommand::Contracts(cmd) => cmd.run(cli, self),
 		}
 	}
 }
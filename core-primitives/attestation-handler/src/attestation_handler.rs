@@ -75,6 +75,18 @@ pub const SIGRL_SUFFIX: &str = "/sgx/dev/attestation/v4/sigrl/";
 #[cfg(not(feature = "production"))]
 pub const REPORT_SUFFIX: &str = "/sgx/dev/attestation/v4/report";
 
+/// Report data binding a quote to the public key of a TLS certificate. The key is in the byte
+/// order it has in the certificate, see [`cert::gen_ecc_cert`].
+fn tls_report_data(pub_k: &sgx_ec256_public_t) -> sgx_report_data_t {
+	let mut report_data = sgx_report_data_t::default();
+	let (gx, gy) = report_data.d.split_at_mut(32);
+	gx.copy_from_slice(&pub_k.gx);
+	gx.reverse();
+	gy.copy_from_slice(&pub_k.gy);
+	gy.reverse();
+	report_data
+}
+
 /// Trait to provide an abstraction to the attestation logic
 pub trait AttestationHandler {
 	/// Generates an encoded remote attestation certificate. Returns DER encoded certificate.
@@ -92,6 +104,16 @@ pub trait AttestationHandler {
 		skip_ra: bool,
 	) -> EnclaveResult<(Vec<u8>, Vec<u8>, Vec<u8>)>;
 
+	/// Returns the DER encoded private key and the DER encoded certificate for a TLS session.
+	/// The report data of the DCAP quote in the certificate is the public key of the
+	/// certificate, so the peer can check that the quote was created for this session.
+	fn generate_dcap_tls_ra_cert(
+		&self,
+		quoting_enclave_target_info: Option<&sgx_target_info_t>,
+		quote_size: Option<&u32>,
+		skip_ra: bool,
+	) -> EnclaveResult<(Vec<u8>, Vec<u8>)>;
+
 	/// Get the measurement register value of the enclave
 	fn get_mrenclave(&self) -> EnclaveResult<[u8; MR_ENCLAVE_SIZE]>;
 
@@ -268,56 +290,28 @@ where
 		quote_size: Option<&u32>,
 		skip_ra: bool,
 	) -> EnclaveResult<(Vec<u8>, Vec<u8>, Vec<u8>)> {
-		if !skip_ra && quoting_enclave_target_info.is_none() && quote_size.is_none() {
-			error!("Enclave Attestation] remote attestation not skipped, but Quoting Enclave (QE) data is not available");
-			return Err(EnclaveError::Sgx(sgx_status_t::SGX_ERROR_UNEXPECTED))
-		}
 		let chain_signer = self.signing_key_repo.retrieve_key()?;
 		info!("[Enclave Attestation] Ed25519 signer pub key: {:?}", chain_signer.public().0);
+		self.generate_dcap_cert_and_quote(quoting_enclave_target_info, quote_size, skip_ra, |_| {
+			let mut report_data = sgx_report_data_t::default();
+			report_data.d[..32].clone_from_slice(&chain_signer.public().0);
+			report_data
+		})
+	}
 
-		let ecc_handle = SgxEccHandle::new();
-		let _result = ecc_handle.open();
-		let (prv_k, pub_k) = ecc_handle.create_key_pair()?;
-		info!("Enclave Attestation] Generated ephemeral ECDSA keypair:");
-		debug!("     pubkey X is {:02x}", pub_k.gx.iter().format(""));
-		debug!("     pubkey Y is {:02x}", pub_k.gy.iter().format(""));
-
-		let qe_quote = if !skip_ra {
-			let qe_quote = match self.retrieve_qe_dcap_quote(
-				&chain_signer.public().0,
-				quoting_enclave_target_info.unwrap(),
-				*quote_size.unwrap(),
-			) {
-				Ok(quote) => quote,
-				Err(e) => {
-					error!("[Enclave] Error in create_dcap_attestation_report: {:?}", e);
-					return Err(e.into())
-				},
-			};
-			qe_quote
-		} else {
-			Default::default()
-		};
-
-		let qe_quote_base_64 = base64::encode(&qe_quote[..]);
-		// generate an ECC certificate
-		debug!("[Enclave] Generate ECC Certificate");
-		let (key_der, cert_der) =
-			match cert::gen_ecc_cert(&qe_quote_base_64, &prv_k, &pub_k, &ecc_handle) {
-				Ok(r) => r,
-				Err(e) => {
-					error!("[Enclave] gen_ecc_cert failed: {:?}", e);
-					return Err(e.into())
-				},
-			};
-
-		let _ = ecc_handle.close();
-
-		debug!("[Enclave] Generated ECC cert info:");
-		trace!("[Enclave] Generated ECC cert info: key_der={:?}", &key_der);
-		trace!("[Enclave] Generated ECC cert info: cert_der={:?}", &cert_der);
-		trace!("[Enclave] Generated ECC cert info: qe_quote={:?}", &qe_quote);
-		Ok((key_der, cert_der, qe_quote))
+	fn generate_dcap_tls_ra_cert(
+		&self,
+		quoting_enclave_target_info: Option<&sgx_target_info_t>,
+		quote_size: Option<&u32>,
+		skip_ra: bool,
+	) -> EnclaveResult<(Vec<u8>, Vec<u8>)> {
+		let (key_der, cert_der, _qe_quote) = self.generate_dcap_cert_and_quote(
+			quoting_enclave_target_info,
+			quote_size,
+			skip_ra,
+			tls_report_data,
+		)?;
+		Ok((key_der, cert_der))
 	}
 }
 
@@ -768,20 +762,79 @@ where
 		Ok(())
 	}
 
+	/// Generates an ephemeral ECC key pair and a certificate with a DCAP quote, whose report data
+	/// is derived from the public key of the certificate with `report_data`.
+	fn generate_dcap_cert_and_quote<F>(
+		&self,
+		quoting_enclave_target_info: Option<&sgx_target_info_t>,
+		quote_size: Option<&u32>,
+		skip_ra: bool,
+		report_data: F,
+	) -> EnclaveResult<(Vec<u8>, Vec<u8>, Vec<u8>)>
+	where
+		F: FnOnce(&sgx_ec256_public_t) -> sgx_report_data_t,
+	{
+		if !skip_ra && quoting_enclave_target_info.is_none() && quote_size.is_none() {
+			error!("Enclave Attestation] remote attestation not skipped, but Quoting Enclave (QE) data is not available");
+			return Err(EnclaveError::Sgx(sgx_status_t::SGX_ERROR_UNEXPECTED))
+		}
+
+		let ecc_handle = SgxEccHandle::new();
+		let _result = ecc_handle.open();
+		let (prv_k, pub_k) = ecc_handle.create_key_pair()?;
+		info!("Enclave Attestation] Generated ephemeral ECDSA keypair:");
+		debug!("     pubkey X is {:02x}", pub_k.gx.iter().format(""));
+		debug!("     pubkey Y is {:02x}", pub_k.gy.iter().format(""));
+
+		let qe_quote = if !skip_ra {
+			let qe_quote = match self.retrieve_qe_dcap_quote(
+				&report_data(&pub_k),
+				quoting_enclave_target_info.unwrap(),
+				*quote_size.unwrap(),
+			) {
+				Ok(quote) => quote,
+				Err(e) => {
+					error!("[Enclave] Error in create_dcap_attestation_report: {:?}", e);
+					return Err(e.into())
+				},
+			};
+			qe_quote
+		} else {
+			Default::default()
+		};
+
+		let qe_quote_base_64 = base64::encode(&qe_quote[..]);
+		// generate an ECC certificate
+		debug!("[Enclave] Generate ECC Certificate");
+		let (key_der, cert_der) =
+			match cert::gen_ecc_cert(&qe_quote_base_64, &prv_k, &pub_k, &ecc_handle) {
+				Ok(r) => r,
+				Err(e) => {
+					error!("[Enclave] gen_ecc_cert failed: {:?}", e);
+					return Err(e.into())
+				},
+			};
+
+		let _ = ecc_handle.close();
+
+		debug!("[Enclave] Generated ECC cert info:");
+		trace!("[Enclave] Generated ECC cert info: key_der={:?}", &key_der);
+		trace!("[Enclave] Generated ECC cert info: cert_der={:?}", &cert_der);
+		trace!("[Enclave] Generated ECC cert info: qe_quote={:?}", &qe_quote);
+		Ok((key_der, cert_der, qe_quote))
+	}
+
 	pub fn retrieve_qe_dcap_quote(
 		&self,
-		pub_k: &[u8; 32],
+		report_data: &sgx_report_data_t,
 		quoting_enclave_target_info: &sgx_target_info_t,
 		quote_size: u32,
 	) -> SgxResult<Vec<u8>> {
-		// Generate app enclave report and include the enclave public key.
+		// Generate app enclave report and include the report data, usually a public key.
 		// The quote will be generated on top of this report and validate that the
 		// report as well as the public key inside it are coming from a legit
 		// intel sgx enclave.
-		let mut report_data: sgx_report_data_t = sgx_report_data_t::default();
-		report_data.d[..32].clone_from_slice(&pub_k[..]);
-
-		let app_report = match rsgx_create_report(quoting_enclave_target_info, &report_data) {
+		let app_report = match rsgx_create_report(quoting_enclave_target_info, report_data) {
 			Ok(report) => {
 				debug!(
 					"rsgx_create_report creation successful. mr_signer: {:?}",
@@ -18,7 +18,10 @@
 #[cfg(all(not(feature = "std"), feature = "sgx"))]
 use crate::sgx_reexport_prelude::*;
 
-use crate::{Error as EnclaveError, Result as EnclaveResult};
+use crate::{
+	dcap_verifier::{DcapQuoteReport, TcbStatus, VerifyDcapQuote},
+	Error as EnclaveError, Result as EnclaveResult,
+};
 use arrayvec::ArrayVec;
 use chrono::DateTime;
 use itertools::Itertools;
@@ -37,7 +40,7 @@ use std::{
 };
 
 type SignatureAlgorithms = &'static [&'static webpki::SignatureAlgorithm];
-static SUPPORTED_SIG_ALGS: SignatureAlgorithms = &[
+pub(crate) static SUPPORTED_SIG_ALGS: SignatureAlgorithms = &[
 	&webpki::ECDSA_P256_SHA256,
 	&webpki::ECDSA_P256_SHA384,
 	&webpki::ECDSA_P384_SHA256,
@@ -259,16 +262,11 @@ pub fn parse_cert_issuer(cert_der: &[u8]) -> SgxResult<Vec<u8>> {
 	Ok(pub_k)
 }
 
-// FIXME: This code is redundant with the host call of the integritee-node
-pub fn verify_mra_cert<A>(
+/// Extracts the public key and the attestation payload of an RA certificate.
+fn extract_mra_cert_payload(
 	cert_der: &[u8],
 	is_payload_base64_encoded: bool,
-	is_dcap: bool,
-	attestation_ocall: &A,
-) -> SgxResult<()>
-where
-	A: EnclaveAttestationOCallApi,
-{
+) -> SgxResult<(Vec<u8>, Vec<u8>)> {
 	// Before we reach here, Webpki already verified the cert is properly signed
 
 	// Search for Public Key prime256v1 OID
@@ -313,67 +311,144 @@ where
 		payload = base64::decode(&payload[..]).or(Err(sgx_status_t::SGX_ERROR_UNEXPECTED))?;
 	}
 	trace!("payload in mra cert verifier is: {:?}", &payload);
-	if !is_dcap {
-		// Extract each field
-		let mut iter = payload.split(|x| *x == b'|');
-		let attn_report_raw = iter.next().ok_or(sgx_status_t::SGX_ERROR_UNEXPECTED)?;
-		let sig_raw = iter.next().ok_or(sgx_status_t::SGX_ERROR_UNEXPECTED)?;
-		let sig = base64::decode(sig_raw).map_err(|e| EnclaveError::Other(e.into()))?;
-
-		let sig_cert_raw = iter.next().ok_or(sgx_status_t::SGX_ERROR_UNEXPECTED)?;
-		let sig_cert_dec = base64::decode_config(sig_cert_raw, base64::STANDARD)
-			.map_err(|e| EnclaveError::Other(e.into()))?;
-		let sig_cert = webpki::EndEntityCert::from(&sig_cert_dec).expect("Bad DER");
-
-		// Verify if the signing cert is issued by Intel CA
-		let mut ias_ca_stripped = IAS_REPORT_CA.to_vec();
-		ias_ca_stripped.retain(|&x| x != b'\r' && x != b'\n');
-		let head_len = "-----BEGIN CERTIFICATE-----".len();
-		let tail_len = "-----END CERTIFICATE-----".len();
-		let full_len = ias_ca_stripped.len();
-		let ias_ca_core: &[u8] = &ias_ca_stripped[head_len..full_len - tail_len];
-		let ias_cert_dec = base64::decode_config(ias_ca_core, base64::STANDARD)
-			.map_err(|e| EnclaveError::Other(e.into()))?;
-
-		let mut ca_reader = BufReader::new(IAS_REPORT_CA);
-
-		let mut root_store = rustls::RootCertStore::empty();
-		root_store.add_pem_file(&mut ca_reader).expect("Failed to add CA");
-
-		let trust_anchors: Vec<webpki::TrustAnchor> =
-			root_store.roots.iter().map(|cert| cert.to_trust_anchor()).collect();
-
-		let now_func = webpki::Time::try_from(SystemTime::now());
-
-		match sig_cert.verify_is_valid_tls_server_cert(
-			SUPPORTED_SIG_ALGS,
-			&webpki::TLSServerTrustAnchors(&trust_anchors),
-			&[ias_cert_dec.as_slice()],
-			now_func.map_err(|_e| EnclaveError::Time)?,
-		) {
-			Ok(_) => info!("Cert is good"),
-			Err(e) => {
-				error!("Cert verification error {:?}", e);
-				return Err(sgx_status_t::SGX_ERROR_UNEXPECTED)
-			},
-		}
+	Ok((pub_k, payload))
+}
 
-		// Verify the signature against the signing cert
-		match sig_cert.verify_signature(&webpki::RSA_PKCS1_2048_8192_SHA256, attn_report_raw, &sig)
-		{
-			Ok(_) => info!("Signature good"),
-			Err(e) => {
-				error!("Signature verification error {:?}", e);
-				return Err(sgx_status_t::SGX_ERROR_UNEXPECTED)
-			},
-		}
+// FIXME: This code is redundant with the host call of the integritee-node
+pub fn verify_mra_cert<A>(
+	cert_der: &[u8],
+	is_payload_base64_encoded: bool,
+	attestation_ocall: &A,
+) -> SgxResult<()>
+where
+	A: EnclaveAttestationOCallApi,
+{
+	let (pub_k, payload) = extract_mra_cert_payload(cert_der, is_payload_base64_encoded)?;
+
+	// Extract each field
+	let mut iter = payload.split(|x| *x == b'|');
+	let attn_report_raw = iter.next().ok_or(sgx_status_t::SGX_ERROR_UNEXPECTED)?;
+	let sig_raw = iter.next().ok_or(sgx_status_t::SGX_ERROR_UNEXPECTED)?;
+	let sig = base64::decode(sig_raw).map_err(|e| EnclaveError::Other(e.into()))?;
+
+	let sig_cert_raw = iter.next().ok_or(sgx_status_t::SGX_ERROR_UNEXPECTED)?;
+	let sig_cert_dec = base64::decode_config(sig_cert_raw, base64::STANDARD)
+		.map_err(|e| EnclaveError::Other(e.into()))?;
+	let sig_cert = webpki::EndEntityCert::from(&sig_cert_dec).expect("Bad DER");
+
+	// Verify if the signing cert is issued by Intel CA
+	let mut ias_ca_stripped = IAS_REPORT_CA.to_vec();
+	ias_ca_stripped.retain(|&x| x != b'\r' && x != b'\n');
+	let head_len = "-----BEGIN CERTIFICATE-----".len();
+	let tail_len = "-----END CERTIFICATE-----".len();
+	let full_len = ias_ca_stripped.len();
+	let ias_ca_core: &[u8] = &ias_ca_stripped[head_len..full_len - tail_len];
+	let ias_cert_dec = base64::decode_config(ias_ca_core, base64::STANDARD)
+		.map_err(|e| EnclaveError::Other(e.into()))?;
+
+	let mut ca_reader = BufReader::new(IAS_REPORT_CA);
+
+	let mut root_store = rustls::RootCertStore::empty();
+	root_store.add_pem_file(&mut ca_reader).expect("Failed to add CA");
+
+	let trust_anchors: Vec<webpki::TrustAnchor> =
+		root_store.roots.iter().map(|cert| cert.to_trust_anchor()).collect();
+
+	let now_func = webpki::Time::try_from(SystemTime::now());
+
+	match sig_cert.verify_is_valid_tls_server_cert(
+		SUPPORTED_SIG_ALGS,
+		&webpki::TLSServerTrustAnchors(&trust_anchors),
+		&[ias_cert_dec.as_slice()],
+		now_func.map_err(|_e| EnclaveError::Time)?,
+	) {
+		Ok(_) => info!("Cert is good"),
+		Err(e) => {
+			error!("Cert verification error {:?}", e);
+			return Err(sgx_status_t::SGX_ERROR_UNEXPECTED)
+		},
+	}
 
-		verify_attn_report(attn_report_raw, pub_k, attestation_ocall)
-	} else {
-		// TODO Refactor state provisioning to not use MURA #1385
-		// TODO DCAP is currently just passed through! SECURITY!!!
-		Ok(())
+	// Verify the signature against the signing cert
+	match sig_cert.verify_signature(&webpki::RSA_PKCS1_2048_8192_SHA256, attn_report_raw, &sig) {
+		Ok(_) => info!("Signature good"),
+		Err(e) => {
+			error!("Signature verification error {:?}", e);
+			return Err(sgx_status_t::SGX_ERROR_UNEXPECTED)
+		},
+	}
+
+	verify_attn_report(attn_report_raw, pub_k, attestation_ocall)
+}
+
+/// Verifies an RA certificate that carries a DCAP quote.
+///
+/// The quote has to be verified with unexpired collateral and its report data has to start with the
+/// public key of the certificate. Returns `SGX_ERROR_UPDATE_NEEDED` if the quote is valid, but the
+/// TCB of the platform is not up to date. It is up to the caller to accept that.
+pub fn verify_dcap_mra_cert<A, V>(
+	cert_der: &[u8],
+	is_payload_base64_encoded: bool,
+	quote_verifier: &V,
+	attestation_ocall: &A,
+) -> SgxResult<()>
+where
+	A: EnclaveAttestationOCallApi,
+	V: VerifyDcapQuote + ?Sized,
+{
+	let (pub_k, quote) = extract_mra_cert_payload(cert_der, is_payload_base64_encoded)?;
+
+	let report = quote_verifier.verify_dcap_quote(&quote).map_err(|e| {
+		error!("DCAP quote verification failed: {:?}", e);
+		sgx_status_t::SGX_ERROR_UNEXPECTED
+	})?;
+
+	let mr_enclave = attestation_ocall.get_mrenclave_of_self()?;
+	verify_dcap_report(&report, &pub_k, &mr_enclave.m)
+}
+
+/// Checks that the verified report of a DCAP quote is bound to the public key `pub_k` of the
+/// certificate and was created by an enclave with our `mr_enclave`.
+fn verify_dcap_report(
+	report: &DcapQuoteReport,
+	pub_k: &[u8],
+	mr_enclave: &[u8; 32],
+) -> SgxResult<()> {
+	if report.report_body.mr_enclave != *mr_enclave {
+		error!(
+			"mr_enclave is not equal to self {:?} != {:?}",
+			report.report_body.mr_enclave, mr_enclave
+		);
+		return Err(sgx_status_t::SGX_ERROR_UNEXPECTED)
+	}
+
+	debug!("dcap quote report_data = {:02x}", report.report_body.report_data.iter().format(""));
+	debug!("Anticipated public key = {:02x}", pub_k.iter().format(""));
+	let report_data = &report.report_body.report_data;
+	if pub_k.len() > report_data.len() || report_data[..pub_k.len()] != pub_k[..] {
+		error!("Report data of the DCAP quote does not match the public key of the certificate");
+		return Err(sgx_status_t::SGX_ERROR_UNEXPECTED)
 	}
+
+	match report.status() {
+		TcbStatus::UpToDate => (),
+		TcbStatus::SWHardeningNeeded =>
+			info!("TCB status of DCAP quote is SWHardeningNeeded, which is considered acceptable."),
+		TcbStatus::Revoked => {
+			error!("TCB of the platform has been revoked");
+			return Err(sgx_status_t::SGX_ERROR_UNEXPECTED)
+		},
+		status => {
+			warn!("TCB status of DCAP quote is {:?}", status);
+			return Err(sgx_status_t::SGX_ERROR_UPDATE_NEEDED)
+		},
+	}
+	if report.collateral_expired {
+		error!("DCAP quote was verified with expired collateral");
+		return Err(sgx_status_t::SGX_ERROR_UNEXPECTED)
+	}
+
+	Ok(())
 }
 
 pub fn verify_attn_report<A>(
@@ -495,3 +570,68 @@ where
 
 	Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::dcap_quote::ReportBody;
+
+	const MR_ENCLAVE: [u8; 32] = [0xab; 32];
+
+	fn report_for_public_key(pub_k: &[u8]) -> DcapQuoteReport {
+		let mut report_data = [0u8; 64];
+		report_data[..pub_k.len()].copy_from_slice(pub_k);
+		DcapQuoteReport {
+			fmspc: [0x00, 0x90, 0x6e, 0xd5, 0x00, 0x00],
+			tcb_status: TcbStatus::UpToDate,
+			qe_tcb_status: TcbStatus::UpToDate,
+			collateral_expired: false,
+			report_body: ReportBody {
+				cpu_svn: [0; 16],
+				misc_select: 0,
+				attributes: [0; 16],
+				mr_enclave: MR_ENCLAVE,
+				mr_signer: [0; 32],
+				isv_prod_id: 0,
+				isv_svn: 0,
+				report_data,
+			},
+		}
+	}
+
+	#[test]
+	fn dcap_report_bound_to_public_key_is_accepted() {
+		let pub_k = [7u8; 64];
+
+		assert!(verify_dcap_report(&report_for_public_key(&pub_k), &pub_k, &MR_ENCLAVE).is_ok());
+	}
+
+	#[test]
+	fn dcap_report_bound_to_other_public_key_is_rejected() {
+		let report = report_for_public_key(&[7u8; 64]);
+
+		let result = verify_dcap_report(&report, &[8u8; 64], &MR_ENCLAVE);
+
+		assert_eq!(result, Err(sgx_status_t::SGX_ERROR_UNEXPECTED));
+	}
+
+	#[test]
+	fn dcap_report_with_too_long_public_key_is_rejected() {
+		let report = report_for_public_key(&[7u8; 64]);
+
+		let result = verify_dcap_report(&report, &[7u8; 65], &MR_ENCLAVE);
+
+		assert_eq!(result, Err(sgx_status_t::SGX_ERROR_UNEXPECTED));
+	}
+
+	#[test]
+	fn dcap_report_verified_with_expired_collateral_is_rejected() {
+		let pub_k = [7u8; 64];
+		let mut report = report_for_public_key(&pub_k);
+		report.collateral_expired = true;
+
+		let result = verify_dcap_report(&report, &pub_k, &MR_ENCLAVE);
+
+		assert_eq!(result, Err(sgx_status_t::SGX_ERROR_UNEXPECTED));
+	}
+}
@@ -16,6 +16,7 @@
 */
 #[cfg(all(not(feature = "std"), feature = "sgx"))]
 use crate::sgx_reexport_prelude::serde_json;
+use codec::{Decode, Encode};
 use sgx_types::sgx_ql_qve_collateral_t;
 use std::{io::Write, string::String, vec::Vec};
 
@@ -23,6 +24,7 @@ use std::{io::Write, string::String, vec::Vec};
 /// See Appendix A.3 in the document
 /// "Intel® Software Guard Extensions (Intel® SGX) Data Center Attestation Primitives: ECDSA Quote Library API"
 /// https://download.01.org/intel-sgx/latest/dcap-latest/linux/docs/Intel_SGX_ECDSA_QuoteLibReference_DCAP_API.pdf
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
 pub struct SgxQlQveCollateral {
 	pub version: u32, // version = 1.  PCK Cert chain is in the Quote.
 	/* intel DCAP 1.13 */
@@ -0,0 +1,333 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Sealed cache of the DCAP collateral, such that quotes can be verified while the PCCS is
+//! unreachable.
+//!
+//! Collateral is fetched from an untrusted source, verified, and sealed per FMSPC. It is refreshed
+//! once it has passed its `nextUpdate` date. If the refresh fails, quotes of the platform are
+//! rejected until fresh collateral can be fetched, expired collateral is never used.
+
+#[cfg(feature = "sgx")]
+use std::sync::SgxRwLock as RwLock;
+
+#[cfg(feature = "std")]
+use std::sync::RwLock;
+
+use crate::{
+	dcap_quote::{DcapQuote, Fmspc, PckExtensions},
+	dcap_verifier::{DcapQuoteReport, DcapQuoteVerifier, VerifyDcapQuote},
+	Error, Result, SgxQlQveCollateral,
+};
+use codec::Decode;
+use itp_ocall_api::EnclaveAttestationOCallApi;
+use itp_sgx_io::SealedIO;
+use itp_time_utils::now_as_secs;
+use log::*;
+use std::{collections::BTreeMap, sync::Arc};
+
+#[cfg(feature = "sgx")]
+pub use sgx::*;
+
+/// File name of the sealed collateral cache.
+pub const SEALED_COLLATERAL_FILE: &str = "dcap_collateral_sealed.bin";
+
+pub type CachedCollateral = BTreeMap<Fmspc, SgxQlQveCollateral>;
+
+/// Untrusted source of the DCAP collateral, usually the PCCS.
+pub trait CollateralSource {
+	fn fetch_collateral(&self, fmspc: Fmspc) -> Result<SgxQlQveCollateral>;
+}
+
+/// Fetches the collateral with an OCall to the untrusted worker.
+pub struct OCallCollateralSource<OCallApi> {
+	ocall_api: Arc<OCallApi>,
+}
+
+impl<OCallApi> OCallCollateralSource<OCallApi> {
+	pub fn new(ocall_api: Arc<OCallApi>) -> Self {
+		OCallCollateralSource { ocall_api }
+	}
+}
+
+impl<OCallApi: EnclaveAttestationOCallApi> CollateralSource for OCallCollateralSource<OCallApi> {
+	fn fetch_collateral(&self, fmspc: Fmspc) -> Result<SgxQlQveCollateral> {
+		let encoded_collateral = self.ocall_api.get_dcap_collateral(fmspc)?;
+		Ok(SgxQlQveCollateral::decode(&mut encoded_collateral.as_slice())?)
+	}
+}
+
+/// Verifies DCAP quotes against cached collateral.
+pub struct CollateralRepository<Source, Seal> {
+	verifier: DcapQuoteVerifier,
+	source: Source,
+	seal: Seal,
+	cache: RwLock<CachedCollateral>,
+}
+
+impl<Source, Seal> CollateralRepository<Source, Seal>
+where
+	Source: CollateralSource,
+	Seal: SealedIO<Unsealed = CachedCollateral>,
+	Error: From<Seal::Error>,
+{
+	/// Loads the sealed collateral. Starts with an empty cache if there is none yet.
+	pub fn new(verifier: DcapQuoteVerifier, source: Source, seal: Seal) -> Self {
+		let cache = seal.unseal().unwrap_or_else(|e| {
+			info!("No sealed DCAP collateral found ({:?}), starting with an empty cache", e);
+			CachedCollateral::default()
+		});
+		CollateralRepository { verifier, source, seal, cache: RwLock::new(cache) }
+	}
+
+	/// Verifies `quote` at unix time `now` (seconds) against the collateral of its platform.
+	pub fn verify_quote(&self, quote: &[u8], now: u64) -> Result<DcapQuoteReport> {
+		let pck_cert = DcapQuote::parse(quote)?
+			.pck_cert_chain
+			.into_iter()
+			.next()
+			.ok_or(Error::InvalidQuote("quote does not contain a PCK certificate"))?;
+		let fmspc = PckExtensions::from_cert_der(&pck_cert)?.fmspc;
+		let collateral = self.collateral(fmspc, now)?;
+		self.verifier.verify_quote(quote, &collateral, now)
+	}
+
+	/// Returns the collateral of the platform `fmspc`.
+	///
+	/// The collateral is fetched if there is none cached, or if the cached one is outdated. If
+	/// the fetch of outdated collateral fails, [`Error::CollateralExpired`] is returned.
+	pub fn collateral(&self, fmspc: Fmspc, now: u64) -> Result<SgxQlQveCollateral> {
+		let cached = self.cache.read().map_err(|_| Error::LockPoisoning)?.get(&fmspc).cloned();
+
+		if let Some(collateral) = &cached {
+			let is_up_to_date = self
+				.verifier
+				.verify_collateral(collateral, now)
+				.map(|verified| verified.next_update() > now)
+				.unwrap_or(false);
+			if is_up_to_date {
+				return Ok(collateral.clone())
+			}
+		}
+
+		self.refresh(fmspc, now).map_err(|e| match cached {
+			Some(_) => {
+				error!(
+					"Failed to refresh the expired DCAP collateral for FMSPC {}: {:?}",
+					hex::encode(fmspc),
+					e
+				);
+				Error::CollateralExpired
+			},
+			None => e,
+		})
+	}
+
+	fn refresh(&self, fmspc: Fmspc, now: u64) -> Result<SgxQlQveCollateral> {
+		let collateral = self.source.fetch_collateral(fmspc)?;
+		if self.verifier.verify_collateral(&collateral, now)?.fmspc() != fmspc {
+			return Err(Error::InvalidCollateral("collateral is for another platform"))
+		}
+
+		let mut cache = self.cache.write().map_err(|_| Error::LockPoisoning)?;
+		cache.insert(fmspc, collateral.clone());
+		// The fetched collateral is valid regardless of whether we manage to seal it.
+		if let Err(e) = self.seal.seal(&cache) {
+			error!("Failed to seal DCAP collateral: {:?}", Error::from(e));
+		}
+		info!("Refreshed DCAP collateral for FMSPC {}", hex::encode(fmspc));
+		Ok(collateral)
+	}
+}
+
+impl<Source, Seal> VerifyDcapQuote for CollateralRepository<Source, Seal>
+where
+	Source: CollateralSource,
+	Seal: SealedIO<Unsealed = CachedCollateral>,
+	Error: From<Seal::Error>,
+{
+	fn verify_dcap_quote(&self, quote: &[u8]) -> Result<DcapQuoteReport> {
+		self.verify_quote(quote, now_as_secs())
+	}
+}
+
+#[cfg(feature = "sgx")]
+pub mod sgx {
+	use super::{CachedCollateral, SEALED_COLLATERAL_FILE};
+	use crate::{Error, Result};
+	use codec::{Decode, Encode};
+	use itp_sgx_io::{seal, unseal, SealedIO};
+	use std::path::PathBuf;
+
+	#[derive(Clone, Debug)]
+	pub struct CollateralSeal {
+		base_path: PathBuf,
+	}
+
+	impl CollateralSeal {
+		pub fn new(base_path: PathBuf) -> Self {
+			Self { base_path }
+		}
+
+		pub fn path(&self) -> PathBuf {
+			self.base_path.join(SEALED_COLLATERAL_FILE)
+		}
+	}
+
+	impl SealedIO for CollateralSeal {
+		type Error = Error;
+		type Unsealed = CachedCollateral;
+
+		fn unseal(&self) -> Result<Self::Unsealed> {
+			Ok(unseal(self.path()).map(|b| Decode::decode(&mut b.as_slice()))??)
+		}
+
+		fn seal(&self, unsealed: &Self::Unsealed) -> Result<()> {
+			Ok(unsealed.using_encoded(|bytes| seal(bytes, self.path()))?)
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::dcap_verifier::{
+		tests::{
+			test_collateral, test_verifier, AFTER_NEXT_UPDATE, NOW, QUOTE_OUT_OF_DATE,
+			QUOTE_UP_TO_DATE,
+		},
+		TcbStatus,
+	};
+	use std::{
+		io::ErrorKind,
+		sync::atomic::{AtomicUsize, Ordering},
+	};
+
+	const FMSPC: Fmspc = [0x00, 0x90, 0x6e, 0xd5, 0x00, 0x00];
+
+	#[derive(Default)]
+	struct CollateralSourceMock {
+		unreachable: bool,
+		fetched: AtomicUsize,
+	}
+
+	impl CollateralSource for CollateralSourceMock {
+		fn fetch_collateral(&self, _fmspc: Fmspc) -> Result<SgxQlQveCollateral> {
+			self.fetched.fetch_add(1, Ordering::SeqCst);
+			if self.unreachable {
+				return Err(Error::IoError(ErrorKind::ConnectionRefused.into()))
+			}
+			Ok(test_collateral())
+		}
+	}
+
+	/// Seals the collateral in memory. Clones share the sealed collateral.
+	#[derive(Clone, Default)]
+	struct CollateralSealMock {
+		sealed: Arc<RwLock<Option<CachedCollateral>>>,
+	}
+
+	impl SealedIO for CollateralSealMock {
+		type Error = Error;
+		type Unsealed = CachedCollateral;
+
+		fn unseal(&self) -> Result<Self::Unsealed> {
+			self.sealed
+				.read()
+				.unwrap()
+				.clone()
+				.ok_or_else(|| Error::IoError(ErrorKind::NotFound.into()))
+		}
+
+		fn seal(&self, unsealed: &Self::Unsealed) -> Result<()> {
+			*self.sealed.write().unwrap() = Some(unsealed.clone());
+			Ok(())
+		}
+	}
+
+	fn repository(
+		source: CollateralSourceMock,
+		seal: CollateralSealMock,
+	) -> CollateralRepository<CollateralSourceMock, CollateralSealMock> {
+		CollateralRepository::new(test_verifier(), source, seal)
+	}
+
+	fn unreachable_source() -> CollateralSourceMock {
+		CollateralSourceMock { unreachable: true, ..Default::default() }
+	}
+
+	#[test]
+	fn collateral_is_fetched_once_and_sealed() {
+		let seal = CollateralSealMock::default();
+		let repository = repository(CollateralSourceMock::default(), seal.clone());
+
+		let report = repository.verify_quote(QUOTE_UP_TO_DATE, NOW).unwrap();
+		repository.verify_quote(QUOTE_OUT_OF_DATE, NOW).unwrap();
+
+		assert_eq!(report.status(), TcbStatus::UpToDate);
+		assert_eq!(repository.source.fetched.load(Ordering::SeqCst), 1);
+		assert_eq!(seal.unseal().unwrap().get(&FMSPC), Some(&test_collateral()));
+	}
+
+	#[test]
+	fn sealed_collateral_is_used_after_restart() {
+		let seal = CollateralSealMock::default();
+		repository(CollateralSourceMock::default(), seal.clone())
+			.verify_quote(QUOTE_UP_TO_DATE, NOW)
+			.unwrap();
+
+		let restarted = repository(unreachable_source(), seal);
+		let report = restarted.verify_quote(QUOTE_UP_TO_DATE, NOW).unwrap();
+
+		assert!(!report.collateral_expired);
+		assert_eq!(restarted.source.fetched.load(Ordering::SeqCst), 0);
+	}
+
+	#[test]
+	fn outdated_collateral_is_refreshed() {
+		let seal = CollateralSealMock::default();
+		repository(CollateralSourceMock::default(), seal.clone())
+			.collateral(FMSPC, NOW)
+			.unwrap();
+
+		let repository = repository(CollateralSourceMock::default(), seal);
+		repository.collateral(FMSPC, AFTER_NEXT_UPDATE).unwrap();
+
+		assert_eq!(repository.source.fetched.load(Ordering::SeqCst), 1);
+	}
+
+	#[test]
+	fn outdated_collateral_is_rejected_if_refresh_fails() {
+		let seal = CollateralSealMock::default();
+		repository(CollateralSourceMock::default(), seal.clone())
+			.collateral(FMSPC, NOW)
+			.unwrap();
+
+		let repository = repository(unreachable_source(), seal);
+		let result = repository.verify_quote(QUOTE_UP_TO_DATE, AFTER_NEXT_UPDATE);
+
+		assert!(matches!(result, Err(Error::CollateralExpired)));
+		assert_eq!(repository.source.fetched.load(Ordering::SeqCst), 1);
+	}
+
+	#[test]
+	fn verification_fails_without_collateral() {
+		let repository = repository(unreachable_source(), CollateralSealMock::default());
+
+		assert!(matches!(repository.verify_quote(QUOTE_UP_TO_DATE, NOW), Err(Error::IoError(_))));
+	}
+}
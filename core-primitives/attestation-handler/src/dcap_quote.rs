@@ -0,0 +1,352 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Parsing of ECDSA (DCAP) quotes and of the SGX extensions of PCK certificates.
+//!
+//! See the document "Intel® Software Guard Extensions (Intel® SGX) Data Center Attestation
+//! Primitives: ECDSA Quote Library API", Appendix A.4, for the quote layout and the
+//! "Intel® SGX PCK Certificate and Certificate Revocation List Profile Specification"
+//! for the PCK certificate extensions.
+
+use crate::{
+	der::{
+		expect_tlv, parse_small_integer, pem_certificate_chain_to_der, TAG_INTEGER,
+		TAG_OCTET_STRING, TAG_OID, TAG_SEQUENCE,
+	},
+	Error, Result,
+};
+use core::convert::{TryFrom, TryInto};
+use std::vec::Vec;
+
+/// Family-Model-Stepping-Platform-CustomSKU of a platform, used to look up its TCB info.
+pub type Fmspc = [u8; 6];
+
+pub const QUOTE_HEADER_SIZE: usize = 48;
+pub const REPORT_BODY_SIZE: usize = 384;
+const ECDSA_SIGNATURE_SIZE: usize = 64;
+const ECDSA_PUBLIC_KEY_SIZE: usize = 64;
+
+const QUOTE_VERSION_3: u16 = 3;
+const ATTESTATION_KEY_TYPE_ECDSA_P256: u16 = 2;
+const TEE_TYPE_SGX: u32 = 0;
+const CERTIFICATION_DATA_PCK_CERT_CHAIN: u16 = 5;
+
+/// DER encoded OID 1.2.840.113741.1.13.1 of the SGX extensions in the PCK certificate.
+const SGX_EXTENSIONS_OID: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf8, 0x4d, 0x01, 0x0d, 0x01];
+/// Number of the SGX TCB components, i.e. the CPUSVN bytes.
+pub const TCB_COMPONENTS: usize = 16;
+// Arcs below `SGX_EXTENSIONS_OID`.
+const SGX_TCB: u8 = 2;
+const SGX_PCE_ID: u8 = 3;
+const SGX_FMSPC: u8 = 4;
+// Arcs below the SGX TCB OID.
+const TCB_PCESVN: u8 = 17;
+
+/// The fields of an SGX report body the verification relies on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReportBody {
+	pub cpu_svn: [u8; 16],
+	pub misc_select: u32,
+	pub attributes: [u8; 16],
+	pub mr_enclave: [u8; 32],
+	pub mr_signer: [u8; 32],
+	pub isv_prod_id: u16,
+	pub isv_svn: u16,
+	pub report_data: [u8; 64],
+}
+
+impl ReportBody {
+	pub fn parse(report_body: &[u8]) -> Result<Self> {
+		if report_body.len() != REPORT_BODY_SIZE {
+			return Err(Error::InvalidQuote("report body has an invalid size"))
+		}
+		let mut reader = Reader::new(report_body);
+		let cpu_svn = reader.array()?;
+		let misc_select = reader.u32()?;
+		reader.skip(12 + 16)?; // reserved, isv_ext_prod_id
+		let attributes = reader.array()?;
+		let mr_enclave = reader.array()?;
+		reader.skip(32)?; // reserved
+		let mr_signer = reader.array()?;
+		reader.skip(32 + 64)?; // reserved, config_id
+		let isv_prod_id = reader.u16()?;
+		let isv_svn = reader.u16()?;
+		reader.skip(2 + 42 + 16)?; // config_svn, reserved, isv_family_id
+		let report_data = reader.array()?;
+
+		Ok(ReportBody {
+			cpu_svn,
+			misc_select,
+			attributes,
+			mr_enclave,
+			mr_signer,
+			isv_prod_id,
+			isv_svn,
+			report_data,
+		})
+	}
+}
+
+/// An ECDSA-256 quote of version 3, as produced by the Intel quoting enclave.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DcapQuote {
+	/// Quote header and ISV enclave report body, i.e. the data signed by the attestation key.
+	pub signed_data: Vec<u8>,
+	pub report_body: ReportBody,
+	/// Signature of `signed_data`, as raw `r || s`.
+	pub signature: [u8; ECDSA_SIGNATURE_SIZE],
+	/// Attestation public key of the quoting enclave, as raw `x || y`.
+	pub attestation_key: [u8; ECDSA_PUBLIC_KEY_SIZE],
+	/// Raw report body of the quoting enclave, signed by the PCK.
+	pub qe_report_raw: Vec<u8>,
+	pub qe_report: ReportBody,
+	/// Signature of `qe_report_raw`, as raw `r || s`.
+	pub qe_report_signature: [u8; ECDSA_SIGNATURE_SIZE],
+	pub qe_authentication_data: Vec<u8>,
+	/// DER encoded PCK certificate chain, leaf first.
+	pub pck_cert_chain: Vec<Vec<u8>>,
+}
+
+impl DcapQuote {
+	pub fn parse(quote: &[u8]) -> Result<Self> {
+		let mut reader = Reader::new(quote);
+		let version = reader.u16()?;
+		let attestation_key_type = reader.u16()?;
+		let tee_type = reader.u32()?;
+		if version != QUOTE_VERSION_3 {
+			return Err(Error::InvalidQuote("unsupported quote version"))
+		}
+		if attestation_key_type != ATTESTATION_KEY_TYPE_ECDSA_P256 {
+			return Err(Error::InvalidQuote("unsupported attestation key type"))
+		}
+		if tee_type != TEE_TYPE_SGX {
+			return Err(Error::InvalidQuote("unsupported TEE type"))
+		}
+		reader.skip(QUOTE_HEADER_SIZE - 8)?;
+		let report_body = ReportBody::parse(reader.take(REPORT_BODY_SIZE)?)?;
+		let signed_data = quote[..QUOTE_HEADER_SIZE + REPORT_BODY_SIZE].to_vec();
+
+		let signature_data_len = reader.u32()? as usize;
+		let mut reader = Reader::new(reader.take(signature_data_len)?);
+		let signature = reader.array()?;
+		let attestation_key = reader.array()?;
+		let qe_report_raw = reader.take(REPORT_BODY_SIZE)?.to_vec();
+		let qe_report = ReportBody::parse(&qe_report_raw)?;
+		let qe_report_signature = reader.array()?;
+		let qe_authentication_data_len = reader.u16()? as usize;
+		let qe_authentication_data = reader.take(qe_authentication_data_len)?.to_vec();
+
+		let certification_data_type = reader.u16()?;
+		if certification_data_type != CERTIFICATION_DATA_PCK_CERT_CHAIN {
+			return Err(Error::InvalidQuote("quote does not contain the PCK certificate chain"))
+		}
+		let certification_data_len = reader.u32()? as usize;
+		let pck_cert_chain = pem_certificate_chain_to_der(reader.take(certification_data_len)?)?;
+
+		Ok(DcapQuote {
+			signed_data,
+			report_body,
+			signature,
+			attestation_key,
+			qe_report_raw,
+			qe_report,
+			qe_report_signature,
+			qe_authentication_data,
+			pck_cert_chain,
+		})
+	}
+}
+
+/// Platform information the PCK certificate attests to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PckExtensions {
+	pub fmspc: Fmspc,
+	pub pce_id: [u8; 2],
+	pub tcb_components: [u8; TCB_COMPONENTS],
+	pub pce_svn: u16,
+}
+
+impl PckExtensions {
+	pub fn from_cert_der(cert_der: &[u8]) -> Result<Self> {
+		let extensions = sgx_extensions(cert_der)?;
+
+		let mut fmspc = None;
+		let mut pce_id = None;
+		let mut tcb = None;
+		for (arc, value) in sgx_extension_entries(extensions)? {
+			match arc {
+				SGX_FMSPC => fmspc = Some(octet_string(value)?),
+				SGX_PCE_ID => pce_id = Some(octet_string(value)?),
+				SGX_TCB => tcb = Some(value),
+				_ => {},
+			}
+		}
+
+		let (tcb, _) = expect_tlv(tcb.ok_or(Error::InvalidPckCertificate)?, TAG_SEQUENCE)?;
+		let mut tcb_components = [0u8; TCB_COMPONENTS];
+		let mut pce_svn = None;
+		for (arc, value) in sgx_extension_entries(tcb.value)? {
+			match arc {
+				1..=16 => tcb_components[arc as usize - 1] = svn(value)?,
+				TCB_PCESVN => pce_svn = Some(svn(value)?),
+				_ => {}, // CPUSVN, which is redundant to the components
+			}
+		}
+
+		Ok(PckExtensions {
+			fmspc: fmspc.ok_or(Error::InvalidPckCertificate)?,
+			pce_id: pce_id.ok_or(Error::InvalidPckCertificate)?,
+			tcb_components,
+			pce_svn: pce_svn.ok_or(Error::InvalidPckCertificate)?,
+		})
+	}
+}
+
+/// Returns the content of the SGX extensions `SEQUENCE` of a PCK certificate.
+fn sgx_extensions(cert_der: &[u8]) -> Result<&[u8]> {
+	let mut oid = vec![TAG_OID, SGX_EXTENSIONS_OID.len() as u8];
+	oid.extend_from_slice(SGX_EXTENSIONS_OID);
+
+	// Same approach as for the public key in `cert::verify_mra_cert`: search for the OID.
+	let offset = cert_der
+		.windows(oid.len())
+		.position(|window| window == oid.as_slice())
+		.ok_or(Error::InvalidPckCertificate)?;
+	let (extension_value, _) = expect_tlv(&cert_der[offset + oid.len()..], TAG_OCTET_STRING)?;
+	let (extensions, _) = expect_tlv(extension_value.value, TAG_SEQUENCE)?;
+	Ok(extensions.value)
+}
+
+/// Splits a sequence of `SEQUENCE { OID, value }` entries below the SGX extensions OID
+/// into the last arc of the OID and the DER encoded value.
+fn sgx_extension_entries(mut entries: &[u8]) -> Result<Vec<(u8, &[u8])>> {
+	let mut result = Vec::new();
+	while !entries.is_empty() {
+		let (entry, rest) = expect_tlv(entries, TAG_SEQUENCE)?;
+		let (oid, value) = expect_tlv(entry.value, TAG_OID)?;
+		if !oid.value.starts_with(SGX_EXTENSIONS_OID) {
+			return Err(Error::InvalidPckCertificate)
+		}
+		let arc = *oid.value.last().ok_or(Error::InvalidPckCertificate)?;
+		result.push((arc, value));
+		entries = rest;
+	}
+	Ok(result)
+}
+
+fn svn<T: TryFrom<u64>>(value: &[u8]) -> Result<T> {
+	let (value, _) = expect_tlv(value, TAG_INTEGER)?;
+	parse_small_integer(value.value)?
+		.try_into()
+		.map_err(|_| Error::InvalidPckCertificate)
+}
+
+fn octet_string<const N: usize>(value: &[u8]) -> Result<[u8; N]> {
+	let (value, _) = expect_tlv(value, TAG_OCTET_STRING)?;
+	value.value.try_into().map_err(|_| Error::InvalidPckCertificate)
+}
+
+/// Little endian reader over the fixed size quote structures.
+struct Reader<'a> {
+	data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+	fn new(data: &'a [u8]) -> Self {
+		Reader { data }
+	}
+
+	fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+		if self.data.len() < len {
+			return Err(Error::InvalidQuote("quote is truncated"))
+		}
+		let (taken, rest) = self.data.split_at(len);
+		self.data = rest;
+		Ok(taken)
+	}
+
+	fn skip(&mut self, len: usize) -> Result<()> {
+		self.take(len).map(|_| ())
+	}
+
+	fn array<const N: usize>(&mut self) -> Result<[u8; N]> {
+		// `take` guarantees the length.
+		Ok(self.take(N)?.try_into().expect("slice has length N; qed"))
+	}
+
+	fn u16(&mut self) -> Result<u16> {
+		Ok(u16::from_le_bytes(self.array()?))
+	}
+
+	fn u32(&mut self) -> Result<u32> {
+		Ok(u32::from_le_bytes(self.array()?))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const QUOTE_UP_TO_DATE: &[u8] = include_bytes!("fixtures/dcap/quote_up_to_date.bin");
+	const TEST_ROOT_CA: &[u8] = include_bytes!("fixtures/dcap/test_root_ca.der");
+
+	#[test]
+	fn quote_is_parsed() {
+		let quote = DcapQuote::parse(QUOTE_UP_TO_DATE).unwrap();
+
+		assert_eq!(quote.report_body.mr_enclave, [0xab; 32]);
+		assert_eq!(quote.report_body.mr_signer, [0xcd; 32]);
+		assert_eq!(quote.qe_report.isv_prod_id, 1);
+		assert_eq!(quote.qe_report.isv_svn, 8);
+		assert_eq!(quote.qe_authentication_data.len(), 32);
+		assert_eq!(quote.pck_cert_chain.len(), 3);
+		assert_eq!(quote.pck_cert_chain[2], TEST_ROOT_CA);
+	}
+
+	#[test]
+	fn truncated_quote_is_rejected() {
+		let truncated = &QUOTE_UP_TO_DATE[..QUOTE_UP_TO_DATE.len() - 10];
+		assert!(matches!(DcapQuote::parse(truncated), Err(Error::InvalidQuote(_))));
+	}
+
+	#[test]
+	fn quote_with_wrong_version_is_rejected() {
+		let mut quote = QUOTE_UP_TO_DATE.to_vec();
+		quote[0] = 2;
+		assert!(matches!(DcapQuote::parse(&quote), Err(Error::InvalidQuote(_))));
+	}
+
+	#[test]
+	fn pck_extensions_are_parsed() {
+		let quote = DcapQuote::parse(QUOTE_UP_TO_DATE).unwrap();
+
+		let extensions = PckExtensions::from_cert_der(&quote.pck_cert_chain[0]).unwrap();
+
+		assert_eq!(extensions.fmspc, [0x00, 0x90, 0x6e, 0xd5, 0x00, 0x00]);
+		assert_eq!(extensions.pce_id, [0, 0]);
+		assert_eq!(extensions.tcb_components, [5; TCB_COMPONENTS]);
+		assert_eq!(extensions.pce_svn, 13);
+	}
+
+	#[test]
+	fn certificate_without_sgx_extensions_is_rejected() {
+		assert!(matches!(
+			PckExtensions::from_cert_der(TEST_ROOT_CA),
+			Err(Error::InvalidPckCertificate)
+		));
+	}
+}
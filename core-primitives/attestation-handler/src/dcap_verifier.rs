@@ -0,0 +1,782 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Verification of DCAP quotes inside the enclave, without the quote verification enclave (QvE).
+//!
+//! The quote is verified against the TCB info and QE identity collateral issued by Intel. The
+//! collateral itself is verified against the Intel SGX root CA, so it may be delivered by an
+//! untrusted source and cached across restarts.
+//!
+//! Like Intel's quote verification library, the certificates are checked against the profiles
+//! Intel issues them with: the signer of the collateral must be the TCB signing certificate and
+//! the quote must come with a PCK certificate issued by a PCK CA.
+
+#[cfg(all(not(feature = "std"), feature = "sgx"))]
+use crate::sgx_reexport_prelude::*;
+
+use crate::{
+	dcap_quote::{DcapQuote, Fmspc, PckExtensions, ReportBody, TCB_COMPONENTS},
+	der::{
+		crl_to_der, pem_certificate_chain_to_der, trim_null_terminator, Certificate, Crl,
+		KEY_USAGE_DIGITAL_SIGNATURE, KEY_USAGE_KEY_CERT_SIGN, KEY_USAGE_NON_REPUDIATION,
+	},
+	Error, Result, SgxQlQveCollateral,
+};
+use chrono::DateTime;
+use codec::{Decode, Encode};
+use core::convert::TryInto;
+use ring::{
+	digest::{digest, SHA256},
+	signature::{UnparsedPublicKey, ECDSA_P256_SHA256_ASN1, ECDSA_P256_SHA256_FIXED},
+};
+use serde_json::Value;
+use std::vec::Vec;
+
+/// Intel SGX root CA, which is the trust anchor of the PCK certificates and the collateral.
+pub const INTEL_SGX_ROOT_CA: &[u8] = include_bytes!("../IntelSGXRootCA.pem");
+
+/// Common names of the certificates Intel issues for DCAP attestation.
+pub const INTEL_CERTIFICATE_NAMES: DcapCertificateNames = DcapCertificateNames {
+	tcb_signing: "Intel SGX TCB Signing",
+	pck_cas: &["Intel SGX PCK Platform CA", "Intel SGX PCK Processor CA"],
+	pck: "Intel SGX PCK Certificate",
+};
+
+const TCB_INFO_ID_SGX: &str = "SGX";
+const QE_IDENTITY_ID_QE: &str = "QE";
+
+/// TCB level status, ordered from best to worst.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Encode, Decode)]
+pub enum TcbStatus {
+	UpToDate,
+	SWHardeningNeeded,
+	ConfigurationNeeded,
+	ConfigurationAndSWHardeningNeeded,
+	OutOfDate,
+	OutOfDateConfigurationNeeded,
+	Revoked,
+}
+
+impl TcbStatus {
+	fn from_collateral(status: &str) -> Result<Self> {
+		match status {
+			"UpToDate" => Ok(TcbStatus::UpToDate),
+			"SWHardeningNeeded" => Ok(TcbStatus::SWHardeningNeeded),
+			"ConfigurationNeeded" => Ok(TcbStatus::ConfigurationNeeded),
+			"ConfigurationAndSWHardeningNeeded" => Ok(TcbStatus::ConfigurationAndSWHardeningNeeded),
+			"OutOfDate" => Ok(TcbStatus::OutOfDate),
+			"OutOfDateConfigurationNeeded" => Ok(TcbStatus::OutOfDateConfigurationNeeded),
+			"Revoked" => Ok(TcbStatus::Revoked),
+			_ => Err(Error::InvalidCollateral("unknown TCB status")),
+		}
+	}
+}
+
+/// Result of a successful quote verification.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DcapQuoteReport {
+	pub fmspc: Fmspc,
+	/// Status of the platform TCB, according to the TCB info.
+	pub tcb_status: TcbStatus,
+	/// Status of the quoting enclave, according to the QE identity.
+	pub qe_tcb_status: TcbStatus,
+	/// The collateral has passed its `nextUpdate` date. Such a quote must not be trusted.
+	pub collateral_expired: bool,
+	/// Report body of the attested enclave.
+	pub report_body: ReportBody,
+}
+
+impl DcapQuoteReport {
+	/// The worse of the platform and the quoting enclave TCB status.
+	pub fn status(&self) -> TcbStatus {
+		self.tcb_status.max(self.qe_tcb_status)
+	}
+}
+
+/// Verifies DCAP quotes at the current time.
+pub trait VerifyDcapQuote {
+	fn verify_dcap_quote(&self, quote: &[u8]) -> Result<DcapQuoteReport>;
+}
+
+/// Collateral whose signatures have been verified against the root CA.
+#[derive(Clone, Debug)]
+pub struct VerifiedCollateral {
+	tcb_info: TcbInfo,
+	qe_identity: QeIdentity,
+	pck_crl_issuer: Vec<u8>,
+	revoked_pck_serials: Vec<Vec<u8>>,
+}
+
+impl VerifiedCollateral {
+	pub fn fmspc(&self) -> Fmspc {
+		self.tcb_info.fmspc
+	}
+
+	/// Unix timestamp (seconds) at which new collateral will be issued.
+	pub fn next_update(&self) -> u64 {
+		self.tcb_info.next_update.min(self.qe_identity.next_update)
+	}
+}
+
+/// Common names of the certificates below the root CA.
+#[derive(Clone, Debug)]
+pub struct DcapCertificateNames {
+	/// Signer of the TCB info and the QE identity, issued by the root CA.
+	pub tcb_signing: &'static str,
+	/// Issuers of the PCK certificates, issued by the root CA.
+	pub pck_cas: &'static [&'static str],
+	pub pck: &'static str,
+}
+
+/// Verifies DCAP quotes and their collateral against a root CA.
+#[derive(Clone, Debug)]
+pub struct DcapQuoteVerifier {
+	/// DER encoded root CA certificate.
+	root_ca: Vec<u8>,
+	certificate_names: DcapCertificateNames,
+}
+
+impl DcapQuoteVerifier {
+	/// Verifier for certificates with the [`INTEL_CERTIFICATE_NAMES`].
+	pub fn new(root_ca_der: Vec<u8>) -> Self {
+		DcapQuoteVerifier { root_ca: root_ca_der, certificate_names: INTEL_CERTIFICATE_NAMES }
+	}
+
+	pub fn with_certificate_names(mut self, certificate_names: DcapCertificateNames) -> Self {
+		self.certificate_names = certificate_names;
+		self
+	}
+
+	/// Verifier with the Intel SGX root CA as trust anchor.
+	pub fn with_intel_root_ca() -> Result<Self> {
+		let root_ca = pem_certificate_chain_to_der(INTEL_SGX_ROOT_CA)?
+			.into_iter()
+			.next()
+			.ok_or(Error::MalformedPem)?;
+		Ok(Self::new(root_ca))
+	}
+
+	/// Verifies the signatures and certificate chains of `collateral` at unix time `now` (seconds).
+	///
+	/// Collateral that has passed its `nextUpdate` date is still accepted. Use
+	/// [`VerifiedCollateral::next_update`] to decide whether it should be refreshed.
+	pub fn verify_collateral(
+		&self,
+		collateral: &SgxQlQveCollateral,
+		now: u64,
+	) -> Result<VerifiedCollateral> {
+		let root_ca = Certificate::parse(&self.root_ca)?;
+
+		let root_ca_crl_der = crl_to_der(&collateral.root_ca_crl)?;
+		let root_ca_crl = Crl::parse(&root_ca_crl_der)?;
+		verify_der_signature(root_ca.public_key, root_ca_crl.tbs, root_ca_crl.signature)
+			.map_err(|_| Error::InvalidCollateral("root CA CRL signature is invalid"))?;
+
+		// The PCK CRL is issued by the PCK platform (or processor) CA, which is signed by the root CA.
+		let pck_crl_issuer_chain =
+			pem_certificate_chain_to_der(trim_null_terminator(&collateral.pck_crl_issuer_chain))?;
+		let pck_crl_issuer_der =
+			pck_crl_issuer_chain.into_iter().next().ok_or(Error::MalformedPem)?;
+		let pck_crl_issuer = Certificate::parse(&pck_crl_issuer_der)?;
+		verify_issued_by(&pck_crl_issuer, &root_ca, now)?;
+		let pck_crl_issuer_name = pck_crl_issuer.common_name().unwrap_or_default();
+		if !self.certificate_names.pck_cas.contains(&pck_crl_issuer_name) {
+			return Err(Error::CertificateChain("PCK CRL issuer is not a PCK CA"))
+		}
+		if root_ca_crl.is_revoked(pck_crl_issuer.serial) {
+			return Err(Error::Revoked("PCK CRL issuer"))
+		}
+
+		let pck_crl_der = crl_to_der(&collateral.pck_crl)?;
+		let pck_crl = Crl::parse(&pck_crl_der)?;
+		verify_der_signature(pck_crl_issuer.public_key, pck_crl.tbs, pck_crl.signature)
+			.map_err(|_| Error::InvalidCollateral("PCK CRL signature is invalid"))?;
+
+		let (tcb_info_json, tcb_info_signature) = collateral
+			.get_tcb_info_split()
+			.ok_or(Error::InvalidCollateral("malformed TCB info"))?;
+		self.verify_signed_collateral(
+			&collateral.tcb_info_issuer_chain,
+			&root_ca,
+			&root_ca_crl,
+			tcb_info_json.as_bytes(),
+			&tcb_info_signature,
+			now,
+		)?;
+		let tcb_info = TcbInfo::from_json(&tcb_info_json)?;
+
+		let (qe_identity_json, qe_identity_signature) = collateral
+			.get_quoting_enclave_split()
+			.ok_or(Error::InvalidCollateral("malformed QE identity"))?;
+		self.verify_signed_collateral(
+			&collateral.qe_identity_issuer_chain,
+			&root_ca,
+			&root_ca_crl,
+			qe_identity_json.as_bytes(),
+			&qe_identity_signature,
+			now,
+		)?;
+		let qe_identity = QeIdentity::from_json(&qe_identity_json)?;
+
+		Ok(VerifiedCollateral {
+			tcb_info,
+			qe_identity,
+			revoked_pck_serials: pck_crl.revoked_serials.iter().map(|s| s.to_vec()).collect(),
+			pck_crl_issuer: pck_crl_issuer_der,
+		})
+	}
+
+	/// Verifies `quote` against `collateral` at unix time `now` (seconds).
+	///
+	/// Returns the TCB status of the quoting platform. It is up to the caller to decide which
+	/// statuses are acceptable.
+	pub fn verify_quote(
+		&self,
+		quote: &[u8],
+		collateral: &SgxQlQveCollateral,
+		now: u64,
+	) -> Result<DcapQuoteReport> {
+		let collateral = self.verify_collateral(collateral, now)?;
+		let quote = DcapQuote::parse(quote)?;
+
+		let (pck_cert, pck_issuer) = match quote.pck_cert_chain.as_slice() {
+			[pck_cert, pck_issuer, ..] => (pck_cert, pck_issuer),
+			_ => return Err(Error::InvalidQuote("incomplete PCK certificate chain")),
+		};
+		// The PCK CA has been verified along with the PCK CRL it issued.
+		if *pck_issuer != collateral.pck_crl_issuer {
+			return Err(Error::QuoteVerification("PCK certificate is not issued by the CRL issuer"))
+		}
+		let pck_certificate = Certificate::parse(pck_cert)?;
+		verify_issued_by(&pck_certificate, &Certificate::parse(pck_issuer)?, now)?;
+		verify_signer_profile(&pck_certificate, self.certificate_names.pck)?;
+		if collateral
+			.revoked_pck_serials
+			.iter()
+			.any(|s| s.as_slice() == pck_certificate.serial)
+		{
+			return Err(Error::Revoked("PCK certificate"))
+		}
+
+		// The PCK vouches for the quoting enclave report ...
+		verify_raw_signature(
+			pck_certificate.public_key,
+			&quote.qe_report_raw,
+			&quote.qe_report_signature,
+		)
+		.map_err(|_| Error::QuoteVerification("QE report signature is invalid"))?;
+
+		// ... which vouches for the attestation key ...
+		let mut attestation_key_data = quote.attestation_key.to_vec();
+		attestation_key_data.extend_from_slice(&quote.qe_authentication_data);
+		let attestation_key_hash = digest(&SHA256, &attestation_key_data);
+		let (hash, padding) = quote.qe_report.report_data.split_at(32);
+		if hash != attestation_key_hash.as_ref() || padding.iter().any(|b| *b != 0) {
+			return Err(Error::QuoteVerification("QE report does not commit to attestation key"))
+		}
+
+		// ... which signed the quote of the attested enclave.
+		let mut attestation_key = vec![0x04];
+		attestation_key.extend_from_slice(&quote.attestation_key);
+		verify_raw_signature(&attestation_key, &quote.signed_data, &quote.signature)
+			.map_err(|_| Error::QuoteVerification("quote signature is invalid"))?;
+
+		let pck_extensions = PckExtensions::from_cert_der(pck_cert)?;
+		let tcb_status = collateral.tcb_info.tcb_status(&pck_extensions)?;
+		let qe_tcb_status = collateral.qe_identity.tcb_status(&quote.qe_report)?;
+
+		Ok(DcapQuoteReport {
+			fmspc: pck_extensions.fmspc,
+			tcb_status,
+			qe_tcb_status,
+			collateral_expired: collateral.next_update() < now,
+			report_body: quote.report_body,
+		})
+	}
+
+	/// Verifies the signing certificate and the signature of TCB info or QE identity.
+	///
+	/// The signer must be the TCB signing certificate, which is issued directly by the root CA.
+	/// The rest of the issuer chain is ignored.
+	fn verify_signed_collateral(
+		&self,
+		issuer_chain_pem: &[u8],
+		root_ca: &Certificate,
+		root_ca_crl: &Crl,
+		data: &[u8],
+		signature: &[u8],
+		now: u64,
+	) -> Result<()> {
+		let issuer_chain = pem_certificate_chain_to_der(trim_null_terminator(issuer_chain_pem))?;
+		let signing_cert = issuer_chain.first().ok_or(Error::MalformedPem)?;
+		let signing_certificate = Certificate::parse(signing_cert)?;
+		verify_issued_by(&signing_certificate, root_ca, now)?;
+		verify_signer_profile(&signing_certificate, self.certificate_names.tcb_signing)?;
+
+		if root_ca_crl.is_revoked(signing_certificate.serial) {
+			return Err(Error::Revoked("collateral signing certificate"))
+		}
+		verify_raw_signature(signing_certificate.public_key, data, signature)
+			.map_err(|_| Error::InvalidCollateral("collateral signature is invalid"))
+	}
+}
+
+/// Verifies that `certificate` is signed by the CA certificate `issuer` and valid at `now`.
+fn verify_issued_by(certificate: &Certificate, issuer: &Certificate, now: u64) -> Result<()> {
+	if certificate.issuer != issuer.subject {
+		return Err(Error::CertificateChain("issuer name does not match"))
+	}
+	if !issuer.is_ca || !issuer.has_key_usage(KEY_USAGE_KEY_CERT_SIGN) {
+		return Err(Error::CertificateChain("issuer is not a CA"))
+	}
+	verify_der_signature(issuer.public_key, certificate.tbs, certificate.signature)
+		.map_err(|_| Error::CertificateChain("certificate signature is invalid"))?;
+	if !certificate.is_valid_at(now) {
+		return Err(Error::CertificateChain("certificate is expired or not yet valid"))
+	}
+	Ok(())
+}
+
+/// Ensures that `certificate` is the end-entity certificate `name`, which may sign data.
+fn verify_signer_profile(certificate: &Certificate, name: &str) -> Result<()> {
+	if certificate.common_name() != Some(name) {
+		return Err(Error::CertificateChain("unexpected certificate subject"))
+	}
+	if certificate.is_ca
+		|| !certificate.has_key_usage(KEY_USAGE_DIGITAL_SIGNATURE | KEY_USAGE_NON_REPUDIATION)
+	{
+		return Err(Error::CertificateChain("certificate is not a signing certificate"))
+	}
+	Ok(())
+}
+
+/// Verifies an ECDSA P-256 signature given as raw `r || s`.
+fn verify_raw_signature(public_key: &[u8], message: &[u8], signature: &[u8]) -> Result<()> {
+	UnparsedPublicKey::new(&ECDSA_P256_SHA256_FIXED, public_key)
+		.verify(message, signature)
+		.map_err(|_| Error::QuoteVerification("invalid signature"))
+}
+
+/// Verifies a DER encoded ECDSA P-256 signature, as used in certificates and CRLs.
+fn verify_der_signature(public_key: &[u8], message: &[u8], signature: &[u8]) -> Result<()> {
+	UnparsedPublicKey::new(&ECDSA_P256_SHA256_ASN1, public_key)
+		.verify(message, signature)
+		.map_err(|_| Error::QuoteVerification("invalid signature"))
+}
+
+#[derive(Clone, Debug)]
+struct TcbLevel {
+	components: [u8; TCB_COMPONENTS],
+	pce_svn: u16,
+	status: TcbStatus,
+}
+
+/// TCB info of a platform type (FMSPC), version 2 or 3.
+#[derive(Clone, Debug)]
+struct TcbInfo {
+	fmspc: Fmspc,
+	pce_id: [u8; 2],
+	next_update: u64,
+	tcb_levels: Vec<TcbLevel>,
+}
+
+impl TcbInfo {
+	fn from_json(json: &str) -> Result<Self> {
+		let value: Value = serde_json::from_str(json)
+			.map_err(|_| Error::InvalidCollateral("TCB info is not valid JSON"))?;
+		if let Some(id) = value["id"].as_str() {
+			if id != TCB_INFO_ID_SGX {
+				return Err(Error::InvalidCollateral("TCB info is not for SGX"))
+			}
+		}
+
+		let tcb_levels = value["tcbLevels"]
+			.as_array()
+			.ok_or(Error::InvalidCollateral("TCB info has no TCB levels"))?
+			.iter()
+			.map(TcbLevel::from_json)
+			.collect::<Result<Vec<_>>>()?;
+
+		Ok(TcbInfo {
+			fmspc: hex_field(&value["fmspc"])?,
+			pce_id: hex_field(&value["pceId"])?,
+			next_update: date_field(&value["nextUpdate"])?,
+			tcb_levels,
+		})
+	}
+
+	/// The status of the first TCB level the platform is at or above.
+	fn tcb_status(&self, pck: &PckExtensions) -> Result<TcbStatus> {
+		if pck.fmspc != self.fmspc || pck.pce_id != self.pce_id {
+			return Err(Error::QuoteVerification("TCB info does not match the platform"))
+		}
+		self.tcb_levels
+			.iter()
+			.find(|level| {
+				pck.pce_svn >= level.pce_svn
+					&& pck.tcb_components.iter().zip(level.components.iter()).all(|(p, l)| p >= l)
+			})
+			.map(|level| level.status)
+			.ok_or(Error::TcbLevelNotFound)
+	}
+}
+
+impl TcbLevel {
+	fn from_json(value: &Value) -> Result<Self> {
+		let tcb = &value["tcb"];
+		let mut components = [0u8; TCB_COMPONENTS];
+		match tcb["sgxtcbcomponents"].as_array() {
+			// Version 3
+			Some(sgx_tcb_components) => {
+				if sgx_tcb_components.len() != TCB_COMPONENTS {
+					return Err(Error::InvalidCollateral("invalid number of TCB components"))
+				}
+				for (component, value) in components.iter_mut().zip(sgx_tcb_components) {
+					*component = u8_field(&value["svn"])?;
+				}
+			},
+			// Version 2
+			None =>
+				for (i, component) in components.iter_mut().enumerate() {
+					*component = u8_field(&tcb[format!("sgxtcbcomp{:02}svn", i + 1).as_str()])?;
+				},
+		}
+
+		Ok(TcbLevel {
+			components,
+			pce_svn: u16_field(&tcb["pcesvn"])?,
+			status: status_field(&value["tcbStatus"])?,
+		})
+	}
+}
+
+/// Identity of the Intel quoting enclave, version 2.
+#[derive(Clone, Debug)]
+struct QeIdentity {
+	next_update: u64,
+	misc_select: u32,
+	misc_select_mask: u32,
+	attributes: [u8; 16],
+	attributes_mask: [u8; 16],
+	mr_signer: [u8; 32],
+	isv_prod_id: u16,
+	/// ISV SVN and status of each TCB level.
+	tcb_levels: Vec<(u16, TcbStatus)>,
+}
+
+impl QeIdentity {
+	fn from_json(json: &str) -> Result<Self> {
+		let value: Value = serde_json::from_str(json)
+			.map_err(|_| Error::InvalidCollateral("QE identity is not valid JSON"))?;
+		if let Some(id) = value["id"].as_str() {
+			if id != QE_IDENTITY_ID_QE {
+				return Err(Error::InvalidCollateral("identity is not of the quoting enclave"))
+			}
+		}
+
+		let tcb_levels = value["tcbLevels"]
+			.as_array()
+			.ok_or(Error::InvalidCollateral("QE identity has no TCB levels"))?
+			.iter()
+			.map(|level| {
+				Ok((u16_field(&level["tcb"]["isvsvn"])?, status_field(&level["tcbStatus"])?))
+			})
+			.collect::<Result<Vec<_>>>()?;
+
+		Ok(QeIdentity {
+			next_update: date_field(&value["nextUpdate"])?,
+			misc_select: u32::from_be_bytes(hex_field(&value["miscselect"])?),
+			misc_select_mask: u32::from_be_bytes(hex_field(&value["miscselectMask"])?),
+			attributes: hex_field(&value["attributes"])?,
+			attributes_mask: hex_field(&value["attributesMask"])?,
+			mr_signer: hex_field(&value["mrsigner"])?,
+			isv_prod_id: u16_field(&value["isvprodid"])?,
+			tcb_levels,
+		})
+	}
+
+	/// Checks that `qe_report` belongs to the quoting enclave and returns its TCB status.
+	fn tcb_status(&self, qe_report: &ReportBody) -> Result<TcbStatus> {
+		let attributes_match = qe_report
+			.attributes
+			.iter()
+			.zip(self.attributes.iter())
+			.zip(self.attributes_mask.iter())
+			.all(|((report, identity), mask)| report & mask == identity & mask);
+
+		if qe_report.misc_select & self.misc_select_mask != self.misc_select & self.misc_select_mask
+			|| !attributes_match
+			|| qe_report.mr_signer != self.mr_signer
+			|| qe_report.isv_prod_id != self.isv_prod_id
+		{
+			return Err(Error::QuoteVerification("QE report does not match the QE identity"))
+		}
+
+		self.tcb_levels
+			.iter()
+			.find(|(isv_svn, _)| qe_report.isv_svn >= *isv_svn)
+			.map(|(_, status)| *status)
+			.ok_or(Error::TcbLevelNotFound)
+	}
+}
+
+fn hex_field<const N: usize>(value: &Value) -> Result<[u8; N]> {
+	value
+		.as_str()
+		.and_then(|s| hex::decode(s).ok())
+		.and_then(|bytes| bytes.try_into().ok())
+		.ok_or(Error::InvalidCollateral("invalid hex field"))
+}
+
+fn date_field(value: &Value) -> Result<u64> {
+	value
+		.as_str()
+		.and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+		.and_then(|date| date.timestamp().try_into().ok())
+		.ok_or(Error::InvalidCollateral("invalid date field"))
+}
+
+fn u8_field(value: &Value) -> Result<u8> {
+	value
+		.as_u64()
+		.and_then(|v| v.try_into().ok())
+		.ok_or(Error::InvalidCollateral("invalid SVN field"))
+}
+
+fn u16_field(value: &Value) -> Result<u16> {
+	value
+		.as_u64()
+		.and_then(|v| v.try_into().ok())
+		.ok_or(Error::InvalidCollateral("invalid SVN field"))
+}
+
+fn status_field(value: &Value) -> Result<TcbStatus> {
+	value
+		.as_str()
+		.ok_or(Error::InvalidCollateral("missing TCB status"))
+		.and_then(TcbStatus::from_collateral)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+	use super::*;
+
+	pub const TEST_ROOT_CA: &[u8] = include_bytes!("fixtures/dcap/test_root_ca.der");
+	pub const QUOTE_UP_TO_DATE: &[u8] = include_bytes!("fixtures/dcap/quote_up_to_date.bin");
+	pub const QUOTE_OUT_OF_DATE: &[u8] = include_bytes!("fixtures/dcap/quote_out_of_date.bin");
+	const PCK_CRL_REVOKED: &[u8] = include_bytes!("fixtures/dcap/pck_crl_revoked.der");
+	/// 2024-01-15, while the fixture collateral is valid.
+	pub const NOW: u64 = 1_705_276_800;
+	/// 2024-03-01, after the `nextUpdate` of the fixture collateral.
+	pub const AFTER_NEXT_UPDATE: u64 = 1_709_251_200;
+
+	pub fn test_collateral() -> SgxQlQveCollateral {
+		SgxQlQveCollateral {
+			version: 3,
+			tee_type: 0,
+			pck_crl_issuer_chain: include_bytes!("fixtures/dcap/pck_crl_issuer_chain.pem").to_vec(),
+			root_ca_crl: include_bytes!("fixtures/dcap/root_ca_crl.der").to_vec(),
+			pck_crl: include_bytes!("fixtures/dcap/pck_crl.der").to_vec(),
+			tcb_info_issuer_chain: include_bytes!("fixtures/dcap/tcb_info_issuer_chain.pem")
+				.to_vec(),
+			tcb_info: include_bytes!("fixtures/dcap/tcb_info.json").to_vec(),
+			qe_identity_issuer_chain: include_bytes!("fixtures/dcap/qe_identity_issuer_chain.pem")
+				.to_vec(),
+			qe_identity: include_bytes!("fixtures/dcap/qe_identity.json").to_vec(),
+		}
+	}
+
+	/// The names of the certificates in the fixtures.
+	const TEST_CERTIFICATE_NAMES: DcapCertificateNames = DcapCertificateNames {
+		tcb_signing: "Test SGX TCB Signing",
+		pck_cas: &["Test SGX PCK Platform CA"],
+		pck: "Test SGX PCK Certificate",
+	};
+
+	pub fn test_verifier() -> DcapQuoteVerifier {
+		DcapQuoteVerifier::new(TEST_ROOT_CA.to_vec()).with_certificate_names(TEST_CERTIFICATE_NAMES)
+	}
+
+	#[test]
+	fn intel_root_ca_is_parsed() {
+		let verifier = DcapQuoteVerifier::with_intel_root_ca().unwrap();
+		assert!(Certificate::parse(&verifier.root_ca).is_ok());
+	}
+
+	#[test]
+	fn intel_root_ca_signature_is_verified() {
+		// The self-signature of the recorded Intel SGX root CA.
+		let verifier = DcapQuoteVerifier::with_intel_root_ca().unwrap();
+		let root_ca = Certificate::parse(&verifier.root_ca).unwrap();
+
+		assert!(verify_der_signature(root_ca.public_key, root_ca.tbs, root_ca.signature).is_ok());
+	}
+
+	#[test]
+	fn up_to_date_quote_is_verified() {
+		let report =
+			test_verifier().verify_quote(QUOTE_UP_TO_DATE, &test_collateral(), NOW).unwrap();
+
+		assert_eq!(report.fmspc, [0x00, 0x90, 0x6e, 0xd5, 0x00, 0x00]);
+		assert_eq!(report.tcb_status, TcbStatus::UpToDate);
+		assert_eq!(report.qe_tcb_status, TcbStatus::UpToDate);
+		assert_eq!(report.status(), TcbStatus::UpToDate);
+		assert!(!report.collateral_expired);
+		assert_eq!(report.report_body.mr_enclave, [0xab; 32]);
+	}
+
+	#[test]
+	fn out_of_date_platform_is_reported() {
+		let report = test_verifier()
+			.verify_quote(QUOTE_OUT_OF_DATE, &test_collateral(), NOW)
+			.unwrap();
+
+		assert_eq!(report.tcb_status, TcbStatus::OutOfDate);
+		assert_eq!(report.status(), TcbStatus::OutOfDate);
+	}
+
+	#[test]
+	fn expired_collateral_is_reported() {
+		let report = test_verifier()
+			.verify_quote(QUOTE_UP_TO_DATE, &test_collateral(), AFTER_NEXT_UPDATE)
+			.unwrap();
+
+		assert!(report.collateral_expired);
+	}
+
+	#[test]
+	fn quote_with_tampered_report_body_is_rejected() {
+		let mut quote = QUOTE_UP_TO_DATE.to_vec();
+		// First byte of the MRENCLAVE.
+		quote[48 + 64] ^= 0xff;
+
+		let result = test_verifier().verify_quote(&quote, &test_collateral(), NOW);
+
+		assert!(matches!(result, Err(Error::QuoteVerification("quote signature is invalid"))));
+	}
+
+	#[test]
+	fn quote_with_tampered_qe_report_is_rejected() {
+		let mut quote = QUOTE_UP_TO_DATE.to_vec();
+		// ISV SVN of the QE report, behind header, report body, signature data length,
+		// quote signature and attestation key.
+		quote[48 + 384 + 4 + 64 + 64 + 258] ^= 0xff;
+
+		let result = test_verifier().verify_quote(&quote, &test_collateral(), NOW);
+
+		assert!(matches!(result, Err(Error::QuoteVerification("QE report signature is invalid"))));
+	}
+
+	#[test]
+	fn quote_is_rejected_with_other_root_ca() {
+		let verifier = DcapQuoteVerifier::with_intel_root_ca().unwrap();
+
+		assert!(verifier.verify_quote(QUOTE_UP_TO_DATE, &test_collateral(), NOW).is_err());
+	}
+
+	#[test]
+	fn revoked_pck_certificate_is_rejected() {
+		let mut collateral = test_collateral();
+		collateral.pck_crl = PCK_CRL_REVOKED.to_vec();
+
+		let result = test_verifier().verify_quote(QUOTE_UP_TO_DATE, &collateral, NOW);
+
+		assert!(matches!(result, Err(Error::Revoked("PCK certificate"))));
+		// The out-of-date platform has another PCK certificate, which is not revoked.
+		assert!(test_verifier().verify_quote(QUOTE_OUT_OF_DATE, &collateral, NOW).is_ok());
+	}
+
+	#[test]
+	fn tampered_tcb_info_is_rejected() {
+		let mut collateral = test_collateral();
+		let tcb_info = String::from_utf8(collateral.tcb_info).unwrap();
+		collateral.tcb_info = tcb_info.replacen("OutOfDate", "UpToDate", 1).into_bytes();
+
+		let result = test_verifier().verify_collateral(&collateral, NOW);
+
+		assert!(matches!(result, Err(Error::InvalidCollateral("collateral signature is invalid"))));
+	}
+
+	#[test]
+	fn collateral_signed_by_other_certificate_is_rejected() {
+		let verifier = test_verifier().with_certificate_names(DcapCertificateNames {
+			tcb_signing: "Test SGX PCK Certificate",
+			..TEST_CERTIFICATE_NAMES
+		});
+
+		let result = verifier.verify_collateral(&test_collateral(), NOW);
+
+		assert!(matches!(result, Err(Error::CertificateChain("unexpected certificate subject"))));
+	}
+
+	#[test]
+	fn collateral_signed_by_ca_is_rejected() {
+		// The PCK CA chains to the root CA as well, but must not sign collateral.
+		let mut collateral = test_collateral();
+		collateral.tcb_info_issuer_chain = collateral.pck_crl_issuer_chain.clone();
+		let verifier = test_verifier().with_certificate_names(DcapCertificateNames {
+			tcb_signing: "Test SGX PCK Platform CA",
+			..TEST_CERTIFICATE_NAMES
+		});
+
+		let result = verifier.verify_collateral(&collateral, NOW);
+
+		assert!(matches!(
+			result,
+			Err(Error::CertificateChain("certificate is not a signing certificate"))
+		));
+	}
+
+	#[test]
+	fn quote_with_other_pck_certificate_name_is_rejected() {
+		let verifier = test_verifier().with_certificate_names(DcapCertificateNames {
+			pck: "Test SGX TCB Signing",
+			..TEST_CERTIFICATE_NAMES
+		});
+
+		let result = verifier.verify_quote(QUOTE_UP_TO_DATE, &test_collateral(), NOW);
+
+		assert!(matches!(result, Err(Error::CertificateChain("unexpected certificate subject"))));
+	}
+
+	#[test]
+	fn collateral_is_rejected_after_certificates_expired() {
+		// 2053-01-02, after the fixture certificates have expired.
+		let result = test_verifier().verify_collateral(&test_collateral(), 2_619_388_800);
+
+		assert!(matches!(
+			result,
+			Err(Error::CertificateChain("certificate is expired or not yet valid"))
+		));
+	}
+
+	#[test]
+	fn collateral_is_rejected_with_intel_certificate_names() {
+		let verifier = DcapQuoteVerifier::new(TEST_ROOT_CA.to_vec());
+
+		assert!(verifier.verify_collateral(&test_collateral(), NOW).is_err());
+	}
+
+	#[test]
+	fn verified_collateral_reports_fmspc_and_next_update() {
+		let collateral = test_verifier().verify_collateral(&test_collateral(), NOW).unwrap();
+
+		assert_eq!(collateral.fmspc(), [0x00, 0x90, 0x6e, 0xd5, 0x00, 0x00]);
+		// 2024-02-01
+		assert_eq!(collateral.next_update(), 1_706_745_600);
+	}
+}
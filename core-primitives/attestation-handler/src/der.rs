@@ -0,0 +1,456 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Minimal DER and PEM helpers for the X.509 structures used in DCAP attestation.
+//!
+//! The DCAP certificate chains are not validated with `webpki`, which only knows TLS and client
+//! certificates. These helpers extract the fields needed to validate them against the Intel
+//! profiles: names, validity, key usage, basic constraints, serial numbers, public keys, CRL
+//! entries and the raw signed data of certificates and CRLs.
+
+#[cfg(all(not(feature = "std"), feature = "sgx"))]
+use crate::sgx_reexport_prelude::*;
+
+use crate::{Error, Result};
+use chrono::NaiveDate;
+use core::convert::TryInto;
+use std::{str, vec::Vec};
+
+pub(crate) const TAG_INTEGER: u8 = 0x02;
+pub(crate) const TAG_BIT_STRING: u8 = 0x03;
+pub(crate) const TAG_OCTET_STRING: u8 = 0x04;
+pub(crate) const TAG_OID: u8 = 0x06;
+pub(crate) const TAG_SEQUENCE: u8 = 0x30;
+const TAG_UTC_TIME: u8 = 0x17;
+const TAG_GENERALIZED_TIME: u8 = 0x18;
+const TAG_BOOLEAN: u8 = 0x01;
+const TAG_SET: u8 = 0x31;
+const TAG_CONTEXT_0: u8 = 0xa0;
+const TAG_CONTEXT_3: u8 = 0xa3;
+
+/// id-at-commonName (2.5.4.3)
+const OID_COMMON_NAME: &[u8] = &[0x55, 0x04, 0x03];
+/// id-ce-keyUsage (2.5.29.15)
+const OID_KEY_USAGE: &[u8] = &[0x55, 0x1d, 0x0f];
+/// id-ce-basicConstraints (2.5.29.19)
+const OID_BASIC_CONSTRAINTS: &[u8] = &[0x55, 0x1d, 0x13];
+
+/// Bits of the first key usage byte.
+pub(crate) const KEY_USAGE_DIGITAL_SIGNATURE: u8 = 0x80;
+pub(crate) const KEY_USAGE_NON_REPUDIATION: u8 = 0x40;
+pub(crate) const KEY_USAGE_KEY_CERT_SIGN: u8 = 0x04;
+
+const PEM_CERTIFICATE_BEGIN: &str = "-----BEGIN CERTIFICATE-----";
+const PEM_CERTIFICATE_END: &str = "-----END CERTIFICATE-----";
+const PEM_CRL_BEGIN: &str = "-----BEGIN X509 CRL-----";
+const PEM_CRL_END: &str = "-----END X509 CRL-----";
+
+/// A single DER element.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Tlv<'a> {
+	pub tag: u8,
+	/// Content octets of the element.
+	pub value: &'a [u8],
+	/// The complete encoding of the element, including tag and length.
+	pub raw: &'a [u8],
+}
+
+/// Splits the first DER element off `input`. Returns the element and the remaining bytes.
+pub(crate) fn read_tlv(input: &[u8]) -> Result<(Tlv, &[u8])> {
+	let tag = *input.first().ok_or(Error::MalformedDer)?;
+	let first_length_byte = *input.get(1).ok_or(Error::MalformedDer)?;
+
+	let (length, header_length) = if first_length_byte < 0x80 {
+		(first_length_byte as usize, 2)
+	} else {
+		let length_bytes = (first_length_byte & 0x7f) as usize;
+		if length_bytes == 0 || length_bytes > 4 {
+			return Err(Error::MalformedDer)
+		}
+		let encoded_length = input.get(2..2 + length_bytes).ok_or(Error::MalformedDer)?;
+		let length = encoded_length.iter().fold(0usize, |acc, b| (acc << 8) | *b as usize);
+		(length, 2 + length_bytes)
+	};
+
+	let end = header_length.checked_add(length).ok_or(Error::MalformedDer)?;
+	let raw = input.get(..end).ok_or(Error::MalformedDer)?;
+	Ok((Tlv { tag, value: &raw[header_length..], raw }, &input[end..]))
+}
+
+/// Reads the next element and ensures it has the `expected` tag.
+pub(crate) fn expect_tlv(input: &[u8], expected: u8) -> Result<(Tlv, &[u8])> {
+	let (tlv, rest) = read_tlv(input)?;
+	if tlv.tag != expected {
+		return Err(Error::MalformedDer)
+	}
+	Ok((tlv, rest))
+}
+
+/// Decodes a DER INTEGER of at most 8 bytes (ignoring a leading zero byte).
+pub(crate) fn parse_small_integer(value: &[u8]) -> Result<u64> {
+	let value = match value {
+		[0, rest @ ..] if !rest.is_empty() => rest,
+		_ => value,
+	};
+	if value.is_empty() || value.len() > 8 {
+		return Err(Error::MalformedDer)
+	}
+	Ok(value.iter().fold(0u64, |acc, b| (acc << 8) | *b as u64))
+}
+
+/// An X.509 certificate, split into the parts needed for signature and revocation checks.
+pub(crate) struct Certificate<'a> {
+	/// DER encoding of the `TBSCertificate`, i.e. the signed data.
+	pub tbs: &'a [u8],
+	pub serial: &'a [u8],
+	/// DER encoded issuer name.
+	pub issuer: &'a [u8],
+	/// DER encoded subject name.
+	pub subject: &'a [u8],
+	/// Unix timestamps (seconds) of the validity period.
+	pub not_before: u64,
+	pub not_after: u64,
+	/// Uncompressed EC public key (`0x04 || x || y`).
+	pub public_key: &'a [u8],
+	/// First byte of the key usage extension, if present.
+	pub key_usage: Option<u8>,
+	/// The basic constraints extension marks the certificate as CA.
+	pub is_ca: bool,
+	/// DER encoded signature of the issuer.
+	pub signature: &'a [u8],
+}
+
+impl<'a> Certificate<'a> {
+	pub fn parse(cert_der: &'a [u8]) -> Result<Self> {
+		let (certificate, _) = expect_tlv(cert_der, TAG_SEQUENCE)?;
+		let (tbs, rest) = expect_tlv(certificate.value, TAG_SEQUENCE)?;
+		let (_signature_algorithm, rest) = expect_tlv(rest, TAG_SEQUENCE)?;
+		let (signature, _) = expect_tlv(rest, TAG_BIT_STRING)?;
+
+		let mut fields = tbs.value;
+		let (first, rest) = read_tlv(fields)?;
+		if first.tag == TAG_CONTEXT_0 {
+			// Skip the optional version.
+			fields = rest;
+		}
+		let (serial, rest) = expect_tlv(fields, TAG_INTEGER)?;
+		let (_signature_algorithm, rest) = expect_tlv(rest, TAG_SEQUENCE)?;
+		let (issuer, rest) = expect_tlv(rest, TAG_SEQUENCE)?;
+		let (validity, rest) = expect_tlv(rest, TAG_SEQUENCE)?;
+		let (subject, rest) = expect_tlv(rest, TAG_SEQUENCE)?;
+		let (subject_public_key_info, mut rest) = expect_tlv(rest, TAG_SEQUENCE)?;
+		let (_, public_key) = expect_tlv(subject_public_key_info.value, TAG_SEQUENCE)?;
+		let (public_key, _) = expect_tlv(public_key, TAG_BIT_STRING)?;
+
+		let (not_before, validity) = read_tlv(validity.value)?;
+		let (not_after, _) = read_tlv(validity)?;
+
+		let mut key_usage = None;
+		let mut is_ca = false;
+		// Skip the optional unique identifiers.
+		while !rest.is_empty() {
+			let (next, remainder) = read_tlv(rest)?;
+			rest = remainder;
+			if next.tag != TAG_CONTEXT_3 {
+				continue
+			}
+			let (extensions, _) = expect_tlv(next.value, TAG_SEQUENCE)?;
+			let mut extensions = extensions.value;
+			while !extensions.is_empty() {
+				let (extension, remainder) = expect_tlv(extensions, TAG_SEQUENCE)?;
+				extensions = remainder;
+				let (oid, value) = expect_tlv(extension.value, TAG_OID)?;
+				let (mut value, remainder) = read_tlv(value)?;
+				if value.tag == TAG_BOOLEAN {
+					// Skip the critical flag.
+					value = expect_tlv(remainder, TAG_OCTET_STRING)?.0;
+				} else if value.tag != TAG_OCTET_STRING {
+					return Err(Error::MalformedDer)
+				}
+				match oid.value {
+					OID_KEY_USAGE => {
+						let (bits, _) = expect_tlv(value.value, TAG_BIT_STRING)?;
+						key_usage = Some(bits.value.get(1).copied().unwrap_or_default());
+					},
+					OID_BASIC_CONSTRAINTS => {
+						let (constraints, _) = expect_tlv(value.value, TAG_SEQUENCE)?;
+						is_ca = match read_tlv(constraints.value) {
+							Ok((ca, _)) if ca.tag == TAG_BOOLEAN => ca.value == [0xff],
+							_ => false,
+						};
+					},
+					_ => {},
+				}
+			}
+		}
+
+		Ok(Certificate {
+			tbs: tbs.raw,
+			serial: serial.value,
+			issuer: issuer.raw,
+			subject: subject.raw,
+			not_before: parse_time(not_before)?,
+			not_after: parse_time(not_after)?,
+			public_key: bit_string_bytes(public_key.value)?,
+			key_usage,
+			is_ca,
+			signature: bit_string_bytes(signature.value)?,
+		})
+	}
+
+	/// The common name of the subject.
+	pub fn common_name(&self) -> Option<&'a str> {
+		let (subject, _) = expect_tlv(self.subject, TAG_SEQUENCE).ok()?;
+		let mut names = subject.value;
+		while !names.is_empty() {
+			let (set, remainder) = expect_tlv(names, TAG_SET).ok()?;
+			names = remainder;
+			let (attribute, _) = expect_tlv(set.value, TAG_SEQUENCE).ok()?;
+			let (oid, value) = expect_tlv(attribute.value, TAG_OID).ok()?;
+			if oid.value == OID_COMMON_NAME {
+				let (value, _) = read_tlv(value).ok()?;
+				return str::from_utf8(value.value).ok()
+			}
+		}
+		None
+	}
+
+	/// The key usage extension is present and allows all of the `required` usages.
+	pub fn has_key_usage(&self, required: u8) -> bool {
+		self.key_usage.map_or(false, |key_usage| key_usage & required == required)
+	}
+
+	pub fn is_valid_at(&self, now: u64) -> bool {
+		self.not_before <= now && now <= self.not_after
+	}
+}
+
+/// Decodes a `UTCTime` or `GeneralizedTime` of the form `[YY]YYMMDDHHMMSSZ` to a unix timestamp.
+fn parse_time(time: Tlv) -> Result<u64> {
+	let (year, rest) = match time.tag {
+		TAG_UTC_TIME => {
+			let year = parse_digits(time.value.get(..2))?;
+			// RFC 5280: years 50 to 99 are in the 20th century.
+			(if year >= 50 { 1900 + year } else { 2000 + year }, &time.value[2..])
+		},
+		TAG_GENERALIZED_TIME => (parse_digits(time.value.get(..4))?, &time.value[4..]),
+		_ => return Err(Error::MalformedDer),
+	};
+	if rest.len() != 11 || rest[10] != b'Z' {
+		return Err(Error::MalformedDer)
+	}
+	let field = |i: usize| parse_digits(rest.get(i..i + 2));
+	NaiveDate::from_ymd_opt(year as i32, field(0)?, field(2)?)
+		.and_then(|date| date.and_hms_opt(field(4)?, field(6)?, field(8)?))
+		.and_then(|time| time.timestamp().try_into().ok())
+		.ok_or(Error::MalformedDer)
+}
+
+fn parse_digits(digits: Option<&[u8]>) -> Result<u32> {
+	let digits = digits.ok_or(Error::MalformedDer)?;
+	if !digits.iter().all(u8::is_ascii_digit) {
+		return Err(Error::MalformedDer)
+	}
+	Ok(digits.iter().fold(0, |acc, d| acc * 10 + (d - b'0') as u32))
+}
+
+/// A certificate revocation list.
+pub(crate) struct Crl<'a> {
+	/// DER encoding of the `TBSCertList`, i.e. the signed data.
+	pub tbs: &'a [u8],
+	/// Serial numbers of all revoked certificates.
+	pub revoked_serials: Vec<&'a [u8]>,
+	/// DER encoded signature of the issuer.
+	pub signature: &'a [u8],
+}
+
+impl<'a> Crl<'a> {
+	pub fn parse(crl_der: &'a [u8]) -> Result<Self> {
+		let (crl, _) = expect_tlv(crl_der, TAG_SEQUENCE)?;
+		let (tbs, rest) = expect_tlv(crl.value, TAG_SEQUENCE)?;
+		let (_signature_algorithm, rest) = expect_tlv(rest, TAG_SEQUENCE)?;
+		let (signature, _) = expect_tlv(rest, TAG_BIT_STRING)?;
+
+		let mut fields = tbs.value;
+		let (first, rest) = read_tlv(fields)?;
+		if first.tag == TAG_INTEGER {
+			// Skip the optional version.
+			fields = rest;
+		}
+		// signature algorithm and issuer
+		let (_, rest) = expect_tlv(fields, TAG_SEQUENCE)?;
+		let (_, rest) = expect_tlv(rest, TAG_SEQUENCE)?;
+		let (_this_update, mut rest) = read_tlv(rest)?;
+
+		let mut revoked_serials = Vec::new();
+		while !rest.is_empty() {
+			let (next, remainder) = read_tlv(rest)?;
+			rest = remainder;
+			match next.tag {
+				TAG_UTC_TIME | TAG_GENERALIZED_TIME => continue, // next update
+				TAG_SEQUENCE => {
+					let mut entries = next.value;
+					while !entries.is_empty() {
+						let (entry, remainder) = expect_tlv(entries, TAG_SEQUENCE)?;
+						let (serial, _) = expect_tlv(entry.value, TAG_INTEGER)?;
+						revoked_serials.push(serial.value);
+						entries = remainder;
+					}
+				},
+				_ => break, // crl extensions
+			}
+		}
+
+		Ok(Crl { tbs: tbs.raw, revoked_serials, signature: bit_string_bytes(signature.value)? })
+	}
+
+	pub fn is_revoked(&self, serial: &[u8]) -> bool {
+		self.revoked_serials.contains(&serial)
+	}
+}
+
+/// Returns the DER encoded certificates of a PEM certificate chain, in the order they appear.
+pub(crate) fn pem_certificate_chain_to_der(pem: &[u8]) -> Result<Vec<Vec<u8>>> {
+	let pem = str::from_utf8(pem).map_err(|_| Error::MalformedPem)?;
+	let mut certificates = Vec::new();
+	let mut remainder = pem;
+	while let Some(begin) = remainder.find(PEM_CERTIFICATE_BEGIN) {
+		let (der, rest) = pem_block_to_der(
+			&remainder[begin + PEM_CERTIFICATE_BEGIN.len()..],
+			PEM_CERTIFICATE_END,
+		)?;
+		certificates.push(der);
+		remainder = rest;
+	}
+	if certificates.is_empty() {
+		return Err(Error::MalformedPem)
+	}
+	Ok(certificates)
+}
+
+/// CRLs are delivered by the different PCCS versions either as DER, as PEM or as hex encoded DER.
+pub(crate) fn crl_to_der(crl: &[u8]) -> Result<Vec<u8>> {
+	if let Ok(crl_str) = str::from_utf8(trim_null_terminator(crl)) {
+		let crl_str = crl_str.trim();
+		if let Some(begin) = crl_str.find(PEM_CRL_BEGIN) {
+			return pem_block_to_der(&crl_str[begin + PEM_CRL_BEGIN.len()..], PEM_CRL_END)
+				.map(|(der, _)| der)
+		}
+		if let Ok(der) = hex::decode(crl_str) {
+			return Ok(der)
+		}
+	}
+	Ok(crl.to_vec())
+}
+
+pub(crate) fn trim_null_terminator(data: &[u8]) -> &[u8] {
+	let end = data.iter().rposition(|b| *b != 0).map_or(0, |p| p + 1);
+	&data[..end]
+}
+
+fn pem_block_to_der<'a>(block: &'a str, end_marker: &str) -> Result<(Vec<u8>, &'a str)> {
+	let end = block.find(end_marker).ok_or(Error::MalformedPem)?;
+	let base64_body: Vec<u8> = block[..end].bytes().filter(|b| !b.is_ascii_whitespace()).collect();
+	let der = base64::decode(&base64_body).map_err(|_| Error::MalformedPem)?;
+	Ok((der, &block[end + end_marker.len()..]))
+}
+
+fn bit_string_bytes(value: &[u8]) -> Result<&[u8]> {
+	match value {
+		// We only deal with keys and signatures, which have no unused bits.
+		[0, bytes @ ..] => Ok(bytes),
+		_ => Err(Error::MalformedDer),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const TEST_ROOT_CA: &[u8] = include_bytes!("fixtures/dcap/test_root_ca.der");
+	const PCK_CRL_REVOKED: &[u8] = include_bytes!("fixtures/dcap/pck_crl_revoked.der");
+	const TCB_INFO_ISSUER_CHAIN: &[u8] = include_bytes!("fixtures/dcap/tcb_info_issuer_chain.pem");
+
+	#[test]
+	fn read_tlv_handles_long_form_length() {
+		let mut encoded = vec![TAG_OCTET_STRING, 0x81, 0x80];
+		encoded.extend_from_slice(&[7u8; 0x80]);
+		encoded.push(0xff);
+
+		let (tlv, rest) = read_tlv(&encoded).unwrap();
+
+		assert_eq!(tlv.tag, TAG_OCTET_STRING);
+		assert_eq!(tlv.value, &[7u8; 0x80][..]);
+		assert_eq!(rest, &[0xff]);
+	}
+
+	#[test]
+	fn read_tlv_fails_on_truncated_input() {
+		assert!(read_tlv(&[TAG_OCTET_STRING, 0x05, 1, 2]).is_err());
+		assert!(read_tlv(&[TAG_OCTET_STRING, 0x82, 0x01]).is_err());
+	}
+
+	#[test]
+	fn pem_chain_is_converted_to_der_in_order() {
+		let chain = pem_certificate_chain_to_der(TCB_INFO_ISSUER_CHAIN).unwrap();
+
+		assert_eq!(chain.len(), 2);
+		assert_eq!(chain[1], TEST_ROOT_CA);
+	}
+
+	#[test]
+	fn certificate_fields_are_extracted() {
+		let certificate = Certificate::parse(TEST_ROOT_CA).unwrap();
+
+		assert_eq!(certificate.serial, &[1]);
+		assert_eq!(certificate.public_key.len(), 65);
+		assert_eq!(certificate.public_key[0], 0x04);
+		assert_eq!(certificate.common_name(), Some("Test SGX Root CA"));
+		assert_eq!(certificate.issuer, certificate.subject);
+		assert!(certificate.is_ca);
+		assert!(certificate.has_key_usage(KEY_USAGE_KEY_CERT_SIGN));
+		assert!(!certificate.has_key_usage(KEY_USAGE_DIGITAL_SIGNATURE));
+		// 2023-01-01 until 2053-01-01
+		assert_eq!(certificate.not_before, 1_672_531_200);
+		assert_eq!(certificate.not_after, 2_619_302_400);
+	}
+
+	#[test]
+	fn signing_certificate_is_no_ca() {
+		let chain = pem_certificate_chain_to_der(TCB_INFO_ISSUER_CHAIN).unwrap();
+		let certificate = Certificate::parse(&chain[0]).unwrap();
+
+		assert_eq!(certificate.common_name(), Some("Test SGX TCB Signing"));
+		assert!(!certificate.is_ca);
+		assert!(certificate.has_key_usage(KEY_USAGE_DIGITAL_SIGNATURE | KEY_USAGE_NON_REPUDIATION));
+	}
+
+	#[test]
+	fn revoked_serials_are_extracted_from_crl() {
+		let crl = Crl::parse(PCK_CRL_REVOKED).unwrap();
+
+		assert!(crl.is_revoked(&[0x10, 0x01]));
+		assert!(!crl.is_revoked(&[0x10, 0x02]));
+	}
+
+	#[test]
+	fn crl_is_decoded_from_hex() {
+		let hex_encoded = hex::encode(PCK_CRL_REVOKED);
+		assert_eq!(crl_to_der(hex_encoded.as_bytes()).unwrap(), PCK_CRL_REVOKED);
+		assert_eq!(crl_to_der(PCK_CRL_REVOKED).unwrap(), PCK_CRL_REVOKED);
+	}
+}
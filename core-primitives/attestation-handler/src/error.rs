@@ -34,6 +34,30 @@ pub enum Error {
 	Crypto(itp_sgx_crypto::Error),
 	#[error("Error specifying time")]
 	Time,
+	#[error("Malformed DER encoding")]
+	MalformedDer,
+	#[error("Malformed PEM encoding")]
+	MalformedPem,
+	#[error("Invalid DCAP quote: {0}")]
+	InvalidQuote(&'static str),
+	#[error("Invalid PCK certificate")]
+	InvalidPckCertificate,
+	#[error("Invalid collateral: {0}")]
+	InvalidCollateral(&'static str),
+	#[error("Cached collateral has expired and could not be refreshed")]
+	CollateralExpired,
+	#[error("Certificate chain verification failed: {0}")]
+	CertificateChain(&'static str),
+	#[error("Quote verification failed: {0}")]
+	QuoteVerification(&'static str),
+	#[error("No TCB level matches the platform")]
+	TcbLevelNotFound,
+	#[error("Revoked: {0}")]
+	Revoked(&'static str),
+	#[error("Codec error: {0:?}")]
+	Codec(codec::Error),
+	#[error("Lock poisoning")]
+	LockPoisoning,
 	#[error(transparent)]
 	Other(#[from] Box<dyn std::error::Error + Sync + Send + 'static>),
 }
@@ -44,6 +68,12 @@ impl From<sgx_status_t> for Error {
 	}
 }
 
+impl From<codec::Error> for Error {
+	fn from(error: codec::Error) -> Self {
+		Self::Codec(error)
+	}
+}
+
 impl From<itp_sgx_crypto::error::Error> for Error {
 	fn from(error: itp_sgx_crypto::error::Error) -> Self {
 		Self::Crypto(error)
@@ -29,6 +29,7 @@ extern crate sgx_tstd as std;
 pub mod sgx_reexport_prelude {
 	pub use base64_sgx as base64;
 	pub use chrono_sgx as chrono;
+	pub use ring_sgx as ring;
 	pub use rustls_sgx as rustls;
 	pub use serde_json_sgx as serde_json;
 	pub use thiserror_sgx as thiserror;
@@ -41,13 +42,23 @@ pub mod attestation_handler;
 
 pub mod collateral;
 
+pub mod collateral_repository;
+
 pub mod cert;
 
+pub mod dcap_quote;
+
+pub mod dcap_verifier;
+
+mod der;
+
 pub mod error;
 
 #[cfg(all(not(feature = "std"), feature = "sgx"))]
 pub use attestation_handler::{AttestationHandler, IntelAttestationHandler, DEV_HOSTNAME};
 pub use collateral::SgxQlQveCollateral;
+pub use collateral_repository::{CollateralRepository, CollateralSource};
+pub use dcap_verifier::{DcapQuoteReport, DcapQuoteVerifier, TcbStatus, VerifyDcapQuote};
 
 pub use error::{Error, Result};
 
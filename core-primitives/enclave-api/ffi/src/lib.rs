@@ -33,6 +33,8 @@ extern "C" {
 	pub fn init_enclave_sidechain_components(
 		eid: sgx_enclave_id_t,
 		retval: *mut sgx_status_t,
+		slot_claim_config: *const u8,
+		slot_claim_config_size: u32,
 	) -> sgx_status_t;
 
 	pub fn init_direct_invocation_server(
@@ -270,4 +272,30 @@ extern "C" {
 		skip_ra: c_int,
 	) -> sgx_status_t;
 
+	pub fn request_rotated_keys(
+		eid: sgx_enclave_id_t,
+		retval: *mut sgx_status_t,
+		socket_fd: c_int,
+		sign_type: sgx_quote_sign_type_t,
+		quoting_enclave_target_info: Option<&sgx_target_info_t>,
+		quote_size: Option<&u32>,
+		shard: *const u8,
+		shard_size: u32,
+		skip_ra: c_int,
+	) -> sgx_status_t;
+
+	pub fn request_shard_provisioning(
+		eid: sgx_enclave_id_t,
+		retval: *mut sgx_status_t,
+		socket_fd: c_int,
+		sign_type: sgx_quote_sign_type_t,
+		quoting_enclave_target_info: Option<&sgx_target_info_t>,
+		quote_size: Option<&u32>,
+		shard: *const u8,
+		shard_size: u32,
+		skip_ra: c_int,
+	) -> sgx_status_t;
+
+	pub fn rotate_keys(eid: sgx_enclave_id_t, retval: *mut sgx_status_t) -> sgx_status_t;
+
 }
@@ -22,6 +22,7 @@ use core::fmt::Debug;
 use itc_parentchain::primitives::{ParentchainId, ParentchainInitParams};
 use itp_stf_interface::ShardCreationInfo;
 use itp_types::{parentchain::Header, Balance, ShardIdentifier};
+use its_primitives::types::slot_claims::SlotClaimConfig;
 use sgx_crypto_helper::rsa3072::Rsa3072PubKey;
 use sp_core::ed25519;
 use teerex_primitives::EnclaveFingerprint;
@@ -36,8 +37,11 @@ pub trait EnclaveBase: Send + Sync + 'static {
 		base_dir: &str,
 	) -> EnclaveResult<()>;
 
-	/// Initialize the enclave sidechain components.
-	fn init_enclave_sidechain_components(&self) -> EnclaveResult<()>;
+	/// Initialize the enclave sidechain components, with the slot claim rules of the sidechain.
+	fn init_enclave_sidechain_components(
+		&self,
+		slot_claim_config: SlotClaimConfig,
+	) -> EnclaveResult<()>;
 
 	/// Initialize the direct invocation RPC server.
 	fn init_direct_invocation_server(&self, rpc_server_addr: String) -> EnclaveResult<()>;
@@ -85,6 +89,10 @@ pub trait EnclaveBase: Send + Sync + 'static {
 	fn get_ecc_vault_pubkey(&self, shard: &ShardIdentifier) -> EnclaveResult<ed25519::Public>;
 
 	fn get_fingerprint(&self) -> EnclaveResult<EnclaveFingerprint>;
+
+	/// Start a rotation of the shielding key and the state key. The new keys are activated at the
+	/// sidechain block at which the enclave account is rotated.
+	fn rotate_keys(&self) -> EnclaveResult<()>;
 }
 
 /// EnclaveApi implementation for Enclave struct
@@ -105,6 +113,7 @@ mod impl_ffi {
 		parentchain::{Balance, Header},
 		ShardIdentifier,
 	};
+	use its_primitives::types::slot_claims::SlotClaimConfig;
 	use log::*;
 	use sgx_crypto_helper::rsa3072::Rsa3072PubKey;
 	use sgx_types::*;
@@ -143,10 +152,22 @@ mod impl_ffi {
 			Ok(())
 		}
 
-		fn init_enclave_sidechain_components(&self) -> EnclaveResult<()> {
+		fn init_enclave_sidechain_components(
+			&self,
+			slot_claim_config: SlotClaimConfig,
+		) -> EnclaveResult<()> {
 			let mut retval = sgx_status_t::SGX_SUCCESS;
 
-			let result = unsafe { ffi::init_enclave_sidechain_components(self.eid, &mut retval) };
+			let encoded_slot_claim_config = slot_claim_config.encode();
+
+			let result = unsafe {
+				ffi::init_enclave_sidechain_components(
+					self.eid,
+					&mut retval,
+					encoded_slot_claim_config.as_ptr(),
+					encoded_slot_claim_config.len() as u32,
+				)
+			};
 
 			ensure!(result == sgx_status_t::SGX_SUCCESS, Error::Sgx(result));
 			ensure!(retval == sgx_status_t::SGX_SUCCESS, Error::Sgx(retval));
@@ -262,7 +283,7 @@ mod impl_ffi {
 			shard: &ShardIdentifier,
 		) -> EnclaveResult<ShardCreationInfo> {
 			let mut retval = sgx_status_t::SGX_SUCCESS;
-			let mut creation_info = [0u8; std::mem::size_of::<ShardCreationInfo>()];
+			let mut creation_info = vec![0u8; ShardCreationInfo::MAX_ENCODED_LEN];
 			let shard_bytes = shard.encode();
 
 			let result = unsafe {
@@ -411,6 +432,17 @@ mod impl_ffi {
 
 			Ok(mr_enclave.into())
 		}
+
+		fn rotate_keys(&self) -> EnclaveResult<()> {
+			let mut retval = sgx_status_t::SGX_SUCCESS;
+
+			let result = unsafe { ffi::rotate_keys(self.eid, &mut retval) };
+
+			ensure!(result == sgx_status_t::SGX_SUCCESS, Error::Sgx(result));
+			ensure!(retval == sgx_status_t::SGX_SUCCESS, Error::Sgx(retval));
+
+			Ok(())
+		}
 	}
 
 	fn init_parentchain_components_ffi(
@@ -80,6 +80,10 @@ pub trait RemoteAttestationCallBacks {
 
 	fn get_dcap_quote(&self, report: sgx_report_t, quote_size: u32) -> EnclaveResult<Vec<u8>>;
 
+	/// Fetch the quote verification collateral of the platform `fmspc`, SCALE encoded as
+	/// `SgxQlQveCollateral`.
+	fn get_encoded_dcap_collateral(&self, fmspc: Fmspc) -> EnclaveResult<Vec<u8>>;
+
 	fn get_qve_report_on_quote(
 		&self,
 		quote: Vec<u8>,
@@ -116,6 +120,28 @@ pub trait TlsRemoteAttestation {
 		shard: &ShardIdentifier,
 		skip_ra: bool,
 	) -> EnclaveResult<()>;
+
+	/// Request the keys of a started key rotation from a fellow validateer of the shard.
+	fn request_rotated_keys(
+		&self,
+		socket_fd: c_int,
+		sign_type: sgx_quote_sign_type_t,
+		quoting_enclave_target_info: Option<&sgx_target_info_t>,
+		quote_size: Option<&u32>,
+		shard: &ShardIdentifier,
+		skip_ra: bool,
+	) -> EnclaveResult<()>;
+
+	/// Request the state of an additional shard from a fellow validateer, while the worker is running.
+	fn request_shard_provisioning(
+		&self,
+		socket_fd: c_int,
+		sign_type: sgx_quote_sign_type_t,
+		quoting_enclave_target_info: Option<&sgx_target_info_t>,
+		quote_size: Option<&u32>,
+		shard: &ShardIdentifier,
+		skip_ra: bool,
+	) -> EnclaveResult<()>;
 }
 
 #[cfg(feature = "implement-ffi")]
@@ -124,6 +150,7 @@ mod impl_ffi {
 	use crate::{error::Error, utils, Enclave, EnclaveResult};
 	use codec::Encode;
 	use frame_support::ensure;
+	use itp_attestation_handler::SgxQlQveCollateral;
 	use itp_enclave_api_ffi as ffi;
 	use itp_settings::worker::EXTRINSIC_MAX_SIZE;
 	use itp_types::ShardIdentifier;
@@ -599,6 +626,18 @@ mod impl_ffi {
 			Ok(quote_vec)
 		}
 
+		fn get_encoded_dcap_collateral(&self, fmspc: Fmspc) -> EnclaveResult<Vec<u8>> {
+			let collateral_ptr = RemoteAttestation::get_dcap_collateral(self, fmspc)?;
+			// SAFETY: `get_dcap_collateral` only returns non-null collateral, initialized by the quote library.
+			let collateral = unsafe { SgxQlQveCollateral::from_c_type(&*collateral_ptr) };
+			let free_status = unsafe { sgx_ql_free_quote_verification_collateral(collateral_ptr) };
+			ensure!(
+				free_status == sgx_quote3_error_t::SGX_QL_SUCCESS,
+				Error::SgxQuote(free_status)
+			);
+			Ok(collateral.encode())
+		}
+
 		fn get_qve_report_on_quote(
 			&self,
 			quote: Vec<u8>,
@@ -804,6 +843,72 @@ mod impl_ffi {
 
 			Ok(())
 		}
+
+		fn request_rotated_keys(
+			&self,
+			socket_fd: c_int,
+			sign_type: sgx_quote_sign_type_t,
+			quoting_enclave_target_info: Option<&sgx_target_info_t>,
+			quote_size: Option<&u32>,
+			shard: &ShardIdentifier,
+			skip_ra: bool,
+		) -> EnclaveResult<()> {
+			let mut retval = sgx_status_t::SGX_SUCCESS;
+
+			let encoded_shard = shard.encode();
+
+			let result = unsafe {
+				ffi::request_rotated_keys(
+					self.eid,
+					&mut retval,
+					socket_fd,
+					sign_type,
+					quoting_enclave_target_info,
+					quote_size,
+					encoded_shard.as_ptr(),
+					encoded_shard.len() as u32,
+					skip_ra.into(),
+				)
+			};
+
+			ensure!(result == sgx_status_t::SGX_SUCCESS, Error::Sgx(result));
+			ensure!(retval == sgx_status_t::SGX_SUCCESS, Error::Sgx(retval));
+
+			Ok(())
+		}
+
+		fn request_shard_provisioning(
+			&self,
+			socket_fd: c_int,
+			sign_type: sgx_quote_sign_type_t,
+			quoting_enclave_target_info: Option<&sgx_target_info_t>,
+			quote_size: Option<&u32>,
+			shard: &ShardIdentifier,
+			skip_ra: bool,
+		) -> EnclaveResult<()> {
+			let mut retval = sgx_status_t::SGX_SUCCESS;
+
+			let encoded_shard = shard.encode();
+
+			let result = unsafe {
+				ffi::request_shard_provisioning(
+					self.eid,
+					&mut retval,
+					socket_fd,
+					sign_type,
+					quoting_enclave_target_info,
+					quote_size,
+					encoded_shard.as_ptr(),
+					encoded_shard.len() as u32,
+					skip_ra.into(),
+				)
+			};
+
+			ensure!(result == sgx_status_t::SGX_SUCCESS, Error::Sgx(result));
+			ensure!(retval == sgx_status_t::SGX_SUCCESS, Error::Sgx(retval));
+
+			Ok(())
+		}
 	}
 
 	fn create_system_path(file_name: &str) -> String {
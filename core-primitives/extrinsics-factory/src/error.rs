@@ -30,6 +30,8 @@ pub enum Error {
 	NonceCache(#[from] itp_nonce_cache::error::Error),
 	#[error("Node API error: {0:?}")]
 	NodeMetadataProvider(#[from] itp_node_api::metadata::provider::Error),
+	#[error("Node metadata error: {0:?}")]
+	NodeMetadata(itp_node_api::metadata::Error),
 	#[error("SGX error, status: {0}")]
 	Sgx(sgx_status_t),
 	#[error(transparent)]
@@ -42,6 +44,12 @@ impl From<sgx_status_t> for Error {
 	}
 }
 
+impl From<itp_node_api::metadata::Error> for Error {
+	fn from(e: itp_node_api::metadata::Error) -> Self {
+		Self::NodeMetadata(e)
+	}
+}
+
 impl From<codec::Error> for Error {
 	fn from(e: codec::Error) -> Self {
 		Self::Other(format!("{:?}", e).into())
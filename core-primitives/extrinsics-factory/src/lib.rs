@@ -48,6 +48,7 @@ use std::{sync::Arc, vec::Vec};
 use substrate_api_client::ac_compose_macros::compose_extrinsic_offline;
 
 pub mod error;
+pub mod vault_withdrawals;
 
 #[cfg(feature = "mocks")]
 pub mod mock;
@@ -0,0 +1,271 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Settlement of all withdrawals from a shard vault with a single parentchain call.
+//!
+//! The transfers are composed into `proxy(vault, None, force_batch([transfers.., remark]))`,
+//! where `remark` is a `system.remark_with_event` carrying the binary Merkle root over the
+//! encoded transfer calls. `force_batch` keeps going if a transfer fails, so a single failing
+//! withdrawal (e.g. below the existential deposit) doesn't hold back all the others. This is
+//! the same outcome as settling every withdrawal with its own extrinsic. Since the transfers are
+//! part of the extrinsic on chain, a beneficiary can rebuild the leaves and prove the inclusion of
+//! their withdrawal against the emitted root.
+
+use crate::{error::Result, ExtrinsicsFactory};
+use itp_binary_merkle_tree::{merkle_proof, merkle_root, MerkleProofWithCodec};
+use itp_node_api::{
+	api_client::SignExtrinsic,
+	metadata::{
+		pallet_proxy::ProxyCallIndexes, pallet_system::SystemCallIndexes,
+		pallet_utility::UtilityCallIndexes, provider::AccessNodeMetadata, NodeMetadata,
+	},
+};
+use itp_nonce_cache::MutateNonce;
+use itp_types::{
+	parentchain::{
+		AccountId, Address, GenericMortality, ParentchainCall, ParentchainId, ProxyType,
+	},
+	OpaqueCall,
+};
+use sp_core::H256;
+use sp_runtime::traits::Keccak256;
+use std::vec::Vec;
+
+/// Settles vault withdrawals in batches instead of one extrinsic per withdrawal.
+pub trait BatchVaultWithdrawals {
+	/// Returns the calls to send to `parentchain_id`, where all withdrawals from the same vault
+	/// are merged into one batch. The batch takes the place of the first withdrawal it contains.
+	fn batch_vault_withdrawals(
+		&self,
+		calls: &[ParentchainCall],
+		parentchain_id: ParentchainId,
+	) -> Result<Vec<(OpaqueCall, GenericMortality)>>;
+}
+
+impl<Signer, NonceCache, NodeMetadataRepository> BatchVaultWithdrawals
+	for ExtrinsicsFactory<Signer, NonceCache, NodeMetadataRepository>
+where
+	Signer: SignExtrinsic<AccountId>,
+	NonceCache: MutateNonce,
+	NodeMetadataRepository: AccessNodeMetadata<MetadataType = NodeMetadata>,
+{
+	fn batch_vault_withdrawals(
+		&self,
+		calls: &[ParentchainCall],
+		parentchain_id: ParentchainId,
+	) -> Result<Vec<(OpaqueCall, GenericMortality)>> {
+		self.node_metadata_repository
+			.get_from_metadata(|m| batch_vault_withdrawals(m, calls, parentchain_id))?
+	}
+}
+
+/// Merkle root over the given transfer calls, as published in the batch settling them.
+pub fn withdrawals_merkle_root(transfers: &[OpaqueCall]) -> H256 {
+	merkle_root::<Keccak256, _>(transfers.iter().map(|transfer| transfer.0.as_slice()))
+}
+
+/// Proof that the transfer at `index` is part of the batch with root
+/// [`withdrawals_merkle_root`] of `transfers`.
+pub fn withdrawal_merkle_proof(
+	transfers: &[OpaqueCall],
+	index: usize,
+) -> MerkleProofWithCodec<H256, Vec<u8>> {
+	merkle_proof::<Keccak256, _, _>(transfers.iter().map(|transfer| transfer.0.clone()), index)
+		.into()
+}
+
+fn batch_vault_withdrawals<M>(
+	metadata: &M,
+	calls: &[ParentchainCall],
+	parentchain_id: ParentchainId,
+) -> Result<Vec<(OpaqueCall, GenericMortality)>>
+where
+	M: ProxyCallIndexes + UtilityCallIndexes + SystemCallIndexes,
+{
+	// Withdrawals grouped by vault, in the order of their first occurrence.
+	let mut batches: Vec<(&AccountId, Vec<OpaqueCall>)> = Vec::new();
+	for (vault, transfer, _) in
+		calls.iter().filter_map(|call| call.as_vault_withdrawal_for(parentchain_id))
+	{
+		let index = match batches.iter().position(|(v, _)| *v == vault) {
+			Some(index) => index,
+			None => {
+				batches.push((vault, Vec::new()));
+				batches.len() - 1
+			},
+		};
+		batches[index].1.push(transfer.clone());
+	}
+
+	let mut settled: Vec<&AccountId> = Vec::new();
+	let mut opaque_calls = Vec::new();
+	for call in calls {
+		let (vault, _, mortality) = match call.as_vault_withdrawal_for(parentchain_id) {
+			Some(withdrawal) => withdrawal,
+			None => {
+				opaque_calls.extend(call.as_call_for(parentchain_id));
+				continue
+			},
+		};
+		if settled.contains(&vault) {
+			continue
+		}
+		settled.push(vault);
+
+		let transfers = batches
+			.iter()
+			.find(|(v, _)| *v == vault)
+			.map(|(_, transfers)| transfers.as_slice())
+			.unwrap_or_default();
+		if transfers.len() < 2 {
+			opaque_calls.extend(call.as_call_for(parentchain_id));
+			continue
+		}
+		opaque_calls.push((withdrawal_batch_call(metadata, vault, transfers)?, mortality.clone()));
+	}
+	Ok(opaque_calls)
+}
+
+fn withdrawal_batch_call<M>(
+	metadata: &M,
+	vault: &AccountId,
+	transfers: &[OpaqueCall],
+) -> Result<OpaqueCall>
+where
+	M: ProxyCallIndexes + UtilityCallIndexes + SystemCallIndexes,
+{
+	let root = withdrawals_merkle_root(transfers);
+	let remark =
+		OpaqueCall::from_tuple(&(metadata.remark_with_event_call_indexes()?, root.0.to_vec()));
+
+	let mut batch = transfers.to_vec();
+	batch.push(remark);
+	let force_batch = OpaqueCall::from_tuple(&(metadata.force_batch_call_indexes()?, batch));
+
+	Ok(OpaqueCall::from_tuple(&(
+		metadata.proxy_call_indexes()?,
+		Address::from(vault.clone()),
+		None::<ProxyType>,
+		force_batch,
+	)))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use codec::{Compact, Encode};
+	use itp_binary_merkle_tree::verify_proof;
+	use itp_node_api::metadata::metadata_mocks::NodeMetadataMock;
+
+	const PARENTCHAIN: ParentchainId = ParentchainId::Integritee;
+
+	fn vault(seed: u8) -> AccountId {
+		AccountId::from([seed; 32])
+	}
+
+	fn transfer(amount: u128) -> OpaqueCall {
+		OpaqueCall::from_tuple(&([10u8, 3u8], Address::from(vault(42)), Compact(amount)))
+	}
+
+	fn withdrawal(vault: AccountId, transfer: OpaqueCall) -> ParentchainCall {
+		ParentchainCall::VaultWithdrawal {
+			parentchain_id: PARENTCHAIN,
+			call: OpaqueCall::from_tuple(&(vault.clone(), &transfer)),
+			vault,
+			transfer,
+			mortality: GenericMortality::immortal(),
+		}
+	}
+
+	fn other_call(byte: u8) -> ParentchainCall {
+		ParentchainCall::new(PARENTCHAIN, OpaqueCall(vec![byte]), GenericMortality::immortal())
+	}
+
+	#[test]
+	fn withdrawals_from_one_vault_are_settled_in_one_batch() {
+		let metadata = NodeMetadataMock::new();
+		let transfers = vec![transfer(1), transfer(2), transfer(3)];
+		let calls: Vec<_> = transfers.iter().map(|t| withdrawal(vault(1), t.clone())).collect();
+
+		let batched = batch_vault_withdrawals(&metadata, &calls, PARENTCHAIN).unwrap();
+
+		assert_eq!(batched.len(), 1);
+		let remark = OpaqueCall::from_tuple(&(
+			metadata.remark_with_event_call_indexes().unwrap(),
+			withdrawals_merkle_root(&transfers).0.to_vec(),
+		));
+		let mut expected_batch = transfers.clone();
+		expected_batch.push(remark);
+		let expected = OpaqueCall::from_tuple(&(
+			metadata.proxy_call_indexes().unwrap(),
+			Address::from(vault(1)),
+			None::<ProxyType>,
+			OpaqueCall::from_tuple(&(metadata.force_batch_call_indexes().unwrap(), expected_batch)),
+		));
+		assert_eq!(batched[0].0, expected);
+	}
+
+	#[test]
+	fn single_withdrawal_and_other_calls_are_kept_in_order() {
+		let metadata = NodeMetadataMock::new();
+		let calls = vec![
+			other_call(1),
+			withdrawal(vault(1), transfer(1)),
+			other_call(2),
+			withdrawal(vault(2), transfer(2)),
+			withdrawal(vault(2), transfer(3)),
+			ParentchainCall::new(
+				ParentchainId::Target(0),
+				OpaqueCall(vec![3]),
+				GenericMortality::immortal(),
+			),
+		];
+
+		let batched: Vec<OpaqueCall> = batch_vault_withdrawals(&metadata, &calls, PARENTCHAIN)
+			.unwrap()
+			.into_iter()
+			.map(|(call, _)| call)
+			.collect();
+
+		assert_eq!(batched.len(), 4);
+		assert_eq!(batched[0], OpaqueCall(vec![1]));
+		assert_eq!(batched[1], calls[1].as_opaque_call_for(PARENTCHAIN).unwrap());
+		assert_eq!(batched[2], OpaqueCall(vec![2]));
+		assert_eq!(
+			batched[3],
+			withdrawal_batch_call(&metadata, &vault(2), &[transfer(2), transfer(3)]).unwrap()
+		);
+	}
+
+	#[test]
+	fn withdrawal_proof_verifies_against_published_root() {
+		let transfers = vec![transfer(1), transfer(2), transfer(3)];
+		let root = withdrawals_merkle_root(&transfers);
+
+		let proof = withdrawal_merkle_proof(&transfers, 1);
+
+		assert_eq!(proof.root, root);
+		assert_eq!(proof.leaf, transfers[1].encode());
+		assert!(verify_proof::<Keccak256, _, _>(
+			&root,
+			proof.proof,
+			proof.number_of_leaves as usize,
+			proof.leaf_index as usize,
+			&proof.leaf,
+		));
+	}
+}
@@ -30,6 +30,8 @@ pub enum Error {
 	Sgx(sgx_status_t),
 	#[error("Queue lock is poisoned")]
 	PoisonedLock,
+	#[error("Queue is full, it holds at most {0} items")]
+	QueueFull(usize),
 	#[error(transparent)]
 	Other(#[from] Box<dyn std::error::Error + Sync + Send + 'static>),
 }
@@ -34,30 +34,45 @@ use std::{collections::VecDeque, vec::Vec};
 /// Uses RwLock internally to guard against concurrent access and ensure all operations are atomic.
 pub struct ImportQueue<Item> {
 	queue: RwLock<VecDeque<Item>>,
+	max_len: Option<usize>,
 }
 
 impl<Item> ImportQueue<Item> {
+	/// Queue which rejects any items pushed while it holds `max_len` items.
+	pub fn bounded(max_len: usize) -> Self {
+		ImportQueue { queue: Default::default(), max_len: Some(max_len) }
+	}
+
 	pub fn is_empty(&self) -> Result<bool> {
 		let queue_lock = self.queue.read().map_err(|_| Error::PoisonedLock)?;
 		Ok(queue_lock.is_empty())
 	}
+
+	fn ensure_capacity(&self, queue: &VecDeque<Item>, additional: usize) -> Result<()> {
+		match self.max_len {
+			Some(max_len) if queue.len() + additional > max_len => Err(Error::QueueFull(max_len)),
+			_ => Ok(()),
+		}
+	}
 }
 
 impl<Item> Default for ImportQueue<Item> {
 	fn default() -> Self {
-		ImportQueue { queue: Default::default() }
+		ImportQueue { queue: Default::default(), max_len: None }
 	}
 }
 
 impl<Item> PushToQueue<Item> for ImportQueue<Item> {
 	fn push_multiple(&self, items: Vec<Item>) -> Result<()> {
 		let mut queue_lock = self.queue.write().map_err(|_| Error::PoisonedLock)?;
+		self.ensure_capacity(&queue_lock, items.len())?;
 		queue_lock.extend(items);
 		Ok(())
 	}
 
 	fn push_single(&self, item: Item) -> Result<()> {
 		let mut queue_lock = self.queue.write().map_err(|_| Error::PoisonedLock)?;
+		self.ensure_capacity(&queue_lock, 1)?;
 		queue_lock.push_back(item);
 		Ok(())
 	}
@@ -146,6 +161,19 @@ mod tests {
 		assert!(queue.is_empty().unwrap());
 	}
 
+	#[test]
+	fn bounded_queue_rejects_items_beyond_its_capacity() {
+		let queue = ImportQueue::<TestBlock>::bounded(3);
+		queue.push_multiple(vec![1, 2]).unwrap();
+
+		assert_matches!(queue.push_multiple(vec![3, 4]), Err(Error::QueueFull(3)));
+		queue.push_single(3).unwrap();
+		assert_matches!(queue.push_single(4), Err(Error::QueueFull(3)));
+
+		assert_eq!(queue.pop_all().unwrap(), vec![1, 2, 3]);
+		queue.push_single(4).unwrap();
+	}
+
 	#[test]
 	fn pop_all_on_default_returns_empty_vec() {
 		let queue = ImportQueue::<TestBlock>::default();
@@ -20,10 +20,11 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use crate::{
-	error::Result, pallet_balances::BalancesCallIndexes,
+	error::Result, pallet_assets::AssetsCallIndexes, pallet_balances::BalancesCallIndexes,
 	pallet_enclave_bridge::EnclaveBridgeCallIndexes, pallet_proxy::ProxyCallIndexes,
-	pallet_sidechain::SidechainCallIndexes, pallet_teerex::TeerexCallIndexes,
-	pallet_timestamp::TimestampCallIndexes,
+	pallet_sidechain::SidechainCallIndexes, pallet_system::SystemCallIndexes,
+	pallet_teerex::TeerexCallIndexes, pallet_timestamp::TimestampCallIndexes,
+	pallet_utility::UtilityCallIndexes,
 };
 use codec::{Decode, Encode};
 use sp_core::storage::StorageKey;
@@ -32,14 +33,17 @@ pub use crate::error::Error;
 pub use itp_api_client_types::{Metadata, MetadataError};
 
 pub mod error;
+pub mod pallet_assets;
 pub mod pallet_balances;
 pub mod pallet_enclave_bridge;
 pub mod pallet_proxy;
 pub mod pallet_sidechain;
+pub mod pallet_system;
 pub mod pallet_teeracle;
 pub mod pallet_teerex;
 
 pub mod pallet_timestamp;
+pub mod pallet_utility;
 
 #[cfg(feature = "mocks")]
 pub mod metadata_mocks;
@@ -50,7 +54,10 @@ pub trait NodeMetadataTrait:
 	+ SidechainCallIndexes
 	+ ProxyCallIndexes
 	+ BalancesCallIndexes
+	+ AssetsCallIndexes
 	+ TimestampCallIndexes
+	+ SystemCallIndexes
+	+ UtilityCallIndexes
 {
 }
 impl<
@@ -59,7 +66,10 @@ impl<
 			+ SidechainCallIndexes
 			+ ProxyCallIndexes
 			+ BalancesCallIndexes
-			+ TimestampCallIndexes,
+			+ AssetsCallIndexes
+			+ TimestampCallIndexes
+			+ SystemCallIndexes
+			+ UtilityCallIndexes,
 	> NodeMetadataTrait for T
 {
 }
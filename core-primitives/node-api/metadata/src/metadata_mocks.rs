@@ -16,10 +16,11 @@
 */
 
 use crate::{
-	error::Result, pallet_balances::BalancesCallIndexes,
+	error::Result, pallet_assets::AssetsCallIndexes, pallet_balances::BalancesCallIndexes,
 	pallet_enclave_bridge::EnclaveBridgeCallIndexes, pallet_proxy::ProxyCallIndexes,
-	pallet_sidechain::SidechainCallIndexes, pallet_teerex::TeerexCallIndexes,
-	pallet_timestamp::TimestampCallIndexes,
+	pallet_sidechain::SidechainCallIndexes, pallet_system::SystemCallIndexes,
+	pallet_teerex::TeerexCallIndexes, pallet_timestamp::TimestampCallIndexes,
+	pallet_utility::UtilityCallIndexes,
 };
 use codec::{Decode, Encode};
 
@@ -57,8 +58,15 @@ pub struct NodeMetadataMock {
 	transfer: u8,
 	transfer_keep_alive: u8,
 	transfer_allow_death: u8,
+	assets_module: u8,
+	assets_transfer: u8,
+	assets_transfer_keep_alive: u8,
 	timestamp_module: u8,
 	timestamp_set: u8,
+	system_module: u8,
+	remark_with_event: u8,
+	utility_module: u8,
+	force_batch: u8,
 	runtime_spec_version: u32,
 	runtime_transaction_version: u32,
 }
@@ -88,8 +96,15 @@ impl NodeMetadataMock {
 			transfer: 7u8,
 			transfer_keep_alive: 3u8,
 			transfer_allow_death: 0u8,
+			assets_module: 52u8,
+			assets_transfer: 8u8,
+			assets_transfer_keep_alive: 9u8,
 			timestamp_module: 3,
 			timestamp_set: 0,
+			system_module: 0,
+			remark_with_event: 7,
+			utility_module: 9,
+			force_batch: 4,
 			runtime_spec_version: 25,
 			runtime_transaction_version: 4,
 		}
@@ -174,8 +189,30 @@ impl BalancesCallIndexes for NodeMetadataMock {
 	}
 }
 
+impl AssetsCallIndexes for NodeMetadataMock {
+	fn assets_transfer_call_indexes(&self) -> Result<[u8; 2]> {
+		Ok([self.assets_module, self.assets_transfer])
+	}
+
+	fn assets_transfer_keep_alive_call_indexes(&self) -> Result<[u8; 2]> {
+		Ok([self.assets_module, self.assets_transfer_keep_alive])
+	}
+}
+
 impl TimestampCallIndexes for NodeMetadataMock {
 	fn timestamp_set_call_indexes(&self) -> Result<[u8; 2]> {
 		Ok([self.timestamp_module, self.timestamp_set])
 	}
 }
+
+impl SystemCallIndexes for NodeMetadataMock {
+	fn remark_with_event_call_indexes(&self) -> Result<[u8; 2]> {
+		Ok([self.system_module, self.remark_with_event])
+	}
+}
+
+impl UtilityCallIndexes for NodeMetadataMock {
+	fn force_batch_call_indexes(&self) -> Result<[u8; 2]> {
+		Ok([self.utility_module, self.force_batch])
+	}
+}
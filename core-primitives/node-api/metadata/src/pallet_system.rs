@@ -31,3 +31,13 @@ impl SystemStorageIndexes for NodeMetadata {
 		self.storage_map_key(SYSTEM, "Account", index)
 	}
 }
+
+pub trait SystemCallIndexes {
+	fn remark_with_event_call_indexes(&self) -> Result<[u8; 2]>;
+}
+
+impl SystemCallIndexes for NodeMetadata {
+	fn remark_with_event_call_indexes(&self) -> Result<[u8; 2]> {
+		self.call_indexes(SYSTEM, "remark_with_event")
+	}
+}
@@ -58,6 +58,9 @@ pub trait EnclaveAttestationOCallApi: Clone + Send + Sync {
 
 	fn get_dcap_quote(&self, report: sgx_report_t, quote_size: u32) -> SgxResult<Vec<u8>>;
 
+	/// Fetch the SCALE encoded quote verification collateral of the platform `fmspc`.
+	fn get_dcap_collateral(&self, fmspc: [u8; 6]) -> SgxResult<Vec<u8>>;
+
 	fn get_qve_report_on_quote(
 		&self,
 		quote: Vec<u8>,
@@ -133,6 +136,24 @@ pub trait EnclaveSidechainOCallApi: Clone + Send + Sync {
 		signed_blocks: Vec<SignedSidechainBlock>,
 	) -> SgxResult<()>;
 
+	/// Gossip our finality votes to the peer validateers.
+	fn propose_finality_votes<SignedFinalityVote: Encode>(
+		&self,
+		votes: Vec<SignedFinalityVote>,
+	) -> SgxResult<()>;
+
+	/// Store the justifications of newly finalized blocks in the sidechain storage.
+	fn store_sidechain_justifications<Justification: Encode>(
+		&self,
+		justifications: Vec<Justification>,
+	) -> SgxResult<()>;
+
+	/// Fetch the justification of the latest finalized block of a shard from the sidechain storage.
+	fn fetch_sidechain_finalized_head<Justification: Decode>(
+		&self,
+		shard_identifier: ShardIdentifier,
+	) -> SgxResult<Option<Justification>>;
+
 	fn fetch_sidechain_blocks_from_peer<SignedSidechainBlock: Decode>(
 		&self,
 		last_imported_block_hash: BlockHash,
@@ -31,6 +31,24 @@ impl<S: StoragePrefix> EnclaveBridgeStorageKeys for S {
 	}
 }
 
+pub struct SystemStorage;
+
+impl StoragePrefix for SystemStorage {
+	fn prefix() -> &'static str {
+		"System"
+	}
+}
+
+pub trait SystemStorageKeys {
+	fn account<T: Encode>(account: T) -> Vec<u8>;
+}
+
+impl<S: StoragePrefix> SystemStorageKeys for S {
+	fn account<T: Encode>(account: T) -> Vec<u8> {
+		storage_map_key(Self::prefix(), "Account", &account, &StorageHasher::Blake2_128Concat)
+	}
+}
+
 pub struct TeeRexStorage;
 
 impl StoragePrefix for TeeRexStorage {
@@ -45,12 +45,16 @@ pub mod files {
 	/// Path to the light-client db for the Integritee parentchain.
 	pub const INTEGRITEE_PARENTCHAIN_LIGHT_CLIENT_DB_PATH: &str = "integritee_lcdb";
 
-	/// Path to the light-client db for the Target A parentchain.
+	/// Path to the light-client db for the first target parentchain (formerly Target A).
 	pub const TARGET_A_PARENTCHAIN_LIGHT_CLIENT_DB_PATH: &str = "target_a_lcdb";
 
-	/// Path to the light-client db for the Target B parentchain.
+	/// Path to the light-client db for the second target parentchain (formerly Target B).
 	pub const TARGET_B_PARENTCHAIN_LIGHT_CLIENT_DB_PATH: &str = "target_b_lcdb";
 
+	/// Path prefix of the light-client db for any further target parentchain.
+	/// The target parentchain index is appended.
+	pub const TARGET_PARENTCHAIN_LIGHT_CLIENT_DB_PATH_PREFIX: &str = "target_lcdb_";
+
 	pub const RA_DUMP_CERT_DER_FILE: &str = "ra_dump_cert.der";
 
 	// used by worker and enclave
@@ -68,6 +72,8 @@ pub mod files {
 
 	pub const SPID_MIN_LENGTH: usize = 32;
 	pub const STATE_SNAPSHOTS_CACHE_SIZE: usize = 4;
+	/// Every n-th state snapshot is persisted as full checkpoint, the ones in between as state diffs.
+	pub const STATE_SNAPSHOTS_CHECKPOINT_INTERVAL: usize = 8;
 }
 
 /// Settings concerning the worker
@@ -95,10 +101,25 @@ pub mod sidechain {
 	use core::time::Duration;
 
 	pub static SLOT_DURATION: Duration = Duration::from_millis(1000);
+	/// Settle all withdrawals from a shard vault within a sidechain block with a single
+	/// proxied `utility.force_batch` call, instead of one parentchain extrinsic per withdrawal.
+	/// Enabled with the `batch-vault-withdrawals` feature.
+	pub const BATCH_VAULT_WITHDRAWALS: bool = cfg!(feature = "batch-vault-withdrawals");
+	/// Max number of finality votes of peers waiting to be counted. Further votes are dropped
+	/// until the queue is emptied in the next slot.
+	pub const MAX_QUEUED_FINALITY_VOTES: usize = 1024;
+	/// Number of sidechain blocks between the start of a key rotation and the block in which all
+	/// validateers switch to the rotated keys. Fellow validateers have to obtain the rotated keys
+	/// in the meantime.
+	pub const KEY_ROTATION_ACTIVATION_DELAY: u64 = 3600;
 }
 
 /// Settings concerning the enclave
-pub mod enclave {}
+pub mod enclave {
+	use core::time::Duration;
+	// Trusted operations encrypted with a rotated shielding key are still accepted for this period
+	pub static SHIELDING_KEY_GRACE_PERIOD: Duration = Duration::from_secs(86400);
+}
 
 /// Settings for the Teeracle
 pub mod teeracle {
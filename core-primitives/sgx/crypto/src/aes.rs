@@ -28,6 +28,7 @@ use ofb::{
 use std::{
 	convert::{TryFrom, TryInto},
 	path::PathBuf,
+	vec::Vec,
 };
 
 type AesOfb = Ofb<Aes128>;
@@ -35,6 +36,9 @@ type AesOfb = Ofb<Aes128>;
 /// File name of the sealed AES key data.
 pub const AES_KEY_FILE_AND_INIT_V: &str = "aes_key_and_iv_sealed_data.bin";
 
+/// Legacy AES-128-OFB state key. Does not authenticate the encrypted data.
+///
+/// Only kept to migrate existing state to [`crate::AesGcm`].
 #[derive(Debug, Default, Encode, Decode, Clone, Copy, PartialEq, Eq)]
 pub struct Aes {
 	pub key: [u8; 16],
@@ -65,11 +69,11 @@ impl AesSeal {
 impl StateCrypto for Aes {
 	type Error = Error;
 
-	fn encrypt(&self, data: &mut [u8]) -> Result<()> {
+	fn encrypt(&self, data: &mut Vec<u8>) -> Result<()> {
 		de_or_encrypt(self, data)
 	}
 
-	fn decrypt(&self, data: &mut [u8]) -> Result<()> {
+	fn decrypt(&self, data: &mut Vec<u8>) -> Result<()> {
 		de_or_encrypt(self, data)
 	}
 }
@@ -0,0 +1,455 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Authenticated state encryption with AES-128-GCM.
+//!
+//! A ciphertext consists of [`AEAD_CIPHERTEXT_MAGIC`], the [`AEAD_CIPHERTEXT_VERSION`] byte,
+//! a random nonce, the encrypted data and the authentication tag. The header (magic, version
+//! and nonce) is authenticated as associated data.
+
+use crate::{
+	aes::Aes,
+	error::{Error, Result},
+	traits::StateCrypto,
+};
+use aes_gcm::{
+	aead::{generic_array::GenericArray, AeadInPlace, NewAead},
+	Aes128Gcm,
+};
+use codec::{Decode, Encode};
+use sp_core::blake2_256;
+use std::{path::PathBuf, vec::Vec};
+
+/// File name of the sealed AES-GCM key.
+pub const AES_GCM_KEY_FILE: &str = "aes_gcm_key_sealed_data.bin";
+
+/// Magic bytes at the start of every AES-GCM ciphertext.
+pub const AEAD_CIPHERTEXT_MAGIC: [u8; 4] = *b"itae";
+
+/// Version of the ciphertext format. Needs to be increased whenever the format changes.
+pub const AEAD_CIPHERTEXT_VERSION: u8 = 1;
+
+const NONCE_LENGTH: usize = 12;
+const TAG_LENGTH: usize = 16;
+const HEADER_LENGTH: usize = AEAD_CIPHERTEXT_MAGIC.len() + 1 + NONCE_LENGTH;
+
+#[derive(Debug, Default, Encode, Decode, Clone, Copy, PartialEq, Eq)]
+pub struct AesGcm {
+	pub key: [u8; 16],
+	/// The legacy AES-OFB key this key was derived from, if any. Only used to decrypt
+	/// sidechain blocks of validateers that still use the legacy state encryption, and
+	/// cleared once the first block with an authenticated payload has been imported.
+	pub legacy_key: Option<Aes>,
+}
+
+impl AesGcm {
+	pub fn new(key: [u8; 16]) -> Self {
+		Self { key, legacy_key: None }
+	}
+
+	/// Creates a fresh random key.
+	pub fn random() -> Result<Self> {
+		Ok(Self::new(random_bytes::<16>()?))
+	}
+
+	/// Deterministically derives the AES-GCM key from a legacy AES-OFB key.
+	///
+	/// All validateers of a shard share the legacy key, so they all end up with the same
+	/// AES-GCM key without having to provision it again.
+	pub fn from_legacy(legacy_key: &Aes) -> Self {
+		let seed = blake2_256(&(b"aes-gcm", legacy_key).encode());
+		let mut key = [0u8; 16];
+		key.copy_from_slice(&seed[..16]);
+		Self { key, legacy_key: Some(*legacy_key) }
+	}
+
+	fn cipher(&self) -> Aes128Gcm {
+		Aes128Gcm::new(GenericArray::from_slice(&self.key))
+	}
+}
+
+/// Checks if `data` starts with a valid AES-GCM ciphertext header.
+pub fn is_aead_ciphertext(data: &[u8]) -> bool {
+	data.len() >= HEADER_LENGTH + TAG_LENGTH
+		&& data[..AEAD_CIPHERTEXT_MAGIC.len()] == AEAD_CIPHERTEXT_MAGIC
+		&& data[AEAD_CIPHERTEXT_MAGIC.len()] == AEAD_CIPHERTEXT_VERSION
+}
+
+impl StateCrypto for AesGcm {
+	type Error = Error;
+
+	fn encrypt(&self, data: &mut Vec<u8>) -> Result<()> {
+		let mut header = Vec::with_capacity(HEADER_LENGTH);
+		header.extend_from_slice(&AEAD_CIPHERTEXT_MAGIC);
+		header.push(AEAD_CIPHERTEXT_VERSION);
+		header.extend_from_slice(&random_bytes::<NONCE_LENGTH>()?);
+
+		let tag = self
+			.cipher()
+			.encrypt_in_place_detached(
+				GenericArray::from_slice(&header[HEADER_LENGTH - NONCE_LENGTH..]),
+				&header,
+				data,
+			)
+			.map_err(|_| Error::AeadEncryption)?;
+
+		let mut ciphertext = header;
+		ciphertext.reserve(data.len() + TAG_LENGTH);
+		ciphertext.extend_from_slice(data);
+		ciphertext.extend_from_slice(&tag);
+		*data = ciphertext;
+		Ok(())
+	}
+
+	fn decrypt(&self, data: &mut Vec<u8>) -> Result<()> {
+		let magic_length = AEAD_CIPHERTEXT_MAGIC.len();
+		if data.len() < HEADER_LENGTH + TAG_LENGTH || data[..magic_length] != AEAD_CIPHERTEXT_MAGIC
+		{
+			return Err(Error::InvalidCiphertextHeader)
+		}
+		let version = data[magic_length];
+		if version != AEAD_CIPHERTEXT_VERSION {
+			return Err(Error::UnsupportedCiphertextVersion(version))
+		}
+
+		let mut plaintext = data.split_off(HEADER_LENGTH);
+		let tag = plaintext.split_off(plaintext.len() - TAG_LENGTH);
+		let header = data.as_slice();
+
+		self.cipher()
+			.decrypt_in_place_detached(
+				GenericArray::from_slice(&header[HEADER_LENGTH - NONCE_LENGTH..]),
+				header,
+				&mut plaintext,
+				GenericArray::from_slice(&tag),
+			)
+			.map_err(|_| Error::AeadAuthentication)?;
+
+		*data = plaintext;
+		Ok(())
+	}
+
+	fn decrypt_legacy_compatible(&self, data: &mut Vec<u8>) -> Result<()> {
+		match self.legacy_key {
+			Some(legacy_key) if !is_aead_ciphertext(data) => legacy_key.decrypt(data),
+			_ => self.decrypt(data),
+		}
+	}
+
+	fn is_legacy_ciphertext(&self, data: &[u8]) -> bool {
+		self.legacy_key.is_some() && !is_aead_ciphertext(data)
+	}
+
+	fn without_legacy_fallback(&self) -> Option<Self> {
+		self.legacy_key.map(|_| Self::new(self.key))
+	}
+}
+
+#[cfg(feature = "sgx")]
+pub(crate) fn random_bytes<const N: usize>() -> Result<[u8; N]> {
+	use sgx_rand::{Rng, StdRng};
+
+	let mut bytes = [0u8; N];
+	StdRng::new()?.fill_bytes(&mut bytes);
+	Ok(bytes)
+}
+
+#[cfg(feature = "std")]
+pub(crate) fn random_bytes<const N: usize>() -> Result<[u8; N]> {
+	use rand::RngCore;
+
+	let mut bytes = [0u8; N];
+	rand::thread_rng().fill_bytes(&mut bytes);
+	Ok(bytes)
+}
+
+#[derive(Clone, Debug)]
+pub struct AesGcmSeal {
+	base_path: PathBuf,
+}
+
+impl AesGcmSeal {
+	pub fn new(base_path: PathBuf) -> Self {
+		Self { base_path }
+	}
+
+	pub fn path(&self) -> PathBuf {
+		self.base_path.join(AES_GCM_KEY_FILE)
+	}
+}
+
+pub trait AesGcmSealing {
+	fn unseal_key(&self) -> Result<AesGcm>;
+
+	fn exists(&self) -> bool;
+
+	fn create_sealed_if_absent(&self) -> Result<()>;
+
+	fn create_sealed(&self) -> Result<()>;
+}
+
+#[cfg(feature = "sgx")]
+pub use sgx::*;
+
+#[cfg(feature = "sgx")]
+pub mod sgx {
+	use super::*;
+	use crate::{key_repository::KeyRepository, AesSeal, AesSealing};
+	use itp_sgx_io::{seal, unseal, SealedIO};
+	use log::info;
+	use sgx_rand::{Rng, StdRng};
+	use std::sgxfs::SgxFile;
+
+	/// Gets a repository for an AES-GCM key.
+	///
+	/// If there is no AES-GCM key at `path` yet, but a legacy AES-OFB key, the AES-GCM key is
+	/// derived from the legacy key. Otherwise, a fresh key is initialized.
+	pub fn get_aes_gcm_repository(path: PathBuf) -> Result<KeyRepository<AesGcm, AesGcmSeal>> {
+		let aes_gcm_seal = AesGcmSeal::new(path.clone());
+		let legacy_seal = AesSeal::new(path);
+		if !aes_gcm_seal.exists() && legacy_seal.exists() {
+			info!(
+				"Deriving AES-GCM state key from legacy AES key {}",
+				legacy_seal.path().display()
+			);
+			aes_gcm_seal.seal(&AesGcm::from_legacy(&legacy_seal.unseal_key()?))?;
+		}
+		aes_gcm_seal.create_sealed_if_absent()?;
+		let aes_gcm_key = aes_gcm_seal.unseal_key()?;
+		Ok(KeyRepository::new(aes_gcm_key, aes_gcm_seal.into()))
+	}
+
+	impl AesGcmSealing for AesGcmSeal {
+		fn unseal_key(&self) -> Result<AesGcm> {
+			self.unseal()
+		}
+
+		fn exists(&self) -> bool {
+			SgxFile::open(self.path()).is_ok()
+		}
+
+		fn create_sealed_if_absent(&self) -> Result<()> {
+			if !self.exists() {
+				info!("Keyfile not found, creating new! {}", self.path().display());
+				return self.create_sealed()
+			}
+			Ok(())
+		}
+
+		fn create_sealed(&self) -> Result<()> {
+			let mut key = [0u8; 16];
+			StdRng::new()?.fill_bytes(&mut key);
+
+			Ok(self.seal(&AesGcm::new(key))?)
+		}
+	}
+
+	impl SealedIO for AesGcmSeal {
+		type Error = Error;
+		type Unsealed = AesGcm;
+
+		fn unseal(&self) -> Result<Self::Unsealed> {
+			Ok(unseal(self.path()).map(|b| Decode::decode(&mut b.as_slice()))??)
+		}
+
+		fn seal(&self, unsealed: &Self::Unsealed) -> Result<()> {
+			Ok(unsealed.using_encoded(|bytes| seal(bytes, self.path()))?)
+		}
+	}
+}
+
+#[cfg(feature = "test")]
+pub mod sgx_tests {
+	use super::sgx::*;
+	use crate::{
+		key_repository::AccessKey, AesGcm, AesGcmSeal, AesGcmSealing, AesSeal, AesSealing,
+	};
+	use itp_sgx_temp_dir::TempDir;
+
+	pub fn using_get_aes_gcm_repository_twice_initializes_key_only_once() {
+		let temp_dir =
+			TempDir::with_prefix("using_get_aes_gcm_repository_twice_initializes_key_only_once")
+				.unwrap();
+		let temp_path = temp_dir.path().to_path_buf();
+		let key1 = get_aes_gcm_repository(temp_path.clone()).unwrap().retrieve_key().unwrap();
+		let key2 = get_aes_gcm_repository(temp_path).unwrap().retrieve_key().unwrap();
+		assert_eq!(key1, key2);
+	}
+
+	pub fn get_aes_gcm_repository_derives_key_from_legacy_key() {
+		let temp_dir =
+			TempDir::with_prefix("get_aes_gcm_repository_derives_key_from_legacy_key").unwrap();
+		let temp_path = temp_dir.path().to_path_buf();
+		let legacy_seal = AesSeal::new(temp_path.clone());
+		legacy_seal.create_sealed().unwrap();
+		let legacy_key = legacy_seal.unseal_key().unwrap();
+
+		let key = get_aes_gcm_repository(temp_path.clone()).unwrap().retrieve_key().unwrap();
+
+		assert_eq!(key, AesGcm::from_legacy(&legacy_key));
+		assert!(AesGcmSeal::new(temp_path).exists());
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn key() -> AesGcm {
+		AesGcm::new([3u8; 16])
+	}
+
+	fn encrypted(plaintext: &[u8]) -> Vec<u8> {
+		let mut data = plaintext.to_vec();
+		key().encrypt(&mut data).unwrap();
+		data
+	}
+
+	#[test]
+	fn encrypt_decrypt_roundtrip_works() {
+		let plaintext = b"some sealed state".to_vec();
+		let mut data = encrypted(&plaintext);
+
+		assert!(is_aead_ciphertext(&data));
+		assert_eq!(data.len(), plaintext.len() + HEADER_LENGTH + TAG_LENGTH);
+
+		key().decrypt(&mut data).unwrap();
+		assert_eq!(data, plaintext);
+	}
+
+	#[test]
+	fn encrypting_twice_uses_different_nonces() {
+		assert_ne!(encrypted(b"state"), encrypted(b"state"));
+	}
+
+	#[test]
+	fn decrypting_tampered_ciphertext_fails() {
+		let mut data = encrypted(b"some sealed state");
+		data[HEADER_LENGTH] ^= 1;
+
+		assert!(matches!(key().decrypt(&mut data), Err(Error::AeadAuthentication)));
+	}
+
+	#[test]
+	fn decrypting_with_tampered_header_fails() {
+		let mut data = encrypted(b"some sealed state");
+		data[HEADER_LENGTH - 1] ^= 1;
+
+		assert!(matches!(key().decrypt(&mut data), Err(Error::AeadAuthentication)));
+	}
+
+	#[test]
+	fn decrypting_with_wrong_key_fails() {
+		let mut data = encrypted(b"some sealed state");
+
+		assert!(matches!(
+			AesGcm::new([4u8; 16]).decrypt(&mut data),
+			Err(Error::AeadAuthentication)
+		));
+	}
+
+	#[test]
+	fn decrypting_without_header_fails() {
+		let mut data = vec![1u8; 64];
+
+		assert!(!is_aead_ciphertext(&data));
+		assert!(matches!(key().decrypt(&mut data), Err(Error::InvalidCiphertextHeader)));
+	}
+
+	#[test]
+	fn decrypting_unknown_version_fails() {
+		let mut data = encrypted(b"some sealed state");
+		data[AEAD_CIPHERTEXT_MAGIC.len()] = AEAD_CIPHERTEXT_VERSION + 1;
+
+		assert!(matches!(
+			key().decrypt(&mut data),
+			Err(Error::UnsupportedCiphertextVersion(v)) if v == AEAD_CIPHERTEXT_VERSION + 1
+		));
+	}
+
+	#[test]
+	fn legacy_compatible_decrypt_falls_back_to_legacy_key() {
+		let legacy_key = Aes::new([1u8; 16], [2u8; 16]);
+		let key = AesGcm::from_legacy(&legacy_key);
+		let plaintext = b"some sidechain block payload".to_vec();
+
+		let mut legacy_data = plaintext.clone();
+		legacy_key.encrypt(&mut legacy_data).unwrap();
+		key.decrypt_legacy_compatible(&mut legacy_data).unwrap();
+		assert_eq!(legacy_data, plaintext);
+
+		let mut data = plaintext.clone();
+		key.encrypt(&mut data).unwrap();
+		key.decrypt_legacy_compatible(&mut data).unwrap();
+		assert_eq!(data, plaintext);
+	}
+
+	#[test]
+	fn decrypt_does_not_fall_back_to_legacy_key() {
+		let legacy_key = Aes::new([1u8; 16], [2u8; 16]);
+		let mut data = vec![1u8; 64];
+		legacy_key.encrypt(&mut data).unwrap();
+
+		assert!(matches!(
+			AesGcm::from_legacy(&legacy_key).decrypt(&mut data),
+			Err(Error::InvalidCiphertextHeader)
+		));
+	}
+
+	#[test]
+	fn legacy_compatible_decrypt_without_legacy_key_fails_for_legacy_ciphertext() {
+		let mut data = vec![1u8; 64];
+		Aes::new([1u8; 16], [2u8; 16]).encrypt(&mut data).unwrap();
+
+		assert!(matches!(
+			key().decrypt_legacy_compatible(&mut data),
+			Err(Error::InvalidCiphertextHeader)
+		));
+	}
+
+	#[test]
+	fn key_without_legacy_fallback_rejects_legacy_ciphertext() {
+		let legacy_key = Aes::new([1u8; 16], [2u8; 16]);
+		let key = AesGcm::from_legacy(&legacy_key);
+		let mut legacy_data = vec![1u8; 64];
+		legacy_key.encrypt(&mut legacy_data).unwrap();
+		assert!(key.is_legacy_ciphertext(&legacy_data));
+		assert!(!key.is_legacy_ciphertext(&encrypted(b"state")));
+
+		let migrated_key = key.without_legacy_fallback().unwrap();
+
+		assert_eq!(migrated_key, AesGcm::new(key.key));
+		assert!(migrated_key.without_legacy_fallback().is_none());
+		assert!(!migrated_key.is_legacy_ciphertext(&legacy_data));
+		assert!(matches!(
+			migrated_key.decrypt_legacy_compatible(&mut legacy_data),
+			Err(Error::InvalidCiphertextHeader)
+		));
+	}
+
+	#[test]
+	fn key_derived_from_legacy_key_is_deterministic() {
+		let legacy_key = Aes::new([1u8; 16], [2u8; 16]);
+
+		assert_eq!(AesGcm::from_legacy(&legacy_key), AesGcm::from_legacy(&legacy_key));
+		assert_ne!(AesGcm::from_legacy(&legacy_key).key, legacy_key.key);
+		assert_ne!(
+			AesGcm::from_legacy(&legacy_key),
+			AesGcm::from_legacy(&Aes::new([1u8; 16], [3u8; 16]))
+		);
+	}
+}
@@ -26,6 +26,11 @@ use std::prelude::v1::Box;
 pub enum Error {
 	IO(std::io::Error),
 	InvalidNonceKeyLength,
+	InvalidCiphertextHeader,
+	#[from(ignore)]
+	UnsupportedCiphertextVersion(u8),
+	AeadEncryption,
+	AeadAuthentication,
 	Codec(codec::Error),
 	Serialization(serde_json::Error),
 	LockPoisoning,
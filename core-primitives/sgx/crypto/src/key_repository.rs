@@ -47,6 +47,30 @@ pub trait MutateKey<KeyType> {
 	fn update_key(&self, key: KeyType) -> Result<()>;
 }
 
+/// Replace a cryptographic key, while retaining the previous one for a grace period.
+pub trait RotateKey<KeyType> {
+	/// Replaces the current key with `key`. The replaced key remains accessible through
+	/// [`AccessRetiredKey`] until the unix timestamp `retired_until` (in milliseconds).
+	fn rotate_key(&self, key: KeyType, retired_until: u64) -> Result<()>;
+}
+
+/// Access the key that was replaced by the last key rotation.
+pub trait AccessRetiredKey {
+	type KeyType;
+
+	/// Returns the retired key, if there is one that is still valid at `now` (unix timestamp in
+	/// milliseconds).
+	fn retrieve_retired_key(&self, now: u64) -> Result<Option<Self::KeyType>>;
+}
+
+/// A key that was replaced by a key rotation and remains valid until `valid_until`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RetiredKey<KeyType> {
+	pub key: KeyType,
+	/// Unix timestamp in milliseconds.
+	pub valid_until: u64,
+}
+
 /// Repository implementation. Stores a cryptographic key in-memory and in a file backed.
 /// Uses the SealedIO trait for the file backend.
 pub struct KeyRepository<KeyType, SealedIo> {
@@ -99,6 +123,107 @@ where
 	}
 }
 
+/// Key repository that supports key rotation. The retired key is sealed
+/// separately, such that it survives a restart of the enclave within its grace period.
+pub struct RotatingKeyRepository<KeyType, SealedIo, RetiredSealedIo> {
+	key_repository: KeyRepository<KeyType, SealedIo>,
+	retired_key_lock: RwLock<Option<RetiredKey<KeyType>>>,
+	retired_sealed_io: Arc<RetiredSealedIo>,
+}
+
+impl<KeyType, SealedIo, RetiredSealedIo> RotatingKeyRepository<KeyType, SealedIo, RetiredSealedIo>
+where
+	RetiredSealedIo: SealedIO<Unsealed = RetiredKey<KeyType>, Error = crate::error::Error>,
+{
+	pub fn new(
+		key: KeyType,
+		sealed_io: Arc<SealedIo>,
+		retired_sealed_io: Arc<RetiredSealedIo>,
+	) -> Self {
+		let retired_key = retired_sealed_io.unseal().ok();
+		RotatingKeyRepository {
+			key_repository: KeyRepository::new(key, sealed_io),
+			retired_key_lock: RwLock::new(retired_key),
+			retired_sealed_io,
+		}
+	}
+}
+
+impl<KeyType, SealedIo, RetiredSealedIo> AccessKey
+	for RotatingKeyRepository<KeyType, SealedIo, RetiredSealedIo>
+where
+	KeyType: Clone,
+	SealedIo: SealedIO<Unsealed = KeyType, Error = crate::error::Error>,
+{
+	type KeyType = KeyType;
+
+	fn retrieve_key(&self) -> Result<Self::KeyType> {
+		self.key_repository.retrieve_key()
+	}
+}
+
+impl<Pair, SealedIo, RetiredSealedIo> AccessPubkey
+	for RotatingKeyRepository<Pair, SealedIo, RetiredSealedIo>
+where
+	Pair: ToPubkey<Error = crate::error::Error> + Clone,
+	SealedIo: SealedIO<Unsealed = Pair, Error = crate::error::Error>,
+{
+	type KeyType = <Pair as ToPubkey>::Pubkey;
+
+	fn retrieve_pubkey(&self) -> Result<Self::KeyType> {
+		self.key_repository.retrieve_pubkey()
+	}
+}
+
+impl<KeyType, SealedIo, RetiredSealedIo> MutateKey<KeyType>
+	for RotatingKeyRepository<KeyType, SealedIo, RetiredSealedIo>
+where
+	KeyType: Clone,
+	SealedIo: SealedIO<Unsealed = KeyType, Error = crate::error::Error>,
+{
+	fn update_key(&self, key: KeyType) -> Result<()> {
+		self.key_repository.update_key(key)
+	}
+}
+
+impl<KeyType, SealedIo, RetiredSealedIo> RotateKey<KeyType>
+	for RotatingKeyRepository<KeyType, SealedIo, RetiredSealedIo>
+where
+	KeyType: Clone,
+	SealedIo: SealedIO<Unsealed = KeyType, Error = crate::error::Error>,
+	RetiredSealedIo: SealedIO<Unsealed = RetiredKey<KeyType>, Error = crate::error::Error>,
+{
+	fn rotate_key(&self, key: KeyType, retired_until: u64) -> Result<()> {
+		let mut retired_key_lock =
+			self.retired_key_lock.write().map_err(|_| Error::LockPoisoning)?;
+
+		// Seal the retired key first, so that a failure in between never loses the current key.
+		let retired_key =
+			RetiredKey { key: self.key_repository.retrieve_key()?, valid_until: retired_until };
+		self.retired_sealed_io.seal(&retired_key)?;
+		self.key_repository.update_key(key)?;
+
+		*retired_key_lock = Some(retired_key);
+		Ok(())
+	}
+}
+
+impl<KeyType, SealedIo, RetiredSealedIo> AccessRetiredKey
+	for RotatingKeyRepository<KeyType, SealedIo, RetiredSealedIo>
+where
+	KeyType: Clone,
+{
+	type KeyType = KeyType;
+
+	fn retrieve_retired_key(&self, now: u64) -> Result<Option<Self::KeyType>> {
+		let retired_key_lock = self.retired_key_lock.read().map_err(|_| Error::LockPoisoning)?;
+		Ok(retired_key_lock
+			.as_ref()
+			.filter(|retired| now < retired.valid_until)
+			.map(|retired| retired.key.clone()))
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -119,4 +244,61 @@ mod tests {
 		assert_eq!(updated_key, key_repository.retrieve_key().unwrap());
 		assert_eq!(updated_key, seal_mock.unseal().unwrap());
 	}
+
+	#[derive(Default)]
+	struct RetiredAesSealMock {
+		retired: RwLock<Option<RetiredKey<Aes>>>,
+	}
+
+	impl SealedIO for RetiredAesSealMock {
+		type Error = Error;
+		type Unsealed = RetiredKey<Aes>;
+
+		fn unseal(&self) -> Result<Self::Unsealed> {
+			self.retired
+				.read()
+				.unwrap()
+				.clone()
+				.ok_or(Error::Other("No retired key".into()))
+		}
+
+		fn seal(&self, unsealed: &Self::Unsealed) -> Result<()> {
+			*self.retired.write().unwrap() = Some(unsealed.clone());
+			Ok(())
+		}
+	}
+
+	#[test]
+	fn rotate_key_retains_previous_key_until_grace_period_ends() {
+		let seal_mock = Arc::new(AesSealMock::default());
+		let retired_seal_mock = Arc::new(RetiredAesSealMock::default());
+		let key_repository = RotatingKeyRepository::new(
+			seal_mock.unseal().unwrap(),
+			seal_mock.clone(),
+			retired_seal_mock.clone(),
+		);
+		let previous_key = key_repository.retrieve_key().unwrap();
+		assert_eq!(None, key_repository.retrieve_retired_key(0).unwrap());
+
+		let rotated_key = Aes::new([2u8; 16], [0u8; 16]);
+		key_repository.rotate_key(rotated_key, 1000).unwrap();
+
+		assert_eq!(rotated_key, key_repository.retrieve_key().unwrap());
+		assert_eq!(rotated_key, seal_mock.unseal().unwrap());
+		assert_eq!(Some(previous_key), key_repository.retrieve_retired_key(999).unwrap());
+		assert_eq!(None, key_repository.retrieve_retired_key(1000).unwrap());
+	}
+
+	#[test]
+	fn retired_key_is_restored_from_seal() {
+		let seal_mock = Arc::new(AesSealMock::default());
+		let retired_seal_mock = Arc::new(RetiredAesSealMock::default());
+		let retired_key = RetiredKey { key: Aes::new([3u8; 16], [0u8; 16]), valid_until: 1000 };
+		retired_seal_mock.seal(&retired_key).unwrap();
+
+		let key_repository =
+			RotatingKeyRepository::new(seal_mock.unseal().unwrap(), seal_mock, retired_seal_mock);
+
+		assert_eq!(Some(retired_key.key), key_repository.retrieve_retired_key(0).unwrap());
+	}
 }
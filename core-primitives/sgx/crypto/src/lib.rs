@@ -33,14 +33,16 @@ pub mod sgx_reexport_prelude {
 }
 
 pub mod aes;
+pub mod aes_gcm;
 pub mod ed25519;
 pub mod ed25519_derivation;
 pub mod error;
 pub mod key_repository;
 pub mod rsa3072;
 pub mod traits;
+pub mod x25519;
 
-pub use self::{aes::*, ed25519::*, rsa3072::*};
+pub use self::{aes::*, aes_gcm::*, ed25519::*, rsa3072::*, x25519::*};
 pub use error::*;
 pub use traits::*;
 
@@ -54,10 +56,21 @@ pub mod tests {
 	};
 
 	pub use super::rsa3072::sgx_tests::{
-		rsa3072_sealing_works, using_get_rsa3072_repository_twice_initializes_key_only_once,
+		rotated_rsa3072_key_is_retained_across_restarts, rsa3072_sealing_works,
+		using_get_rsa3072_repository_twice_initializes_key_only_once,
 	};
 
 	pub use super::aes::sgx_tests::{
 		aes_sealing_works, using_get_aes_repository_twice_initializes_key_only_once,
 	};
+
+	pub use super::aes_gcm::sgx_tests::{
+		get_aes_gcm_repository_derives_key_from_legacy_key,
+		using_get_aes_gcm_repository_twice_initializes_key_only_once,
+	};
+
+	pub use super::x25519::sgx_tests::{
+		rotated_x25519_key_is_retained_across_restarts,
+		using_get_x25519_repository_twice_initializes_key_only_once, x25519_sealing_works,
+	};
 }
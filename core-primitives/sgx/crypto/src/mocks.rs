@@ -24,7 +24,7 @@ use std::sync::RwLock;
 use crate::{
 	aes::Aes,
 	error::{Error, Result},
-	key_repository::{AccessKey, MutateKey},
+	key_repository::{AccessKey, AccessRetiredKey, MutateKey},
 };
 use itp_sgx_io::{SealedIO, StaticSealedIO};
 use sgx_crypto_helper::rsa3072::Rsa3072KeyPair;
@@ -68,6 +68,26 @@ where
 	}
 }
 
+/// Retired key repository mock, the retired key is valid until `valid_until`.
+pub struct RetiredKeyRepositoryMock<KeyType> {
+	key: KeyType,
+	valid_until: u64,
+}
+
+impl<KeyType> RetiredKeyRepositoryMock<KeyType> {
+	pub fn new(key: KeyType, valid_until: u64) -> Self {
+		RetiredKeyRepositoryMock { key, valid_until }
+	}
+}
+
+impl<KeyType: Clone> AccessRetiredKey for RetiredKeyRepositoryMock<KeyType> {
+	type KeyType = KeyType;
+
+	fn retrieve_retired_key(&self, now: u64) -> Result<Option<Self::KeyType>> {
+		Ok((now < self.valid_until).then(|| self.key.clone()))
+	}
+}
+
 #[derive(Default)]
 pub struct AesSealMock {
 	aes: RwLock<Aes>,
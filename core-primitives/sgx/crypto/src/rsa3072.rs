@@ -35,6 +35,9 @@ pub use sgx::*;
 /// File name of the sealed RSA key file.
 pub const RSA3072_SEALED_KEY_FILE: &str = "rsa3072_key_sealed.bin";
 
+/// File name of the sealed RSA key that was replaced by the last key rotation.
+pub const RETIRED_RSA3072_SEALED_KEY_FILE: &str = "rsa3072_key_retired_sealed.bin";
+
 impl ShieldingCryptoEncrypt for Rsa3072KeyPair {
 	type Error = Error;
 
@@ -92,7 +95,8 @@ pub trait RsaSealing {
 #[cfg(feature = "sgx")]
 pub mod sgx {
 	use super::*;
-	use crate::key_repository::KeyRepository;
+	use crate::key_repository::{KeyRepository, RetiredKey, RotatingKeyRepository};
+	use core::convert::TryInto;
 	use itp_sgx_io::{seal, unseal, SealedIO};
 	use log::*;
 	use std::path::PathBuf;
@@ -108,6 +112,21 @@ pub mod sgx {
 		Ok(KeyRepository::new(shielding_key, rsa_seal.into()))
 	}
 
+	/// Gets a rotating repository for an Rsa3072 keypair and initializes
+	/// a fresh key pair if it doesn't exist at `path`.
+	pub fn get_rotating_rsa3072_repository(
+		path: PathBuf,
+	) -> Result<RotatingKeyRepository<Rsa3072KeyPair, Rsa3072Seal, RetiredRsa3072Seal>> {
+		let rsa_seal = Rsa3072Seal::new(path.clone());
+		rsa_seal.create_sealed_if_absent()?;
+		let shielding_key = rsa_seal.unseal_pair()?;
+		Ok(RotatingKeyRepository::new(
+			shielding_key,
+			rsa_seal.into(),
+			RetiredRsa3072Seal::new(path).into(),
+		))
+	}
+
 	#[derive(Clone, Debug)]
 	pub struct Rsa3072Seal {
 		base_path: PathBuf,
@@ -169,14 +188,64 @@ pub mod sgx {
 			Ok(seal(&key_json, self.path())?)
 		}
 	}
+
+	/// Seals the retired RSA key together with the end of its grace period.
+	#[derive(Clone, Debug)]
+	pub struct RetiredRsa3072Seal {
+		base_path: PathBuf,
+	}
+
+	impl RetiredRsa3072Seal {
+		pub fn new(base_path: PathBuf) -> Self {
+			Self { base_path }
+		}
+
+		pub fn path(&self) -> PathBuf {
+			self.base_path.join(RETIRED_RSA3072_SEALED_KEY_FILE)
+		}
+	}
+
+	impl SealedIO for RetiredRsa3072Seal {
+		type Error = Error;
+		type Unsealed = RetiredKey<Rsa3072KeyPair>;
+
+		fn unseal(&self) -> Result<Self::Unsealed> {
+			let raw = unseal(self.path())?;
+			if raw.len() < 8 {
+				return Err(Error::Other("Sealed retired RSA key is too short".into()))
+			}
+			let (valid_until, key_json) = raw.split_at(8);
+			let valid_until = u64::from_le_bytes(
+				valid_until.try_into().map_err(|e| Error::Other(format!("{:?}", e).into()))?,
+			);
+			let key: Rsa3072KeyPair = serde_json::from_slice(key_json)
+				.map_err(|e| Error::Other(format!("{:?}", e).into()))?;
+			Ok(RetiredKey { key, valid_until })
+		}
+
+		fn seal(&self, unsealed: &Self::Unsealed) -> Result<()> {
+			let mut raw = unsealed.valid_until.to_le_bytes().to_vec();
+			raw.extend(
+				serde_json::to_vec(&unsealed.key)
+					.map_err(|e| Error::Other(format!("{:?}", e).into()))?,
+			);
+			Ok(seal(&raw, self.path())?)
+		}
+	}
 }
 
 #[cfg(feature = "test")]
 pub mod sgx_tests {
 	use super::{serde_json, sgx::*};
-	use crate::{key_repository::AccessKey, RsaSealing, ToPubkey};
+	use crate::{
+		key_repository::{AccessKey, AccessRetiredKey, RotateKey},
+		RsaSealing, ToPubkey,
+	};
 	use itp_sgx_temp_dir::TempDir;
-	use sgx_crypto_helper::rsa3072::Rsa3072PubKey;
+	use sgx_crypto_helper::{
+		rsa3072::{Rsa3072KeyPair, Rsa3072PubKey},
+		RsaKeyPair,
+	};
 
 	/// Helper method because Rsa3072 does not implement `Eq`.
 	pub fn equal(pubkey1: &Rsa3072PubKey, pubkey2: &Rsa3072PubKey) -> bool {
@@ -218,4 +287,22 @@ pub mod sgx_tests {
 
 		assert!(!equal(&pair_different.pubkey().unwrap(), &pair.pubkey().unwrap()));
 	}
+
+	pub fn rotated_rsa3072_key_is_retained_across_restarts() {
+		let temp_dir =
+			TempDir::with_prefix("rotated_rsa3072_key_is_retained_across_restarts").unwrap();
+		let temp_path = temp_dir.path().to_path_buf();
+		let repository = get_rotating_rsa3072_repository(temp_path.clone()).unwrap();
+		let previous_key = repository.retrieve_key().unwrap();
+		let rotated_key = Rsa3072KeyPair::new().unwrap();
+
+		repository.rotate_key(rotated_key, 1000).unwrap();
+
+		let repository = get_rotating_rsa3072_repository(temp_path).unwrap();
+		let current_key = repository.retrieve_key().unwrap();
+		let retired_key = repository.retrieve_retired_key(999).unwrap().unwrap();
+		assert!(equal(&current_key.pubkey().unwrap(), &rotated_key.pubkey().unwrap()));
+		assert!(equal(&retired_key.pubkey().unwrap(), &previous_key.pubkey().unwrap()));
+		assert!(repository.retrieve_retired_key(1000).unwrap().is_none());
+	}
 }
@@ -18,10 +18,34 @@
 //! Abstraction over the state crypto that is used in the enclave
 use std::{fmt::Debug, vec::Vec};
 
+/// Encrypts and decrypts data in-place. The length of the data may change,
+/// e.g. to accommodate a ciphertext header or an authentication tag.
 pub trait StateCrypto {
 	type Error: Debug;
-	fn encrypt(&self, data: &mut [u8]) -> Result<(), Self::Error>;
-	fn decrypt(&self, data: &mut [u8]) -> Result<(), Self::Error>;
+	fn encrypt(&self, data: &mut Vec<u8>) -> Result<(), Self::Error>;
+	fn decrypt(&self, data: &mut Vec<u8>) -> Result<(), Self::Error>;
+
+	/// Decrypts data that may still be encrypted in a legacy format.
+	///
+	/// Legacy formats are not necessarily authenticated, so this is only to be used for data
+	/// whose integrity is ensured otherwise, e.g. by the signature of a sidechain block.
+	fn decrypt_legacy_compatible(&self, data: &mut Vec<u8>) -> Result<(), Self::Error> {
+		self.decrypt(data)
+	}
+
+	/// Checks if `decrypt_legacy_compatible` would decrypt `data` in a legacy format.
+	fn is_legacy_ciphertext(&self, _data: &[u8]) -> bool {
+		false
+	}
+
+	/// Returns this key without the legacy fallback of `decrypt_legacy_compatible`,
+	/// if it still has one.
+	fn without_legacy_fallback(&self) -> Option<Self>
+	where
+		Self: Sized,
+	{
+		None
+	}
 }
 
 pub trait ShieldingCryptoEncrypt {
@@ -0,0 +1,408 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Hybrid shielding encryption based on X25519 and AES-GCM (ECIES).
+//!
+//! A ciphertext consists of [`X25519_CIPHERTEXT_MAGIC`], the [`X25519_CIPHERTEXT_VERSION`] byte,
+//! the ephemeral public key of the sender and the AES-GCM ciphertext of the data. The AES-GCM key
+//! is derived from the X25519 shared secret and both public keys.
+
+use crate::{
+	aes_gcm::{random_bytes, AesGcm},
+	error::{Error, Result},
+	traits::{ShieldingCryptoDecrypt, ShieldingCryptoEncrypt, StateCrypto},
+	ToPubkey,
+};
+use codec::{Decode, Encode};
+use sp_core::blake2_256;
+use std::{path::PathBuf, vec::Vec};
+use x25519_dalek::{PublicKey, StaticSecret};
+
+/// File name of the sealed X25519 key file.
+pub const X25519_SEALED_KEY_FILE: &str = "x25519_key_sealed.bin";
+
+/// File name of the sealed X25519 key that was replaced by the last key rotation.
+pub const RETIRED_X25519_SEALED_KEY_FILE: &str = "x25519_key_retired_sealed.bin";
+
+/// Magic bytes at the start of every X25519 shielding ciphertext.
+pub const X25519_CIPHERTEXT_MAGIC: [u8; 4] = *b"itxs";
+
+/// Version of the ciphertext format. Needs to be increased whenever the format changes.
+pub const X25519_CIPHERTEXT_VERSION: u8 = 1;
+
+const PUBLIC_KEY_LENGTH: usize = 32;
+const HEADER_LENGTH: usize = X25519_CIPHERTEXT_MAGIC.len() + 1 + PUBLIC_KEY_LENGTH;
+
+/// X25519 shielding key pair. Only the secret is stored, the public key is derived from it.
+#[derive(Clone, Default, Encode, Decode, PartialEq, Eq)]
+pub struct X25519KeyPair {
+	secret: [u8; 32],
+}
+
+impl X25519KeyPair {
+	pub fn from_secret(secret: [u8; 32]) -> Self {
+		Self { secret: StaticSecret::from(secret).to_bytes() }
+	}
+
+	/// Creates a fresh random key pair.
+	pub fn random() -> Result<Self> {
+		Ok(Self::from_secret(random_bytes::<32>()?))
+	}
+
+	pub fn public_key(&self) -> X25519PubKey {
+		X25519PubKey(*PublicKey::from(&StaticSecret::from(self.secret)).as_bytes())
+	}
+}
+
+#[derive(Debug, Default, Encode, Decode, Clone, Copy, PartialEq, Eq)]
+pub struct X25519PubKey(pub [u8; 32]);
+
+/// Checks if `data` starts with a valid X25519 shielding ciphertext header.
+pub fn is_x25519_ciphertext(data: &[u8]) -> bool {
+	data.len() > HEADER_LENGTH
+		&& data[..X25519_CIPHERTEXT_MAGIC.len()] == X25519_CIPHERTEXT_MAGIC
+		&& data[X25519_CIPHERTEXT_MAGIC.len()] == X25519_CIPHERTEXT_VERSION
+}
+
+fn derive_aes_gcm_key(
+	shared_secret: &[u8; 32],
+	ephemeral_public: &[u8; 32],
+	recipient_public: &[u8; 32],
+) -> AesGcm {
+	let seed = blake2_256(
+		&(b"x25519-shielding", shared_secret, ephemeral_public, recipient_public).encode(),
+	);
+	let mut key = [0u8; 16];
+	key.copy_from_slice(&seed[..16]);
+	AesGcm::new(key)
+}
+
+impl ShieldingCryptoEncrypt for X25519PubKey {
+	type Error = Error;
+
+	fn encrypt(&self, data: &[u8]) -> Result<Vec<u8>> {
+		let ephemeral_secret = StaticSecret::from(random_bytes::<32>()?);
+		let ephemeral_public = *PublicKey::from(&ephemeral_secret).as_bytes();
+		let shared_secret = ephemeral_secret.diffie_hellman(&PublicKey::from(self.0));
+
+		let mut payload = data.to_vec();
+		derive_aes_gcm_key(shared_secret.as_bytes(), &ephemeral_public, &self.0)
+			.encrypt(&mut payload)?;
+
+		let mut ciphertext = Vec::with_capacity(HEADER_LENGTH + payload.len());
+		ciphertext.extend_from_slice(&X25519_CIPHERTEXT_MAGIC);
+		ciphertext.push(X25519_CIPHERTEXT_VERSION);
+		ciphertext.extend_from_slice(&ephemeral_public);
+		ciphertext.extend_from_slice(&payload);
+		Ok(ciphertext)
+	}
+}
+
+impl ShieldingCryptoEncrypt for X25519KeyPair {
+	type Error = Error;
+
+	fn encrypt(&self, data: &[u8]) -> Result<Vec<u8>> {
+		self.public_key().encrypt(data)
+	}
+}
+
+impl ShieldingCryptoDecrypt for X25519KeyPair {
+	type Error = Error;
+
+	fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>> {
+		let magic_length = X25519_CIPHERTEXT_MAGIC.len();
+		if data.len() <= HEADER_LENGTH || data[..magic_length] != X25519_CIPHERTEXT_MAGIC {
+			return Err(Error::InvalidCiphertextHeader)
+		}
+		let version = data[magic_length];
+		if version != X25519_CIPHERTEXT_VERSION {
+			return Err(Error::UnsupportedCiphertextVersion(version))
+		}
+
+		let mut ephemeral_public = [0u8; PUBLIC_KEY_LENGTH];
+		ephemeral_public.copy_from_slice(&data[magic_length + 1..HEADER_LENGTH]);
+		let shared_secret =
+			StaticSecret::from(self.secret).diffie_hellman(&PublicKey::from(ephemeral_public));
+
+		let mut payload = data[HEADER_LENGTH..].to_vec();
+		derive_aes_gcm_key(shared_secret.as_bytes(), &ephemeral_public, &self.public_key().0)
+			.decrypt(&mut payload)?;
+		Ok(payload)
+	}
+}
+
+impl ToPubkey for X25519KeyPair {
+	type Error = Error;
+	type Pubkey = X25519PubKey;
+
+	fn pubkey(&self) -> Result<Self::Pubkey> {
+		Ok(self.public_key())
+	}
+}
+
+#[derive(Clone, Debug)]
+pub struct X25519Seal {
+	base_path: PathBuf,
+}
+
+impl X25519Seal {
+	pub fn new(base_path: PathBuf) -> Self {
+		Self { base_path }
+	}
+
+	pub fn path(&self) -> PathBuf {
+		self.base_path.join(X25519_SEALED_KEY_FILE)
+	}
+}
+
+pub trait X25519Sealing {
+	fn unseal_pubkey(&self) -> Result<X25519PubKey>;
+
+	fn unseal_pair(&self) -> Result<X25519KeyPair>;
+
+	fn exists(&self) -> bool;
+
+	fn create_sealed_if_absent(&self) -> Result<()>;
+
+	fn create_sealed(&self) -> Result<()>;
+}
+
+#[cfg(feature = "sgx")]
+pub use sgx::*;
+
+#[cfg(feature = "sgx")]
+pub mod sgx {
+	use super::*;
+	use crate::key_repository::{KeyRepository, RetiredKey, RotatingKeyRepository};
+	use itp_sgx_io::{seal, unseal, SealedIO};
+	use log::info;
+	use std::sgxfs::SgxFile;
+
+	/// Gets a repository for an X25519 key pair and initializes
+	/// a fresh key pair if it doesn't exist at `path`.
+	pub fn get_x25519_repository(
+		path: PathBuf,
+	) -> Result<KeyRepository<X25519KeyPair, X25519Seal>> {
+		let x25519_seal = X25519Seal::new(path);
+		x25519_seal.create_sealed_if_absent()?;
+		let shielding_key = x25519_seal.unseal_pair()?;
+		Ok(KeyRepository::new(shielding_key, x25519_seal.into()))
+	}
+
+	/// Gets a rotating repository for an X25519 key pair and initializes
+	/// a fresh key pair if it doesn't exist at `path`.
+	pub fn get_rotating_x25519_repository(
+		path: PathBuf,
+	) -> Result<RotatingKeyRepository<X25519KeyPair, X25519Seal, RetiredX25519Seal>> {
+		let x25519_seal = X25519Seal::new(path.clone());
+		x25519_seal.create_sealed_if_absent()?;
+		let shielding_key = x25519_seal.unseal_pair()?;
+		Ok(RotatingKeyRepository::new(
+			shielding_key,
+			x25519_seal.into(),
+			RetiredX25519Seal::new(path).into(),
+		))
+	}
+
+	impl X25519Sealing for X25519Seal {
+		fn unseal_pubkey(&self) -> Result<X25519PubKey> {
+			self.unseal()?.pubkey()
+		}
+
+		fn unseal_pair(&self) -> Result<X25519KeyPair> {
+			self.unseal()
+		}
+
+		fn exists(&self) -> bool {
+			SgxFile::open(self.path()).is_ok()
+		}
+
+		fn create_sealed_if_absent(&self) -> Result<()> {
+			if !self.exists() {
+				info!("Keyfile not found, creating new! {}", self.path().display());
+				return self.create_sealed()
+			}
+			Ok(())
+		}
+
+		fn create_sealed(&self) -> Result<()> {
+			let key_pair = X25519KeyPair::random()?;
+			info!("Generated X25519 key pair. PubKey: {:?}", key_pair.public_key());
+			self.seal(&key_pair)
+		}
+	}
+
+	impl SealedIO for X25519Seal {
+		type Error = Error;
+		type Unsealed = X25519KeyPair;
+
+		fn unseal(&self) -> Result<Self::Unsealed> {
+			Ok(unseal(self.path()).map(|b| Decode::decode(&mut b.as_slice()))??)
+		}
+
+		fn seal(&self, unsealed: &Self::Unsealed) -> Result<()> {
+			Ok(unsealed.using_encoded(|bytes| seal(bytes, self.path()))?)
+		}
+	}
+
+	/// Seals the retired X25519 key together with the end of its grace period.
+	#[derive(Clone, Debug)]
+	pub struct RetiredX25519Seal {
+		base_path: PathBuf,
+	}
+
+	impl RetiredX25519Seal {
+		pub fn new(base_path: PathBuf) -> Self {
+			Self { base_path }
+		}
+
+		pub fn path(&self) -> PathBuf {
+			self.base_path.join(RETIRED_X25519_SEALED_KEY_FILE)
+		}
+	}
+
+	impl SealedIO for RetiredX25519Seal {
+		type Error = Error;
+		type Unsealed = RetiredKey<X25519KeyPair>;
+
+		fn unseal(&self) -> Result<Self::Unsealed> {
+			let (valid_until, key) = unseal(self.path())
+				.map(|b| <(u64, X25519KeyPair)>::decode(&mut b.as_slice()))??;
+			Ok(RetiredKey { key, valid_until })
+		}
+
+		fn seal(&self, unsealed: &Self::Unsealed) -> Result<()> {
+			Ok((unsealed.valid_until, &unsealed.key)
+				.using_encoded(|bytes| seal(bytes, self.path()))?)
+		}
+	}
+}
+
+#[cfg(feature = "test")]
+pub mod sgx_tests {
+	use super::sgx::*;
+	use crate::{
+		key_repository::{AccessKey, AccessPubkey, AccessRetiredKey, RotateKey},
+		ShieldingCryptoDecrypt, ShieldingCryptoEncrypt, X25519KeyPair, X25519Seal, X25519Sealing,
+	};
+	use itp_sgx_temp_dir::TempDir;
+
+	pub fn using_get_x25519_repository_twice_initializes_key_only_once() {
+		let temp_dir =
+			TempDir::with_prefix("using_get_x25519_repository_twice_initializes_key_only_once")
+				.unwrap();
+		let temp_path = temp_dir.path().to_path_buf();
+		let key1 = get_x25519_repository(temp_path.clone()).unwrap().retrieve_pubkey().unwrap();
+		let key2 = get_x25519_repository(temp_path).unwrap().retrieve_pubkey().unwrap();
+		assert_eq!(key1, key2);
+	}
+
+	pub fn x25519_sealing_works() {
+		let temp_dir = TempDir::with_prefix("x25519_sealing_works").unwrap();
+		let seal = X25519Seal::new(temp_dir.path().to_path_buf());
+
+		// Create new sealed keys and unseal them.
+		assert!(!seal.exists());
+		seal.create_sealed_if_absent().unwrap();
+		let pubkey = seal.unseal_pubkey().unwrap();
+
+		assert!(seal.exists());
+
+		// Should not change anything because the key is already there.
+		seal.create_sealed_if_absent().unwrap();
+		assert_eq!(pubkey, seal.unseal_pubkey().unwrap());
+
+		// Encrypting for the sealed public key can be decrypted with the sealed pair.
+		let ciphertext = pubkey.encrypt(b"trusted operation").unwrap();
+		let key_repository = get_x25519_repository(temp_dir.path().to_path_buf()).unwrap();
+		assert_eq!(
+			key_repository.retrieve_key().unwrap().decrypt(&ciphertext).unwrap(),
+			b"trusted operation".to_vec()
+		);
+
+		// Should overwrite previous keys.
+		seal.create_sealed().unwrap();
+		assert_ne!(pubkey, seal.unseal_pubkey().unwrap());
+	}
+
+	pub fn rotated_x25519_key_is_retained_across_restarts() {
+		let temp_dir =
+			TempDir::with_prefix("rotated_x25519_key_is_retained_across_restarts").unwrap();
+		let temp_path = temp_dir.path().to_path_buf();
+		let repository = get_rotating_x25519_repository(temp_path.clone()).unwrap();
+		let previous_key = repository.retrieve_key().unwrap();
+		let rotated_key = X25519KeyPair::from_secret([9u8; 32]);
+
+		repository.rotate_key(rotated_key.clone(), 1000).unwrap();
+
+		let repository = get_rotating_x25519_repository(temp_path).unwrap();
+		assert!(repository.retrieve_key().unwrap() == rotated_key);
+		assert!(repository.retrieve_retired_key(999).unwrap().unwrap() == previous_key);
+		assert!(repository.retrieve_retired_key(1000).unwrap().is_none());
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn key_pair() -> X25519KeyPair {
+		X25519KeyPair::from_secret([7u8; 32])
+	}
+
+	#[test]
+	fn encrypt_decrypt_roundtrip_works() {
+		let plaintext = b"some trusted operation".to_vec();
+		let ciphertext = key_pair().public_key().encrypt(&plaintext).unwrap();
+
+		assert!(is_x25519_ciphertext(&ciphertext));
+		assert_eq!(key_pair().decrypt(&ciphertext).unwrap(), plaintext);
+	}
+
+	#[test]
+	fn encrypting_twice_uses_different_ephemeral_keys() {
+		let public_key = key_pair().public_key();
+
+		assert_ne!(public_key.encrypt(b"data").unwrap(), public_key.encrypt(b"data").unwrap());
+	}
+
+	#[test]
+	fn decrypting_with_wrong_key_fails() {
+		let ciphertext = key_pair().public_key().encrypt(b"data").unwrap();
+
+		assert!(matches!(
+			X25519KeyPair::from_secret([8u8; 32]).decrypt(&ciphertext),
+			Err(Error::AeadAuthentication)
+		));
+	}
+
+	#[test]
+	fn decrypting_with_tampered_ephemeral_key_fails() {
+		let mut ciphertext = key_pair().public_key().encrypt(b"data").unwrap();
+		ciphertext[HEADER_LENGTH - 1] ^= 1;
+
+		assert!(key_pair().decrypt(&ciphertext).is_err());
+	}
+
+	#[test]
+	fn decrypting_rsa_sized_data_fails() {
+		let data = vec![3u8; 384];
+
+		assert!(!is_x25519_ciphertext(&data));
+		assert!(matches!(key_pair().decrypt(&data), Err(Error::InvalidCiphertextHeader)));
+	}
+}
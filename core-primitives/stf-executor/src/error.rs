@@ -47,6 +47,12 @@ pub enum Error {
 	OcallApi(itp_ocall_api::Error),
 	#[error("Crypto error: {0}")]
 	Crypto(itp_sgx_crypto::error::Error),
+	#[error("Storage error: {0}")]
+	Storage(#[from] itp_storage::error::Error),
+	#[error("Lock poisoning")]
+	LockPoisoning,
+	#[error("State has changed since the latest sidechain block of the shard")]
+	StateCommitmentOutdated,
 	#[error(transparent)]
 	Other(#[from] Box<dyn std::error::Error + Sync + Send + 'static>),
 }
@@ -26,7 +26,7 @@ use itp_node_api::metadata::{provider::AccessNodeMetadata, NodeMetadataTrait};
 use itp_ocall_api::{EnclaveAttestationOCallApi, EnclaveMetricsOCallApi, EnclaveOnChainOCallApi};
 use itp_sgx_externalities::{SgxExternalitiesTrait, StateHash};
 use itp_stf_interface::{
-	parentchain_pallet::ParentchainPalletInstancesInterface, StateCallInterface, UpdateState,
+	parentchain_pallet::ParentchainPalletInterface, StateCallInterface, UpdateState,
 };
 use itp_stf_primitives::{
 	error::StfError,
@@ -145,13 +145,13 @@ where
 					"trusted_call wants to send encoded call to [Integritee] parentchain: 0x{} with mortality {:?}",
 					hex::encode(call.encode()), mortality
 				),
-				ParentchainCall::TargetA { call, mortality } => trace!(
-					"trusted_call wants to send encoded call to [TargetA] parentchain: 0x{} with mortality {:?}",
-					hex::encode(call.encode()), mortality
+				ParentchainCall::Target { index, call, mortality } => trace!(
+					"trusted_call wants to send encoded call to [Target{}] parentchain: 0x{} with mortality {:?}",
+					index, hex::encode(call.encode()), mortality
 				),
-				ParentchainCall::TargetB { call, mortality } => trace!(
-					"trusted_call wants to send encoded call to [TargetB] parentchain: 0x{} with mortality {:?}",
-					hex::encode(call.encode()), mortality
+				ParentchainCall::VaultWithdrawal { parentchain_id, call, mortality, .. } => trace!(
+					"trusted_call wants to send vault withdrawal to [{:?}] parentchain: 0x{} with mortality {:?}",
+					parentchain_id, hex::encode(call.encode()), mortality
 				),
 			}
 		}
@@ -170,11 +170,7 @@ where
 	Stf: UpdateState<
 			StateHandler::StateT,
 			<StateHandler::StateT as SgxExternalitiesTrait>::SgxExternalitiesDiffType,
-		> + ParentchainPalletInstancesInterface<
-			StateHandler::StateT,
-			ParentchainHeader,
-			Error = StfError,
-		>,
+		> + ParentchainPalletInterface<StateHandler::StateT, ParentchainHeader, Error = StfError>,
 	<StateHandler::StateT as SgxExternalitiesTrait>::SgxExternalitiesDiffType:
 		IntoIterator<Item = (Vec<u8>, Option<Vec<u8>>)>,
 	<StateHandler::StateT as SgxExternalitiesTrait>::SgxExternalitiesDiffType:
@@ -202,14 +198,7 @@ where
 		let shards = self.state_handler.list_shards()?;
 		for shard_id in shards {
 			let (state_lock, mut state) = self.state_handler.load_for_mutation(&shard_id)?;
-			match parentchain_id {
-				ParentchainId::Integritee =>
-					Stf::update_parentchain_integritee_block(&mut state, header.clone()),
-				ParentchainId::TargetA =>
-					Stf::update_parentchain_target_a_block(&mut state, header.clone()),
-				ParentchainId::TargetB =>
-					Stf::update_parentchain_target_a_block(&mut state, header.clone()),
-			}?;
+			Stf::update_parentchain_block(&mut state, header.clone(), *parentchain_id)?;
 			self.state_handler.write_after_mutation(state, state_lock, &shard_id)?;
 		}
 
@@ -234,13 +223,12 @@ impl<OCallApi, StateHandler, NodeMetadataRepository, Stf, TCS, G>
 where
 	<StateHandler::StateT as SgxExternalitiesTrait>::SgxExternalitiesDiffType:
 		From<BTreeMap<Vec<u8>, Option<Vec<u8>>>> + IntoIterator<Item = (Vec<u8>, Option<Vec<u8>>)>,
-	<Stf as ParentchainPalletInstancesInterface<StateHandler::StateT, ParentchainHeader>>::Error:
-		Debug,
+	<Stf as ParentchainPalletInterface<StateHandler::StateT, ParentchainHeader>>::Error: Debug,
 	NodeMetadataRepository: AccessNodeMetadata,
 	OCallApi: EnclaveAttestationOCallApi + EnclaveOnChainOCallApi,
 	StateHandler: HandleState<HashType = H256> + QueryShardState,
 	StateHandler::StateT: Encode + SgxExternalitiesTrait,
-	Stf: ParentchainPalletInstancesInterface<StateHandler::StateT, ParentchainHeader>
+	Stf: ParentchainPalletInterface<StateHandler::StateT, ParentchainHeader>
 		+ UpdateState<
 			StateHandler::StateT,
 			<StateHandler::StateT as SgxExternalitiesTrait>::SgxExternalitiesDiffType,
@@ -273,7 +261,9 @@ where
 
 			Stf::apply_state_diff(&mut state, per_shard_update.into());
 			Stf::apply_state_diff(&mut state, state_diff_update.clone().into());
-			if let Err(e) = Stf::update_parentchain_integritee_block(&mut state, header.clone()) {
+			if let Err(e) =
+				Stf::update_parentchain_block(&mut state, header.clone(), ParentchainId::Integritee)
+			{
 				error!("Could not update parentchain block. {:?}: {:?}", shard_id, e)
 			}
 
@@ -365,27 +355,24 @@ where
 		let successful_call_count =
 			executed_and_failed_calls.iter().filter(|call| call.is_success()).count();
 		let failed_call_count = executed_and_failed_calls.len() - successful_call_count;
-		self.ocall_api
-			.update_metrics(vec![
-				EnclaveMetric::StfStateUpdateExecutionDuration(duration_now() - started_at),
-				EnclaveMetric::StfStateUpdateExecutedCallsCount(true, successful_call_count as u64),
-				EnclaveMetric::StfStateUpdateExecutedCallsCount(false, failed_call_count as u64),
-				EnclaveMetric::TopPoolAPrioriSizeSet(trusted_calls.len() as u64),
-				EnclaveMetric::StfStateSizeSet(*shard, state_size_bytes as u64),
-				EnclaveMetric::StfRuntimeTotalIssuanceSet(runtime_metrics.total_issuance),
-				EnclaveMetric::StfRuntimeParentchainProcessedBlockNumberSet(
-					ParentchainId::Integritee,
-					runtime_metrics.parentchain_integritee_processed_block_number,
-				),
+		let mut metrics = vec![
+			EnclaveMetric::StfStateUpdateExecutionDuration(duration_now() - started_at),
+			EnclaveMetric::StfStateUpdateExecutedCallsCount(true, successful_call_count as u64),
+			EnclaveMetric::StfStateUpdateExecutedCallsCount(false, failed_call_count as u64),
+			EnclaveMetric::TopPoolAPrioriSizeSet(trusted_calls.len() as u64),
+			EnclaveMetric::StfStateSizeSet(*shard, state_size_bytes as u64),
+			EnclaveMetric::StfRuntimeTotalIssuanceSet(runtime_metrics.total_issuance),
+		];
+		metrics.extend(runtime_metrics.parentchain_processed_block_numbers.into_iter().map(
+			|(parentchain_id, block_number)| {
 				EnclaveMetric::StfRuntimeParentchainProcessedBlockNumberSet(
-					ParentchainId::TargetA,
-					runtime_metrics.parentchain_target_a_processed_block_number,
-				),
-				EnclaveMetric::StfRuntimeParentchainProcessedBlockNumberSet(
-					ParentchainId::TargetB,
-					runtime_metrics.parentchain_target_b_processed_block_number,
-				),
-			])
+					parentchain_id,
+					block_number,
+				)
+			},
+		));
+		self.ocall_api
+			.update_metrics(metrics)
 			.unwrap_or_else(|e| error!("failed to update prometheus metric: {:?}", e));
 		Ok(BatchExecutionResult {
 			executed_operations: executed_and_failed_calls,
@@ -428,30 +415,32 @@ where
 				.unwrap_or(-1.0)
 		})
 		.unwrap_or(-1.0);
-	// fallback to zero is fine here
-	let parentchain_integritee_processed_block_number: u32 = state
-		.get(&storage_value_key("ParentchainIntegritee", "Number"))
-		.map(|v| BlockNumber::decode(&mut v.as_slice()).unwrap_or_default())
-		.unwrap_or_default();
-	let parentchain_target_a_processed_block_number: u32 = state
-		.get(&storage_value_key("ParentchainTargetA", "Number"))
-		.map(|v| BlockNumber::decode(&mut v.as_slice()).unwrap_or_default())
-		.unwrap_or_default();
-	let parentchain_target_b_processed_block_number: u32 = state
-		.get(&storage_value_key("ParentchainTargetB", "Number"))
-		.map(|v| BlockNumber::decode(&mut v.as_slice()).unwrap_or_default())
-		.unwrap_or_default();
-	RuntimeMetrics {
-		total_issuance,
-		parentchain_integritee_processed_block_number,
-		parentchain_target_a_processed_block_number,
-		parentchain_target_b_processed_block_number,
+	// iterate over the `Number` map of the parentchain pallet, which is keyed by parentchain id
+	let number_prefix = storage_value_key("Parentchain", "Number");
+	let mut parentchain_processed_block_numbers = Vec::new();
+	let mut key = number_prefix.clone();
+	while let Some(next_key) = state.next_storage_key(&key) {
+		if !next_key.starts_with(&number_prefix) {
+			break
+		}
+		// the key is prefixed by the `Blake2_128Concat` hash of the parentchain id
+		let maybe_parentchain_id = next_key
+			.get(number_prefix.len() + 16..)
+			.and_then(|mut encoded_id| ParentchainId::decode(&mut encoded_id).ok());
+		// fallback to zero is fine here
+		let block_number: u32 = state
+			.get(&next_key)
+			.map(|v| BlockNumber::decode(&mut v.as_slice()).unwrap_or_default())
+			.unwrap_or_default();
+		if let Some(parentchain_id) = maybe_parentchain_id {
+			parentchain_processed_block_numbers.push((parentchain_id, block_number));
+		}
+		key = next_key;
 	}
+	RuntimeMetrics { total_issuance, parentchain_processed_block_numbers }
 }
 
 struct RuntimeMetrics {
 	total_issuance: f64,
-	parentchain_integritee_processed_block_number: u32,
-	parentchain_target_a_processed_block_number: u32,
-	parentchain_target_b_processed_block_number: u32,
+	parentchain_processed_block_numbers: Vec<(ParentchainId, u32)>,
 }
@@ -18,9 +18,9 @@
 //! Getter executor uses the state observer to get the most recent state and runs the getter on it.
 //! The getter is verified (signature verfification) inside the `GetState` implementation.
 
-use crate::{error::Result, state_getter::GetState};
+use crate::{error::Result, state_commitment::StateCommitmentCache, state_getter::GetState};
 use codec::Decode;
-use itp_stf_primitives::traits::GetterAuthorization;
+use itp_stf_primitives::{traits::GetterAuthorization, types::GetterResultWithProof};
 use itp_stf_state_observer::traits::ObserveState;
 use itp_types::ShardIdentifier;
 use log::*;
@@ -33,6 +33,14 @@ pub trait ExecuteGetter {
 		shard: &ShardIdentifier,
 		encoded_signed_getter: Vec<u8>,
 	) -> Result<Option<Vec<u8>>>;
+
+	/// Executes the getter and returns its result together with a proof of all state entries
+	/// it has read, against the state root of the latest sidechain block.
+	fn execute_getter_with_proof(
+		&self,
+		shard: &ShardIdentifier,
+		encoded_signed_getter: Vec<u8>,
+	) -> Result<GetterResultWithProof>;
 }
 
 pub struct GetterExecutor<StateObserver, StateGetter, G>
@@ -40,6 +48,7 @@ where
 	G: PartialEq,
 {
 	state_observer: Arc<StateObserver>,
+	state_commitment_cache: Arc<StateCommitmentCache>,
 	_phantom: PhantomData<StateGetter>,
 	_phantom_getter: PhantomData<G>,
 }
@@ -48,8 +57,16 @@ impl<StateObserver, StateGetter, G> GetterExecutor<StateObserver, StateGetter, G
 where
 	G: PartialEq,
 {
-	pub fn new(state_observer: Arc<StateObserver>) -> Self {
-		Self { state_observer, _phantom: Default::default(), _phantom_getter: Default::default() }
+	pub fn new(
+		state_observer: Arc<StateObserver>,
+		state_commitment_cache: Arc<StateCommitmentCache>,
+	) -> Self {
+		Self {
+			state_observer,
+			state_commitment_cache,
+			_phantom: Default::default(),
+			_phantom_getter: Default::default(),
+		}
 	}
 }
 
@@ -76,6 +93,27 @@ where
 
 		Ok(state_result)
 	}
+
+	fn execute_getter_with_proof(
+		&self,
+		shard: &ShardIdentifier,
+		encoded_signed_getter: Vec<u8>,
+	) -> Result<GetterResultWithProof> {
+		let getter = G::decode(&mut encoded_signed_getter.as_slice())?;
+		trace!("Successfully decoded trusted getter");
+
+		let getter_timer_start = Instant::now();
+		let result_with_proof = self.state_observer.observe_state(shard, |state| {
+			StateGetter::get_state_with_proof(getter, state, shard, &self.state_commitment_cache)
+		})??;
+
+		debug!(
+			"Getter executed and proof created in {} ms",
+			getter_timer_start.elapsed().as_millis()
+		);
+
+		Ok(result_with_proof)
+	}
 }
 
 #[cfg(test)]
@@ -87,6 +125,7 @@ mod tests {
 	use itp_test::mock::stf_mock::{
 		GetterMock, PublicGetterMock, TrustedGetterMock, TrustedGetterSignedMock,
 	};
+	use itp_types::H256;
 
 	type TestState = u64;
 	type TestStateObserver = ObserveStateMock<TestState>;
@@ -96,6 +135,19 @@ mod tests {
 		fn get_state(_getter: GetterMock, state: &mut TestState) -> Result<Option<Vec<u8>>> {
 			Ok(Some(state.encode()))
 		}
+
+		fn get_state_with_proof(
+			getter: GetterMock,
+			state: &mut TestState,
+			_shard: &ShardIdentifier,
+			_state_commitment_cache: &StateCommitmentCache,
+		) -> Result<GetterResultWithProof> {
+			Ok(GetterResultWithProof {
+				value: Self::get_state(getter, state)?,
+				state_root: H256::from_low_u64_be(*state),
+				..Default::default()
+			})
+		}
 	}
 
 	type TestGetterExecutor = GetterExecutor<TestStateObserver, TestStateGetter, GetterMock>;
@@ -104,7 +156,7 @@ mod tests {
 	fn executing_getters_works() {
 		let test_state = 23489u64;
 		let state_observer = Arc::new(TestStateObserver::new(test_state));
-		let getter_executor = TestGetterExecutor::new(state_observer);
+		let getter_executor = TestGetterExecutor::new(state_observer, Default::default());
 		let getter = GetterMock::trusted(dummy_trusted_getter());
 
 		let state_result = getter_executor
@@ -119,7 +171,7 @@ mod tests {
 	fn executing_public_getter_works() {
 		let test_state = 23489u64;
 		let state_observer = Arc::new(TestStateObserver::new(test_state));
-		let getter_executor = TestGetterExecutor::new(state_observer);
+		let getter_executor = TestGetterExecutor::new(state_observer, Default::default());
 		let getter = GetterMock::public(PublicGetterMock::some_value);
 
 		let state_result = getter_executor
@@ -129,6 +181,22 @@ mod tests {
 		let decoded_state: TestState = Decode::decode(&mut state_result.as_slice()).unwrap();
 		assert_eq!(decoded_state, test_state);
 	}
+	#[test]
+	fn executing_getter_with_proof_works() {
+		let test_state = 23489u64;
+		let state_observer = Arc::new(TestStateObserver::new(test_state));
+		let getter_executor = TestGetterExecutor::new(state_observer, Default::default());
+		let getter = GetterMock::trusted(dummy_trusted_getter());
+
+		let result = getter_executor
+			.execute_getter_with_proof(&ShardIdentifier::default(), getter.encode())
+			.unwrap();
+		let decoded_state: TestState =
+			Decode::decode(&mut result.value.unwrap().as_slice()).unwrap();
+		assert_eq!(decoded_state, test_state);
+		assert_eq!(result.state_root, H256::from_low_u64_be(test_state));
+	}
+
 	fn dummy_trusted_getter() -> TrustedGetterSignedMock {
 		TrustedGetterSignedMock { getter: TrustedGetterMock::some_value, signature: true }
 		//			TrustedGetter::nonce(AccountId::new([0u8; 32])),
@@ -39,6 +39,7 @@ pub mod sgx_reexport_prelude {
 
 pub mod error;
 pub mod getter_executor;
+pub mod state_commitment;
 pub mod state_getter;
 pub mod traits;
 
@@ -17,6 +17,7 @@
 
 use crate::{
 	error::Result,
+	state_commitment::StateCommitmentCache,
 	state_getter::GetState,
 	traits::{StateUpdateProposer, StfEnclaveSigning},
 	BatchExecutionResult, ExecutedOperation,
@@ -26,7 +27,7 @@ use core::fmt::Debug;
 use itp_sgx_externalities::SgxExternalitiesTrait;
 use itp_stf_primitives::{
 	traits::TrustedCallSigning,
-	types::{AccountId, KeyPair, ShardIdentifier, TrustedOperationOrHash},
+	types::{AccountId, GetterResultWithProof, KeyPair, ShardIdentifier, TrustedOperationOrHash},
 };
 use itp_types::H256;
 use sp_core::Pair;
@@ -158,4 +159,13 @@ where
 	fn get_state(_getter: G, state: &mut StateType) -> Result<Option<Vec<u8>>> {
 		Ok(Some(state.encode()))
 	}
+
+	fn get_state_with_proof(
+		_getter: G,
+		state: &mut StateType,
+		_shard: &ShardIdentifier,
+		_state_commitment_cache: &StateCommitmentCache,
+	) -> Result<GetterResultWithProof> {
+		Ok(GetterResultWithProof { value: Some(state.encode()), ..Default::default() })
+	}
 }
@@ -0,0 +1,272 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Merkle commitment to the state of a shard, as found in the `state_root` of a sidechain header.
+
+#[cfg(all(not(feature = "std"), feature = "sgx"))]
+use std::sync::SgxRwLock as RwLock;
+
+#[cfg(feature = "std")]
+use std::sync::RwLock;
+
+use crate::error::{Error, Result};
+use itp_sgx_externalities::SgxExternalities;
+use itp_storage::{storage_value_key, StorageProof, StorageProofProvider};
+use itp_types::{ShardIdentifier, H256};
+use sp_runtime::traits::BlakeTwo256;
+use std::{collections::BTreeMap, sync::Arc, vec::Vec};
+
+/// Prefix of every committed value.
+///
+/// It makes each value longer than the inline threshold of the trie layout, so values are stored
+/// as separate nodes. Hence, the nodes of neighbouring entries in a proof only contain hashes,
+/// and never the values themselves.
+pub const COMMITTED_VALUE_PREFIX: [u8; 33] = [0u8; 33];
+
+/// Trie over all committed entries of a state.
+pub type CommittedTrie = StorageProofProvider<BlakeTwo256>;
+
+/// Returns the value as it is committed in the trie.
+pub fn committed_value(value: &[u8]) -> Vec<u8> {
+	let mut committed = COMMITTED_VALUE_PREFIX.to_vec();
+	committed.extend_from_slice(value);
+	committed
+}
+
+/// Returns the original value of a value read from the trie, e.g. with a proof checker.
+pub fn value_from_commitment(committed: &[u8]) -> Option<&[u8]> {
+	committed.strip_prefix(&COMMITTED_VALUE_PREFIX[..])
+}
+
+/// Commitment to a state by the root of a Patricia-Merkle trie, which has the same layout as
+/// Substrate's storage. Hence, proofs can be checked with `itp_storage::StorageProofChecker`,
+/// see [`value_from_commitment`] for the values read with it.
+pub trait StateCommitment {
+	/// Builds the trie over all committed state entries.
+	fn committed_trie(&self) -> Result<CommittedTrie>;
+
+	/// Root of the trie over all committed state entries.
+	fn state_root(&self) -> Result<H256> {
+		Ok(self.committed_trie()?.root())
+	}
+}
+
+impl StateCommitment for SgxExternalities {
+	fn committed_trie(&self) -> Result<CommittedTrie> {
+		// The last block is only recorded in the state upon importing the block. So the header of
+		// the block itself can't commit to it, and it's excluded from the commitment.
+		let last_block_key = storage_value_key("System", "LastBlock");
+		let last_hash_key = storage_value_key("System", "LastHash");
+
+		Ok(StorageProofProvider::new(
+			self.state
+				.iter()
+				.filter(|(key, _)| **key != last_block_key && **key != last_hash_key)
+				.map(|(key, value)| (key, committed_value(value))),
+		)?)
+	}
+}
+
+/// Keeps the trie committed in the latest sidechain block of each shard, so that it's built
+/// only once per block, and not for every proof.
+#[derive(Default)]
+pub struct StateCommitmentCache {
+	tries: RwLock<BTreeMap<ShardIdentifier, Arc<CommittedTrie>>>,
+	prepared_tries: RwLock<BTreeMap<ShardIdentifier, Arc<CommittedTrie>>>,
+}
+
+impl StateCommitmentCache {
+	/// Commits to the state of a new block of the shard, and returns the state root.
+	pub fn commit<State: StateCommitment>(
+		&self,
+		shard: &ShardIdentifier,
+		state: &State,
+	) -> Result<H256> {
+		let state_root = self.prepare(shard, state)?;
+		self.commit_prepared(shard)?;
+		Ok(state_root)
+	}
+
+	/// Builds the trie of the state of a block that is yet to be imported, and returns the
+	/// state root.
+	///
+	/// Proofs are still created against the previously committed trie, until the block has been
+	/// imported and the trie is committed with [`Self::commit_prepared`].
+	pub fn prepare<State: StateCommitment>(
+		&self,
+		shard: &ShardIdentifier,
+		state: &State,
+	) -> Result<H256> {
+		let trie = state.committed_trie()?;
+		let state_root = trie.root();
+		self.prepared_tries
+			.write()
+			.map_err(|_| Error::LockPoisoning)?
+			.insert(*shard, Arc::new(trie));
+		Ok(state_root)
+	}
+
+	/// Commits the trie that was last prepared for the shard.
+	pub fn commit_prepared(&self, shard: &ShardIdentifier) -> Result<()> {
+		let trie = self
+			.prepared_tries
+			.write()
+			.map_err(|_| Error::LockPoisoning)?
+			.remove(shard)
+			.ok_or(Error::StateCommitmentOutdated)?;
+		self.tries.write().map_err(|_| Error::LockPoisoning)?.insert(*shard, trie);
+		Ok(())
+	}
+
+	/// Creates a proof for the given keys against the state root of the latest block of the shard.
+	///
+	/// Fails if any of the values in `state` differs from the committed one, i.e. if the state
+	/// has changed since that block.
+	pub fn prove_read(
+		&self,
+		shard: &ShardIdentifier,
+		state: &SgxExternalities,
+		keys: &[Vec<u8>],
+	) -> Result<(H256, StorageProof)> {
+		let trie = self
+			.tries
+			.read()
+			.map_err(|_| Error::LockPoisoning)?
+			.get(shard)
+			.cloned()
+			.ok_or(Error::StateCommitmentOutdated)?;
+
+		for key in keys {
+			let committed = trie.get(key)?;
+			if committed.as_deref().and_then(value_from_commitment)
+				!= state.state.get(key).map(|v| v.as_slice())
+			{
+				return Err(Error::StateCommitmentOutdated)
+			}
+		}
+		Ok((trie.root(), trie.prove_read(keys)?))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use core::assert_matches::assert_matches;
+	use itp_sgx_externalities::SgxExternalitiesTrait;
+	use itp_storage::StorageProofChecker;
+
+	fn state_with_entries() -> SgxExternalities {
+		let mut state = SgxExternalities::default();
+		state.insert(b"key".to_vec(), b"value".to_vec());
+		state.insert(b"other_key".to_vec(), b"other_value".to_vec());
+		state
+	}
+
+	#[test]
+	fn proof_against_state_root_can_be_checked() {
+		let shard = ShardIdentifier::default();
+		let state = state_with_entries();
+		let cache = StateCommitmentCache::default();
+		let state_root = cache.commit(&shard, &state).unwrap();
+
+		let (root, proof) = cache.prove_read(&shard, &state, &[b"key".to_vec()]).unwrap();
+
+		assert_eq!(root, state_root);
+		assert_eq!(root, state.state_root().unwrap());
+		let committed = StorageProofChecker::<BlakeTwo256>::check_proof(root, b"key", proof)
+			.unwrap()
+			.unwrap();
+		assert_eq!(value_from_commitment(&committed), Some(&b"value"[..]));
+	}
+
+	#[test]
+	fn proof_does_not_contain_values_of_other_entries() {
+		let shard = ShardIdentifier::default();
+		let state = state_with_entries();
+		let cache = StateCommitmentCache::default();
+		cache.commit(&shard, &state).unwrap();
+
+		let (_, proof) = cache.prove_read(&shard, &state, &[b"key".to_vec()]).unwrap();
+
+		assert!(!proof
+			.iter()
+			.any(|node| node.windows(b"other_value".len()).any(|w| w == b"other_value")));
+	}
+
+	#[test]
+	fn prove_read_fails_if_state_changed_since_commit() {
+		let shard = ShardIdentifier::default();
+		let mut state = state_with_entries();
+		let cache = StateCommitmentCache::default();
+		cache.commit(&shard, &state).unwrap();
+
+		state.insert(b"key".to_vec(), b"new_value".to_vec());
+
+		assert_matches!(
+			cache.prove_read(&shard, &state, &[b"key".to_vec()]),
+			Err(Error::StateCommitmentOutdated)
+		);
+	}
+
+	#[test]
+	fn prove_read_fails_without_commitment() {
+		let state = state_with_entries();
+
+		assert_matches!(
+			StateCommitmentCache::default().prove_read(
+				&ShardIdentifier::default(),
+				&state,
+				&[b"key".to_vec()]
+			),
+			Err(Error::StateCommitmentOutdated)
+		);
+	}
+
+	#[test]
+	fn prepared_trie_is_only_used_for_proofs_after_commit() {
+		let shard = ShardIdentifier::default();
+		let state = state_with_entries();
+		let cache = StateCommitmentCache::default();
+		let state_root = cache.commit(&shard, &state).unwrap();
+
+		let mut new_state = state.clone();
+		new_state.insert(b"key".to_vec(), b"new_value".to_vec());
+		let new_state_root = cache.prepare(&shard, &new_state).unwrap();
+
+		assert_ne!(new_state_root, state_root);
+		assert_eq!(cache.prove_read(&shard, &state, &[b"key".to_vec()]).unwrap().0, state_root);
+
+		cache.commit_prepared(&shard).unwrap();
+
+		assert_eq!(
+			cache.prove_read(&shard, &new_state, &[b"key".to_vec()]).unwrap().0,
+			new_state_root
+		);
+	}
+
+	#[test]
+	fn recording_the_last_block_does_not_change_the_state_root() {
+		let mut state = SgxExternalities::default();
+		state.insert(b"key".to_vec(), b"value".to_vec());
+		let state_root = state.state_root().unwrap();
+
+		state.insert(storage_value_key("System", "LastBlock"), vec![1, 2, 3]);
+		state.insert(storage_value_key("System", "LastHash"), vec![4, 5, 6]);
+
+		assert_eq!(state.state_root().unwrap(), state_root);
+	}
+}
@@ -15,12 +15,16 @@
 
 */
 
-use crate::error::{Error, Result};
+use crate::{
+	error::{Error, Result},
+	state_commitment::StateCommitmentCache,
+};
 use codec::Decode;
 use core::marker::PhantomData;
-use itp_sgx_externalities::SgxExternalities;
+use itp_sgx_externalities::{record_storage_reads, SgxExternalities};
 use itp_stf_interface::StateGetterInterface;
-use itp_stf_primitives::traits::GetterAuthorization;
+use itp_stf_primitives::{traits::GetterAuthorization, types::GetterResultWithProof};
+use itp_types::ShardIdentifier;
 use log::*;
 use std::vec::Vec;
 
@@ -31,6 +35,16 @@ pub trait GetState<StateType, G: PartialEq + Decode + GetterAuthorization> {
 	/// Also verifies the signature of the trusted getter and returns an error
 	/// if it's invalid.
 	fn get_state(getter: G, state: &mut StateType) -> Result<Option<Vec<u8>>>;
+
+	/// Executes a trusted getter like [`GetState::get_state`], and additionally returns a proof
+	/// of all state entries the getter has read, against the state root of the latest block of
+	/// the shard.
+	fn get_state_with_proof(
+		getter: G,
+		state: &mut StateType,
+		shard: &ShardIdentifier,
+		state_commitment_cache: &StateCommitmentCache,
+	) -> Result<GetterResultWithProof>;
 }
 
 pub struct StfStateGetter<Stf> {
@@ -50,11 +64,28 @@ where
 		debug!("getter authorized. calling into STF to get state");
 		Ok(Stf::execute_getter(state, getter))
 	}
+
+	fn get_state_with_proof(
+		getter: G,
+		state: &mut SgxExternalities,
+		shard: &ShardIdentifier,
+		state_commitment_cache: &StateCommitmentCache,
+	) -> Result<GetterResultWithProof> {
+		let (value, read_keys) = record_storage_reads(|| Self::get_state(getter, state));
+		let value = value?;
+
+		// Besides the entries that were read, the proof only contains hashes.
+		let read_keys: Vec<Vec<u8>> = read_keys.into_iter().collect();
+		let (state_root, proof) = state_commitment_cache.prove_read(shard, state, &read_keys)?;
+
+		Ok(GetterResultWithProof { value, state_root, read_keys, proof })
+	}
 }
 
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::state_commitment::StateCommitment;
 	use core::assert_matches::assert_matches;
 
 	use itp_test::mock::stf_mock::{
@@ -82,4 +113,41 @@ mod tests {
 		let mut state = SgxExternalities::default();
 		assert!(TestStateGetter::get_state(GetterMock::trusted(getter), &mut state).is_ok());
 	}
+
+	#[test]
+	fn upon_false_signature_get_stf_state_with_proof_errs() {
+		let getter =
+			TrustedGetterSignedMock { getter: TrustedGetterMock::some_value, signature: false };
+		let mut state = SgxExternalities::default();
+
+		assert_matches!(
+			TestStateGetter::get_state_with_proof(
+				GetterMock::trusted(getter),
+				&mut state,
+				&ShardIdentifier::default(),
+				&StateCommitmentCache::default()
+			),
+			Err(Error::GetterIsNotAuthorized)
+		);
+	}
+
+	#[test]
+	fn state_getter_with_proof_returns_proof_against_state_root() {
+		let getter =
+			TrustedGetterSignedMock { getter: TrustedGetterMock::some_value, signature: true };
+		let mut state = SgxExternalities::default();
+		let shard = ShardIdentifier::default();
+		let state_commitment_cache = StateCommitmentCache::default();
+		state_commitment_cache.commit(&shard, &state).unwrap();
+
+		let result = TestStateGetter::get_state_with_proof(
+			GetterMock::trusted(getter),
+			&mut state,
+			&shard,
+			&state_commitment_cache,
+		)
+		.unwrap();
+
+		assert_eq!(result.state_root, state.state_root().unwrap());
+	}
 }
@@ -24,12 +24,14 @@ extern crate alloc;
 
 use alloc::{sync::Arc, vec::Vec};
 use codec::{Decode, Encode};
-use core::fmt::Debug;
+use core::{fmt::Debug, mem::size_of};
 use itp_node_api_metadata::NodeMetadataTrait;
 use itp_node_api_metadata_provider::AccessNodeMetadata;
 use itp_stf_primitives::traits::TrustedCallVerification;
 use itp_types::{
-	parentchain::{AccountId, BlockHash, BlockNumber, ParentchainCall, ParentchainId},
+	parentchain::{
+		AccountId, BlockHash, BlockNumber, ParentchainCall, ParentchainId, TargetParentchainIndex,
+	},
 	Moment,
 };
 
@@ -47,6 +49,13 @@ pub trait InitState<State, AccountId> {
 	fn init_state(enclave_account: AccountId) -> State;
 }
 
+/// Interface to replace the enclave account of an initialized state.
+pub trait UpdateEnclaveAccount<State, AccountId> {
+	/// Sets the account the enclave signs its own trusted calls with, e.g. after the
+	/// shielding key it is derived from was rotated.
+	fn update_enclave_account(state: &mut State, enclave_account: AccountId);
+}
+
 /// Interface to query shard vault account for shard
 pub trait ShardVaultQuery<S> {
 	fn get_vault(state: &mut S) -> Option<(AccountId, ParentchainId)>;
@@ -129,19 +138,24 @@ pub struct BlockMetadata {
 	pub timestamp: Option<Moment>,
 }
 
-#[derive(Debug, Clone, Copy, Encode, Decode)]
+#[derive(Debug, Clone, Default, Encode, Decode)]
 pub struct ShardCreationInfo {
 	pub integritee: Option<BlockMetadata>,
-	pub target_a: Option<BlockMetadata>,
-	pub target_b: Option<BlockMetadata>,
+	pub targets: Vec<(TargetParentchainIndex, BlockMetadata)>,
 }
 
 impl ShardCreationInfo {
+	/// Upper bound of the encoded size, there can't be more than one entry per target index.
+	pub const MAX_ENCODED_LEN: usize = 1
+		+ size_of::<BlockMetadata>()
+		+ 2 + (TargetParentchainIndex::MAX as usize + 1)
+		* (size_of::<TargetParentchainIndex>() + size_of::<BlockMetadata>());
+
 	pub fn for_parentchain(&self, id: ParentchainId) -> Option<BlockMetadata> {
 		match id {
 			ParentchainId::Integritee => self.integritee,
-			ParentchainId::TargetA => self.target_a,
-			ParentchainId::TargetB => self.target_b,
+			ParentchainId::Target(index) =>
+				self.targets.iter().find(|(i, _)| *i == index).map(|(_, metadata)| *metadata),
 		}
 	}
 }
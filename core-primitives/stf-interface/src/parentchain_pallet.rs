@@ -18,25 +18,14 @@
 use itp_types::parentchain::{AccountId, Hash, ParentchainId};
 
 /// Interface trait of the parentchain pallet.
-pub trait ParentchainPalletInstancesInterface<State, ParentchainHeader> {
+pub trait ParentchainPalletInterface<State, ParentchainHeader> {
 	type Error;
 
 	/// Updates the block number, block hash and parent hash of the parentchain block.
-	fn update_parentchain_integritee_block(
-		state: &mut State,
-		header: ParentchainHeader,
-	) -> Result<(), Self::Error>;
-
-	/// Updates the block number, block hash and parent hash of the parentchain block.
-	fn update_parentchain_target_a_block(
-		state: &mut State,
-		header: ParentchainHeader,
-	) -> Result<(), Self::Error>;
-
-	/// Updates the block number, block hash and parent hash of the parentchain block.
-	fn update_parentchain_target_b_block(
+	fn update_parentchain_block(
 		state: &mut State,
 		header: ParentchainHeader,
+		parentchain_id: ParentchainId,
 	) -> Result<(), Self::Error>;
 
 	fn init_shard_vault_account(
@@ -201,3 +201,20 @@ impl<StateUpdate: Encode> StatePayload<StateUpdate> {
 		}
 	}
 }
+
+/// Result of a getter, together with a proof of all state entries the getter has read.
+///
+/// The proof is a set of trie nodes that is valid against `state_root`, which is the state root
+/// committed in the sidechain header. It can be checked with `itp_storage::StorageProofChecker`.
+/// The values in the trie are prefixed, see `itp_stf_executor::state_commitment`.
+#[derive(PartialEq, Eq, Clone, Debug, Default, Encode, Decode)]
+pub struct GetterResultWithProof {
+	/// Encoded result of the getter.
+	pub value: Option<Vec<u8>>,
+	/// State root the proof is valid against.
+	pub state_root: H256,
+	/// Storage keys that were read while executing the getter.
+	pub read_keys: Vec<Vec<u8>>,
+	/// Trie nodes needed to read the values of `read_keys`.
+	pub proof: Vec<Vec<u8>>,
+}
@@ -39,8 +39,14 @@ pub enum Error {
 	InvalidStateId(StateId),
 	#[error("Shard is invalid and does not exist: {0}")]
 	InvalidShard(ShardIdentifier),
+	#[error("Shard already exists: {0}")]
+	ShardAlreadyExists(ShardIdentifier),
 	#[error("State with hash {0} could not be found in the state repository")]
 	StateNotFoundInRepository(String),
+	#[error("No state checkpoint found to replay the state diff with ID {0} on")]
+	MissingStateCheckpoint(StateId),
+	#[error("Replaying the state diff with ID {0} resulted in an unexpected state hash")]
+	StateDiffHashMismatch(StateId),
 	#[error("State observer error: {0}")]
 	StateObserver(#[from] itp_stf_state_observer::error::Error),
 	#[error("Cache size for registry is zero")]
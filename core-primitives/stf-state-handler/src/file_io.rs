@@ -44,6 +44,53 @@ use std::{
 /// It is also the suffix of all past snapshots.
 pub const ENCRYPTED_STATE_FILE: &str = "state.bin";
 
+/// File name suffix of encrypted state diff segments.
+///
+/// A diff segment only contains the changes with respect to the preceding snapshot.
+pub const ENCRYPTED_STATE_DIFF_FILE: &str = "state_diff.bin";
+
+/// Suffix of snapshot files that have been removed from the snapshot history, but are still
+/// needed to replay the diff segments of newer snapshots.
+pub const PRUNED_SNAPSHOT_FILE_SUFFIX: &str = ".pruned";
+
+/// Suffix of snapshot files that have been re-encrypted with a new state key, but not yet
+/// replaced the original file.
+pub const REENCRYPTED_FILE_SUFFIX: &str = ".reencrypted";
+
+/// Kind of a state snapshot file.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum SnapshotFileKind {
+	/// Full state, can be loaded on its own.
+	Checkpoint,
+	/// State diff, has to be replayed on top of the preceding snapshots.
+	Diff,
+}
+
+/// A state snapshot file in a shard directory.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct SnapshotFile {
+	pub state_id: StateId,
+	pub kind: SnapshotFileKind,
+	pub pruned: bool,
+}
+
+impl SnapshotFile {
+	pub fn new(state_id: StateId, kind: SnapshotFileKind, pruned: bool) -> Self {
+		SnapshotFile { state_id, kind, pruned }
+	}
+
+	pub fn file_name(&self) -> String {
+		let file_name = match self.kind {
+			SnapshotFileKind::Checkpoint => to_file_name(self.state_id),
+			SnapshotFileKind::Diff => to_diff_file_name(self.state_id),
+		};
+		match self.pruned {
+			true => format!("{}{}", file_name, PRUNED_SNAPSHOT_FILE_SUFFIX),
+			false => file_name,
+		}
+	}
+}
+
 /// Helps with file system operations of all files relevant for the State.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct StateDir {
@@ -78,6 +125,18 @@ impl StateDir {
 		Ok(state_ids_for_shard(shard_path.as_path())?.collect())
 	}
 
+	/// Lists all snapshot files of a shard, including pruned ones, sorted by state ID
+	/// (oldest first).
+	pub fn list_snapshot_files_for_shard(
+		&self,
+		shard_identifier: &ShardIdentifier,
+	) -> Result<Vec<SnapshotFile>> {
+		let shard_path = self.shard_path(shard_identifier);
+		let mut snapshot_files: Vec<_> = snapshot_files_for_shard(shard_path.as_path())?.collect();
+		snapshot_files.sort_unstable_by_key(|snapshot_file| snapshot_file.state_id);
+		Ok(snapshot_files)
+	}
+
 	pub fn purge_shard_dir(&self, shard: &ShardIdentifier) {
 		let shard_dir_path = self.shard_path(shard);
 		if let Err(e) = std::fs::remove_dir_all(&shard_dir_path) {
@@ -98,8 +157,22 @@ impl StateDir {
 		self.shard_path(shard).join(to_file_name(state_id))
 	}
 
+	pub fn state_diff_file_path(&self, shard: &ShardIdentifier, state_id: StateId) -> PathBuf {
+		self.shard_path(shard).join(to_diff_file_name(state_id))
+	}
+
+	pub fn snapshot_file_path(
+		&self,
+		shard: &ShardIdentifier,
+		snapshot_file: &SnapshotFile,
+	) -> PathBuf {
+		self.shard_path(shard).join(snapshot_file.file_name())
+	}
+
+	/// Checks if a snapshot (checkpoint or diff segment) exists for the given state ID.
 	pub fn file_for_state_exists(&self, shard: &ShardIdentifier, state_id: StateId) -> bool {
 		self.state_file_path(shard, state_id).exists()
+			|| self.state_diff_file_path(shard, state_id).exists()
 	}
 
 	#[cfg(feature = "test")]
@@ -170,7 +243,7 @@ pub mod sgx {
 	use codec::Decode;
 	use core::fmt::Debug;
 	use itp_hashing::Hash;
-	use itp_sgx_crypto::{key_repository::AccessKey, StateCrypto};
+	use itp_sgx_crypto::{is_aead_ciphertext, key_repository::AccessKey, StateCrypto};
 	use itp_sgx_externalities::SgxExternalitiesTrait;
 	use itp_sgx_io::{read as io_read, write as io_write};
 	use itp_time_utils::duration_now;
@@ -178,10 +251,26 @@ pub mod sgx {
 	use log::*;
 	use std::{fs, marker::PhantomData, path::Path, sync::Arc};
 
+	/// Content of a state diff segment file.
+	///
+	/// The state diff has to be the last field, because its decoding consumes the entire input.
+	#[derive(Encode, Decode)]
+	struct StateDiffSegment<StateDiff> {
+		/// Hash of the state after applying the diff, used to verify the replay.
+		state_hash: H256,
+		state_diff: StateDiff,
+	}
+
 	/// SGX state file I/O.
+	///
+	/// Per default, every write persists the full state. With a checkpoint interval larger
+	/// than 1, only every n-th snapshot is a full checkpoint and the snapshots in between
+	/// are persisted as diff segments, which are replayed on top of the preceding checkpoint
+	/// when loading.
 	pub struct SgxStateFileIo<StateKeyRepository, State> {
 		state_key_repository: Arc<StateKeyRepository>,
 		state_dir: StateDir,
+		checkpoint_interval: usize,
 		_phantom: PhantomData<State>,
 	}
 
@@ -189,10 +278,28 @@ pub mod sgx {
 	where
 		StateKeyRepository: AccessKey,
 		<StateKeyRepository as AccessKey>::KeyType: StateCrypto,
-		State: SgxExternalitiesTrait,
+		State: SgxExternalitiesTrait + Hash<H256>,
+		<State as SgxExternalitiesTrait>::SgxExternalitiesType: Encode + Decode,
+		<State as SgxExternalitiesTrait>::SgxExternalitiesDiffType:
+			Encode + Decode + IntoIterator<Item = (Vec<u8>, Option<Vec<u8>>)>,
 	{
 		pub fn new(state_key_repository: Arc<StateKeyRepository>, state_dir: StateDir) -> Self {
-			SgxStateFileIo { state_key_repository, state_dir, _phantom: PhantomData }
+			Self::new_with_checkpoint_interval(state_key_repository, state_dir, 1)
+		}
+
+		/// Creates a state file I/O that writes a full checkpoint only every
+		/// `checkpoint_interval` snapshots, and diff segments otherwise.
+		pub fn new_with_checkpoint_interval(
+			state_key_repository: Arc<StateKeyRepository>,
+			state_dir: StateDir,
+			checkpoint_interval: usize,
+		) -> Self {
+			SgxStateFileIo {
+				state_key_repository,
+				state_dir,
+				checkpoint_interval: checkpoint_interval.max(1),
+				_phantom: PhantomData,
+			}
 		}
 
 		fn read(&self, path: &Path) -> Result<Vec<u8>> {
@@ -220,28 +327,13 @@ pub mod sgx {
 				.map_err(|e| Error::Other(format!("{:?}", e).into()))?;
 			Ok(state)
 		}
-	}
-
-	impl<StateKeyRepository, State> StateFileIo for SgxStateFileIo<StateKeyRepository, State>
-	where
-		StateKeyRepository: AccessKey,
-		<StateKeyRepository as AccessKey>::KeyType: StateCrypto,
-		State: SgxExternalitiesTrait + Hash<H256> + Debug,
-		<State as SgxExternalitiesTrait>::SgxExternalitiesType: Encode + Decode,
-	{
-		type StateType = State;
-		type HashType = H256;
 
-		fn load(
+		fn load_checkpoint(
 			&self,
 			shard_identifier: &ShardIdentifier,
-			state_id: StateId,
-		) -> Result<Self::StateType> {
-			if !self.state_dir.file_for_state_exists(shard_identifier, state_id) {
-				return Err(Error::InvalidStateId(state_id))
-			}
-
-			let state_path = self.state_dir.state_file_path(shard_identifier, state_id);
+			checkpoint: &SnapshotFile,
+		) -> Result<State> {
+			let state_path = self.state_dir.snapshot_file_path(shard_identifier, checkpoint);
 			let started_at = duration_now();
 			trace!("loading state from: {:?}", state_path);
 			let state_encoded = self.read(&state_path)?;
@@ -260,9 +352,161 @@ pub mod sgx {
 
 			trace!("state decoded successfully");
 			// Add empty state-diff.
-			let state_with_diff = State::new(state);
-			trace!("New state created: {:?}", state_with_diff);
-			Ok(state_with_diff)
+			Ok(State::new(state))
+		}
+
+		/// Loads the preceding checkpoint and replays all diff segments up to `state_id`.
+		fn load_from_diff_segments(
+			&self,
+			shard_identifier: &ShardIdentifier,
+			state_id: StateId,
+		) -> Result<State> {
+			let snapshot_files = self.state_dir.list_snapshot_files_for_shard(shard_identifier)?;
+			let position = position_of_snapshot(&snapshot_files, state_id)?;
+			let checkpoint_position = snapshot_files[..=position]
+				.iter()
+				.rposition(|snapshot_file| snapshot_file.kind == SnapshotFileKind::Checkpoint)
+				.ok_or(Error::MissingStateCheckpoint(state_id))?;
+
+			let checkpoint = &snapshot_files[checkpoint_position];
+			let mut state = self.load_checkpoint(shard_identifier, checkpoint)?;
+
+			for diff_segment in &snapshot_files[checkpoint_position + 1..=position] {
+				let diff_path = self.state_dir.snapshot_file_path(shard_identifier, diff_segment);
+				let segment_encoded = self.read(&diff_path)?;
+				let segment = StateDiffSegment::<
+					<State as SgxExternalitiesTrait>::SgxExternalitiesDiffType,
+				>::decode(&mut segment_encoded.as_slice())?;
+
+				for (key, maybe_value) in segment.state_diff {
+					match maybe_value {
+						Some(value) => {
+							state.insert(key, value);
+						},
+						None => {
+							state.remove(&key);
+						},
+					}
+				}
+
+				if state.hash() != segment.state_hash {
+					return Err(Error::StateDiffHashMismatch(diff_segment.state_id))
+				}
+			}
+
+			trace!(
+				"Replayed {} state diff segment(s) on top of checkpoint {}",
+				position - checkpoint_position,
+				checkpoint.state_id
+			);
+			state.prune_state_diff();
+			Ok(state)
+		}
+
+		/// A checkpoint is written if there is no preceding checkpoint, or if the number of
+		/// diff segments since the last checkpoint reached the checkpoint interval.
+		fn is_checkpoint_due(
+			&self,
+			shard_identifier: &ShardIdentifier,
+			state_id: StateId,
+		) -> Result<bool> {
+			if self.checkpoint_interval <= 1 {
+				return Ok(true)
+			}
+
+			let snapshot_files = self.state_dir.list_snapshot_files_for_shard(shard_identifier)?;
+			let diffs_since_checkpoint = snapshot_files
+				.iter()
+				.rev()
+				.filter(|snapshot_file| snapshot_file.state_id < state_id)
+				.position(|snapshot_file| snapshot_file.kind == SnapshotFileKind::Checkpoint);
+
+			Ok(match diffs_since_checkpoint {
+				Some(number_of_diffs) => number_of_diffs + 1 >= self.checkpoint_interval,
+				None => true,
+			})
+		}
+
+		fn write_snapshot_file(
+			&self,
+			shard_identifier: &ShardIdentifier,
+			snapshot_file: SnapshotFile,
+			plaintext: Vec<u8>,
+		) -> Result<()> {
+			let path = self.state_dir.snapshot_file_path(shard_identifier, &snapshot_file);
+			trace!("writing state to: {:?}", path);
+			let cyphertext = self.encrypt(plaintext)?;
+			io_write(&cyphertext, &path)?;
+
+			// Make sure we never have a checkpoint and a diff segment with the same state ID.
+			let other_kind = match snapshot_file.kind {
+				SnapshotFileKind::Checkpoint => SnapshotFileKind::Diff,
+				SnapshotFileKind::Diff => SnapshotFileKind::Checkpoint,
+			};
+			remove_file_if_exists(&self.state_dir.snapshot_file_path(
+				shard_identifier,
+				&SnapshotFile::new(snapshot_file.state_id, other_kind, false),
+			))
+		}
+
+		/// Deletes all pruned snapshot files that are no longer needed to replay
+		/// the diff segments of the remaining snapshots.
+		fn remove_obsolete_pruned_files(&self, shard_identifier: &ShardIdentifier) -> Result<()> {
+			let snapshot_files = self.state_dir.list_snapshot_files_for_shard(shard_identifier)?;
+			let required_from = match snapshot_files.iter().position(|file| !file.pruned) {
+				// Replaying the oldest live snapshot starts at its preceding checkpoint.
+				Some(oldest_live_position) => snapshot_files[..=oldest_live_position]
+					.iter()
+					.rposition(|file| file.kind == SnapshotFileKind::Checkpoint)
+					.unwrap_or_default(),
+				// Without any live snapshots, none of the pruned ones are needed anymore.
+				None => snapshot_files.len(),
+			};
+
+			for snapshot_file in snapshot_files[..required_from]
+				.iter()
+				.filter(|snapshot_file| snapshot_file.pruned)
+			{
+				trace!("Removing obsolete pruned snapshot {}", snapshot_file.state_id);
+				fs::remove_file(
+					self.state_dir.snapshot_file_path(shard_identifier, snapshot_file),
+				)?;
+			}
+			Ok(())
+		}
+	}
+
+	impl<StateKeyRepository, State> StateFileIo for SgxStateFileIo<StateKeyRepository, State>
+	where
+		StateKeyRepository: AccessKey,
+		<StateKeyRepository as AccessKey>::KeyType: StateCrypto,
+		State: SgxExternalitiesTrait + Hash<H256> + Debug,
+		<State as SgxExternalitiesTrait>::SgxExternalitiesType: Encode + Decode,
+		<State as SgxExternalitiesTrait>::SgxExternalitiesDiffType:
+			Encode + Decode + IntoIterator<Item = (Vec<u8>, Option<Vec<u8>>)>,
+	{
+		type StateType = State;
+		type HashType = H256;
+
+		fn load(
+			&self,
+			shard_identifier: &ShardIdentifier,
+			state_id: StateId,
+		) -> Result<Self::StateType> {
+			let checkpoint = SnapshotFile::new(state_id, SnapshotFileKind::Checkpoint, false);
+			let diff_segment = SnapshotFile::new(state_id, SnapshotFileKind::Diff, false);
+
+			let state = if self.state_dir.snapshot_file_path(shard_identifier, &checkpoint).exists()
+			{
+				self.load_checkpoint(shard_identifier, &checkpoint)?
+			} else if self.state_dir.snapshot_file_path(shard_identifier, &diff_segment).exists() {
+				self.load_from_diff_segments(shard_identifier, state_id)?
+			} else {
+				return Err(Error::InvalidStateId(state_id))
+			};
+
+			trace!("New state created: {:?}", state);
+			Ok(state)
 		}
 
 		fn compute_hash(
@@ -281,10 +525,19 @@ pub mod sgx {
 			state: &Self::StateType,
 		) -> Result<Self::HashType> {
 			self.state_dir.create_shard(&shard_identifier)?;
-			self.write(shard_identifier, state_id, state)
+			self.write_snapshot_file(
+				shard_identifier,
+				SnapshotFile::new(state_id, SnapshotFileKind::Checkpoint, false),
+				state.state().encode(),
+			)?;
+			Ok(state.hash())
 		}
 
-		/// Writes the state (without the state diff) encrypted into the enclave storage.
+		/// Writes the state encrypted into the enclave storage, either as full checkpoint
+		/// (without the state diff) or as diff segment (only the state diff).
+		///
+		/// For a diff segment, the state diff must contain all changes with respect to the
+		/// most recent snapshot of the shard.
 		/// Returns the hash of the saved state (independent of the diff!).
 		fn write(
 			&self,
@@ -292,15 +545,24 @@ pub mod sgx {
 			state_id: StateId,
 			state: &Self::StateType,
 		) -> Result<Self::HashType> {
-			let state_path = self.state_dir.state_file_path(shard_identifier, state_id);
-			trace!("writing state to: {:?}", state_path);
 			let started_at = duration_now();
-			// Only save the state, the state diff is pruned.
-			let cyphertext = self.encrypt(state.state().encode())?;
-
 			let state_hash = state.hash();
 
-			io_write(&cyphertext, &state_path)?;
+			if self.is_checkpoint_due(shard_identifier, state_id)? {
+				// Only save the state, the state diff is pruned.
+				self.write_snapshot_file(
+					shard_identifier,
+					SnapshotFile::new(state_id, SnapshotFileKind::Checkpoint, false),
+					state.state().encode(),
+				)?;
+			} else {
+				let segment = StateDiffSegment { state_hash, state_diff: state.state_diff() };
+				self.write_snapshot_file(
+					shard_identifier,
+					SnapshotFile::new(state_id, SnapshotFileKind::Diff, false),
+					segment.encode(),
+				)?;
+			}
 
 			let write_duration = duration_now() - started_at;
 			trace!("state encrypted and stored in {:.4}s", write_duration.as_secs_f32());
@@ -308,8 +570,30 @@ pub mod sgx {
 			Ok(state_hash)
 		}
 
+		/// Removes a state snapshot.
+		///
+		/// If the succeeding snapshot is a diff segment, it still depends on the removed one.
+		/// In that case the file is only marked as pruned, and deleted as soon as it is
+		/// not needed anymore.
 		fn remove(&self, shard_identifier: &ShardIdentifier, state_id: StateId) -> Result<()> {
-			Ok(fs::remove_file(self.state_dir.state_file_path(shard_identifier, state_id))?)
+			let snapshot_files = self.state_dir.list_snapshot_files_for_shard(shard_identifier)?;
+			let position = position_of_snapshot(&snapshot_files, state_id)?;
+			let snapshot_file = snapshot_files[position];
+			let snapshot_path = self.state_dir.snapshot_file_path(shard_identifier, &snapshot_file);
+
+			match snapshot_files.get(position + 1) {
+				Some(successor) if successor.kind == SnapshotFileKind::Diff => {
+					let pruned_snapshot_file =
+						SnapshotFile::new(state_id, snapshot_file.kind, true);
+					fs::rename(
+						snapshot_path,
+						self.state_dir.snapshot_file_path(shard_identifier, &pruned_snapshot_file),
+					)?;
+				},
+				_ => fs::remove_file(snapshot_path)?,
+			}
+
+			self.remove_obsolete_pruned_files(shard_identifier)
 		}
 
 		fn shard_exists(&self, shard_identifier: &ShardIdentifier) -> bool {
@@ -324,13 +608,153 @@ pub mod sgx {
 			self.state_dir.list_state_ids_for_shard(shard)
 		}
 	}
+
+	/// Re-encrypts all snapshot files of all shards that are still encrypted with the legacy
+	/// (unauthenticated) state key with the authenticated state key.
+	///
+	/// Every file is re-encrypted into a staged file first, which then replaces the original
+	/// one. Files that are already encrypted with the authenticated format are skipped, so an
+	/// interrupted migration can simply be run again. Returns the number of migrated files.
+	pub fn migrate_legacy_state_encryption<LegacyKey, Key>(
+		state_dir: &StateDir,
+		legacy_key: &LegacyKey,
+		key: &Key,
+	) -> Result<usize>
+	where
+		LegacyKey: StateCrypto,
+		Key: StateCrypto,
+	{
+		let mut migrated_files = 0;
+		for shard in state_dir.list_shards()? {
+			for snapshot_file in state_dir.list_snapshot_files_for_shard(&shard)? {
+				let path = state_dir.snapshot_file_path(&shard, &snapshot_file);
+				let mut bytes = io_read(&path)?;
+				if bytes.is_empty() || is_aead_ciphertext(&bytes) {
+					continue
+				}
+
+				legacy_key
+					.decrypt(&mut bytes)
+					.map_err(|e| Error::Other(format!("{:?}", e).into()))?;
+				key.encrypt(&mut bytes).map_err(|e| Error::Other(format!("{:?}", e).into()))?;
+				let staged_path = reencrypted_file_path(&path);
+				io_write(&bytes, &staged_path)?;
+				fs::rename(&staged_path, &path)?;
+				migrated_files += 1;
+			}
+		}
+		Ok(migrated_files)
+	}
+
+	/// First step of a state key rotation: writes a copy of every snapshot file, re-encrypted
+	/// with `new_key`, next to the original file.
+	///
+	/// The original files are untouched, so the state stays readable with `key` until the
+	/// new key is persisted and [`complete_state_reencryption`] is run. Returns the number of
+	/// staged files.
+	pub fn stage_state_reencryption<Key, NewKey>(
+		state_dir: &StateDir,
+		key: &Key,
+		new_key: &NewKey,
+	) -> Result<usize>
+	where
+		Key: StateCrypto,
+		NewKey: StateCrypto,
+	{
+		let mut staged_files = 0;
+		for shard in state_dir.list_shards()? {
+			for snapshot_file in state_dir.list_snapshot_files_for_shard(&shard)? {
+				let path = state_dir.snapshot_file_path(&shard, &snapshot_file);
+				let mut bytes = io_read(&path)?;
+				if !bytes.is_empty() {
+					key.decrypt(&mut bytes).map_err(|e| Error::Other(format!("{:?}", e).into()))?;
+					new_key
+						.encrypt(&mut bytes)
+						.map_err(|e| Error::Other(format!("{:?}", e).into()))?;
+				}
+				io_write(&bytes, &reencrypted_file_path(&path))?;
+				staged_files += 1;
+			}
+		}
+		Ok(staged_files)
+	}
+
+	/// Second step of a state key rotation: replaces the original snapshot files with the
+	/// staged ones that can be decrypted with the current state `key`. Staged files that are
+	/// encrypted with another key stem from an aborted rotation and are removed.
+	///
+	/// Relies on authenticated encryption to tell the keys apart, and is safe to run again
+	/// after an interruption. Returns the number of replaced files.
+	pub fn complete_state_reencryption<Key: StateCrypto>(
+		state_dir: &StateDir,
+		key: &Key,
+	) -> Result<usize> {
+		let mut replaced_files = 0;
+		for shard in state_dir.list_shards()? {
+			let shard_path = state_dir.shard_path(&shard);
+			for (staged_file_name, file_name) in reencrypted_files_for_shard(&shard_path)? {
+				let staged_path = shard_path.join(staged_file_name);
+				let mut bytes = io_read(&staged_path)?;
+				if bytes.is_empty() || key.decrypt(&mut bytes).is_ok() {
+					fs::rename(&staged_path, shard_path.join(file_name))?;
+					replaced_files += 1;
+				} else {
+					warn!(
+						"Removing re-encrypted state file {:?} of an aborted key rotation",
+						staged_path
+					);
+					fs::remove_file(&staged_path)?;
+				}
+			}
+		}
+		Ok(replaced_files)
+	}
+
+	fn reencrypted_file_path(path: &Path) -> PathBuf {
+		let mut file_name = path.file_name().map(|f| f.to_os_string()).unwrap_or_default();
+		file_name.push(REENCRYPTED_FILE_SUFFIX);
+		path.with_file_name(file_name)
+	}
+
+	/// Returns the staged file names together with the names of the files they replace.
+	fn reencrypted_files_for_shard(shard_path: &Path) -> Result<Vec<(String, String)>> {
+		Ok(items_in_directory(shard_path)?
+			.filter_map(|item| {
+				let file_name = String::from(item.strip_suffix(REENCRYPTED_FILE_SUFFIX)?);
+				Some((item, file_name))
+			})
+			.collect())
+	}
+
+	fn position_of_snapshot(snapshot_files: &[SnapshotFile], state_id: StateId) -> Result<usize> {
+		snapshot_files
+			.iter()
+			.position(|snapshot_file| snapshot_file.state_id == state_id && !snapshot_file.pruned)
+			.ok_or(Error::InvalidStateId(state_id))
+	}
+
+	fn remove_file_if_exists(path: &Path) -> Result<()> {
+		if path.exists() {
+			fs::remove_file(path)?;
+		}
+		Ok(())
+	}
 }
 
-/// Lists all files with a valid state snapshot naming pattern.
+/// Lists all files with a valid state snapshot naming pattern, ignoring pruned snapshots.
 pub(crate) fn state_ids_for_shard(shard_path: &Path) -> Result<impl Iterator<Item = StateId>> {
-	Ok(items_in_directory(shard_path)?.filter_map(|item| {
-		match extract_state_id_from_file_name(&item) {
-			Some(state_id) => Some(state_id),
+	Ok(snapshot_files_for_shard(shard_path)?
+		.filter(|snapshot_file| !snapshot_file.pruned)
+		.map(|snapshot_file| snapshot_file.state_id))
+}
+
+/// Lists all files with a valid state snapshot naming pattern, including pruned snapshots.
+fn snapshot_files_for_shard(shard_path: &Path) -> Result<impl Iterator<Item = SnapshotFile>> {
+	Ok(items_in_directory(shard_path)?.filter_map(
+		|item| match extract_snapshot_file_from_file_name(&item) {
+			Some(snapshot_file) => Some(snapshot_file),
+			// Staged files of a state key rotation are handled separately.
+			None if item.ends_with(REENCRYPTED_FILE_SUFFIX) => None,
 			None => {
 				log::warn!(
 				"Found item ({}) that does not match state snapshot naming pattern, ignoring it",
@@ -338,8 +762,8 @@ pub(crate) fn state_ids_for_shard(shard_path: &Path) -> Result<impl Iterator<Ite
 			);
 				None
 			},
-		}
-	}))
+		},
+	))
 }
 
 /// Returns an iterator over all valid shards in a directory.
@@ -382,9 +806,32 @@ fn to_file_name(state_id: StateId) -> String {
 	format!("{}_{}", state_id, ENCRYPTED_STATE_FILE)
 }
 
+fn to_diff_file_name(state_id: StateId) -> String {
+	format!("{}_{}", state_id, ENCRYPTED_STATE_DIFF_FILE)
+}
+
+#[cfg(test)]
 fn extract_state_id_from_file_name(file_name: &str) -> Option<StateId> {
-	let state_id_str = file_name.strip_suffix(format!("_{}", ENCRYPTED_STATE_FILE).as_str())?;
-	state_id_str.parse::<StateId>().ok()
+	extract_snapshot_file_from_file_name(file_name)
+		.filter(|snapshot_file| !snapshot_file.pruned)
+		.map(|snapshot_file| snapshot_file.state_id)
+}
+
+fn extract_snapshot_file_from_file_name(file_name: &str) -> Option<SnapshotFile> {
+	let (file_name, pruned) = match file_name.strip_suffix(PRUNED_SNAPSHOT_FILE_SUFFIX) {
+		Some(file_name) => (file_name, true),
+		None => (file_name, false),
+	};
+	let (state_id_str, kind) =
+		match file_name.strip_suffix(format!("_{}", ENCRYPTED_STATE_FILE).as_str()) {
+			Some(state_id_str) => (state_id_str, SnapshotFileKind::Checkpoint),
+			None => (
+				file_name.strip_suffix(format!("_{}", ENCRYPTED_STATE_DIFF_FILE).as_str())?,
+				SnapshotFileKind::Diff,
+			),
+		};
+	let state_id = state_id_str.parse::<StateId>().ok()?;
+	Some(SnapshotFile::new(state_id, kind, pruned))
 }
 
 #[cfg(test)]
@@ -432,4 +879,30 @@ mod tests {
 		)
 		.is_none());
 	}
+
+	#[test]
+	fn extract_snapshot_file_from_file_name_works() {
+		let checkpoint = SnapshotFile::new(42, SnapshotFileKind::Checkpoint, false);
+		let diff = SnapshotFile::new(43, SnapshotFileKind::Diff, false);
+		let pruned_diff = SnapshotFile::new(44, SnapshotFileKind::Diff, true);
+
+		assert_eq!(to_file_name(42), checkpoint.file_name());
+		for snapshot_file in [checkpoint, diff, pruned_diff] {
+			assert_eq!(
+				snapshot_file,
+				extract_snapshot_file_from_file_name(snapshot_file.file_name().as_str()).unwrap()
+			);
+		}
+
+		assert_eq!(
+			1234u128,
+			extract_state_id_from_file_name(to_diff_file_name(1234).as_str()).unwrap()
+		);
+		assert!(extract_state_id_from_file_name(pruned_diff.file_name().as_str()).is_none());
+		assert!(extract_snapshot_file_from_file_name(
+			format!("1234_{}-other", ENCRYPTED_STATE_DIFF_FILE).as_str()
+		)
+		.is_none());
+		assert!(extract_snapshot_file_from_file_name(ENCRYPTED_STATE_DIFF_FILE).is_none());
+	}
 }
@@ -35,6 +35,16 @@ pub trait HandleState {
 	/// Initializes a default state for the shard and returns its hash.
 	fn initialize_shard(&self, shard: ShardIdentifier) -> Result<Self::HashType>;
 
+	/// Initialize a new shard with the given state.
+	///
+	/// Fails if the shard exists already. The check and the write happen under the same lock,
+	/// so a shard that is initialized concurrently is never overridden.
+	fn initialize_shard_with(
+		&self,
+		state: Self::StateT,
+		shard: &ShardIdentifier,
+	) -> Result<Self::HashType>;
+
 	/// Execute a function that acts (immutably) on the current state.
 	///
 	/// This allows access to the state, without any cloning.
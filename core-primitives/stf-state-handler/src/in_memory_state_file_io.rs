@@ -218,7 +218,7 @@ fn sgx_externalities_selector() -> InnerStateSelector<SgxExternalitiesType, SgxE
 }
 
 fn sgx_externalities_wrapper() -> ExternalStateGenerator<SgxExternalitiesType, SgxExternalities> {
-	Box::new(|s| SgxExternalities { state: s, state_diff: Default::default() })
+	Box::new(|s| SgxExternalities { state: s, ..Default::default() })
 }
 
 #[cfg(feature = "sgx")]
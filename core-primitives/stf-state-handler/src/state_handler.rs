@@ -142,6 +142,17 @@ where
 
 		state_snapshots_lock.update(shard, state, state_hash)
 	}
+
+	/// Executes a function while no state snapshot can be written, e.g. to re-encrypt
+	/// the state files on disk.
+	pub fn execute_with_snapshots_locked<E, R>(&self, executing_function: E) -> Result<R>
+	where
+		E: FnOnce() -> R,
+	{
+		let _state_snapshots_lock =
+			self.state_snapshot_repository.write().map_err(|_| Error::LockPoisoning)?;
+		Ok(executing_function())
+	}
 }
 
 impl<Repository, StateObserver, StateInitializer> HandleState
@@ -162,6 +173,18 @@ where
 		self.reset(initialized_state, &shard)
 	}
 
+	fn initialize_shard_with(
+		&self,
+		state: Self::StateT,
+		shard: &ShardIdentifier,
+	) -> Result<Self::HashType> {
+		let state_write_lock = self.states_map_lock.write().map_err(|_| Error::LockPoisoning)?;
+		if state_write_lock.contains_key(shard) {
+			return Err(Error::ShardAlreadyExists(*shard))
+		}
+		self.write_after_mutation(state, state_write_lock, shard)
+	}
+
 	fn execute_on_current<E, R>(&self, shard: &ShardIdentifier, executing_function: E) -> Result<R>
 	where
 		E: FnOnce(&Self::StateT, Self::HashType) -> R,
@@ -208,23 +231,33 @@ where
 	) -> Result<Self::HashType> {
 		debug!("Writing state");
 		trace!("State: {:?}", state);
-		state.prune_state_diff(); // Remove state diff before storing.
 		let state_hash = state.hash();
-		// We create a state copy here, in order to serve the state observer. This does not scale
-		// well and we will want a better solution in the future, maybe with #459.
-		state_lock.insert(*shard, (state.clone(), state_hash));
-		drop(state_lock); // Drop the write lock as early as possible.
-
+		// The snapshot repository may persist the state as diff to the previous snapshot. The
+		// state diff is not reliable for that, as it could have been pruned in between.
+		state.stage_snapshot_diff();
+		// Keep the write lock until the snapshot is persisted, so that concurrent writes
+		// are persisted in the same order as they are applied.
 		self.update_state_snapshot(shard, &state, state_hash)?;
 
+		// Remove the state diff before storing it and handing it to the observer. We create a
+		// state copy here, in order to serve the state observer. This does not scale well and we
+		// will want a better solution in the future, maybe with #459.
+		state.prune_state_diff();
+		state_lock.insert(*shard, (state.clone(), state_hash));
+		drop(state_lock);
+
 		self.state_observer.queue_state_update(*shard, state)?;
 		Ok(state_hash)
 	}
 
-	fn reset(&self, state: Self::StateT, shard: &ShardIdentifier) -> Result<Self::HashType> {
+	fn reset(&self, mut state: Self::StateT, shard: &ShardIdentifier) -> Result<Self::HashType> {
 		debug!("Resetting state");
 		trace!("Resetting state: {:?}", state);
 		let state_write_lock = self.states_map_lock.write().map_err(|_| Error::LockPoisoning)?;
+		// The state is not necessarily derived from the current one, so its changes are unknown.
+		if let Some((current_state, _)) = state_write_lock.get(shard) {
+			state.recompute_snapshot_diff(current_state.state());
+		}
 		self.write_after_mutation(state, state_write_lock, shard)
 	}
 }
@@ -259,7 +292,7 @@ mod tests {
 	use itp_sgx_externalities::{SgxExternalities, SgxExternalitiesType};
 	use itp_stf_state_observer::mock::UpdateStateMock;
 	use itp_types::H256;
-	use std::{collections::VecDeque, sync::Arc, thread};
+	use std::{assert_matches::assert_matches, collections::VecDeque, sync::Arc, thread};
 
 	type TestState = SgxExternalities;
 	type TestHash = H256;
@@ -356,6 +389,19 @@ mod tests {
 		assert!(!state_handler.shard_exists(&ShardIdentifier::random()).unwrap());
 	}
 
+	#[test]
+	fn initialize_shard_with_fails_for_existing_shard() {
+		let shard_id = ShardIdentifier::random();
+		let state_handler = default_state_handler();
+		state_handler.initialize_shard_with(create_state(1), &shard_id).unwrap();
+
+		let result = state_handler.initialize_shard_with(create_state(2), &shard_id);
+
+		assert_matches!(result, Err(Error::ShardAlreadyExists(_)));
+		let (state, _) = state_handler.load_cloned(&shard_id).unwrap();
+		assert_eq!(state.get(&"key_1".encode()), Some(&1u64.encode()));
+	}
+
 	#[test]
 	fn load_from_repository_works() {
 		let state_observer = Arc::new(TestStateObserver::default());
@@ -402,6 +448,30 @@ mod tests {
 		assert_eq!(state_without_diff, loaded_state);
 	}
 
+	#[test]
+	fn write_after_mutation_hands_complete_state_diff_to_repository() {
+		let shard_id = ShardIdentifier::random();
+		let state_handler = default_state_handler();
+		state_handler.reset(create_state(1u64), &shard_id).unwrap();
+
+		let (lock, mut state) = state_handler.load_for_mutation(&shard_id).unwrap();
+		state.insert("key_2".encode(), 2u64.encode());
+		// Pruning in between must not affect the state diff handed to the repository.
+		state.prune_state_diff();
+		state.insert("key_3".encode(), 3u64.encode());
+		state_handler.write_after_mutation(state, lock, &shard_id).unwrap();
+
+		let latest_snapshot = state_handler
+			.state_snapshot_repository
+			.read()
+			.unwrap()
+			.load_latest(&shard_id)
+			.unwrap();
+		assert_eq!(2, latest_snapshot.state_diff().len());
+		assert_eq!(Some(&Some(2u64.encode())), latest_snapshot.state_diff().get(&"key_2".encode()));
+		assert!(state_handler.load_cloned(&shard_id).unwrap().0.state_diff().is_empty());
+	}
+
 	fn default_state_handler() -> Arc<TestStateHandler> {
 		let state_observer = Arc::new(TestStateObserver::default());
 		let state_initializer = Arc::new(TestStateInitializer::new(Default::default()));
@@ -16,11 +16,18 @@
 */
 
 use crate::{
-	file_io::{sgx::SgxStateFileIo, StateDir, StateFileIo},
+	file_io::{
+		sgx::{
+			complete_state_reencryption, migrate_legacy_state_encryption, stage_state_reencryption,
+			SgxStateFileIo,
+		},
+		SnapshotFileKind, StateDir, StateFileIo, REENCRYPTED_FILE_SUFFIX,
+	},
 	handle_state::HandleState,
 	in_memory_state_file_io::sgx::create_in_memory_state_io_from_shards_directories,
 	query_shard_state::QueryShardState,
 	state_handler::StateHandler,
+	state_snapshot_primitives::StateId,
 	state_snapshot_repository::{StateSnapshotRepository, VersionedStateAccess},
 	state_snapshot_repository_loader::StateSnapshotRepositoryLoader,
 	test::mocks::initialize_state_mock::InitializeStateMock,
@@ -28,22 +35,24 @@ use crate::{
 use codec::{Decode, Encode};
 use itp_hashing::Hash;
 use itp_sgx_crypto::{
-	get_aes_repository,
-	key_repository::{AccessKey, KeyRepository},
-	Aes, AesSeal, StateCrypto,
+	get_aes_gcm_repository, get_aes_repository, is_aead_ciphertext,
+	key_repository::{AccessKey, KeyRepository, MutateKey},
+	Aes, AesGcm, AesGcmSeal, AesSeal, StateCrypto,
 };
 use itp_sgx_externalities::{SgxExternalities, SgxExternalitiesTrait, SgxExternalitiesType};
-use itp_sgx_io::write;
+use itp_sgx_io::{read, write};
 use itp_sgx_temp_dir::TempDir;
 use itp_stf_state_observer::state_observer::StateObserver;
 use itp_types::{ShardIdentifier, H256};
-use std::{sync::Arc, thread, vec::Vec};
+use std::{path::Path, sync::Arc, thread, vec, vec::Vec};
 
 const STATE_SNAPSHOTS_CACHE_SIZE: usize = 3;
 
-type StateKeyRepository = KeyRepository<Aes, AesSeal>;
+type StateKeyRepository = KeyRepository<AesGcm, AesGcmSeal>;
+type LegacyStateKeyRepository = KeyRepository<Aes, AesSeal>;
 type TestStateInitializer = InitializeStateMock<SgxExternalities>;
 type TestStateFileIo = SgxStateFileIo<StateKeyRepository, SgxExternalities>;
+type LegacyTestStateFileIo = SgxStateFileIo<LegacyStateKeyRepository, SgxExternalities>;
 type TestStateRepository = StateSnapshotRepository<TestStateFileIo>;
 type TestStateRepositoryLoader =
 	StateSnapshotRepositoryLoader<TestStateFileIo, TestStateInitializer>;
@@ -69,7 +78,7 @@ pub fn test_encrypt_decrypt_state_type_works() {
 	// given
 	let state = given_hello_world_state();
 	let temp_dir = TempDir::with_prefix("test_encrypt_decrypt_state_type_works").unwrap();
-	let state_key = get_aes_repository(temp_dir.path().to_path_buf())
+	let state_key = get_aes_gcm_repository(temp_dir.path().to_path_buf())
 		.unwrap()
 		.retrieve_key()
 		.unwrap();
@@ -273,6 +282,171 @@ pub fn test_state_files_from_handler_can_be_loaded_again() {
 	);
 }
 
+pub fn test_delta_file_io_writes_diff_segments_and_replays_them() {
+	let shard: ShardIdentifier = [23u8; 32].into();
+	let (_temp_dir, state_key_access, state_dir) =
+		test_setup("test_delta_file_io_writes_diff_segments_and_replays_them", &shard);
+
+	let file_io =
+		TestStateFileIo::new_with_checkpoint_interval(state_key_access, state_dir.clone(), 3);
+
+	let mut state = SgxExternalities::new(Default::default());
+	file_io.initialize_shard(&shard, 1, &state).unwrap();
+
+	let mut state_hashes = Vec::new();
+	for state_id in 2u128..=4u128 {
+		state.prune_state_diff();
+		state.insert("counter".encode(), state_id.encode());
+		state.insert(state_id.encode(), "value".encode());
+		state_hashes.push(file_io.write(&shard, state_id, &state).unwrap());
+	}
+
+	let snapshot_kinds: Vec<_> = state_dir
+		.list_snapshot_files_for_shard(&shard)
+		.unwrap()
+		.into_iter()
+		.map(|snapshot_file| snapshot_file.kind)
+		.collect();
+	assert_eq!(
+		snapshot_kinds,
+		vec![
+			SnapshotFileKind::Checkpoint,
+			SnapshotFileKind::Diff,
+			SnapshotFileKind::Diff,
+			SnapshotFileKind::Checkpoint
+		]
+	);
+
+	let loaded_state = file_io.load(&shard, 3).unwrap();
+	assert_eq!(state_hashes[1], loaded_state.hash());
+	assert_eq!(&3u128.encode(), loaded_state.get("counter".encode().as_slice()).unwrap());
+	assert!(loaded_state.state_diff().is_empty());
+	assert_eq!(state.state, file_io.load(&shard, 4).unwrap().state);
+}
+
+pub fn test_delta_file_io_keeps_removed_snapshots_until_no_longer_needed() {
+	let shard: ShardIdentifier = [24u8; 32].into();
+	let (_temp_dir, state_key_access, state_dir) =
+		test_setup("test_delta_file_io_keeps_removed_snapshots_until_no_longer_needed", &shard);
+
+	let file_io =
+		TestStateFileIo::new_with_checkpoint_interval(state_key_access, state_dir.clone(), 3);
+
+	let mut state = SgxExternalities::new(Default::default());
+	file_io.initialize_shard(&shard, 1, &state).unwrap();
+	for state_id in 2u128..=5u128 {
+		state.prune_state_diff();
+		state.insert("counter".encode(), state_id.encode());
+		file_io.write(&shard, state_id, &state).unwrap();
+	}
+
+	// Snapshot 3 depends on 1 and 2, so these are only marked as pruned.
+	file_io.remove(&shard, 1).unwrap();
+	file_io.remove(&shard, 2).unwrap();
+	assert_eq!(vec![3, 4, 5], sorted_state_ids(&state_dir, &shard));
+	assert_eq!(5, state_dir.list_snapshot_files_for_shard(&shard).unwrap().len());
+	assert_eq!(
+		&3u128.encode(),
+		file_io.load(&shard, 3).unwrap().get("counter".encode().as_slice()).unwrap()
+	);
+
+	// Snapshot 4 is a checkpoint, so once 3 is removed, none of the older files are needed.
+	file_io.remove(&shard, 3).unwrap();
+	assert_eq!(vec![4, 5], sorted_state_ids(&state_dir, &shard));
+	assert_eq!(2, state_dir.list_snapshot_files_for_shard(&shard).unwrap().len());
+	assert_eq!(state.state, file_io.load(&shard, 5).unwrap().state);
+}
+
+pub fn test_loading_tampered_state_file_fails() {
+	let shard: ShardIdentifier = [25u8; 32].into();
+	let (_temp_dir, state_key_access, state_dir) =
+		test_setup("test_loading_tampered_state_file_fails", &shard);
+
+	let file_io = TestStateFileIo::new(state_key_access, state_dir.clone());
+	file_io.initialize_shard(&shard, 1, &given_hello_world_state()).unwrap();
+
+	let state_file_path = state_dir.state_file_path(&shard, 1);
+	let mut encrypted_state = read(&state_file_path).unwrap();
+	assert!(is_aead_ciphertext(&encrypted_state));
+	let last = encrypted_state.len() - 1;
+	encrypted_state[last] ^= 1;
+	write(&encrypted_state, &state_file_path).unwrap();
+
+	assert!(file_io.load(&shard, 1).is_err());
+}
+
+pub fn test_migrate_legacy_state_encryption_reencrypts_all_snapshot_files() {
+	let shard: ShardIdentifier = [26u8; 32].into();
+	let temp_dir =
+		TempDir::with_prefix("test_migrate_legacy_state_encryption_reencrypts_all_snapshot_files")
+			.unwrap();
+	let state_dir = StateDir::new(temp_dir.path().to_path_buf());
+	state_dir.given_initialized_shard(&shard);
+
+	let legacy_key_access = Arc::new(get_aes_repository(temp_dir.path().to_path_buf()).unwrap());
+	let legacy_file_io = LegacyTestStateFileIo::new_with_checkpoint_interval(
+		legacy_key_access.clone(),
+		state_dir.clone(),
+		3,
+	);
+	let mut state = SgxExternalities::new(Default::default());
+	legacy_file_io.initialize_shard(&shard, 1, &state).unwrap();
+	for state_id in 2u128..=3u128 {
+		state.prune_state_diff();
+		state.insert("counter".encode(), state_id.encode());
+		legacy_file_io.write(&shard, state_id, &state).unwrap();
+	}
+
+	// The AES-GCM key is derived from the legacy key.
+	let state_key_access = Arc::new(get_aes_gcm_repository(temp_dir.path().to_path_buf()).unwrap());
+	let legacy_key = legacy_key_access.retrieve_key().unwrap();
+	let state_key = state_key_access.retrieve_key().unwrap();
+
+	assert_eq!(3, migrate_legacy_state_encryption(&state_dir, &legacy_key, &state_key).unwrap());
+	// Running the migration again does not touch the migrated files.
+	assert_eq!(0, migrate_legacy_state_encryption(&state_dir, &legacy_key, &state_key).unwrap());
+
+	assert!(is_aead_ciphertext(&read(state_dir.state_diff_file_path(&shard, 3)).unwrap()));
+	// The staged files replaced the original ones.
+	let mut staged_path = state_dir.state_diff_file_path(&shard, 3).into_os_string();
+	staged_path.push(REENCRYPTED_FILE_SUFFIX);
+	assert!(!Path::new(&staged_path).exists());
+	let file_io = TestStateFileIo::new(state_key_access, state_dir);
+	assert_eq!(state.state, file_io.load(&shard, 3).unwrap().state);
+}
+
+pub fn test_state_reencryption_replaces_files_only_after_key_update() {
+	let shard: ShardIdentifier = [27u8; 32].into();
+	let (_temp_dir, state_key_access, state_dir) =
+		test_setup("test_state_reencryption_replaces_files_only_after_key_update", &shard);
+	let file_io = TestStateFileIo::new_with_checkpoint_interval(
+		state_key_access.clone(),
+		state_dir.clone(),
+		2,
+	);
+	let mut state = SgxExternalities::new(Default::default());
+	file_io.initialize_shard(&shard, 1, &state).unwrap();
+	state.insert("counter".encode(), 2u32.encode());
+	file_io.write(&shard, 2, &state).unwrap();
+
+	let key = state_key_access.retrieve_key().unwrap();
+	let new_key = AesGcm::new([7u8; 16]);
+
+	// An aborted rotation leaves the state files untouched.
+	assert_eq!(2, stage_state_reencryption(&state_dir, &key, &new_key).unwrap());
+	assert_eq!(0, complete_state_reencryption(&state_dir, &key).unwrap());
+	assert_eq!(state.state, file_io.load(&shard, 2).unwrap().state);
+
+	assert_eq!(2, stage_state_reencryption(&state_dir, &key, &new_key).unwrap());
+	state_key_access.update_key(new_key).unwrap();
+	assert_eq!(2, complete_state_reencryption(&state_dir, &new_key).unwrap());
+
+	assert_eq!(state.state, file_io.load(&shard, 2).unwrap().state);
+	let mut state_ids = file_io.list_state_ids_for_shard(&shard).unwrap();
+	state_ids.sort();
+	assert_eq!(vec![1, 2], state_ids);
+}
+
 pub fn test_list_state_ids_ignores_files_not_matching_the_pattern() {
 	let shard: ShardIdentifier = [21u8; 32].into();
 	let (_temp_dir, state_key_access, state_dir) =
@@ -342,6 +516,12 @@ fn update_state(
 	state_handler.write_after_mutation(state_to_mutate, lock, shard).unwrap()
 }
 
+fn sorted_state_ids(state_dir: &StateDir, shard: &ShardIdentifier) -> Vec<StateId> {
+	let mut state_ids = state_dir.list_state_ids_for_shard(shard).unwrap();
+	state_ids.sort_unstable();
+	state_ids
+}
+
 fn given_hello_world_state() -> SgxExternalities {
 	let key: Vec<u8> = "hello".encode();
 	let value: Vec<u8> = "world".encode();
@@ -352,7 +532,7 @@ fn given_hello_world_state() -> SgxExternalities {
 
 fn test_setup(id: &str, shard: &ShardIdentifier) -> (TempDir, Arc<StateKeyRepository>, StateDir) {
 	let temp_dir = TempDir::with_prefix(id).unwrap();
-	let state_key_access = Arc::new(get_aes_repository(temp_dir.path().to_path_buf()).unwrap());
+	let state_key_access = Arc::new(get_aes_gcm_repository(temp_dir.path().to_path_buf()).unwrap());
 	let state_dir = StateDir::new(temp_dir.path().to_path_buf());
 	state_dir.given_initialized_shard(shard);
 
@@ -13,6 +13,8 @@ pub enum Error {
 	StorageRootMismatch,
 	#[error("Storage value unavailable")]
 	StorageValueUnavailable,
+	#[error("Failed to build storage trie")]
+	TrieConstructionFailed,
 	#[error(transparent)]
 	#[cfg(feature = "std")]
 	Codec(#[from] codec::Error),
@@ -39,5 +41,6 @@ pub enum Error {
 	/// InvalidStorageProof,
 	StorageRootMismatch,
 	StorageValueUnavailable,
+	TrieConstructionFailed,
 	Codec(codec::Error),
 }
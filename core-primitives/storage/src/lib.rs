@@ -27,9 +27,11 @@ pub use error::Error;
 pub use frame_metadata::v14::StorageHasher;
 pub use keys::*;
 pub use proof::*;
+pub use storage_proof_provider::*;
 pub use verify_storage_proof::*;
 
 pub mod error;
 pub mod keys;
 pub mod proof;
+pub mod storage_proof_provider;
 pub mod verify_storage_proof;
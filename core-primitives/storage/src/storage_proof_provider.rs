@@ -0,0 +1,149 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Logic for creating storage proofs, which can be checked with the [`StorageProofChecker`].
+//!
+//! [`StorageProofChecker`]: crate::proof::StorageProofChecker
+
+use crate::{error::Error, proof::StorageProof};
+use sp_core::Hasher;
+use sp_std::vec::Vec;
+use sp_trie::{LayoutV1, MemoryDB, Recorder, Trie, TrieDBBuilder, TrieDBMutBuilder, TrieMut};
+
+/// Merklizes a set of key-value pairs into a Patricia-Merkle trie, with the same layout as
+/// Substrate's storage, and creates proofs for entries of it.
+pub struct StorageProofProvider<H: Hasher> {
+	root: H::Out,
+	db: MemoryDB<H>,
+}
+
+impl<H: Hasher> StorageProofProvider<H> {
+	/// Builds the trie from the given entries.
+	pub fn new<K, V>(entries: impl IntoIterator<Item = (K, V)>) -> Result<Self, Error>
+	where
+		K: AsRef<[u8]>,
+		V: AsRef<[u8]>,
+	{
+		let mut db = MemoryDB::default();
+		let mut root = Default::default();
+		{
+			let mut trie = TrieDBMutBuilder::<LayoutV1<H>>::new(&mut db, &mut root).build();
+			for (key, value) in entries {
+				trie.insert(key.as_ref(), value.as_ref())
+					.map_err(|_| Error::TrieConstructionFailed)?;
+			}
+			// The root is only written back once the trie is dropped.
+		}
+		Ok(StorageProofProvider { root, db })
+	}
+
+	/// The storage root of the trie.
+	pub fn root(&self) -> H::Out {
+		self.root
+	}
+
+	/// Reads the value stored under the key.
+	pub fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+		TrieDBBuilder::<LayoutV1<H>>::new(&self.db, &self.root)
+			.build()
+			.get(key)
+			.map_err(|_| Error::StorageValueUnavailable)
+	}
+
+	/// Creates a proof for the given keys, which contains all trie nodes needed to read
+	/// their values, or to prove that they do not exist.
+	pub fn prove_read<K: AsRef<[u8]>>(
+		&self,
+		keys: impl IntoIterator<Item = K>,
+	) -> Result<StorageProof, Error> {
+		let mut recorder = Recorder::<LayoutV1<H>>::new();
+		{
+			let trie = TrieDBBuilder::<LayoutV1<H>>::new(&self.db, &self.root)
+				.with_recorder(&mut recorder)
+				.build();
+			for key in keys {
+				trie.get(key.as_ref()).map_err(|_| Error::StorageValueUnavailable)?;
+			}
+		}
+
+		let mut proof: StorageProof =
+			recorder.drain().into_iter().map(|record| record.data).collect();
+		// Nodes shared between several keys are only needed once.
+		proof.sort();
+		proof.dedup();
+		Ok(proof)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::proof::StorageProofChecker;
+	use sp_core::{Blake2Hasher, H256};
+
+	fn test_entries() -> Vec<(Vec<u8>, Vec<u8>)> {
+		vec![
+			(b"key1".to_vec(), b"value1".to_vec()),
+			(b"key2".to_vec(), b"value2".to_vec()),
+			(b"key3".to_vec(), b"value3".to_vec()),
+			// Value is too big to fit in a branch node
+			(b"key11".to_vec(), vec![0u8; 32]),
+		]
+	}
+
+	#[test]
+	fn created_proof_is_accepted_by_storage_proof_checker() {
+		let provider = StorageProofProvider::<Blake2Hasher>::new(test_entries()).unwrap();
+		let proof = provider.prove_read([&b"key1"[..], &b"key11"[..], &b"key22"[..]]).unwrap();
+
+		let checker = StorageProofChecker::<Blake2Hasher>::new(provider.root(), proof).unwrap();
+		assert_eq!(checker.read_value(b"key1"), Ok(Some(b"value1".to_vec())));
+		assert_eq!(checker.read_value(b"key11"), Ok(Some(vec![0u8; 32])));
+		assert_eq!(checker.read_value(b"key22"), Ok(None));
+		assert_eq!(checker.read_value(b"key3"), Err(Error::StorageValueUnavailable));
+	}
+
+	#[test]
+	fn get_returns_inserted_values() {
+		let provider = StorageProofProvider::<Blake2Hasher>::new(test_entries()).unwrap();
+
+		assert_eq!(provider.get(b"key11"), Ok(Some(vec![0u8; 32])));
+		assert_eq!(provider.get(b"key22"), Ok(None));
+	}
+
+	#[test]
+	fn root_is_independent_of_insertion_order() {
+		let mut reversed_entries = test_entries();
+		reversed_entries.reverse();
+
+		assert_eq!(
+			StorageProofProvider::<Blake2Hasher>::new(test_entries()).unwrap().root(),
+			StorageProofProvider::<Blake2Hasher>::new(reversed_entries).unwrap().root()
+		);
+	}
+
+	#[test]
+	fn proof_is_rejected_for_other_root() {
+		let provider = StorageProofProvider::<Blake2Hasher>::new(test_entries()).unwrap();
+		let proof = provider.prove_read([&b"key1"[..]]).unwrap();
+
+		assert_eq!(
+			StorageProofChecker::<Blake2Hasher>::check_proof(H256::random(), b"key1", proof),
+			Err(Error::StorageRootMismatch)
+		);
+	}
+}
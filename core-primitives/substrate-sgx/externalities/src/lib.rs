@@ -25,11 +25,12 @@ use codec::{Decode, Encode, EncodeAppend};
 use core::ops::Bound;
 use derive_more::{Deref, DerefMut, From, IntoIterator};
 use itp_hashing::Hash;
+use scope_limited::record_storage_read;
 use serde::{Deserialize, Serialize};
 use sp_core::{hashing::blake2_256, H256};
 use std::{collections::BTreeMap, vec, vec::Vec};
 
-pub use scope_limited::{set_and_run_with_externalities, with_externalities};
+pub use scope_limited::{record_storage_reads, set_and_run_with_externalities, with_externalities};
 
 // Unfortunately we cannot use `serde_with::serde_as` to serialize our map (which would be very convenient)
 // because it has pulls in the serde and serde_json dependency with `std`, not `default-features=no`.
@@ -63,10 +64,22 @@ pub struct SgxExternalitiesType(#[serde(with = "vectorize")] InternalMap<Vec<u8>
 )]
 pub struct SgxExternalitiesDiffType(#[serde(with = "vectorize")] InternalMap<Option<Vec<u8>>>);
 
-#[derive(Clone, Debug, Default, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Eq, Encode, Decode, Serialize, Deserialize)]
 pub struct SgxExternalities {
 	pub state: SgxExternalitiesType,
 	pub state_diff: SgxExternalitiesDiffType,
+	/// Changes since the state was last persisted. Unlike the state diff, these are not pruned
+	/// during execution and are never encoded.
+	#[codec(skip)]
+	#[serde(skip)]
+	pub snapshot_diff: SgxExternalitiesDiffType,
+}
+
+// The snapshot diff is bookkeeping of the persistence and does not affect the state itself.
+impl PartialEq for SgxExternalities {
+	fn eq(&self, other: &Self) -> bool {
+		self.state == other.state && self.state_diff == other.state_diff
+	}
 }
 
 pub trait StateHash {
@@ -119,6 +132,16 @@ pub trait SgxExternalitiesTrait {
 	/// Prunes the state diff.
 	fn prune_state_diff(&mut self);
 
+	/// Replaces the state diff with all changes since the last call, independent of any pruning
+	/// of the state diff in between.
+	fn stage_snapshot_diff(&mut self);
+
+	/// Replaces the snapshot diff with the complete diff of the current state against
+	/// `previous_state`.
+	///
+	/// Only needed if the state was not derived from `previous_state`, as it iterates the whole state.
+	fn recompute_snapshot_diff(&mut self, previous_state: &Self::SgxExternalitiesType);
+
 	/// Execute the given closure while `self` is set as externalities.
 	///
 	/// Returns the result of the given closure.
@@ -134,7 +157,7 @@ where
 	type SgxExternalitiesDiffType = SgxExternalitiesDiffType;
 
 	fn new(state: Self::SgxExternalitiesType) -> Self {
-		Self { state, state_diff: Default::default() }
+		Self { state, state_diff: Default::default(), snapshot_diff: Default::default() }
 	}
 
 	fn state(&self) -> &Self::SgxExternalitiesType {
@@ -147,21 +170,25 @@ where
 
 	fn insert(&mut self, key: Vec<u8>, value: Vec<u8>) -> Option<Vec<u8>> {
 		self.state_diff.insert(key.clone(), Some(value.clone()));
+		self.snapshot_diff.insert(key.clone(), Some(value.clone()));
 		self.state.insert(key, value)
 	}
 
 	fn append(&mut self, key: Vec<u8>, value: Vec<u8>) {
 		let current = self.state.entry(key.clone()).or_default();
 		let updated_value = StorageAppend::new(current).append(value);
-		self.state_diff.insert(key, Some(updated_value));
+		self.state_diff.insert(key.clone(), Some(updated_value.clone()));
+		self.snapshot_diff.insert(key, Some(updated_value));
 	}
 
 	fn remove(&mut self, key: &[u8]) -> Option<Vec<u8>> {
 		self.state_diff.insert(key.to_vec(), None);
+		self.snapshot_diff.insert(key.to_vec(), None);
 		self.state.remove(key)
 	}
 
 	fn get(&self, key: &[u8]) -> Option<&Vec<u8>> {
+		record_storage_read(key);
 		self.state.get(key)
 	}
 
@@ -170,18 +197,43 @@ where
 	}
 
 	fn contains_key(&self, key: &[u8]) -> bool {
+		record_storage_read(key);
 		self.state.contains_key(key)
 	}
 
 	fn next_storage_key(&self, key: &[u8]) -> Option<Vec<u8>> {
+		record_storage_read(key);
 		let range = (Bound::Excluded(key), Bound::Unbounded);
-		self.state.range::<[u8], _>(range).next().map(|(k, _v)| k.to_vec()) // directly return k as _v is never None in our case
+		let next_key = self.state.range::<[u8], _>(range).next().map(|(k, _v)| k.to_vec()); // directly return k as _v is never None in our case
+		if let Some(next_key) = &next_key {
+			record_storage_read(next_key);
+		}
+		next_key
 	}
 
 	fn prune_state_diff(&mut self) {
 		self.state_diff.clear();
 	}
 
+	fn stage_snapshot_diff(&mut self) {
+		self.state_diff = core::mem::take(&mut self.snapshot_diff);
+	}
+
+	fn recompute_snapshot_diff(&mut self, previous_state: &Self::SgxExternalitiesType) {
+		let mut snapshot_diff = SgxExternalitiesDiffType::default();
+		for (key, value) in self.state.iter() {
+			if previous_state.get(key) != Some(value) {
+				snapshot_diff.insert(key.clone(), Some(value.clone()));
+			}
+		}
+		for key in previous_state.keys() {
+			if !self.state.contains_key(key) {
+				snapshot_diff.insert(key.clone(), None);
+			}
+		}
+		self.snapshot_diff = snapshot_diff;
+	}
+
 	fn clear_prefix(&mut self, key_prefix: &[u8], _maybe_limit: Option<u32>) -> u32 {
 		// Inspired by Substrate https://github.com/paritytech/substrate/blob/c8653447fc8ef8d95a92fe164c96dffb37919e85/primitives/state-machine/src/basic.rs#L242-L254
 		let to_remove = self
@@ -358,6 +410,90 @@ pub mod tests {
 		assert_eq!(ext.get(&world), None);
 	}
 
+	#[test]
+	fn recompute_snapshot_diff_works() {
+		let mut previous = SgxExternalities::default();
+		previous.insert(b"unchanged".to_vec(), b"value".to_vec());
+		previous.insert(b"changed".to_vec(), b"old".to_vec());
+		previous.insert(b"removed".to_vec(), b"value".to_vec());
+		previous.prune_state_diff();
+
+		let mut current = previous.clone();
+		current.insert(b"changed".to_vec(), b"new".to_vec());
+		current.insert(b"added".to_vec(), b"value".to_vec());
+		current.remove(b"removed");
+		// Simulate an unrelated change, which is not part of the diff against `previous`.
+		current.insert(b"unchanged".to_vec(), b"value".to_vec());
+
+		current.recompute_snapshot_diff(&previous.state);
+
+		assert_eq!(current.snapshot_diff.len(), 3);
+		assert_eq!(current.snapshot_diff.get(&b"changed"[..]), Some(&Some(b"new".to_vec())));
+		assert_eq!(current.snapshot_diff.get(&b"added"[..]), Some(&Some(b"value".to_vec())));
+		assert_eq!(current.snapshot_diff.get(&b"removed"[..]), Some(&None));
+	}
+
+	#[test]
+	fn stage_snapshot_diff_keeps_pruned_changes() {
+		let mut ext = SgxExternalities::default();
+		ext.insert(b"changed".to_vec(), b"value".to_vec());
+		ext.prune_state_diff();
+		ext.remove(b"removed");
+
+		ext.stage_snapshot_diff();
+
+		assert_eq!(ext.state_diff.len(), 2);
+		assert_eq!(ext.state_diff.get(&b"changed"[..]), Some(&Some(b"value".to_vec())));
+		assert_eq!(ext.state_diff.get(&b"removed"[..]), Some(&None));
+		assert!(ext.snapshot_diff.is_empty());
+	}
+
+	#[test]
+	fn snapshot_diff_is_not_encoded() {
+		let mut ext = SgxExternalities::default();
+		ext.insert(b"key".to_vec(), b"value".to_vec());
+
+		let decoded = SgxExternalities::decode(&mut ext.encode().as_slice()).unwrap();
+
+		assert_eq!(decoded, ext);
+		assert!(decoded.snapshot_diff.is_empty());
+	}
+
+	#[test]
+	fn record_storage_reads_records_all_read_keys() {
+		let mut externalities = SgxExternalities::default();
+		externalities.insert(b"a".to_vec(), b"1".to_vec());
+		externalities.insert(b"b".to_vec(), b"2".to_vec());
+		externalities.insert(b"c".to_vec(), b"3".to_vec());
+
+		let (value, read_keys) = record_storage_reads(|| {
+			externalities.execute_with(|| {
+				with_externalities(|e| {
+					let _ = e.contains_key(b"missing");
+					let _ = e.next_storage_key(b"a");
+					e.get(b"c").cloned()
+				})
+				.unwrap()
+			})
+		});
+
+		assert_eq!(value, Some(b"3".to_vec()));
+		let expected_keys: Vec<Vec<u8>> =
+			vec![b"a".to_vec(), b"b".to_vec(), b"c".to_vec(), b"missing".to_vec()];
+		assert_eq!(read_keys.into_iter().collect::<Vec<_>>(), expected_keys);
+	}
+
+	#[test]
+	fn storage_reads_are_not_recorded_outside_of_recording_scope() {
+		let mut externalities = SgxExternalities::default();
+		externalities.insert(b"a".to_vec(), b"1".to_vec());
+
+		let _ = externalities.get(b"a");
+		let ((), read_keys) = record_storage_reads(|| {});
+
+		assert!(read_keys.is_empty());
+	}
+
 	#[test]
 	fn clear_prefix_works() {
 		let mut externalities = SgxExternalities::default();
@@ -15,11 +15,14 @@
 
 */
 
-//! Stores the externalities in an `environmental` value to make it scope limited available.
+//! Stores the externalities and the storage read recorder in `environmental` values to make them
+//! scope limited available.
 
 use crate::SgxExternalities;
+use std::{collections::BTreeSet, vec::Vec};
 
 environmental::environmental!(ext: SgxExternalities);
+environmental::environmental!(read_keys: BTreeSet<Vec<u8>>);
 
 /// Set the given externalities while executing the given closure. To get access to the
 /// externalities while executing the given closure [`with_externalities`] grants access to them.
@@ -36,3 +39,18 @@ pub fn set_and_run_with_externalities<F: FnOnce() -> R, R>(ext: &mut SgxExternal
 pub fn with_externalities<F: FnOnce(&mut SgxExternalities) -> R, R>(f: F) -> Option<R> {
 	ext::with(f)
 }
+
+/// Execute the given closure while recording the keys of all storage entries that are read from
+/// the externalities in the meantime.
+///
+/// Returns the result of the closure and the recorded keys.
+pub fn record_storage_reads<F: FnOnce() -> R, R>(f: F) -> (R, BTreeSet<Vec<u8>>) {
+	let mut keys = BTreeSet::new();
+	let result = read_keys::using(&mut keys, f);
+	(result, keys)
+}
+
+/// Record a read of the given key, if we are within [`record_storage_reads`].
+pub(crate) fn record_storage_read(key: &[u8]) {
+	read_keys::with(|keys| keys.insert(key.to_vec()));
+}
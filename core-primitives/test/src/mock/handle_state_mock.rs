@@ -56,6 +56,18 @@ impl HandleState for HandleStateMock {
 		self.reset(StfState::default(), &shard)
 	}
 
+	fn initialize_shard_with(
+		&self,
+		state: Self::StateT,
+		shard: &ShardIdentifier,
+	) -> Result<Self::HashType> {
+		let write_lock = self.state_map.write().unwrap();
+		if write_lock.contains_key(shard) {
+			return Err(Error::ShardAlreadyExists(*shard))
+		}
+		self.write_after_mutation(state, write_lock, shard)
+	}
+
 	fn execute_on_current<E, R>(&self, shard: &ShardIdentifier, executing_function: E) -> Result<R>
 	where
 		E: FnOnce(&Self::StateT, Self::HashType) -> R,
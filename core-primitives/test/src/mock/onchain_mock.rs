@@ -30,13 +30,19 @@ use itp_types::{
 };
 use sgx_types::*;
 use sp_core::H256;
-use sp_runtime::{traits::Header as HeaderTrait, AccountId32, OpaqueExtrinsic};
+use sp_runtime::{
+	generic::SignedBlock,
+	traits::{Block as BlockTrait, Header as HeaderTrait},
+	AccountId32, Justifications, OpaqueExtrinsic,
+};
 use sp_std::prelude::*;
 use std::collections::HashMap;
 
 #[derive(Default, Clone, Debug)]
 pub struct OnchainMock {
 	inner: HashMap<Vec<u8>, Vec<u8>>,
+	blocks: HashMap<H256, (Vec<u8>, Vec<u8>)>,
+	justifications: HashMap<H256, Justifications>,
 	mr_enclave: [u8; SGX_HASH_SIZE],
 }
 
@@ -71,6 +77,21 @@ impl OnchainMock {
 		self
 	}
 
+	pub fn with_block<Block: BlockTrait<Hash = H256>>(
+		mut self,
+		signed_block: &SignedBlock<Block>,
+		raw_events: Vec<u8>,
+	) -> Self {
+		self.blocks
+			.insert(signed_block.block.header().hash(), (signed_block.encode(), raw_events));
+		self
+	}
+
+	pub fn with_justifications(mut self, hash: H256, justifications: Justifications) -> Self {
+		self.justifications.insert(hash, justifications);
+		self
+	}
+
 	pub fn with_mr_enclave(mut self, mr_enclave: [u8; SGX_HASH_SIZE]) -> Self {
 		self.mr_enclave = mr_enclave;
 		self
@@ -120,6 +141,10 @@ impl EnclaveAttestationOCallApi for OnchainMock {
 		todo!()
 	}
 
+	fn get_dcap_collateral(&self, _fmspc: [u8; 6]) -> SgxResult<Vec<u8>> {
+		todo!()
+	}
+
 	fn get_qve_report_on_quote(
 		&self,
 		_quote: Vec<u8>,
@@ -159,6 +184,27 @@ impl EnclaveSidechainOCallApi for OnchainMock {
 		Ok(())
 	}
 
+	fn propose_finality_votes<SignedFinalityVote: Encode>(
+		&self,
+		_votes: Vec<SignedFinalityVote>,
+	) -> SgxResult<()> {
+		Ok(())
+	}
+
+	fn store_sidechain_justifications<Justification: Encode>(
+		&self,
+		_justifications: Vec<Justification>,
+	) -> SgxResult<()> {
+		Ok(())
+	}
+
+	fn fetch_sidechain_finalized_head<Justification: Decode>(
+		&self,
+		_shard_identifier: ShardIdentifier,
+	) -> SgxResult<Option<Justification>> {
+		Ok(None)
+	}
+
 	fn fetch_sidechain_blocks_from_peer<SignedSidechainBlock: Decode>(
 		&self,
 		_last_imported_block_hash: BlockHash,
@@ -187,10 +233,21 @@ impl EnclaveOnChainOCallApi for OnchainMock {
 
 	fn worker_request<Header: HeaderTrait<Hash = H256>, V: Encode + Decode>(
 		&self,
-		_req: Vec<WorkerRequest>,
+		req: Vec<WorkerRequest>,
 		_: &ParentchainId,
 	) -> SgxResult<Vec<WorkerResponse<Header, V>>> {
-		Ok(Vec::new())
+		Ok(req
+			.into_iter()
+			.filter_map(|request| match request {
+				WorkerRequest::ChainBlock(hash) =>
+					Some(WorkerResponse::ChainBlock(self.blocks.get(&hash).cloned())),
+				WorkerRequest::ChainBlockJustifications(hash) =>
+					Some(WorkerResponse::ChainBlockJustifications(
+						self.justifications.get(&hash).cloned(),
+					)),
+				_ => None,
+			})
+			.collect())
 	}
 
 	fn get_storage_verified<Header: HeaderTrait<Hash = H256>, V: Decode>(
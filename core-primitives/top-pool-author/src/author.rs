@@ -17,21 +17,28 @@
 
 #[cfg(all(not(feature = "std"), feature = "sgx"))]
 use crate::sgx_reexport_prelude::*;
-use core::fmt::Debug;
+use core::{fmt::Debug, time::Duration};
 
 use crate::{
 	client_error::Error as ClientError,
 	error::{Error as StateRpcError, Result},
+	persistence::{PendingTrustedOperation, PersistTopPool},
 	top_filter::Filter,
 	traits::{AuthorApi, OnBlockImported},
 };
 use codec::{Decode, Encode};
-use itp_sgx_crypto::{key_repository::AccessKey, ShieldingCryptoDecrypt};
+use itc_direct_rpc_server::WatchAccountOperations;
+use itp_sgx_crypto::{
+	is_x25519_ciphertext,
+	key_repository::{AccessKey, AccessRetiredKey},
+	ShieldingCryptoDecrypt, X25519KeyPair,
+};
 use itp_stf_primitives::{
 	traits::{PoolTransactionValidation, TrustedCallVerification},
 	types::{AccountId, TrustedOperation as StfTrustedOperation, TrustedOperationOrHash},
 };
 use itp_stf_state_handler::query_shard_state::QueryShardState;
+use itp_time_utils::now_as_millis;
 use itp_top_pool::{
 	error::{Error as PoolError, IntoPoolError},
 	primitives::{
@@ -41,7 +48,10 @@ use itp_top_pool::{
 };
 use itp_types::{BlockHash as SidechainBlockHash, ShardIdentifier};
 use jsonrpc_core::{
-	futures::future::{ready, TryFutureExt},
+	futures::{
+		executor,
+		future::{ready, TryFutureExt},
+	},
 	Error as RpcError,
 };
 use log::*;
@@ -83,6 +93,19 @@ where
 	top_filter: TopFilter,
 	state_facade: Arc<StateFacade>,
 	shielding_key_repo: Arc<ShieldingKeyRepository>,
+	x25519_shielding_key_repo: Option<Arc<dyn AccessKey<KeyType = X25519KeyPair> + Send + Sync>>,
+	retired_x25519_shielding_key_repo:
+		Option<Arc<dyn AccessRetiredKey<KeyType = X25519KeyPair> + Send + Sync>>,
+	retired_shielding_key_repo: Option<
+		Arc<
+			dyn AccessRetiredKey<KeyType = <ShieldingKeyRepository as AccessKey>::KeyType>
+				+ Send
+				+ Sync,
+		>,
+	>,
+	top_pool_persistence: Option<Arc<dyn PersistTopPool>>,
+	account_operation_watcher:
+		Option<Arc<dyn WatchAccountOperations<Hash = TxHash, AccountId = AccountId>>>,
 }
 
 impl<TopPool, TopFilter, StateFacade, ShieldingKeyRepository, TCS, G>
@@ -103,13 +126,132 @@ where
 		state_facade: Arc<StateFacade>,
 		encryption_key: Arc<ShieldingKeyRepository>,
 	) -> Self {
-		Author { top_pool, top_filter, state_facade, shielding_key_repo: encryption_key }
+		Author {
+			top_pool,
+			top_filter,
+			state_facade,
+			shielding_key_repo: encryption_key,
+			x25519_shielding_key_repo: None,
+			retired_x25519_shielding_key_repo: None,
+			retired_shielding_key_repo: None,
+			top_pool_persistence: None,
+			account_operation_watcher: None,
+		}
+	}
+
+	/// Additionally accept trusted operations that are encrypted with the X25519 (hybrid)
+	/// shielding key. Operations encrypted with the RSA shielding key are still accepted.
+	pub fn with_x25519_shielding_key(
+		mut self,
+		x25519_shielding_key_repo: Arc<dyn AccessKey<KeyType = X25519KeyPair> + Send + Sync>,
+	) -> Self {
+		self.x25519_shielding_key_repo = Some(x25519_shielding_key_repo);
+		self
+	}
+
+	/// Additionally accept trusted operations that are encrypted with the X25519 shielding key
+	/// that was replaced by a key rotation, as long as its grace period has not ended.
+	pub fn with_retired_x25519_shielding_key(
+		mut self,
+		retired_x25519_shielding_key_repo: Arc<
+			dyn AccessRetiredKey<KeyType = X25519KeyPair> + Send + Sync,
+		>,
+	) -> Self {
+		self.retired_x25519_shielding_key_repo = Some(retired_x25519_shielding_key_repo);
+		self
+	}
+
+	/// Additionally accept trusted operations that are encrypted with the shielding key
+	/// that was replaced by a key rotation, as long as its grace period has not ended.
+	pub fn with_retired_shielding_key(
+		mut self,
+		retired_shielding_key_repo: Arc<
+			dyn AccessRetiredKey<KeyType = <ShieldingKeyRepository as AccessKey>::KeyType>
+				+ Send
+				+ Sync,
+		>,
+	) -> Self {
+		self.retired_shielding_key_repo = Some(retired_shielding_key_repo);
+		self
+	}
+
+	/// Decrypts an encrypted trusted operation. The shielding scheme is determined by
+	/// the ciphertext header, everything else is treated as RSA ciphertext.
+	fn decrypt_trusted_operation(&self, ciphertext: &[u8]) -> Option<Vec<u8>> {
+		match &self.x25519_shielding_key_repo {
+			Some(x25519_key_repo) if is_x25519_ciphertext(ciphertext) => x25519_key_repo
+				.retrieve_key()
+				.ok()?
+				.decrypt(ciphertext)
+				.ok()
+				.or_else(|| self.decrypt_with_retired_x25519_shielding_key(ciphertext)),
+			_ => self
+				.shielding_key_repo
+				.retrieve_key()
+				.ok()?
+				.decrypt(ciphertext)
+				.ok()
+				.or_else(|| self.decrypt_with_retired_shielding_key(ciphertext)),
+		}
+	}
+
+	fn decrypt_with_retired_shielding_key(&self, ciphertext: &[u8]) -> Option<Vec<u8>> {
+		let retired_key = self
+			.retired_shielding_key_repo
+			.as_ref()?
+			.retrieve_retired_key(now_as_millis())
+			.ok()??;
+		debug!("Decrypting trusted operation with the retired shielding key");
+		retired_key.decrypt(ciphertext).ok()
+	}
+
+	fn decrypt_with_retired_x25519_shielding_key(&self, ciphertext: &[u8]) -> Option<Vec<u8>> {
+		let retired_key = self
+			.retired_x25519_shielding_key_repo
+			.as_ref()?
+			.retrieve_retired_key(now_as_millis())
+			.ok()??;
+		debug!("Decrypting trusted operation with the retired X25519 shielding key");
+		retired_key.decrypt(ciphertext).ok()
+	}
+
+	/// Persist pending trusted calls, such that they can be restored after a restart
+	/// with `restore_persisted_top_pool`.
+	pub fn with_persistence(mut self, top_pool_persistence: Arc<dyn PersistTopPool>) -> Self {
+		self.top_pool_persistence = Some(top_pool_persistence);
+		self
+	}
+
+	/// Watch the trusted calls of accounts that clients have subscribed to, even if they are
+	/// submitted without `author_submitAndWatchExtrinsic`.
+	pub fn with_account_operation_watcher(
+		mut self,
+		account_operation_watcher: Arc<
+			dyn WatchAccountOperations<Hash = TxHash, AccountId = AccountId>,
+		>,
+	) -> Self {
+		self.account_operation_watcher = Some(account_operation_watcher);
+		self
+	}
+
+	/// Drops the persisted trusted operations that left the pool and persists the current bans.
+	fn update_persisted_top_pool(&self) {
+		if let Some(persistence) = &self.top_pool_persistence {
+			if let Err(e) = persistence.update(
+				&|operation| self.top_pool.is_imported(&operation.hash, operation.shard),
+				self.top_pool.banned_operations(),
+			) {
+				error!("Failed to update persisted trusted operation pool: {:?}", e);
+			}
+		}
 	}
 }
 
 enum TopSubmissionMode {
 	Submit,
 	SubmitWatch,
+	/// Submits an operation that is persisted already, see `restore_persisted_top_pool`.
+	Restore,
 }
 
 impl<TopPool, TopFilter, StateFacade, ShieldingKeyRepository, TCS, G>
@@ -155,13 +297,9 @@ where
 		};
 
 		// decrypt call
-		let shielding_key = match self.shielding_key_repo.retrieve_key() {
-			Ok(k) => k,
-			Err(_) => return Box::pin(ready(Err(ClientError::BadFormatDecipher.into()))),
-		};
-		let request_vec = match shielding_key.decrypt(ext.as_slice()) {
-			Ok(req) => req,
-			Err(_) => return Box::pin(ready(Err(ClientError::BadFormatDecipher.into()))),
+		let request_vec = match self.decrypt_trusted_operation(ext.as_slice()) {
+			Some(req) => req,
+			None => return Box::pin(ready(Err(ClientError::BadFormatDecipher.into()))),
 		};
 		// decode call
 		let trusted_operation =
@@ -197,8 +335,27 @@ where
 			);
 		}
 
-		match submission_mode {
-			TopSubmissionMode::Submit => Box::pin(
+		// Only trusted calls are persisted, getters are answered right away anyway.
+		let persistence = self.top_pool_persistence.clone().filter(|_| {
+			!matches!(submission_mode, TopSubmissionMode::Restore)
+				&& trusted_operation.to_call().is_some()
+		});
+
+		// The status updates of calls signed by a subscribed account are sent to its subscribers.
+		let submission_mode =
+			match (&self.account_operation_watcher, trusted_operation.signed_caller_account()) {
+				(Some(watcher), Some(account))
+					if watcher.watch_operation(
+						self.hash_of(&trusted_operation),
+						&shard,
+						account,
+					) =>
+					TopSubmissionMode::SubmitWatch,
+				_ => submission_mode,
+			};
+
+		let submitted: PoolFuture<TxHash, RpcError> = match submission_mode {
+			TopSubmissionMode::Submit | TopSubmissionMode::Restore => Box::pin(
 				self.top_pool
 					.submit_one(
 						&generic::BlockId::hash(best_block_hash),
@@ -219,7 +376,57 @@ where
 					)
 					.map_err(map_top_error::<TopPool, TCS, G>),
 			),
+		};
+
+		match persistence {
+			None => submitted,
+			Some(persistence) => Box::pin(submitted.map_ok(move |hash| {
+				let operation = PendingTrustedOperation { hash, shard, encrypted_operation: ext };
+				if let Err(e) = persistence.insert(operation) {
+					error!("Failed to persist trusted operation {:?}: {:?}", hash, e);
+				}
+				hash
+			})),
+		}
+	}
+
+	/// Re-validates the persisted trusted calls into the pool. To be called once on startup.
+	///
+	/// Persisted bans are restored first, such that banned operations are still rejected.
+	/// Returns the number of restored operations.
+	pub fn restore_persisted_top_pool(&self) -> usize {
+		let persisted = match &self.top_pool_persistence {
+			Some(persistence) => persistence.load(),
+			None => return 0,
+		};
+		self.top_pool.ban_operations(
+			persisted
+				.banned
+				.into_iter()
+				.map(|(hash, millis)| (hash, Duration::from_millis(millis)))
+				.collect(),
+		);
+
+		let pending = persisted.pending.len();
+		let mut restored = 0;
+		for operation in persisted.pending {
+			let submitted = self.process_top(
+				operation.encrypted_operation,
+				operation.shard,
+				TopSubmissionMode::Restore,
+			);
+			match executor::block_on(submitted) {
+				Ok(_) => restored += 1,
+				Err(e) => debug!(
+					"Dropping persisted trusted operation {:?}, it's not valid anymore: {:?}",
+					operation.hash, e
+				),
+			}
 		}
+		self.update_persisted_top_pool();
+
+		info!("Restored {} of {} persisted trusted operations", restored, pending);
+		restored
 	}
 
 	fn remove_top(
@@ -374,6 +581,7 @@ where
 				failed_to_remove.push(executed_call);
 			}
 		}
+		self.update_persisted_top_pool();
 		failed_to_remove
 	}
 
@@ -396,6 +604,7 @@ where
 	type Hash = TxHash;
 
 	fn on_block_imported(&self, hashes: &[Self::Hash], block_hash: SidechainBlockHash) {
-		self.top_pool.on_block_imported(hashes, block_hash)
+		self.top_pool.on_block_imported(hashes, block_hash);
+		self.update_persisted_top_pool();
 	}
 }
@@ -17,14 +17,20 @@
 
 use crate::{
 	author::Author,
+	persistence::{PersistTopPool, TopPoolPersistence},
 	test_fixtures::shard_id,
-	test_utils::submit_operation_to_top_pool,
+	test_utils::{submit_operation_to_top_pool, TopPoolSealMock},
 	top_filter::{AllowAllTopsFilter, Filter, GettersOnlyFilter},
-	traits::AuthorApi,
+	traits::{AuthorApi, OnBlockImported},
 };
 use codec::{Decode, Encode};
-use itp_sgx_crypto::{mocks::KeyRepositoryMock, ShieldingCryptoDecrypt, ShieldingCryptoEncrypt};
+use itc_direct_rpc_server::WatchAccountOperations;
+use itp_sgx_crypto::{
+	mocks::{KeyRepositoryMock, RetiredKeyRepositoryMock},
+	ShieldingCryptoDecrypt, ShieldingCryptoEncrypt, X25519KeyPair,
+};
 
+use itp_stf_primitives::types::{AccountId, ShardIdentifier};
 use itp_stf_state_handler::handle_state::HandleState;
 use itp_test::mock::{
 	handle_state_mock::HandleStateMock,
@@ -35,11 +41,16 @@ use itp_test::mock::{
 		mock_top_trusted_getter_signed, GetterMock, TrustedCallSignedMock, TrustedOperationMock,
 	},
 };
-use itp_top_pool::mocks::trusted_operation_pool_mock::TrustedOperationPoolMock;
+use itp_top_pool::{
+	mocks::trusted_operation_pool_mock::TrustedOperationPoolMock, primitives::TrustedOperationPool,
+};
 
 use sgx_crypto_helper::{rsa3072::Rsa3072KeyPair, RsaKeyPair};
 use sp_core::H256;
-use std::sync::Arc;
+use std::{
+	sync::{Arc, RwLock},
+	time::Duration,
+};
 
 type TestAuthor<Filter> = Author<
 	TrustedOperationPoolMock<TrustedOperationMock>,
@@ -124,6 +135,158 @@ fn submitting_indirect_call_works() {
 	assert_eq!(1, author.get_pending_trusted_calls(shard_id()).len());
 }
 
+#[test]
+fn submitting_x25519_and_rsa_encrypted_operations_works() {
+	let (author, top_pool, shielding_key) = create_author_with_filter(AllowAllTopsFilter::new());
+	let x25519_key = X25519KeyPair::from_secret([5u8; 32]);
+	let author =
+		author.with_x25519_shielding_key(Arc::new(KeyRepositoryMock::new(x25519_key.clone())));
+
+	let top_getter = mock_top_trusted_getter_signed();
+	let top_call = mock_top_direct_trusted_call_signed();
+	submit_operation_to_top_pool(&author, &top_getter, &x25519_key.public_key(), shard_id())
+		.unwrap();
+	assert_eq!(1, top_pool.get_last_submitted_transactions().len());
+
+	submit_operation_to_top_pool(&author, &top_call, &shielding_key, shard_id()).unwrap();
+	assert_eq!(1, author.get_pending_trusted_calls(shard_id()).len());
+}
+
+#[test]
+fn submitting_x25519_encrypted_operation_without_x25519_key_returns_error() {
+	let (author, top_pool, _) = create_author_with_filter(AllowAllTopsFilter::new());
+	let x25519_key = X25519KeyPair::from_secret([5u8; 32]);
+
+	let top_getter = mock_top_trusted_getter_signed();
+	let submit_response =
+		submit_operation_to_top_pool(&author, &top_getter, &x25519_key.public_key(), shard_id());
+
+	assert!(submit_response.is_err());
+	assert!(top_pool.get_last_submitted_transactions().is_empty());
+}
+
+#[test]
+fn operations_encrypted_with_retired_shielding_key_are_accepted_within_grace_period() {
+	let (author, top_pool, _) = create_author_with_filter(AllowAllTopsFilter::new());
+	let retired_key = ShieldingCryptoMock::default();
+	let author = author.with_retired_shielding_key(Arc::new(RetiredKeyRepositoryMock::new(
+		retired_key.clone(),
+		u64::MAX,
+	)));
+
+	let top_call = mock_top_direct_trusted_call_signed();
+	submit_operation_to_top_pool(&author, &top_call, &retired_key, shard_id()).unwrap();
+
+	assert_eq!(1, top_pool.get_last_submitted_transactions().len());
+}
+
+#[test]
+fn operations_encrypted_with_expired_shielding_key_are_rejected() {
+	let (author, top_pool, _) = create_author_with_filter(AllowAllTopsFilter::new());
+	let retired_key = ShieldingCryptoMock::default();
+	let author = author.with_retired_shielding_key(Arc::new(RetiredKeyRepositoryMock::new(
+		retired_key.clone(),
+		0,
+	)));
+
+	let top_call = mock_top_direct_trusted_call_signed();
+	let submit_response =
+		submit_operation_to_top_pool(&author, &top_call, &retired_key, shard_id());
+
+	assert!(submit_response.is_err());
+	assert!(top_pool.get_last_submitted_transactions().is_empty());
+}
+
+#[test]
+fn operations_encrypted_with_retired_x25519_shielding_key_are_accepted_within_grace_period() {
+	let (author, top_pool, _) = create_author_with_filter(AllowAllTopsFilter::new());
+	let retired_key = X25519KeyPair::from_secret([5u8; 32]);
+	let author = author
+		.with_x25519_shielding_key(Arc::new(KeyRepositoryMock::new(X25519KeyPair::from_secret(
+			[6u8; 32],
+		))))
+		.with_retired_x25519_shielding_key(Arc::new(RetiredKeyRepositoryMock::new(
+			retired_key.clone(),
+			u64::MAX,
+		)));
+
+	let top_getter = mock_top_trusted_getter_signed();
+	submit_operation_to_top_pool(&author, &top_getter, &retired_key.public_key(), shard_id())
+		.unwrap();
+
+	assert_eq!(1, top_pool.get_last_submitted_transactions().len());
+}
+
+#[test]
+fn persisted_calls_are_restored_into_new_pool() {
+	let seal = TopPoolSealMock::default();
+	let (author, top_pool, shielding_key) = create_author_with_filter(AllowAllTopsFilter::new());
+	let author = author.with_persistence(Arc::new(TopPoolPersistence::new(seal.clone())));
+	top_pool.ban_operations(vec![(H256::from_low_u64_be(1), Duration::from_secs(60))]);
+
+	let top_call = mock_top_direct_trusted_call_signed();
+	let top_getter = mock_top_trusted_getter_signed();
+	let _ = submit_operation_to_top_pool(&author, &top_getter, &shielding_key, shard_id()).unwrap();
+	let call_hash =
+		submit_operation_to_top_pool(&author, &top_call, &shielding_key, shard_id()).unwrap();
+	// Bans are persisted whenever the pool is updated with a block.
+	author.on_block_imported(&[], H256::default());
+
+	let persisted = TopPoolPersistence::new(seal.clone()).load();
+	assert_eq!(vec![call_hash], persisted.pending.iter().map(|o| o.hash).collect::<Vec<_>>());
+	assert_eq!(1, persisted.banned.len());
+
+	// Simulate a restart, the shielding key is the same.
+	let (restarted_author, restarted_top_pool, _) =
+		create_author_with_filter(AllowAllTopsFilter::new());
+	let restarted_author =
+		restarted_author.with_persistence(Arc::new(TopPoolPersistence::new(seal)));
+
+	assert_eq!(1, restarted_author.restore_persisted_top_pool());
+	assert!(restarted_top_pool.is_imported(&call_hash, shard_id()));
+	assert_eq!(
+		restarted_top_pool
+			.banned_operations()
+			.into_iter()
+			.map(|(hash, _)| hash)
+			.collect::<Vec<_>>(),
+		vec![H256::from_low_u64_be(1)]
+	);
+}
+
+#[test]
+fn calls_of_subscribed_accounts_are_watched() {
+	let (author, _, shielding_key) = create_author_with_filter(AllowAllTopsFilter::new());
+	let watcher = Arc::new(AccountOperationWatcherMock::default());
+	let author = author.with_account_operation_watcher(watcher.clone());
+
+	let top_call = mock_top_direct_trusted_call_signed();
+	let top_getter = mock_top_trusted_getter_signed();
+	let call_hash =
+		submit_operation_to_top_pool(&author, &top_call, &shielding_key, shard_id()).unwrap();
+	submit_operation_to_top_pool(&author, &top_getter, &shielding_key, shard_id()).unwrap();
+
+	assert_eq!(
+		*watcher.watched.read().unwrap(),
+		vec![(call_hash, shard_id(), top_call.signed_caller_account().unwrap().clone())]
+	);
+}
+
+#[derive(Default)]
+struct AccountOperationWatcherMock {
+	watched: RwLock<Vec<(H256, ShardIdentifier, AccountId)>>,
+}
+
+impl WatchAccountOperations for AccountOperationWatcherMock {
+	type Hash = H256;
+	type AccountId = AccountId;
+
+	fn watch_operation(&self, hash: H256, shard: &ShardIdentifier, account: &AccountId) -> bool {
+		self.watched.write().unwrap().push((hash, *shard, account.clone()));
+		true
+	}
+}
+
 fn create_author_with_filter<F: Filter<Value = TrustedOperationMock>>(
 	filter: F,
 ) -> (TestAuthor<F>, Arc<TrustedOperationPoolMock<TrustedOperationMock>>, ShieldingCryptoMock) {
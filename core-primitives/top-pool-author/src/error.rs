@@ -65,6 +65,10 @@ pub enum Error {
 
 	#[display(fmt = "Codec error: {}", _0)]
 	CodecError(codec::Error),
+
+	/// Failed to access the persisted trusted operation pool.
+	#[display(fmt = "IO error: {}", _0)]
+	Io(std::io::Error),
 }
 
 impl error::Error for Error {
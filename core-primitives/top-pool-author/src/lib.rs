@@ -35,6 +35,7 @@ pub mod api;
 pub mod author;
 pub mod client_error;
 pub mod error;
+pub mod persistence;
 pub mod top_filter;
 pub mod traits;
 
@@ -0,0 +1,312 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Optional persistence of the pending trusted operations, such that they survive a restart.
+//!
+//! Trusted calls are persisted as they were submitted, i.e. encrypted with the shielding key, and
+//! are re-validated into the pool on startup. Operations banned by the pool rotator are persisted
+//! together with their remaining ban time.
+//!
+//! The pool is sealed at most once per pool update, i.e. per imported sidechain block, and not
+//! for every submitted operation.
+
+#[cfg(all(not(feature = "std"), feature = "sgx"))]
+use crate::sgx_reexport_prelude::*;
+
+#[cfg(feature = "sgx")]
+use std::sync::SgxRwLock as RwLock;
+
+#[cfg(feature = "std")]
+use std::sync::RwLock;
+
+use crate::error::{Error, Result};
+use codec::{Decode, Encode};
+use core::{
+	sync::atomic::{AtomicBool, Ordering},
+	time::Duration,
+};
+use itp_sgx_io::SealedIO;
+use itp_top_pool::primitives::TxHash;
+use itp_types::ShardIdentifier;
+use log::*;
+use std::{collections::BTreeSet, io::ErrorKind, vec::Vec};
+
+#[cfg(feature = "sgx")]
+pub use sgx::*;
+
+/// File name of the sealed trusted operation pool.
+pub const SEALED_TOP_POOL_FILE: &str = "top_pool_sealed.bin";
+
+/// Trusted operation as it was submitted via RPC, i.e. still encrypted.
+#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq)]
+pub struct PendingTrustedOperation {
+	pub hash: TxHash,
+	pub shard: ShardIdentifier,
+	pub encrypted_operation: Vec<u8>,
+}
+
+#[derive(Encode, Decode, Clone, Debug, Default, PartialEq, Eq)]
+pub struct PersistedTopPool {
+	/// Pending operations in the order they were submitted.
+	pub pending: Vec<PendingTrustedOperation>,
+	/// Banned operations with their remaining ban time in milliseconds, as of when the pool was
+	/// sealed.
+	pub banned: Vec<(TxHash, u64)>,
+}
+
+/// Persistence of the trusted operation pool.
+pub trait PersistTopPool: Send + Sync {
+	/// Returns the pending and banned operations as they were persisted.
+	fn load(&self) -> PersistedTopPool;
+
+	/// Adds a newly submitted operation. Replaces an operation with the same hash.
+	///
+	/// The operation is sealed with the next `update`.
+	fn insert(&self, operation: PendingTrustedOperation) -> Result<()>;
+
+	/// Retains the pending operations for which `keep` returns true and replaces the banned ones.
+	/// Seals the pool if it has changed since it has been sealed last.
+	fn update(
+		&self,
+		keep: &dyn Fn(&PendingTrustedOperation) -> bool,
+		banned: Vec<(TxHash, Duration)>,
+	) -> Result<()>;
+}
+
+/// Keeps a copy of the persisted pool in memory, and seals it upon updates.
+pub struct TopPoolPersistence<Seal> {
+	seal: Seal,
+	persisted: RwLock<PersistedTopPool>,
+	has_unsealed_operations: AtomicBool,
+}
+
+impl<Seal> TopPoolPersistence<Seal>
+where
+	Seal: SealedIO<Unsealed = PersistedTopPool>,
+	Error: From<Seal::Error>,
+{
+	/// Loads the sealed pool. Starts with an empty pool if there is none yet, or if it can't be
+	/// unsealed.
+	pub fn new(seal: Seal) -> Self {
+		let persisted = seal.unseal().map_err(Error::from).unwrap_or_else(|e| {
+			match e {
+				Error::Io(ref e) if e.kind() == ErrorKind::NotFound =>
+					info!("No persisted trusted operation pool found, starting with an empty one"),
+				e => error!(
+					"Failed to unseal the persisted trusted operation pool ({:?}), its operations are lost",
+					e
+				),
+			}
+			PersistedTopPool::default()
+		});
+		TopPoolPersistence {
+			seal,
+			persisted: RwLock::new(persisted),
+			has_unsealed_operations: AtomicBool::new(false),
+		}
+	}
+
+	fn seal(&self, persisted: &PersistedTopPool) -> Result<()> {
+		Ok(self.seal.seal(persisted)?)
+	}
+}
+
+impl<Seal> PersistTopPool for TopPoolPersistence<Seal>
+where
+	Seal: SealedIO<Unsealed = PersistedTopPool> + Send + Sync,
+	Error: From<Seal::Error>,
+{
+	fn load(&self) -> PersistedTopPool {
+		self.persisted.read().unwrap().clone()
+	}
+
+	fn insert(&self, operation: PendingTrustedOperation) -> Result<()> {
+		let mut persisted = self.persisted.write().unwrap();
+		persisted.pending.retain(|o| o.hash != operation.hash);
+		persisted.pending.push(operation);
+		self.has_unsealed_operations.store(true, Ordering::SeqCst);
+		Ok(())
+	}
+
+	fn update(
+		&self,
+		keep: &dyn Fn(&PendingTrustedOperation) -> bool,
+		banned: Vec<(TxHash, Duration)>,
+	) -> Result<()> {
+		let mut persisted = self.persisted.write().unwrap();
+		let pending_before = persisted.pending.len();
+		persisted.pending.retain(|o| keep(o));
+
+		// The remaining ban times change with every update, so only a change of the banned
+		// operations themselves requires sealing.
+		let bans_changed = persisted.banned.iter().map(|(hash, _)| *hash).collect::<BTreeSet<_>>()
+			!= banned.iter().map(|(hash, _)| *hash).collect::<BTreeSet<_>>();
+		if persisted.pending.len() == pending_before
+			&& !bans_changed
+			&& !self.has_unsealed_operations.load(Ordering::SeqCst)
+		{
+			return Ok(())
+		}
+
+		persisted.banned =
+			banned.into_iter().map(|(hash, d)| (hash, d.as_millis() as u64)).collect();
+		self.seal(&persisted)?;
+		self.has_unsealed_operations.store(false, Ordering::SeqCst);
+		Ok(())
+	}
+}
+
+#[cfg(feature = "sgx")]
+pub mod sgx {
+	use super::{PersistedTopPool, SEALED_TOP_POOL_FILE};
+	use crate::error::{Error, Result};
+	use codec::{Decode, Encode};
+	use itp_sgx_io::{seal, unseal, SealedIO};
+	use log::*;
+	use std::{
+		format, fs,
+		path::{Path, PathBuf},
+	};
+
+	#[derive(Clone, Debug)]
+	pub struct TopPoolSeal {
+		base_path: PathBuf,
+	}
+
+	impl TopPoolSeal {
+		pub fn new(base_path: PathBuf) -> Self {
+			Self { base_path }
+		}
+
+		pub fn path(&self) -> PathBuf {
+			self.base_path.join(SEALED_TOP_POOL_FILE)
+		}
+
+		/// Copy of the pool that is sealed before the pool file itself is overwritten.
+		///
+		/// A sealed file can't be renamed, its name is part of the sealed data. Hence, the pool
+		/// is replaced by first sealing the copy, then the pool file, and only then removing the
+		/// copy. If sealing is interrupted, one of the two files is always complete.
+		pub fn staged_path(&self) -> PathBuf {
+			self.base_path.join(format!("{}.staged", SEALED_TOP_POOL_FILE))
+		}
+	}
+
+	fn unseal_pool(path: &Path) -> Result<PersistedTopPool> {
+		Ok(unseal(path).map(|b| Decode::decode(&mut b.as_slice()))??)
+	}
+
+	impl SealedIO for TopPoolSeal {
+		type Error = Error;
+		type Unsealed = PersistedTopPool;
+
+		fn unseal(&self) -> Result<Self::Unsealed> {
+			match unseal_pool(&self.path()) {
+				Ok(pool) => Ok(pool),
+				Err(e) if self.staged_path().exists() => {
+					warn!(
+						"Failed to unseal the trusted operation pool ({:?}), sealing has been interrupted, restoring the staged copy",
+						e
+					);
+					unseal_pool(&self.staged_path())
+				},
+				Err(e) => Err(e),
+			}
+		}
+
+		fn seal(&self, unsealed: &Self::Unsealed) -> Result<()> {
+			unsealed.using_encoded(|bytes| seal(bytes, self.staged_path()))?;
+			unsealed.using_encoded(|bytes| seal(bytes, self.path()))?;
+			Ok(fs::remove_file(self.staged_path())?)
+		}
+	}
+}
+
+#[cfg(test)]
+pub mod tests {
+	use super::*;
+	use crate::test_utils::TopPoolSealMock;
+	use sp_core::H256;
+
+	fn pending_operation(hash: u64) -> PendingTrustedOperation {
+		PendingTrustedOperation {
+			hash: H256::from_low_u64_be(hash),
+			shard: ShardIdentifier::default(),
+			encrypted_operation: hash.encode(),
+		}
+	}
+
+	#[test]
+	fn inserted_operations_are_sealed_with_next_update() {
+		let seal = TopPoolSealMock::default();
+		let persistence = TopPoolPersistence::new(seal.clone());
+
+		persistence.insert(pending_operation(1)).unwrap();
+		persistence.insert(pending_operation(2)).unwrap();
+		persistence.insert(pending_operation(1)).unwrap();
+
+		let expected = vec![pending_operation(2), pending_operation(1)];
+		assert_eq!(persistence.load().pending, expected);
+		assert_eq!(seal.number_of_seals(), 0);
+
+		persistence.update(&|_| true, vec![]).unwrap();
+
+		assert_eq!(seal.number_of_seals(), 1);
+		assert_eq!(TopPoolPersistence::new(seal).load().pending, expected);
+	}
+
+	#[test]
+	fn update_seals_only_if_pool_changed() {
+		let seal = TopPoolSealMock::default();
+		let persistence = TopPoolPersistence::new(seal.clone());
+		let banned_hash = H256::from_low_u64_be(1);
+		persistence.insert(pending_operation(2)).unwrap();
+		persistence
+			.update(&|_| true, vec![(banned_hash, Duration::from_secs(3))])
+			.unwrap();
+
+		persistence
+			.update(&|_| true, vec![(banned_hash, Duration::from_secs(2))])
+			.unwrap();
+		assert_eq!(seal.number_of_seals(), 1);
+
+		persistence.update(&|_| true, vec![]).unwrap();
+		assert_eq!(seal.number_of_seals(), 2);
+
+		persistence.update(&|_| false, vec![]).unwrap();
+		assert_eq!(seal.number_of_seals(), 3);
+	}
+
+	#[test]
+	fn update_retains_operations_and_replaces_bans() {
+		let seal = TopPoolSealMock::default();
+		let persistence = TopPoolPersistence::new(seal.clone());
+		persistence.insert(pending_operation(1)).unwrap();
+		persistence.insert(pending_operation(2)).unwrap();
+
+		persistence
+			.update(
+				&|o| o.hash != H256::from_low_u64_be(1),
+				vec![(H256::from_low_u64_be(1), Duration::from_secs(2))],
+			)
+			.unwrap();
+
+		let persisted = TopPoolPersistence::new(seal).load();
+		assert_eq!(persisted.pending, vec![pending_operation(2)]);
+		assert_eq!(persisted.banned, vec![(H256::from_low_u64_be(1), 2000)]);
+	}
+}
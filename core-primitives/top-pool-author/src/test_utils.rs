@@ -18,13 +18,31 @@
 #[cfg(all(not(feature = "std"), feature = "sgx"))]
 use crate::sgx_reexport_prelude::*;
 
-use crate::traits::AuthorApi;
+#[cfg(feature = "sgx")]
+use std::sync::SgxRwLock as RwLock;
+
+#[cfg(feature = "std")]
+use std::sync::RwLock;
+
+use crate::{
+	error::{Error, Result},
+	persistence::PersistedTopPool,
+	traits::AuthorApi,
+};
 use codec::Encode;
 use itp_sgx_crypto::ShieldingCryptoEncrypt;
+use itp_sgx_io::SealedIO;
 use itp_stf_primitives::types::{ShardIdentifier, TrustedOperation as StfTrustedOperation};
 use jsonrpc_core::futures::executor;
 use sp_core::H256;
-use std::fmt::Debug;
+use std::{
+	fmt::Debug,
+	io::ErrorKind,
+	sync::{
+		atomic::{AtomicUsize, Ordering},
+		Arc,
+	},
+};
 
 /// Test utility function to submit a trusted operation on an RPC author
 pub fn submit_operation_to_top_pool<R, S, TCS, G>(
@@ -44,3 +62,35 @@ where
 	let submit_future = async { author.watch_top(top_encrypted, shard).await };
 	executor::block_on(submit_future)
 }
+
+/// Seals the trusted operation pool in memory. Clones share the sealed pool.
+#[derive(Clone, Default)]
+pub struct TopPoolSealMock {
+	sealed: Arc<RwLock<Option<PersistedTopPool>>>,
+	number_of_seals: Arc<AtomicUsize>,
+}
+
+impl TopPoolSealMock {
+	pub fn number_of_seals(&self) -> usize {
+		self.number_of_seals.load(Ordering::SeqCst)
+	}
+}
+
+impl SealedIO for TopPoolSealMock {
+	type Error = Error;
+	type Unsealed = PersistedTopPool;
+
+	fn unseal(&self) -> Result<Self::Unsealed> {
+		self.sealed
+			.read()
+			.unwrap()
+			.clone()
+			.ok_or_else(|| Error::Io(ErrorKind::NotFound.into()))
+	}
+
+	fn seal(&self, unsealed: &Self::Unsealed) -> Result<()> {
+		*self.sealed.write().unwrap() = Some(unsealed.clone());
+		self.number_of_seals.fetch_add(1, Ordering::SeqCst);
+		Ok(())
+	}
+}
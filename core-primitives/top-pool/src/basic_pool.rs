@@ -37,7 +37,7 @@ use crate::{
 };
 use alloc::{boxed::Box, string::String, sync::Arc};
 use codec::Encode;
-use core::{marker::PhantomData, pin::Pin};
+use core::{marker::PhantomData, pin::Pin, time::Duration};
 use itc_direct_rpc_server::SendRpcResponse;
 use itp_stf_primitives::{traits::PoolTransactionValidation, types::ShardIdentifier};
 use its_primitives::types::BlockHash as SidechainBlockHash;
@@ -49,7 +49,7 @@ use sp_runtime::{
 	generic::BlockId,
 	traits::{Block as BlockT, NumberFor, Zero},
 };
-use std::{collections::HashMap, vec, vec::Vec};
+use std::{collections::HashMap, time::Instant, vec, vec::Vec};
 
 type BoxedReadyIterator<Data> = Box<dyn Iterator<Item = Arc<TrustedOperation<Data>>> + Send>;
 
@@ -247,4 +247,16 @@ where
 	fn on_block_imported(&self, hashes: &[TxHash], block_hash: SidechainBlockHash) {
 		self.pool.validated_pool().on_block_imported(hashes, block_hash);
 	}
+
+	fn is_imported(&self, hash: &TxHash, shard: ShardIdentifier) -> bool {
+		self.pool.validated_pool().is_imported(hash, shard)
+	}
+
+	fn banned_operations(&self) -> Vec<(TxHash, Duration)> {
+		self.pool.validated_pool().banned(&Instant::now())
+	}
+
+	fn ban_operations(&self, bans: Vec<(TxHash, Duration)>) {
+		self.pool.validated_pool().ban_for(&Instant::now(), bans)
+	}
 }
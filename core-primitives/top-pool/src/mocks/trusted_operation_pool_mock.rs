@@ -34,7 +34,7 @@ use crate::{
 	},
 };
 use codec::Encode;
-use core::{future::Future, pin::Pin};
+use core::{future::Future, pin::Pin, time::Duration};
 
 use itp_types::{Block, BlockHash as SidechainBlockHash, ShardIdentifier, H256};
 use jsonrpc_core::futures::future::ready;
@@ -49,6 +49,7 @@ use std::{boxed::Box, collections::HashMap, string::String, sync::Arc, vec, vec:
 /// To be used in unit tests
 pub struct TrustedOperationPoolMock<TOP: Encode + Clone + Send + Sync + 'static> {
 	submitted_transactions: RwLock<HashMap<ShardIdentifier, TxPayload<TOP>>>,
+	banned_operations: RwLock<Vec<(TxHash, Duration)>>,
 }
 
 /// Transaction payload
@@ -62,7 +63,10 @@ pub struct TxPayload<TOP: Encode + Clone + Send + Sync + 'static> {
 
 impl<TOP: Encode + Clone + Send + Sync + 'static> Default for TrustedOperationPoolMock<TOP> {
 	fn default() -> Self {
-		TrustedOperationPoolMock::<TOP> { submitted_transactions: RwLock::new(HashMap::new()) }
+		TrustedOperationPoolMock::<TOP> {
+			submitted_transactions: RwLock::new(HashMap::new()),
+			banned_operations: RwLock::new(Vec::new()),
+		}
 	}
 }
 
@@ -212,6 +216,22 @@ where
 	}
 
 	fn on_block_imported(&self, _hashes: &[TxHash], _block_hash: SidechainBlockHash) {}
+
+	fn is_imported(&self, hash: &TxHash, shard: ShardIdentifier) -> bool {
+		let transactions = self.submitted_transactions.read().unwrap();
+		transactions
+			.get(&shard)
+			.map(|payload| payload.xts.iter().any(|top| hash_of_top(top) == *hash))
+			.unwrap_or(false)
+	}
+
+	fn banned_operations(&self) -> Vec<(TxHash, Duration)> {
+		self.banned_operations.read().unwrap().clone()
+	}
+
+	fn ban_operations(&self, bans: Vec<(TxHash, Duration)>) {
+		self.banned_operations.write().unwrap().extend(bans)
+	}
 }
 
 fn default_pool_status() -> PoolStatus {
@@ -8,7 +8,7 @@ use crate::error;
 use alloc::{boxed::Box, string::String, sync::Arc, vec::Vec};
 use byteorder::{BigEndian, ByteOrder};
 use codec::{Decode, Encode};
-use core::pin::Pin;
+use core::{pin::Pin, time::Duration};
 use itp_stf_primitives::types::ShardIdentifier;
 use itp_types::BlockHash as SidechainBlockHash;
 use jsonrpc_core::futures::{channel::mpsc::Receiver, Future, Stream};
@@ -252,6 +252,15 @@ pub trait TrustedOperationPool<TOP>: Send + Sync {
 
 	/// Notify the listener of top inclusion in sidechain block
 	fn on_block_imported(&self, hashes: &[TxHash], block_hash: SidechainBlockHash);
+
+	/// Returns true if the operation is in the pool, either ready or waiting for its requirements.
+	fn is_imported(&self, hash: &TxHash, shard: ShardIdentifier) -> bool;
+
+	/// Returns all banned operations together with their remaining ban time.
+	fn banned_operations(&self) -> Vec<(TxHash, Duration)>;
+
+	/// Bans the given operations for their individual ban time, e.g. to restore persisted bans.
+	fn ban_operations(&self, bans: Vec<(TxHash, Duration)>);
 }
 
 /// The source of the transaction.
@@ -32,6 +32,7 @@ use std::{
 	collections::HashMap,
 	iter,
 	time::{Duration, Instant},
+	vec::Vec,
 };
 
 /// Expected size of the banned extrinsics cache.
@@ -77,6 +78,28 @@ impl PoolRotator {
 		}
 	}
 
+	/// Bans given set of hashes, each for an individual amount of time.
+	///
+	/// Used to restore bans which were persisted with [`Self::banned`].
+	pub fn ban_for(&self, now: &Instant, bans: impl IntoIterator<Item = (TxHash, Duration)>) {
+		let mut banned = self.banned_until.write().unwrap();
+
+		for (hash, ban_time) in bans {
+			banned.insert(hash, *now + ban_time);
+		}
+	}
+
+	/// Returns all currently banned hashes together with their remaining ban time.
+	pub fn banned(&self, now: &Instant) -> Vec<(TxHash, Duration)> {
+		self.banned_until
+			.read()
+			.unwrap()
+			.iter()
+			.filter(|(_, until)| **until > *now)
+			.map(|(hash, until)| (*hash, *until - *now))
+			.collect()
+	}
+
 	/// Bans extrinsic if it's stale.
 	///
 	/// Returns `true` if extrinsic is stale and got banned.
@@ -182,6 +205,27 @@ pub mod tests {
 		assert!(!rotator.is_banned(&hash));
 	}
 
+	#[test]
+	pub fn test_should_restore_bans_with_remaining_ban_time() {
+		// given
+		let (hash, tx) = tx();
+		let rotator = rotator();
+		let now = Instant::now();
+		assert!(rotator.ban_if_stale(&now, 1, &tx));
+		let later = now + rotator.ban_time / 2;
+
+		// when
+		let banned = rotator.banned(&later);
+		let restored_rotator = super::PoolRotator::default();
+		restored_rotator.ban_for(&later, banned.clone());
+
+		// then
+		assert_eq!(banned, vec![(hash, rotator.ban_time / 2)]);
+		assert!(restored_rotator.is_banned(&hash));
+		restored_rotator.clear_timeouts(&(now + rotator.ban_time + Duration::from_millis(1)));
+		assert!(!restored_rotator.is_banned(&hash));
+	}
+
 	#[test]
 	pub fn test_should_garbage_collect() {
 		// given
@@ -53,7 +53,7 @@ use std::{
 	format,
 	string::String,
 	sync::Arc,
-	time::Instant,
+	time::{Duration, Instant},
 	vec,
 	vec::Vec,
 };
@@ -141,6 +141,21 @@ where
 		self.rotator.is_banned(hash)
 	}
 
+	/// Bans given set of hashes, each for an individual amount of time.
+	pub fn ban_for(&self, now: &Instant, bans: impl IntoIterator<Item = (TxHash, Duration)>) {
+		self.rotator.ban_for(now, bans)
+	}
+
+	/// Returns all banned hashes together with their remaining ban time.
+	pub fn banned(&self, now: &Instant) -> Vec<(TxHash, Duration)> {
+		self.rotator.banned(now)
+	}
+
+	/// Returns true if the operation is in the pool, either ready or waiting for its requirements.
+	pub fn is_imported(&self, tx_hash: &TxHash, shard: ShardIdentifier) -> bool {
+		self.pool.read().unwrap().is_imported(tx_hash, shard)
+	}
+
 	/// A fast check before doing any further processing of a operation, like validation.
 	///
 	/// If `ingore_banned` is `true`, it will not check if the operation is banned.
@@ -111,12 +111,27 @@ pub enum TrustedOperationStatus {
 	Invalid,
 }
 
+/// Update that is sent to the clients that subscribed to an account
+/// with `author_subscribeAccountEvents`.
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub enum AccountEventUpdate {
+	/// Status of a trusted operation (given by its hash) that was signed by the account.
+	TrustedOperationStatus(H256, TrustedOperationStatus),
+	/// Encoded runtime event record of the STF that touches the account,
+	/// emitted in the sidechain block with the given hash.
+	RuntimeEvent(BlockHash, Vec<u8>),
+}
+
 #[derive(Encode, Decode, Clone, Debug, PartialEq)]
 pub enum WorkerRequest {
 	ChainStorage(Vec<u8>, Option<BlockHash>), // (storage_key, at_block)
 	/// for awareness: we call it unverified because there is no way how the enclave could verify the correctness of the information
 	LatestParentchainHeaderUnverified,
 	NextNonceFor(AccountId),
+	/// Signed block with the given hash, together with its events.
+	ChainBlock(BlockHash),
+	/// Justifications of the given block, if any.
+	ChainBlockJustifications(BlockHash),
 }
 
 #[derive(Encode, Decode, Clone, Debug, PartialEq)]
@@ -124,6 +139,8 @@ pub enum WorkerResponse<H: HeaderTrait, V: Encode + Decode> {
 	ChainStorage(Vec<u8>, Option<V>, Option<Vec<Vec<u8>>>), // (storage_key, storage_value, storage_proof)
 	LatestParentchainHeaderUnverified(H),
 	NextNonce(Option<Nonce>),
+	ChainBlock(Option<(Vec<u8>, Vec<u8>)>), // (encoded signed block, raw events)
+	ChainBlockJustifications(Option<sp_runtime::Justifications>),
 }
 
 impl<H: HeaderTrait> From<WorkerResponse<H, Vec<u8>>> for StorageEntry<Vec<u8>> {
@@ -16,12 +16,13 @@
 */
 
 use crate::{OpaqueCall, PalletString, ShardIdentifier};
-use alloc::{format, vec::Vec};
+use alloc::{format, string::String, vec::Vec};
 use codec::{Decode, Encode};
 use core::fmt::Debug;
 use frame_support::pallet_prelude::Pays;
 use itp_stf_primitives::traits::{IndirectExecutor, TrustedCallVerification};
 use itp_utils::stringify::account_id_to_string;
+use scale_info::TypeInfo;
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
 pub use sidechain_primitives::SidechainBlockConfirmation;
@@ -42,6 +43,7 @@ pub type StorageProof = Vec<Vec<u8>>;
 // Basic Types.
 pub type Index = u32;
 pub type Balance = u128;
+pub type AssetId = u32;
 pub type Hash = sp_core::H256;
 
 // Account Types.
@@ -67,27 +69,39 @@ pub type BlockHash = sp_core::H256;
 /// Alias to 512-bit hash when used in the context of a transaction signature on the chain.
 pub type Signature = MultiSignature;
 
-#[derive(Encode, Decode, Copy, Clone, Debug, Default, PartialEq, Eq)]
+/// Index of a target parentchain within the worker's list of configured target parentchains.
+pub type TargetParentchainIndex = u8;
+
+#[derive(
+	Encode, Decode, Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, TypeInfo,
+)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub enum ParentchainId {
 	/// The Integritee Parentchain, the trust root of the enclave and serving finality to sidechains.
 	#[default]
 	Integritee,
-	/// A target chain containing custom business logic.
-	TargetA,
-	/// Another target chain containing custom business logic.
-	TargetB,
+	/// A target chain containing custom business logic, identified by its index in the list of
+	/// configured target parentchains.
+	Target(TargetParentchainIndex),
+}
+
+impl ParentchainId {
+	/// Returns the index of the target parentchain, or `None` for the Integritee parentchain.
+	pub fn target_index(&self) -> Option<TargetParentchainIndex> {
+		match self {
+			ParentchainId::Integritee => None,
+			ParentchainId::Target(index) => Some(*index),
+		}
+	}
 }
 
 #[cfg(feature = "std")]
 impl std::fmt::Display for ParentchainId {
 	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-		let message = match self {
-			ParentchainId::Integritee => "Integritee",
-			ParentchainId::TargetA => "TargetA",
-			ParentchainId::TargetB => "TargetB",
-		};
-		write!(f, "{}", message)
+		match self {
+			ParentchainId::Integritee => write!(f, "Integritee"),
+			ParentchainId::Target(index) => write!(f, "Target{}", index),
+		}
 	}
 }
 
@@ -101,6 +115,35 @@ pub trait FilterEvents {
 	fn get_extrinsic_statuses(&self) -> core::result::Result<Vec<ExtrinsicStatus>, Self::Error>;
 
 	fn get_transfer_events(&self) -> core::result::Result<Vec<BalanceTransfer>, Self::Error>;
+
+	fn get_asset_transfer_events(&self) -> core::result::Result<Vec<AssetTransfer>, Self::Error>;
+
+	/// All events of the block in their original order, without decoding the fields.
+	fn get_raw_events(&self) -> core::result::Result<Vec<RawEvent>, Self::Error>;
+}
+
+/// A parentchain event identified by its pallet and variant name, with SCALE-encoded fields.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RawEvent {
+	pub pallet: String,
+	pub variant: String,
+	pub fields: Vec<u8>,
+}
+
+impl RawEvent {
+	pub fn new<E: StaticEvent + Encode>(event: &E) -> Self {
+		Self { pallet: E::PALLET.into(), variant: E::EVENT.into(), fields: event.encode() }
+	}
+
+	/// Decodes the event as `E` if pallet and variant name match.
+	pub fn as_event<E: StaticEvent + Decode>(
+		&self,
+	) -> Option<core::result::Result<E, codec::Error>> {
+		if self.pallet != E::PALLET || self.variant != E::EVENT {
+			return None
+		}
+		Some(E::decode(&mut self.fields.as_slice()))
+	}
 }
 
 #[derive(Encode, Decode, Debug)]
@@ -161,6 +204,32 @@ impl StaticEvent for BalanceTransfer {
 	const EVENT: &'static str = "Transfer";
 }
 
+#[derive(Encode, Decode, Debug)]
+pub struct AssetTransfer {
+	pub asset_id: AssetId,
+	pub from: AccountId,
+	pub to: AccountId,
+	pub amount: Balance,
+}
+
+impl core::fmt::Display for AssetTransfer {
+	fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+		let message = format!(
+			"AssetTransfer :: asset_id: {}, from: {}, to: {}, amount: {}",
+			self.asset_id,
+			account_id_to_string::<AccountId>(&self.from),
+			account_id_to_string::<AccountId>(&self.to),
+			self.amount
+		);
+		write!(f, "{}", message)
+	}
+}
+
+impl StaticEvent for AssetTransfer {
+	const PALLET: &'static str = "Assets";
+	const EVENT: &'static str = "Transferred";
+}
+
 #[derive(Encode, Decode, Debug)]
 pub struct AddedSgxEnclave {
 	pub registered_by: AccountId,
@@ -301,53 +370,79 @@ impl GenericMortality {
 /// a wrapper to target calls to specific parentchains
 #[derive(Encode, Debug, Clone, PartialEq, Eq)]
 pub enum ParentchainCall {
-	Integritee { call: OpaqueCall, mortality: GenericMortality },
-	TargetA { call: OpaqueCall, mortality: GenericMortality },
-	TargetB { call: OpaqueCall, mortality: GenericMortality },
+	Integritee {
+		call: OpaqueCall,
+		mortality: GenericMortality,
+	},
+	Target {
+		index: TargetParentchainIndex,
+		call: OpaqueCall,
+		mortality: GenericMortality,
+	},
+	/// Transfer out of a shard vault. `call` proxies `transfer` on behalf of `vault`, so it can
+	/// be sent on its own, whereas `transfer` allows settling several withdrawals in one batch.
+	VaultWithdrawal {
+		parentchain_id: ParentchainId,
+		vault: AccountId,
+		transfer: OpaqueCall,
+		call: OpaqueCall,
+		mortality: GenericMortality,
+	},
 }
 
 impl ParentchainCall {
-	pub fn as_integritee(&self) -> Option<(OpaqueCall, GenericMortality)> {
-		if let Self::Integritee { call, mortality } = self {
-			Some((call.clone(), mortality.clone()))
-		} else {
-			None
+	pub fn new(
+		parentchain_id: ParentchainId,
+		call: OpaqueCall,
+		mortality: GenericMortality,
+	) -> Self {
+		match parentchain_id {
+			ParentchainId::Integritee => Self::Integritee { call, mortality },
+			ParentchainId::Target(index) => Self::Target { index, call, mortality },
 		}
 	}
-	pub fn as_target_a(&self) -> Option<(OpaqueCall, GenericMortality)> {
-		if let Self::TargetA { call, mortality } = self {
-			Some((call.clone(), mortality.clone()))
-		} else {
-			None
+
+	pub fn parentchain_id(&self) -> ParentchainId {
+		match self {
+			Self::Integritee { .. } => ParentchainId::Integritee,
+			Self::Target { index, .. } => ParentchainId::Target(*index),
+			Self::VaultWithdrawal { parentchain_id, .. } => *parentchain_id,
+		}
+	}
+
+	pub fn as_integritee(&self) -> Option<(OpaqueCall, GenericMortality)> {
+		self.as_call_for(ParentchainId::Integritee)
+	}
+
+	pub fn as_call_for(
+		&self,
+		parentchain_id: ParentchainId,
+	) -> Option<(OpaqueCall, GenericMortality)> {
+		if self.parentchain_id() != parentchain_id {
+			return None
+		}
+		match self {
+			Self::Integritee { call, mortality }
+			| Self::Target { call, mortality, .. }
+			| Self::VaultWithdrawal { call, mortality, .. } => Some((call.clone(), mortality.clone())),
 		}
 	}
-	pub fn as_target_b(&self) -> Option<(OpaqueCall, GenericMortality)> {
-		if let Self::TargetB { call, mortality } = self {
-			Some((call.clone(), mortality.clone()))
-		} else {
-			None
+
+	/// Returns vault, transfer call and mortality if this is a withdrawal from a shard vault on
+	/// `parentchain_id`.
+	pub fn as_vault_withdrawal_for(
+		&self,
+		parentchain_id: ParentchainId,
+	) -> Option<(&AccountId, &OpaqueCall, &GenericMortality)> {
+		match self {
+			Self::VaultWithdrawal { parentchain_id: id, vault, transfer, mortality, .. }
+				if *id == parentchain_id =>
+				Some((vault, transfer, mortality)),
+			_ => None,
 		}
 	}
+
 	pub fn as_opaque_call_for(&self, parentchain_id: ParentchainId) -> Option<OpaqueCall> {
-		match parentchain_id {
-			ParentchainId::Integritee =>
-				if let Self::Integritee { call, mortality: _ } = self {
-					Some(call.clone())
-				} else {
-					None
-				},
-			ParentchainId::TargetA =>
-				if let Self::TargetA { call, mortality: _ } = self {
-					Some(call.clone())
-				} else {
-					None
-				},
-			ParentchainId::TargetB =>
-				if let Self::TargetB { call, mortality: _ } = self {
-					Some(call.clone())
-				} else {
-					None
-				},
-		}
+		self.as_call_for(parentchain_id).map(|(call, _)| call)
 	}
 }
@@ -0,0 +1,527 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Subscriptions of clients to everything that touches an account (`author_subscribeAccountEvents`).
+//!
+//! In contrast to `author_submitAndWatchExtrinsic`, the status updates of a trusted operation are
+//! sent to every client that subscribed to the account that signed the operation, no matter over
+//! which connection the operation was submitted.
+//!
+//! A subscription is bound to the shard it was requested for, the account's operations and
+//! events of other shards are not sent to it.
+
+#[cfg(all(not(feature = "std"), feature = "sgx"))]
+use std::sync::SgxRwLock as RwLock;
+
+#[cfg(feature = "std")]
+use std::sync::RwLock;
+
+use crate::{
+	rpc_responder::continue_watching, DirectRpcError, DirectRpcResult, ObserveClosedConnections,
+	SendRpcResponse, SendSubscriptionUpdate, WatchAccountOperations,
+};
+use itp_time_utils::now_as_millis;
+use itp_types::{AccountEventUpdate, ShardIdentifier, TrustedOperationStatus, H256};
+use itp_utils::ToHexPrefixed;
+use log::*;
+use std::{collections::HashMap, fmt::Debug, hash::Hash, sync::Arc, vec::Vec};
+
+/// RPC method of the subscription, also used as method of the subscription updates.
+pub const ACCOUNT_EVENTS_SUBSCRIPTION_METHOD: &str = "author_subscribeAccountEvents";
+
+/// Max number of trusted operations that are watched on behalf of account subscriptions.
+pub const MAX_WATCHED_ACCOUNT_OPERATIONS: usize = 4096;
+
+/// Max number of subscriptions of a single account.
+pub const MAX_SUBSCRIPTIONS_PER_ACCOUNT: usize = 16;
+
+/// Max number of subscriptions of all accounts.
+pub const MAX_ACCOUNT_SUBSCRIPTIONS: usize = 4096;
+
+/// Max number of subscription ids that are remembered until they expire, such that a
+/// subscription request can't be replayed once its subscription has been dropped.
+pub const MAX_USED_ACCOUNT_SUBSCRIPTIONS: usize = 4 * MAX_ACCOUNT_SUBSCRIPTIONS;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct AccountSubscription {
+	id: H256,
+	/// Unix timestamp in milliseconds.
+	expires_at: u64,
+}
+
+impl AccountSubscription {
+	fn is_expired(&self, now: u64) -> bool {
+		self.expires_at <= now
+	}
+}
+
+pub struct AccountSubscriptionHandler<Responder, AccountId>
+where
+	Responder: SendSubscriptionUpdate<Hash = H256>,
+	AccountId: Eq + Hash + Clone + Debug + Send + Sync,
+{
+	responder: Arc<Responder>,
+	subscriptions: RwLock<HashMap<(ShardIdentifier, AccountId), Vec<AccountSubscription>>>,
+	/// Ids of all subscriptions that have not expired yet, including the dropped ones.
+	used_subscriptions: RwLock<HashMap<H256, u64>>,
+	watched_operations: RwLock<HashMap<H256, (ShardIdentifier, AccountId)>>,
+}
+
+impl<Responder, AccountId> AccountSubscriptionHandler<Responder, AccountId>
+where
+	Responder: SendSubscriptionUpdate<Hash = H256>,
+	AccountId: Eq + Hash + Clone + Debug + Send + Sync,
+{
+	pub fn new(responder: Arc<Responder>) -> Self {
+		AccountSubscriptionHandler {
+			responder,
+			subscriptions: Default::default(),
+			used_subscriptions: Default::default(),
+			watched_operations: Default::default(),
+		}
+	}
+
+	/// Sends all further updates of the account in the shard to the connection that is registered
+	/// under the subscription id, until the subscription expires (unix timestamp in millis).
+	///
+	/// Fails if the subscription id has been used before, even if that subscription has been
+	/// dropped in the meantime, or if there are too many subscriptions.
+	pub fn subscribe(
+		&self,
+		shard: ShardIdentifier,
+		account: AccountId,
+		subscription: H256,
+		expires_at: u64,
+	) -> DirectRpcResult<()> {
+		let now = now_as_millis();
+		let mut subscriptions = self.subscriptions.write().expect("Lock poisoning");
+		let mut used_subscriptions = self.used_subscriptions.write().expect("Lock poisoning");
+		self.remove_expired_subscriptions(&mut subscriptions, now);
+		used_subscriptions.retain(|_, expires_at| *expires_at > now);
+
+		if used_subscriptions.contains_key(&subscription) {
+			return Err(DirectRpcError::Other("Subscription request has been used already".into()))
+		}
+		if subscriptions.values().map(Vec::len).sum::<usize>() >= MAX_ACCOUNT_SUBSCRIPTIONS
+			|| used_subscriptions.len() >= MAX_USED_ACCOUNT_SUBSCRIPTIONS
+		{
+			return Err(DirectRpcError::Other("Too many account subscriptions".into()))
+		}
+		let key = (shard, account);
+		if subscriptions.get(&key).map_or(0, Vec::len) >= MAX_SUBSCRIPTIONS_PER_ACCOUNT {
+			return Err(DirectRpcError::Other("Too many subscriptions of the account".into()))
+		}
+
+		used_subscriptions.insert(subscription, expires_at);
+		subscriptions
+			.entry(key)
+			.or_default()
+			.push(AccountSubscription { id: subscription, expires_at });
+		Ok(())
+	}
+
+	/// All accounts that have at least one subscription in the shard.
+	pub fn subscribed_accounts(&self, shard: &ShardIdentifier) -> Vec<AccountId> {
+		self.subscriptions
+			.read()
+			.expect("Lock poisoning")
+			.keys()
+			.filter(|(s, _)| s == shard)
+			.map(|(_, account)| account.clone())
+			.collect()
+	}
+
+	/// Sends the update to all subscribers of the account in the shard.
+	///
+	/// Subscriptions that have expired or whose connection has been closed are removed.
+	/// A subscription whose connection is not (yet) registered is kept, as the connection is only
+	/// registered after the response to the subscription request has been sent.
+	pub fn notify(
+		&self,
+		shard: &ShardIdentifier,
+		account: &AccountId,
+		update: &AccountEventUpdate,
+	) {
+		let subscriptions = match self
+			.subscriptions
+			.read()
+			.expect("Lock poisoning")
+			.get(&(*shard, account.clone()))
+		{
+			Some(subscriptions) => subscriptions.clone(),
+			None => return,
+		};
+
+		let now = now_as_millis();
+		let result = update.to_hex();
+		let ended_subscriptions: Vec<H256> = subscriptions
+			.into_iter()
+			.filter(|subscription| {
+				if subscription.is_expired(now) {
+					debug!("Account subscription {:?} has expired", subscription.id);
+					self.responder.end_subscription(subscription.id);
+					return true
+				}
+				match self.responder.send_subscription_update(
+					ACCOUNT_EVENTS_SUBSCRIPTION_METHOD,
+					subscription.id,
+					result.clone(),
+				) {
+					Ok(()) | Err(DirectRpcError::InvalidConnectionHash) => false,
+					Err(e) => {
+						debug!("Removing account subscription {:?}: {:?}", subscription.id, e);
+						true
+					},
+				}
+			})
+			.map(|subscription| subscription.id)
+			.collect();
+
+		if !ended_subscriptions.is_empty() {
+			self.remove_subscriptions(&ended_subscriptions);
+		}
+	}
+
+	fn remove_subscriptions(&self, ended_subscriptions: &[H256]) {
+		let mut subscriptions = self.subscriptions.write().expect("Lock poisoning");
+		subscriptions.values_mut().for_each(|account_subscriptions| {
+			account_subscriptions.retain(|s| !ended_subscriptions.contains(&s.id))
+		});
+		subscriptions.retain(|_, account_subscriptions| !account_subscriptions.is_empty());
+	}
+
+	fn remove_expired_subscriptions(
+		&self,
+		subscriptions: &mut HashMap<(ShardIdentifier, AccountId), Vec<AccountSubscription>>,
+		now: u64,
+	) {
+		subscriptions.values_mut().for_each(|account_subscriptions| {
+			account_subscriptions.retain(|s| {
+				if s.is_expired(now) {
+					self.responder.end_subscription(s.id);
+				}
+				!s.is_expired(now)
+			})
+		});
+		subscriptions.retain(|_, account_subscriptions| !account_subscriptions.is_empty());
+	}
+
+	fn is_subscribed(&self, shard: &ShardIdentifier, account: &AccountId) -> bool {
+		self.subscriptions
+			.read()
+			.expect("Lock poisoning")
+			.contains_key(&(*shard, account.clone()))
+	}
+}
+
+impl<Responder, AccountId> ObserveClosedConnections
+	for AccountSubscriptionHandler<Responder, AccountId>
+where
+	Responder: SendSubscriptionUpdate<Hash = H256>,
+	AccountId: Eq + Hash + Clone + Debug + Send + Sync,
+{
+	type Hash = H256;
+
+	fn on_connection_closed(&self, hashes: &[H256]) {
+		self.remove_subscriptions(hashes);
+	}
+}
+
+impl<Responder, AccountId> WatchAccountOperations
+	for AccountSubscriptionHandler<Responder, AccountId>
+where
+	Responder: SendSubscriptionUpdate<Hash = H256>,
+	AccountId: Eq + Hash + Clone + Debug + Send + Sync,
+{
+	type Hash = H256;
+	type AccountId = AccountId;
+
+	fn watch_operation(&self, hash: H256, shard: &ShardIdentifier, account: &AccountId) -> bool {
+		if !self.is_subscribed(shard, account) {
+			return false
+		}
+
+		let mut watched_operations = self.watched_operations.write().expect("Lock poisoning");
+		if watched_operations.len() >= MAX_WATCHED_ACCOUNT_OPERATIONS
+			&& !watched_operations.contains_key(&hash)
+		{
+			warn!("Too many watched operations, not watching {:?} for {:?}", hash, account);
+			return false
+		}
+		watched_operations.insert(hash, (*shard, account.clone()));
+		true
+	}
+}
+
+impl<Responder, AccountId> SendRpcResponse for AccountSubscriptionHandler<Responder, AccountId>
+where
+	Responder: SendSubscriptionUpdate<Hash = H256>,
+	AccountId: Eq + Hash + Clone + Debug + Send + Sync,
+{
+	type Hash = H256;
+
+	fn update_status_event(
+		&self,
+		hash: H256,
+		status_update: TrustedOperationStatus,
+	) -> DirectRpcResult<()> {
+		let maybe_account = if continue_watching(&status_update) {
+			self.watched_operations.read().expect("Lock poisoning").get(&hash).cloned()
+		} else {
+			self.watched_operations.write().expect("Lock poisoning").remove(&hash)
+		};
+
+		if let Some((shard, account)) = maybe_account.as_ref() {
+			self.notify(
+				shard,
+				account,
+				&AccountEventUpdate::TrustedOperationStatus(hash, status_update),
+			);
+		}
+
+		match self.responder.update_status_event(hash, status_update) {
+			// The operation has not been submitted with `author_submitAndWatchExtrinsic`,
+			// it is only watched on behalf of the account subscriptions.
+			Err(DirectRpcError::InvalidConnectionHash) if maybe_account.is_some() => Ok(()),
+			result => result,
+		}
+	}
+
+	fn send_state(&self, hash: H256, state_encoded: Vec<u8>) -> DirectRpcResult<()> {
+		self.responder.send_state(hash, state_encoded)
+	}
+}
+
+#[cfg(test)]
+pub mod tests {
+	use super::*;
+	use crate::{
+		builders::rpc_response_builder::RpcResponseBuilder,
+		mocks::response_channel_mock::ResponseChannelMock, response_channel::ResponseChannel,
+		rpc_connection_registry::ConnectionRegistry, rpc_responder::RpcResponder,
+		RpcConnectionRegistry,
+	};
+	use std::assert_matches::assert_matches;
+
+	type TestConnectionToken = u64;
+	type TestResponseChannel = ResponseChannelMock<TestConnectionToken>;
+	type TestConnectionRegistry = ConnectionRegistry<H256, TestConnectionToken>;
+	type TestResponder = RpcResponder<TestConnectionRegistry, H256, TestResponseChannel>;
+	type TestHandler = AccountSubscriptionHandler<TestResponder, u32>;
+
+	#[test]
+	fn updates_are_only_sent_to_subscribers_of_the_account() {
+		let (handler, connection_registry, response_channel) = create_handler();
+		subscribe(&handler, &connection_registry, 1, H256::from_low_u64_be(1));
+		subscribe(&handler, &connection_registry, 1, H256::from_low_u64_be(2));
+		subscribe(&handler, &connection_registry, 2, H256::from_low_u64_be(3));
+
+		handler.notify(
+			&shard(),
+			&1,
+			&AccountEventUpdate::RuntimeEvent(H256::from_low_u64_be(7), vec![1, 2]),
+		);
+
+		assert_eq!(2, response_channel.number_of_updates());
+		assert_eq!(2, handler.subscribed_accounts(&shard()).len());
+	}
+
+	#[test]
+	fn subscriptions_with_closed_connections_are_removed() {
+		let connection_registry = Arc::new(TestConnectionRegistry::new());
+		let responder =
+			Arc::new(RpcResponder::new(connection_registry.clone(), Arc::new(ClosedChannel)));
+		let handler = AccountSubscriptionHandler::<_, u32>::new(responder);
+		let subscription = H256::from_low_u64_be(1);
+		connection_registry.store(subscription, 1, RpcResponseBuilder::new().with_id(1).build());
+		handler.subscribe(shard(), 1, subscription, u64::MAX).unwrap();
+
+		handler.notify(
+			&shard(),
+			&1,
+			&AccountEventUpdate::RuntimeEvent(H256::from_low_u64_be(7), vec![1, 2]),
+		);
+
+		assert!(handler.subscribed_accounts(&shard()).is_empty());
+		assert!(connection_registry.is_empty());
+	}
+
+	#[test]
+	fn subscriptions_are_kept_until_their_connection_is_registered() {
+		let (handler, _, response_channel) = create_handler();
+		handler.subscribe(shard(), 1, H256::from_low_u64_be(1), u64::MAX).unwrap();
+
+		handler.notify(
+			&shard(),
+			&1,
+			&AccountEventUpdate::RuntimeEvent(H256::from_low_u64_be(7), vec![1, 2]),
+		);
+
+		assert_eq!(0, response_channel.number_of_updates());
+		assert_eq!(vec![1], handler.subscribed_accounts(&shard()));
+	}
+
+	#[test]
+	fn subscriptions_of_closed_connections_are_dropped() {
+		let (handler, connection_registry, _) = create_handler();
+		subscribe(&handler, &connection_registry, 1, H256::from_low_u64_be(1));
+		subscribe(&handler, &connection_registry, 2, H256::from_low_u64_be(2));
+
+		handler.on_connection_closed(&[H256::from_low_u64_be(1), H256::from_low_u64_be(42)]);
+
+		assert_eq!(vec![2], handler.subscribed_accounts(&shard()));
+	}
+
+	#[test]
+	fn expired_subscriptions_are_removed_with_their_connection() {
+		let (handler, connection_registry, response_channel) = create_handler();
+		let subscription = H256::from_low_u64_be(1);
+		connection_registry.store(subscription, 1, RpcResponseBuilder::new().with_id(1).build());
+		handler.subscribe(shard(), 1, subscription, now_as_millis() - 1).unwrap();
+
+		handler.notify(
+			&shard(),
+			&1,
+			&AccountEventUpdate::RuntimeEvent(H256::from_low_u64_be(7), vec![1, 2]),
+		);
+
+		assert_eq!(0, response_channel.number_of_updates());
+		assert!(handler.subscribed_accounts(&shard()).is_empty());
+		assert!(connection_registry.is_empty());
+	}
+
+	#[test]
+	fn updates_are_only_sent_to_subscribers_of_the_shard() {
+		let (handler, connection_registry, response_channel) = create_handler();
+		subscribe(&handler, &connection_registry, 1, H256::from_low_u64_be(1));
+
+		let other_shard = H256::repeat_byte(2);
+		handler.notify(
+			&other_shard,
+			&1,
+			&AccountEventUpdate::RuntimeEvent(H256::from_low_u64_be(7), vec![1, 2]),
+		);
+		assert!(!handler.watch_operation(H256::from_low_u64_be(42), &other_shard, &1));
+
+		assert_eq!(0, response_channel.number_of_updates());
+		assert!(handler.subscribed_accounts(&other_shard).is_empty());
+	}
+
+	#[test]
+	fn existing_subscription_is_rejected() {
+		let (handler, connection_registry, _) = create_handler();
+		subscribe(&handler, &connection_registry, 1, H256::from_low_u64_be(1));
+
+		assert!(handler.subscribe(shard(), 2, H256::from_low_u64_be(1), u64::MAX).is_err());
+		assert_eq!(vec![1], handler.subscribed_accounts(&shard()));
+	}
+
+	#[test]
+	fn dropped_subscription_can_not_be_subscribed_again() {
+		let (handler, connection_registry, _) = create_handler();
+		subscribe(&handler, &connection_registry, 1, H256::from_low_u64_be(1));
+		handler.on_connection_closed(&[H256::from_low_u64_be(1)]);
+		assert!(handler.subscribed_accounts(&shard()).is_empty());
+
+		assert!(handler.subscribe(shard(), 1, H256::from_low_u64_be(1), u64::MAX).is_err());
+		assert!(handler.subscribed_accounts(&shard()).is_empty());
+	}
+
+	#[test]
+	fn subscriptions_per_account_are_capped() {
+		let (handler, connection_registry, _) = create_handler();
+		for i in 0..MAX_SUBSCRIPTIONS_PER_ACCOUNT {
+			subscribe(&handler, &connection_registry, 1, H256::from_low_u64_be(i as u64));
+		}
+
+		let subscription = H256::from_low_u64_be(MAX_SUBSCRIPTIONS_PER_ACCOUNT as u64);
+		assert!(handler.subscribe(shard(), 1, subscription, u64::MAX).is_err());
+		assert!(handler.subscribe(shard(), 2, subscription, u64::MAX).is_ok());
+	}
+
+	#[test]
+	fn operations_are_only_watched_for_subscribed_accounts() {
+		let (handler, connection_registry, _) = create_handler();
+		subscribe(&handler, &connection_registry, 1, H256::from_low_u64_be(1));
+
+		assert!(handler.watch_operation(H256::from_low_u64_be(42), &shard(), &1));
+		assert!(!handler.watch_operation(H256::from_low_u64_be(7), &shard(), &2));
+	}
+
+	#[test]
+	fn status_of_watched_operation_is_sent_to_subscribers_until_it_is_final() {
+		let (handler, connection_registry, response_channel) = create_handler();
+		subscribe(&handler, &connection_registry, 1, H256::from_low_u64_be(1));
+		let operation_hash = H256::from_low_u64_be(42);
+		handler.watch_operation(operation_hash, &shard(), &1);
+
+		// The operation was not submitted with `author_submitAndWatchExtrinsic`.
+		assert!(handler
+			.update_status_event(operation_hash, TrustedOperationStatus::Ready)
+			.is_ok());
+		assert!(handler
+			.update_status_event(
+				operation_hash,
+				TrustedOperationStatus::InSidechainBlock(H256::from_low_u64_be(7))
+			)
+			.is_ok());
+		assert_eq!(2, response_channel.number_of_updates());
+
+		assert_matches!(
+			handler.update_status_event(operation_hash, TrustedOperationStatus::Finalized),
+			Err(DirectRpcError::InvalidConnectionHash)
+		);
+		assert_eq!(2, response_channel.number_of_updates());
+	}
+
+	struct ClosedChannel;
+
+	impl ResponseChannel<TestConnectionToken> for ClosedChannel {
+		type Error = DirectRpcError;
+
+		fn respond(
+			&self,
+			_token: TestConnectionToken,
+			_message: String,
+		) -> Result<(), Self::Error> {
+			Err(DirectRpcError::Other("connection closed".into()))
+		}
+	}
+
+	fn shard() -> ShardIdentifier {
+		H256::repeat_byte(1)
+	}
+
+	fn create_handler() -> (TestHandler, Arc<TestConnectionRegistry>, Arc<TestResponseChannel>) {
+		let connection_registry = Arc::new(TestConnectionRegistry::new());
+		let response_channel = Arc::new(TestResponseChannel::default());
+		let responder =
+			Arc::new(RpcResponder::new(connection_registry.clone(), response_channel.clone()));
+		(TestHandler::new(responder), connection_registry, response_channel)
+	}
+
+	fn subscribe(
+		handler: &TestHandler,
+		connection_registry: &TestConnectionRegistry,
+		account: u32,
+		subscription: H256,
+	) {
+		let rpc_response = RpcResponseBuilder::new().with_id(1).build();
+		connection_registry.store(subscription, account as u64, rpc_response);
+		handler.subscribe(shard(), account, subscription, u64::MAX).unwrap();
+	}
+}
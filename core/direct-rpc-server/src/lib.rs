@@ -39,10 +39,10 @@ use crate::rpc_watch_extractor::RpcWatchExtractor;
 use codec::{Encode, Error as CodecError};
 use itc_tls_websocket_server::error::WebSocketError;
 use itp_rpc::RpcResponse;
-use itp_types::TrustedOperationStatus;
+use itp_types::{ShardIdentifier, TrustedOperationStatus};
 use serde_json::error::Error as SerdeJsonError;
 use sp_runtime::traits;
-use std::{boxed::Box, fmt::Debug, vec::Vec};
+use std::{boxed::Box, fmt::Debug, string::String, vec::Vec};
 
 #[cfg(any(test, feature = "mocks"))]
 pub mod mocks;
@@ -50,6 +50,7 @@ pub mod mocks;
 #[cfg(test)]
 mod builders;
 
+pub mod account_subscriptions;
 pub mod response_channel;
 pub mod rpc_connection_registry;
 pub mod rpc_responder;
@@ -80,11 +81,22 @@ impl<T: std::hash::Hash + traits::Member + Encode> RpcHash for T {}
 /// Registry for RPC connections (i.e. connections that are kept alive to send updates).
 pub trait RpcConnectionRegistry: Send + Sync {
 	type Hash: RpcHash;
-	type Connection: Copy + Debug;
+	type Connection: Copy + Debug + PartialEq;
 
 	fn store(&self, hash: Self::Hash, connection: Self::Connection, rpc_response: RpcResponse);
 
 	fn withdraw(&self, hash: &Self::Hash) -> Option<(Self::Connection, RpcResponse)>;
+
+	/// Removes all entries of a connection and returns their hashes.
+	fn withdraw_connection(&self, connection: &Self::Connection) -> Vec<Self::Hash>;
+}
+
+/// Observes connections that have been closed by the client.
+pub trait ObserveClosedConnections: Send + Sync {
+	type Hash: RpcHash;
+
+	/// Called with the hashes that were registered for the closed connection.
+	fn on_connection_closed(&self, hashes: &[Self::Hash]);
 }
 
 /// Sends an RPC response back to the client.
@@ -100,6 +112,35 @@ pub trait SendRpcResponse: Send + Sync {
 	fn send_state(&self, hash: Self::Hash, state_encoded: Vec<u8>) -> DirectRpcResult<()>;
 }
 
+/// Sends updates of a subscription, whose connection is kept alive until the client disconnects.
+pub trait SendSubscriptionUpdate: SendRpcResponse {
+	fn send_subscription_update(
+		&self,
+		method: &str,
+		subscription: Self::Hash,
+		result: String,
+	) -> DirectRpcResult<()>;
+
+	/// Stops sending updates of the subscription, without closing its connection.
+	fn end_subscription(&self, subscription: Self::Hash);
+}
+
+/// Watches the trusted operations of the accounts that clients have subscribed to.
+pub trait WatchAccountOperations: Send + Sync {
+	type Hash: RpcHash;
+	type AccountId;
+
+	/// Watches the status of a trusted operation that was signed by `account` for `shard`.
+	///
+	/// Returns false if no client has subscribed to the account in the shard.
+	fn watch_operation(
+		&self,
+		hash: Self::Hash,
+		shard: &ShardIdentifier,
+		account: &Self::AccountId,
+	) -> bool;
+}
+
 /// Determines if a given connection must be watched (i.e. kept alive),
 /// based on the information in the RpcResponse.
 pub trait DetermineWatch: Send + Sync {
@@ -23,14 +23,14 @@ use std::sync::RwLock;
 
 use crate::{RpcConnectionRegistry, RpcHash};
 use itp_rpc::RpcResponse;
-use std::{collections::HashMap, fmt::Debug};
+use std::{collections::HashMap, fmt::Debug, vec::Vec};
 
 type HashMapLock<K, V> = RwLock<HashMap<K, V>>;
 
 pub struct ConnectionRegistry<Hash, Token>
 where
 	Hash: RpcHash,
-	Token: Copy + Send + Sync + Debug,
+	Token: Copy + Send + Sync + Debug + PartialEq,
 {
 	connection_map: HashMapLock<<Self as RpcConnectionRegistry>::Hash, (Token, RpcResponse)>,
 }
@@ -38,7 +38,7 @@ where
 impl<Hash, Token> ConnectionRegistry<Hash, Token>
 where
 	Hash: RpcHash,
-	Token: Copy + Send + Sync + Debug,
+	Token: Copy + Send + Sync + Debug + PartialEq,
 {
 	pub fn new() -> Self {
 		Self::default()
@@ -53,7 +53,7 @@ where
 impl<Hash, Token> Default for ConnectionRegistry<Hash, Token>
 where
 	Hash: RpcHash,
-	Token: Copy + Send + Sync + Debug,
+	Token: Copy + Send + Sync + Debug + PartialEq,
 {
 	fn default() -> Self {
 		ConnectionRegistry { connection_map: RwLock::new(HashMap::default()) }
@@ -63,7 +63,7 @@ where
 impl<Hash, Token> RpcConnectionRegistry for ConnectionRegistry<Hash, Token>
 where
 	Hash: RpcHash,
-	Token: Copy + Send + Sync + Debug,
+	Token: Copy + Send + Sync + Debug + PartialEq,
 {
 	type Hash = Hash;
 	type Connection = Token;
@@ -77,6 +77,19 @@ where
 		let mut map = self.connection_map.write().expect("Lock poisoning");
 		map.remove(hash)
 	}
+
+	fn withdraw_connection(&self, connection: &Self::Connection) -> Vec<Self::Hash> {
+		let mut map = self.connection_map.write().expect("Lock poisoning");
+		let hashes: Vec<Hash> = map
+			.iter()
+			.filter(|(_, (token, _))| token == connection)
+			.map(|(hash, _)| hash.clone())
+			.collect();
+		hashes.iter().for_each(|hash| {
+			map.remove(hash);
+		});
+		hashes
+	}
 }
 
 #[cfg(test)]
@@ -119,6 +132,22 @@ pub mod tests {
 		assert!(registry.is_empty());
 	}
 
+	#[test]
+	pub fn withdrawing_connection_removes_all_its_hashes() {
+		let registry = TestRegistry::new();
+
+		registry.store("first".to_string(), 1, dummy_rpc_response());
+		registry.store("second".to_string(), 1, dummy_rpc_response());
+		registry.store("third".to_string(), 2, dummy_rpc_response());
+
+		let mut hashes = registry.withdraw_connection(&1);
+		hashes.sort();
+
+		assert_eq!(vec!["first".to_string(), "second".to_string()], hashes);
+		assert!(registry.withdraw(&"third".to_string()).is_some());
+		assert!(registry.is_empty());
+	}
+
 	fn dummy_rpc_response() -> RpcResponse {
 		RpcResponse { jsonrpc: String::new(), result: Default::default(), id: Id::Number(1u32) }
 	}
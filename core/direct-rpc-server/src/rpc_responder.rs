@@ -17,9 +17,9 @@
 
 use crate::{
 	response_channel::ResponseChannel, DirectRpcError, DirectRpcResult, RpcConnectionRegistry,
-	RpcHash, SendRpcResponse,
+	RpcHash, SendRpcResponse, SendSubscriptionUpdate,
 };
-use alloc::string::ToString;
+use alloc::string::{String, ToString};
 use itp_rpc::{RpcResponse, RpcReturnValue, RpcSubscriptionUpdate, SubscriptionParams};
 use itp_types::{DirectRequestStatus, TrustedOperationStatus};
 use itp_utils::ToHexPrefixed;
@@ -147,7 +147,46 @@ where
 	}
 }
 
-fn continue_watching(status: &TrustedOperationStatus) -> bool {
+impl<Registry, Hash, ResponseChannelType> SendSubscriptionUpdate
+	for RpcResponder<Registry, Hash, ResponseChannelType>
+where
+	Registry: RpcConnectionRegistry<Hash = Hash>,
+	Hash: RpcHash,
+	ResponseChannelType: ResponseChannel<Registry::Connection>,
+{
+	fn send_subscription_update(
+		&self,
+		method: &str,
+		subscription: Hash,
+		result: String,
+	) -> DirectRpcResult<()> {
+		debug!("sending subscription update");
+
+		// withdraw removes it from the registry
+		let (connection_token, rpc_response) = self
+			.connection_registry
+			.withdraw(&subscription)
+			.ok_or(DirectRpcError::InvalidConnectionHash)?;
+
+		let sub = RpcSubscriptionUpdate::new(
+			method.to_string(),
+			SubscriptionParams { error: None, result, subscription: subscription.to_hex() },
+		);
+
+		// In case the client has disconnected, the connection is not stored back.
+		self.encode_and_send_subscription_update(connection_token, &sub)?;
+		self.connection_registry.store(subscription, connection_token, rpc_response);
+
+		debug!("sending subscription update successful");
+		Ok(())
+	}
+
+	fn end_subscription(&self, subscription: Hash) {
+		self.connection_registry.withdraw(&subscription);
+	}
+}
+
+pub(crate) fn continue_watching(status: &TrustedOperationStatus) -> bool {
 	!matches!(
 		status,
 		TrustedOperationStatus::Invalid
@@ -254,6 +293,41 @@ pub mod tests {
 		assert_eq!(1, websocket_responder.number_of_updates());
 	}
 
+	#[test]
+	fn sending_subscription_update_keeps_connection() {
+		let connection_hash = String::from("conn_hash");
+		let connection_registry = create_registry_with_single_connection(connection_hash.clone());
+
+		let websocket_responder = Arc::new(TestResponseChannel::default());
+		let rpc_responder =
+			RpcResponder::new(connection_registry.clone(), websocket_responder.clone());
+
+		for _ in 0..2 {
+			rpc_responder
+				.send_subscription_update("test_subscribe", connection_hash.clone(), "0x01".into())
+				.unwrap();
+		}
+
+		verify_open_connection(&connection_hash, connection_registry);
+		assert_eq!(2, websocket_responder.number_of_updates());
+	}
+
+	#[test]
+	fn given_empty_registry_when_sending_subscription_update_then_return_error() {
+		let connection_registry = Arc::new(TestConnectionRegistry::new());
+		let websocket_responder = Arc::new(TestResponseChannel::default());
+		let rpc_responder = RpcResponder::new(connection_registry, websocket_responder);
+
+		assert_matches!(
+			rpc_responder.send_subscription_update(
+				"test_subscribe",
+				"hash".to_string(),
+				"0x01".into()
+			),
+			Err(DirectRpcError::InvalidConnectionHash)
+		);
+	}
+
 	#[test]
 	fn test_continue_watching() {
 		assert!(!continue_watching(&TrustedOperationStatus::Invalid));
@@ -18,7 +18,7 @@
 #[cfg(all(not(feature = "std"), feature = "sgx"))]
 use crate::sgx_reexport_prelude::*;
 
-use crate::{DetermineWatch, RpcConnectionRegistry, RpcHash};
+use crate::{DetermineWatch, ObserveClosedConnections, RpcConnectionRegistry, RpcHash};
 use itc_tls_websocket_server::{error::WebSocketResult, ConnectionToken, WebSocketMessageHandler};
 use jsonrpc_core::IoHandler;
 use log::*;
@@ -33,6 +33,7 @@ where
 	rpc_io_handler: IoHandler,
 	connection_watcher: Arc<Watcher>,
 	connection_registry: Arc<Registry>,
+	closed_connection_observer: Option<Arc<dyn ObserveClosedConnections<Hash = Hash>>>,
 }
 
 impl<Watcher, Registry, Hash> RpcWsHandler<Watcher, Registry, Hash>
@@ -46,7 +47,21 @@ where
 		connection_watcher: Arc<Watcher>,
 		connection_registry: Arc<Registry>,
 	) -> Self {
-		RpcWsHandler { rpc_io_handler, connection_watcher, connection_registry }
+		RpcWsHandler {
+			rpc_io_handler,
+			connection_watcher,
+			connection_registry,
+			closed_connection_observer: None,
+		}
+	}
+
+	/// Informs the observer about the hashes of connections that the client has closed.
+	pub fn with_closed_connection_observer(
+		mut self,
+		observer: Arc<dyn ObserveClosedConnections<Hash = Hash>>,
+	) -> Self {
+		self.closed_connection_observer = Some(observer);
+		self
 	}
 }
 
@@ -82,6 +97,18 @@ where
 
 		Ok(maybe_rpc_response)
 	}
+
+	fn handle_connection_closed(&self, connection_token: ConnectionToken) {
+		let hashes = self.connection_registry.withdraw_connection(&connection_token.into());
+		if hashes.is_empty() {
+			return
+		}
+
+		debug!("Connection {:?} closed, removed {} watched hashes", connection_token, hashes.len());
+		if let Some(observer) = &self.closed_connection_observer {
+			observer.on_connection_closed(&hashes);
+		}
+	}
 }
 
 #[cfg(test)]
@@ -165,6 +192,41 @@ pub mod tests {
 		assert!(connection_registry.is_empty());
 	}
 
+	#[test]
+	fn closing_connection_withdraws_its_hashes_and_informs_observer() {
+		let io_handler = create_io_handler_with_method(RPC_METHOD_NAME);
+
+		let connection_hash = String::from("connection_hash");
+		let (connection_token, message) = create_message_to_handle(RPC_METHOD_NAME);
+
+		let (ws_handler, connection_registry) =
+			create_ws_handler(io_handler, Some(connection_hash.clone()));
+		let observer = Arc::new(ClosedConnectionObserverMock::default());
+		let ws_handler = ws_handler.with_closed_connection_observer(observer.clone());
+
+		ws_handler.handle_message(connection_token, message).unwrap();
+		ws_handler.handle_connection_closed(ConnectionToken(7));
+		assert!(observer.closed_hashes.lock().unwrap().is_empty());
+
+		ws_handler.handle_connection_closed(connection_token);
+
+		assert!(connection_registry.is_empty());
+		assert_eq!(vec![connection_hash], *observer.closed_hashes.lock().unwrap());
+	}
+
+	#[derive(Default)]
+	struct ClosedConnectionObserverMock {
+		closed_hashes: std::sync::Mutex<Vec<String>>,
+	}
+
+	impl ObserveClosedConnections for ClosedConnectionObserverMock {
+		type Hash = String;
+
+		fn on_connection_closed(&self, hashes: &[String]) {
+			self.closed_hashes.lock().unwrap().extend_from_slice(hashes);
+		}
+	}
+
 	fn create_message_to_handle(method_name: &str) -> (ConnectionToken, String) {
 		let json_rpc_pre_method = r#"{"jsonrpc": "2.0", "method": ""#;
 		let json_rpc_post_method = r#"", "params": {}, "id": 1}"#;
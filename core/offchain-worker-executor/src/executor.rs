@@ -15,7 +15,10 @@
 
 */
 
-use crate::error::Result;
+use crate::{
+	error::Result,
+	offchain_task::{OffchainTaskOutput, OffchainTaskScheduler, TaskId},
+};
 use codec::{Decode, Encode};
 use core::fmt::Debug;
 use itc_parentchain_light_client::{
@@ -25,15 +28,21 @@ use itc_parentchain_light_client::{
 use itp_extrinsics_factory::CreateExtrinsics;
 use itp_stf_executor::{traits::StateUpdateProposer, ExecutedOperation};
 use itp_stf_interface::system_pallet::SystemPalletEventInterface;
-use itp_stf_primitives::{traits::TrustedCallVerification, types::TrustedOperationOrHash};
+use itp_stf_primitives::{
+	traits::TrustedCallVerification,
+	types::{TrustedOperation, TrustedOperationOrHash},
+};
 use itp_stf_state_handler::{handle_state::HandleState, query_shard_state::QueryShardState};
 use itp_top_pool_author::traits::AuthorApi;
 use itp_types::{
-	parentchain::{GenericMortality, ParentchainCall},
+	parentchain::{BlockNumber, GenericMortality, ParentchainCall},
 	OpaqueCall, ShardIdentifier, H256,
 };
 use log::*;
-use sp_runtime::traits::Block;
+use sp_runtime::{
+	traits::{Block, Header as HeaderTrait},
+	SaturatedConversion,
+};
 use std::{marker::PhantomData, sync::Arc, time::Duration, vec::Vec};
 
 /// Off-chain worker executor implementation.
@@ -44,6 +53,9 @@ use std::{marker::PhantomData, sync::Arc, time::Duration, vec::Vec};
 ///
 /// The trigger to start executing calls is given when the parentchain block imported event is
 /// signaled (event listener).
+///
+/// Periodic offchain tasks registered with the [`OffchainTaskScheduler`] are run before the calls
+/// are executed, their trusted calls are executed in the same batch as the calls of the pool.
 pub struct Executor<
 	ParentchainBlock,
 	TopPoolAuthor,
@@ -54,12 +66,17 @@ pub struct Executor<
 	Stf,
 	TCS,
 	G,
-> {
+> where
+	StfExecutor: StateUpdateProposer<TCS, G>,
+	TCS: PartialEq + Encode + Decode + Debug + Send + Sync,
+	G: PartialEq + Encode + Decode + Debug + Send + Sync,
+{
 	top_pool_author: Arc<TopPoolAuthor>,
 	stf_executor: Arc<StfExecutor>,
 	state_handler: Arc<StateHandler>,
 	validator_accessor: Arc<ValidatorAccessor>,
 	extrinsics_factory: Arc<ExtrinsicsFactory>,
+	task_scheduler: Option<Arc<OffchainTaskScheduler<StfExecutor::Externalities, TCS>>>,
 	_phantom: PhantomData<(ParentchainBlock, Stf, TCS, G)>,
 }
 
@@ -88,6 +105,7 @@ impl<
 	ParentchainBlock: Block<Hash = H256>,
 	StfExecutor: StateUpdateProposer<TCS, G>,
 	TopPoolAuthor: AuthorApi<H256, ParentchainBlock::Hash, TCS, G>,
+	StfExecutor::Externalities: Clone,
 	StateHandler: QueryShardState + HandleState<StateT = StfExecutor::Externalities>,
 	ValidatorAccessor: ValidatorAccess<ParentchainBlock> + Send + Sync + 'static,
 	ExtrinsicsFactory: CreateExtrinsics,
@@ -109,10 +127,20 @@ impl<
 			state_handler,
 			validator_accessor,
 			extrinsics_factory,
+			task_scheduler: None,
 			_phantom: Default::default(),
 		}
 	}
 
+	/// Run the offchain tasks registered with the scheduler.
+	pub fn with_task_scheduler(
+		mut self,
+		task_scheduler: Arc<OffchainTaskScheduler<StfExecutor::Externalities, TCS>>,
+	) -> Self {
+		self.task_scheduler = Some(task_scheduler);
+		self
+	}
+
 	pub fn execute(&self) -> Result<()> {
 		let max_duration = Duration::from_secs(5);
 		let latest_parentchain_header = self.get_latest_parentchain_header()?;
@@ -127,7 +155,14 @@ impl<
 				"executing pending tops in top pool with status: {:?}",
 				self.top_pool_author.get_status(shard)
 			);
-			let trusted_calls = self.top_pool_author.get_pending_trusted_calls(shard);
+			let mut trusted_calls = self.top_pool_author.get_pending_trusted_calls(shard);
+
+			let (mut task_output, ran_tasks) =
+				self.run_offchain_tasks(&shard, &latest_parentchain_header)?;
+			trusted_calls
+				.extend(task_output.trusted_calls.into_iter().map(TrustedOperation::direct_call));
+			parentchain_effects.append(&mut task_output.parentchain_calls);
+
 			trace!("Executing {} trusted calls on shard {:?}", trusted_calls.len(), shard);
 
 			let batch_execution_result = self.stf_executor.propose_state_update(
@@ -160,6 +195,9 @@ impl<
 			// Remove successful operations from pool
 			self.remove_calls_from_pool(&shard, successful_operations);
 
+			// Only now the output of the tasks has been executed, otherwise they are run again.
+			self.record_offchain_task_runs(&shard, &ran_tasks, &latest_parentchain_header);
+
 			// TODO: notify parentchain about executed operations? -> add to parentchain effects
 		}
 
@@ -170,6 +208,56 @@ impl<
 		Ok(())
 	}
 
+	/// Run the offchain tasks that are due on a copy of the current state of the shard.
+	///
+	/// Returns their combined output and the tasks that ran successfully. A failing task is
+	/// skipped, so it does not prevent the other tasks and the calls of the pool from being
+	/// executed. It is still due and runs again with the next execution.
+	fn run_offchain_tasks(
+		&self,
+		shard: &ShardIdentifier,
+		latest_parentchain_header: &ParentchainBlock::Header,
+	) -> Result<(OffchainTaskOutput<TCS>, Vec<TaskId>)> {
+		let mut output = OffchainTaskOutput::default();
+		let mut ran_tasks = Vec::new();
+		let task_scheduler = match &self.task_scheduler {
+			Some(task_scheduler) => task_scheduler,
+			None => return Ok((output, ran_tasks)),
+		};
+
+		let block_number: BlockNumber = (*latest_parentchain_header.number()).saturated_into();
+		let due_tasks = task_scheduler.due_tasks(shard, block_number);
+		if due_tasks.is_empty() {
+			return Ok((output, ran_tasks))
+		}
+
+		let (state, _) = self.state_handler.load_cloned(shard)?;
+		for (id, task) in due_tasks {
+			debug!("Running offchain task {} on shard {:?}", task.name(), shard);
+			match task.run(shard, &mut state.clone(), block_number) {
+				Ok(task_output) => {
+					output.append(task_output);
+					ran_tasks.push(id);
+				},
+				Err(e) =>
+					error!("Offchain task {} failed on shard {:?}: {:?}", task.name(), shard, e),
+			}
+		}
+		Ok((output, ran_tasks))
+	}
+
+	fn record_offchain_task_runs(
+		&self,
+		shard: &ShardIdentifier,
+		ran_tasks: &[TaskId],
+		latest_parentchain_header: &ParentchainBlock::Header,
+	) {
+		if let Some(task_scheduler) = &self.task_scheduler {
+			let block_number: BlockNumber = (*latest_parentchain_header.number()).saturated_into();
+			task_scheduler.record_runs(shard, ran_tasks, block_number);
+		}
+	}
+
 	fn get_latest_parentchain_header(&self) -> Result<ParentchainBlock::Header> {
 		let header = self.validator_accessor.execute_on_validator(|v| {
 			let latest_parentchain_header = v.latest_finalized_header()?;
@@ -192,25 +280,14 @@ impl<
 			.iter()
 			.filter_map(|parentchain_call| parentchain_call.as_integritee())
 			.collect();
-		let target_a_calls: Vec<(OpaqueCall, GenericMortality)> = parentchain_effects
-			.iter()
-			.filter_map(|parentchain_call| parentchain_call.as_target_a())
-			.collect();
-		let target_b_calls: Vec<(OpaqueCall, GenericMortality)> = parentchain_effects
-			.iter()
-			.filter_map(|parentchain_call| parentchain_call.as_target_b())
-			.collect();
+		let target_calls_count = parentchain_effects.len() - integritee_calls.len();
 		debug!(
-			"stf wants to send calls to parentchains: Integritee: {} TargetA: {} TargetB: {}",
+			"stf wants to send calls to parentchains: Integritee: {} Targets: {}",
 			integritee_calls.len(),
-			target_a_calls.len(),
-			target_b_calls.len()
+			target_calls_count
 		);
-		if !target_a_calls.is_empty() {
-			warn!("sending extrinsics to target A unimplemented for OCW")
-		};
-		if !target_b_calls.is_empty() {
-			warn!("sending extrinsics to target B unimplemented for OCW")
+		if target_calls_count > 0 {
+			warn!("sending extrinsics to target parentchains unimplemented for OCW")
 		};
 
 		let extrinsics =
@@ -257,8 +334,12 @@ mod tests {
 	use itp_top_pool_author::mocks::AuthorApiMock;
 	use itp_types::Block as ParentchainBlock;
 
+	use crate::{error::Error, offchain_task::OffchainTask};
 	use itp_test::mock::stf_mock::mock_top_indirect_trusted_call_signed;
-	use std::boxed::Box;
+	use std::{
+		boxed::Box,
+		sync::atomic::{AtomicU32, Ordering},
+	};
 
 	type TestStateHandler = HandleStateMock;
 	type TestStfInterface = SystemPalletEventInterfaceMock;
@@ -343,6 +424,71 @@ mod tests {
 		assert_eq!(TestStfInterface::get_event_count(&mut stf_executor.get_state()), 0);
 	}
 
+	#[test]
+	fn due_offchain_tasks_are_run_once_per_parentchain_block() {
+		let stf_executor = Arc::new(TestStfExecutor::new(State::default()));
+		let top_pool_author = Arc::new(TestTopPoolAuthor::default());
+		let task = Arc::new(CountingTask::default());
+		let task_scheduler = Arc::new(OffchainTaskScheduler::new());
+		task_scheduler.register(task.clone());
+
+		let executor =
+			create_executor(top_pool_author, stf_executor).with_task_scheduler(task_scheduler);
+
+		executor.execute().unwrap();
+		executor.execute().unwrap();
+
+		assert_eq!(task.runs.load(Ordering::SeqCst), 1);
+	}
+
+	#[test]
+	fn failed_offchain_task_is_run_again_with_the_next_execution() {
+		let stf_executor = Arc::new(TestStfExecutor::new(State::default()));
+		let top_pool_author = Arc::new(TestTopPoolAuthor::default());
+		let task = Arc::new(CountingTask { runs: AtomicU32::new(0), failing_runs: 1 });
+		let task_scheduler = Arc::new(OffchainTaskScheduler::new());
+		task_scheduler.register(task.clone());
+
+		let executor =
+			create_executor(top_pool_author, stf_executor).with_task_scheduler(task_scheduler);
+
+		executor.execute().unwrap();
+		executor.execute().unwrap();
+		executor.execute().unwrap();
+
+		assert_eq!(task.runs.load(Ordering::SeqCst), 2);
+	}
+
+	#[derive(Default)]
+	struct CountingTask {
+		runs: AtomicU32,
+		/// Number of first runs that fail.
+		failing_runs: u32,
+	}
+
+	impl OffchainTask<State, TrustedCallSignedMock> for CountingTask {
+		fn name(&self) -> &'static str {
+			"counting_task"
+		}
+
+		fn period(&self) -> BlockNumber {
+			1
+		}
+
+		fn run(
+			&self,
+			_shard: &ShardIdentifier,
+			_state: &mut State,
+			_parentchain_block_number: BlockNumber,
+		) -> Result<OffchainTaskOutput<TrustedCallSignedMock>> {
+			let previous_runs = self.runs.fetch_add(1, Ordering::SeqCst);
+			if previous_runs < self.failing_runs {
+				return Err(Error::Other("task failed".into()))
+			}
+			Ok(OffchainTaskOutput::default())
+		}
+	}
+
 	fn create_executor(
 		top_pool_author: Arc<TestTopPoolAuthor>,
 		stf_executor: Arc<TestStfExecutor>,
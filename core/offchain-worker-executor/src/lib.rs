@@ -31,3 +31,4 @@ pub mod sgx_reexport_prelude {
 
 pub mod error;
 pub mod executor;
+pub mod offchain_task;
@@ -0,0 +1,237 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Periodic offchain tasks, similar to the offchain workers of Substrate.
+//!
+//! A task is run inside the enclave every `period` parentchain blocks, against the current state
+//! of each shard. It can compute anything on the private state and return trusted calls, which are
+//! executed together with the calls of the TOP pool, and parentchain calls, which are sent as
+//! extrinsics signed by the enclave.
+
+#[cfg(all(not(feature = "std"), feature = "sgx"))]
+use std::sync::SgxRwLock as RwLock;
+
+#[cfg(feature = "std")]
+use std::sync::RwLock;
+
+use crate::error::Result;
+use itp_types::{
+	parentchain::{BlockNumber, ParentchainCall},
+	ShardIdentifier,
+};
+use std::{collections::BTreeMap, sync::Arc, vec::Vec};
+
+/// Everything an offchain task wants to have executed.
+pub struct OffchainTaskOutput<TCS> {
+	pub trusted_calls: Vec<TCS>,
+	pub parentchain_calls: Vec<ParentchainCall>,
+}
+
+impl<TCS> Default for OffchainTaskOutput<TCS> {
+	fn default() -> Self {
+		OffchainTaskOutput { trusted_calls: Vec::new(), parentchain_calls: Vec::new() }
+	}
+}
+
+impl<TCS> OffchainTaskOutput<TCS> {
+	pub fn append(&mut self, mut other: Self) {
+		self.trusted_calls.append(&mut other.trusted_calls);
+		self.parentchain_calls.append(&mut other.parentchain_calls);
+	}
+}
+
+pub trait OffchainTask<State, TCS>: Send + Sync {
+	/// Name of the task, for logging.
+	fn name(&self) -> &'static str;
+
+	/// Number of parentchain blocks between two runs of the task on a shard.
+	fn period(&self) -> BlockNumber;
+
+	/// Run the task on a copy of the shard state.
+	///
+	/// Changes to the state are discarded, the state can only be changed with trusted calls.
+	fn run(
+		&self,
+		shard: &ShardIdentifier,
+		state: &mut State,
+		parentchain_block_number: BlockNumber,
+	) -> Result<OffchainTaskOutput<TCS>>;
+}
+
+/// Identifies a registered task within its scheduler.
+pub type TaskId = usize;
+
+struct RegisteredTask<State, TCS> {
+	task: Arc<dyn OffchainTask<State, TCS>>,
+	last_runs: BTreeMap<ShardIdentifier, BlockNumber>,
+}
+
+/// Keeps the registered offchain tasks and decides when they are due.
+pub struct OffchainTaskScheduler<State, TCS> {
+	tasks: RwLock<Vec<RegisteredTask<State, TCS>>>,
+}
+
+impl<State, TCS> Default for OffchainTaskScheduler<State, TCS> {
+	fn default() -> Self {
+		OffchainTaskScheduler { tasks: Default::default() }
+	}
+}
+
+impl<State, TCS> OffchainTaskScheduler<State, TCS> {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Register a task, it is run on each shard the next time the executor runs.
+	pub fn register(&self, task: Arc<dyn OffchainTask<State, TCS>>) {
+		self.tasks
+			.write()
+			.expect("Lock poisoning")
+			.push(RegisteredTask { task, last_runs: Default::default() });
+	}
+
+	/// Tasks that are due on the shard at the given parentchain block.
+	///
+	/// A task stays due until its run is recorded with [`Self::record_runs`].
+	pub fn due_tasks(
+		&self,
+		shard: &ShardIdentifier,
+		block_number: BlockNumber,
+	) -> Vec<(TaskId, Arc<dyn OffchainTask<State, TCS>>)> {
+		let tasks = self.tasks.read().expect("Lock poisoning");
+		tasks
+			.iter()
+			.enumerate()
+			.filter(|(_, registered)| {
+				let period = registered.task.period().max(1);
+				registered
+					.last_runs
+					.get(shard)
+					.map_or(true, |last_run| block_number >= last_run.saturating_add(period))
+			})
+			.map(|(id, registered)| (id, registered.task.clone()))
+			.collect()
+	}
+
+	/// Records that the tasks have run on the shard at the given parentchain block, i.e. that
+	/// their output has been executed. They are due again one period later.
+	pub fn record_runs(
+		&self,
+		shard: &ShardIdentifier,
+		task_ids: &[TaskId],
+		block_number: BlockNumber,
+	) {
+		let mut tasks = self.tasks.write().expect("Lock poisoning");
+		for id in task_ids {
+			if let Some(registered) = tasks.get_mut(*id) {
+				registered.last_runs.insert(*shard, block_number);
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	struct TestTask {
+		period: BlockNumber,
+	}
+
+	impl OffchainTask<(), u32> for TestTask {
+		fn name(&self) -> &'static str {
+			"test_task"
+		}
+
+		fn period(&self) -> BlockNumber {
+			self.period
+		}
+
+		fn run(
+			&self,
+			_shard: &ShardIdentifier,
+			_state: &mut (),
+			parentchain_block_number: BlockNumber,
+		) -> Result<OffchainTaskOutput<u32>> {
+			Ok(OffchainTaskOutput {
+				trusted_calls: vec![parentchain_block_number],
+				parentchain_calls: Vec::new(),
+			})
+		}
+	}
+
+	fn shard(id: u64) -> ShardIdentifier {
+		ShardIdentifier::from_low_u64_be(id)
+	}
+
+	/// Runs the due tasks and records their runs, like the executor does if the output could be
+	/// executed.
+	fn run_due_tasks(
+		scheduler: &OffchainTaskScheduler<(), u32>,
+		shard: &ShardIdentifier,
+		block_number: BlockNumber,
+	) -> usize {
+		let due_tasks = scheduler.due_tasks(shard, block_number);
+		let ids: Vec<TaskId> = due_tasks.iter().map(|(id, _)| *id).collect();
+		scheduler.record_runs(shard, &ids, block_number);
+		ids.len()
+	}
+
+	#[test]
+	fn task_is_due_every_period() {
+		let scheduler = OffchainTaskScheduler::new();
+		scheduler.register(Arc::new(TestTask { period: 3 }));
+
+		let due_at: Vec<BlockNumber> =
+			(10..20).filter(|n| run_due_tasks(&scheduler, &shard(1), *n) > 0).collect();
+
+		assert_eq!(due_at, vec![10, 13, 16, 19]);
+	}
+
+	#[test]
+	fn task_is_due_on_each_shard_separately() {
+		let scheduler = OffchainTaskScheduler::new();
+		scheduler.register(Arc::new(TestTask { period: 5 }));
+
+		assert_eq!(run_due_tasks(&scheduler, &shard(1), 10), 1);
+		assert_eq!(run_due_tasks(&scheduler, &shard(2), 11), 1);
+		assert_eq!(run_due_tasks(&scheduler, &shard(1), 11), 0);
+	}
+
+	#[test]
+	fn task_with_period_zero_is_due_once_per_block() {
+		let scheduler = OffchainTaskScheduler::new();
+		scheduler.register(Arc::new(TestTask { period: 0 }));
+
+		assert_eq!(run_due_tasks(&scheduler, &shard(1), 10), 1);
+		assert_eq!(run_due_tasks(&scheduler, &shard(1), 10), 0);
+		assert_eq!(run_due_tasks(&scheduler, &shard(1), 11), 1);
+	}
+
+	#[test]
+	fn task_stays_due_until_its_run_is_recorded() {
+		let scheduler = OffchainTaskScheduler::new();
+		scheduler.register(Arc::new(TestTask { period: 5 }));
+
+		assert_eq!(scheduler.due_tasks(&shard(1), 10).len(), 1);
+		assert_eq!(scheduler.due_tasks(&shard(1), 11).len(), 1);
+
+		scheduler.record_runs(&shard(1), &[0], 11);
+		assert!(scheduler.due_tasks(&shard(1), 12).is_empty());
+		assert_eq!(scheduler.due_tasks(&shard(1), 16).len(), 1);
+	}
+}
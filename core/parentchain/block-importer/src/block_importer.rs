@@ -17,12 +17,16 @@
 
 //! Imports parentchain blocks and executes any indirect calls found in the extrinsics.
 
-use crate::{error::Result, ImportParentchainBlocks};
+use crate::{
+	error::{Error, Result},
+	ImportParentchainBlocks,
+};
 
 use ita_stf::ParentchainHeader;
 use itc_parentchain_indirect_calls_executor::ExecuteIndirectCalls;
 use itc_parentchain_light_client::{
-	concurrent_access::ValidatorAccess, BlockNumberOps, ExtrinsicSender, Validator,
+	concurrent_access::ValidatorAccess, error::Error as LightClientError, BlockNumberOps,
+	ExtrinsicSender, Validator,
 };
 use itp_extrinsics_factory::CreateExtrinsics;
 use itp_stf_executor::traits::StfUpdateState;
@@ -90,6 +94,96 @@ impl<
 	}
 }
 
+impl<
+		ParentchainBlock,
+		ValidatorAccessor,
+		StfExecutor,
+		ExtrinsicsFactory,
+		IndirectCallsExecutor,
+	>
+	ParentchainBlockImporter<
+		ParentchainBlock,
+		ValidatorAccessor,
+		StfExecutor,
+		ExtrinsicsFactory,
+		IndirectCallsExecutor,
+	> where
+	ParentchainBlock: ParentchainBlockTrait<Hash = H256, Header = ParentchainHeader>,
+	NumberFor<ParentchainBlock>: BlockNumberOps,
+	ValidatorAccessor: ValidatorAccess<ParentchainBlock> + IdentifyParentchain,
+	StfExecutor: StfUpdateState<ParentchainHeader, ParentchainId>,
+	ExtrinsicsFactory: CreateExtrinsics,
+	IndirectCallsExecutor: ExecuteIndirectCalls,
+{
+	/// Submits the block to the light client, updates the states and executes the indirect calls
+	/// of the block. Confirmation calls are added to `calls`.
+	fn import_block(
+		&self,
+		signed_block: SignedBlockG<ParentchainBlock>,
+		raw_events: Vec<u8>,
+		calls: &mut Vec<(OpaqueCall, GenericMortality)>,
+	) -> Result<()> {
+		let id = self.validator_accessor.parentchain_id();
+
+		if let Err(e) = self
+			.validator_accessor
+			.execute_mut_on_validator(|v| v.submit_block(&signed_block))
+		{
+			error!("[{:?}] Header submission to light client failed for block number {} and hash {:?}: {:?}", id, signed_block.block.header().number(), signed_block.block.hash(), e);
+
+			return Err(e.into())
+		}
+
+		// check if we can fast-sync
+		if let Some(creation_block) = self.shard_creation_info.for_parentchain(id) {
+			if signed_block.block.header().number < creation_block.number {
+				trace!(
+					"fast-syncing block import, ignoring any invocations before block {:}",
+					creation_block.number
+				);
+				return Ok(())
+			}
+		}
+
+		let block = signed_block.block;
+		// Perform state updates.
+		if let Err(e) = self
+			.stf_executor
+			.update_states(block.header(), &self.validator_accessor.parentchain_id())
+		{
+			error!("[{:?}] Error performing state updates upon block import", id);
+			return Err(e.into())
+		}
+
+		// Execute indirect calls that were found in the extrinsics of the block,
+		// incl. shielding and unshielding.
+		match self
+			.indirect_calls_executor
+			.execute_indirect_calls_in_extrinsics(&block, &raw_events)
+		{
+			Ok(Some(confirm_processed_parentchain_block_call)) => {
+				let opaque_call = confirm_processed_parentchain_block_call;
+				// if we have significant downtime, this mortality means we will not confirm all imported blocks
+				let mortality = GenericMortality {
+					era: Era::mortal(512, (*block.header().number()).into()),
+					mortality_checkpoint: Some(block.hash()),
+				};
+				calls.push((opaque_call, mortality));
+			},
+			Ok(None) => trace!("omitting confirmation call to non-integritee parentchain"),
+			Err(e) => error!("[{:?}] Error executing relevant extrinsics: {:?}", id, e),
+		};
+
+		info!(
+			"[{:?}] Successfully imported parentchain block (number: {}, hash: {})",
+			id,
+			block.header().number,
+			block.header().hash()
+		);
+		Ok(())
+	}
+}
+
 impl<
 		ParentchainBlock,
 		ValidatorAccessor,
@@ -135,61 +229,21 @@ impl<
 		for (signed_block, raw_events) in
 			blocks_to_import.into_iter().zip(events_to_import_aligned.into_iter())
 		{
-			if let Err(e) = self
+			// Blocks we have missed are fetched by the light client, and go through the whole
+			// import as well, such that none of their indirect calls are skipped.
+			let missing_ancestors = self
 				.validator_accessor
-				.execute_mut_on_validator(|v| v.submit_block(&signed_block))
-			{
-				error!("[{:?}] Header submission to light client failed for block number {} and hash {:?}: {:?}", id, signed_block.block.header().number(), signed_block.block.hash(), e);
-
-				return Err(e.into())
-			}
-
-			// check if we can fast-sync
-			if let Some(creation_block) = self.shard_creation_info.for_parentchain(id) {
-				if signed_block.block.header().number < creation_block.number {
-					trace!(
-						"fast-syncing block import, ignoring any invocations before block {:}",
-						creation_block.number
-					);
-					continue
+				.execute_on_validator(|v| v.fetch_missing_ancestors(&signed_block))?;
+			for (ancestor, ancestor_events) in missing_ancestors.into_iter() {
+				match self.import_block(ancestor, ancestor_events, &mut calls) {
+					Err(Error::LightClient(LightClientError::BlockAlreadyImported)) => {
+						debug!("[{:?}] Skipping already imported ancestor block", id);
+					},
+					result => result?,
 				}
 			}
 
-			let block = signed_block.block;
-			// Perform state updates.
-			if let Err(e) = self
-				.stf_executor
-				.update_states(block.header(), &self.validator_accessor.parentchain_id())
-			{
-				error!("[{:?}] Error performing state updates upon block import", id);
-				return Err(e.into())
-			}
-
-			// Execute indirect calls that were found in the extrinsics of the block,
-			// incl. shielding and unshielding.
-			match self
-				.indirect_calls_executor
-				.execute_indirect_calls_in_extrinsics(&block, &raw_events)
-			{
-				Ok(Some(confirm_processed_parentchain_block_call)) => {
-					let opaque_call = confirm_processed_parentchain_block_call;
-					// if we have significant downtime, this mortality means we will not confirm all imported blocks
-					let mortality = GenericMortality {
-						era: Era::mortal(512, (*block.header().number()).into()),
-						mortality_checkpoint: Some(block.hash()),
-					};
-					calls.push((opaque_call, mortality));
-				},
-				Ok(None) => trace!("omitting confirmation call to non-integritee parentchain"),
-				Err(e) => error!("[{:?}] Error executing relevant extrinsics: {:?}", id, e),
-			};
-
-			info!(
-				"[{:?}] Successfully imported parentchain block (number: {}, hash: {})",
-				id,
-				block.header().number,
-				block.header().hash()
-			);
+			self.import_block(signed_block, raw_events, &mut calls)?;
 		}
 
 		// Create extrinsics for all `block processed` calls we've gathered.
@@ -22,7 +22,7 @@ use crate::sgx_reexport_prelude::*;
 use crate::{
 	error::{Error, Result},
 	filter_metadata::{EventsFromMetadata, FilterIntoDataFrom},
-	traits::{ExecuteIndirectCalls, IndirectDispatch},
+	traits::{ExecuteIndirectCalls, GetParentchainId, IndirectDispatch},
 };
 use alloc::format;
 use binary_merkle_tree::merkle_root;
@@ -32,12 +32,16 @@ use itp_node_api::metadata::{
 	pallet_enclave_bridge::EnclaveBridgeCallIndexes, provider::AccessNodeMetadata,
 	NodeMetadataTrait,
 };
-use itp_sgx_crypto::{key_repository::AccessKey, ShieldingCryptoDecrypt, ShieldingCryptoEncrypt};
+use itp_sgx_crypto::{
+	key_repository::{AccessKey, AccessRetiredKey},
+	ShieldingCryptoDecrypt, ShieldingCryptoEncrypt,
+};
 use itp_stf_executor::traits::{StfEnclaveSigning, StfShardVaultQuery};
 use itp_stf_primitives::{
 	traits::{IndirectExecutor, TrustedCallSigning, TrustedCallVerification},
 	types::AccountId,
 };
+use itp_time_utils::now_as_millis;
 use itp_top_pool_author::traits::AuthorApi;
 use itp_types::{
 	parentchain::{ExtrinsicStatus, FilterEvents, HandleParentchainEvents, ParentchainId},
@@ -58,8 +62,17 @@ pub struct IndirectCallsExecutor<
 	ParentchainEventHandler,
 	TCS,
 	G,
-> {
+> where
+	ShieldingKeyRepository: AccessKey,
+{
 	pub(crate) shielding_key_repo: Arc<ShieldingKeyRepository>,
+	retired_shielding_key_repo: Option<
+		Arc<
+			dyn AccessRetiredKey<KeyType = <ShieldingKeyRepository as AccessKey>::KeyType>
+				+ Send
+				+ Sync,
+		>,
+	>,
 	pub stf_enclave_signer: Arc<StfEnclaveSigner>,
 	pub(crate) top_pool_author: Arc<TopPoolAuthor>,
 	pub(crate) node_meta_data_provider: Arc<NodeMetadataProvider>,
@@ -87,7 +100,8 @@ impl<
 		ParentchainEventHandler,
 		TCS,
 		G,
-	>
+	> where
+	ShieldingKeyRepository: AccessKey,
 {
 	pub fn new(
 		shielding_key_repo: Arc<ShieldingKeyRepository>,
@@ -98,6 +112,7 @@ impl<
 	) -> Self {
 		IndirectCallsExecutor {
 			shielding_key_repo,
+			retired_shielding_key_repo: None,
 			stf_enclave_signer,
 			top_pool_author,
 			node_meta_data_provider,
@@ -105,6 +120,34 @@ impl<
 			_phantom: Default::default(),
 		}
 	}
+
+	/// Additionally decrypt indirect calls with the shielding key that was replaced by a key
+	/// rotation, as long as its grace period has not ended. Parentchain blocks may still contain
+	/// calls that were encrypted for the previous key.
+	pub fn with_retired_shielding_key(
+		mut self,
+		retired_shielding_key_repo: Arc<
+			dyn AccessRetiredKey<KeyType = <ShieldingKeyRepository as AccessKey>::KeyType>
+				+ Send
+				+ Sync,
+		>,
+	) -> Self {
+		self.retired_shielding_key_repo = Some(retired_shielding_key_repo);
+		self
+	}
+
+	fn decrypt_with_retired_shielding_key(&self, encrypted: &[u8]) -> Option<Vec<u8>>
+	where
+		<ShieldingKeyRepository as AccessKey>::KeyType: ShieldingCryptoDecrypt,
+	{
+		let retired_key = self
+			.retired_shielding_key_repo
+			.as_ref()?
+			.retrieve_retired_key(now_as_millis())
+			.ok()??;
+		debug!("Decrypting indirect call with the retired shielding key");
+		retired_key.decrypt(encrypted).ok()
+	}
 }
 
 impl<
@@ -279,7 +322,10 @@ impl<
 
 	fn decrypt(&self, encrypted: &[u8]) -> Result<Vec<u8>> {
 		let key = self.shielding_key_repo.retrieve_key()?;
-		Ok(key.decrypt(encrypted)?)
+		match key.decrypt(encrypted) {
+			Ok(decrypted) => Ok(decrypted),
+			Err(e) => self.decrypt_with_retired_shielding_key(encrypted).ok_or_else(|| e.into()),
+		}
 	}
 
 	fn encrypt(&self, value: &[u8]) -> Result<Vec<u8>> {
@@ -304,6 +350,35 @@ impl<
 	}
 }
 
+impl<
+		ShieldingKeyRepository,
+		StfEnclaveSigner,
+		TopPoolAuthor,
+		NodeMetadataProvider,
+		FilterIndirectCalls,
+		EventFilter,
+		PrivacySidechain,
+		TCS,
+		G,
+	> GetParentchainId
+	for IndirectCallsExecutor<
+		ShieldingKeyRepository,
+		StfEnclaveSigner,
+		TopPoolAuthor,
+		NodeMetadataProvider,
+		FilterIndirectCalls,
+		EventFilter,
+		PrivacySidechain,
+		TCS,
+		G,
+	> where
+	ShieldingKeyRepository: AccessKey,
+{
+	fn parentchain_id(&self) -> ParentchainId {
+		self.parentchain_id
+	}
+}
+
 pub(crate) fn hash_of<T: Encode>(xt: &T) -> H256 {
 	blake2_256(&xt.encode()).into()
 }
@@ -321,7 +396,7 @@ mod test {
 		},
 		metadata::{metadata_mocks::NodeMetadataMock, provider::NodeMetadataRepository},
 	};
-	use itp_sgx_crypto::mocks::KeyRepositoryMock;
+	use itp_sgx_crypto::mocks::{KeyRepositoryMock, RetiredKeyRepositoryMock};
 	use itp_stf_executor::mocks::StfEnclaveSignerMock;
 	use itp_stf_primitives::{
 		traits::TrustedCallVerification,
@@ -415,6 +490,33 @@ mod test {
 		assert!(trusted_call_signed.verify_signature(&mr_enclave, &shard_id()));
 	}
 
+	#[test]
+	fn shielding_call_encrypted_with_retired_shielding_key_can_be_added_to_pool() {
+		let _ = env_logger::builder().is_test(true).try_init();
+
+		let (indirect_calls_executor, top_pool_author, _) =
+			test_fixtures([33u8; 32], NodeMetadataMock::new());
+		let retired_key = ShieldingCryptoMock::default();
+		let indirect_calls_executor = indirect_calls_executor.with_retired_shielding_key(Arc::new(
+			RetiredKeyRepositoryMock::new(retired_key.clone(), u64::MAX),
+		));
+
+		let opaque_extrinsic = OpaqueExtrinsic::from_bytes(
+			shield_funds_unchecked_extrinsic(&retired_key).encode().as_slice(),
+		)
+		.unwrap();
+
+		let parentchain_block = ParentchainBlockBuilder::default()
+			.with_extrinsics(vec![opaque_extrinsic])
+			.build();
+
+		indirect_calls_executor
+			.execute_indirect_calls_in_extrinsics(&parentchain_block, &Vec::new())
+			.unwrap();
+
+		assert_eq!(1, top_pool_author.pending_tops(shard_id()).unwrap().len());
+	}
+
 	#[test]
 	fn ensure_empty_extrinsic_vec_triggers_zero_filled_merkle_root() {
 		// given
@@ -49,4 +49,4 @@ pub mod filter_metadata;
 
 pub use error::{Error, Result};
 pub use executor::IndirectCallsExecutor;
-pub use traits::{ExecuteIndirectCalls, IndirectDispatch};
+pub use traits::{ExecuteIndirectCalls, GetParentchainId, IndirectDispatch};
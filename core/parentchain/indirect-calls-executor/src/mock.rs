@@ -14,7 +14,10 @@ use itp_sgx_runtime_primitives::types::{AccountId, Balance};
 use itp_stf_primitives::{traits::IndirectExecutor, types::Signature};
 use itp_test::mock::stf_mock::{GetterMock, TrustedCallMock, TrustedCallSignedMock};
 use itp_types::{
-	parentchain::{BalanceTransfer, ExtrinsicStatus, FilterEvents, HandleParentchainEvents},
+	parentchain::{
+		AssetTransfer, BalanceTransfer, ExtrinsicStatus, FilterEvents, HandleParentchainEvents,
+		RawEvent,
+	},
 	Address, Request, ShardIdentifier, H256,
 };
 use log::*;
@@ -175,6 +178,25 @@ impl FilterEvents for MockEvents {
 		};
 		Ok(Vec::from([transfer]))
 	}
+
+	fn get_asset_transfer_events(&self) -> core::result::Result<Vec<AssetTransfer>, Self::Error> {
+		let transfer = AssetTransfer {
+			asset_id: Default::default(),
+			to: [0u8; 32].into(),
+			from: [0u8; 32].into(),
+			amount: Balance::default(),
+		};
+		Ok(Vec::from([transfer]))
+	}
+
+	fn get_raw_events(&self) -> core::result::Result<Vec<RawEvent>, Self::Error> {
+		let transfer = BalanceTransfer {
+			to: [0u8; 32].into(),
+			from: [0u8; 32].into(),
+			amount: Balance::default(),
+		};
+		Ok(Vec::from([RawEvent::new(&transfer)]))
+	}
 }
 
 pub struct MockParentchainEventHandler {}
@@ -19,7 +19,7 @@ use crate::{error::Result, Error};
 use codec::{Decode, Encode};
 use core::fmt::Debug;
 use itp_stf_primitives::traits::{IndirectExecutor, TrustedCallVerification};
-use itp_types::{OpaqueCall, H256};
+use itp_types::{parentchain::ParentchainId, OpaqueCall, H256};
 use sp_runtime::traits::{Block as ParentchainBlockTrait, Header};
 use std::vec::Vec;
 
@@ -56,3 +56,8 @@ where
 {
 	fn dispatch(&self, executor: &E) -> Result<()>;
 }
+
+/// Trait to get the parentchain whose blocks an indirect executor processes.
+pub trait GetParentchainId {
+	fn parentchain_id(&self) -> ParentchainId;
+}
@@ -49,6 +49,12 @@ pub enum Error {
 	InvalidFinalityProof(#[from] JustificationError),
 	#[error("Header ancestry mismatch")]
 	HeaderAncestryMismatch,
+	#[error("Block has already been imported")]
+	BlockAlreadyImported,
+	#[error("Gap of {0} missing headers is too large to catch up")]
+	CatchUpGapTooLarge(u64),
+	#[error("Parentchain data unavailable: {0}")]
+	ParentchainDataUnavailable(String),
 	#[error("Poisoned validator lock")]
 	PoisonedLock,
 	#[error("No Justification found")]
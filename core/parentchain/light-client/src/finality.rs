@@ -80,8 +80,6 @@ where
 		justifications: Option<Justifications>,
 		relay: &mut RelayState<Block>,
 	) -> Result<()> {
-		Self::apply_validator_set_change(relay, &header);
-
 		// Check that the header has been finalized
 		let voter_set =
 			VoterSet::new(validator_set.clone().into_iter()).expect("VoterSet may not be empty");
@@ -105,15 +103,15 @@ where
 					// FIXME: Printing error upon invalid justification, but this will need a better fix
 					// see issue #353
 					error!("Block {:?} contained invalid justification: {:?}", block_num, err);
-					relay.unjustified_headers.push(block_hash);
-					relay.set_last_finalized_block_header(header);
 					return Err(err)
 				}
 				Self::schedule_validator_set_change(relay, &header);
+				Self::apply_validator_set_change(relay, &header);
 
 				Ok(())
 			},
 			None => {
+				Self::apply_validator_set_change(relay, &header);
 				relay.unjustified_headers.push(block_hash);
 				relay.set_last_finalized_block_header(header);
 
@@ -132,11 +130,16 @@ impl GrandpaFinality {
 		relay: &mut RelayState<Block>,
 		header: &Block::Header,
 	) {
-		if let Some(change) = relay.scheduled_change.take() {
-			if &change.at_block == header.number() {
-				relay.current_validator_set = change.next_authority_list;
-				relay.current_validator_set_id += 1;
-			}
+		// The enactment block is still finalized by the old set, the new set takes over afterwards.
+		// Keep the change around until then, as it may be scheduled with a delay.
+		if relay
+			.scheduled_change
+			.as_ref()
+			.map_or(false, |change| &change.at_block <= header.number())
+		{
+			let change = relay.scheduled_change.take().expect("Checked above that it is some; qed");
+			relay.current_validator_set = change.next_authority_list;
+			relay.current_validator_set_id += 1;
 		}
 	}
 
@@ -235,7 +235,7 @@ pub fn read_or_init_grandpa_validator<B, OCallApi, LightClientSeal>(
 	parentchain_id: ParentchainId,
 ) -> Result<LightValidation<B, OCallApi>>
 where
-	B: Block,
+	B: Block<Hash = Hash>,
 	NumberFor<B>: finality_grandpa::BlockNumberOps,
 	OCallApi: EnclaveOnChainOCallApi,
 	LightClientSeal:
@@ -295,7 +295,7 @@ pub fn read_or_init_parachain_validator<B, OCallApi, LightClientSeal>(
 	parentchain_id: ParentchainId,
 ) -> Result<LightValidation<B, OCallApi>>
 where
-	B: Block,
+	B: Block<Hash = Hash>,
 	NumberFor<B>: finality_grandpa::BlockNumberOps,
 	OCallApi: EnclaveOnChainOCallApi,
 	LightClientSeal: LightClientSealing<LightClientState = LightValidationState<B>>,
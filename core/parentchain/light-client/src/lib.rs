@@ -74,6 +74,15 @@ where
 {
 	fn submit_block(&mut self, signed_block: &SignedBlock<Block>) -> Result<(), Error>;
 
+	/// Fetches the blocks between the last finalized header and `signed_block` from the
+	/// parentchain, together with their raw events, ordered by ascending block number.
+	///
+	/// They have to be imported before `signed_block` can be submitted.
+	fn fetch_missing_ancestors(
+		&self,
+		signed_block: &SignedBlock<Block>,
+	) -> Result<Vec<(SignedBlock<Block>, Vec<u8>)>, Error>;
+
 	fn get_state(&self) -> &LightValidationState<Block>;
 }
 
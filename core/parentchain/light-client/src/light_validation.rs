@@ -18,22 +18,34 @@
 //! Light-client validation crate that verifies parentchain blocks.
 
 use crate::{
-	error::Error, finality::Finality, light_validation_state::LightValidationState,
-	AuthorityListRef, ExtrinsicSender, HashFor, HashingFor, LightClientState, NumberFor, Validator,
+	error::Error, finality::Finality, grandpa_log, light_validation_state::LightValidationState,
+	state::ScheduledChangeAtBlock, AuthorityList, AuthorityListRef, ExtrinsicSender, HashFor,
+	HashingFor, LightClientState, NumberFor, SetId, Validator,
 };
-use codec::Encode;
+use codec::{Decode, Encode};
 use core::iter::Iterator;
 use itp_ocall_api::EnclaveOnChainOCallApi;
-use itp_storage::{Error as StorageError, StorageProof, StorageProofChecker};
-use itp_types::parentchain::{Hash, IdentifyParentchain, ParentchainId};
-use log::error;
+use itp_storage::{storage_value_key, Error as StorageError, StorageProof, StorageProofChecker};
+use itp_types::{
+	parentchain::{Hash, IdentifyParentchain, ParentchainId},
+	WorkerRequest, WorkerResponse,
+};
+use log::*;
+use sp_consensus_grandpa::GRANDPA_AUTHORITIES_KEY;
 use sp_runtime::{
 	generic::SignedBlock,
 	traits::{Block as ParentchainBlockTrait, Block as BlockT, Header as HeaderTrait},
-	Justifications, OpaqueExtrinsic,
+	Justifications, OpaqueExtrinsic, SaturatedConversion,
 };
 use std::{boxed::Box, fmt, sync::Arc, vec::Vec};
 
+/// Maximum number of missing blocks that are fetched from the parentchain
+/// to close a gap between the last finalized header and a submitted block.
+pub const MAX_CATCH_UP_BLOCKS: u64 = 1000;
+
+/// Version prefix of the authority list stored under `GRANDPA_AUTHORITIES_KEY`.
+const AUTHORITIES_VERSION: u8 = 1;
+
 #[derive(Clone)]
 pub struct LightValidation<Block: ParentchainBlockTrait, OcallApi> {
 	light_validation_state: LightValidationState<Block>,
@@ -91,6 +103,21 @@ impl<Block: ParentchainBlockTrait, OcallApi: EnclaveOnChainOCallApi>
 		}
 	}
 
+	fn is_imported(&self, hash: &HashFor<Block>) -> bool {
+		let relay = self.light_validation_state.get_relay();
+		relay.genesis_hash == *hash
+			|| relay.last_finalized_block_header.hash() == *hash
+			|| relay.header_hashes().contains(hash)
+			|| relay.unjustified_headers.contains(hash)
+	}
+}
+
+impl<Block, OcallApi> LightValidation<Block, OcallApi>
+where
+	NumberFor<Block>: finality_grandpa::BlockNumberOps,
+	Block: ParentchainBlockTrait<Hash = Hash>,
+	OcallApi: EnclaveOnChainOCallApi,
+{
 	fn submit_finalized_headers(
 		&mut self,
 		header: Block::Header,
@@ -106,19 +133,34 @@ impl<Block: ParentchainBlockTrait, OcallApi: EnclaveOnChainOCallApi>
 		let last_header = &relay.last_finalized_block_header;
 		Self::verify_ancestry(ancestry_proof, last_header.hash(), &header)?;
 
-		if let Err(e) = self.finality.validate(
+		let validation = match self.finality.validate(
 			header.clone(),
 			&validator_set,
 			validator_set_id,
-			justifications,
+			justifications.clone(),
 			relay,
 		) {
+			Err(Error::InvalidFinalityProof(e)) => {
+				warn!(
+					"[{:?}] Invalid justification for block nr {:?}: {:?}. Trying to recover the authority set",
+					self.parentchain_id,
+					header.number(),
+					e
+				);
+				self.validate_with_recovered_authority_set(&header, justifications)
+			},
+			validation => validation,
+		};
+
+		if let Err(e) = validation {
 			match e {
 				Error::NoJustificationFound => return Ok(()),
 				_ => return Err(e),
 			}
 		}
 
+		let relay = self.light_validation_state.get_relay_mut();
+
 		// Todo: Justifying the headers here is actually wrong, but it prevents an ever-growing
 		// `unjustified_headers` queue because in the parachain case we won't have justifications,
 		// and in solo chain setups we only get a justification upon an Grandpa authority change.
@@ -135,19 +177,185 @@ impl<Block: ParentchainBlockTrait, OcallApi: EnclaveOnChainOCallApi>
 
 		Ok(())
 	}
+
+	/// Fetches the signed block with the given hash and its raw events from the parentchain.
+	fn fetch_block(&self, hash: Hash) -> Result<(SignedBlock<Block>, Vec<u8>), Error> {
+		let response = self
+			.ocall_api
+			.worker_request::<Block::Header, Vec<u8>>(
+				vec![WorkerRequest::ChainBlock(hash)],
+				&self.parentchain_id,
+			)
+			.map_err(|e| Error::ParentchainDataUnavailable(format!("block {:?}: {}", hash, e)))?;
+
+		let (encoded_block, raw_events) = match response.into_iter().next() {
+			Some(WorkerResponse::ChainBlock(Some(block))) => block,
+			_ => return Err(Error::ParentchainDataUnavailable(format!("block {:?}", hash))),
+		};
+		let signed_block = SignedBlock::<Block>::decode(&mut encoded_block.as_slice())
+			.map_err(|e| Error::ParentchainDataUnavailable(format!("block {:?}: {}", hash, e)))?;
+
+		// The hash commits to the header, so we don't have to trust the ocall for it. The
+		// extrinsics and events are as trustworthy as the ones of any other imported block.
+		if signed_block.block.header().hash() != hash {
+			return Err(Error::ParentchainDataUnavailable(format!("block {:?}", hash)))
+		}
+		Ok((signed_block, raw_events))
+	}
+
+	fn fetch_justifications(&self, hash: Hash) -> Result<Option<Justifications>, Error> {
+		let response = self
+			.ocall_api
+			.worker_request::<Block::Header, Vec<u8>>(
+				vec![WorkerRequest::ChainBlockJustifications(hash)],
+				&self.parentchain_id,
+			)
+			.map_err(|e| {
+				Error::ParentchainDataUnavailable(format!("justifications {:?}: {}", hash, e))
+			})?;
+
+		match response.into_iter().next() {
+			Some(WorkerResponse::ChainBlockJustifications(justifications)) => Ok(justifications),
+			_ => Err(Error::ParentchainDataUnavailable(format!("justifications {:?}", hash))),
+		}
+	}
+
+	/// Returns the given justifications, or fetches them from the parentchain if the header
+	/// announces an authority set change, which we can't follow without a justification.
+	fn justifications_for(
+		&self,
+		header: &Block::Header,
+		justifications: Option<Justifications>,
+	) -> Option<Justifications> {
+		if justifications.is_some() || grandpa_log::<Block>(header.digest()).is_none() {
+			return justifications
+		}
+
+		debug!(
+			"[{:?}] Block nr {:?} announces an authority set change, fetching its justifications",
+			self.parentchain_id,
+			header.number()
+		);
+		self.fetch_justifications(header.hash()).unwrap_or_else(|e| {
+			warn!("[{:?}] Could not fetch justifications: {:?}", self.parentchain_id, e);
+			None
+		})
+	}
+
+	/// Reads the grandpa authority set that is active in the state of `header`
+	/// with verified storage proofs.
+	fn fetch_authority_set(
+		&self,
+		header: &Block::Header,
+	) -> Result<RecoveredAuthoritySet<Block>, Error> {
+		let storage_error = |e: itp_ocall_api::Error| {
+			Error::ParentchainDataUnavailable(format!("authority set: {}", e))
+		};
+
+		let (version, authorities): (u8, AuthorityList) = self
+			.ocall_api
+			.get_storage_verified(GRANDPA_AUTHORITIES_KEY.to_vec(), header, &self.parentchain_id)
+			.map_err(storage_error)?
+			.into_tuple()
+			.1
+			.ok_or(StorageError::StorageValueUnavailable)?;
+		if version != AUTHORITIES_VERSION {
+			return Err(Error::ValidatorSetMismatch)
+		}
+
+		let set_id: SetId = self
+			.ocall_api
+			.get_storage_verified(
+				storage_value_key("Grandpa", "CurrentSetId"),
+				header,
+				&self.parentchain_id,
+			)
+			.map_err(storage_error)?
+			.into_tuple()
+			.1
+			.unwrap_or_default();
+
+		// Prefix of the pallet's `StoredPendingChange`: (scheduled_at, delay, next_authorities).
+		let pending_change: Option<(NumberFor<Block>, NumberFor<Block>, AuthorityList)> = self
+			.ocall_api
+			.get_storage_verified(
+				storage_value_key("Grandpa", "PendingChange"),
+				header,
+				&self.parentchain_id,
+			)
+			.map_err(storage_error)?
+			.into_tuple()
+			.1;
+
+		// The set id is already incremented when a change is scheduled, whereas
+		// the authorities are only replaced once the change is enacted.
+		Ok(match pending_change {
+			Some((scheduled_at, delay, next_authority_list)) => RecoveredAuthoritySet {
+				authorities,
+				set_id: set_id.saturating_sub(1),
+				scheduled_change: Some(ScheduledChangeAtBlock {
+					at_block: scheduled_at + delay,
+					next_authority_list,
+				}),
+			},
+			None => RecoveredAuthoritySet { authorities, set_id, scheduled_change: None },
+		})
+	}
+
+	/// Validates `header` against the authority set found in the state of the last finalized
+	/// header, which is its parent. This catches up with authority set changes we have missed.
+	fn validate_with_recovered_authority_set(
+		&mut self,
+		header: &Block::Header,
+		justifications: Option<Justifications>,
+	) -> Result<(), Error> {
+		let parent = self.light_validation_state.get_relay().last_finalized_block_header.clone();
+		let recovered = self.fetch_authority_set(&parent)?;
+
+		let mut relay = self.light_validation_state.get_relay().clone();
+		relay.current_validator_set = recovered.authorities.clone();
+		relay.current_validator_set_id = recovered.set_id;
+		relay.scheduled_change = recovered.scheduled_change;
+
+		self.finality.validate(
+			header.clone(),
+			&recovered.authorities,
+			recovered.set_id,
+			justifications,
+			&mut relay,
+		)?;
+
+		info!(
+			"[{:?}] Recovered authority set with id {} at block nr {:?}",
+			self.parentchain_id,
+			recovered.set_id,
+			parent.number()
+		);
+		*self.light_validation_state.get_relay_mut() = relay;
+		Ok(())
+	}
+}
+
+struct RecoveredAuthoritySet<Block: ParentchainBlockTrait> {
+	authorities: AuthorityList,
+	set_id: SetId,
+	scheduled_change: Option<ScheduledChangeAtBlock<Block::Header>>,
 }
 
 impl<Block, OCallApi> Validator<Block> for LightValidation<Block, OCallApi>
 where
 	NumberFor<Block>: finality_grandpa::BlockNumberOps,
-	Block: ParentchainBlockTrait,
+	Block: ParentchainBlockTrait<Hash = Hash>,
 	OCallApi: EnclaveOnChainOCallApi,
 {
 	fn submit_block(&mut self, signed_block: &SignedBlock<Block>) -> Result<(), Error> {
 		let header = signed_block.block.header();
-		let justifications = signed_block.justifications.clone();
 
-		let relay = self.light_validation_state.get_relay_mut();
+		if self.is_imported(&header.hash()) {
+			return Err(Error::BlockAlreadyImported)
+		}
+
+		let relay = self.light_validation_state.get_relay();
 
 		if relay.last_finalized_block_header.hash() != *header.parent_hash() {
 			error!("header ancestry mismatch! last imported was block nr {:?} with hash {:?}, attempting to import nr {:?} with hash {:?} and ancestor {:?}",
@@ -160,9 +368,55 @@ where
 			return Err(Error::HeaderAncestryMismatch)
 		}
 
+		let justifications = self.justifications_for(header, signed_block.justifications.clone());
 		self.submit_finalized_headers(header.clone(), vec![], justifications)
 	}
 
+	fn fetch_missing_ancestors(
+		&self,
+		signed_block: &SignedBlock<Block>,
+	) -> Result<Vec<(SignedBlock<Block>, Vec<u8>)>, Error> {
+		let header = signed_block.block.header();
+		let last_finalized = &self.light_validation_state.get_relay().last_finalized_block_header;
+
+		// Blocks that are imported already or don't lie ahead of us are rejected by `submit_block`.
+		if *header.parent_hash() == last_finalized.hash()
+			|| header.number() <= last_finalized.number()
+			|| self.is_imported(&header.hash())
+		{
+			return Ok(Vec::new())
+		}
+
+		let gap = (*header.number() - *last_finalized.number())
+			.saturated_into::<u64>()
+			.saturating_sub(1);
+		if gap > MAX_CATCH_UP_BLOCKS {
+			return Err(Error::CatchUpGapTooLarge(gap))
+		}
+
+		let last_finalized_hash = last_finalized.hash();
+		let mut ancestors = Vec::new();
+		let mut next_hash = *header.parent_hash();
+		while next_hash != last_finalized_hash {
+			if ancestors.len() as u64 >= gap {
+				// We walked past the last finalized block number, so `header` is on another fork.
+				return Err(Error::HeaderAncestryMismatch)
+			}
+			let (ancestor, raw_events) = self.fetch_block(next_hash)?;
+			next_hash = *ancestor.block.header().parent_hash();
+			ancestors.push((ancestor, raw_events));
+		}
+		ancestors.reverse();
+
+		info!(
+			"[{:?}] Fetched {} missing blocks before block nr {:?}",
+			self.parentchain_id,
+			ancestors.len(),
+			header.number()
+		);
+		Ok(ancestors)
+	}
+
 	fn get_state(&self) -> &LightValidationState<Block> {
 		&self.light_validation_state
 	}
@@ -224,7 +478,7 @@ pub fn check_validator_set_proof<Block: ParentchainBlockTrait>(
 	// By encoding the given set we should have an easy way to compare
 	// with the stuff we get out of storage via `read_value`
 	let mut encoded_validator_set = validator_set.encode();
-	encoded_validator_set.insert(0, 1); // Add AUTHORITIES_VERISON == 1
+	encoded_validator_set.insert(0, AUTHORITIES_VERSION);
 	let actual_validator_set = checker
 		.read_value(b":grandpa_authorities")?
 		.ok_or(StorageError::StorageValueUnavailable)?;
@@ -235,3 +489,355 @@ pub fn check_validator_set_proof<Block: ParentchainBlockTrait>(
 		Err(Error::ValidatorSetMismatch)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{finality::GrandpaFinality, state::RelayState};
+	use finality_grandpa::{Commit, Message, Precommit, SignedPrecommit};
+	use itc_parentchain_test::{ParentchainBlockBuilder, ParentchainHeaderBuilder};
+	use itp_test::mock::onchain_mock::OnchainMock;
+	use itp_types::{Block, Header};
+	use sp_consensus_grandpa::{
+		localized_payload, ConsensusLog, ScheduledChange, GRANDPA_ENGINE_ID,
+	};
+	use sp_keyring::Ed25519Keyring;
+	use sp_runtime::{
+		generic::{Digest, DigestItem},
+		OpaqueExtrinsic,
+	};
+
+	type TestValidator = LightValidation<Block, OnchainMock>;
+
+	fn authorities(keys: &[Ed25519Keyring]) -> AuthorityList {
+		keys.iter().map(|key| (key.public().into(), 1)).collect()
+	}
+
+	fn scheduled_change_digest(next_authorities: &[Ed25519Keyring]) -> Digest {
+		let change =
+			ScheduledChange { next_authorities: authorities(next_authorities), delay: 0u32 };
+		Digest {
+			logs: vec![DigestItem::Consensus(
+				GRANDPA_ENGINE_ID,
+				ConsensusLog::ScheduledChange(change).encode(),
+			)],
+		}
+	}
+
+	/// Records a chain of headers on top of `parent`, one for each given digest.
+	fn headers_on_top_of(parent: &Header, digests: Vec<Digest>) -> Vec<Header> {
+		let mut parent = parent.clone();
+		digests
+			.into_iter()
+			.map(|digest| {
+				let header = ParentchainHeaderBuilder::default()
+					.with_number(parent.number + 1)
+					.with_parent_hash(parent.hash())
+					.with_digest(digest)
+					.build();
+				parent = header.clone();
+				header
+			})
+			.collect()
+	}
+
+	fn justification(header: &Header, set_id: SetId, signers: &[Ed25519Keyring]) -> Justifications {
+		let round = 1u64;
+		let precommit = Precommit { target_hash: header.hash(), target_number: header.number };
+		let precommits = signers
+			.iter()
+			.map(|signer| {
+				let message = Message::Precommit(precommit.clone());
+				let payload = localized_payload(round, set_id, &message);
+				SignedPrecommit {
+					precommit: precommit.clone(),
+					signature: signer.sign(&payload).into(),
+					id: signer.public().into(),
+				}
+			})
+			.collect();
+		let commit =
+			Commit { target_hash: header.hash(), target_number: header.number, precommits };
+
+		// Same encoding as `GrandpaJustification { round, commit, votes_ancestries }`.
+		Justifications::from((GRANDPA_ENGINE_ID, (round, commit, Vec::<Header>::new()).encode()))
+	}
+
+	fn signed_block(header: &Header, justifications: Option<Justifications>) -> SignedBlock<Block> {
+		let builder =
+			ParentchainBlockBuilder::<OpaqueExtrinsic>::default().with_header(header.clone());
+		match justifications {
+			Some(justifications) => builder.with_justifications(justifications).build_signed(),
+			None => builder.build_signed(),
+		}
+	}
+
+	/// Imports the missing ancestors of `signed_block` before the block itself,
+	/// like the parentchain block importer does.
+	fn import_with_catch_up(
+		validator: &mut TestValidator,
+		signed_block: &SignedBlock<Block>,
+	) -> Result<(), Error> {
+		for (ancestor, _raw_events) in validator.fetch_missing_ancestors(signed_block)? {
+			validator.submit_block(&ancestor)?;
+		}
+		validator.submit_block(signed_block)
+	}
+
+	fn grandpa_validator(ocall_api: OnchainMock, genesis: &Header) -> TestValidator {
+		LightValidation::new(
+			Arc::new(ocall_api),
+			Arc::new(Box::new(GrandpaFinality)),
+			RelayState::new(genesis.clone(), authorities(&[Ed25519Keyring::Alice])).into(),
+			ParentchainId::Integritee,
+		)
+	}
+
+	fn genesis() -> Header {
+		ParentchainHeaderBuilder::default().build()
+	}
+
+	#[test]
+	fn submit_block_imports_linear_chain() {
+		let genesis = genesis();
+		let headers = headers_on_top_of(&genesis, vec![Digest::default(); 3]);
+		let mut validator = grandpa_validator(OnchainMock::default(), &genesis);
+
+		for header in headers.iter() {
+			validator.submit_block(&signed_block(header, None)).unwrap();
+		}
+
+		assert_eq!(validator.latest_finalized_header().unwrap(), headers[2]);
+	}
+
+	#[test]
+	fn submit_block_rejects_already_imported_block() {
+		let genesis = genesis();
+		let headers = headers_on_top_of(&genesis, vec![Digest::default(); 2]);
+		let mut validator = grandpa_validator(OnchainMock::default(), &genesis);
+
+		for header in headers.iter() {
+			validator.submit_block(&signed_block(header, None)).unwrap();
+		}
+
+		assert!(matches!(
+			validator.submit_block(&signed_block(&headers[0], None)),
+			Err(Error::BlockAlreadyImported)
+		));
+		assert_eq!(validator.latest_finalized_header().unwrap(), headers[1]);
+	}
+
+	#[test]
+	fn submit_block_rejects_fork_below_last_finalized_block() {
+		let genesis = genesis();
+		let headers = headers_on_top_of(&genesis, vec![Digest::default(); 2]);
+		let fork = ParentchainHeaderBuilder::default()
+			.with_number(2)
+			.with_parent_hash(headers[0].hash())
+			.with_state_root([1u8; 32].into())
+			.build();
+		let mut validator = grandpa_validator(OnchainMock::default(), &genesis);
+
+		for header in headers.iter() {
+			validator.submit_block(&signed_block(header, None)).unwrap();
+		}
+
+		assert!(matches!(
+			validator.submit_block(&signed_block(&fork, None)),
+			Err(Error::HeaderAncestryMismatch)
+		));
+		assert_eq!(validator.latest_finalized_header().unwrap(), headers[1]);
+	}
+
+	#[test]
+	fn submit_block_rejects_block_with_missing_ancestors() {
+		let genesis = genesis();
+		let headers = headers_on_top_of(&genesis, vec![Digest::default(); 3]);
+		let mut validator = grandpa_validator(OnchainMock::default(), &genesis);
+
+		assert!(matches!(
+			validator.submit_block(&signed_block(&headers[2], None)),
+			Err(Error::HeaderAncestryMismatch)
+		));
+		assert_eq!(validator.latest_finalized_header().unwrap(), genesis);
+	}
+
+	#[test]
+	fn fetch_missing_ancestors_returns_blocks_with_events() {
+		let genesis = genesis();
+		let headers = headers_on_top_of(&genesis, vec![Digest::default(); 5]);
+		let ocall_api = headers[..4].iter().enumerate().fold(
+			OnchainMock::default(),
+			|ocall_api, (i, header)| {
+				ocall_api.with_block(&signed_block(header, None), vec![i as u8])
+			},
+		);
+		let mut validator = grandpa_validator(ocall_api, &genesis);
+
+		let ancestors =
+			validator.fetch_missing_ancestors(&signed_block(&headers[4], None)).unwrap();
+
+		assert_eq!(
+			ancestors,
+			headers[..4]
+				.iter()
+				.enumerate()
+				.map(|(i, header)| (signed_block(header, None), vec![i as u8]))
+				.collect::<Vec<_>>()
+		);
+		import_with_catch_up(&mut validator, &signed_block(&headers[4], None)).unwrap();
+		assert_eq!(validator.latest_finalized_header().unwrap(), headers[4]);
+		assert_eq!(validator.penultimate_finalized_block_header().unwrap(), headers[3]);
+		assert!(headers.iter().all(|header| validator.is_imported(&header.hash())));
+	}
+
+	#[test]
+	fn fetch_missing_ancestors_returns_nothing_for_next_or_imported_block() {
+		let genesis = genesis();
+		let headers = headers_on_top_of(&genesis, vec![Digest::default(); 2]);
+		let mut validator = grandpa_validator(OnchainMock::default(), &genesis);
+		validator.submit_block(&signed_block(&headers[0], None)).unwrap();
+
+		assert!(validator
+			.fetch_missing_ancestors(&signed_block(&headers[0], None))
+			.unwrap()
+			.is_empty());
+		assert!(validator
+			.fetch_missing_ancestors(&signed_block(&headers[1], None))
+			.unwrap()
+			.is_empty());
+	}
+
+	#[test]
+	fn fetch_missing_ancestors_fails_if_blocks_are_unavailable() {
+		let genesis = genesis();
+		let headers = headers_on_top_of(&genesis, vec![Digest::default(); 3]);
+		let validator = grandpa_validator(OnchainMock::default(), &genesis);
+
+		assert!(matches!(
+			validator.fetch_missing_ancestors(&signed_block(&headers[2], None)),
+			Err(Error::ParentchainDataUnavailable(_))
+		));
+		assert_eq!(validator.latest_finalized_header().unwrap(), genesis);
+	}
+
+	#[test]
+	fn submit_block_fetches_missing_justification_of_authority_set_change() {
+		let genesis = genesis();
+		let headers = headers_on_top_of(
+			&genesis,
+			vec![scheduled_change_digest(&[Ed25519Keyring::Bob]), Digest::default()],
+		);
+		let ocall_api = OnchainMock::default().with_justifications(
+			headers[0].hash(),
+			justification(&headers[0], 0, &[Ed25519Keyring::Alice]),
+		);
+		let mut validator = grandpa_validator(ocall_api, &genesis);
+
+		validator.submit_block(&signed_block(&headers[0], None)).unwrap();
+		validator
+			.submit_block(&signed_block(
+				&headers[1],
+				Some(justification(&headers[1], 1, &[Ed25519Keyring::Bob])),
+			))
+			.unwrap();
+
+		let relay = validator.get_state().get_relay();
+		assert_eq!(relay.last_finalized_block_header, headers[1]);
+		assert_eq!(relay.current_validator_set, authorities(&[Ed25519Keyring::Bob]));
+		assert_eq!(relay.current_validator_set_id, 1);
+		assert!(relay.unjustified_headers.is_empty());
+	}
+
+	#[test]
+	fn submit_block_catches_up_across_authority_set_change() {
+		let genesis = genesis();
+		let headers = headers_on_top_of(
+			&genesis,
+			vec![
+				Digest::default(),
+				scheduled_change_digest(&[Ed25519Keyring::Bob]),
+				Digest::default(),
+				Digest::default(),
+			],
+		);
+		let ocall_api = OnchainMock::default()
+			.with_block(&signed_block(&headers[0], None), vec![])
+			.with_block(
+				&signed_block(
+					&headers[1],
+					Some(justification(&headers[1], 0, &[Ed25519Keyring::Alice])),
+				),
+				vec![],
+			)
+			.with_block(&signed_block(&headers[2], None), vec![]);
+		let mut validator = grandpa_validator(ocall_api, &genesis);
+
+		import_with_catch_up(
+			&mut validator,
+			&signed_block(&headers[3], Some(justification(&headers[3], 1, &[Ed25519Keyring::Bob]))),
+		)
+		.unwrap();
+
+		let relay = validator.get_state().get_relay();
+		assert_eq!(relay.last_finalized_block_header, headers[3]);
+		assert_eq!(relay.current_validator_set, authorities(&[Ed25519Keyring::Bob]));
+		assert_eq!(relay.current_validator_set_id, 1);
+		assert!(relay.unjustified_headers.is_empty());
+	}
+
+	#[test]
+	fn submit_block_recovers_missed_authority_set_change_from_state() {
+		let genesis = genesis();
+		let headers = headers_on_top_of(
+			&genesis,
+			vec![scheduled_change_digest(&[Ed25519Keyring::Bob]), Digest::default()],
+		);
+		let ocall_api = OnchainMock::default().with_storage_entries_at_header(
+			&headers[0],
+			vec![
+				(
+					GRANDPA_AUTHORITIES_KEY.to_vec(),
+					(AUTHORITIES_VERSION, authorities(&[Ed25519Keyring::Bob])).encode(),
+				),
+				(storage_value_key("Grandpa", "CurrentSetId"), 1u64.encode()),
+			],
+		);
+		let mut validator = grandpa_validator(ocall_api, &genesis);
+
+		// The justification announcing the change has been missed.
+		validator.submit_block(&signed_block(&headers[0], None)).unwrap();
+		validator
+			.submit_block(&signed_block(
+				&headers[1],
+				Some(justification(&headers[1], 1, &[Ed25519Keyring::Bob])),
+			))
+			.unwrap();
+
+		let relay = validator.get_state().get_relay();
+		assert_eq!(relay.last_finalized_block_header, headers[1]);
+		assert_eq!(relay.current_validator_set, authorities(&[Ed25519Keyring::Bob]));
+		assert_eq!(relay.current_validator_set_id, 1);
+		assert!(relay.unjustified_headers.is_empty());
+	}
+
+	#[test]
+	fn submit_block_does_not_adopt_block_if_authority_set_cannot_be_recovered() {
+		let genesis = genesis();
+		let headers = headers_on_top_of(&genesis, vec![Digest::default()]);
+		let mut validator = grandpa_validator(OnchainMock::default(), &genesis);
+
+		assert!(validator
+			.submit_block(&signed_block(
+				&headers[0],
+				Some(justification(&headers[0], 1, &[Ed25519Keyring::Bob])),
+			))
+			.is_err());
+
+		let relay = validator.get_state().get_relay();
+		assert_eq!(relay.last_finalized_block_header, genesis);
+		assert!(relay.unjustified_headers.is_empty());
+		assert!(!validator.is_imported(&headers[0].hash()));
+		assert_eq!(relay.current_validator_set_id, 0);
+	}
+}
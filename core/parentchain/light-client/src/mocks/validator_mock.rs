@@ -49,6 +49,13 @@ impl Validator<Block> for ValidatorMock {
 		Ok(())
 	}
 
+	fn fetch_missing_ancestors(
+		&self,
+		_signed_block: &SignedBlock<Block>,
+	) -> Result<Vec<(SignedBlock<Block>, Vec<u8>)>> {
+		Ok(Vec::new())
+	}
+
 	fn get_state(&self) -> &LightValidationState<Block> {
 		&self.light_validation_state
 	}
@@ -25,11 +25,15 @@ use alloc::vec::Vec;
 use sp_runtime::traits::MaybeSerialize;
 
 pub use itp_types::Header;
-pub use sp_runtime::generic::{Block, SignedBlock};
+pub use sp_runtime::{
+	generic::{Block, SignedBlock},
+	Justifications,
+};
 
 pub struct ParentchainBlockBuilder<Extrinsic> {
 	header: Header,
 	extrinsics: Vec<Extrinsic>,
+	justifications: Option<Justifications>,
 }
 
 impl<Extrinsic> Default for ParentchainBlockBuilder<Extrinsic> {
@@ -37,6 +41,7 @@ impl<Extrinsic> Default for ParentchainBlockBuilder<Extrinsic> {
 		ParentchainBlockBuilder {
 			header: ParentchainHeaderBuilder::default().build(),
 			extrinsics: Default::default(),
+			justifications: None,
 		}
 	}
 }
@@ -52,11 +57,17 @@ impl<Extrinsic: MaybeSerialize> ParentchainBlockBuilder<Extrinsic> {
 		self
 	}
 
+	pub fn with_justifications(mut self, justifications: Justifications) -> Self {
+		self.justifications = Some(justifications);
+		self
+	}
+
 	pub fn build(self) -> Block<Header, Extrinsic> {
 		Block { header: self.header, extrinsics: self.extrinsics }
 	}
 
 	pub fn build_signed(self) -> SignedBlock<Block<Header, Extrinsic>> {
-		SignedBlock { block: self.build(), justifications: None }
+		let justifications = self.justifications.clone();
+		SignedBlock { block: self.build(), justifications }
 	}
 }
@@ -41,6 +41,16 @@ impl ParentchainHeaderBuilder {
 		self
 	}
 
+	pub fn with_state_root(mut self, state_root: H256) -> Self {
+		self.state_root = state_root;
+		self
+	}
+
+	pub fn with_digest(mut self, digest: Digest) -> Self {
+		self.digest = digest;
+		self
+	}
+
 	pub fn build(self) -> Header {
 		Header {
 			number: self.number,
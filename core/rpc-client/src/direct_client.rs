@@ -22,6 +22,7 @@ use codec::Decode;
 use frame_metadata::RuntimeMetadataPrefixed;
 use itp_api_client_types::Metadata;
 use itp_rpc::{RpcRequest, RpcResponse, RpcReturnValue};
+use itp_sgx_crypto::X25519PubKey;
 use itp_types::DirectRequestStatus;
 use itp_utils::FromHexPrefixed;
 use log::*;
@@ -48,10 +49,12 @@ pub trait DirectApi {
 	/// Server connection with more than one response.
 	fn watch(&self, request: String, sender: MpscSender<String>) -> JoinHandle<()>;
 	fn get_rsa_pubkey(&self) -> Result<Rsa3072PubKey>;
+	fn get_x25519_pubkey(&self) -> Result<X25519PubKey>;
 	fn get_mu_ra_url(&self) -> Result<String>;
 	fn get_untrusted_worker_url(&self) -> Result<String>;
 	fn get_state_metadata(&self) -> Result<Metadata>;
 	fn import_sidechain_blocks(&self, blocks_encoded: String) -> Result<()>;
+	fn import_finality_votes(&self, votes_encoded: String) -> Result<()>;
 
 	fn send(&self, request: &str) -> Result<()>;
 	/// Close any open websocket connection.
@@ -110,6 +113,23 @@ impl DirectApi for DirectClient {
 		Ok(shielding_pubkey)
 	}
 
+	fn get_x25519_pubkey(&self) -> Result<X25519PubKey> {
+		let jsonrpc_call: String = RpcRequest::compose_jsonrpc_call(
+			"author_getX25519ShieldingKey".to_string(),
+			Default::default(),
+		)?;
+
+		// Send json rpc call to ws server.
+		let response_str = self.get(&jsonrpc_call)?;
+
+		let shielding_pubkey_string: String = decode_from_rpc_response(&response_str)?;
+		let shielding_pubkey = X25519PubKey::from_hex(&shielding_pubkey_string)
+			.map_err(|e| Error::Custom(format!("{:?}", e).into()))?;
+
+		info!("[+] Got X25519 public key of enclave");
+		Ok(shielding_pubkey)
+	}
+
 	fn get_mu_ra_url(&self) -> Result<String> {
 		let jsonrpc_call: String =
 			RpcRequest::compose_jsonrpc_call("author_getMuRaUrl".to_string(), Default::default())?;
@@ -165,6 +185,15 @@ impl DirectApi for DirectClient {
 		Ok(())
 	}
 
+	fn import_finality_votes(&self, finality_votes_encoded: String) -> Result<()> {
+		let jsonrpc_call: String = RpcRequest::compose_jsonrpc_call(
+			"sidechain_importFinalityVotes".to_owned(),
+			vec![finality_votes_encoded],
+		)?;
+		self.get(&jsonrpc_call)?;
+		Ok(())
+	}
+
 	fn send(&self, request: &str) -> Result<()> {
 		self.web_socket_control.send(request)
 	}
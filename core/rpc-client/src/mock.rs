@@ -21,12 +21,14 @@ use crate::{direct_client::DirectApi, error::Result};
 use codec::Decode;
 use frame_metadata::RuntimeMetadataPrefixed;
 use itp_api_client_types::Metadata;
+use itp_sgx_crypto::X25519PubKey;
 use sgx_crypto_helper::rsa3072::Rsa3072PubKey;
 use std::{sync::mpsc::Sender as MpscSender, thread::JoinHandle};
 
 #[derive(Clone, Default)]
 pub struct DirectClientMock {
 	rsa_pubkey: Rsa3072PubKey,
+	x25519_pubkey: X25519PubKey,
 	mu_ra_url: String,
 	untrusted_worker_url: String,
 	metadata: Vec<u8>,
@@ -39,7 +41,13 @@ impl DirectClientMock {
 		untrusted_worker_url: String,
 		metadata: Vec<u8>,
 	) -> Self {
-		Self { rsa_pubkey, mu_ra_url, untrusted_worker_url, metadata }
+		Self {
+			rsa_pubkey,
+			x25519_pubkey: Default::default(),
+			mu_ra_url,
+			untrusted_worker_url,
+			metadata,
+		}
 	}
 
 	pub fn with_rsa_pubkey(mut self, key: Rsa3072PubKey) -> Self {
@@ -47,6 +55,11 @@ impl DirectClientMock {
 		self
 	}
 
+	pub fn with_x25519_pubkey(mut self, key: X25519PubKey) -> Self {
+		self.x25519_pubkey = key;
+		self
+	}
+
 	pub fn with_mu_ra_url(mut self, url: &str) -> Self {
 		self.mu_ra_url = url.to_string();
 		self
@@ -76,6 +89,10 @@ impl DirectApi for DirectClientMock {
 		Ok(self.rsa_pubkey)
 	}
 
+	fn get_x25519_pubkey(&self) -> Result<X25519PubKey> {
+		Ok(self.x25519_pubkey)
+	}
+
 	fn get_mu_ra_url(&self) -> Result<String> {
 		Ok(self.mu_ra_url.clone())
 	}
@@ -93,6 +110,10 @@ impl DirectApi for DirectClientMock {
 		Ok(())
 	}
 
+	fn import_finality_votes(&self, _votes_encoded: String) -> Result<()> {
+		Ok(())
+	}
+
 	fn send(&self, _request: &str) -> Result<()> {
 		unimplemented!()
 	}
@@ -0,0 +1,246 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+//! Untrusted RPC methods to explore the sidechain blocks in storage.
+
+use its_primitives::{
+	traits::{Block as BlockTrait, Header as HeaderTrait, SignedBlock as SignedBlockTrait},
+	types::{BlockHash, BlockNumber, ShardIdentifier, SignedBlock},
+};
+use its_rpc_handler::constants::{
+	RPC_METHOD_NAME_GET_BLOCK_BY_HASH, RPC_METHOD_NAME_GET_BLOCK_BY_NUMBER,
+	RPC_METHOD_NAME_GET_BLOCK_BY_SIGNED_TOP, RPC_METHOD_NAME_GET_FINALIZED_HEAD,
+	RPC_METHOD_NAME_GET_JUSTIFICATION, RPC_METHOD_NAME_GET_LATEST_BLOCKS,
+	RPC_METHOD_NAME_GET_SHARDS, RPC_METHOD_NAME_GET_SHARD_STATISTICS,
+	RPC_METHOD_NAME_SUBSCRIBE_NEW_BLOCKS, RPC_METHOD_NAME_UNSUBSCRIBE_NEW_BLOCKS,
+};
+use its_storage::interface::{QueryBlocks, SubscribeNewBlocks};
+use jsonrpsee::{
+	types::{error::CallError, Error},
+	RpcModule,
+};
+use log::*;
+use sp_core::H256;
+use std::sync::{
+	atomic::{AtomicUsize, Ordering},
+	Arc,
+};
+use tokio::sync::mpsc::{channel, error::TrySendError, Receiver};
+
+/// Max number of blocks returned by a single `sidechain_getLatestBlocks` request.
+pub const MAX_LATEST_BLOCKS: u32 = 100;
+
+/// Max number of concurrent `sidechain_subscribeNewBlocks` subscriptions.
+pub const MAX_NEW_BLOCKS_SUBSCRIPTIONS: usize = 64;
+
+/// Max number of blocks that are buffered for a `sidechain_subscribeNewBlocks` subscription.
+/// A subscriber that falls further behind is dropped.
+pub const NEW_BLOCKS_BUFFER_SIZE: usize = 32;
+
+/// RPC server module builder for exploring the sidechain blocks in storage.
+pub struct BlockExplorerModuleBuilder<QueryBlocksFromStorage> {
+	sidechain_block_query: Arc<QueryBlocksFromStorage>,
+}
+
+impl<QueryBlocksFromStorage> BlockExplorerModuleBuilder<QueryBlocksFromStorage>
+where
+	QueryBlocksFromStorage:
+		QueryBlocks<SignedBlock> + SubscribeNewBlocks<SignedBlock> + Send + Sync + 'static,
+{
+	pub fn new(sidechain_block_query: Arc<QueryBlocksFromStorage>) -> Self {
+		BlockExplorerModuleBuilder { sidechain_block_query }
+	}
+
+	pub fn build(self) -> Result<RpcModule<Arc<QueryBlocksFromStorage>>, Error> {
+		let mut module = RpcModule::new(self.sidechain_block_query);
+
+		module.register_method(RPC_METHOD_NAME_GET_SHARDS, |_, storage| {
+			debug!("{}", RPC_METHOD_NAME_GET_SHARDS);
+			Ok(storage.shards())
+		})?;
+
+		module.register_method(RPC_METHOD_NAME_GET_BLOCK_BY_HASH, |params, storage| {
+			debug!("{}: {:?}", RPC_METHOD_NAME_GET_BLOCK_BY_HASH, params);
+			let block_hash = params.one::<BlockHash>()?;
+			storage.fetch_block(&block_hash).map_err(storage_error)
+		})?;
+
+		module.register_method(RPC_METHOD_NAME_GET_BLOCK_BY_NUMBER, |params, storage| {
+			debug!("{}: {:?}", RPC_METHOD_NAME_GET_BLOCK_BY_NUMBER, params);
+			let (shard, block_number) = params.one::<(ShardIdentifier, BlockNumber)>()?;
+			storage.fetch_block_by_number(&shard, block_number).map_err(storage_error)
+		})?;
+
+		module.register_method(RPC_METHOD_NAME_GET_LATEST_BLOCKS, |params, storage| {
+			debug!("{}: {:?}", RPC_METHOD_NAME_GET_LATEST_BLOCKS, params);
+			let (shard, max) = params.one::<(ShardIdentifier, u32)>()?;
+			storage
+				.fetch_latest_blocks(&shard, max.min(MAX_LATEST_BLOCKS) as usize)
+				.map_err(storage_error)
+		})?;
+
+		module.register_method(RPC_METHOD_NAME_GET_BLOCK_BY_SIGNED_TOP, |params, storage| {
+			debug!("{}: {:?}", RPC_METHOD_NAME_GET_BLOCK_BY_SIGNED_TOP, params);
+			let top_hash = params.one::<H256>()?;
+			storage.fetch_block_by_signed_top(&top_hash).map_err(storage_error)
+		})?;
+
+		module.register_method(RPC_METHOD_NAME_GET_SHARD_STATISTICS, |params, storage| {
+			debug!("{}: {:?}", RPC_METHOD_NAME_GET_SHARD_STATISTICS, params);
+			let shard = params.one::<ShardIdentifier>()?;
+			Ok(storage.shard_statistics(&shard))
+		})?;
+
+		module.register_method(RPC_METHOD_NAME_GET_JUSTIFICATION, |params, storage| {
+			debug!("{}: {:?}", RPC_METHOD_NAME_GET_JUSTIFICATION, params);
+			let block_hash = params.one::<BlockHash>()?;
+			storage.fetch_justification(&block_hash).map_err(storage_error)
+		})?;
+
+		module.register_method(RPC_METHOD_NAME_GET_FINALIZED_HEAD, |params, storage| {
+			debug!("{}: {:?}", RPC_METHOD_NAME_GET_FINALIZED_HEAD, params);
+			let shard = params.one::<ShardIdentifier>()?;
+			storage.fetch_finalized_head(&shard).map_err(storage_error)
+		})?;
+
+		let subscriptions = Arc::new(AtomicUsize::new(0));
+		module.register_subscription(
+			RPC_METHOD_NAME_SUBSCRIBE_NEW_BLOCKS,
+			RPC_METHOD_NAME_UNSUBSCRIBE_NEW_BLOCKS,
+			move |params, mut sink, storage| {
+				debug!("{}: {:?}", RPC_METHOD_NAME_SUBSCRIBE_NEW_BLOCKS, params);
+				let shard = params.one::<ShardIdentifier>().map_err(Error::Call)?;
+				if subscriptions.fetch_add(1, Ordering::SeqCst) >= MAX_NEW_BLOCKS_SUBSCRIPTIONS {
+					subscriptions.fetch_sub(1, Ordering::SeqCst);
+					return Err(Error::Call(CallError::Failed(
+						"Too many new sidechain blocks subscriptions".to_string().into(),
+					)))
+				}
+				let mut new_blocks = new_blocks_receiver(storage.as_ref(), shard);
+				let subscriptions = subscriptions.clone();
+				// Ends once the subscriber is gone, which drops the receiver and thereby
+				// removes the callback from the storage with the next stored block, or once
+				// the subscriber has fallen behind and the storage dropped the sender.
+				tokio::spawn(async move {
+					while let Some(block) = new_blocks.recv().await {
+						if sink.send(&block).is_err() {
+							debug!("New sidechain blocks subscription closed");
+							break
+						}
+					}
+					subscriptions.fetch_sub(1, Ordering::SeqCst);
+				});
+				Ok(())
+			},
+		)?;
+
+		Ok(module)
+	}
+}
+
+/// Receives every block of `shard` stored from now on. The storage stops sending blocks once the
+/// receiver is dropped, or once more than [`NEW_BLOCKS_BUFFER_SIZE`] blocks haven't been received.
+fn new_blocks_receiver<Storage: SubscribeNewBlocks<SignedBlock>>(
+	storage: &Storage,
+	shard: ShardIdentifier,
+) -> Receiver<SignedBlock> {
+	let (sender, receiver) = channel(NEW_BLOCKS_BUFFER_SIZE);
+	storage.subscribe_new_blocks(Box::new(move |block: &SignedBlock| {
+		if block.block().header().shard_id() != shard {
+			return !sender.is_closed()
+		}
+		match sender.try_send(block.clone()) {
+			Ok(()) => true,
+			Err(TrySendError::Full(_)) => {
+				warn!("Dropping new sidechain blocks subscriber, which has fallen behind");
+				false
+			},
+			Err(TrySendError::Closed(_)) => false,
+		}
+	}));
+	receiver
+}
+
+fn storage_error(e: its_storage::Error) -> CallError {
+	error!("Failed to query sidechain blocks from storage: {:?}", e);
+	CallError::Failed(e.into())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use its_storage::fetch_blocks_mock::FetchBlocksMock;
+	use its_test::{
+		sidechain_block_builder::{SidechainBlockBuilder, SidechainBlockBuilderTrait},
+		sidechain_header_builder::SidechainHeaderBuilder,
+	};
+
+	fn blocks(count: usize) -> Vec<SignedBlock> {
+		let shard = ShardIdentifier::random();
+		(0..count)
+			.map(|_| {
+				SidechainBlockBuilder::random()
+					.with_header(SidechainHeaderBuilder::random().with_shard(shard).build())
+					.build_signed()
+			})
+			.collect()
+	}
+
+	#[test]
+	fn new_blocks_receiver_receives_blocks_of_its_shard_only() {
+		let shard_blocks = blocks(2);
+		let other_shard_blocks = blocks(1);
+		let storage = FetchBlocksMock::default();
+		let shard = shard_blocks[0].block().header().shard_id();
+
+		let mut receiver = new_blocks_receiver(&storage, shard);
+		storage.notify_new_blocks(&other_shard_blocks);
+		storage.notify_new_blocks(&shard_blocks);
+
+		assert_eq!(receiver.try_recv().unwrap(), shard_blocks[0]);
+		assert_eq!(receiver.try_recv().unwrap(), shard_blocks[1]);
+		assert!(receiver.try_recv().is_err());
+	}
+
+	#[test]
+	fn dropping_new_blocks_receiver_removes_subscription() {
+		let blocks = blocks(1);
+		let storage = FetchBlocksMock::default();
+
+		let receiver = new_blocks_receiver(&storage, blocks[0].block().header().shard_id());
+		assert_eq!(storage.number_of_new_block_subscribers(), 1);
+
+		drop(receiver);
+		storage.notify_new_blocks(&blocks);
+
+		assert_eq!(storage.number_of_new_block_subscribers(), 0);
+	}
+
+	#[test]
+	fn new_blocks_receiver_that_falls_behind_is_dropped() {
+		let blocks = blocks(NEW_BLOCKS_BUFFER_SIZE + 1);
+		let storage = FetchBlocksMock::default();
+
+		let mut receiver = new_blocks_receiver(&storage, blocks[0].block().header().shard_id());
+		storage.notify_new_blocks(&blocks);
+
+		assert_eq!(storage.number_of_new_block_subscribers(), 0);
+		for block in blocks.iter().take(NEW_BLOCKS_BUFFER_SIZE) {
+			assert_eq!(&receiver.try_recv().unwrap(), block);
+		}
+		assert!(receiver.try_recv().is_err());
+	}
+}
@@ -15,13 +15,16 @@
 
 */
 
+use crate::block_explorer::BlockExplorerModuleBuilder;
 use its_peer_fetch::block_fetch_server::BlockFetchServerModuleBuilder;
 use its_primitives::types::block::SignedBlock;
-use its_storage::interface::FetchBlocks;
+use its_storage::interface::{FetchBlocks, QueryBlocks, SubscribeNewBlocks};
 use jsonrpsee::{types::error::CallError, ws_server::WsServerBuilder};
 use std::{net::SocketAddr, sync::Arc};
 use tokio::net::ToSocketAddrs;
 
+pub mod block_explorer;
+
 #[cfg(test)]
 mod mock;
 #[cfg(test)]
@@ -32,10 +35,20 @@ pub async fn run_server<FetchSidechainBlocks>(
 	sidechain_block_fetcher: Arc<FetchSidechainBlocks>,
 ) -> anyhow::Result<SocketAddr>
 where
-	FetchSidechainBlocks: FetchBlocks<SignedBlock> + Send + Sync + 'static,
+	FetchSidechainBlocks: FetchBlocks<SignedBlock>
+		+ QueryBlocks<SignedBlock>
+		+ SubscribeNewBlocks<SignedBlock>
+		+ Send
+		+ Sync
+		+ 'static,
 {
 	let mut server = WsServerBuilder::default().build(addr).await?;
 
+	let block_explorer_module = BlockExplorerModuleBuilder::new(sidechain_block_fetcher.clone())
+		.build()
+		.map_err(|e| CallError::Failed(e.to_string().into()))?;
+	server.register_module(block_explorer_module).unwrap();
+
 	let fetch_sidechain_blocks_module = BlockFetchServerModuleBuilder::new(sidechain_block_fetcher)
 		.build()
 		.map_err(|e| CallError::Failed(e.to_string().into()))?; // `to_string` necessary due to no all errors implementing Send + Sync.
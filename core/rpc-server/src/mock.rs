@@ -17,9 +17,16 @@
 
 use its_primitives::{
 	traits::ShardIdentifierFor,
-	types::{BlockHash, SignedBlock, SignedBlock as SignedSidechainBlock},
+	types::{
+		justification::Justification, BlockHash, BlockNumber, SignedBlock,
+		SignedBlock as SignedSidechainBlock,
+	},
 };
-use its_storage::interface::FetchBlocks;
+use its_storage::{
+	interface::{FetchBlocks, NewBlockCallback, QueryBlocks, SubscribeNewBlocks},
+	ShardStatistics,
+};
+use sp_core::H256;
 
 pub struct TestEnclave;
 
@@ -43,3 +50,61 @@ impl FetchBlocks<SignedSidechainBlock> for MockSidechainBlockFetcher {
 		Ok(Vec::new())
 	}
 }
+
+impl SubscribeNewBlocks<SignedSidechainBlock> for MockSidechainBlockFetcher {
+	fn subscribe_new_blocks(&self, _callback: NewBlockCallback<SignedSidechainBlock>) {}
+}
+
+impl QueryBlocks<SignedSidechainBlock> for MockSidechainBlockFetcher {
+	fn shards(&self) -> Vec<ShardIdentifierFor<SignedBlock>> {
+		Vec::new()
+	}
+
+	fn fetch_block(&self, _block_hash: &BlockHash) -> its_storage::Result<Option<SignedBlock>> {
+		Ok(None)
+	}
+
+	fn fetch_block_by_number(
+		&self,
+		_shard_identifier: &ShardIdentifierFor<SignedBlock>,
+		_block_number: BlockNumber,
+	) -> its_storage::Result<Option<SignedBlock>> {
+		Ok(None)
+	}
+
+	fn fetch_latest_blocks(
+		&self,
+		_shard_identifier: &ShardIdentifierFor<SignedBlock>,
+		_max: usize,
+	) -> its_storage::Result<Vec<SignedBlock>> {
+		Ok(Vec::new())
+	}
+
+	fn fetch_block_by_signed_top(
+		&self,
+		_top_hash: &H256,
+	) -> its_storage::Result<Option<SignedBlock>> {
+		Ok(None)
+	}
+
+	fn shard_statistics(
+		&self,
+		_shard_identifier: &ShardIdentifierFor<SignedBlock>,
+	) -> Option<ShardStatistics> {
+		None
+	}
+
+	fn fetch_justification(
+		&self,
+		_block_hash: &BlockHash,
+	) -> its_storage::Result<Option<Justification>> {
+		Ok(None)
+	}
+
+	fn fetch_finalized_head(
+		&self,
+		_shard_identifier: &ShardIdentifierFor<SignedBlock>,
+	) -> its_storage::Result<Option<Justification>> {
+		Ok(None)
+	}
+}
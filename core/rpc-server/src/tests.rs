@@ -18,8 +18,10 @@
 use super::*;
 use crate::mock::MockSidechainBlockFetcher;
 
-use its_primitives::types::{header::ShardIdentifier, BlockHash};
-use its_rpc_handler::constants::RPC_METHOD_NAME_FETCH_BLOCKS_FROM_PEER;
+use its_primitives::types::{header::ShardIdentifier, BlockHash, SignedBlock};
+use its_rpc_handler::constants::{
+	RPC_METHOD_NAME_FETCH_BLOCKS_FROM_PEER, RPC_METHOD_NAME_GET_LATEST_BLOCKS,
+};
 use its_test::sidechain_block_builder::SidechainBlockBuilderTrait;
 use jsonrpsee::{
 	types::{to_json_value, traits::Client},
@@ -50,3 +52,19 @@ async fn test_client_calls() {
 	//received no blocks from server
 	assert!(response.is_empty());
 }
+
+#[tokio::test]
+async fn test_get_latest_blocks() {
+	init();
+	let addr = run_server("127.0.0.1:0", Arc::new(MockSidechainBlockFetcher)).await.unwrap();
+
+	let url = format!("ws://{}", addr);
+	let client = WsClientBuilder::default().build(&url).await.unwrap();
+	let param_json = to_json_value((ShardIdentifier::default(), 10u32)).unwrap();
+	let response: Vec<SignedBlock> = client
+		.request(RPC_METHOD_NAME_GET_LATEST_BLOCKS, vec![param_json].into())
+		.await
+		.unwrap();
+
+	assert!(response.is_empty());
+}
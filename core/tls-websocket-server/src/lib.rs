@@ -85,6 +85,9 @@ pub trait WebSocketMessageHandler: Send + Sync {
 		connection_token: ConnectionToken,
 		message: String,
 	) -> WebSocketResult<Option<String>>;
+
+	/// Called after a connection has been closed and removed from the server.
+	fn handle_connection_closed(&self, _connection_token: ConnectionToken) {}
 }
 
 /// Allows to send response messages to a specific connection.
@@ -118,6 +118,7 @@ where
 		let mut connections_lock =
 			self.connections.write().map_err(|_| WebSocketError::LockPoisoning)?;
 
+		let mut is_closed = false;
 		if let Some(connection) = connections_lock.get_mut(&token) {
 			connection.on_ready(poll, event)?;
 
@@ -129,8 +130,15 @@ where
 					token,
 					connections_lock.len()
 				);
+				is_closed = true;
 			}
 		}
+		drop(connections_lock);
+
+		// Called without holding the lock, the handler might send messages to other connections.
+		if is_closed {
+			self.connection_handler.handle_connection_closed(token.into());
+		}
 
 		Ok(())
 	}
@@ -110,14 +110,14 @@ pub fn create_ra_report_and_signature(
 			}
 		},
 		RemoteAttestationType::Dcap => {
-			match attestation_handler.generate_dcap_ra_cert(
+			match attestation_handler.generate_dcap_tls_ra_cert(
 				quoting_enclave_target_info,
 				quote_size,
 				skip_ra,
 			) {
-				Ok((key_der, cert_der, _qe_quote)) => Ok((key_der, cert_der)),
+				Ok(dcap) => Ok(dcap),
 				Err(e) => {
-					error!("generate_dcap_ra_cert failure: {:?}", e);
+					error!("generate_dcap_tls_ra_cert failure: {:?}", e);
 					Err(e.into())
 				},
 			}
@@ -16,7 +16,7 @@
 */
 
 use derive_more::From;
-use itp_types::parentchain::ParentchainId;
+use itp_types::parentchain::{ParentchainId, TargetParentchainIndex};
 use sgx_types::{sgx_quote3_error_t, sgx_status_t};
 use std::{boxed::Box, result::Result as StdResult, string::String};
 
@@ -45,8 +45,8 @@ pub enum Error {
 	NoShardAssigned,
 	TooManyShardsAssigned,
 	NoIntegriteeParentchainAssigned,
-	NoTargetAParentchainAssigned,
-	NoTargetBParentchainAssigned,
+	#[from(ignore)]
+	NoTargetParentchainAssigned(TargetParentchainIndex),
 	ParentChainValidation(itp_storage::error::Error),
 	ParentChainSync,
 	PrimitivesAccess(itp_primitives_cache::error::Error),
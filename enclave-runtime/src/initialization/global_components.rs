@@ -22,20 +22,20 @@
 use crate::{
 	initialization::parentchain::{
 		integritee_parachain::IntegriteeParachainHandler,
-		integritee_solochain::IntegriteeSolochainHandler,
-		target_a_parachain::TargetAParachainHandler, target_a_solochain::TargetASolochainHandler,
-		target_b_parachain::TargetBParachainHandler, target_b_solochain::TargetBSolochainHandler,
+		integritee_solochain::IntegriteeSolochainHandler, target_parachain::TargetParachainHandler,
+		target_solochain::TargetSolochainHandler,
 	},
 	ocall::OcallApi,
 	rpc::rpc_response_channel::RpcResponseChannel,
-	tls_ra::seal_handler::SealHandler,
+	tls_ra::seal_handler::{SealHandler, ShardSealHandler},
 };
-use ita_parentchain_interface::{integritee, target_a, target_b};
+use ita_parentchain_interface::{integritee, target};
 use ita_sgx_runtime::Runtime;
 use ita_stf::{Getter, State as StfState, Stf, TrustedCallSigned};
 use itc_direct_rpc_server::{
-	rpc_connection_registry::ConnectionRegistry, rpc_responder::RpcResponder,
-	rpc_watch_extractor::RpcWatchExtractor, rpc_ws_handler::RpcWsHandler,
+	account_subscriptions::AccountSubscriptionHandler, rpc_connection_registry::ConnectionRegistry,
+	rpc_responder::RpcResponder, rpc_watch_extractor::RpcWatchExtractor,
+	rpc_ws_handler::RpcWsHandler,
 };
 use itc_parentchain::{
 	block_import_dispatcher::{
@@ -52,7 +52,10 @@ use itc_parentchain::{
 use itc_tls_websocket_server::{
 	config_provider::FromFileConfigProvider, ws_server::TungsteniteWsServer, ConnectionToken,
 };
-use itp_attestation_handler::IntelAttestationHandler;
+use itp_attestation_handler::{
+	collateral_repository::{CollateralSeal, OCallCollateralSource},
+	CollateralRepository, IntelAttestationHandler,
+};
 use itp_component_container::ComponentContainer;
 use itp_extrinsics_factory::ExtrinsicsFactory;
 use itp_import_queue::ImportQueue;
@@ -61,12 +64,16 @@ use itp_node_api::{
 	metadata::{provider::NodeMetadataRepository, NodeMetadata},
 };
 use itp_nonce_cache::NonceCache;
-use itp_sgx_crypto::{key_repository::KeyRepository, Aes, AesSeal, Ed25519Seal, Rsa3072Seal};
+use itp_sgx_crypto::{
+	key_repository::{KeyRepository, RotatingKeyRepository},
+	AesGcm, AesGcmSeal, Ed25519Seal, RetiredRsa3072Seal, RetiredX25519Seal, Rsa3072Seal,
+	X25519KeyPair, X25519Seal,
+};
 use itp_stf_executor::{
 	enclave_signer::StfEnclaveSigner, executor::StfExecutor, getter_executor::GetterExecutor,
-	state_getter::StfStateGetter,
+	state_commitment::StateCommitmentCache, state_getter::StfStateGetter,
 };
-use itp_stf_primitives::types::{Hash, TrustedOperation};
+use itp_stf_primitives::types::{AccountId, Hash, TrustedOperation};
 use itp_stf_state_handler::{
 	file_io::sgx::SgxStateFileIo, state_initializer::StateInitializer,
 	state_snapshot_repository::StateSnapshotRepository, StateHandler,
@@ -76,23 +83,36 @@ use itp_top_pool::basic_pool::BasicPool;
 use itp_top_pool_author::{
 	api::SidechainApi,
 	author::{Author, AuthorTopFilter},
+	persistence::{TopPoolPersistence, TopPoolSeal},
+};
+use itp_types::{
+	parentchain::TargetParentchainIndex, Block as ParentchainBlock,
+	SignedBlock as SignedParentchainBlock,
 };
-use itp_types::{Block as ParentchainBlock, SignedBlock as SignedParentchainBlock};
 use its_block_header_cache::SidechainBlockHeaderCache;
 use its_primitives::{
 	traits::{Block as SidechainBlockTrait, SignedBlock as SignedSidechainBlockTrait},
-	types::{block::SignedBlock as SignedSidechainBlock, header::SidechainHeader},
+	types::{
+		block::SignedBlock as SignedSidechainBlock, header::SidechainHeader,
+		justification::SignedFinalityVote, slot_claims::SlotClaimConfig,
+	},
 };
 use its_sidechain::{
 	aura::block_importer::BlockImporter as SidechainBlockImporter,
 	block_composer::BlockComposer,
-	consensus_common::{BlockImportConfirmationHandler, BlockImportQueueWorker, PeerBlockSync},
+	consensus_common::{
+		BlockImportConfirmationHandler, BlockImportQueueWorker, FinalityGadget,
+		OwnFinalityVotesSeal, PeerBlockSync,
+	},
 };
 use lazy_static::lazy_static;
 use sgx_crypto_helper::rsa3072::Rsa3072KeyPair;
 use sgx_tstd::vec::Vec;
 use sp_core::{ed25519, ed25519::Pair};
-use std::sync::Arc;
+use std::{
+	collections::BTreeMap,
+	sync::{Arc, SgxRwLock},
+};
 
 pub type EnclaveParentchainSigner =
 	itp_node_api::api_client::StaticExtrinsicSigner<Pair, PairSignature>;
@@ -100,8 +120,11 @@ pub type EnclaveParentchainSigner =
 pub type EnclaveGetter = Getter;
 pub type EnclaveTrustedCallSigned = TrustedCallSigned;
 pub type EnclaveStf = Stf<EnclaveTrustedCallSigned, EnclaveGetter, StfState, Runtime>;
-pub type EnclaveStateKeyRepository = KeyRepository<Aes, AesSeal>;
-pub type EnclaveShieldingKeyRepository = KeyRepository<Rsa3072KeyPair, Rsa3072Seal>;
+pub type EnclaveStateKeyRepository = KeyRepository<AesGcm, AesGcmSeal>;
+pub type EnclaveShieldingKeyRepository =
+	RotatingKeyRepository<Rsa3072KeyPair, Rsa3072Seal, RetiredRsa3072Seal>;
+pub type EnclaveX25519ShieldingKeyRepository =
+	RotatingKeyRepository<X25519KeyPair, X25519Seal, RetiredX25519Seal>;
 pub type EnclaveSigningKeyRepository = KeyRepository<ed25519::Pair, Ed25519Seal>;
 pub type EnclaveStateFileIo = SgxStateFileIo<EnclaveStateKeyRepository, StfState>;
 pub type EnclaveStateSnapshotRepository = StateSnapshotRepository<EnclaveStateFileIo>;
@@ -133,12 +156,16 @@ pub type EnclaveStfEnclaveSigner = StfEnclaveSigner<
 >;
 pub type EnclaveAttestationHandler =
 	IntelAttestationHandler<EnclaveOCallApi, EnclaveSigningKeyRepository>;
+pub type EnclaveDcapCollateralRepository =
+	CollateralRepository<OCallCollateralSource<EnclaveOCallApi>, CollateralSeal>;
 
 pub type EnclaveRpcConnectionRegistry = ConnectionRegistry<Hash, ConnectionToken>;
 pub type EnclaveRpcWsHandler =
 	RpcWsHandler<RpcWatchExtractor<Hash>, EnclaveRpcConnectionRegistry, Hash>;
 pub type EnclaveWebSocketServer = TungsteniteWsServer<EnclaveRpcWsHandler, FromFileConfigProvider>;
 pub type EnclaveRpcResponder = RpcResponder<EnclaveRpcConnectionRegistry, Hash, RpcResponseChannel>;
+pub type EnclaveAccountSubscriptionHandler =
+	AccountSubscriptionHandler<EnclaveRpcResponder, AccountId>;
 pub type EnclaveSidechainApi = SidechainApi<ParentchainBlock, EnclaveTrustedCallSigned>;
 
 // Parentchain types relevant for all parentchains
@@ -154,15 +181,13 @@ pub type EnclaveValidatorAccessor = ValidatorAccessor<
 >;
 
 pub type IntegriteeParentchainBlockImportQueue = ImportQueue<SignedParentchainBlock>;
-pub type TargetAParentchainBlockImportQueue = ImportQueue<SignedParentchainBlock>;
-pub type TargetBParentchainBlockImportQueue = ImportQueue<SignedParentchainBlock>;
+pub type TargetParentchainBlockImportQueue = ImportQueue<SignedParentchainBlock>;
 
 /// Import queue for the events
 ///
 /// Note: `Vec<u8>` is correct. It should not be `Vec<Vec<u8>`
 pub type IntegriteeParentchainEventImportQueue = ImportQueue<Vec<u8>>;
-pub type TargetAParentchainEventImportQueue = ImportQueue<Vec<u8>>;
-pub type TargetBParentchainEventImportQueue = ImportQueue<Vec<u8>>;
+pub type TargetParentchainEventImportQueue = ImportQueue<Vec<u8>>;
 
 // Stuff for the integritee parentchain
 
@@ -200,95 +225,59 @@ pub type IntegriteeParentchainBlockImportDispatcher = BlockImportDispatcher<
 	IntegriteeParentchainImmediateBlockImportDispatcher,
 >;
 
-// Stuff for the Target A parentchain
+// Stuff for the target parentchains
 
-/// IndirectCalls executor instance of the Target A parentchain.
+/// IndirectCalls executor instance of a target parentchain.
 ///
 /// **Note**: The filter here is purely used for demo purposes.
 ///
 /// Also note that the extrinsic parser must be changed if the signed extra contains the
 /// `AssetTxPayment`.
-pub type TargetAParentchainIndirectCallsExecutor = IndirectCallsExecutor<
+pub type TargetParentchainIndirectCallsExecutor = IndirectCallsExecutor<
 	EnclaveShieldingKeyRepository,
 	EnclaveStfEnclaveSigner,
 	EnclaveTopPoolAuthor,
 	EnclaveNodeMetadataRepository,
-	target_a::ExtrinsicFilter,
-	EventCreator<target_a::FilterableEvents>,
-	target_a::ParentchainEventHandler,
+	target::ExtrinsicFilter,
+	EventCreator<target::FilterableEvents>,
+	target::ParentchainEventHandler,
 	EnclaveTrustedCallSigned,
 	EnclaveGetter,
 >;
 
-pub type TargetAParentchainBlockImporter = ParentchainBlockImporter<
+pub type TargetParentchainBlockImporter = ParentchainBlockImporter<
 	ParentchainBlock,
 	EnclaveValidatorAccessor,
 	EnclaveStfExecutor,
 	EnclaveExtrinsicsFactory,
-	TargetAParentchainIndirectCallsExecutor,
+	TargetParentchainIndirectCallsExecutor,
 >;
 
-pub type TargetAParentchainTriggeredBlockImportDispatcher = TriggeredDispatcher<
-	TargetAParentchainBlockImporter,
-	TargetAParentchainBlockImportQueue,
-	TargetAParentchainEventImportQueue,
+pub type TargetParentchainTriggeredBlockImportDispatcher = TriggeredDispatcher<
+	TargetParentchainBlockImporter,
+	TargetParentchainBlockImportQueue,
+	TargetParentchainEventImportQueue,
 >;
 
-pub type TargetAParentchainImmediateBlockImportDispatcher =
-	ImmediateDispatcher<TargetAParentchainBlockImporter>;
+pub type TargetParentchainImmediateBlockImportDispatcher =
+	ImmediateDispatcher<TargetParentchainBlockImporter>;
 
-pub type TargetAParentchainBlockImportDispatcher = BlockImportDispatcher<
-	TargetAParentchainTriggeredBlockImportDispatcher,
-	TargetAParentchainImmediateBlockImportDispatcher,
+pub type TargetParentchainBlockImportDispatcher = BlockImportDispatcher<
+	TargetParentchainTriggeredBlockImportDispatcher,
+	TargetParentchainImmediateBlockImportDispatcher,
 >;
 
-// Stuff for the Target B parentchain
-
-/// IndirectCalls executor instance of the Target B parentchain.
+/// Components of the target parentchains, by target parentchain index.
 ///
-/// **Note**: The filter here is purely used for demo purposes.
-///
-/// Also note that the extrinsic parser must be changed if the signed extra contains the
-/// `AssetTxPayment`.
-pub type TargetBParentchainIndirectCallsExecutor = IndirectCallsExecutor<
-	EnclaveShieldingKeyRepository,
-	EnclaveStfEnclaveSigner,
-	EnclaveTopPoolAuthor,
-	EnclaveNodeMetadataRepository,
-	target_b::ExtrinsicFilter,
-	EventCreator<target_b::FilterableEvents>,
-	target_b::ParentchainEventHandler,
-	EnclaveTrustedCallSigned,
-	EnclaveGetter,
->;
-
-pub type TargetBParentchainBlockImporter = ParentchainBlockImporter<
-	ParentchainBlock,
-	EnclaveValidatorAccessor,
-	EnclaveStfExecutor,
-	EnclaveExtrinsicsFactory,
-	TargetBParentchainIndirectCallsExecutor,
->;
-
-pub type TargetBParentchainTriggeredBlockImportDispatcher = TriggeredDispatcher<
-	TargetBParentchainBlockImporter,
-	TargetBParentchainBlockImportQueue,
-	TargetBParentchainEventImportQueue,
->;
-
-pub type TargetBParentchainImmediateBlockImportDispatcher =
-	ImmediateDispatcher<TargetBParentchainBlockImporter>;
-
-pub type TargetBParentchainBlockImportDispatcher = BlockImportDispatcher<
-	TargetBParentchainTriggeredBlockImportDispatcher,
-	TargetBParentchainImmediateBlockImportDispatcher,
->;
+/// In contrast to the `ComponentContainer`, entries are added whenever a target parentchain
+/// is initialized, as their number is only known at runtime.
+pub type TargetParentchainComponents<T> = SgxRwLock<BTreeMap<TargetParentchainIndex, Arc<T>>>;
 
 /// Sidechain types
 pub type EnclaveTopPool = BasicPool<
 	EnclaveSidechainApi,
 	ParentchainBlock,
-	EnclaveRpcResponder,
+	EnclaveAccountSubscriptionHandler,
 	TrustedOperation<EnclaveTrustedCallSigned, EnclaveGetter>,
 >;
 
@@ -300,6 +289,7 @@ pub type EnclaveTopPoolAuthor = Author<
 	EnclaveTrustedCallSigned,
 	EnclaveGetter,
 >;
+pub type EnclaveTopPoolPersistence = TopPoolPersistence<TopPoolSeal>;
 pub type EnclaveSidechainBlockComposer =
 	BlockComposer<ParentchainBlock, SignedSidechainBlock, Pair, EnclaveStateKeyRepository>;
 pub type EnclaveSidechainBlockImporter = SidechainBlockImporter<
@@ -316,6 +306,8 @@ pub type EnclaveSidechainBlockImporter = SidechainBlockImporter<
 	EnclaveGetter,
 >;
 pub type EnclaveSidechainBlockImportQueue = ImportQueue<SignedSidechainBlock>;
+pub type EnclaveSidechainFinalityVoteQueue = ImportQueue<SignedFinalityVote>;
+pub type EnclaveSidechainFinalityGadget = FinalityGadget<OwnFinalityVotesSeal>;
 pub type EnclaveBlockImportConfirmationHandler = BlockImportConfirmationHandler<
 	ParentchainBlock,
 	<<SignedSidechainBlock as SignedSidechainBlockTrait>::Block as SidechainBlockTrait>::HeaderType,
@@ -338,10 +330,23 @@ pub type EnclaveSidechainBlockImportQueueWorker = BlockImportQueueWorker<
 >;
 pub type EnclaveSealHandler = SealHandler<
 	EnclaveShieldingKeyRepository,
+	EnclaveX25519ShieldingKeyRepository,
 	EnclaveStateKeyRepository,
 	EnclaveStateHandler,
 	EnclaveLightClientSeal,
 >;
+pub type EnclaveShardSealHandler = ShardSealHandler<
+	EnclaveShieldingKeyRepository,
+	EnclaveX25519ShieldingKeyRepository,
+	EnclaveStateKeyRepository,
+	EnclaveStateHandler,
+	EnclaveValidatorAccessor,
+>;
+pub type EnclaveOffchainTaskScheduler =
+	itc_offchain_worker_executor::offchain_task::OffchainTaskScheduler<
+		StfState,
+		EnclaveTrustedCallSigned,
+	>;
 pub type EnclaveOffchainWorkerExecutor = itc_offchain_worker_executor::executor::Executor<
 	ParentchainBlock,
 	EnclaveTopPoolAuthor,
@@ -366,6 +371,11 @@ pub static GLOBAL_SHIELDING_KEY_REPOSITORY_COMPONENT: ComponentContainer<
 	EnclaveShieldingKeyRepository,
 > = ComponentContainer::new("Shielding key repository");
 
+/// X25519 (hybrid) shielding key repository
+pub static GLOBAL_X25519_SHIELDING_KEY_REPOSITORY_COMPONENT: ComponentContainer<
+	EnclaveX25519ShieldingKeyRepository,
+> = ComponentContainer::new("X25519 shielding key repository");
+
 /// Signing key repository
 pub static GLOBAL_SIGNING_KEY_REPOSITORY_COMPONENT: ComponentContainer<
 	EnclaveSigningKeyRepository,
@@ -376,16 +386,6 @@ pub static GLOBAL_INTEGRITEE_PARENTCHAIN_LIGHT_CLIENT_SEAL: ComponentContainer<
 	EnclaveLightClientSeal,
 > = ComponentContainer::new("Integritee Parentchain EnclaveLightClientSealSync");
 
-/// Light client db seal for the Target A parentchain.
-pub static GLOBAL_TARGET_A_PARENTCHAIN_LIGHT_CLIENT_SEAL: ComponentContainer<
-	EnclaveLightClientSeal,
-> = ComponentContainer::new("Target A EnclaveLightClientSealSync");
-
-/// Light client db seal for the Target A parentchain.
-pub static GLOBAL_TARGET_B_PARENTCHAIN_LIGHT_CLIENT_SEAL: ComponentContainer<
-	EnclaveLightClientSeal,
-> = ComponentContainer::new("Target B EnclaveLightClientSealSync");
-
 /// O-Call API
 pub static GLOBAL_OCALL_API_COMPONENT: ComponentContainer<EnclaveOCallApi> =
 	ComponentContainer::new("O-call API");
@@ -410,6 +410,11 @@ pub static GLOBAL_TOP_POOL_AUTHOR_COMPONENT: ComponentContainer<EnclaveTopPoolAu
 pub static GLOBAL_ATTESTATION_HANDLER_COMPONENT: ComponentContainer<EnclaveAttestationHandler> =
 	ComponentContainer::new("Attestation handler");
 
+/// DCAP collateral repository, verifies the quotes of fellow validateers
+pub static GLOBAL_DCAP_COLLATERAL_REPOSITORY_COMPONENT: ComponentContainer<
+	EnclaveDcapCollateralRepository,
+> = ComponentContainer::new("DCAP collateral repository");
+
 // Parentchain component instances
 //-------------------------------------------------------------------------------------------------
 
@@ -417,14 +422,26 @@ lazy_static! {
 	/// Global nonce cache for the Integritee Parentchain.
 	pub static ref GLOBAL_INTEGRITEE_PARENTCHAIN_NONCE_CACHE: Arc<NonceCache> = Default::default();
 
-	/// Global nonce cache for the Target A parentchain..
-	pub static ref GLOBAL_TARGET_A_PARENTCHAIN_NONCE_CACHE: Arc<NonceCache> = Default::default();
+	/// Global nonce caches for the target parentchains.
+	pub static ref GLOBAL_TARGET_PARENTCHAIN_NONCE_CACHES: TargetParentchainComponents<NonceCache> = Default::default();
+
+	/// Light client db seals for the target parentchains.
+	pub static ref GLOBAL_TARGET_PARENTCHAIN_LIGHT_CLIENT_SEALS: TargetParentchainComponents<EnclaveLightClientSeal> = Default::default();
 
-	/// Global nonce cache for the Target B parentchain..
-	pub static ref GLOBAL_TARGET_B_PARENTCHAIN_NONCE_CACHE: Arc<NonceCache> = Default::default();
+	/// Solochain handlers of the target parentchains.
+	pub static ref GLOBAL_TARGET_SOLOCHAIN_HANDLER_COMPONENTS: TargetParentchainComponents<TargetSolochainHandler> = Default::default();
+
+	/// Parachain handlers of the target parentchains.
+	pub static ref GLOBAL_TARGET_PARACHAIN_HANDLER_COMPONENTS: TargetParentchainComponents<TargetParachainHandler> = Default::default();
 
 	/// Global sidechain header cache
 	pub static ref GLOBAL_SIDECHAIN_BLOCK_HEADER_CACHE: Arc<SidechainBlockHeaderCache<SidechainHeader>> = Default::default();
+
+	/// Trie of the state committed in the latest sidechain block of each shard.
+	pub static ref GLOBAL_STATE_COMMITMENT_CACHE: Arc<StateCommitmentCache> = Default::default();
+
+	/// Periodic offchain tasks of the offchain worker, run against the state of each shard.
+	pub static ref GLOBAL_OFFCHAIN_TASK_SCHEDULER: Arc<EnclaveOffchainTaskScheduler> = Default::default();
 }
 
 /// Solochain Handler.
@@ -436,22 +453,6 @@ pub static GLOBAL_INTEGRITEE_PARACHAIN_HANDLER_COMPONENT: ComponentContainer<
 	IntegriteeParachainHandler,
 > = ComponentContainer::new("integritee parachain handler");
 
-pub static GLOBAL_TARGET_A_SOLOCHAIN_HANDLER_COMPONENT: ComponentContainer<
-	TargetASolochainHandler,
-> = ComponentContainer::new("target A solochain handler");
-
-pub static GLOBAL_TARGET_A_PARACHAIN_HANDLER_COMPONENT: ComponentContainer<
-	TargetAParachainHandler,
-> = ComponentContainer::new("target A parachain handler");
-
-pub static GLOBAL_TARGET_B_SOLOCHAIN_HANDLER_COMPONENT: ComponentContainer<
-	TargetBSolochainHandler,
-> = ComponentContainer::new("target B solochain handler");
-
-pub static GLOBAL_TARGET_B_PARACHAIN_HANDLER_COMPONENT: ComponentContainer<
-	TargetBParachainHandler,
-> = ComponentContainer::new("target B parachain handler");
-
 // Sidechain component instances
 //-------------------------------------------------------------------------------------------------
 
@@ -459,11 +460,26 @@ pub static GLOBAL_TARGET_B_PARACHAIN_HANDLER_COMPONENT: ComponentContainer<
 pub static GLOBAL_RPC_WS_HANDLER_COMPONENT: ComponentContainer<EnclaveRpcWsHandler> =
 	ComponentContainer::new("rpc_ws_handler");
 
+/// Subscriptions of clients to the events of accounts.
+pub static GLOBAL_ACCOUNT_SUBSCRIPTION_HANDLER_COMPONENT: ComponentContainer<
+	EnclaveAccountSubscriptionHandler,
+> = ComponentContainer::new("account_subscription_handler");
+
 /// Sidechain import queue.
 pub static GLOBAL_SIDECHAIN_IMPORT_QUEUE_COMPONENT: ComponentContainer<
 	EnclaveSidechainBlockImportQueue,
 > = ComponentContainer::new("sidechain_import_queue");
 
+/// Sidechain finality vote queue.
+pub static GLOBAL_SIDECHAIN_FINALITY_VOTE_QUEUE_COMPONENT: ComponentContainer<
+	EnclaveSidechainFinalityVoteQueue,
+> = ComponentContainer::new("sidechain_finality_vote_queue");
+
+/// Sidechain finality gadget.
+pub static GLOBAL_SIDECHAIN_FINALITY_GADGET_COMPONENT: ComponentContainer<
+	EnclaveSidechainFinalityGadget,
+> = ComponentContainer::new("sidechain_finality_gadget");
+
 /// Sidechain import queue worker - processes the import queue.
 pub static GLOBAL_SIDECHAIN_IMPORT_QUEUE_WORKER_COMPONENT: ComponentContainer<
 	EnclaveSidechainBlockImportQueueWorker,
@@ -474,6 +490,10 @@ pub static GLOBAL_SIDECHAIN_BLOCK_COMPOSER_COMPONENT: ComponentContainer<
 	EnclaveSidechainBlockComposer,
 > = ComponentContainer::new("sidechain_block_composer");
 
+/// Slot claim rules of the sidechain.
+pub static GLOBAL_SLOT_CLAIM_CONFIG_COMPONENT: ComponentContainer<SlotClaimConfig> =
+	ComponentContainer::new("slot_claim_config");
+
 /// Sidechain block syncer.
 pub static GLOBAL_SIDECHAIN_BLOCK_SYNCER_COMPONENT: ComponentContainer<
 	EnclaveSidechainBlockSyncer,
@@ -20,25 +20,34 @@ pub mod parentchain;
 use crate::{
 	error::{Error, Result as EnclaveResult},
 	initialization::global_components::{
-		EnclaveBlockImportConfirmationHandler, EnclaveGetterExecutor, EnclaveLightClientSeal,
+		EnclaveAccountSubscriptionHandler, EnclaveBlockImportConfirmationHandler,
+		EnclaveDcapCollateralRepository, EnclaveGetterExecutor, EnclaveLightClientSeal,
 		EnclaveRpcConnectionRegistry, EnclaveRpcResponder, EnclaveShieldingKeyRepository,
 		EnclaveSidechainApi, EnclaveSidechainBlockImportQueueWorker, EnclaveSidechainBlockImporter,
-		EnclaveSidechainBlockSyncer, EnclaveStateFileIo, EnclaveStateHandler,
-		EnclaveStateInitializer, EnclaveStateObserver, EnclaveStateSnapshotRepository,
-		EnclaveStfEnclaveSigner, EnclaveTopPool, EnclaveTopPoolAuthor,
-		GLOBAL_ATTESTATION_HANDLER_COMPONENT, GLOBAL_INTEGRITEE_PARENTCHAIN_LIGHT_CLIENT_SEAL,
-		GLOBAL_OCALL_API_COMPONENT, GLOBAL_RPC_WS_HANDLER_COMPONENT,
-		GLOBAL_SHIELDING_KEY_REPOSITORY_COMPONENT, GLOBAL_SIDECHAIN_BLOCK_COMPOSER_COMPONENT,
-		GLOBAL_SIDECHAIN_BLOCK_HEADER_CACHE, GLOBAL_SIDECHAIN_BLOCK_SYNCER_COMPONENT,
-		GLOBAL_SIDECHAIN_IMPORT_QUEUE_COMPONENT, GLOBAL_SIDECHAIN_IMPORT_QUEUE_WORKER_COMPONENT,
-		GLOBAL_SIGNING_KEY_REPOSITORY_COMPONENT, GLOBAL_STATE_HANDLER_COMPONENT,
-		GLOBAL_STATE_KEY_REPOSITORY_COMPONENT, GLOBAL_STATE_OBSERVER_COMPONENT,
-		GLOBAL_TARGET_A_PARENTCHAIN_LIGHT_CLIENT_SEAL,
-		GLOBAL_TARGET_B_PARENTCHAIN_LIGHT_CLIENT_SEAL, GLOBAL_TOP_POOL_AUTHOR_COMPONENT,
-		GLOBAL_WEB_SOCKET_SERVER_COMPONENT,
+		EnclaveSidechainBlockSyncer, EnclaveSidechainFinalityGadget, EnclaveStateFileIo,
+		EnclaveStateHandler, EnclaveStateInitializer, EnclaveStateKeyRepository,
+		EnclaveStateObserver, EnclaveStateSnapshotRepository, EnclaveStfEnclaveSigner,
+		EnclaveTopPool, EnclaveTopPoolAuthor, EnclaveTopPoolPersistence, EnclaveValidatorAccessor,
+		EnclaveX25519ShieldingKeyRepository, GLOBAL_ACCOUNT_SUBSCRIPTION_HANDLER_COMPONENT,
+		GLOBAL_ATTESTATION_HANDLER_COMPONENT, GLOBAL_DCAP_COLLATERAL_REPOSITORY_COMPONENT,
+		GLOBAL_INTEGRITEE_PARENTCHAIN_LIGHT_CLIENT_SEAL, GLOBAL_OCALL_API_COMPONENT,
+		GLOBAL_RPC_WS_HANDLER_COMPONENT, GLOBAL_SHIELDING_KEY_REPOSITORY_COMPONENT,
+		GLOBAL_SIDECHAIN_BLOCK_COMPOSER_COMPONENT, GLOBAL_SIDECHAIN_BLOCK_HEADER_CACHE,
+		GLOBAL_SIDECHAIN_BLOCK_SYNCER_COMPONENT, GLOBAL_SIDECHAIN_FINALITY_GADGET_COMPONENT,
+		GLOBAL_SIDECHAIN_FINALITY_VOTE_QUEUE_COMPONENT, GLOBAL_SIDECHAIN_IMPORT_QUEUE_COMPONENT,
+		GLOBAL_SIDECHAIN_IMPORT_QUEUE_WORKER_COMPONENT, GLOBAL_SIGNING_KEY_REPOSITORY_COMPONENT,
+		GLOBAL_SLOT_CLAIM_CONFIG_COMPONENT, GLOBAL_STATE_COMMITMENT_CACHE,
+		GLOBAL_STATE_HANDLER_COMPONENT, GLOBAL_STATE_KEY_REPOSITORY_COMPONENT,
+		GLOBAL_STATE_OBSERVER_COMPONENT, GLOBAL_TOP_POOL_AUTHOR_COMPONENT,
+		GLOBAL_WEB_SOCKET_SERVER_COMPONENT, GLOBAL_X25519_SHIELDING_KEY_REPOSITORY_COMPONENT,
 	},
+	key_rotation::{restore_pending_key_rotation, KeyRotationActivator},
 	ocall::OcallApi,
-	rpc::{common_api::add_common_api, rpc_response_channel::RpcResponseChannel},
+	rpc::{
+		account_events_api::{add_account_events_api, AccountEventNotifier},
+		common_api::add_common_api,
+		rpc_response_channel::RpcResponseChannel,
+	},
 	utils::{
 		get_extrinsic_factory_from_integritee_solo_or_parachain,
 		get_node_metadata_repository_from_integritee_solo_or_parachain,
@@ -54,33 +63,62 @@ use itc_direct_rpc_server::{
 	create_determine_watch, rpc_connection_registry::ConnectionRegistry,
 	rpc_ws_handler::RpcWsHandler,
 };
+use itc_parentchain::light_client::{concurrent_access::ValidatorAccess, LightClientState};
 use itc_tls_websocket_server::{
 	certificate_generation::ed25519_self_signed_certificate, create_ws_server, ConnectionToken,
 	WebSocketServer,
 };
-use itp_attestation_handler::IntelAttestationHandler;
+use itp_attestation_handler::{
+	collateral_repository::{CollateralSeal, OCallCollateralSource},
+	DcapQuoteVerifier, IntelAttestationHandler,
+};
 use itp_component_container::{ComponentGetter, ComponentInitializer};
+use itp_ocall_api::EnclaveSidechainOCallApi;
 use itp_primitives_cache::GLOBAL_PRIMITIVES_CACHE;
 use itp_settings::files::{
 	INTEGRITEE_PARENTCHAIN_LIGHT_CLIENT_DB_PATH, STATE_SNAPSHOTS_CACHE_SIZE,
-	TARGET_A_PARENTCHAIN_LIGHT_CLIENT_DB_PATH, TARGET_B_PARENTCHAIN_LIGHT_CLIENT_DB_PATH,
+	STATE_SNAPSHOTS_CHECKPOINT_INTERVAL,
 };
 use itp_sgx_crypto::{
-	get_aes_repository, get_ed25519_repository, get_rsa3072_repository, key_repository::AccessKey,
+	get_aes_gcm_repository, get_ed25519_repository, get_rotating_rsa3072_repository,
+	get_rotating_x25519_repository, key_repository::AccessKey, AesSeal, AesSealing,
 };
 use itp_stf_state_handler::{
-	file_io::StateDir, handle_state::HandleState, query_shard_state::QueryShardState,
+	file_io::{
+		sgx::{complete_state_reencryption, migrate_legacy_state_encryption},
+		StateDir,
+	},
+	handle_state::HandleState,
+	query_shard_state::QueryShardState,
 	state_snapshot_repository::VersionedStateAccess,
-	state_snapshot_repository_loader::StateSnapshotRepositoryLoader, StateHandler,
+	state_snapshot_repository_loader::StateSnapshotRepositoryLoader,
+	StateHandler,
 };
 use itp_top_pool::pool::Options as PoolOptions;
-use itp_top_pool_author::author::AuthorTopFilter;
+use itp_top_pool_author::{author::AuthorTopFilter, persistence::TopPoolSeal};
 use itp_types::{parentchain::ParentchainId, ShardIdentifier};
-use its_sidechain::block_composer::BlockComposer;
+use its_primitives::types::{
+	block::SignedBlock as SignedSidechainBlock, justification::Justification,
+	slot_claims::SlotClaimConfig,
+};
+use its_sidechain::{
+	block_composer::BlockComposer,
+	consensus_common::{Error as ConsensusError, FinalizeBlocks, OwnFinalityVotesSeal},
+	validateer_fetch::ValidateerFetch,
+};
 use jsonrpc_core::IoHandler;
 use log::*;
-use sp_core::crypto::Pair;
-use std::{collections::HashMap, path::PathBuf, string::String, sync::Arc};
+use sp_core::{
+	crypto::{Pair, UncheckedFrom},
+	ed25519,
+};
+use std::{
+	collections::HashMap,
+	fs,
+	path::{Path, PathBuf},
+	string::String,
+	sync::Arc,
+};
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
@@ -94,13 +132,21 @@ pub(crate) fn init_enclave(
 	let signer = signing_key_repository.retrieve_key()?;
 	info!("[Enclave initialized] Ed25519 prim raw : {:?}", signer.public().0);
 
-	let shielding_key_repository = Arc::new(get_rsa3072_repository(base_dir.clone())?);
+	let shielding_key_repository = Arc::new(get_rotating_rsa3072_repository(base_dir.clone())?);
 	GLOBAL_SHIELDING_KEY_REPOSITORY_COMPONENT.initialize(shielding_key_repository.clone());
 
+	let x25519_shielding_key_repository =
+		Arc::new(get_rotating_x25519_repository(base_dir.clone())?);
+	GLOBAL_X25519_SHIELDING_KEY_REPOSITORY_COMPONENT
+		.initialize(x25519_shielding_key_repository.clone());
+
 	// Create the aes key that is used for state encryption such that a key is always present in tests.
 	// It will be overwritten anyway if mutual remote attestation is performed with the primary worker.
-	let state_key_repository = Arc::new(get_aes_repository(base_dir.clone())?);
+	let state_key_repository = Arc::new(get_aes_gcm_repository(base_dir.clone())?);
 	GLOBAL_STATE_KEY_REPOSITORY_COMPONENT.initialize(state_key_repository.clone());
+	migrate_legacy_state_key(&base_dir, &state_key_repository)?;
+	complete_interrupted_state_key_rotation(&base_dir, &state_key_repository)?;
+	restore_pending_key_rotation(&base_dir)?;
 
 	let integritee_light_client_seal = Arc::new(EnclaveLightClientSeal::new(
 		base_dir.join(INTEGRITEE_PARENTCHAIN_LIGHT_CLIENT_DB_PATH),
@@ -108,20 +154,11 @@ pub(crate) fn init_enclave(
 	)?);
 	GLOBAL_INTEGRITEE_PARENTCHAIN_LIGHT_CLIENT_SEAL.initialize(integritee_light_client_seal);
 
-	let target_a_light_client_seal = Arc::new(EnclaveLightClientSeal::new(
-		base_dir.join(TARGET_A_PARENTCHAIN_LIGHT_CLIENT_DB_PATH),
-		ParentchainId::TargetA,
-	)?);
-	GLOBAL_TARGET_A_PARENTCHAIN_LIGHT_CLIENT_SEAL.initialize(target_a_light_client_seal);
-
-	let target_b_light_client_seal = Arc::new(EnclaveLightClientSeal::new(
-		base_dir.join(TARGET_B_PARENTCHAIN_LIGHT_CLIENT_DB_PATH),
-		ParentchainId::TargetB,
-	)?);
-	GLOBAL_TARGET_B_PARENTCHAIN_LIGHT_CLIENT_SEAL.initialize(target_b_light_client_seal);
-
-	let state_file_io =
-		Arc::new(EnclaveStateFileIo::new(state_key_repository, StateDir::new(base_dir)));
+	let state_file_io = Arc::new(EnclaveStateFileIo::new_with_checkpoint_interval(
+		state_key_repository,
+		StateDir::new(base_dir.clone()),
+		STATE_SNAPSHOTS_CHECKPOINT_INTERVAL,
+	));
 	let state_initializer =
 		Arc::new(EnclaveStateInitializer::new(shielding_key_repository.clone()));
 	let state_snapshot_repository_loader = StateSnapshotRepositoryLoader::<
@@ -162,6 +199,9 @@ pub(crate) fn init_enclave(
 	let watch_extractor = Arc::new(create_determine_watch::<Hash>());
 
 	let connection_registry = Arc::new(ConnectionRegistry::<Hash, ConnectionToken>::new());
+	let account_subscription_handler =
+		create_account_subscription_handler(connection_registry.clone());
+	GLOBAL_ACCOUNT_SUBSCRIPTION_HANDLER_COMPONENT.initialize(account_subscription_handler.clone());
 
 	// We initialize components for the public RPC / direct invocation server here, so we can start the server
 	// before registering on the parentchain. If we started the RPC AFTER registering on the parentchain and
@@ -170,14 +210,25 @@ pub(crate) fn init_enclave(
 	// validateer completely breaking (IO PipeError).
 	// Corresponding GH issues are #545 and #600.
 
+	let top_pool_persistence =
+		Arc::new(EnclaveTopPoolPersistence::new(TopPoolSeal::new(base_dir.clone())));
 	let top_pool_author = create_top_pool_author(
-		connection_registry.clone(),
-		state_handler,
+		account_subscription_handler.clone(),
+		state_handler.clone(),
 		shielding_key_repository.clone(),
+		x25519_shielding_key_repository.clone(),
+		top_pool_persistence,
 	);
+	// Trusted calls which were pending when the worker stopped are validated again.
+	top_pool_author.restore_persisted_top_pool();
 	GLOBAL_TOP_POOL_AUTHOR_COMPONENT.initialize(top_pool_author.clone());
 
-	let getter_executor = Arc::new(EnclaveGetterExecutor::new(state_observer));
+	let getter_executor =
+		Arc::new(EnclaveGetterExecutor::new(state_observer, GLOBAL_STATE_COMMITMENT_CACHE.clone()));
+
+	let finality_gadget =
+		Arc::new(EnclaveSidechainFinalityGadget::new(OwnFinalityVotesSeal::new(base_dir.clone()))?);
+	GLOBAL_SIDECHAIN_FINALITY_GADGET_COMPONENT.initialize(finality_gadget.clone());
 
 	let mut io_handler = IoHandler::new();
 	add_common_api(
@@ -185,24 +236,49 @@ pub(crate) fn init_enclave(
 		top_pool_author,
 		getter_executor,
 		shielding_key_repository,
+		x25519_shielding_key_repository,
 		ocall_api.clone(),
 		VERSION.into(),
 		GLOBAL_SIDECHAIN_BLOCK_HEADER_CACHE.clone(),
+		finality_gadget,
+	);
+	add_account_events_api(
+		&mut io_handler,
+		account_subscription_handler.clone(),
+		state_handler,
+		ocall_api.clone(),
 	);
 
 	#[cfg(feature = "sidechain")]
 	{
-		use crate::initialization::global_components::EnclaveSidechainBlockImportQueue;
+		use crate::initialization::global_components::{
+			EnclaveSidechainBlockImportQueue, EnclaveSidechainFinalityVoteQueue,
+		};
+		use itp_settings::sidechain::MAX_QUEUED_FINALITY_VOTES;
 		use its_rpc_handler::add_sidechain_api;
 		let sidechain_block_import_queue = Arc::new(EnclaveSidechainBlockImportQueue::default());
 		GLOBAL_SIDECHAIN_IMPORT_QUEUE_COMPONENT.initialize(sidechain_block_import_queue);
+		let finality_vote_queue =
+			Arc::new(EnclaveSidechainFinalityVoteQueue::bounded(MAX_QUEUED_FINALITY_VOTES));
+		GLOBAL_SIDECHAIN_FINALITY_VOTE_QUEUE_COMPONENT.initialize(finality_vote_queue);
 		let sidechain_import_queue = GLOBAL_SIDECHAIN_IMPORT_QUEUE_COMPONENT.get()?;
-		add_sidechain_api(&mut io_handler, sidechain_import_queue);
+		let finality_vote_queue = GLOBAL_SIDECHAIN_FINALITY_VOTE_QUEUE_COMPONENT.get()?;
+		add_sidechain_api(&mut io_handler, sidechain_import_queue, finality_vote_queue);
 	}
 
-	let rpc_handler = Arc::new(RpcWsHandler::new(io_handler, watch_extractor, connection_registry));
+	let rpc_handler = Arc::new(
+		RpcWsHandler::new(io_handler, watch_extractor, connection_registry)
+			.with_closed_connection_observer(account_subscription_handler),
+	);
 	GLOBAL_RPC_WS_HANDLER_COMPONENT.initialize(rpc_handler);
 
+	let dcap_collateral_repository = Arc::new(EnclaveDcapCollateralRepository::new(
+		DcapQuoteVerifier::with_intel_root_ca()?,
+		OCallCollateralSource::new(ocall_api.clone()),
+		CollateralSeal::new(base_dir),
+	));
+	GLOBAL_DCAP_COLLATERAL_REPOSITORY_COMPONENT.initialize(dcap_collateral_repository);
+
 	let attestation_handler =
 		Arc::new(IntelAttestationHandler::new(ocall_api, signing_key_repository));
 	GLOBAL_ATTESTATION_HANDLER_COMPONENT.initialize(attestation_handler);
@@ -210,6 +286,46 @@ pub(crate) fn init_enclave(
 	Ok(())
 }
 
+/// Re-encrypts the state files that are still encrypted with the legacy AES-OFB state key.
+///
+/// The legacy key is removed once all files are migrated, so this only has an effect
+/// on the first start after the upgrade (or after an interrupted migration).
+fn migrate_legacy_state_key(
+	base_dir: &Path,
+	state_key_repository: &EnclaveStateKeyRepository,
+) -> EnclaveResult<()> {
+	let legacy_state_key_seal = AesSeal::new(base_dir.to_path_buf());
+	if !legacy_state_key_seal.exists() {
+		return Ok(())
+	}
+
+	let migrated_files = migrate_legacy_state_encryption(
+		&StateDir::new(base_dir.to_path_buf()),
+		&legacy_state_key_seal.unseal_key()?,
+		&state_key_repository.retrieve_key()?,
+	)?;
+	info!("Re-encrypted {} state file(s) with the AES-GCM state key", migrated_files);
+
+	fs::remove_file(legacy_state_key_seal.path())?;
+	Ok(())
+}
+
+/// Completes the re-encryption of the state files, in case the enclave stopped during a
+/// state key rotation.
+fn complete_interrupted_state_key_rotation(
+	base_dir: &Path,
+	state_key_repository: &EnclaveStateKeyRepository,
+) -> EnclaveResult<()> {
+	let replaced_files = complete_state_reencryption(
+		&StateDir::new(base_dir.to_path_buf()),
+		&state_key_repository.retrieve_key()?,
+	)?;
+	if replaced_files > 0 {
+		info!("Completed interrupted state key rotation of {} state file(s)", replaced_files);
+	}
+	Ok(())
+}
+
 fn initialize_state_observer(
 	snapshot_repository: &EnclaveStateSnapshotRepository,
 ) -> EnclaveResult<Arc<EnclaveStateObserver>> {
@@ -225,7 +341,9 @@ fn initialize_state_observer(
 	Ok(Arc::new(EnclaveStateObserver::from_map(states_map)))
 }
 
-pub(crate) fn init_enclave_sidechain_components() -> EnclaveResult<()> {
+pub(crate) fn init_enclave_sidechain_components(
+	slot_claim_config: SlotClaimConfig,
+) -> EnclaveResult<()> {
 	let state_handler = GLOBAL_STATE_HANDLER_COMPONENT.get()?;
 	let ocall_api = GLOBAL_OCALL_API_COMPONENT.get()?;
 	let top_pool_author = GLOBAL_TOP_POOL_AUTHOR_COMPONENT.get()?;
@@ -236,20 +354,37 @@ pub(crate) fn init_enclave_sidechain_components() -> EnclaveResult<()> {
 
 	let signer = GLOBAL_SIGNING_KEY_REPOSITORY_COMPONENT.get()?.retrieve_key()?;
 
-	let sidechain_block_importer = Arc::new(EnclaveSidechainBlockImporter::new(
-		state_handler,
-		state_key_repository.clone(),
-		top_pool_author,
-		parentchain_block_import_dispatcher,
-		ocall_api.clone(),
-		GLOBAL_SIDECHAIN_BLOCK_HEADER_CACHE.clone(),
-	));
+	let account_event_notifier =
+		Arc::new(AccountEventNotifier::new(GLOBAL_ACCOUNT_SUBSCRIPTION_HANDLER_COMPONENT.get()?));
+
+	let sidechain_block_importer = Arc::new(
+		EnclaveSidechainBlockImporter::new(
+			state_handler.clone(),
+			state_key_repository.clone(),
+			top_pool_author,
+			parentchain_block_import_dispatcher,
+			ocall_api.clone(),
+			GLOBAL_SIDECHAIN_BLOCK_HEADER_CACHE.clone(),
+			GLOBAL_STATE_COMMITMENT_CACHE.clone(),
+		)
+		.with_imported_state_observer(account_event_notifier)
+		.with_imported_state_observer(Arc::new(KeyRotationActivator))
+		.with_slot_claim_config(slot_claim_config),
+	);
 
 	let sidechain_block_import_queue = GLOBAL_SIDECHAIN_IMPORT_QUEUE_COMPONENT.get()?;
 	let metadata_repository = get_node_metadata_repository_from_integritee_solo_or_parachain()?;
 	let extrinsics_factory = get_extrinsic_factory_from_integritee_solo_or_parachain()?;
 	let validator_accessor = get_validator_accessor_from_integritee_solo_or_parachain()?;
 
+	if let Err(e) = resume_sidechain_finality(
+		state_handler.as_ref(),
+		ocall_api.as_ref(),
+		validator_accessor.as_ref(),
+	) {
+		warn!("Could not resume the sidechain finality from the storage: {:?}", e);
+	}
+
 	let sidechain_block_import_confirmation_handler =
 		Arc::new(EnclaveBlockImportConfirmationHandler::new(
 			metadata_repository,
@@ -271,9 +406,48 @@ pub(crate) fn init_enclave_sidechain_components() -> EnclaveResult<()> {
 		));
 	GLOBAL_SIDECHAIN_IMPORT_QUEUE_WORKER_COMPONENT.initialize(sidechain_block_import_queue_worker);
 
-	let block_composer = Arc::new(BlockComposer::new(signer, state_key_repository));
+	let block_composer = Arc::new(BlockComposer::new(
+		signer,
+		state_key_repository,
+		GLOBAL_STATE_COMMITMENT_CACHE.clone(),
+	));
 	GLOBAL_SIDECHAIN_BLOCK_COMPOSER_COMPONENT.initialize(block_composer);
 
+	GLOBAL_SLOT_CLAIM_CONFIG_COMPONENT.initialize(Arc::new(slot_claim_config));
+
+	Ok(())
+}
+
+/// Resume the finality gadget from the finalized heads of our shards in the sidechain storage.
+fn resume_sidechain_finality(
+	state_handler: &EnclaveStateHandler,
+	ocall_api: &OcallApi,
+	validator_accessor: &EnclaveValidatorAccessor,
+) -> EnclaveResult<()> {
+	let finality_gadget = GLOBAL_SIDECHAIN_FINALITY_GADGET_COMPONENT.get()?;
+	let parentchain_header =
+		validator_accessor.execute_on_validator(|v| v.latest_finalized_header())?;
+
+	for shard in state_handler.list_shards()? {
+		let justification =
+			match ocall_api.fetch_sidechain_finalized_head::<Justification>(shard)? {
+				Some(justification) => justification,
+				None => continue,
+			};
+		let validateers: Vec<ed25519::Public> = ocall_api
+			.current_validateers::<_, SignedSidechainBlock>(&parentchain_header, shard)
+			.map_err(|e| ConsensusError::CouldNotGetAuthorities(e.to_string()))?
+			.iter()
+			.map(|account| ed25519::Public::unchecked_from(*account.as_ref()))
+			.collect();
+
+		let block_number = justification.block_number();
+		if finality_gadget.import_justification(justification, &validateers)? {
+			info!("Resumed sidechain finality of shard {:?} at block {}", shard, block_number);
+		} else {
+			warn!("Finalized head of shard {:?} in the sidechain storage is not valid", shard);
+		}
+	}
 	Ok(())
 }
 
@@ -310,23 +484,42 @@ pub(crate) fn init_shard(shard: ShardIdentifier) -> EnclaveResult<()> {
 	Ok(())
 }
 
+/// Initialize the handler of the account subscriptions, which sends the status updates
+/// of the trusted operations to the RPC clients.
+pub fn create_account_subscription_handler(
+	connection_registry: Arc<EnclaveRpcConnectionRegistry>,
+) -> Arc<EnclaveAccountSubscriptionHandler> {
+	let response_channel = Arc::new(RpcResponseChannel::default());
+	let rpc_responder = Arc::new(EnclaveRpcResponder::new(connection_registry, response_channel));
+	Arc::new(EnclaveAccountSubscriptionHandler::new(rpc_responder))
+}
+
 /// Initialize the TOP pool author component.
 pub fn create_top_pool_author(
-	connection_registry: Arc<EnclaveRpcConnectionRegistry>,
+	account_subscription_handler: Arc<EnclaveAccountSubscriptionHandler>,
 	state_handler: Arc<EnclaveStateHandler>,
 	shielding_key_repository: Arc<EnclaveShieldingKeyRepository>,
+	x25519_shielding_key_repository: Arc<EnclaveX25519ShieldingKeyRepository>,
+	top_pool_persistence: Arc<EnclaveTopPoolPersistence>,
 ) -> Arc<EnclaveTopPoolAuthor> {
-	let response_channel = Arc::new(RpcResponseChannel::default());
-	let rpc_responder = Arc::new(EnclaveRpcResponder::new(connection_registry, response_channel));
-
 	let side_chain_api = Arc::new(EnclaveSidechainApi::new());
-	let top_pool =
-		Arc::new(EnclaveTopPool::create(PoolOptions::default(), side_chain_api, rpc_responder));
+	let top_pool = Arc::new(EnclaveTopPool::create(
+		PoolOptions::default(),
+		side_chain_api,
+		account_subscription_handler.clone(),
+	));
 
-	Arc::new(EnclaveTopPoolAuthor::new(
-		top_pool,
-		AuthorTopFilter::<TrustedCallSigned, Getter>::new(),
-		state_handler,
-		shielding_key_repository,
-	))
+	Arc::new(
+		EnclaveTopPoolAuthor::new(
+			top_pool,
+			AuthorTopFilter::<TrustedCallSigned, Getter>::new(),
+			state_handler,
+			shielding_key_repository.clone(),
+		)
+		.with_x25519_shielding_key(x25519_shielding_key_repository.clone())
+		.with_retired_x25519_shielding_key(x25519_shielding_key_repository)
+		.with_retired_shielding_key(shielding_key_repository)
+		.with_persistence(top_pool_persistence)
+		.with_account_operation_watcher(account_subscription_handler),
+	)
 }
@@ -16,28 +16,25 @@
 */
 
 use crate::{
-	error::Result,
+	error::{Error, Result},
 	initialization::{
 		global_components::{
-			EnclaveExtrinsicsFactory, EnclaveNodeMetadataRepository, EnclaveOffchainWorkerExecutor,
-			EnclaveParentchainSigner, EnclaveStfExecutor, EnclaveValidatorAccessor,
-			IntegriteeParentchainBlockImportDispatcher, IntegriteeParentchainBlockImportQueue,
-			IntegriteeParentchainBlockImporter, IntegriteeParentchainEventImportQueue,
+			EnclaveExtrinsicsFactory, EnclaveLightClientSeal, EnclaveNodeMetadataRepository,
+			EnclaveOffchainWorkerExecutor, EnclaveParentchainSigner, EnclaveStfExecutor,
+			EnclaveValidatorAccessor, IntegriteeParentchainBlockImportDispatcher,
+			IntegriteeParentchainBlockImportQueue, IntegriteeParentchainBlockImporter,
+			IntegriteeParentchainEventImportQueue,
 			IntegriteeParentchainImmediateBlockImportDispatcher,
 			IntegriteeParentchainIndirectCallsExecutor,
 			IntegriteeParentchainTriggeredBlockImportDispatcher,
-			TargetAParentchainBlockImportDispatcher, TargetAParentchainBlockImportQueue,
-			TargetAParentchainBlockImporter, TargetAParentchainEventImportQueue,
-			TargetAParentchainImmediateBlockImportDispatcher,
-			TargetAParentchainIndirectCallsExecutor,
-			TargetAParentchainTriggeredBlockImportDispatcher,
-			TargetBParentchainBlockImportDispatcher, TargetBParentchainBlockImportQueue,
-			TargetBParentchainBlockImporter, TargetBParentchainEventImportQueue,
-			TargetBParentchainImmediateBlockImportDispatcher,
-			TargetBParentchainIndirectCallsExecutor,
-			TargetBParentchainTriggeredBlockImportDispatcher, GLOBAL_OCALL_API_COMPONENT,
-			GLOBAL_SHIELDING_KEY_REPOSITORY_COMPONENT, GLOBAL_SIGNING_KEY_REPOSITORY_COMPONENT,
-			GLOBAL_STATE_HANDLER_COMPONENT, GLOBAL_STATE_OBSERVER_COMPONENT,
+			TargetParentchainBlockImportDispatcher, TargetParentchainBlockImportQueue,
+			TargetParentchainBlockImporter, TargetParentchainEventImportQueue,
+			TargetParentchainImmediateBlockImportDispatcher,
+			TargetParentchainIndirectCallsExecutor,
+			TargetParentchainTriggeredBlockImportDispatcher, GLOBAL_OCALL_API_COMPONENT,
+			GLOBAL_OFFCHAIN_TASK_SCHEDULER, GLOBAL_SHIELDING_KEY_REPOSITORY_COMPONENT,
+			GLOBAL_SIGNING_KEY_REPOSITORY_COMPONENT, GLOBAL_STATE_HANDLER_COMPONENT,
+			GLOBAL_STATE_OBSERVER_COMPONENT, GLOBAL_TARGET_PARENTCHAIN_LIGHT_CLIENT_SEALS,
 			GLOBAL_TOP_POOL_AUTHOR_COMPONENT,
 		},
 		EnclaveStfEnclaveSigner,
@@ -45,12 +42,16 @@ use crate::{
 };
 use itp_component_container::ComponentGetter;
 use itp_nonce_cache::NonceCache;
+use itp_settings::files::{
+	TARGET_A_PARENTCHAIN_LIGHT_CLIENT_DB_PATH, TARGET_B_PARENTCHAIN_LIGHT_CLIENT_DB_PATH,
+	TARGET_PARENTCHAIN_LIGHT_CLIENT_DB_PATH_PREFIX,
+};
 use itp_sgx_crypto::key_repository::AccessKey;
 use itp_stf_interface::ShardCreationInfo;
-use itp_types::parentchain::ParentchainId;
+use itp_types::parentchain::{ParentchainId, TargetParentchainIndex};
 use log::*;
 use sp_core::H256;
-use std::sync::Arc;
+use std::{format, path::Path, string::String, sync::Arc};
 
 pub(crate) fn create_integritee_parentchain_block_importer(
 	validator_access: Arc<EnclaveValidatorAccessor>,
@@ -70,13 +71,16 @@ pub(crate) fn create_integritee_parentchain_block_importer(
 		shielding_key_repository.clone(),
 		top_pool_author.clone(),
 	));
-	let indirect_calls_executor = Arc::new(IntegriteeParentchainIndirectCallsExecutor::new(
-		shielding_key_repository,
-		stf_enclave_signer,
-		top_pool_author,
-		node_metadata_repository,
-		ParentchainId::Integritee,
-	));
+	let indirect_calls_executor = Arc::new(
+		IntegriteeParentchainIndirectCallsExecutor::new(
+			shielding_key_repository.clone(),
+			stf_enclave_signer,
+			top_pool_author,
+			node_metadata_repository,
+			ParentchainId::Integritee,
+		)
+		.with_retired_shielding_key(shielding_key_repository),
+	);
 	Ok(IntegriteeParentchainBlockImporter::new(
 		validator_access,
 		stf_executor,
@@ -87,13 +91,14 @@ pub(crate) fn create_integritee_parentchain_block_importer(
 	))
 }
 
-pub(crate) fn create_target_a_parentchain_block_importer(
+pub(crate) fn create_target_parentchain_block_importer(
+	index: TargetParentchainIndex,
 	validator_access: Arc<EnclaveValidatorAccessor>,
 	stf_executor: Arc<EnclaveStfExecutor>,
 	extrinsics_factory: Arc<EnclaveExtrinsicsFactory>,
 	node_metadata_repository: Arc<EnclaveNodeMetadataRepository>,
 	shard_creation_info: ShardCreationInfo,
-) -> Result<TargetAParentchainBlockImporter> {
+) -> Result<TargetParentchainBlockImporter> {
 	let state_observer = GLOBAL_STATE_OBSERVER_COMPONENT.get()?;
 	let top_pool_author = GLOBAL_TOP_POOL_AUTHOR_COMPONENT.get()?;
 	let shielding_key_repository = GLOBAL_SHIELDING_KEY_REPOSITORY_COMPONENT.get()?;
@@ -105,55 +110,23 @@ pub(crate) fn create_target_a_parentchain_block_importer(
 		shielding_key_repository.clone(),
 		top_pool_author.clone(),
 	));
-	let indirect_calls_executor = Arc::new(TargetAParentchainIndirectCallsExecutor::new(
-		shielding_key_repository,
-		stf_enclave_signer,
-		top_pool_author,
-		node_metadata_repository,
-		ParentchainId::TargetA,
-	));
-	Ok(TargetAParentchainBlockImporter::new(
-		validator_access,
-		stf_executor,
-		extrinsics_factory,
-		indirect_calls_executor,
-		shard_creation_info,
-		ParentchainId::TargetA,
-	))
-}
-
-pub(crate) fn create_target_b_parentchain_block_importer(
-	validator_access: Arc<EnclaveValidatorAccessor>,
-	stf_executor: Arc<EnclaveStfExecutor>,
-	extrinsics_factory: Arc<EnclaveExtrinsicsFactory>,
-	node_metadata_repository: Arc<EnclaveNodeMetadataRepository>,
-	shard_creation_info: ShardCreationInfo,
-) -> Result<TargetBParentchainBlockImporter> {
-	let state_observer = GLOBAL_STATE_OBSERVER_COMPONENT.get()?;
-	let top_pool_author = GLOBAL_TOP_POOL_AUTHOR_COMPONENT.get()?;
-	let shielding_key_repository = GLOBAL_SHIELDING_KEY_REPOSITORY_COMPONENT.get()?;
-	let ocall_api = GLOBAL_OCALL_API_COMPONENT.get()?;
-
-	let stf_enclave_signer = Arc::new(EnclaveStfEnclaveSigner::new(
-		state_observer,
-		ocall_api,
-		shielding_key_repository.clone(),
-		top_pool_author.clone(),
-	));
-	let indirect_calls_executor = Arc::new(TargetBParentchainIndirectCallsExecutor::new(
-		shielding_key_repository,
-		stf_enclave_signer,
-		top_pool_author,
-		node_metadata_repository,
-		ParentchainId::TargetB,
-	));
-	Ok(TargetBParentchainBlockImporter::new(
+	let indirect_calls_executor = Arc::new(
+		TargetParentchainIndirectCallsExecutor::new(
+			shielding_key_repository.clone(),
+			stf_enclave_signer,
+			top_pool_author,
+			node_metadata_repository,
+			ParentchainId::Target(index),
+		)
+		.with_retired_shielding_key(shielding_key_repository),
+	);
+	Ok(TargetParentchainBlockImporter::new(
 		validator_access,
 		stf_executor,
 		extrinsics_factory,
 		indirect_calls_executor,
 		shard_creation_info,
-		ParentchainId::TargetB,
+		ParentchainId::Target(index),
 	))
 }
 
@@ -181,13 +154,17 @@ pub(crate) fn create_integritee_offchain_immediate_import_dispatcher(
 	let state_handler = GLOBAL_STATE_HANDLER_COMPONENT.get()?;
 	let top_pool_author = GLOBAL_TOP_POOL_AUTHOR_COMPONENT.get()?;
 
-	let offchain_worker_executor = Arc::new(EnclaveOffchainWorkerExecutor::new(
-		top_pool_author,
-		stf_executor,
-		state_handler,
-		validator_access,
-		extrinsics_factory,
-	));
+	// The periods of the offchain tasks are counted in blocks of the Integritee parentchain.
+	let offchain_worker_executor = Arc::new(
+		EnclaveOffchainWorkerExecutor::new(
+			top_pool_author,
+			stf_executor,
+			state_handler,
+			validator_access,
+			extrinsics_factory,
+		)
+		.with_task_scheduler(GLOBAL_OFFCHAIN_TASK_SCHEDULER.clone()),
+	);
 	let immediate_dispatcher = IntegriteeParentchainImmediateBlockImportDispatcher::new(
 		block_importer,
 	)
@@ -202,42 +179,12 @@ pub(crate) fn create_integritee_offchain_immediate_import_dispatcher(
 	))))
 }
 
-pub(crate) fn create_target_a_offchain_immediate_import_dispatcher(
-	stf_executor: Arc<EnclaveStfExecutor>,
-	block_importer: TargetAParentchainBlockImporter,
-	validator_access: Arc<EnclaveValidatorAccessor>,
-	extrinsics_factory: Arc<EnclaveExtrinsicsFactory>,
-) -> Result<Arc<TargetAParentchainBlockImportDispatcher>> {
-	let state_handler = GLOBAL_STATE_HANDLER_COMPONENT.get()?;
-	let top_pool_author = GLOBAL_TOP_POOL_AUTHOR_COMPONENT.get()?;
-
-	let offchain_worker_executor = Arc::new(EnclaveOffchainWorkerExecutor::new(
-		top_pool_author,
-		stf_executor,
-		state_handler,
-		validator_access,
-		extrinsics_factory,
-	));
-	let immediate_dispatcher = TargetAParentchainImmediateBlockImportDispatcher::new(
-		block_importer,
-	)
-	.with_observer(move || {
-		if let Err(e) = offchain_worker_executor.execute() {
-			error!("Failed to execute trusted calls: {:?}", e);
-		}
-	});
-
-	Ok(Arc::new(TargetAParentchainBlockImportDispatcher::new_immediate_dispatcher(Arc::new(
-		immediate_dispatcher,
-	))))
-}
-
-pub(crate) fn create_target_b_offchain_immediate_import_dispatcher(
+pub(crate) fn create_target_offchain_immediate_import_dispatcher(
 	stf_executor: Arc<EnclaveStfExecutor>,
-	block_importer: TargetBParentchainBlockImporter,
+	block_importer: TargetParentchainBlockImporter,
 	validator_access: Arc<EnclaveValidatorAccessor>,
 	extrinsics_factory: Arc<EnclaveExtrinsicsFactory>,
-) -> Result<Arc<TargetBParentchainBlockImportDispatcher>> {
+) -> Result<Arc<TargetParentchainBlockImportDispatcher>> {
 	let state_handler = GLOBAL_STATE_HANDLER_COMPONENT.get()?;
 	let top_pool_author = GLOBAL_TOP_POOL_AUTHOR_COMPONENT.get()?;
 
@@ -248,16 +195,14 @@ pub(crate) fn create_target_b_offchain_immediate_import_dispatcher(
 		validator_access,
 		extrinsics_factory,
 	));
-	let immediate_dispatcher = TargetBParentchainImmediateBlockImportDispatcher::new(
-		block_importer,
-	)
-	.with_observer(move || {
-		if let Err(e) = offchain_worker_executor.execute() {
-			error!("Failed to execute trusted calls: {:?}", e);
-		}
-	});
+	let immediate_dispatcher = TargetParentchainImmediateBlockImportDispatcher::new(block_importer)
+		.with_observer(move || {
+			if let Err(e) = offchain_worker_executor.execute() {
+				error!("Failed to execute trusted calls: {:?}", e);
+			}
+		});
 
-	Ok(Arc::new(TargetBParentchainBlockImportDispatcher::new_immediate_dispatcher(Arc::new(
+	Ok(Arc::new(TargetParentchainBlockImportDispatcher::new_immediate_dispatcher(Arc::new(
 		immediate_dispatcher,
 	))))
 }
@@ -277,32 +222,45 @@ pub(crate) fn create_sidechain_triggered_import_dispatcher(
 	)))
 }
 
-pub(crate) fn create_sidechain_triggered_import_dispatcher_for_target_a(
-	block_importer: TargetAParentchainBlockImporter,
-) -> Arc<TargetAParentchainBlockImportDispatcher> {
-	let parentchain_block_import_queue = TargetAParentchainBlockImportQueue::default();
-	let parentchain_event_import_queue = TargetAParentchainEventImportQueue::default();
-	let triggered_dispatcher = TargetAParentchainTriggeredBlockImportDispatcher::new(
+pub(crate) fn create_sidechain_triggered_import_dispatcher_for_target(
+	block_importer: TargetParentchainBlockImporter,
+) -> Arc<TargetParentchainBlockImportDispatcher> {
+	let parentchain_block_import_queue = TargetParentchainBlockImportQueue::default();
+	let parentchain_event_import_queue = TargetParentchainEventImportQueue::default();
+	let triggered_dispatcher = TargetParentchainTriggeredBlockImportDispatcher::new(
 		block_importer,
 		parentchain_block_import_queue,
 		parentchain_event_import_queue,
 	);
-	Arc::new(TargetAParentchainBlockImportDispatcher::new_triggered_dispatcher(Arc::new(
+	Arc::new(TargetParentchainBlockImportDispatcher::new_triggered_dispatcher(Arc::new(
 		triggered_dispatcher,
 	)))
 }
 
-pub(crate) fn create_sidechain_triggered_import_dispatcher_for_target_b(
-	block_importer: TargetBParentchainBlockImporter,
-) -> Arc<TargetBParentchainBlockImportDispatcher> {
-	let parentchain_block_import_queue = TargetBParentchainBlockImportQueue::default();
-	let parentchain_event_import_queue = TargetBParentchainEventImportQueue::default();
-	let triggered_dispatcher = TargetBParentchainTriggeredBlockImportDispatcher::new(
-		block_importer,
-		parentchain_block_import_queue,
-		parentchain_event_import_queue,
-	);
-	Arc::new(TargetBParentchainBlockImportDispatcher::new_triggered_dispatcher(Arc::new(
-		triggered_dispatcher,
-	)))
+/// Returns the light client seal of a target parentchain, which is created on first access.
+pub(crate) fn get_or_init_target_light_client_seal(
+	base_path: &Path,
+	index: TargetParentchainIndex,
+) -> Result<Arc<EnclaveLightClientSeal>> {
+	let mut seals = GLOBAL_TARGET_PARENTCHAIN_LIGHT_CLIENT_SEALS
+		.write()
+		.map_err(|_| Error::MutexAccess)?;
+	if let Some(seal) = seals.get(&index) {
+		return Ok(seal.clone())
+	}
+	let seal = Arc::new(EnclaveLightClientSeal::new(
+		base_path.join(target_light_client_db_path(index)),
+		ParentchainId::Target(index),
+	)?);
+	seals.insert(index, seal.clone());
+	Ok(seal)
+}
+
+/// The first two target parentchains keep the light client db paths of Target A and B.
+fn target_light_client_db_path(index: TargetParentchainIndex) -> String {
+	match index {
+		0 => TARGET_A_PARENTCHAIN_LIGHT_CLIENT_DB_PATH.into(),
+		1 => TARGET_B_PARENTCHAIN_LIGHT_CLIENT_DB_PATH.into(),
+		_ => format!("{}{}", TARGET_PARENTCHAIN_LIGHT_CLIENT_DB_PATH_PREFIX, index),
+	}
 }
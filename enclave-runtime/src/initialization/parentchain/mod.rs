@@ -16,21 +16,15 @@
 */
 
 use crate::{
-	error::Result,
+	error::{Error, Result},
 	initialization::{
 		global_components::{
 			GLOBAL_INTEGRITEE_PARACHAIN_HANDLER_COMPONENT,
 			GLOBAL_INTEGRITEE_SOLOCHAIN_HANDLER_COMPONENT,
-			GLOBAL_TARGET_A_PARACHAIN_HANDLER_COMPONENT,
-			GLOBAL_TARGET_A_SOLOCHAIN_HANDLER_COMPONENT,
-			GLOBAL_TARGET_B_PARACHAIN_HANDLER_COMPONENT,
-			GLOBAL_TARGET_B_SOLOCHAIN_HANDLER_COMPONENT,
+			GLOBAL_TARGET_PARACHAIN_HANDLER_COMPONENTS, GLOBAL_TARGET_SOLOCHAIN_HANDLER_COMPONENTS,
 		},
 		parentchain::{
-			target_a_parachain::TargetAParachainHandler,
-			target_a_solochain::TargetASolochainHandler,
-			target_b_parachain::TargetBParachainHandler,
-			target_b_solochain::TargetBSolochainHandler,
+			target_parachain::TargetParachainHandler, target_solochain::TargetSolochainHandler,
 		},
 	},
 	shard_creation_info::get_shard_creation_info_internal,
@@ -50,10 +44,8 @@ use std::{path::PathBuf, vec::Vec};
 mod common;
 pub mod integritee_parachain;
 pub mod integritee_solochain;
-pub mod target_a_parachain;
-pub mod target_a_solochain;
-pub mod target_b_parachain;
-pub mod target_b_solochain;
+pub mod target_parachain;
+pub mod target_solochain;
 
 pub(crate) fn init_parentchain_components<WorkerModeProvider: ProvideWorkerMode>(
 	base_path: PathBuf,
@@ -67,7 +59,7 @@ pub(crate) fn init_parentchain_components<WorkerModeProvider: ProvideWorkerMode>
 			);
 			let shard_creation_info = get_shard_creation_info_internal(shard)?;
 
-			// todo: query timestamp of creation header to give a creation reference to target_a/b as well in order to fast-sync
+			// todo: query timestamp of creation header to give a creation reference to the target parentchains as well in order to fast-sync
 			match id {
 				ParentchainId::Integritee => {
 					let handler = IntegriteeParachainHandler::init::<WorkerModeProvider>(
@@ -81,28 +73,20 @@ pub(crate) fn init_parentchain_components<WorkerModeProvider: ProvideWorkerMode>
 					GLOBAL_INTEGRITEE_PARACHAIN_HANDLER_COMPONENT.initialize(handler.into());
 					Ok(header.encode())
 				},
-				ParentchainId::TargetA => {
-					let handler = TargetAParachainHandler::init::<WorkerModeProvider>(
+				ParentchainId::Target(index) => {
+					let handler = TargetParachainHandler::init::<WorkerModeProvider>(
 						base_path,
+						index,
 						params,
 						shard_creation_info,
 					)?;
 					let header = handler
 						.validator_accessor
 						.execute_on_validator(|v| v.latest_finalized_header())?;
-					GLOBAL_TARGET_A_PARACHAIN_HANDLER_COMPONENT.initialize(handler.into());
-					Ok(header.encode())
-				},
-				ParentchainId::TargetB => {
-					let handler = TargetBParachainHandler::init::<WorkerModeProvider>(
-						base_path,
-						params,
-						shard_creation_info,
-					)?;
-					let header = handler
-						.validator_accessor
-						.execute_on_validator(|v| v.latest_finalized_header())?;
-					GLOBAL_TARGET_B_PARACHAIN_HANDLER_COMPONENT.initialize(handler.into());
+					GLOBAL_TARGET_PARACHAIN_HANDLER_COMPONENTS
+						.write()
+						.map_err(|_| Error::MutexAccess)?
+						.insert(index, handler.into());
 					Ok(header.encode())
 				},
 			}
@@ -113,7 +97,7 @@ pub(crate) fn init_parentchain_components<WorkerModeProvider: ProvideWorkerMode>
 				id, shard
 			);
 			let shard_creation_info = get_shard_creation_info_internal(shard)?;
-			// todo: query timestamp of creation header to give a creation reference to target_a/b as well in order to fast-sync
+			// todo: query timestamp of creation header to give a creation reference to the target parentchains as well in order to fast-sync
 			match id {
 				ParentchainId::Integritee => {
 					let handler = IntegriteeSolochainHandler::init::<WorkerModeProvider>(
@@ -127,28 +111,20 @@ pub(crate) fn init_parentchain_components<WorkerModeProvider: ProvideWorkerMode>
 					GLOBAL_INTEGRITEE_SOLOCHAIN_HANDLER_COMPONENT.initialize(handler.into());
 					Ok(header.encode())
 				},
-				ParentchainId::TargetA => {
-					let handler = TargetASolochainHandler::init::<WorkerModeProvider>(
-						base_path,
-						params,
-						shard_creation_info,
-					)?;
-					let header = handler
-						.validator_accessor
-						.execute_on_validator(|v| v.latest_finalized_header())?;
-					GLOBAL_TARGET_A_SOLOCHAIN_HANDLER_COMPONENT.initialize(handler.into());
-					Ok(header.encode())
-				},
-				ParentchainId::TargetB => {
-					let handler = TargetBSolochainHandler::init::<WorkerModeProvider>(
+				ParentchainId::Target(index) => {
+					let handler = TargetSolochainHandler::init::<WorkerModeProvider>(
 						base_path,
+						index,
 						params,
 						shard_creation_info,
 					)?;
 					let header = handler
 						.validator_accessor
 						.execute_on_validator(|v| v.latest_finalized_header())?;
-					GLOBAL_TARGET_B_SOLOCHAIN_HANDLER_COMPONENT.initialize(handler.into());
+					GLOBAL_TARGET_SOLOCHAIN_HANDLER_COMPONENTS
+						.write()
+						.map_err(|_| Error::MutexAccess)?
+						.insert(index, handler.into());
 					Ok(header.encode())
 				},
 			}
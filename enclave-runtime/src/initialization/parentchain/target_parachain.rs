@@ -15,49 +15,44 @@
 
 */
 
-//! Naive implementation of adding a second parachain handler to the setup.
-//!
-//! Ideally, most of the redundant code can be abstracted away, but it turns out
-//! that this is quite tedious, so for now this is a copy-past of the [IntegriteeParachainHandler]:
-//! * https://github.com/integritee-network/worker/issues/1417
-
 use crate::{
 	error::Result,
 	initialization::{
 		global_components::{
 			EnclaveExtrinsicsFactory, EnclaveNodeMetadataRepository, EnclaveOCallApi,
-			EnclaveStfExecutor, EnclaveValidatorAccessor, TargetBParentchainBlockImportDispatcher,
+			EnclaveStfExecutor, EnclaveValidatorAccessor, TargetParentchainBlockImportDispatcher,
 			GLOBAL_OCALL_API_COMPONENT, GLOBAL_STATE_HANDLER_COMPONENT,
-			GLOBAL_TARGET_B_PARENTCHAIN_LIGHT_CLIENT_SEAL, GLOBAL_TARGET_B_PARENTCHAIN_NONCE_CACHE,
 		},
 		parentchain::common::{
-			create_extrinsics_factory, create_sidechain_triggered_import_dispatcher_for_target_b,
-			create_target_b_offchain_immediate_import_dispatcher,
-			create_target_b_parentchain_block_importer,
+			create_extrinsics_factory, create_sidechain_triggered_import_dispatcher_for_target,
+			create_target_offchain_immediate_import_dispatcher,
+			create_target_parentchain_block_importer, get_or_init_target_light_client_seal,
 		},
 	},
+	utils::get_target_parentchain_nonce_cache,
 };
 use itc_parentchain::light_client::{concurrent_access::ValidatorAccess, LightClientState};
 pub use itc_parentchain::primitives::{ParachainBlock, ParachainHeader, ParachainParams};
 use itp_component_container::ComponentGetter;
 use itp_settings::worker_mode::{ProvideWorkerMode, WorkerMode};
 use itp_stf_interface::ShardCreationInfo;
-use itp_types::parentchain::ParentchainId;
+use itp_types::parentchain::{ParentchainId, TargetParentchainIndex};
 use std::{path::PathBuf, sync::Arc};
 
 #[derive(Clone)]
-pub struct TargetBParachainHandler {
+pub struct TargetParachainHandler {
 	pub genesis_header: ParachainHeader,
 	pub node_metadata_repository: Arc<EnclaveNodeMetadataRepository>,
 	pub stf_executor: Arc<EnclaveStfExecutor>,
 	pub validator_accessor: Arc<EnclaveValidatorAccessor>,
 	pub extrinsics_factory: Arc<EnclaveExtrinsicsFactory>,
-	pub import_dispatcher: Arc<TargetBParentchainBlockImportDispatcher>,
+	pub import_dispatcher: Arc<TargetParentchainBlockImportDispatcher>,
 }
 
-impl TargetBParachainHandler {
+impl TargetParachainHandler {
 	pub fn init<WorkerModeProvider: ProvideWorkerMode>(
-		_base_path: PathBuf,
+		base_path: PathBuf,
+		index: TargetParentchainIndex,
 		params: ParachainParams,
 		shard_creation_info: ShardCreationInfo,
 	) -> Result<Self> {
@@ -67,12 +62,13 @@ impl TargetBParachainHandler {
 
 		let genesis_header = params.genesis_header.clone();
 
-		let light_client_seal = GLOBAL_TARGET_B_PARENTCHAIN_LIGHT_CLIENT_SEAL.get()?;
-		let validator = itc_parentchain::light_client::io::read_or_init_parachain_validator::<
-			ParachainBlock,
-			EnclaveOCallApi,
-			_,
-		>(params, ocall_api.clone(), &*light_client_seal, ParentchainId::TargetB)?;
+		let light_client_seal = get_or_init_target_light_client_seal(&base_path, index)?;
+		let validator =
+			itc_parentchain::light_client::io::read_or_init_parachain_validator::<
+				ParachainBlock,
+				EnclaveOCallApi,
+				_,
+			>(params, ocall_api.clone(), &*light_client_seal, ParentchainId::Target(index))?;
 		let validator_accessor =
 			Arc::new(EnclaveValidatorAccessor::new(validator, light_client_seal));
 
@@ -80,7 +76,7 @@ impl TargetBParachainHandler {
 
 		let extrinsics_factory = create_extrinsics_factory(
 			genesis_hash,
-			GLOBAL_TARGET_B_PARENTCHAIN_NONCE_CACHE.clone(),
+			get_target_parentchain_nonce_cache(index)?,
 			node_metadata_repository.clone(),
 		)?;
 
@@ -90,7 +86,8 @@ impl TargetBParachainHandler {
 			node_metadata_repository.clone(),
 		));
 
-		let block_importer = create_target_b_parentchain_block_importer(
+		let block_importer = create_target_parentchain_block_importer(
+			index,
 			validator_accessor.clone(),
 			stf_executor.clone(),
 			extrinsics_factory.clone(),
@@ -99,16 +96,16 @@ impl TargetBParachainHandler {
 		)?;
 
 		let import_dispatcher = match WorkerModeProvider::worker_mode() {
-			WorkerMode::OffChainWorker => create_target_b_offchain_immediate_import_dispatcher(
+			WorkerMode::OffChainWorker => create_target_offchain_immediate_import_dispatcher(
 				stf_executor.clone(),
 				block_importer,
 				validator_accessor.clone(),
 				extrinsics_factory.clone(),
 			)?,
 			WorkerMode::Sidechain =>
-				create_sidechain_triggered_import_dispatcher_for_target_b(block_importer),
+				create_sidechain_triggered_import_dispatcher_for_target(block_importer),
 			WorkerMode::Teeracle =>
-				Arc::new(TargetBParentchainBlockImportDispatcher::new_empty_dispatcher()),
+				Arc::new(TargetParentchainBlockImportDispatcher::new_empty_dispatcher()),
 		};
 
 		let parachain_handler = Self {
@@ -20,52 +20,54 @@ use crate::{
 	initialization::{
 		global_components::{
 			EnclaveExtrinsicsFactory, EnclaveNodeMetadataRepository, EnclaveOCallApi,
-			EnclaveStfExecutor, EnclaveValidatorAccessor, TargetBParentchainBlockImportDispatcher,
+			EnclaveStfExecutor, EnclaveValidatorAccessor, TargetParentchainBlockImportDispatcher,
 			GLOBAL_OCALL_API_COMPONENT, GLOBAL_STATE_HANDLER_COMPONENT,
-			GLOBAL_TARGET_B_PARENTCHAIN_LIGHT_CLIENT_SEAL, GLOBAL_TARGET_B_PARENTCHAIN_NONCE_CACHE,
 		},
 		parentchain::common::{
-			create_extrinsics_factory, create_sidechain_triggered_import_dispatcher_for_target_b,
-			create_target_b_offchain_immediate_import_dispatcher,
-			create_target_b_parentchain_block_importer,
+			create_extrinsics_factory, create_sidechain_triggered_import_dispatcher_for_target,
+			create_target_offchain_immediate_import_dispatcher,
+			create_target_parentchain_block_importer, get_or_init_target_light_client_seal,
 		},
 	},
+	utils::get_target_parentchain_nonce_cache,
 };
 use itc_parentchain::light_client::{concurrent_access::ValidatorAccess, LightClientState};
 pub use itc_parentchain::primitives::{SolochainBlock, SolochainHeader, SolochainParams};
 use itp_component_container::ComponentGetter;
 use itp_settings::worker_mode::{ProvideWorkerMode, WorkerMode};
 use itp_stf_interface::ShardCreationInfo;
-use itp_types::parentchain::ParentchainId;
+use itp_types::parentchain::{ParentchainId, TargetParentchainIndex};
 use std::{path::PathBuf, sync::Arc};
 
-pub struct TargetBSolochainHandler {
+pub struct TargetSolochainHandler {
 	pub genesis_header: SolochainHeader,
 	pub node_metadata_repository: Arc<EnclaveNodeMetadataRepository>,
 	pub stf_executor: Arc<EnclaveStfExecutor>,
 	pub validator_accessor: Arc<EnclaveValidatorAccessor>,
 	pub extrinsics_factory: Arc<EnclaveExtrinsicsFactory>,
-	pub import_dispatcher: Arc<TargetBParentchainBlockImportDispatcher>,
+	pub import_dispatcher: Arc<TargetParentchainBlockImportDispatcher>,
 }
 
-impl TargetBSolochainHandler {
+impl TargetSolochainHandler {
 	pub fn init<WorkerModeProvider: ProvideWorkerMode>(
-		_base_path: PathBuf,
+		base_path: PathBuf,
+		index: TargetParentchainIndex,
 		params: SolochainParams,
 		shard_creation_info: ShardCreationInfo,
 	) -> Result<Self> {
 		let ocall_api = GLOBAL_OCALL_API_COMPONENT.get()?;
 		let state_handler = GLOBAL_STATE_HANDLER_COMPONENT.get()?;
-		let light_client_seal = GLOBAL_TARGET_B_PARENTCHAIN_LIGHT_CLIENT_SEAL.get()?;
+		let light_client_seal = get_or_init_target_light_client_seal(&base_path, index)?;
 		let node_metadata_repository = Arc::new(EnclaveNodeMetadataRepository::default());
 
 		let genesis_header = params.genesis_header.clone();
 
-		let validator = itc_parentchain::light_client::io::read_or_init_grandpa_validator::<
-			SolochainBlock,
-			EnclaveOCallApi,
-			_,
-		>(params, ocall_api.clone(), &*light_client_seal, ParentchainId::TargetB)?;
+		let validator =
+			itc_parentchain::light_client::io::read_or_init_grandpa_validator::<
+				SolochainBlock,
+				EnclaveOCallApi,
+				_,
+			>(params, ocall_api.clone(), &*light_client_seal, ParentchainId::Target(index))?;
 		let validator_accessor =
 			Arc::new(EnclaveValidatorAccessor::new(validator, light_client_seal));
 
@@ -73,7 +75,7 @@ impl TargetBSolochainHandler {
 
 		let extrinsics_factory = create_extrinsics_factory(
 			genesis_hash,
-			GLOBAL_TARGET_B_PARENTCHAIN_NONCE_CACHE.clone(),
+			get_target_parentchain_nonce_cache(index)?,
 			node_metadata_repository.clone(),
 		)?;
 
@@ -83,7 +85,8 @@ impl TargetBSolochainHandler {
 			node_metadata_repository.clone(),
 		));
 
-		let block_importer = create_target_b_parentchain_block_importer(
+		let block_importer = create_target_parentchain_block_importer(
+			index,
 			validator_accessor.clone(),
 			stf_executor.clone(),
 			extrinsics_factory.clone(),
@@ -92,16 +95,16 @@ impl TargetBSolochainHandler {
 		)?;
 
 		let import_dispatcher = match WorkerModeProvider::worker_mode() {
-			WorkerMode::OffChainWorker => create_target_b_offchain_immediate_import_dispatcher(
+			WorkerMode::OffChainWorker => create_target_offchain_immediate_import_dispatcher(
 				stf_executor.clone(),
 				block_importer,
 				validator_accessor.clone(),
 				extrinsics_factory.clone(),
 			)?,
 			WorkerMode::Sidechain =>
-				create_sidechain_triggered_import_dispatcher_for_target_b(block_importer),
+				create_sidechain_triggered_import_dispatcher_for_target(block_importer),
 			WorkerMode::Teeracle =>
-				Arc::new(TargetBParentchainBlockImportDispatcher::new_empty_dispatcher()),
+				Arc::new(TargetParentchainBlockImportDispatcher::new_empty_dispatcher()),
 		};
 
 		let solochain_handler = Self {
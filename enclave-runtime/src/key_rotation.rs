@@ -0,0 +1,432 @@
+/*
+	Copyright 2021 Integritee AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Rotation of the shielding keys and the state key.
+//!
+//! The rotation is coordinated through the sidechain, so that all validateers of a shard switch
+//! to the new keys at the same sidechain block:
+//!
+//! 1. The rotating validateer generates the new keys and stages them as pending key rotation. It
+//!    submits a `rotate_enclave_account` trusted call to every shard, which schedules the rotation
+//!    of the enclave account [`KEY_ROTATION_ACTIVATION_DELAY`] sidechain blocks ahead. The enclave
+//!    account is derived from the shielding key.
+//! 2. Fellow validateers stage the same keys with `request_rotated_keys` in the meantime.
+//! 3. In the scheduled block, the STF moves the funds and assets of the previous enclave account to
+//!    the new one. Every validateer switches to the pending keys as soon as it has imported (or
+//!    proposed) that block, see [`KeyRotationActivator`].
+//!
+//! The state files are re-encrypted with the new state key. Trusted operations encrypted with
+//! the previous RSA or X25519 shielding key are still accepted until [`SHIELDING_KEY_GRACE_PERIOD`]
+//! has passed.
+
+use crate::{
+	error::{Error, Result as EnclaveResult},
+	get_base_path,
+	initialization::global_components::{
+		EnclaveStfEnclaveSigner, GLOBAL_OCALL_API_COMPONENT,
+		GLOBAL_SHIELDING_KEY_REPOSITORY_COMPONENT, GLOBAL_STATE_HANDLER_COMPONENT,
+		GLOBAL_STATE_KEY_REPOSITORY_COMPONENT, GLOBAL_STATE_OBSERVER_COMPONENT,
+		GLOBAL_TOP_POOL_AUTHOR_COMPONENT, GLOBAL_X25519_SHIELDING_KEY_REPOSITORY_COMPONENT,
+	},
+	tls_ra::seal_handler::SealStateAndKeys,
+};
+use codec::{Decode, Encode};
+use ita_stf::{Getter, TrustedCall, TrustedCallSigned, ENCLAVE_ACCOUNT_KEY};
+use itp_component_container::ComponentGetter;
+use itp_settings::{enclave::SHIELDING_KEY_GRACE_PERIOD, sidechain::KEY_ROTATION_ACTIVATION_DELAY};
+use itp_sgx_crypto::{
+	ed25519_derivation::DeriveEd25519,
+	key_repository::{AccessKey, MutateKey, RotateKey},
+	AesGcm, ShieldingCryptoEncrypt, ToPubkey, X25519KeyPair,
+};
+use itp_sgx_externalities::SgxExternalities;
+use itp_sgx_io::{seal, unseal};
+use itp_stf_executor::traits::StfEnclaveSigning;
+use itp_stf_primitives::types::{TrustedOperation, TrustedOperationOrHash};
+use itp_stf_state_handler::{
+	file_io::{
+		sgx::{complete_state_reencryption, stage_state_reencryption},
+		StateDir,
+	},
+	handle_state::HandleState,
+	query_shard_state::QueryShardState,
+};
+use itp_time_utils::now_as_millis;
+use itp_top_pool_author::traits::AuthorApi;
+use itp_types::{AccountId, ShardIdentifier, H256};
+use its_sidechain::{
+	consensus_common::ObserveImportedState,
+	state::{SidechainState, SidechainSystemExt},
+};
+use jsonrpc_core::futures::executor;
+use lazy_static::lazy_static;
+use log::*;
+use sgx_crypto_helper::{rsa3072::Rsa3072KeyPair, RsaKeyPair};
+use sgx_types::sgx_status_t;
+use sp_core::Pair;
+use std::{
+	fs,
+	path::{Path, PathBuf},
+	sync::SgxRwLock as RwLock,
+	vec,
+	vec::Vec,
+};
+
+/// File name of the sealed keys of a key rotation that has not been activated yet.
+pub const PENDING_KEY_ROTATION_FILE: &str = "pending_key_rotation_sealed.bin";
+
+lazy_static! {
+	/// The staged key rotation, restored from its sealed file on startup.
+	static ref PENDING_KEY_ROTATION: RwLock<Option<PendingKeyRotation>> = Default::default();
+}
+
+/// Keys that replace the current shielding keys and state key, once the enclave account of the
+/// shard has been rotated to the account derived from the new shielding key.
+#[derive(Clone, Encode, Decode)]
+pub struct PendingKeyRotation {
+	/// The RSA shielding key, JSON encoded like the sealed key.
+	shielding_key: Vec<u8>,
+	x25519_shielding_key: X25519KeyPair,
+	state_key: AesGcm,
+}
+
+impl PendingKeyRotation {
+	pub fn new(
+		shielding_key: &Rsa3072KeyPair,
+		x25519_shielding_key: X25519KeyPair,
+		state_key: AesGcm,
+	) -> EnclaveResult<Self> {
+		let shielding_key =
+			serde_json::to_vec(shielding_key).map_err(|e| Error::Other(e.into()))?;
+		Ok(Self { shielding_key, x25519_shielding_key, state_key })
+	}
+
+	/// Creates freshly generated keys.
+	fn random() -> EnclaveResult<Self> {
+		let shielding_key =
+			Rsa3072KeyPair::new().map_err(|e| Error::Other(format!("{:?}", e).into()))?;
+		Self::new(&shielding_key, X25519KeyPair::random()?, AesGcm::random()?)
+	}
+
+	fn shielding_key(&self) -> EnclaveResult<Rsa3072KeyPair> {
+		serde_json::from_slice(&self.shielding_key).map_err(|e| Error::Other(e.into()))
+	}
+
+	/// The enclave account that is derived from the new shielding key.
+	fn enclave_account(&self) -> EnclaveResult<AccountId> {
+		Ok(self.shielding_key()?.derive_ed25519()?.public().into())
+	}
+}
+
+/// Starts a rotation of the shielding keys and the state key of this enclave to freshly
+/// generated keys.
+///
+/// Other validateers of the shard obtain the new keys with `request_rotated_keys`.
+#[no_mangle]
+pub unsafe extern "C" fn rotate_keys() -> sgx_status_t {
+	if let Err(e) = rotate_keys_internal() {
+		error!("Failed to rotate keys: {:?}", e);
+		return sgx_status_t::SGX_ERROR_UNEXPECTED
+	}
+	sgx_status_t::SGX_SUCCESS
+}
+
+fn rotate_keys_internal() -> EnclaveResult<()> {
+	let key_rotation = PendingKeyRotation::random()?;
+	// The calls have to be signed by the current enclave account, i.e. before the rotation.
+	let enclave_account_rotations =
+		sign_enclave_account_rotations(key_rotation.enclave_account()?)?;
+	stage_key_rotation(key_rotation)?;
+	if let Err(e) = submit_trusted_calls(enclave_account_rotations) {
+		// Otherwise, the funds of the enclave account could be moved to an account whose keys
+		// are lost with the next key rotation.
+		discard_pending_key_rotation()?;
+		return Err(e)
+	}
+	Ok(())
+}
+
+/// Stages the keys of a key rotation until the enclave account has been rotated in the state.
+///
+/// If the enclave account of a shard has already been rotated, e.g. because we obtained the keys
+/// from a fellow validateer after the rotation, the keys are activated right away.
+pub(crate) fn stage_key_rotation(key_rotation: PendingKeyRotation) -> EnclaveResult<()> {
+	let mut pending_key_rotation = PENDING_KEY_ROTATION
+		.write()
+		.map_err(|_| Error::Other("Lock poisoning".into()))?;
+	seal(&key_rotation.encode(), pending_key_rotation_path()?)?;
+	info!("Staged key rotation for enclave account {:?}", key_rotation.enclave_account()?);
+	*pending_key_rotation = Some(key_rotation);
+	drop(pending_key_rotation);
+
+	let state_handler = GLOBAL_STATE_HANDLER_COMPONENT.get()?;
+	for shard in state_handler.list_shards()? {
+		state_handler.execute_on_current(&shard, activate_pending_key_rotation)??;
+	}
+	Ok(())
+}
+
+/// Restores the pending key rotation from its sealed file, if there is one.
+pub(crate) fn restore_pending_key_rotation(base_dir: &Path) -> EnclaveResult<()> {
+	let path = base_dir.join(PENDING_KEY_ROTATION_FILE);
+	if !path.exists() {
+		return Ok(())
+	}
+	let key_rotation = PendingKeyRotation::decode(&mut unseal(path)?.as_slice())?;
+	info!(
+		"Restored pending key rotation for enclave account {:?}",
+		key_rotation.enclave_account()?
+	);
+	*PENDING_KEY_ROTATION
+		.write()
+		.map_err(|_| Error::Other("Lock poisoning".into()))? = Some(key_rotation);
+	Ok(())
+}
+
+/// The staged key rotation, if any.
+pub(crate) fn pending_key_rotation() -> EnclaveResult<Option<PendingKeyRotation>> {
+	Ok(PENDING_KEY_ROTATION
+		.read()
+		.map_err(|_| Error::Other("Lock poisoning".into()))?
+		.clone())
+}
+
+fn discard_pending_key_rotation() -> EnclaveResult<()> {
+	let mut pending_key_rotation = PENDING_KEY_ROTATION
+		.write()
+		.map_err(|_| Error::Other("Lock poisoning".into()))?;
+	let path = pending_key_rotation_path()?;
+	if path.exists() {
+		fs::remove_file(path)?;
+	}
+	*pending_key_rotation = None;
+	Ok(())
+}
+
+fn pending_key_rotation_path() -> EnclaveResult<PathBuf> {
+	Ok(get_base_path()?.join(PENDING_KEY_ROTATION_FILE))
+}
+
+/// Switches to the keys of the pending key rotation, if the enclave account in `state` is the one
+/// derived from the new shielding key.
+fn activate_pending_key_rotation(state: &SgxExternalities, _: H256) -> EnclaveResult<()> {
+	let key_rotation = match pending_key_rotation()? {
+		Some(key_rotation) => key_rotation,
+		None => return Ok(()),
+	};
+	let enclave_account: Option<AccountId> = state.get_with_name("Sudo", ENCLAVE_ACCOUNT_KEY);
+	if enclave_account != Some(key_rotation.enclave_account()?) {
+		return Ok(())
+	}
+
+	info!("Enclave account has been rotated, switching to the rotated keys");
+	rotate_shielding_key(key_rotation.shielding_key()?)?;
+	rotate_x25519_shielding_key(key_rotation.x25519_shielding_key)?;
+	rotate_state_key(key_rotation.state_key)?;
+	discard_pending_key_rotation()
+}
+
+/// Activates the pending key rotation in the sidechain block that rotates the enclave account.
+///
+/// The keys are switched right after the block has been imported or proposed. The block itself
+/// is still encrypted with the previous state key, all later blocks with the new one.
+#[derive(Default)]
+pub struct KeyRotationActivator;
+
+impl ObserveImportedState<SgxExternalities> for KeyRotationActivator {
+	fn on_state_imported(
+		&self,
+		_shard: ShardIdentifier,
+		block_hash: H256,
+		state: &SgxExternalities,
+	) {
+		if let Err(e) = activate_pending_key_rotation(state, block_hash) {
+			error!("Failed to activate the pending key rotation: {:?}", e);
+		}
+	}
+}
+
+/// Replaces the shielding key. The enclave account in the state of the shards is not touched,
+/// it is rotated by the STF at the sidechain block agreed on in the `rotate_enclave_account` call.
+pub(crate) fn rotate_shielding_key(shielding_key: Rsa3072KeyPair) -> EnclaveResult<()> {
+	let shielding_key_repository = GLOBAL_SHIELDING_KEY_REPOSITORY_COMPONENT.get()?;
+	if is_same_shielding_key(&shielding_key_repository.retrieve_key()?, &shielding_key)? {
+		info!("Shielding key is already up to date, skipping rotation");
+		return Ok(())
+	}
+
+	let retired_until = now_as_millis() + SHIELDING_KEY_GRACE_PERIOD.as_millis() as u64;
+	shielding_key_repository.rotate_key(shielding_key, retired_until)?;
+	info!("Rotated shielding key, the previous key is accepted until {}", retired_until);
+	Ok(())
+}
+
+/// Replaces the X25519 shielding key. The enclave account is derived from the RSA key only.
+pub(crate) fn rotate_x25519_shielding_key(shielding_key: X25519KeyPair) -> EnclaveResult<()> {
+	let shielding_key_repository = GLOBAL_X25519_SHIELDING_KEY_REPOSITORY_COMPONENT.get()?;
+	if shielding_key_repository.retrieve_key()? == shielding_key {
+		info!("X25519 shielding key is already up to date, skipping rotation");
+		return Ok(())
+	}
+
+	let retired_until = now_as_millis() + SHIELDING_KEY_GRACE_PERIOD.as_millis() as u64;
+	shielding_key_repository.rotate_key(shielding_key, retired_until)?;
+	info!("Rotated X25519 shielding key, the previous key is accepted until {}", retired_until);
+	Ok(())
+}
+
+/// Replaces the state key and re-encrypts all state files with it.
+///
+/// No state snapshot is written in the meantime. If the enclave stops during the rotation,
+/// the re-encryption is completed (or discarded) on the next start.
+pub(crate) fn rotate_state_key(state_key: AesGcm) -> EnclaveResult<()> {
+	let state_key_repository = GLOBAL_STATE_KEY_REPOSITORY_COMPONENT.get()?;
+	let previous_state_key = state_key_repository.retrieve_key()?;
+	if previous_state_key == state_key {
+		info!("State key is already up to date, skipping rotation");
+		return Ok(())
+	}
+
+	let state_handler = GLOBAL_STATE_HANDLER_COMPONENT.get()?;
+	let state_dir = StateDir::new(get_base_path()?);
+	state_handler.execute_with_snapshots_locked(|| -> EnclaveResult<()> {
+		let staged_files = stage_state_reencryption(&state_dir, &previous_state_key, &state_key)?;
+		state_key_repository.update_key(state_key)?;
+		let replaced_files = complete_state_reencryption(&state_dir, &state_key)?;
+		info!(
+			"Rotated state key, re-encrypted {} of {} state file(s)",
+			replaced_files, staged_files
+		);
+		Ok(())
+	})?
+}
+
+/// Signs a `rotate_enclave_account` trusted call for every shard with the current enclave account.
+///
+/// The enclave account is rotated [`KEY_ROTATION_ACTIVATION_DELAY`] blocks after the latest
+/// sidechain block of the shard.
+fn sign_enclave_account_rotations(
+	new_enclave_account: AccountId,
+) -> EnclaveResult<Vec<(ShardIdentifier, TrustedCallSigned)>> {
+	let stf_enclave_signer = EnclaveStfEnclaveSigner::new(
+		GLOBAL_STATE_OBSERVER_COMPONENT.get()?,
+		GLOBAL_OCALL_API_COMPONENT.get()?,
+		GLOBAL_SHIELDING_KEY_REPOSITORY_COMPONENT.get()?,
+		GLOBAL_TOP_POOL_AUTHOR_COMPONENT.get()?,
+	);
+	let enclave_account = stf_enclave_signer.get_enclave_account()?;
+
+	let state_handler = GLOBAL_STATE_HANDLER_COMPONENT.get()?;
+	let mut signed_calls = Vec::new();
+	for shard in state_handler.list_shards()? {
+		let block_number = state_handler
+			.execute_on_current(&shard, |state, _| state.get_block_number())?
+			.unwrap_or_default();
+		let trusted_call = TrustedCall::rotate_enclave_account(
+			enclave_account.clone(),
+			new_enclave_account.clone(),
+			block_number + KEY_ROTATION_ACTIVATION_DELAY,
+		);
+		signed_calls.push((shard, stf_enclave_signer.sign_call_with_self(&trusted_call, &shard)?));
+	}
+	Ok(signed_calls)
+}
+
+/// Submits the trusted calls to the pool, encrypted with the current shielding key. They are
+/// persisted with the pool, so they are included in a block even if the worker is restarted.
+///
+/// Either all calls are submitted, or the ones submitted before an error are removed again.
+fn submit_trusted_calls(calls: Vec<(ShardIdentifier, TrustedCallSigned)>) -> EnclaveResult<()> {
+	let shielding_key = GLOBAL_SHIELDING_KEY_REPOSITORY_COMPONENT.get()?.retrieve_key()?;
+	let top_pool_author = GLOBAL_TOP_POOL_AUTHOR_COMPONENT.get()?;
+	let mut submitted_calls = Vec::new();
+	for (shard, call) in calls {
+		let trusted_operation = TrustedOperation::<TrustedCallSigned, Getter>::indirect_call(call);
+		let submission = shielding_key.encrypt(&trusted_operation.encode()).and_then(
+			|encrypted_trusted_operation| {
+				executor::block_on(top_pool_author.submit_top(encrypted_trusted_operation, shard))
+					.map_err(|e| Error::Other(format!("{:?}", e).into()))
+			},
+		);
+		match submission {
+			Ok(hash) => {
+				info!("Submitted the enclave account rotation {:?} for shard {:?}", hash, shard);
+				submitted_calls.push((shard, hash));
+			},
+			Err(e) => {
+				for (shard, hash) in submitted_calls {
+					top_pool_author.remove_calls_from_pool(
+						shard,
+						vec![(TrustedOperationOrHash::Hash(hash), false)],
+					);
+				}
+				return Err(e)
+			},
+		}
+	}
+	Ok(())
+}
+
+pub(crate) fn is_same_shielding_key(
+	key: &Rsa3072KeyPair,
+	other: &Rsa3072KeyPair,
+) -> EnclaveResult<bool> {
+	let encoded_pubkey = |key: &Rsa3072KeyPair| -> EnclaveResult<Vec<u8>> {
+		serde_json::to_vec(&key.pubkey()?).map_err(|e| Error::Other(e.into()))
+	};
+	Ok(encoded_pubkey(key)? == encoded_pubkey(other)?)
+}
+
+/// Seal handler for keys that were rotated by another validateer.
+///
+/// Only the pending key rotation is expected, any other payload is rejected.
+#[derive(Default)]
+pub struct KeyRotationSealHandler;
+
+impl SealStateAndKeys for KeyRotationSealHandler {
+	fn seal_shielding_key(&self, _bytes: &[u8]) -> EnclaveResult<()> {
+		Err(Error::Other("Unexpected shielding key during key rotation".into()))
+	}
+
+	fn seal_x25519_shielding_key(&self, _bytes: &[u8]) -> EnclaveResult<()> {
+		Err(Error::Other("Unexpected X25519 shielding key during key rotation".into()))
+	}
+
+	fn seal_state_key(&self, _bytes: &[u8]) -> EnclaveResult<()> {
+		Err(Error::Other("Unexpected state key during key rotation".into()))
+	}
+
+	fn seal_pending_key_rotation(&self, mut bytes: &[u8]) -> EnclaveResult<()> {
+		stage_key_rotation(PendingKeyRotation::decode(&mut bytes)?)
+	}
+
+	fn seal_state(&self, _bytes: &[u8], shard: &ShardIdentifier) -> EnclaveResult<()> {
+		Err(Error::Other(
+			format!("Unexpected state of shard {:?} during key rotation", shard).into(),
+		))
+	}
+
+	fn seal_new_empty_state(&self, shard: &ShardIdentifier) -> EnclaveResult<()> {
+		Err(Error::Other(
+			format!("Unexpected reset of shard {:?} during key rotation", shard).into(),
+		))
+	}
+
+	fn seal_light_client_state(&self, _bytes: &[u8]) -> EnclaveResult<()> {
+		Err(Error::Other("Unexpected light client state during key rotation".into()))
+	}
+}
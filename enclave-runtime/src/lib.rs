@@ -36,14 +36,11 @@ use crate::{
 		GLOBAL_INTEGRITEE_PARACHAIN_HANDLER_COMPONENT, GLOBAL_INTEGRITEE_PARENTCHAIN_NONCE_CACHE,
 		GLOBAL_INTEGRITEE_SOLOCHAIN_HANDLER_COMPONENT, GLOBAL_SHIELDING_KEY_REPOSITORY_COMPONENT,
 		GLOBAL_SIGNING_KEY_REPOSITORY_COMPONENT, GLOBAL_STATE_HANDLER_COMPONENT,
-		GLOBAL_TARGET_A_PARACHAIN_HANDLER_COMPONENT, GLOBAL_TARGET_A_PARENTCHAIN_NONCE_CACHE,
-		GLOBAL_TARGET_A_SOLOCHAIN_HANDLER_COMPONENT, GLOBAL_TARGET_B_PARACHAIN_HANDLER_COMPONENT,
-		GLOBAL_TARGET_B_PARENTCHAIN_NONCE_CACHE, GLOBAL_TARGET_B_SOLOCHAIN_HANDLER_COMPONENT,
 	},
 	utils::{
-		get_node_metadata_repository_from_integritee_solo_or_parachain,
-		get_node_metadata_repository_from_target_a_solo_or_parachain,
-		get_node_metadata_repository_from_target_b_solo_or_parachain, DecodeRaw,
+		get_import_dispatcher_from_target_solo_or_parachain,
+		get_node_metadata_repository_from_solo_or_parachain, get_target_parentchain_nonce_cache,
+		DecodeRaw,
 	},
 };
 use codec::Decode;
@@ -59,6 +56,7 @@ use itp_sgx_crypto::key_repository::AccessPubkey;
 use itp_storage::{StorageProof, StorageProofChecker};
 use itp_types::{ShardIdentifier, SignedBlock};
 use itp_utils::write_slice_and_whitespace_pad;
+use its_primitives::types::slot_claims::SlotClaimConfig;
 use log::*;
 use once_cell::sync::OnceCell;
 use sgx_types::sgx_status_t;
@@ -74,6 +72,7 @@ mod attestation;
 mod empty_impls;
 mod initialization;
 mod ipfs;
+mod key_rotation;
 mod ocall;
 mod shard_config;
 mod shard_creation_info;
@@ -234,13 +233,18 @@ pub unsafe extern "C" fn set_nonce(
 
 	info!("Setting the nonce of the enclave to: {} for parentchain: {:?}", *nonce, id);
 
-	let nonce_lock = match id {
-		ParentchainId::Integritee => GLOBAL_INTEGRITEE_PARENTCHAIN_NONCE_CACHE.load_for_mutation(),
-		ParentchainId::TargetA => GLOBAL_TARGET_A_PARENTCHAIN_NONCE_CACHE.load_for_mutation(),
-		ParentchainId::TargetB => GLOBAL_TARGET_B_PARENTCHAIN_NONCE_CACHE.load_for_mutation(),
+	let nonce_cache = match id {
+		ParentchainId::Integritee => GLOBAL_INTEGRITEE_PARENTCHAIN_NONCE_CACHE.clone(),
+		ParentchainId::Target(index) => match get_target_parentchain_nonce_cache(index) {
+			Ok(nonce_cache) => nonce_cache,
+			Err(e) => {
+				error!("Failed to get {:?} parentchain nonce cache: {:?}", id, e);
+				return sgx_status_t::SGX_ERROR_UNEXPECTED
+			},
+		},
 	};
 
-	match nonce_lock {
+	match nonce_cache.load_for_mutation() {
 		Ok(mut nonce_guard) => *nonce_guard = Nonce(*nonce),
 		Err(e) => {
 			error!("Failed to set {:?} parentchain nonce in enclave: {:?}", id, e);
@@ -276,14 +280,7 @@ pub unsafe extern "C" fn set_node_metadata(
 
 	info!("Setting node meta data for parentchain: {:?}", id);
 
-	let node_metadata_repository = match id {
-		ParentchainId::Integritee =>
-			get_node_metadata_repository_from_integritee_solo_or_parachain(),
-		ParentchainId::TargetA => get_node_metadata_repository_from_target_a_solo_or_parachain(),
-		ParentchainId::TargetB => get_node_metadata_repository_from_target_b_solo_or_parachain(),
-	};
-
-	match node_metadata_repository {
+	match get_node_metadata_repository_from_solo_or_parachain(id) {
 		Ok(repo) => repo.set_metadata(metadata),
 		Err(e) => {
 			error!("Could not get {:?} parentchain component: {:?}", id, e);
@@ -302,8 +299,22 @@ pub unsafe extern "C" fn set_node_metadata(
 /// (parentchain components) have been initialized (because we need the parentchain
 /// block import dispatcher).
 #[no_mangle]
-pub unsafe extern "C" fn init_enclave_sidechain_components() -> sgx_status_t {
-	if let Err(e) = initialization::init_enclave_sidechain_components() {
+pub unsafe extern "C" fn init_enclave_sidechain_components(
+	slot_claim_config: *const u8,
+	slot_claim_config_size: u32,
+) -> sgx_status_t {
+	let mut slot_claim_config_encoded =
+		slice::from_raw_parts(slot_claim_config, slot_claim_config_size as usize);
+
+	let slot_claim_config = match SlotClaimConfig::decode(&mut slot_claim_config_encoded) {
+		Ok(c) => c,
+		Err(e) => {
+			error!("Decoding slot claim config failed. Error: {:?}", e);
+			return sgx_status_t::SGX_ERROR_UNEXPECTED
+		},
+	};
+
+	if let Err(e) = initialization::init_enclave_sidechain_components(slot_claim_config) {
 		error!("Failed to initialize sidechain components: {:?}", e);
 		return sgx_status_t::SGX_ERROR_UNEXPECTED
 	}
@@ -489,39 +500,12 @@ fn dispatch_parentchain_blocks_for_import<WorkerModeProvider: ProvideWorkerMode>
 				return Err(Error::NoIntegriteeParentchainAssigned)
 			};
 		},
-		ParentchainId::TargetA => {
-			if let Ok(handler) = GLOBAL_TARGET_A_SOLOCHAIN_HANDLER_COMPONENT.get() {
-				handler.import_dispatcher.dispatch_import(
-					blocks_to_sync,
-					events_to_sync,
-					immediate_import,
-				)?;
-			} else if let Ok(handler) = GLOBAL_TARGET_A_PARACHAIN_HANDLER_COMPONENT.get() {
-				handler.import_dispatcher.dispatch_import(
-					blocks_to_sync,
-					events_to_sync,
-					immediate_import,
-				)?;
-			} else {
-				return Err(Error::NoTargetAParentchainAssigned)
-			};
-		},
-		ParentchainId::TargetB => {
-			if let Ok(handler) = GLOBAL_TARGET_B_SOLOCHAIN_HANDLER_COMPONENT.get() {
-				handler.import_dispatcher.dispatch_import(
-					blocks_to_sync,
-					events_to_sync,
-					immediate_import,
-				)?;
-			} else if let Ok(handler) = GLOBAL_TARGET_B_PARACHAIN_HANDLER_COMPONENT.get() {
-				handler.import_dispatcher.dispatch_import(
-					blocks_to_sync,
-					events_to_sync,
-					immediate_import,
-				)?;
-			} else {
-				return Err(Error::NoTargetBParentchainAssigned)
-			};
+		ParentchainId::Target(index) => {
+			get_import_dispatcher_from_target_solo_or_parachain(*index)?.dispatch_import(
+				blocks_to_sync,
+				events_to_sync,
+				immediate_import,
+			)?;
 		},
 	}
 
@@ -27,6 +27,7 @@ use std::{ptr, sync::Arc, vec::Vec};
 use std::sync::SgxRwLock as RwLock;
 
 const RET_QUOTE_BUF_LEN: usize = 2048;
+const RET_COLLATERAL_BUF_LEN: usize = 65536;
 
 lazy_static! {
 	/// Global cache of MRENCLAVE
@@ -159,6 +160,33 @@ impl EnclaveAttestationOCallApi for OcallApi {
 		Ok(quote_vec)
 	}
 
+	fn get_dcap_collateral(&self, fmspc: [u8; 6]) -> SgxResult<Vec<u8>> {
+		let mut return_collateral_buf = vec![0u8; RET_COLLATERAL_BUF_LEN];
+		let mut collateral_len: u32 = 0;
+		let mut rt: sgx_status_t = sgx_status_t::SGX_ERROR_UNEXPECTED;
+
+		let result = unsafe {
+			ffi::ocall_get_dcap_collateral(
+				&mut rt as *mut sgx_status_t,
+				fmspc.as_ptr(),
+				fmspc.len() as u32,
+				return_collateral_buf.as_mut_ptr(),
+				RET_COLLATERAL_BUF_LEN as u32,
+				&mut collateral_len as *mut u32,
+			)
+		};
+
+		ensure!(result == sgx_status_t::SGX_SUCCESS, result);
+		ensure!(rt == sgx_status_t::SGX_SUCCESS, rt);
+		ensure!(
+			(collateral_len as usize) <= RET_COLLATERAL_BUF_LEN,
+			sgx_status_t::SGX_ERROR_INVALID_PARAMETER
+		);
+
+		return_collateral_buf.truncate(collateral_len as usize);
+		Ok(return_collateral_buf)
+	}
+
 	fn get_qve_report_on_quote(
 		&self,
 		quote: Vec<u8>,
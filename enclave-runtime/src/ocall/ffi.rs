@@ -47,6 +47,15 @@ extern "C" {
 		quote_size: u32,
 	) -> sgx_status_t;
 
+	pub fn ocall_get_dcap_collateral(
+		ret_val: *mut sgx_status_t,
+		p_fmspc: *const u8,
+		fmspc_size: u32,
+		p_collateral: *mut u8,
+		collateral_max_size: u32,
+		p_collateral_size: *mut u32,
+	) -> sgx_status_t;
+
 	pub fn ocall_get_qve_report_on_quote(
 		ret_val: *mut sgx_status_t,
 		p_quote: *const u8,
@@ -95,6 +104,26 @@ extern "C" {
 		signed_blocks_size: u32,
 	) -> sgx_status_t;
 
+	pub fn ocall_propose_finality_votes(
+		ret_val: *mut sgx_status_t,
+		votes: *const u8,
+		votes_size: u32,
+	) -> sgx_status_t;
+
+	pub fn ocall_store_sidechain_justifications(
+		ret_val: *mut sgx_status_t,
+		justifications: *const u8,
+		justifications_size: u32,
+	) -> sgx_status_t;
+
+	pub fn ocall_fetch_sidechain_finalized_head(
+		ret_val: *mut sgx_status_t,
+		shard_identifier: *const u8,
+		shard_identifier_size: u32,
+		justification: *mut u8,
+		justification_size: u32,
+	) -> sgx_status_t;
+
 	pub fn ocall_fetch_sidechain_blocks_from_peer(
 		ret_val: *mut sgx_status_t,
 		last_imported_block_hash: *const u8,
@@ -68,6 +68,77 @@ impl EnclaveSidechainOCallApi for OcallApi {
 		Ok(())
 	}
 
+	fn propose_finality_votes<SignedFinalityVote: Encode>(
+		&self,
+		votes: Vec<SignedFinalityVote>,
+	) -> SgxResult<()> {
+		let mut rt: sgx_status_t = sgx_status_t::SGX_ERROR_UNEXPECTED;
+		let votes_encoded = votes.encode();
+
+		let res = unsafe {
+			ffi::ocall_propose_finality_votes(
+				&mut rt as *mut sgx_status_t,
+				votes_encoded.as_ptr(),
+				votes_encoded.len() as u32,
+			)
+		};
+
+		ensure!(rt == sgx_status_t::SGX_SUCCESS, rt);
+		ensure!(res == sgx_status_t::SGX_SUCCESS, res);
+
+		Ok(())
+	}
+
+	fn store_sidechain_justifications<Justification: Encode>(
+		&self,
+		justifications: Vec<Justification>,
+	) -> SgxResult<()> {
+		let mut rt: sgx_status_t = sgx_status_t::SGX_ERROR_UNEXPECTED;
+		let justifications_encoded = justifications.encode();
+
+		let res = unsafe {
+			ffi::ocall_store_sidechain_justifications(
+				&mut rt as *mut sgx_status_t,
+				justifications_encoded.as_ptr(),
+				justifications_encoded.len() as u32,
+			)
+		};
+
+		ensure!(rt == sgx_status_t::SGX_SUCCESS, rt);
+		ensure!(res == sgx_status_t::SGX_SUCCESS, res);
+
+		Ok(())
+	}
+
+	fn fetch_sidechain_finalized_head<Justification: Decode>(
+		&self,
+		shard_identifier: ShardIdentifier,
+	) -> SgxResult<Option<Justification>> {
+		const JUSTIFICATION_BUFFER_SIZE: usize = 65536; // Holds the votes of several hundred validateers.
+
+		let mut rt: sgx_status_t = sgx_status_t::SGX_ERROR_UNEXPECTED;
+		let shard_identifier_encoded = shard_identifier.encode();
+		let mut justification_encoded: Vec<u8> = vec![0; JUSTIFICATION_BUFFER_SIZE];
+
+		let res = unsafe {
+			ffi::ocall_fetch_sidechain_finalized_head(
+				&mut rt as *mut sgx_status_t,
+				shard_identifier_encoded.as_ptr(),
+				shard_identifier_encoded.len() as u32,
+				justification_encoded.as_mut_ptr(),
+				justification_encoded.len() as u32,
+			)
+		};
+
+		ensure!(rt == sgx_status_t::SGX_SUCCESS, rt);
+		ensure!(res == sgx_status_t::SGX_SUCCESS, res);
+
+		Decode::decode(&mut justification_encoded.as_slice()).map_err(|e| {
+			error!("Failed to decode finalized head: {}", e);
+			sgx_status_t::SGX_ERROR_UNEXPECTED
+		})
+	}
+
 	fn fetch_sidechain_blocks_from_peer<SignedSidechainBlock: Decode>(
 		&self,
 		last_imported_block_hash: BlockHash,
@@ -0,0 +1,156 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Subscription of clients to the trusted operation statuses and runtime events of their account.
+
+use crate::initialization::global_components::{EnclaveAccountSubscriptionHandler, EnclaveStf};
+use codec::Encode;
+use ita_sgx_runtime::accounts_of_event;
+use ita_stf::{AccountEventsSubscriptionSigned, State as StfState};
+use itc_direct_rpc_server::account_subscriptions::ACCOUNT_EVENTS_SUBSCRIPTION_METHOD;
+use itp_enclave_metrics::EnclaveMetric;
+use itp_ocall_api::{EnclaveAttestationOCallApi, EnclaveMetricsOCallApi};
+use itp_rpc::RpcReturnValue;
+use itp_sgx_externalities::SgxExternalities;
+use itp_stf_interface::system_pallet::SystemPalletEventInterface;
+use itp_stf_primitives::types::AccountId;
+use itp_stf_state_handler::query_shard_state::QueryShardState;
+use itp_time_utils::now_as_millis;
+use itp_types::{AccountEventUpdate, ShardIdentifier, H256};
+use itp_utils::{FromHexPrefixed, ToHexPrefixed};
+use its_sidechain::{consensus_common::ObserveImportedState, state::SidechainState};
+use jsonrpc_core::{serde_json::json, IoHandler, Params};
+use log::*;
+use std::{borrow::ToOwned, format, string::String, sync::Arc, vec, vec::Vec};
+
+type EventRecord = <EnclaveStf as SystemPalletEventInterface<StfState>>::EventRecord;
+
+pub fn add_account_events_api<StateHandler, OCallApi>(
+	io_handler: &mut IoHandler,
+	account_subscription_handler: Arc<EnclaveAccountSubscriptionHandler>,
+	state_handler: Arc<StateHandler>,
+	ocall_api: Arc<OCallApi>,
+) where
+	StateHandler: QueryShardState + Send + Sync + 'static,
+	OCallApi: EnclaveMetricsOCallApi + EnclaveAttestationOCallApi + Send + Sync + 'static,
+{
+	io_handler.add_sync_method(ACCOUNT_EVENTS_SUBSCRIPTION_METHOD, move |params: Params| {
+		debug!("worker_api_direct rpc was called: author_subscribeAccountEvents");
+		ocall_api
+			.update_metrics(vec![EnclaveMetric::RpcRequestsIncrement])
+			.unwrap_or_else(|e| error!("failed to update prometheus metric: {:?}", e));
+		let json_value = match subscribe_account_events_inner(
+			account_subscription_handler.as_ref(),
+			state_handler.as_ref(),
+			ocall_api.as_ref(),
+			params,
+		) {
+			// Only return the subscription id, like `author_submitAndWatchExtrinsic`.
+			Ok(subscription_id) => subscription_id.to_hex(),
+			Err(error) => compute_hex_encoded_return_error(error.as_str()),
+		};
+		Ok(json!(json_value))
+	});
+}
+
+fn subscribe_account_events_inner<StateHandler, OCallApi>(
+	account_subscription_handler: &EnclaveAccountSubscriptionHandler,
+	state_handler: &StateHandler,
+	ocall_api: &OCallApi,
+	params: Params,
+) -> Result<H256, String>
+where
+	StateHandler: QueryShardState,
+	OCallApi: EnclaveAttestationOCallApi,
+{
+	let hex_encoded_params = params.parse::<Vec<String>>().map_err(|e| format!("{:?}", e))?;
+	let hex_encoded_request = hex_encoded_params
+		.get(0)
+		.ok_or_else(|| "Missing subscription request".to_owned())?;
+
+	let request = AccountEventsSubscriptionSigned::from_hex(hex_encoded_request)
+		.map_err(|e| format!("Could not decode subscription request: {:?}", e))?;
+	let mrenclave = ocall_api.get_mrenclave_of_self().map_err(|e| format!("{:?}", e))?;
+	if !request.verify_signature(&mrenclave.m) {
+		return Err("Subscription request is not signed by the account for this enclave".to_owned())
+	}
+
+	let subscription = &request.subscription;
+	if !state_handler
+		.shard_exists(&subscription.shard)
+		.map_err(|e| format!("{:?}", e))?
+	{
+		return Err("Subscription request is for an unknown shard".to_owned())
+	}
+	if !subscription.is_valid_at(now_as_millis()) {
+		return Err("Subscription request has expired or expires too late".to_owned())
+	}
+
+	let subscription_id = request.subscription_id();
+	account_subscription_handler
+		.subscribe(
+			subscription.shard,
+			subscription.account.clone(),
+			subscription_id,
+			subscription.expires_at,
+		)
+		.map_err(|e| format!("{:?}", e))?;
+	Ok(subscription_id)
+}
+
+fn compute_hex_encoded_return_error(error_msg: &str) -> String {
+	RpcReturnValue::from_error_message(error_msg).to_hex()
+}
+
+/// Sends the runtime events of every imported sidechain block to the subscribers of the
+/// accounts they touch, in the shard of the block.
+pub struct AccountEventNotifier {
+	account_subscription_handler: Arc<EnclaveAccountSubscriptionHandler>,
+}
+
+impl AccountEventNotifier {
+	pub fn new(account_subscription_handler: Arc<EnclaveAccountSubscriptionHandler>) -> Self {
+		AccountEventNotifier { account_subscription_handler }
+	}
+}
+
+impl ObserveImportedState<SgxExternalities> for AccountEventNotifier {
+	fn on_state_imported(
+		&self,
+		shard: ShardIdentifier,
+		block_hash: H256,
+		state: &SgxExternalities,
+	) {
+		let accounts = self.account_subscription_handler.subscribed_accounts(&shard);
+		if accounts.is_empty() {
+			return
+		}
+
+		// The events are reset at the beginning of each block, so these are the ones of this block.
+		let events: Vec<EventRecord> = state.get_with_name("System", "Events").unwrap_or_default();
+		for event in events.iter() {
+			let touched_accounts: Vec<AccountId> = accounts_of_event(&event.event);
+			for account in accounts.iter().filter(|a| touched_accounts.contains(a)) {
+				self.account_subscription_handler.notify(
+					&shard,
+					account,
+					&AccountEventUpdate::RuntimeEvent(block_hash, event.encode()),
+				);
+			}
+		}
+	}
+}
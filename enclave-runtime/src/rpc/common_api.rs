@@ -35,14 +35,19 @@ use itp_enclave_metrics::EnclaveMetric;
 use itp_ocall_api::{EnclaveAttestationOCallApi, EnclaveMetricsOCallApi};
 use itp_primitives_cache::{GetPrimitives, GLOBAL_PRIMITIVES_CACHE};
 use itp_rpc::RpcReturnValue;
-use itp_sgx_crypto::key_repository::AccessPubkey;
+use itp_sgx_crypto::{key_repository::AccessPubkey, X25519PubKey};
 use itp_stf_executor::{getter_executor::ExecuteGetter, traits::StfShardVaultQuery};
+use itp_stf_primitives::types::GetterResultWithProof;
 use itp_top_pool_author::traits::AuthorApi;
 use itp_types::{DirectRequestStatus, EnclaveFingerprint, Request, ShardIdentifier, H256};
 use itp_utils::{FromHexPrefixed, ToHexPrefixed};
 use its_block_header_cache::{GetSidechainBlockHeader, SidechainBlockHeaderCache};
 use its_primitives::types::header::SidechainHeader;
-use its_rpc_handler::direct_top_pool_api::add_top_pool_direct_rpc_methods;
+use its_rpc_handler::{
+	constants::RPC_METHOD_NAME_GET_FINALIZED_HEAD,
+	direct_top_pool_api::add_top_pool_direct_rpc_methods,
+};
+use its_sidechain::consensus_common::FinalizeBlocks;
 use jsonrpc_core::{serde_json::json, IoHandler, Params, Value};
 use log::debug;
 use sgx_crypto_helper::rsa3072::Rsa3072PubKey;
@@ -53,19 +58,30 @@ fn compute_hex_encoded_return_error(error_msg: &str) -> String {
 	RpcReturnValue::from_error_message(error_msg).to_hex()
 }
 
-pub fn add_common_api<Author, GetterExecutor, AccessShieldingKey, OCallApi>(
+pub fn add_common_api<
+	Author,
+	GetterExecutor,
+	AccessShieldingKey,
+	AccessX25519ShieldingKey,
+	OCallApi,
+	FinalityGadget,
+>(
 	io_handler: &mut IoHandler,
 	top_pool_author: Arc<Author>,
 	getter_executor: Arc<GetterExecutor>,
 	shielding_key: Arc<AccessShieldingKey>,
+	x25519_shielding_key: Arc<AccessX25519ShieldingKey>,
 	ocall_api: Arc<OCallApi>,
 	enclave_version: String,
 	sidechain_header_cache: Arc<SidechainBlockHeaderCache<SidechainHeader>>,
+	sidechain_finality_gadget: Arc<FinalityGadget>,
 ) where
 	Author: AuthorApi<H256, H256, TrustedCallSigned, Getter> + Send + Sync + 'static,
 	GetterExecutor: ExecuteGetter + Send + Sync + 'static,
 	AccessShieldingKey: AccessPubkey<KeyType = Rsa3072PubKey> + Send + Sync + 'static,
+	AccessX25519ShieldingKey: AccessPubkey<KeyType = X25519PubKey> + Send + Sync + 'static,
 	OCallApi: EnclaveMetricsOCallApi + Send + Sync + 'static,
+	FinalityGadget: FinalizeBlocks + Send + Sync + 'static,
 {
 	add_top_pool_direct_rpc_methods(top_pool_author.clone(), io_handler, ocall_api.clone());
 
@@ -96,6 +112,25 @@ pub fn add_common_api<Author, GetterExecutor, AccessShieldingKey, OCallApi>(
 		Ok(json!(json_value.to_hex()))
 	});
 
+	let local_ocall_api = ocall_api.clone();
+	io_handler.add_sync_method("author_getX25519ShieldingKey", move |_: Params| {
+		debug!("worker_api_direct rpc was called: author_getX25519ShieldingKey");
+		local_ocall_api
+			.update_metrics(vec![EnclaveMetric::RpcRequestsIncrement])
+			.unwrap_or_else(|e| error!("failed to update prometheus metric: {:?}", e));
+		let x25519_pubkey = match x25519_shielding_key.retrieve_pubkey() {
+			Ok(key) => key,
+			Err(status) => {
+				let error_msg: String = format!("Could not get x25519 pubkey due to: {}", status);
+				return Ok(json!(compute_hex_encoded_return_error(error_msg.as_str())))
+			},
+		};
+
+		let json_value =
+			RpcReturnValue::new(x25519_pubkey.to_hex().encode(), false, DirectRequestStatus::Ok);
+		Ok(json!(json_value.to_hex()))
+	});
+
 	let local_top_pool_author = top_pool_author.clone();
 	let local_ocall_api = ocall_api.clone();
 	io_handler.add_sync_method("author_getShardVault", move |_: Params| {
@@ -198,6 +233,31 @@ pub fn add_common_api<Author, GetterExecutor, AccessShieldingKey, OCallApi>(
 		Ok(json!(json_value.to_hex()))
 	});
 
+	let local_ocall_api = ocall_api.clone();
+	io_handler.add_sync_method(RPC_METHOD_NAME_GET_FINALIZED_HEAD, move |params: Params| {
+		debug!("worker_api_direct rpc was called: {}", RPC_METHOD_NAME_GET_FINALIZED_HEAD);
+		local_ocall_api
+			.update_metrics(vec![EnclaveMetric::RpcRequestsIncrement])
+			.unwrap_or_else(|e| error!("failed to update prometheus metric: {:?}", e));
+		let shard = match shard_from_params(params) {
+			Ok(shard) => shard,
+			Err(e) => return Ok(json!(compute_hex_encoded_return_error(e.as_str()))),
+		};
+		let json_value = match sidechain_finality_gadget.finalized_head(&shard) {
+			Ok(Some(justification)) =>
+				RpcReturnValue::new(justification.encode(), false, DirectRequestStatus::Ok),
+			Ok(None) => {
+				let error_msg = "No sidechain block has been finalized yet";
+				return Ok(json!(compute_hex_encoded_return_error(error_msg)))
+			},
+			Err(e) => {
+				let error_msg: String = format!("Could not get finalized head due to: {:?}", e);
+				return Ok(json!(compute_hex_encoded_return_error(error_msg.as_str())))
+			},
+		};
+		Ok(json!(json_value.to_hex()))
+	});
+
 	let local_ocall_api = ocall_api.clone();
 	io_handler.add_sync_method("state_getMetadata", move |_: Params| {
 		debug!("worker_api_direct rpc was called: state_getMetadata");
@@ -220,12 +280,13 @@ pub fn add_common_api<Author, GetterExecutor, AccessShieldingKey, OCallApi>(
 	});
 
 	let local_ocall_api = ocall_api.clone();
+	let local_getter_executor = getter_executor.clone();
 	io_handler.add_sync_method("state_executeGetter", move |params: Params| {
 		debug!("worker_api_direct rpc was called: state_executeGetter");
 		local_ocall_api
 			.update_metrics(vec![EnclaveMetric::RpcRequestsIncrement])
 			.unwrap_or_else(|e| error!("failed to update prometheus metric: {:?}", e));
-		let json_value = match execute_getter_inner(getter_executor.as_ref(), params) {
+		let json_value = match execute_getter_inner(local_getter_executor.as_ref(), params) {
 			Ok(state_getter_value) => RpcReturnValue {
 				do_watch: false,
 				value: state_getter_value.encode(),
@@ -237,6 +298,24 @@ pub fn add_common_api<Author, GetterExecutor, AccessShieldingKey, OCallApi>(
 		Ok(json!(json_value))
 	});
 
+	let local_ocall_api = ocall_api.clone();
+	io_handler.add_sync_method("state_executeGetterWithProof", move |params: Params| {
+		debug!("worker_api_direct rpc was called: state_executeGetterWithProof");
+		local_ocall_api
+			.update_metrics(vec![EnclaveMetric::RpcRequestsIncrement])
+			.unwrap_or_else(|e| error!("failed to update prometheus metric: {:?}", e));
+		let json_value = match execute_getter_with_proof_inner(getter_executor.as_ref(), params) {
+			Ok(result_with_proof) => RpcReturnValue {
+				do_watch: false,
+				value: result_with_proof.encode(),
+				status: DirectRequestStatus::Ok,
+			}
+			.to_hex(),
+			Err(error) => compute_hex_encoded_return_error(error.as_str()),
+		};
+		Ok(json!(json_value))
+	});
+
 	let local_ocall_api = ocall_api.clone();
 	io_handler.add_sync_method("attesteer_forwardDcapQuote", move |params: Params| {
 		debug!("worker_api_direct rpc was called: attesteer_forwardDcapQuote");
@@ -329,6 +408,29 @@ fn execute_getter_inner<GE: ExecuteGetter>(
 	Ok(getter_result)
 }
 
+fn execute_getter_with_proof_inner<GE: ExecuteGetter>(
+	getter_executor: &GE,
+	params: Params,
+) -> Result<GetterResultWithProof, String> {
+	let hex_encoded_params = params.parse::<Vec<String>>().map_err(|e| format!("{:?}", e))?;
+
+	let request =
+		Request::from_hex(&hex_encoded_params[0].clone()).map_err(|e| format!("{:?}", e))?;
+
+	// The proof is created against the state root of the latest block of the requested shard.
+	getter_executor
+		.execute_getter_with_proof(&request.shard, request.cyphertext)
+		.map_err(|e| format!("{:?}", e))
+}
+
+fn shard_from_params(params: Params) -> Result<ShardIdentifier, String> {
+	let hex_encoded_params = params.parse::<Vec<String>>().map_err(|e| format!("{:?}", e))?;
+	let hex_encoded_shard = hex_encoded_params
+		.first()
+		.ok_or_else(|| "Missing shard parameter".to_string())?;
+	ShardIdentifier::from_hex(hex_encoded_shard).map_err(|e| format!("{:?}", e))
+}
+
 fn forward_dcap_quote_inner(params: Params) -> Result<OpaqueExtrinsic, String> {
 	let hex_encoded_params = params.parse::<Vec<String>>().map_err(|e| format!("{:?}", e))?;
 
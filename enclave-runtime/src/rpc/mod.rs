@@ -15,5 +15,6 @@
 
 */
 
+pub mod account_events_api;
 pub mod common_api;
 pub mod rpc_response_channel;
@@ -23,13 +23,9 @@ use crate::{
 use codec::{Decode, Encode};
 use itp_component_container::ComponentGetter;
 
-use crate::utils::{
-	get_validator_accessor_from_integritee_solo_or_parachain,
-	get_validator_accessor_from_target_a_solo_or_parachain,
-	get_validator_accessor_from_target_b_solo_or_parachain,
-};
+use crate::utils::get_validator_accessor_from_solo_or_parachain;
 use itp_stf_interface::{
-	parentchain_pallet::ParentchainPalletInstancesInterface, ShardCreationInfo, ShardCreationQuery,
+	parentchain_pallet::ParentchainPalletInterface, ShardCreationInfo, ShardCreationQuery,
 };
 use itp_stf_state_handler::{handle_state::HandleState, query_shard_state::QueryShardState};
 use itp_types::{
@@ -120,12 +116,8 @@ fn init_shard_creation_parentchain_header_internal(
 }
 
 fn get_genesis_hash(parentchain_id: ParentchainId) -> EnclaveResult<Hash> {
-	let va = match parentchain_id {
-		ParentchainId::Integritee => get_validator_accessor_from_integritee_solo_or_parachain(),
-		ParentchainId::TargetA => get_validator_accessor_from_target_a_solo_or_parachain(),
-		ParentchainId::TargetB => get_validator_accessor_from_target_b_solo_or_parachain(),
-	}?;
-	va.genesis_hash()
+	get_validator_accessor_from_solo_or_parachain(parentchain_id)?
+		.genesis_hash()
 		.ok_or_else(|| Error::Other("genesis hash missing for parentchain".into()))
 }
 /// reads the shard vault account id form state if it has been initialized previously
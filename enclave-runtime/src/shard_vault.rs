@@ -21,12 +21,8 @@ use crate::{
 	},
 	std::string::ToString,
 	utils::{
-		get_extrinsic_factory_from_integritee_solo_or_parachain,
-		get_extrinsic_factory_from_target_a_solo_or_parachain,
-		get_extrinsic_factory_from_target_b_solo_or_parachain,
-		get_node_metadata_repository_from_integritee_solo_or_parachain,
-		get_node_metadata_repository_from_target_a_solo_or_parachain,
-		get_node_metadata_repository_from_target_b_solo_or_parachain, try_mortality, DecodeRaw,
+		get_extrinsic_factory_from_solo_or_parachain,
+		get_node_metadata_repository_from_solo_or_parachain, try_mortality, DecodeRaw,
 	},
 };
 use codec::{Compact, Decode, Encode};
@@ -40,7 +36,7 @@ use itp_node_api_metadata::pallet_proxy::ProxyCallIndexes;
 use itp_nonce_cache::NonceCache;
 use itp_ocall_api::EnclaveOnChainOCallApi;
 use itp_sgx_crypto::key_repository::AccessKey;
-use itp_stf_interface::{parentchain_pallet::ParentchainPalletInstancesInterface, ShardVaultQuery};
+use itp_stf_interface::{parentchain_pallet::ParentchainPalletInterface, ShardVaultQuery};
 use itp_stf_state_handler::{handle_state::HandleState, query_shard_state::QueryShardState};
 use itp_types::{
 	parentchain::{AccountId, Address, Balance, ParentchainId, ProxyType},
@@ -150,29 +146,8 @@ pub(crate) fn init_proxied_shard_vault_internal(
 	EnclaveStf::init_shard_vault_account(&mut state, vault.public().into(), parentchain_id)
 		.map_err(|e| Error::Stf(e.to_string()))?;
 	state_handler.write_after_mutation(state, state_lock, &shard)?;
-	let (enclave_extrinsics_factory, node_metadata_repo) = match parentchain_id {
-		ParentchainId::Integritee => {
-			let enclave_extrinsics_factory =
-				get_extrinsic_factory_from_integritee_solo_or_parachain()?;
-			let node_metadata_repo =
-				get_node_metadata_repository_from_integritee_solo_or_parachain()?;
-			(enclave_extrinsics_factory, node_metadata_repo)
-		},
-		ParentchainId::TargetA => {
-			let enclave_extrinsics_factory =
-				get_extrinsic_factory_from_target_a_solo_or_parachain()?;
-			let node_metadata_repo =
-				get_node_metadata_repository_from_target_a_solo_or_parachain()?;
-			(enclave_extrinsics_factory, node_metadata_repo)
-		},
-		ParentchainId::TargetB => {
-			let enclave_extrinsics_factory =
-				get_extrinsic_factory_from_target_b_solo_or_parachain()?;
-			let node_metadata_repo =
-				get_node_metadata_repository_from_target_b_solo_or_parachain()?;
-			(enclave_extrinsics_factory, node_metadata_repo)
-		},
-	};
+	let enclave_extrinsics_factory = get_extrinsic_factory_from_solo_or_parachain(parentchain_id)?;
+	let node_metadata_repo = get_node_metadata_repository_from_solo_or_parachain(parentchain_id)?;
 
 	info!(
 		"[{:?}] send existential funds from enclave account to vault account: {:?}",
@@ -235,17 +210,8 @@ pub(crate) fn add_shard_vault_proxy(
 	let ocall_api = GLOBAL_OCALL_API_COMPONENT.get()?;
 	let (vault, parentchain_id) = get_shard_vault_internal(shard)?;
 
-	let enclave_extrinsics_factory = match parentchain_id {
-		ParentchainId::Integritee => get_extrinsic_factory_from_integritee_solo_or_parachain()?,
-		ParentchainId::TargetA => get_extrinsic_factory_from_target_a_solo_or_parachain()?,
-		ParentchainId::TargetB => get_extrinsic_factory_from_target_b_solo_or_parachain()?,
-	};
-	let node_metadata_repo = match parentchain_id {
-		ParentchainId::Integritee =>
-			get_node_metadata_repository_from_integritee_solo_or_parachain()?,
-		ParentchainId::TargetA => get_node_metadata_repository_from_target_a_solo_or_parachain()?,
-		ParentchainId::TargetB => get_node_metadata_repository_from_target_b_solo_or_parachain()?,
-	};
+	let enclave_extrinsics_factory = get_extrinsic_factory_from_solo_or_parachain(parentchain_id)?;
+	let node_metadata_repo = get_node_metadata_repository_from_solo_or_parachain(parentchain_id)?;
 
 	debug!(
 		"adding proxy 0x{} to shard vault account 0x{} on {:?}",
@@ -16,7 +16,10 @@
 
 */
 
-use crate::{rpc::common_api::add_common_api, Hash};
+use crate::{
+	initialization::global_components::EnclaveSidechainFinalityGadget,
+	rpc::common_api::add_common_api, Hash,
+};
 use codec::{Decode, Encode};
 use ita_stf::{Getter, TrustedGetter, TrustedGetterSigned};
 use itc_direct_rpc_server::{
@@ -25,9 +28,10 @@ use itc_direct_rpc_server::{
 };
 use itc_tls_websocket_server::{ConnectionToken, WebSocketMessageHandler};
 use itp_rpc::{RpcRequest, RpcReturnValue};
-use itp_sgx_crypto::get_rsa3072_repository;
+use itp_sgx_crypto::{get_rsa3072_repository, get_x25519_repository, key_repository::AccessPubkey};
 use itp_sgx_temp_dir::TempDir;
 use itp_stf_executor::{getter_executor::GetterExecutor, mocks::GetStateMock};
+use itp_stf_primitives::types::GetterResultWithProof;
 use itp_stf_state_observer::mock::ObserveStateMock;
 use itp_test::mock::onchain_mock::OnchainMock;
 use itp_top_pool_author::mocks::AuthorApiMock;
@@ -35,6 +39,7 @@ use itp_types::{AccountId, DirectRequestStatus, Request, ShardIdentifier};
 use itp_utils::{FromHexPrefixed, ToHexPrefixed};
 use its_block_header_cache::{CachedSidechainBlockHeader, SidechainBlockHeaderCache};
 use its_primitives::types::header::SidechainHeader;
+use its_sidechain::consensus_common::OwnFinalityVotesSeal;
 use jsonrpc_core::IoHandler;
 use sp_core::ed25519::Signature;
 use sp_runtime::MultiSignature;
@@ -48,11 +53,14 @@ pub fn get_state_request_works() {
 	let connection_registry = Arc::new(ConnectionRegistry::<Hash, ConnectionToken>::new());
 	let watch_extractor = Arc::new(create_determine_watch::<Hash>());
 	let rsa_repository = get_rsa3072_repository(temp_dir.path().to_path_buf()).unwrap();
+	let x25519_repository = get_x25519_repository(temp_dir.path().to_path_buf()).unwrap();
 
 	let state: TestState = 78234u64;
 	let state_observer = Arc::new(ObserveStateMock::<TestState>::new(state));
-	let getter_executor =
-		Arc::new(GetterExecutor::<_, GetStateMock<TestState>, Getter>::new(state_observer));
+	let getter_executor = Arc::new(GetterExecutor::<_, GetStateMock<TestState>, Getter>::new(
+		state_observer,
+		Default::default(),
+	));
 	let top_pool_author = Arc::new(AuthorApiMock::default());
 	let ocall_api = Arc::new(OnchainMock::default());
 	let mut io_handler = IoHandler::new();
@@ -61,12 +69,14 @@ pub fn get_state_request_works() {
 		top_pool_author,
 		getter_executor,
 		Arc::new(rsa_repository),
+		Arc::new(x25519_repository),
 		ocall_api,
 		"0.0.0-test".into(),
 		SidechainBlockHeaderCache::new(
 			CachedSidechainBlockHeader(SidechainHeader::default()).into(),
 		)
 		.into(),
+		finality_gadget(&temp_dir),
 	);
 
 	let rpc_handler = Arc::new(RpcWsHandler::new(io_handler, watch_extractor, connection_registry));
@@ -100,3 +110,124 @@ pub fn get_state_request_works() {
 		Option::decode(&mut rpc_return_value.value.as_slice()).unwrap();
 	assert_eq!(decoded_value, Some(state.encode()));
 }
+
+pub fn get_state_with_proof_request_works() {
+	type TestState = u64;
+
+	let temp_dir = TempDir::with_prefix("get_state_with_proof_request_works").unwrap();
+
+	let connection_registry = Arc::new(ConnectionRegistry::<Hash, ConnectionToken>::new());
+	let watch_extractor = Arc::new(create_determine_watch::<Hash>());
+	let rsa_repository = get_rsa3072_repository(temp_dir.path().to_path_buf()).unwrap();
+	let x25519_repository = get_x25519_repository(temp_dir.path().to_path_buf()).unwrap();
+
+	let state: TestState = 78234u64;
+	let state_observer = Arc::new(ObserveStateMock::<TestState>::new(state));
+	let getter_executor = Arc::new(GetterExecutor::<_, GetStateMock<TestState>, Getter>::new(
+		state_observer,
+		Default::default(),
+	));
+	let top_pool_author = Arc::new(AuthorApiMock::default());
+	let ocall_api = Arc::new(OnchainMock::default());
+	let mut io_handler = IoHandler::new();
+	add_common_api(
+		&mut io_handler,
+		top_pool_author,
+		getter_executor,
+		Arc::new(rsa_repository),
+		Arc::new(x25519_repository),
+		ocall_api,
+		"0.0.0-test".into(),
+		SidechainBlockHeaderCache::new(
+			CachedSidechainBlockHeader(SidechainHeader::default()).into(),
+		)
+		.into(),
+		finality_gadget(&temp_dir),
+	);
+
+	let rpc_handler = Arc::new(RpcWsHandler::new(io_handler, watch_extractor, connection_registry));
+
+	let getter = Getter::trusted(TrustedGetterSigned::new(
+		TrustedGetter::account_info(AccountId::new([0u8; 32])),
+		MultiSignature::Ed25519(Signature::from_raw([0u8; 64])),
+	));
+
+	let request = Request { shard: ShardIdentifier::default(), cyphertext: getter.encode() };
+
+	let request_string = RpcRequest::compose_jsonrpc_call(
+		"state_executeGetterWithProof".to_string(),
+		vec![request.to_hex()],
+	)
+	.unwrap();
+
+	let response_string =
+		rpc_handler.handle_message(ConnectionToken(1), request_string).unwrap().unwrap();
+
+	// The mock returns an empty proof against the default state root, which matches the one of
+	// the default header in the cache.
+	let expected_result =
+		GetterResultWithProof { value: Some(state.encode()), ..Default::default() };
+	let expected_return_value = RpcReturnValue {
+		do_watch: false,
+		value: expected_result.encode(),
+		status: DirectRequestStatus::Ok,
+	};
+	assert!(response_string.contains(&expected_return_value.to_hex()));
+}
+
+pub fn get_x25519_shielding_key_request_works() {
+	let temp_dir = TempDir::with_prefix("get_x25519_shielding_key_request_works").unwrap();
+
+	let connection_registry = Arc::new(ConnectionRegistry::<Hash, ConnectionToken>::new());
+	let watch_extractor = Arc::new(create_determine_watch::<Hash>());
+	let rsa_repository = get_rsa3072_repository(temp_dir.path().to_path_buf()).unwrap();
+	let x25519_repository = Arc::new(get_x25519_repository(temp_dir.path().to_path_buf()).unwrap());
+
+	let state_observer = Arc::new(ObserveStateMock::<u64>::new(0u64));
+	let getter_executor = Arc::new(GetterExecutor::<_, GetStateMock<u64>, Getter>::new(
+		state_observer,
+		Default::default(),
+	));
+	let mut io_handler = IoHandler::new();
+	add_common_api(
+		&mut io_handler,
+		Arc::new(AuthorApiMock::default()),
+		getter_executor,
+		Arc::new(rsa_repository),
+		x25519_repository.clone(),
+		Arc::new(OnchainMock::default()),
+		"0.0.0-test".into(),
+		SidechainBlockHeaderCache::new(
+			CachedSidechainBlockHeader(SidechainHeader::default()).into(),
+		)
+		.into(),
+		finality_gadget(&temp_dir),
+	);
+
+	let rpc_handler = Arc::new(RpcWsHandler::new(io_handler, watch_extractor, connection_registry));
+
+	let request_string = RpcRequest::compose_jsonrpc_call(
+		"author_getX25519ShieldingKey".to_string(),
+		Default::default(),
+	)
+	.unwrap();
+
+	let response_string =
+		rpc_handler.handle_message(ConnectionToken(1), request_string).unwrap().unwrap();
+
+	let expected_return_value = RpcReturnValue {
+		do_watch: false,
+		value: x25519_repository.retrieve_pubkey().unwrap().to_hex().encode(),
+		status: DirectRequestStatus::Ok,
+	};
+	assert!(response_string.contains(&expected_return_value.to_hex()));
+}
+
+fn finality_gadget(temp_dir: &TempDir) -> Arc<EnclaveSidechainFinalityGadget> {
+	Arc::new(
+		EnclaveSidechainFinalityGadget::new(OwnFinalityVotesSeal::new(
+			temp_dir.path().to_path_buf(),
+		))
+		.unwrap(),
+	)
+}
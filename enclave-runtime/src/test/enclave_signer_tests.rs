@@ -25,7 +25,7 @@ use itp_sgx_crypto::{
 use itp_sgx_externalities::SgxExternalities;
 use itp_stf_executor::{enclave_signer::StfEnclaveSigner, traits::StfEnclaveSigning};
 use itp_stf_interface::{
-	mocks::GetterExecutorMock, parentchain_pallet::ParentchainPalletInstancesInterface,
+	mocks::GetterExecutorMock, parentchain_pallet::ParentchainPalletInterface,
 	system_pallet::SystemPalletAccountInterface, InitState, StateCallInterface,
 };
 use itp_stf_primitives::{
@@ -19,7 +19,7 @@
 use super::test_setup::TestStf;
 use ita_stf::{stf_sgx_tests::StfState, State};
 use itp_sgx_externalities::{SgxExternalities, SgxExternalitiesTrait};
-use itp_stf_interface::{parentchain_pallet::ParentchainPalletInstancesInterface, InitState};
+use itp_stf_interface::{parentchain_pallet::ParentchainPalletInterface, InitState};
 use itp_stf_primitives::types::AccountId;
 use itp_stf_state_handler::handle_state::HandleState;
 use itp_types::{parentchain::ParentchainId, ShardIdentifier};
@@ -62,6 +62,10 @@ impl EnclaveAttestationOCallApi for AttestationOCallMock {
 		unreachable!()
 	}
 
+	fn get_dcap_collateral(&self, _fmspc: [u8; 6]) -> SgxResult<Vec<u8>> {
+		unreachable!()
+	}
+
 	fn get_qve_report_on_quote(
 		&self,
 		_quote: Vec<u8>,
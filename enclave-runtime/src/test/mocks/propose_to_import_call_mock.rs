@@ -113,6 +113,27 @@ impl EnclaveSidechainOCallApi for ProposeToImportOCallApi {
 		Ok(())
 	}
 
+	fn propose_finality_votes<SignedFinalityVote: Encode>(
+		&self,
+		_votes: Vec<SignedFinalityVote>,
+	) -> SgxResult<()> {
+		Ok(())
+	}
+
+	fn store_sidechain_justifications<Justification: Encode>(
+		&self,
+		_justifications: Vec<Justification>,
+	) -> SgxResult<()> {
+		Ok(())
+	}
+
+	fn fetch_sidechain_finalized_head<Justification: Decode>(
+		&self,
+		_shard_identifier: ShardIdentifier,
+	) -> SgxResult<Option<Justification>> {
+		Ok(None)
+	}
+
 	fn fetch_sidechain_blocks_from_peer<SignedSidechainBlock: Decode>(
 		&self,
 		_last_imported_block_hash: BlockHash,
@@ -23,7 +23,7 @@ use ita_sgx_runtime::Runtime;
 use ita_stf::{Getter, Stf, TrustedCallSigned};
 use itc_parentchain::block_import_dispatcher::trigger_parentchain_block_import_mock::TriggerParentchainBlockImportMock;
 use itp_node_api::metadata::{metadata_mocks::NodeMetadataMock, provider::NodeMetadataRepository};
-use itp_sgx_crypto::{mocks::KeyRepositoryMock, Aes};
+use itp_sgx_crypto::{mocks::KeyRepositoryMock, AesGcm};
 use itp_sgx_externalities::SgxExternalities;
 use itp_stf_executor::executor::StfExecutor;
 use itp_stf_primitives::types::TrustedOperation;
@@ -39,7 +39,7 @@ use sp_core::ed25519 as spEd25519;
 
 pub type TestSigner = spEd25519::Pair;
 pub type TestShieldingKey = Rsa3072KeyPair;
-pub type TestStateKey = Aes;
+pub type TestStateKey = AesGcm;
 
 pub type TestGetter = Getter;
 pub type TestCall = TrustedCallSigned;
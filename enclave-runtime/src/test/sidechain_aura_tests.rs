@@ -41,7 +41,7 @@ use itp_settings::{
 	sidechain::SLOT_DURATION,
 	worker_mode::{ProvideWorkerMode, WorkerMode, WorkerModeProvider},
 };
-use itp_sgx_crypto::{Aes, ShieldingCryptoEncrypt, StateCrypto};
+use itp_sgx_crypto::{AesGcm, ShieldingCryptoEncrypt, StateCrypto};
 use itp_sgx_externalities::SgxExternalitiesDiffType;
 use itp_stf_interface::system_pallet::{SystemPalletAccountInterface, SystemPalletEventInterface};
 use itp_stf_primitives::types::{StatePayload, TrustedOperation};
@@ -59,7 +59,7 @@ use log::*;
 use primitive_types::H256;
 use sgx_crypto_helper::RsaKeyPair;
 use sp_core::{ed25519, Pair};
-use std::{sync::Arc, vec, vec::Vec};
+use std::{collections::BTreeMap, sync::Arc, vec, vec::Vec};
 
 /// Integration test for sidechain block production and block import.
 /// (requires Sidechain mode)
@@ -79,7 +79,7 @@ pub fn produce_sidechain_block_and_import_it() {
 
 	let signer = TestSigner::from_seed(b"42315678901234567890123456789012");
 	let shielding_key = TestShieldingKey::new().unwrap();
-	let state_key = TestStateKey::new([3u8; 16], [1u8; 16]);
+	let state_key = TestStateKey::new([3u8; 16]);
 	let shielding_key_repo = Arc::new(TestShieldingKeyRepo::new(shielding_key));
 	let state_key_repo = Arc::new(TestStateKeyRepo::new(state_key));
 	let parentchain_header = ParentchainHeaderBuilder::default().build();
@@ -114,8 +114,10 @@ pub fn produce_sidechain_block_and_import_it() {
 		parentchain_block_import_trigger.clone(),
 		ocall_api.clone(),
 		SidechainBlockHeaderCache::default().into(),
+		Default::default(),
 	));
-	let block_composer = Arc::new(TestBlockComposer::new(signer, state_key_repo));
+	let block_composer =
+		Arc::new(TestBlockComposer::new(signer, state_key_repo, Default::default()));
 	let proposer_environment =
 		ProposerFactory::new(top_pool_author.clone(), stf_executor, block_composer);
 
@@ -158,8 +160,7 @@ pub fn produce_sidechain_block_and_import_it() {
 		SLOT_DURATION,
 		ends_at,
 		parentchain_header.clone(),
-		None,
-		None,
+		Default::default(),
 	);
 
 	info!("Test setup is done.");
@@ -168,15 +169,16 @@ pub fn produce_sidechain_block_and_import_it() {
 
 	info!("Executing AURA on slot..");
 	let (blocks, opaque_calls) =
-		exec_aura_on_slot::<_, ParentchainBlock, SignedSidechainBlock, _, _, _, _, _>(
+		exec_aura_on_slot::<_, ParentchainBlock, SignedSidechainBlock, _, _, _, _>(
 			slot_info,
 			signer,
 			ocall_api,
 			parentchain_block_import_trigger.clone(),
-			None::<Arc<TestParentchainBlockImportTrigger>>,
-			None::<Arc<TestParentchainBlockImportTrigger>>,
+			BTreeMap::<_, Arc<TestParentchainBlockImportTrigger>>::new(),
 			proposer_environment,
 			shards,
+			Default::default(),
+			None,
 		)
 		.unwrap();
 
@@ -245,7 +247,7 @@ fn encrypted_trusted_operation_transfer_balance<
 
 fn get_state_hashes_from_block(
 	signed_block: &SignedSidechainBlock,
-	state_key: &Aes,
+	state_key: &AesGcm,
 ) -> (H256, H256) {
 	let mut encrypted_state_diff = signed_block.block.block_data().encrypted_state_diff.clone();
 	state_key.decrypt(&mut encrypted_state_diff).unwrap();
@@ -49,7 +49,7 @@ use log::*;
 use primitive_types::H256;
 use sgx_crypto_helper::RsaKeyPair;
 use sp_core::Pair;
-use std::{sync::Arc, vec};
+use std::{collections::BTreeMap, sync::Arc, vec};
 
 /// Integration test to ensure the events are reset upon block import.
 /// Otherwise we will have an ever growing state.
@@ -66,7 +66,7 @@ pub fn ensure_events_get_reset_upon_block_proposal() {
 
 	let signer = TestSigner::from_seed(b"42315678901234567890123456789012");
 	let shielding_key = TestShieldingKey::new().unwrap();
-	let state_key = TestStateKey::new([3u8; 16], [1u8; 16]);
+	let state_key = TestStateKey::new([3u8; 16]);
 	let shielding_key_repo = Arc::new(TestShieldingKeyRepo::new(shielding_key));
 	let state_key_repo = Arc::new(TestStateKeyRepo::new(state_key));
 	let parentchain_header = ParentchainHeaderBuilder::default().build();
@@ -101,8 +101,10 @@ pub fn ensure_events_get_reset_upon_block_proposal() {
 		parentchain_block_import_trigger.clone(),
 		ocall_api.clone(),
 		SidechainBlockHeaderCache::default().into(),
+		Default::default(),
 	));
-	let block_composer = Arc::new(TestBlockComposer::new(signer, state_key_repo));
+	let block_composer =
+		Arc::new(TestBlockComposer::new(signer, state_key_repo, Default::default()));
 	let proposer_environment = ProposerFactory::new(top_pool_author, stf_executor, block_composer);
 
 	// Add some events to the state.
@@ -134,21 +136,21 @@ pub fn ensure_events_get_reset_upon_block_proposal() {
 		SLOT_DURATION,
 		ends_at,
 		parentchain_header.clone(),
-		None,
-		None,
+		Default::default(),
 	);
 
 	info!("Executing AURA on slot..");
 	let (blocks, opaque_calls) =
-		exec_aura_on_slot::<_, ParentchainBlock, SignedSidechainBlock, _, _, _, _, _>(
+		exec_aura_on_slot::<_, ParentchainBlock, SignedSidechainBlock, _, _, _, _>(
 			slot_info,
 			signer,
 			ocall_api,
 			parentchain_block_import_trigger,
-			None::<Arc<TestParentchainBlockImportTrigger>>,
-			None::<Arc<TestParentchainBlockImportTrigger>>,
+			BTreeMap::<_, Arc<TestParentchainBlockImportTrigger>>::new(),
 			proposer_environment,
 			shards,
+			Default::default(),
+			None,
 		)
 		.unwrap();
 
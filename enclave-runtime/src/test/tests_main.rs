@@ -31,7 +31,7 @@ use crate::{
 	tls_ra,
 };
 use codec::Decode;
-use ita_sgx_runtime::ParentchainIntegritee;
+use ita_sgx_runtime::Parentchain;
 use ita_stf::{
 	helpers::{account_key_hash, set_block_number},
 	stf_sgx_tests,
@@ -39,13 +39,13 @@ use ita_stf::{
 	AccountInfo, Getter, State, TrustedCall, TrustedCallSigned, TrustedGetter,
 };
 use itp_node_api::metadata::{metadata_mocks::NodeMetadataMock, provider::NodeMetadataRepository};
-use itp_sgx_crypto::{Aes, StateCrypto};
+use itp_sgx_crypto::{AesGcm, StateCrypto};
 use itp_sgx_externalities::{SgxExternalitiesDiffType, SgxExternalitiesTrait, StateHash};
 use itp_stf_executor::{
 	executor_tests as stf_executor_tests, traits::StateUpdateProposer, BatchExecutionResult,
 };
 use itp_stf_interface::{
-	parentchain_pallet::ParentchainPalletInstancesInterface,
+	parentchain_pallet::ParentchainPalletInterface,
 	system_pallet::{SystemPalletAccountInterface, SystemPalletEventInterface},
 	StateCallInterface,
 };
@@ -78,7 +78,14 @@ pub extern "C" fn test_main_entrance() -> size_t {
 	rsgx_unit_tests!(
 		itp_attestation_handler::attestation_handler::tests::decode_spid_works,
 		stf_sgx_tests::enclave_account_initialization_works,
+		stf_sgx_tests::enclave_account_update_works,
+		stf_sgx_tests::rotate_enclave_account_migrates_funds_and_assets,
 		stf_sgx_tests::shield_funds_increments_signer_account_nonce,
+		stf_sgx_tests::shield_assets_creates_asset_and_mints_to_beneficiary,
+		stf_sgx_tests::shield_assets_fails_for_other_parentchain_than_shard_vault,
+		stf_sgx_tests::scheduled_call_is_executed_when_due,
+		stf_sgx_tests::multisig_call_is_executed_when_threshold_is_reached,
+		stf_sgx_tests::trapping_contract_does_not_change_state,
 		stf_sgx_tests::test_root_account_exists_after_initialization,
 		itp_stf_state_handler::test::sgx_tests::test_write_and_load_state_works,
 		itp_stf_state_handler::test::sgx_tests::test_sgx_state_decode_encode_works,
@@ -89,14 +96,25 @@ pub extern "C" fn test_main_entrance() -> size_t {
 		itp_stf_state_handler::test::sgx_tests::test_multiple_state_updates_create_snapshots_up_to_cache_size,
 		itp_stf_state_handler::test::sgx_tests::test_state_files_from_handler_can_be_loaded_again,
 		itp_stf_state_handler::test::sgx_tests::test_file_io_get_state_hash_works,
+		itp_stf_state_handler::test::sgx_tests::test_delta_file_io_writes_diff_segments_and_replays_them,
+		itp_stf_state_handler::test::sgx_tests::test_delta_file_io_keeps_removed_snapshots_until_no_longer_needed,
+		itp_stf_state_handler::test::sgx_tests::test_loading_tampered_state_file_fails,
+		itp_stf_state_handler::test::sgx_tests::test_migrate_legacy_state_encryption_reencrypts_all_snapshot_files,
+		itp_stf_state_handler::test::sgx_tests::test_state_reencryption_replaces_files_only_after_key_update,
 		itp_stf_state_handler::test::sgx_tests::test_list_state_ids_ignores_files_not_matching_the_pattern,
 		itp_stf_state_handler::test::sgx_tests::test_in_memory_state_initializes_from_shard_directory,
 		itp_sgx_crypto::tests::aes_sealing_works,
 		itp_sgx_crypto::tests::using_get_aes_repository_twice_initializes_key_only_once,
+		itp_sgx_crypto::tests::using_get_aes_gcm_repository_twice_initializes_key_only_once,
+		itp_sgx_crypto::tests::get_aes_gcm_repository_derives_key_from_legacy_key,
+		itp_sgx_crypto::tests::x25519_sealing_works,
+		itp_sgx_crypto::tests::rotated_x25519_key_is_retained_across_restarts,
+		itp_sgx_crypto::tests::using_get_x25519_repository_twice_initializes_key_only_once,
 		itp_sgx_crypto::tests::ed25529_sealing_works,
 		itp_sgx_crypto::tests::using_get_ed25519_repository_twice_initializes_key_only_once,
 		itp_sgx_crypto::tests::rsa3072_sealing_works,
 		itp_sgx_crypto::tests::using_get_rsa3072_repository_twice_initializes_key_only_once,
+		itp_sgx_crypto::tests::rotated_rsa3072_key_is_retained_across_restarts,
 		test_compose_block,
 		test_submit_trusted_call_to_top_pool,
 		test_submit_trusted_getter_to_top_pool,
@@ -144,16 +162,29 @@ pub extern "C" fn test_main_entrance() -> size_t {
 		tls_ra::seal_handler::test::seal_shielding_key_works,
 		tls_ra::seal_handler::test::seal_shielding_key_fails_for_invalid_key,
 		tls_ra::seal_handler::test::unseal_seal_shielding_key_works,
+		tls_ra::seal_handler::test::unseal_seal_x25519_shielding_key_works,
 		tls_ra::seal_handler::test::seal_state_key_works,
+		tls_ra::seal_handler::test::seal_state_key_converts_legacy_key,
 		tls_ra::seal_handler::test::seal_state_key_fails_for_invalid_key,
 		tls_ra::seal_handler::test::unseal_seal_state_key_works,
 		tls_ra::seal_handler::test::seal_state_works,
 		tls_ra::seal_handler::test::seal_state_fails_for_invalid_state,
 		tls_ra::seal_handler::test::unseal_seal_state_works,
+		tls_ra::seal_handler::test::seal_shard_state_works,
+		tls_ra::seal_handler::test::seal_shard_state_fails_without_light_client_state,
+		tls_ra::seal_handler::test::seal_shard_state_fails_without_keys,
+		tls_ra::seal_handler::test::seal_shard_state_fails_for_different_state_key,
+		tls_ra::seal_handler::test::seal_shard_state_fails_for_different_shielding_key,
+		tls_ra::seal_handler::test::seal_shard_state_fails_for_light_client_at_different_block,
+		tls_ra::seal_handler::test::seal_shard_state_fails_for_existing_shard,
 		tls_ra::tests::test_tls_ra_server_client_networking,
 		tls_ra::tests::test_state_and_key_provisioning,
+		tls_ra::tests::test_tls_ra_rotated_keys_are_provisioned_without_state,
+		tls_ra::tests::test_tls_ra_shard_is_provisioned_with_keys,
 		// RPC tests
 		direct_rpc_tests::get_state_request_works,
+		direct_rpc_tests::get_state_with_proof_request_works,
+		direct_rpc_tests::get_x25519_shielding_key_request_works,
 
 		// EVM tests
 		run_evm_tests,
@@ -479,11 +510,15 @@ fn test_call_set_update_parentchain_block() {
 		Default::default(),
 	);
 
-	TestStf::update_parentchain_integritee_block(&mut state, header.clone()).unwrap();
+	let parentchain_id = ParentchainId::Integritee;
+	TestStf::update_parentchain_block(&mut state, header.clone(), parentchain_id).unwrap();
 
-	assert_eq!(Some(header.hash()), state.execute_with(ParentchainIntegritee::block_hash));
-	assert_eq!(Some(parent_hash), state.execute_with(ParentchainIntegritee::parent_hash));
-	assert_eq!(Some(block_number), state.execute_with(ParentchainIntegritee::block_number));
+	assert_eq!(Some(header.hash()), state.execute_with(|| Parentchain::block_hash(parentchain_id)));
+	assert_eq!(Some(parent_hash), state.execute_with(|| Parentchain::parent_hash(parentchain_id)));
+	assert_eq!(
+		Some(block_number),
+		state.execute_with(|| Parentchain::block_number(parentchain_id))
+	);
 }
 
 fn test_signature_must_match_public_sender_in_call() {
@@ -712,8 +747,8 @@ pub fn encrypted_state_diff_from_encrypted(
 	StatePayload::decode(&mut encrypted_payload.as_slice()).unwrap()
 }
 
-pub fn state_key() -> Aes {
-	Aes::default()
+pub fn state_key() -> AesGcm {
+	AesGcm::default()
 }
 
 /// Some random account that has no funds in the `Stf`'s `test_genesis` config.
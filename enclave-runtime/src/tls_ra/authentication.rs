@@ -16,6 +16,8 @@
 */
 
 //! Remote attestation certificate authentication of server and client
+#[cfg(feature = "dcap")]
+use crate::initialization::global_components::GLOBAL_DCAP_COLLATERAL_REPOSITORY_COMPONENT;
 use itp_attestation_handler::cert;
 use itp_ocall_api::EnclaveAttestationOCallApi;
 use log::*;
@@ -68,11 +70,7 @@ where
 			return Err(rustls::TLSError::NoCertificatesPresented)
 		}
 
-		#[cfg(feature = "dcap")]
-		let is_dcap = true;
-		#[cfg(not(feature = "dcap"))]
-		let is_dcap = false;
-		match cert::verify_mra_cert(&certs[0].0, true, is_dcap, &self.attestation_ocall) {
+		match verify_mra_cert(&certs[0].0, &self.attestation_ocall) {
 			Ok(()) => Ok(rustls::ClientCertVerified::assertion()),
 			Err(sgx_status_t::SGX_ERROR_UPDATE_NEEDED) =>
 				if self.outdated_ok {
@@ -126,12 +124,8 @@ where
 			return Err(rustls::TLSError::NoCertificatesPresented)
 		}
 
-		#[cfg(feature = "dcap")]
-		let is_dcap = true;
-		#[cfg(not(feature = "dcap"))]
-		let is_dcap = false;
 		// This call will automatically verify cert is properly signed
-		match cert::verify_mra_cert(&certs[0].0, true, is_dcap, &self.attestation_ocall) {
+		match verify_mra_cert(&certs[0].0, &self.attestation_ocall) {
 			Ok(()) => Ok(rustls::ServerCertVerified::assertion()),
 			Err(sgx_status_t::SGX_ERROR_UPDATE_NEEDED) =>
 				if self.outdated_ok {
@@ -144,3 +138,23 @@ where
 		}
 	}
 }
+
+#[cfg(feature = "dcap")]
+fn verify_mra_cert<A: EnclaveAttestationOCallApi>(
+	cert_der: &[u8],
+	attestation_ocall: &A,
+) -> SgxResult<()> {
+	let quote_verifier = GLOBAL_DCAP_COLLATERAL_REPOSITORY_COMPONENT.get().map_err(|e| {
+		error!("Failed to get DCAP collateral repository: {:?}", e);
+		sgx_status_t::SGX_ERROR_UNEXPECTED
+	})?;
+	cert::verify_dcap_mra_cert(cert_der, true, quote_verifier.as_ref(), attestation_ocall)
+}
+
+#[cfg(not(feature = "dcap"))]
+fn verify_mra_cert<A: EnclaveAttestationOCallApi>(
+	cert_der: &[u8],
+	attestation_ocall: &A,
+) -> SgxResult<()> {
+	cert::verify_mra_cert(cert_der, true, attestation_ocall)
+}
@@ -26,7 +26,9 @@ use std::{
 #[derive(Clone)]
 pub struct SealHandlerMock {
 	pub shielding_key: Arc<RwLock<Vec<u8>>>,
+	pub x25519_shielding_key: Arc<RwLock<Vec<u8>>>,
 	pub state_key: Arc<RwLock<Vec<u8>>>,
+	pub pending_key_rotation: Arc<RwLock<Vec<u8>>>,
 	pub state: Arc<RwLock<Vec<u8>>>,
 	pub light_client_state: Arc<RwLock<Vec<u8>>>,
 }
@@ -38,7 +40,24 @@ impl SealHandlerMock {
 		state: Arc<RwLock<Vec<u8>>>,
 		light_client_state: Arc<RwLock<Vec<u8>>>,
 	) -> Self {
-		Self { shielding_key, state_key, state, light_client_state }
+		Self {
+			shielding_key,
+			x25519_shielding_key: Default::default(),
+			state_key,
+			pending_key_rotation: Default::default(),
+			state,
+			light_client_state,
+		}
+	}
+
+	pub fn with_x25519_shielding_key(mut self, x25519_shielding_key: Arc<RwLock<Vec<u8>>>) -> Self {
+		self.x25519_shielding_key = x25519_shielding_key;
+		self
+	}
+
+	pub fn with_pending_key_rotation(mut self, pending_key_rotation: Arc<RwLock<Vec<u8>>>) -> Self {
+		self.pending_key_rotation = pending_key_rotation;
+		self
 	}
 }
 
@@ -48,11 +67,21 @@ impl SealStateAndKeys for SealHandlerMock {
 		Ok(())
 	}
 
+	fn seal_x25519_shielding_key(&self, bytes: &[u8]) -> EnclaveResult<()> {
+		*self.x25519_shielding_key.write().unwrap() = bytes.to_vec();
+		Ok(())
+	}
+
 	fn seal_state_key(&self, bytes: &[u8]) -> EnclaveResult<()> {
 		*self.state_key.write().unwrap() = bytes.to_vec();
 		Ok(())
 	}
 
+	fn seal_pending_key_rotation(&self, bytes: &[u8]) -> EnclaveResult<()> {
+		*self.pending_key_rotation.write().unwrap() = bytes.to_vec();
+		Ok(())
+	}
+
 	fn seal_state(&self, bytes: &[u8], _shard: &ShardIdentifier) -> EnclaveResult<()> {
 		*self.state.write().unwrap() = bytes.to_vec();
 		Ok(())
@@ -73,10 +102,18 @@ impl UnsealStateAndKeys for SealHandlerMock {
 		Ok(self.shielding_key.read().unwrap().clone())
 	}
 
+	fn unseal_x25519_shielding_key(&self) -> EnclaveResult<Vec<u8>> {
+		Ok(self.x25519_shielding_key.read().unwrap().clone())
+	}
+
 	fn unseal_state_key(&self) -> EnclaveResult<Vec<u8>> {
 		Ok(self.state_key.read().unwrap().clone())
 	}
 
+	fn unseal_pending_key_rotation(&self) -> EnclaveResult<Vec<u8>> {
+		Ok(self.pending_key_rotation.read().unwrap().clone())
+	}
+
 	fn unseal_state(&self, _shard: &ShardIdentifier) -> EnclaveResult<Vec<u8>> {
 		Ok(self.state.read().unwrap().clone())
 	}
@@ -53,6 +53,8 @@ pub enum Opcode {
 	StateKey,
 	State,
 	LightClient,
+	X25519ShieldingKey,
+	PendingKeyRotation,
 }
 
 impl From<u8> for Opcode {
@@ -62,6 +64,8 @@ impl From<u8> for Opcode {
 			1 => Opcode::StateKey,
 			2 => Opcode::State,
 			3 => Opcode::LightClient,
+			4 => Opcode::X25519ShieldingKey,
+			5 => Opcode::PendingKeyRotation,
 			_ => unimplemented!("Unsupported/unknown Opcode for MU-RA exchange"),
 		}
 	}
@@ -78,4 +82,17 @@ impl Opcode {
 pub struct ClientProvisioningRequest {
 	pub shard: ShardIdentifier,
 	pub account: AccountId,
+	pub kind: ProvisioningRequestKind,
+}
+
+/// Indicates which payloads the client requests from the provisioning server.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Decode, Encode, MaxEncodedLen)]
+pub enum ProvisioningRequestKind {
+	/// Everything a new worker needs, depending on the worker mode of the server.
+	Bootstrap,
+	/// Only the keys of a pending key rotation, which are activated once the enclave account has
+	/// been rotated in the state.
+	RotatedKeys,
+	/// Only the state of the shard and the light client state, for an already running worker.
+	Shard,
 }
@@ -18,46 +18,86 @@
 //! Abstraction of the reading (unseal) and storing (seal) part of the
 //! shielding key, state key and state.
 
-use crate::error::{Error as EnclaveError, Result as EnclaveResult};
+use crate::{
+	error::{Error as EnclaveError, Result as EnclaveResult},
+	key_rotation::{is_same_shielding_key, pending_key_rotation, PendingKeyRotation},
+};
 use codec::{Decode, Encode};
+use core::cell::RefCell;
 use ita_stf::{State as StfState, StateType as StfStateType};
-use itc_parentchain::light_client::LightClientSealing;
+use itc_parentchain::light_client::{
+	concurrent_access::ValidatorAccess, light_validation_state::LightValidationState,
+	LightClientSealing, LightClientState, Validator,
+};
 use itp_sgx_crypto::{
 	key_repository::{AccessKey, MutateKey},
-	Aes,
+	Aes, AesGcm, X25519KeyPair,
 };
 use itp_sgx_externalities::SgxExternalitiesTrait;
 use itp_stf_state_handler::handle_state::HandleState;
-use itp_types::ShardIdentifier;
+use itp_types::{Block as ParentchainBlock, ShardIdentifier};
 use log::*;
 use sgx_crypto_helper::rsa3072::Rsa3072KeyPair;
-use std::{sync::Arc, vec::Vec};
+use sp_runtime::traits::Header;
+use std::{format, sync::Arc, vec::Vec};
+
+/// Encoded length of the legacy AES state key (key and IV), as provisioned by older workers.
+/// The AES-GCM state key has a different encoded length.
+const LEGACY_STATE_KEY_LENGTH: usize = 32;
 
-/// Handles the sealing and unsealing of the shielding key, state key and the state.
+/// Handles the sealing and unsealing of the shielding keys, state key and the state.
 #[derive(Default)]
-pub struct SealHandler<ShieldingKeyRepository, StateKeyRepository, StateHandler, LightClientSeal> {
+pub struct SealHandler<
+	ShieldingKeyRepository,
+	X25519ShieldingKeyRepository,
+	StateKeyRepository,
+	StateHandler,
+	LightClientSeal,
+> {
 	state_handler: Arc<StateHandler>,
 	state_key_repository: Arc<StateKeyRepository>,
 	shielding_key_repository: Arc<ShieldingKeyRepository>,
+	x25519_shielding_key_repository: Arc<X25519ShieldingKeyRepository>,
 	light_client_seal: Arc<LightClientSeal>,
 }
 
-impl<ShieldingKeyRepository, StateKeyRepository, StateHandler, LightClientSeal>
-	SealHandler<ShieldingKeyRepository, StateKeyRepository, StateHandler, LightClientSeal>
+impl<
+		ShieldingKeyRepository,
+		X25519ShieldingKeyRepository,
+		StateKeyRepository,
+		StateHandler,
+		LightClientSeal,
+	>
+	SealHandler<
+		ShieldingKeyRepository,
+		X25519ShieldingKeyRepository,
+		StateKeyRepository,
+		StateHandler,
+		LightClientSeal,
+	>
 {
 	pub fn new(
 		state_handler: Arc<StateHandler>,
 		state_key_repository: Arc<StateKeyRepository>,
 		shielding_key_repository: Arc<ShieldingKeyRepository>,
+		x25519_shielding_key_repository: Arc<X25519ShieldingKeyRepository>,
 		light_client_seal: Arc<LightClientSeal>,
 	) -> Self {
-		Self { state_handler, state_key_repository, shielding_key_repository, light_client_seal }
+		Self {
+			state_handler,
+			state_key_repository,
+			shielding_key_repository,
+			x25519_shielding_key_repository,
+			light_client_seal,
+		}
 	}
 }
 
 pub trait SealStateAndKeys {
 	fn seal_shielding_key(&self, bytes: &[u8]) -> EnclaveResult<()>;
+	fn seal_x25519_shielding_key(&self, bytes: &[u8]) -> EnclaveResult<()>;
 	fn seal_state_key(&self, bytes: &[u8]) -> EnclaveResult<()>;
+	fn seal_pending_key_rotation(&self, bytes: &[u8]) -> EnclaveResult<()>;
 	fn seal_state(&self, bytes: &[u8], shard: &ShardIdentifier) -> EnclaveResult<()>;
 	fn seal_new_empty_state(&self, shard: &ShardIdentifier) -> EnclaveResult<()>;
 	fn seal_light_client_state(&self, bytes: &[u8]) -> EnclaveResult<()>;
@@ -65,16 +105,31 @@ pub trait SealStateAndKeys {
 
 pub trait UnsealStateAndKeys {
 	fn unseal_shielding_key(&self) -> EnclaveResult<Vec<u8>>;
+	fn unseal_x25519_shielding_key(&self) -> EnclaveResult<Vec<u8>>;
 	fn unseal_state_key(&self) -> EnclaveResult<Vec<u8>>;
+	fn unseal_pending_key_rotation(&self) -> EnclaveResult<Vec<u8>>;
 	fn unseal_state(&self, shard: &ShardIdentifier) -> EnclaveResult<Vec<u8>>;
 	fn unseal_light_client_state(&self) -> EnclaveResult<Vec<u8>>;
 }
 
-impl<ShieldingKeyRepository, StateKeyRepository, StateHandler, LightClientSeal> SealStateAndKeys
-	for SealHandler<ShieldingKeyRepository, StateKeyRepository, StateHandler, LightClientSeal>
+impl<
+		ShieldingKeyRepository,
+		X25519ShieldingKeyRepository,
+		StateKeyRepository,
+		StateHandler,
+		LightClientSeal,
+	> SealStateAndKeys
+	for SealHandler<
+		ShieldingKeyRepository,
+		X25519ShieldingKeyRepository,
+		StateKeyRepository,
+		StateHandler,
+		LightClientSeal,
+	>
 where
 	ShieldingKeyRepository: AccessKey<KeyType = Rsa3072KeyPair> + MutateKey<Rsa3072KeyPair>,
-	StateKeyRepository: AccessKey<KeyType = Aes> + MutateKey<Aes>,
+	X25519ShieldingKeyRepository: AccessKey<KeyType = X25519KeyPair> + MutateKey<X25519KeyPair>,
+	StateKeyRepository: AccessKey<KeyType = AesGcm> + MutateKey<AesGcm>,
 	StateHandler: HandleState<StateT = StfState>,
 	LightClientSeal: LightClientSealing,
 	LightClientSeal::LightClientState: Decode,
@@ -89,13 +144,24 @@ where
 		Ok(())
 	}
 
-	fn seal_state_key(&self, mut bytes: &[u8]) -> EnclaveResult<()> {
-		let aes = Aes::decode(&mut bytes)?;
-		self.state_key_repository.update_key(aes)?;
+	fn seal_x25519_shielding_key(&self, mut bytes: &[u8]) -> EnclaveResult<()> {
+		let key = X25519KeyPair::decode(&mut bytes)?;
+		self.x25519_shielding_key_repository.update_key(key)?;
+		info!("Successfully stored a new X25519 shielding key");
+		Ok(())
+	}
+
+	fn seal_state_key(&self, bytes: &[u8]) -> EnclaveResult<()> {
+		let state_key = decode_state_key(bytes)?;
+		self.state_key_repository.update_key(state_key)?;
 		info!("Successfully stored a new state key");
 		Ok(())
 	}
 
+	fn seal_pending_key_rotation(&self, _bytes: &[u8]) -> EnclaveResult<()> {
+		Err(EnclaveError::Other("Unexpected key rotation during provisioning".into()))
+	}
+
 	fn seal_state(&self, mut bytes: &[u8], shard: &ShardIdentifier) -> EnclaveResult<()> {
 		let state = StfStateType::decode(&mut bytes)?;
 		let state_with_empty_diff = StfState::new(state);
@@ -126,11 +192,24 @@ where
 	}
 }
 
-impl<ShieldingKeyRepository, StateKeyRepository, StateHandler, LightClientSeal> UnsealStateAndKeys
-	for SealHandler<ShieldingKeyRepository, StateKeyRepository, StateHandler, LightClientSeal>
+impl<
+		ShieldingKeyRepository,
+		X25519ShieldingKeyRepository,
+		StateKeyRepository,
+		StateHandler,
+		LightClientSeal,
+	> UnsealStateAndKeys
+	for SealHandler<
+		ShieldingKeyRepository,
+		X25519ShieldingKeyRepository,
+		StateKeyRepository,
+		StateHandler,
+		LightClientSeal,
+	>
 where
 	ShieldingKeyRepository: AccessKey<KeyType = Rsa3072KeyPair> + MutateKey<Rsa3072KeyPair>,
-	StateKeyRepository: AccessKey<KeyType = Aes> + MutateKey<Aes>,
+	X25519ShieldingKeyRepository: AccessKey<KeyType = X25519KeyPair> + MutateKey<X25519KeyPair>,
+	StateKeyRepository: AccessKey<KeyType = AesGcm> + MutateKey<AesGcm>,
 	StateHandler: HandleState<StateT = StfState>,
 	LightClientSeal: LightClientSealing,
 	LightClientSeal::LightClientState: Encode,
@@ -143,6 +222,13 @@ where
 		serde_json::to_vec(&shielding_key).map_err(|e| EnclaveError::Other(e.into()))
 	}
 
+	fn unseal_x25519_shielding_key(&self) -> EnclaveResult<Vec<u8>> {
+		self.x25519_shielding_key_repository
+			.retrieve_key()
+			.map(|k| k.encode())
+			.map_err(|e| EnclaveError::Other(format!("{:?}", e).into()))
+	}
+
 	fn unseal_state_key(&self) -> EnclaveResult<Vec<u8>> {
 		self.state_key_repository
 			.retrieve_key()
@@ -150,6 +236,19 @@ where
 			.map_err(|e| EnclaveError::Other(format!("{:?}", e).into()))
 	}
 
+	/// The keys of the pending key rotation or, if it has already been activated, our current keys.
+	fn unseal_pending_key_rotation(&self) -> EnclaveResult<Vec<u8>> {
+		if let Some(key_rotation) = pending_key_rotation()? {
+			return Ok(key_rotation.encode())
+		}
+		let key_rotation = PendingKeyRotation::new(
+			&self.shielding_key_repository.retrieve_key()?,
+			self.x25519_shielding_key_repository.retrieve_key()?,
+			self.state_key_repository.retrieve_key()?,
+		)?;
+		Ok(key_rotation.encode())
+	}
+
 	fn unseal_state(&self, shard: &ShardIdentifier) -> EnclaveResult<Vec<u8>> {
 		Ok(self.state_handler.execute_on_current(shard, |state, _| state.state.encode())?)
 	}
@@ -159,23 +258,261 @@ where
 	}
 }
 
+/// Seals the state of a single shard that is provisioned to an already running worker.
+///
+/// The state is sealed unmodified, so the provisioning validateer has to share our shielding keys
+/// and state key. It sends them along with the state and they are compared to ours. The provided
+/// light client state is not sealed either, it is only used to check that the provided state
+/// matches the parentchain blocks our own light client has imported.
+pub struct ShardSealHandler<
+	ShieldingKeyRepository,
+	X25519ShieldingKeyRepository,
+	StateKeyRepository,
+	StateHandler,
+	ValidatorAccessor,
+> {
+	state_handler: Arc<StateHandler>,
+	validator_accessor: Arc<ValidatorAccessor>,
+	shielding_key_repository: Arc<ShieldingKeyRepository>,
+	x25519_shielding_key_repository: Arc<X25519ShieldingKeyRepository>,
+	state_key_repository: Arc<StateKeyRepository>,
+	matching_keys: RefCell<MatchingKeys>,
+	provided_light_client_state: RefCell<Option<LightValidationState<ParentchainBlock>>>,
+}
+
+/// The provided keys that were found to match ours.
+#[derive(Default)]
+struct MatchingKeys {
+	shielding_key: bool,
+	x25519_shielding_key: bool,
+	state_key: bool,
+}
+
+impl MatchingKeys {
+	fn all(&self) -> bool {
+		self.shielding_key && self.x25519_shielding_key && self.state_key
+	}
+}
+
+impl<
+		ShieldingKeyRepository,
+		X25519ShieldingKeyRepository,
+		StateKeyRepository,
+		StateHandler,
+		ValidatorAccessor,
+	>
+	ShardSealHandler<
+		ShieldingKeyRepository,
+		X25519ShieldingKeyRepository,
+		StateKeyRepository,
+		StateHandler,
+		ValidatorAccessor,
+	>
+{
+	pub fn new(
+		state_handler: Arc<StateHandler>,
+		validator_accessor: Arc<ValidatorAccessor>,
+		shielding_key_repository: Arc<ShieldingKeyRepository>,
+		x25519_shielding_key_repository: Arc<X25519ShieldingKeyRepository>,
+		state_key_repository: Arc<StateKeyRepository>,
+	) -> Self {
+		Self {
+			state_handler,
+			validator_accessor,
+			shielding_key_repository,
+			x25519_shielding_key_repository,
+			state_key_repository,
+			matching_keys: RefCell::new(MatchingKeys::default()),
+			provided_light_client_state: RefCell::new(None),
+		}
+	}
+}
+
+impl<
+		ShieldingKeyRepository,
+		X25519ShieldingKeyRepository,
+		StateKeyRepository,
+		StateHandler,
+		ValidatorAccessor,
+	> SealStateAndKeys
+	for ShardSealHandler<
+		ShieldingKeyRepository,
+		X25519ShieldingKeyRepository,
+		StateKeyRepository,
+		StateHandler,
+		ValidatorAccessor,
+	>
+where
+	ShieldingKeyRepository: AccessKey<KeyType = Rsa3072KeyPair>,
+	X25519ShieldingKeyRepository: AccessKey<KeyType = X25519KeyPair>,
+	StateKeyRepository: AccessKey<KeyType = AesGcm>,
+	StateHandler: HandleState<StateT = StfState>,
+	ValidatorAccessor: ValidatorAccess<ParentchainBlock>,
+	ValidatorAccessor::ValidatorType: Validator<ParentchainBlock>,
+{
+	fn seal_shielding_key(&self, bytes: &[u8]) -> EnclaveResult<()> {
+		let key: Rsa3072KeyPair = serde_json::from_slice(bytes).map_err(|e| {
+			error!("    [Enclave] Received Invalid RSA key");
+			EnclaveError::Other(e.into())
+		})?;
+		if !is_same_shielding_key(&key, &self.shielding_key_repository.retrieve_key()?)? {
+			return Err(EnclaveError::Other("Provided shielding key does not match ours".into()))
+		}
+		self.matching_keys.borrow_mut().shielding_key = true;
+		Ok(())
+	}
+
+	fn seal_x25519_shielding_key(&self, mut bytes: &[u8]) -> EnclaveResult<()> {
+		let key = X25519KeyPair::decode(&mut bytes)?;
+		if key != self.x25519_shielding_key_repository.retrieve_key()? {
+			return Err(EnclaveError::Other(
+				"Provided X25519 shielding key does not match ours".into(),
+			))
+		}
+		self.matching_keys.borrow_mut().x25519_shielding_key = true;
+		Ok(())
+	}
+
+	fn seal_state_key(&self, bytes: &[u8]) -> EnclaveResult<()> {
+		if decode_state_key(bytes)? != self.state_key_repository.retrieve_key()? {
+			return Err(EnclaveError::Other("Provided state key does not match ours".into()))
+		}
+		self.matching_keys.borrow_mut().state_key = true;
+		Ok(())
+	}
+
+	fn seal_pending_key_rotation(&self, _bytes: &[u8]) -> EnclaveResult<()> {
+		Err(EnclaveError::Other("Unexpected key rotation during shard provisioning".into()))
+	}
+
+	fn seal_state(&self, mut bytes: &[u8], shard: &ShardIdentifier) -> EnclaveResult<()> {
+		if !self.matching_keys.borrow().all() {
+			return Err(EnclaveError::Other(
+				"Matching keys must be provisioned before the state".into(),
+			))
+		}
+		let provided_light_client_state =
+			self.provided_light_client_state.borrow_mut().take().ok_or_else(|| {
+				EnclaveError::Other(
+					"Light client state must be provisioned before the state".into(),
+				)
+			})?;
+
+		let state = StfState::new(StfStateType::decode(&mut bytes)?);
+
+		// Hold the validator lock, so no parentchain block is imported in the meantime.
+		self.validator_accessor.execute_on_validator(|validator| {
+			Ok(ensure_same_light_client_state(validator.get_state(), &provided_light_client_state)
+				.and_then(|_| Ok(self.state_handler.initialize_shard_with(state, shard)?)))
+		})??;
+		info!("Successfully provisioned shard {:?}", shard);
+		Ok(())
+	}
+
+	fn seal_new_empty_state(&self, shard: &ShardIdentifier) -> EnclaveResult<()> {
+		Err(EnclaveError::Other(
+			format!("Unexpected reset of shard {:?} during shard provisioning", shard).into(),
+		))
+	}
+
+	fn seal_light_client_state(&self, mut bytes: &[u8]) -> EnclaveResult<()> {
+		let state = LightValidationState::<ParentchainBlock>::decode(&mut bytes)?;
+		*self.provided_light_client_state.borrow_mut() = Some(state);
+		Ok(())
+	}
+}
+
+/// Decodes a provisioned state key.
+///
+/// Primary workers that have not been upgraded yet still provision the legacy AES key.
+fn decode_state_key(mut bytes: &[u8]) -> EnclaveResult<AesGcm> {
+	if bytes.len() == LEGACY_STATE_KEY_LENGTH {
+		info!("Received a legacy state key, deriving the AES-GCM state key from it");
+		Ok(AesGcm::from_legacy(&Aes::decode(&mut bytes)?))
+	} else {
+		Ok(AesGcm::decode(&mut bytes)?)
+	}
+}
+
+/// Ensures that both light clients follow the same parentchain and have finalized the same block.
+fn ensure_same_light_client_state(
+	own: &LightValidationState<ParentchainBlock>,
+	provided: &LightValidationState<ParentchainBlock>,
+) -> EnclaveResult<()> {
+	if own.genesis_hash()? != provided.genesis_hash()? {
+		return Err(EnclaveError::Other("Provided light client follows another parentchain".into()))
+	}
+	let own_header = own.latest_finalized_header()?;
+	let provided_header = provided.latest_finalized_header()?;
+	if own_header.hash() != provided_header.hash() {
+		return Err(EnclaveError::Other(
+			format!(
+				"Provided light client is at block {}, ours is at block {}. Retry once both are in sync",
+				provided_header.number(),
+				own_header.number()
+			)
+			.into(),
+		))
+	}
+	Ok(())
+}
+
 #[cfg(feature = "test")]
 pub mod test {
 	use super::*;
-	use itc_parentchain::light_client::mocks::validator_mock_seal::LightValidationStateSealMock;
+	use itc_parentchain::light_client::{
+		mocks::{
+			validator_access_mock::ValidatorAccessMock, validator_mock::ValidatorMock,
+			validator_mock_seal::LightValidationStateSealMock,
+		},
+		state::RelayState,
+	};
+	use itc_parentchain_test::ParentchainHeaderBuilder;
 	use itp_sgx_crypto::mocks::KeyRepositoryMock;
+	use itp_stf_state_handler::query_shard_state::QueryShardState;
 	use itp_test::mock::handle_state_mock::HandleStateMock;
 
-	type StateKeyRepositoryMock = KeyRepositoryMock<Aes>;
+	type StateKeyRepositoryMock = KeyRepositoryMock<AesGcm>;
 	type ShieldingKeyRepositoryMock = KeyRepositoryMock<Rsa3072KeyPair>;
+	type X25519ShieldingKeyRepositoryMock = KeyRepositoryMock<X25519KeyPair>;
 
 	type SealHandlerMock = SealHandler<
 		ShieldingKeyRepositoryMock,
+		X25519ShieldingKeyRepositoryMock,
 		StateKeyRepositoryMock,
 		HandleStateMock,
 		LightValidationStateSealMock,
 	>;
 
+	type ShardSealHandlerMock = ShardSealHandler<
+		ShieldingKeyRepositoryMock,
+		X25519ShieldingKeyRepositoryMock,
+		StateKeyRepositoryMock,
+		HandleStateMock,
+		ValidatorAccessMock,
+	>;
+
+	fn shard_seal_handler() -> ShardSealHandlerMock {
+		ShardSealHandler::new(
+			Arc::new(HandleStateMock::default()),
+			Arc::new(ValidatorAccessMock::default()),
+			Arc::new(ShieldingKeyRepositoryMock::new(Rsa3072KeyPair::new().unwrap())),
+			Arc::new(X25519ShieldingKeyRepositoryMock::default()),
+			Arc::new(StateKeyRepositoryMock::default()),
+		)
+	}
+
+	fn seal_matching_keys(seal_handler: &ShardSealHandlerMock) {
+		let shielding_key = seal_handler.shielding_key_repository.retrieve_key().unwrap();
+		seal_handler
+			.seal_shielding_key(&serde_json::to_vec(&shielding_key).unwrap())
+			.unwrap();
+		seal_handler
+			.seal_x25519_shielding_key(&X25519KeyPair::default().encode())
+			.unwrap();
+		seal_handler.seal_state_key(&AesGcm::default().encode()).unwrap();
+	}
+
 	pub fn seal_shielding_key_works() {
 		let seal_handler = SealHandlerMock::default();
 		let key_pair_in_bytes = serde_json::to_vec(&Rsa3072KeyPair::default()).unwrap();
@@ -203,15 +540,36 @@ pub mod test {
 		assert!(result.is_ok());
 	}
 
+	pub fn unseal_seal_x25519_shielding_key_works() {
+		let seal_handler = SealHandlerMock::default();
+		let key = X25519KeyPair::from_secret([4u8; 32]);
+
+		seal_handler.seal_x25519_shielding_key(&key.encode()).unwrap();
+
+		assert_eq!(seal_handler.unseal_x25519_shielding_key().unwrap(), key.encode());
+	}
+
 	pub fn seal_state_key_works() {
 		let seal_handler = SealHandlerMock::default();
-		let key_pair_in_bytes = Aes::default().encode();
+		let key_pair_in_bytes = AesGcm::default().encode();
 
 		let result = seal_handler.seal_state_key(&key_pair_in_bytes);
 
 		assert!(result.is_ok());
 	}
 
+	pub fn seal_state_key_converts_legacy_key() {
+		let seal_handler = SealHandlerMock::default();
+		let legacy_key = Aes::new([1u8; 16], [2u8; 16]);
+
+		seal_handler.seal_state_key(&legacy_key.encode()).unwrap();
+
+		assert_eq!(
+			seal_handler.state_key_repository.retrieve_key().unwrap(),
+			AesGcm::from_legacy(&legacy_key)
+		);
+	}
+
 	pub fn seal_state_key_fails_for_invalid_key() {
 		let seal_handler = SealHandlerMock::default();
 
@@ -265,4 +623,95 @@ pub mod test {
 
 		assert!(result.is_ok());
 	}
+
+	pub fn seal_shard_state_works() {
+		let seal_handler = shard_seal_handler();
+		let shard = ShardIdentifier::default();
+		let light_client_state = ValidatorMock::default().get_state().encode();
+		let state = <HandleStateMock as HandleState>::StateT::default();
+
+		seal_matching_keys(&seal_handler);
+		seal_handler.seal_light_client_state(&light_client_state).unwrap();
+		let result = seal_handler.seal_state(&state.encode(), &shard);
+
+		assert!(result.is_ok());
+		assert!(seal_handler.state_handler.shard_exists(&shard).unwrap());
+	}
+
+	pub fn seal_shard_state_fails_without_light_client_state() {
+		let seal_handler = shard_seal_handler();
+		let shard = ShardIdentifier::default();
+		let state = <HandleStateMock as HandleState>::StateT::default();
+
+		seal_matching_keys(&seal_handler);
+		let result = seal_handler.seal_state(&state.encode(), &shard);
+
+		assert!(result.is_err());
+		assert!(!seal_handler.state_handler.shard_exists(&shard).unwrap());
+	}
+
+	pub fn seal_shard_state_fails_without_keys() {
+		let seal_handler = shard_seal_handler();
+		let shard = ShardIdentifier::default();
+		let light_client_state = ValidatorMock::default().get_state().encode();
+		let state = <HandleStateMock as HandleState>::StateT::default();
+
+		seal_handler.seal_light_client_state(&light_client_state).unwrap();
+		let result = seal_handler.seal_state(&state.encode(), &shard);
+
+		assert!(result.is_err());
+		assert!(!seal_handler.state_handler.shard_exists(&shard).unwrap());
+	}
+
+	pub fn seal_shard_state_fails_for_different_state_key() {
+		let seal_handler = shard_seal_handler();
+
+		let result = seal_handler.seal_state_key(&AesGcm::new([3u8; 16]).encode());
+
+		assert!(result.is_err());
+		assert!(!seal_handler.matching_keys.borrow().state_key);
+	}
+
+	pub fn seal_shard_state_fails_for_different_shielding_key() {
+		let seal_handler = shard_seal_handler();
+		let other_key = Rsa3072KeyPair::new().unwrap();
+
+		let result = seal_handler.seal_shielding_key(&serde_json::to_vec(&other_key).unwrap());
+
+		assert!(result.is_err());
+		assert!(!seal_handler.matching_keys.borrow().shielding_key);
+	}
+
+	pub fn seal_shard_state_fails_for_light_client_at_different_block() {
+		let seal_handler = shard_seal_handler();
+		let shard = ShardIdentifier::default();
+		let mut relay_state =
+			RelayState::new(ParentchainHeaderBuilder::default().build(), Default::default());
+		relay_state.set_last_finalized_block_header(
+			ParentchainHeaderBuilder::default().with_number(10).build(),
+		);
+		let light_client_state = LightValidationState::<ParentchainBlock>::new(relay_state);
+		let state = <HandleStateMock as HandleState>::StateT::default();
+
+		seal_matching_keys(&seal_handler);
+		seal_handler.seal_light_client_state(&light_client_state.encode()).unwrap();
+		let result = seal_handler.seal_state(&state.encode(), &shard);
+
+		assert!(result.is_err());
+		assert!(!seal_handler.state_handler.shard_exists(&shard).unwrap());
+	}
+
+	pub fn seal_shard_state_fails_for_existing_shard() {
+		let seal_handler = shard_seal_handler();
+		let shard = ShardIdentifier::default();
+		seal_handler.state_handler.initialize_shard(shard).unwrap();
+		let light_client_state = ValidatorMock::default().get_state().encode();
+		let state = <HandleStateMock as HandleState>::StateT::default();
+
+		seal_matching_keys(&seal_handler);
+		seal_handler.seal_light_client_state(&light_client_state).unwrap();
+		let result = seal_handler.seal_state(&state.encode(), &shard);
+
+		assert!(result.is_err());
+	}
 }
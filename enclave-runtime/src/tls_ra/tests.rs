@@ -18,7 +18,11 @@
 //! Tests of tls-ra client / server communication.
 
 use super::{
-	mocks::SealHandlerMock, tls_ra_client::request_state_provisioning_internal,
+	mocks::SealHandlerMock,
+	tls_ra_client::{
+		request_rotated_keys_internal, request_shard_provisioning_internal,
+		request_state_provisioning_internal,
+	},
 	tls_ra_server::run_state_provisioning_server_internal,
 };
 use crate::{
@@ -28,7 +32,7 @@ use crate::{
 use ita_stf::State;
 use itc_parentchain::light_client::mocks::validator_mock_seal::LightValidationStateSealMock;
 use itp_settings::worker_mode::{ProvideWorkerMode, WorkerMode, WorkerModeProvider};
-use itp_sgx_crypto::{mocks::KeyRepositoryMock, Aes};
+use itp_sgx_crypto::{mocks::KeyRepositoryMock, AesGcm, X25519KeyPair};
 use itp_stf_interface::InitState;
 use itp_stf_primitives::types::AccountId;
 use itp_stf_state_handler::handle_state::HandleState;
@@ -74,6 +78,7 @@ pub fn test_tls_ra_server_client_networking() {
 	let shard = ShardIdentifier::default();
 	let client_account = AccountId::from([42; 32]);
 	let shielding_key_encoded = vec![1, 2, 3];
+	let x25519_shielding_key_encoded = vec![4, 2, 8];
 	let state_key_encoded = vec![5, 2, 3, 7];
 	let state_encoded = Vec::from([1u8; 26000]); // Have a decently sized state, so read() must be called multiple times.
 	let light_client_state_encoded = Vec::from([1u8; 10000]); // Have a decently sized state, so read() must be called multiple times.
@@ -83,11 +88,13 @@ pub fn test_tls_ra_server_client_networking() {
 		Arc::new(RwLock::new(state_key_encoded.clone())),
 		Arc::new(RwLock::new(state_encoded.clone())),
 		Arc::new(RwLock::new(light_client_state_encoded.clone())),
-	);
+	)
+	.with_x25519_shielding_key(Arc::new(RwLock::new(x25519_shielding_key_encoded.clone())));
 	let initial_client_state = vec![0, 0, 1];
 	let initial_client_state_key = vec![0, 0, 2];
 	let initial_client_light_client_state = vec![0, 0, 3];
 	let client_shielding_key = Arc::new(RwLock::new(Vec::new()));
+	let client_x25519_shielding_key = Arc::new(RwLock::new(Vec::new()));
 	let client_state_key = Arc::new(RwLock::new(initial_client_state_key.clone()));
 	let client_state = Arc::new(RwLock::new(initial_client_state.clone()));
 	let client_light_client_state = Arc::new(RwLock::new(initial_client_light_client_state));
@@ -97,7 +104,8 @@ pub fn test_tls_ra_server_client_networking() {
 		client_state_key.clone(),
 		client_state.clone(),
 		client_light_client_state.clone(),
-	);
+	)
+	.with_x25519_shielding_key(client_x25519_shielding_key.clone());
 
 	let port: u16 = 3149;
 
@@ -126,6 +134,7 @@ pub fn test_tls_ra_server_client_networking() {
 
 	assert!(result.is_ok());
 	assert_eq!(*client_shielding_key.read().unwrap(), shielding_key_encoded);
+	assert_eq!(*client_x25519_shielding_key.read().unwrap(), x25519_shielding_key_encoded);
 	assert_eq!(*client_light_client_state.read().unwrap(), light_client_state_encoded);
 
 	// State and state-key are provisioned only in sidechain or OCW mode
@@ -139,18 +148,161 @@ pub fn test_tls_ra_server_client_networking() {
 	}
 }
 
+pub fn test_tls_ra_rotated_keys_are_provisioned_without_state() {
+	let shard = ShardIdentifier::default();
+	let client_account = AccountId::from([42; 32]);
+	let shielding_key_encoded = vec![1, 2, 3];
+	let x25519_shielding_key_encoded = vec![4, 2, 8];
+	let state_key_encoded = vec![5, 2, 3, 7];
+	let pending_key_rotation_encoded = vec![9, 1, 6];
+
+	let server_seal_handler = SealHandlerMock::new(
+		Arc::new(RwLock::new(shielding_key_encoded)),
+		Arc::new(RwLock::new(state_key_encoded)),
+		Arc::new(RwLock::new(vec![1u8; 100])),
+		Arc::new(RwLock::new(vec![1u8; 100])),
+	)
+	.with_x25519_shielding_key(Arc::new(RwLock::new(x25519_shielding_key_encoded)))
+	.with_pending_key_rotation(Arc::new(RwLock::new(pending_key_rotation_encoded.clone())));
+	let initial_client_state = vec![0, 0, 1];
+	let initial_client_light_client_state = vec![0, 0, 3];
+	let client_shielding_key = Arc::new(RwLock::new(Vec::new()));
+	let client_x25519_shielding_key = Arc::new(RwLock::new(Vec::new()));
+	let client_state_key = Arc::new(RwLock::new(Vec::new()));
+	let client_pending_key_rotation = Arc::new(RwLock::new(Vec::new()));
+	let client_state = Arc::new(RwLock::new(initial_client_state.clone()));
+	let client_light_client_state =
+		Arc::new(RwLock::new(initial_client_light_client_state.clone()));
+
+	let client_seal_handler = SealHandlerMock::new(
+		client_shielding_key.clone(),
+		client_state_key.clone(),
+		client_state.clone(),
+		client_light_client_state.clone(),
+	)
+	.with_x25519_shielding_key(client_x25519_shielding_key.clone())
+	.with_pending_key_rotation(client_pending_key_rotation.clone());
+
+	let port: u16 = 3151;
+
+	// Start server.
+	let server_thread_handle = thread::spawn(move || {
+		run_state_provisioning_server(server_seal_handler, port);
+	});
+	thread::sleep(Duration::from_secs(1));
+
+	// Start client.
+	let socket = TcpStream::connect(server_addr(port)).unwrap();
+	let sgx_target_info: sgx_target_info_t = sgx_target_info_t::default();
+	let result = request_rotated_keys_internal(
+		socket.as_raw_fd(),
+		SIGN_TYPE,
+		Some(&sgx_target_info),
+		Some(&QUOTE_SIZE),
+		shard,
+		SKIP_RA,
+		client_seal_handler,
+		client_account,
+	);
+
+	// Ensure server thread has finished.
+	server_thread_handle.join().unwrap();
+
+	assert!(result.is_ok());
+	// The keys are only activated once the enclave account has been rotated.
+	assert_eq!(*client_pending_key_rotation.read().unwrap(), pending_key_rotation_encoded);
+	assert!(client_shielding_key.read().unwrap().is_empty());
+	assert!(client_x25519_shielding_key.read().unwrap().is_empty());
+	assert!(client_state_key.read().unwrap().is_empty());
+	assert_eq!(*client_state.read().unwrap(), initial_client_state);
+	assert_eq!(*client_light_client_state.read().unwrap(), initial_client_light_client_state);
+}
+
+pub fn test_tls_ra_shard_is_provisioned_with_keys() {
+	let shard = ShardIdentifier::default();
+	let client_account = AccountId::from([42; 32]);
+	let shielding_key_encoded = vec![1u8; 100];
+	let x25519_shielding_key_encoded = vec![1u8; 32];
+	let state_key_encoded = vec![2u8; 100];
+	let state_encoded = vec![1, 4, 2, 3];
+	let light_client_state_encoded = vec![7, 3, 2];
+
+	let server_seal_handler = SealHandlerMock::new(
+		Arc::new(RwLock::new(shielding_key_encoded.clone())),
+		Arc::new(RwLock::new(state_key_encoded.clone())),
+		Arc::new(RwLock::new(state_encoded.clone())),
+		Arc::new(RwLock::new(light_client_state_encoded.clone())),
+	)
+	.with_x25519_shielding_key(Arc::new(RwLock::new(x25519_shielding_key_encoded.clone())));
+	let client_shielding_key = Arc::new(RwLock::new(Vec::new()));
+	let client_x25519_shielding_key = Arc::new(RwLock::new(Vec::new()));
+	let client_state_key = Arc::new(RwLock::new(Vec::new()));
+	let client_state = Arc::new(RwLock::new(Vec::new()));
+	let client_light_client_state = Arc::new(RwLock::new(Vec::new()));
+
+	let client_seal_handler = SealHandlerMock::new(
+		client_shielding_key.clone(),
+		client_state_key.clone(),
+		client_state.clone(),
+		client_light_client_state.clone(),
+	)
+	.with_x25519_shielding_key(client_x25519_shielding_key.clone());
+
+	let port: u16 = 3152;
+
+	// Start server.
+	let server_thread_handle = thread::spawn(move || {
+		run_state_provisioning_server(server_seal_handler, port);
+	});
+	thread::sleep(Duration::from_secs(1));
+
+	// Start client.
+	let socket = TcpStream::connect(server_addr(port)).unwrap();
+	let sgx_target_info: sgx_target_info_t = sgx_target_info_t::default();
+	let result = request_shard_provisioning_internal(
+		socket.as_raw_fd(),
+		SIGN_TYPE,
+		Some(&sgx_target_info),
+		Some(&QUOTE_SIZE),
+		shard,
+		SKIP_RA,
+		client_seal_handler,
+		client_account,
+	);
+
+	// Ensure server thread has finished.
+	server_thread_handle.join().unwrap();
+
+	assert!(result.is_ok());
+	assert_eq!(*client_shielding_key.read().unwrap(), shielding_key_encoded);
+	assert_eq!(*client_x25519_shielding_key.read().unwrap(), x25519_shielding_key_encoded);
+	assert_eq!(*client_state_key.read().unwrap(), state_key_encoded);
+	assert_eq!(*client_state.read().unwrap(), state_encoded);
+	assert_eq!(*client_light_client_state.read().unwrap(), light_client_state_encoded);
+}
+
 // Test state and key provisioning with 'real' data structures.
 pub fn test_state_and_key_provisioning() {
 	let client_account = AccountId::from([42; 32]);
-	let state_key = Aes::new([3u8; 16], [0u8; 16]);
+	let state_key = AesGcm::new([3u8; 16]);
 	let shielding_key = Rsa3072KeyPair::new().unwrap();
 	let initialized_state = EnclaveStf::init_state(AccountId::new([1u8; 32]));
 	let shard = ShardIdentifier::from([1u8; 32]);
 
-	let server_seal_handler =
-		create_seal_handler(state_key, shielding_key, initialized_state, &shard);
-	let client_seal_handler =
-		create_seal_handler(Aes::default(), Rsa3072KeyPair::default(), State::default(), &shard);
+	let server_seal_handler = create_seal_handler(
+		state_key,
+		shielding_key,
+		X25519KeyPair::from_secret([7u8; 32]),
+		initialized_state,
+		&shard,
+	);
+	let client_seal_handler = create_seal_handler(
+		AesGcm::default(),
+		Rsa3072KeyPair::default(),
+		X25519KeyPair::default(),
+		State::default(),
+		&shard,
+	);
 
 	let port: u16 = 3150;
 
@@ -181,17 +333,26 @@ pub fn test_state_and_key_provisioning() {
 }
 
 fn create_seal_handler(
-	state_key: Aes,
+	state_key: AesGcm,
 	shielding_key: Rsa3072KeyPair,
+	x25519_shielding_key: X25519KeyPair,
 	state: State,
 	shard: &ShardIdentifier,
 ) -> impl UnsealStateAndKeys + SealStateAndKeys {
-	let state_key_repository = Arc::new(KeyRepositoryMock::<Aes>::new(state_key));
+	let state_key_repository = Arc::new(KeyRepositoryMock::<AesGcm>::new(state_key));
 	let shielding_key_repository =
 		Arc::new(KeyRepositoryMock::<Rsa3072KeyPair>::new(shielding_key));
+	let x25519_shielding_key_repository =
+		Arc::new(KeyRepositoryMock::<X25519KeyPair>::new(x25519_shielding_key));
 	let state_handler = Arc::new(HandleStateMock::default());
 	state_handler.reset(state, shard).unwrap();
 	let seal = Arc::new(LightValidationStateSealMock::new());
 
-	SealHandler::new(state_handler, state_key_repository, shielding_key_repository, seal)
+	SealHandler::new(
+		state_handler,
+		state_key_repository,
+		shielding_key_repository,
+		x25519_shielding_key_repository,
+		seal,
+	)
 }
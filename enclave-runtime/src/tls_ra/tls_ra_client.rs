@@ -22,12 +22,15 @@ use crate::{
 	attestation::create_ra_report_and_signature,
 	error::{Error as EnclaveError, Result as EnclaveResult},
 	initialization::global_components::{
-		EnclaveSealHandler, GLOBAL_INTEGRITEE_PARENTCHAIN_LIGHT_CLIENT_SEAL,
-		GLOBAL_SHIELDING_KEY_REPOSITORY_COMPONENT, GLOBAL_STATE_KEY_REPOSITORY_COMPONENT,
+		EnclaveSealHandler, EnclaveShardSealHandler,
+		GLOBAL_INTEGRITEE_PARENTCHAIN_LIGHT_CLIENT_SEAL, GLOBAL_SHIELDING_KEY_REPOSITORY_COMPONENT,
+		GLOBAL_STATE_KEY_REPOSITORY_COMPONENT, GLOBAL_X25519_SHIELDING_KEY_REPOSITORY_COMPONENT,
 	},
+	key_rotation::KeyRotationSealHandler,
 	ocall::OcallApi,
 	shard_config::init_shard_config,
-	tls_ra::{seal_handler::SealStateAndKeys, ClientProvisioningRequest},
+	tls_ra::{seal_handler::SealStateAndKeys, ClientProvisioningRequest, ProvisioningRequestKind},
+	utils::get_validator_accessor_from_integritee_solo_or_parachain,
 	GLOBAL_SIGNING_KEY_REPOSITORY_COMPONENT, GLOBAL_STATE_HANDLER_COMPONENT,
 };
 use codec::Encode;
@@ -60,6 +63,7 @@ where
 	tls_stream: Stream<'a, ClientSession, TcpStream>,
 	seal_handler: StateAndKeySealer,
 	shard: ShardIdentifier,
+	kind: ProvisioningRequestKind,
 }
 
 impl<'a, StateAndKeySealer> TlsClient<'a, StateAndKeySealer>
@@ -70,8 +74,9 @@ where
 		tls_stream: Stream<'a, ClientSession, TcpStream>,
 		seal_handler: StateAndKeySealer,
 		shard: ShardIdentifier,
+		kind: ProvisioningRequestKind,
 	) -> TlsClient<StateAndKeySealer> {
-		TlsClient { tls_stream, seal_handler, shard }
+		TlsClient { tls_stream, seal_handler, shard, kind }
 	}
 
 	/// Read all data sent by the server of the specific shard.
@@ -90,8 +95,9 @@ where
 	/// Send the shard of the state we want to receive to the provisioning server.
 	fn send_provisioning_request(&mut self, account: AccountId) -> EnclaveResult<()> {
 		debug!("self.send_provisioning_request() called.");
-		self.tls_stream
-			.write_all(&ClientProvisioningRequest { shard: self.shard, account }.encode())?;
+		self.tls_stream.write_all(
+			&ClientProvisioningRequest { shard: self.shard, account, kind: self.kind }.encode(),
+		)?;
 		debug!("write_all succeeded.");
 		Ok(())
 	}
@@ -112,8 +118,9 @@ where
 		info!("Successfully read and sealed all data sent by the state provisioning server.");
 
 		// In case we receive a shielding key, but no state, we need to reset our state
-		// to update the enclave account.
-		if received_payloads.contains(&Opcode::ShieldingKey)
+		// to update the enclave account. Rotated keys are handled by the seal handler.
+		if self.kind == ProvisioningRequestKind::Bootstrap
+			&& received_payloads.contains(&Opcode::ShieldingKey)
 			&& !received_payloads.contains(&Opcode::State)
 		{
 			self.seal_handler.seal_new_empty_state(&self.shard)?;
@@ -134,9 +141,11 @@ where
 		let bytes = self.read_until(header.payload_length as usize)?;
 		match header.opcode {
 			Opcode::ShieldingKey => self.seal_handler.seal_shielding_key(&bytes)?,
+			Opcode::X25519ShieldingKey => self.seal_handler.seal_x25519_shielding_key(&bytes)?,
 			Opcode::StateKey => self.seal_handler.seal_state_key(&bytes)?,
 			Opcode::State => self.seal_handler.seal_state(&bytes, &self.shard)?,
 			Opcode::LightClient => self.seal_handler.seal_light_client_state(&bytes)?,
+			Opcode::PendingKeyRotation => self.seal_handler.seal_pending_key_rotation(&bytes)?,
 		};
 		Ok(Some(header.opcode))
 	}
@@ -201,6 +210,15 @@ pub unsafe extern "C" fn request_state_provisioning(
 		},
 	};
 
+	let x25519_shielding_key_repository =
+		match GLOBAL_X25519_SHIELDING_KEY_REPOSITORY_COMPONENT.get() {
+			Ok(s) => s,
+			Err(e) => {
+				error!("{:?}", e);
+				return sgx_status_t::SGX_ERROR_UNEXPECTED
+			},
+		};
+
 	let light_client_seal = match GLOBAL_INTEGRITEE_PARENTCHAIN_LIGHT_CLIENT_SEAL.get() {
 		Ok(s) => s,
 		Err(e) => {
@@ -213,6 +231,7 @@ pub unsafe extern "C" fn request_state_provisioning(
 		state_handler,
 		state_key_repository,
 		shielding_key_repository,
+		x25519_shielding_key_repository,
 		light_client_seal,
 	);
 
@@ -251,6 +270,153 @@ pub unsafe extern "C" fn request_state_provisioning(
 	sgx_status_t::SGX_SUCCESS
 }
 
+/// Requests the keys of a started key rotation from a fellow validateer of the shard.
+///
+/// The keys are staged and activated locally once the enclave account of the shard has been
+/// rotated, including the re-encryption of the state.
+#[no_mangle]
+pub unsafe extern "C" fn request_rotated_keys(
+	socket_fd: c_int,
+	sign_type: sgx_quote_sign_type_t,
+	quoting_enclave_target_info: Option<&sgx_target_info_t>,
+	quote_size: Option<&u32>,
+	shard: *const u8,
+	shard_size: u32,
+	skip_ra: c_int,
+) -> sgx_status_t {
+	let _ = backtrace::enable_backtrace("enclave.signed.so", PrintFormat::Short);
+	let shard = ShardIdentifier::from_slice(slice::from_raw_parts(shard, shard_size as usize));
+
+	let signing_key_repository = match GLOBAL_SIGNING_KEY_REPOSITORY_COMPONENT.get() {
+		Ok(s) => s,
+		Err(e) => {
+			error!("{:?}", e);
+			return sgx_status_t::SGX_ERROR_UNEXPECTED
+		},
+	};
+
+	let client_account = match signing_key_repository.retrieve_pubkey() {
+		Ok(s) => AccountId::from(s),
+		Err(e) => return e.into(),
+	};
+
+	if let Err(e) = request_rotated_keys_internal(
+		socket_fd,
+		sign_type,
+		quoting_enclave_target_info,
+		quote_size,
+		shard,
+		skip_ra,
+		KeyRotationSealHandler,
+		client_account,
+	) {
+		error!("Failed to obtain rotated keys due to: {:?}", e);
+		return e.into()
+	};
+
+	sgx_status_t::SGX_SUCCESS
+}
+
+/// Requests the state of an additional shard from a fellow validateer, while the worker is running.
+///
+/// The state is only sealed if the fellow validateer uses the same keys as this worker and its
+/// light client has imported the same parentchain blocks as ours.
+#[no_mangle]
+pub unsafe extern "C" fn request_shard_provisioning(
+	socket_fd: c_int,
+	sign_type: sgx_quote_sign_type_t,
+	quoting_enclave_target_info: Option<&sgx_target_info_t>,
+	quote_size: Option<&u32>,
+	shard: *const u8,
+	shard_size: u32,
+	skip_ra: c_int,
+) -> sgx_status_t {
+	let _ = backtrace::enable_backtrace("enclave.signed.so", PrintFormat::Short);
+	let shard = ShardIdentifier::from_slice(slice::from_raw_parts(shard, shard_size as usize));
+
+	let state_handler = match GLOBAL_STATE_HANDLER_COMPONENT.get() {
+		Ok(s) => s,
+		Err(e) => {
+			error!("{:?}", e);
+			return sgx_status_t::SGX_ERROR_UNEXPECTED
+		},
+	};
+
+	let validator_accessor = match get_validator_accessor_from_integritee_solo_or_parachain() {
+		Ok(v) => v,
+		Err(e) => {
+			error!("{:?}", e);
+			return sgx_status_t::SGX_ERROR_UNEXPECTED
+		},
+	};
+
+	let shielding_key_repository = match GLOBAL_SHIELDING_KEY_REPOSITORY_COMPONENT.get() {
+		Ok(s) => s,
+		Err(e) => {
+			error!("{:?}", e);
+			return sgx_status_t::SGX_ERROR_UNEXPECTED
+		},
+	};
+
+	let x25519_shielding_key_repository =
+		match GLOBAL_X25519_SHIELDING_KEY_REPOSITORY_COMPONENT.get() {
+			Ok(s) => s,
+			Err(e) => {
+				error!("{:?}", e);
+				return sgx_status_t::SGX_ERROR_UNEXPECTED
+			},
+		};
+
+	let state_key_repository = match GLOBAL_STATE_KEY_REPOSITORY_COMPONENT.get() {
+		Ok(s) => s,
+		Err(e) => {
+			error!("{:?}", e);
+			return sgx_status_t::SGX_ERROR_UNEXPECTED
+		},
+	};
+
+	let signing_key_repository = match GLOBAL_SIGNING_KEY_REPOSITORY_COMPONENT.get() {
+		Ok(s) => s,
+		Err(e) => {
+			error!("{:?}", e);
+			return sgx_status_t::SGX_ERROR_UNEXPECTED
+		},
+	};
+
+	let client_account = match signing_key_repository.retrieve_pubkey() {
+		Ok(s) => AccountId::from(s),
+		Err(e) => return e.into(),
+	};
+
+	let seal_handler = EnclaveShardSealHandler::new(
+		state_handler,
+		validator_accessor,
+		shielding_key_repository,
+		x25519_shielding_key_repository,
+		state_key_repository,
+	);
+
+	if let Err(e) = request_shard_provisioning_internal(
+		socket_fd,
+		sign_type,
+		quoting_enclave_target_info,
+		quote_size,
+		shard,
+		skip_ra,
+		seal_handler,
+		client_account,
+	) {
+		error!("Failed to provision shard {:?} due to: {:?}", shard, e);
+		return e.into()
+	};
+
+	if let Err(e) = init_shard_config(shard) {
+		error!("touch shard error: {:?}", e);
+		return sgx_status_t::SGX_ERROR_UNEXPECTED
+	}
+	sgx_status_t::SGX_SUCCESS
+}
+
 /// Internal [`request_state_provisioning`] function to be able to use the handy `?` operator.
 // allowing clippy rant because this fn will be refactored with MU RA deprecation
 #[allow(clippy::too_many_arguments)]
@@ -263,6 +429,84 @@ pub(crate) fn request_state_provisioning_internal<StateAndKeySealer: SealStateAn
 	skip_ra: c_int,
 	seal_handler: StateAndKeySealer,
 	client_account: AccountId,
+) -> EnclaveResult<()> {
+	info!("Requesting keys and state from mu-ra server of fellow validateer");
+	request_provisioning_internal(
+		socket_fd,
+		sign_type,
+		quoting_enclave_target_info,
+		quote_size,
+		shard,
+		skip_ra,
+		seal_handler,
+		client_account,
+		ProvisioningRequestKind::Bootstrap,
+	)
+}
+
+/// Internal [`request_rotated_keys`] function to be able to use the handy `?` operator.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn request_rotated_keys_internal<StateAndKeySealer: SealStateAndKeys>(
+	socket_fd: c_int,
+	sign_type: sgx_quote_sign_type_t,
+	quoting_enclave_target_info: Option<&sgx_target_info_t>,
+	quote_size: Option<&u32>,
+	shard: ShardIdentifier,
+	skip_ra: c_int,
+	seal_handler: StateAndKeySealer,
+	client_account: AccountId,
+) -> EnclaveResult<()> {
+	info!("Requesting rotated keys from mu-ra server of fellow validateer");
+	request_provisioning_internal(
+		socket_fd,
+		sign_type,
+		quoting_enclave_target_info,
+		quote_size,
+		shard,
+		skip_ra,
+		seal_handler,
+		client_account,
+		ProvisioningRequestKind::RotatedKeys,
+	)
+}
+
+/// Internal [`request_shard_provisioning`] function to be able to use the handy `?` operator.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn request_shard_provisioning_internal<StateAndKeySealer: SealStateAndKeys>(
+	socket_fd: c_int,
+	sign_type: sgx_quote_sign_type_t,
+	quoting_enclave_target_info: Option<&sgx_target_info_t>,
+	quote_size: Option<&u32>,
+	shard: ShardIdentifier,
+	skip_ra: c_int,
+	seal_handler: StateAndKeySealer,
+	client_account: AccountId,
+) -> EnclaveResult<()> {
+	info!("Requesting state of shard {:?} from mu-ra server of fellow validateer", shard);
+	request_provisioning_internal(
+		socket_fd,
+		sign_type,
+		quoting_enclave_target_info,
+		quote_size,
+		shard,
+		skip_ra,
+		seal_handler,
+		client_account,
+		ProvisioningRequestKind::Shard,
+	)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn request_provisioning_internal<StateAndKeySealer: SealStateAndKeys>(
+	socket_fd: c_int,
+	sign_type: sgx_quote_sign_type_t,
+	quoting_enclave_target_info: Option<&sgx_target_info_t>,
+	quote_size: Option<&u32>,
+	shard: ShardIdentifier,
+	skip_ra: c_int,
+	seal_handler: StateAndKeySealer,
+	client_account: AccountId,
+	kind: ProvisioningRequestKind,
 ) -> EnclaveResult<()> {
 	debug!("Client config generate...");
 	let client_config = tls_client_config(
@@ -280,9 +524,9 @@ pub(crate) fn request_state_provisioning_internal<StateAndKeySealer: SealStateAn
 		rustls::Stream::new(&mut client_session, &mut tcp_stream),
 		seal_handler,
 		shard,
+		kind,
 	);
 
-	info!("Requesting keys and state from mu-ra server of fellow validateer");
 	client.obtain_provisioning_for_shard(client_account)
 }
 
@@ -17,20 +17,24 @@
 
 //! Implementation of the server part of the state provisioning.
 
-use super::{authentication::ClientAuth, ClientProvisioningRequest, Opcode, TcpHeader};
+use super::{
+	authentication::ClientAuth, ClientProvisioningRequest, Opcode, ProvisioningRequestKind,
+	TcpHeader,
+};
 use crate::{
 	attestation::create_ra_report_and_signature,
 	error::{Error as EnclaveError, Result as EnclaveResult},
 	initialization::global_components::{
 		EnclaveSealHandler, GLOBAL_INTEGRITEE_PARENTCHAIN_LIGHT_CLIENT_SEAL,
 		GLOBAL_SHIELDING_KEY_REPOSITORY_COMPONENT, GLOBAL_STATE_KEY_REPOSITORY_COMPONENT,
+		GLOBAL_X25519_SHIELDING_KEY_REPOSITORY_COMPONENT,
 	},
 	ocall::OcallApi,
 	shard_vault::add_shard_vault_proxy,
 	tls_ra::seal_handler::UnsealStateAndKeys,
 	GLOBAL_STATE_HANDLER_COMPONENT,
 };
-use codec::Decode;
+use codec::{Decode, MaxEncodedLen};
 use itp_attestation_handler::RemoteAttestationType;
 use itp_component_container::ComponentGetter;
 use itp_ocall_api::EnclaveAttestationOCallApi;
@@ -50,6 +54,11 @@ use std::{
 enum ProvisioningPayload {
 	Everything,
 	ShieldingKeyAndLightClient,
+	/// Keys of a pending key rotation, requested by peers after a key rotation has been started.
+	Keys,
+	/// Keys, light client state and the state of a single shard, requested by running peers.
+	/// The keys are sent for the peer to check that it can use the state as it is.
+	KeysStateAndLightClient,
 }
 
 impl From<WorkerMode> for ProvisioningPayload {
@@ -91,7 +100,18 @@ where
 		let request = self.await_shard_request_from_client()?;
 		println!("    [Enclave] (MU-RA-Server) handle_shard_request_from_client, await_shard_request_from_client() OK");
 		println!("    [Enclave] (MU-RA-Server) handle_shard_request_from_client, write_all()");
-		self.write_provisioning_payloads(&request.shard)?;
+		match request.kind {
+			ProvisioningRequestKind::Bootstrap =>
+				self.write_provisioning_payloads(&request.shard, self.provisioning_payload.clone())?,
+			ProvisioningRequestKind::RotatedKeys => {
+				// The client is already a validateer of the shard, it only lacks the rotated keys.
+				return self.write_provisioning_payloads(&request.shard, ProvisioningPayload::Keys)
+			},
+			ProvisioningRequestKind::Shard => self.write_provisioning_payloads(
+				&request.shard,
+				ProvisioningPayload::KeysStateAndLightClient,
+			)?,
+		}
 
 		info!(
 			"will make client account 0x{} a proxy of vault for shard {:?}",
@@ -109,7 +129,7 @@ where
 
 	/// Read the shard of the state the client wants to receive.
 	fn await_shard_request_from_client(&mut self) -> EnclaveResult<ClientProvisioningRequest> {
-		let mut request = [0u8; std::mem::size_of::<ClientProvisioningRequest>()];
+		let mut request = vec![0u8; ClientProvisioningRequest::max_encoded_len()];
 		println!(
 			"    [Enclave] (MU-RA-Server) await_shard_request_from_client, calling read_exact()"
 		);
@@ -119,18 +139,36 @@ where
 	}
 
 	/// Sends all relevant data to the client.
-	fn write_provisioning_payloads(&mut self, shard: &ShardIdentifier) -> EnclaveResult<()> {
-		debug!("Provisioning is set to: {:?}", self.provisioning_payload);
-		match self.provisioning_payload {
+	fn write_provisioning_payloads(
+		&mut self,
+		shard: &ShardIdentifier,
+		provisioning_payload: ProvisioningPayload,
+	) -> EnclaveResult<()> {
+		debug!("Provisioning is set to: {:?}", provisioning_payload);
+		match provisioning_payload {
 			ProvisioningPayload::Everything => {
 				self.write_shielding_key()?;
+				self.write_x25519_shielding_key()?;
 				self.write_state_key()?;
 				self.write_state(shard)?;
 				self.write_light_client_state()?;
 			},
 			ProvisioningPayload::ShieldingKeyAndLightClient => {
 				self.write_shielding_key()?;
+				self.write_x25519_shielding_key()?;
+				self.write_light_client_state()?;
+			},
+			ProvisioningPayload::Keys => {
+				self.write_pending_key_rotation()?;
+			},
+			ProvisioningPayload::KeysStateAndLightClient => {
+				// Keys and light client state are sent first, the client checks them before
+				// sealing the state.
+				self.write_shielding_key()?;
+				self.write_x25519_shielding_key()?;
+				self.write_state_key()?;
 				self.write_light_client_state()?;
+				self.write_state(shard)?;
 			},
 		}
 
@@ -144,12 +182,24 @@ where
 		Ok(())
 	}
 
+	fn write_x25519_shielding_key(&mut self) -> EnclaveResult<()> {
+		let shielding_key = self.seal_handler.unseal_x25519_shielding_key()?;
+		self.write(Opcode::X25519ShieldingKey, &shielding_key)?;
+		Ok(())
+	}
+
 	fn write_state_key(&mut self) -> EnclaveResult<()> {
 		let state_key = self.seal_handler.unseal_state_key()?;
 		self.write(Opcode::StateKey, &state_key)?;
 		Ok(())
 	}
 
+	fn write_pending_key_rotation(&mut self) -> EnclaveResult<()> {
+		let key_rotation = self.seal_handler.unseal_pending_key_rotation()?;
+		self.write(Opcode::PendingKeyRotation, &key_rotation)?;
+		Ok(())
+	}
+
 	fn write_state(&mut self, shard: &ShardIdentifier) -> EnclaveResult<()> {
 		let state = self.seal_handler.unseal_state(shard)?;
 		self.write(Opcode::State, &state)?;
@@ -217,6 +267,15 @@ pub unsafe extern "C" fn run_state_provisioning_server(
 		},
 	};
 
+	let x25519_shielding_key_repository =
+		match GLOBAL_X25519_SHIELDING_KEY_REPOSITORY_COMPONENT.get() {
+			Ok(s) => s,
+			Err(e) => {
+				error!("{:?}", e);
+				return sgx_status_t::SGX_ERROR_UNEXPECTED
+			},
+		};
+
 	let light_client_seal = match GLOBAL_INTEGRITEE_PARENTCHAIN_LIGHT_CLIENT_SEAL.get() {
 		Ok(s) => s,
 		Err(e) => {
@@ -229,6 +288,7 @@ pub unsafe extern "C" fn run_state_provisioning_server(
 		state_handler,
 		state_key_repository,
 		shielding_key_repository,
+		x25519_shielding_key_repository,
 		light_client_seal,
 	);
 
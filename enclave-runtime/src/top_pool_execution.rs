@@ -17,25 +17,26 @@
 use crate::{
 	error::{Error, Result},
 	initialization::global_components::{
-		GLOBAL_OCALL_API_COMPONENT, GLOBAL_SIDECHAIN_BLOCK_COMPOSER_COMPONENT,
+		GLOBAL_ACCOUNT_SUBSCRIPTION_HANDLER_COMPONENT, GLOBAL_OCALL_API_COMPONENT,
+		GLOBAL_SIDECHAIN_BLOCK_COMPOSER_COMPONENT, GLOBAL_SIDECHAIN_BLOCK_HEADER_CACHE,
+		GLOBAL_SIDECHAIN_FINALITY_GADGET_COMPONENT, GLOBAL_SIDECHAIN_FINALITY_VOTE_QUEUE_COMPONENT,
 		GLOBAL_SIDECHAIN_IMPORT_QUEUE_WORKER_COMPONENT, GLOBAL_SIGNING_KEY_REPOSITORY_COMPONENT,
-		GLOBAL_STATE_HANDLER_COMPONENT, GLOBAL_TOP_POOL_AUTHOR_COMPONENT,
+		GLOBAL_SLOT_CLAIM_CONFIG_COMPONENT, GLOBAL_STATE_HANDLER_COMPONENT,
+		GLOBAL_TOP_POOL_AUTHOR_COMPONENT,
 	},
+	key_rotation::KeyRotationActivator,
+	rpc::account_events_api::AccountEventNotifier,
 	shard_vault::get_shard_vault_internal,
 	sync::{EnclaveLock, EnclaveStateRWLock},
 	utils::{
-		get_extrinsic_factory_from_integritee_solo_or_parachain,
-		get_extrinsic_factory_from_target_a_solo_or_parachain,
-		get_extrinsic_factory_from_target_b_solo_or_parachain,
+		get_extrinsic_factory_from_solo_or_parachain,
 		get_stf_executor_from_integritee_solo_or_parachain,
-		get_stf_executor_from_target_a_solo_or_parachain,
-		get_stf_executor_from_target_b_solo_or_parachain,
+		get_stf_executor_from_target_solo_or_parachain, get_target_parentchain_indices,
 		get_triggered_dispatcher_from_integritee_solo_or_parachain,
-		get_triggered_dispatcher_from_target_a_solo_or_parachain,
-		get_triggered_dispatcher_from_target_b_solo_or_parachain,
+		get_triggered_dispatcher_from_target_solo_or_parachain,
 		get_validator_accessor_from_integritee_solo_or_parachain,
-		get_validator_accessor_from_target_a_solo_or_parachain,
-		get_validator_accessor_from_target_b_solo_or_parachain, update_nonce_cache,
+		get_validator_accessor_from_solo_or_parachain,
+		get_validator_accessor_from_target_solo_or_parachain, update_nonce_cache,
 	},
 };
 use codec::Encode;
@@ -48,36 +49,58 @@ use itc_parentchain::{
 };
 use itp_component_container::ComponentGetter;
 use itp_enclave_metrics::EnclaveMetric;
-use itp_extrinsics_factory::CreateExtrinsics;
+use itp_extrinsics_factory::{vault_withdrawals::BatchVaultWithdrawals, CreateExtrinsics};
+use itp_import_queue::PopFromQueue;
 use itp_ocall_api::{EnclaveMetricsOCallApi, EnclaveOnChainOCallApi, EnclaveSidechainOCallApi};
 use itp_pallet_storage::{SidechainPalletStorage, SidechainPalletStorageKeys};
-use itp_settings::sidechain::SLOT_DURATION;
+use itp_settings::sidechain::{BATCH_VAULT_WITHDRAWALS, SLOT_DURATION};
 use itp_sgx_crypto::key_repository::AccessKey;
-use itp_stf_state_handler::query_shard_state::QueryShardState;
+use itp_stf_state_handler::{handle_state::HandleState, query_shard_state::QueryShardState};
 use itp_time_utils::duration_now;
 use itp_types::{
-	parentchain::{GenericMortality, ParentchainCall, ParentchainId, SidechainBlockConfirmation},
-	Block, OpaqueCall, H256,
+	parentchain::{
+		GenericMortality, ParentchainCall, ParentchainId, SidechainBlockConfirmation,
+		TargetParentchainIndex,
+	},
+	Block, OpaqueCall, ShardIdentifier, H256,
 };
+use its_block_header_cache::GetSidechainBlockHeader;
+use its_block_verification::slot::slot_from_timestamp_and_duration;
 use its_primitives::{
 	traits::{
-		Block as SidechainBlockTrait, Header as HeaderTrait, ShardIdentifierFor, SignedBlock,
+		Block as SidechainBlockTrait, BlockData, Header as HeaderTrait, ShardIdentifierFor,
+		SignedBlock,
+	},
+	types::{
+		block::{Block as SidechainBlock, BlockNumber, SignedBlock as SignedSidechainBlock},
+		slot_claims::SlotClaimConfig,
 	},
-	types::block::SignedBlock as SignedSidechainBlock,
 };
 use its_sidechain::{
 	aura::{proposer_factory::ProposerFactory, Aura, SlotClaimStrategy},
-	consensus_common::{Environment, Error as ConsensusError, ProcessBlockImportQueue},
-	slots::{yield_next_slot, LastSlot, PerShardSlotWorkerScheduler, SlotInfo},
+	consensus_common::{
+		Environment, Error as ConsensusError, FinalizeBlocks, ProcessBlockImportQueue,
+	},
+	slots::{yield_next_slot, LastSlot, PerShardSlotWorkerScheduler, Slot, SlotInfo},
+	state::LastBlockExt,
 	validateer_fetch::ValidateerFetch,
 };
 use log::*;
 use sgx_types::sgx_status_t;
-use sp_core::{crypto::UncheckedFrom, Pair};
+use sp_core::{crypto::UncheckedFrom, ed25519, Pair};
 use sp_runtime::{
-	generic::SignedBlock as SignedParentchainBlock, traits::Block as BlockTrait, MultiSignature,
+	generic::SignedBlock as SignedParentchainBlock,
+	traits::{Block as BlockTrait, Header as ParentchainHeaderTrait},
+	MultiSignature,
+};
+use std::{
+	collections::{BTreeMap, BTreeSet},
+	format,
+	string::ToString,
+	sync::Arc,
+	time::{Duration, Instant},
+	vec::Vec,
 };
-use std::{sync::Arc, time::Instant, vec::Vec};
 
 #[no_mangle]
 pub unsafe extern "C" fn execute_trusted_calls() -> sgx_status_t {
@@ -97,6 +120,7 @@ pub unsafe extern "C" fn execute_trusted_calls() -> sgx_status_t {
 /// *   Import all pending parentchain blocks.
 /// *   Sends sidechain `confirm_block` xt's with the produced sidechain blocks.
 /// *   Broadcast produced sidechain blocks to peer validateers.
+/// *   Broadcast our finality vote and store newly finalized sidechain blocks.
 fn execute_top_pool_trusted_calls_internal() -> Result<()> {
 	let start_time = Instant::now();
 
@@ -108,32 +132,23 @@ fn execute_top_pool_trusted_calls_internal() -> Result<()> {
 
 	let integritee_parentchain_import_dispatcher =
 		get_triggered_dispatcher_from_integritee_solo_or_parachain()?;
-	let maybe_target_a_parentchain_import_dispatcher =
-		get_triggered_dispatcher_from_target_a_solo_or_parachain().ok();
-	let maybe_target_b_parentchain_import_dispatcher =
-		get_triggered_dispatcher_from_target_b_solo_or_parachain().ok();
-
-	let maybe_latest_target_a_parentchain_header =
-		if let Some(ref _triggered_dispatcher) = maybe_target_a_parentchain_import_dispatcher {
-			let validator_access = get_validator_accessor_from_target_a_solo_or_parachain()?;
-			Some(validator_access.execute_on_validator(|v| {
-				let latest_parentchain_header = v.latest_finalized_header()?;
-				Ok(latest_parentchain_header)
-			})?)
-		} else {
-			None
-		};
 
-	let maybe_latest_target_b_parentchain_header =
-		if let Some(ref _triggered_dispatcher) = maybe_target_b_parentchain_import_dispatcher {
-			let validator_access = get_validator_accessor_from_target_b_solo_or_parachain()?;
-			Some(validator_access.execute_on_validator(|v| {
+	// Only target parentchains whose blocks are imported by the sidechain have a triggered dispatcher.
+	let mut target_parentchain_import_dispatchers = BTreeMap::new();
+	let mut latest_target_parentchain_headers = BTreeMap::new();
+	for index in get_target_parentchain_indices()? {
+		if let Ok(triggered_dispatcher) =
+			get_triggered_dispatcher_from_target_solo_or_parachain(index)
+		{
+			let validator_access = get_validator_accessor_from_target_solo_or_parachain(index)?;
+			let latest_parentchain_header = validator_access.execute_on_validator(|v| {
 				let latest_parentchain_header = v.latest_finalized_header()?;
 				Ok(latest_parentchain_header)
-			})?)
-		} else {
-			None
-		};
+			})?;
+			target_parentchain_import_dispatchers.insert(index, triggered_dispatcher);
+			latest_target_parentchain_headers.insert(index, latest_parentchain_header);
+		}
+	}
 
 	let integritee_validator_access = get_validator_accessor_from_integritee_solo_or_parachain()?;
 
@@ -188,30 +203,36 @@ fn execute_top_pool_trusted_calls_internal() -> Result<()> {
 	trace!("using StfExecutor from {:?} parentchain", vault_target);
 	let stf_executor = match vault_target {
 		ParentchainId::Integritee => get_stf_executor_from_integritee_solo_or_parachain()?,
-		ParentchainId::TargetA => get_stf_executor_from_target_a_solo_or_parachain()?,
-		ParentchainId::TargetB => get_stf_executor_from_target_b_solo_or_parachain()?,
+		ParentchainId::Target(index) => get_stf_executor_from_target_solo_or_parachain(index)?,
 	};
 
 	let top_pool_author = GLOBAL_TOP_POOL_AUTHOR_COMPONENT.get()?;
 
 	let block_composer = GLOBAL_SIDECHAIN_BLOCK_COMPOSER_COMPONENT.get()?;
 
+	let slot_claim_config = GLOBAL_SLOT_CLAIM_CONFIG_COMPONENT.get()?;
+
 	let authority = GLOBAL_SIGNING_KEY_REPOSITORY_COMPONENT.get()?.retrieve_key()?;
 
 	update_nonce_cache(authority.public().into(), ParentchainId::Integritee)?;
-	if maybe_target_a_parentchain_import_dispatcher.is_some() {
-		update_nonce_cache(authority.public().into(), ParentchainId::TargetA)?;
+	for index in target_parentchain_import_dispatchers.keys() {
+		update_nonce_cache(authority.public().into(), ParentchainId::Target(*index))?;
 	}
-	if maybe_target_b_parentchain_import_dispatcher.is_some() {
-		update_nonce_cache(authority.public().into(), ParentchainId::TargetB)?;
+
+	if let Err(e) = finalize_sidechain_blocks::<_, SignedSidechainBlock, _>(
+		shard,
+		&latest_integritee_parentchain_header,
+		&authority,
+		ocall_api.as_ref(),
+	) {
+		warn!("Failed to process sidechain finality votes: {:?}", e);
 	}
 
 	match yield_next_slot(
 		slot_beginning_timestamp,
 		SLOT_DURATION,
 		latest_integritee_parentchain_header,
-		maybe_latest_target_a_parentchain_header,
-		maybe_latest_target_b_parentchain_header,
+		latest_target_parentchain_headers,
 		&mut LastSlot,
 	)? {
 		Some(slot) => {
@@ -222,22 +243,40 @@ fn execute_top_pool_trusted_calls_internal() -> Result<()> {
 
 			log_remaining_slot_duration(&slot, SlotStage::BeforeAura);
 
+			// Needed to determine the slot claim rules and whether the slot passes on to a
+			// fallback author.
+			let last_block = state_handler.execute_on_current(&shard, |state, _| {
+				LastBlockExt::<SidechainBlock>::get_last_block(state).map(|block| {
+					let slot = slot_from_timestamp_and_duration(
+						Duration::from_millis(block.block_data().timestamp()),
+						SLOT_DURATION,
+					);
+					(block.header().block_number(), slot)
+				})
+			})?;
+
+			let account_event_notifier = Arc::new(AccountEventNotifier::new(
+				GLOBAL_ACCOUNT_SUBSCRIPTION_HANDLER_COMPONENT.get()?,
+			));
 			let env = ProposerFactory::<Block, _, _, _>::new(
 				top_pool_author,
 				stf_executor,
 				block_composer,
-			);
+			)
+			.with_proposed_state_observer(account_event_notifier)
+			.with_proposed_state_observer(Arc::new(KeyRotationActivator));
 
 			let (blocks, parentchain_calls) =
-				exec_aura_on_slot::<_, _, SignedSidechainBlock, _, _, _, _, _>(
+				exec_aura_on_slot::<_, _, SignedSidechainBlock, _, _, _, _>(
 					slot.clone(),
 					authority,
 					ocall_api.clone(),
 					integritee_parentchain_import_dispatcher,
-					maybe_target_a_parentchain_import_dispatcher,
-					maybe_target_b_parentchain_import_dispatcher,
+					target_parentchain_import_dispatchers,
 					env,
 					shards,
+					*slot_claim_config,
+					last_block,
 				)?;
 
 			debug!("Aura executed successfully");
@@ -261,6 +300,63 @@ fn execute_top_pool_trusted_calls_internal() -> Result<()> {
 	Ok(())
 }
 
+/// Votes for the latest imported sidechain block and counts the finality votes of our peers.
+///
+/// Justifications of newly finalized blocks are handed to the untrusted worker for storage.
+fn finalize_sidechain_blocks<ParentchainHeader, SignedSidechainBlock, OCallApi>(
+	shard: ShardIdentifier,
+	parentchain_header: &ParentchainHeader,
+	authority: &ed25519::Pair,
+	ocall_api: &OCallApi,
+) -> Result<()>
+where
+	ParentchainHeader: ParentchainHeaderTrait<Hash = H256>,
+	SignedSidechainBlock: SignedBlock + 'static,
+	<<SignedSidechainBlock as SignedBlock>::Block as SidechainBlockTrait>::HeaderType:
+		HeaderTrait<ShardIdentifier = H256>,
+	OCallApi: ValidateerFetch + EnclaveSidechainOCallApi,
+{
+	let finality_gadget = GLOBAL_SIDECHAIN_FINALITY_GADGET_COMPONENT.get()?;
+	let vote_queue = GLOBAL_SIDECHAIN_FINALITY_VOTE_QUEUE_COMPONENT.get()?;
+
+	let mut votes = vote_queue
+		.pop_all()
+		.map_err(|e| Error::Other(format!("Failed to pop finality votes: {:?}", e).into()))?;
+
+	let latest_header = GLOBAL_SIDECHAIN_BLOCK_HEADER_CACHE
+		.get_header()
+		.map_err(|e| Error::Other(format!("Failed to get sidechain header: {:?}", e).into()))?
+		.0;
+	if latest_header.block_number() > 0 {
+		if let Some(own_vote) = finality_gadget.vote(
+			authority,
+			shard,
+			latest_header.block_number(),
+			latest_header.hash(),
+		)? {
+			ocall_api.propose_finality_votes(vec![own_vote.clone()])?;
+			votes.push(own_vote);
+		}
+	}
+
+	if votes.is_empty() {
+		return Ok(())
+	}
+
+	let validateers: Vec<ed25519::Public> = ocall_api
+		.current_validateers::<ParentchainHeader, SignedSidechainBlock>(parentchain_header, shard)
+		.map_err(|e| ConsensusError::CouldNotGetAuthorities(e.to_string()))?
+		.iter()
+		.map(|account| ed25519::Public::unchecked_from(*account.as_ref()))
+		.collect();
+
+	let justifications = finality_gadget.import_votes(votes, &validateers)?;
+	if !justifications.is_empty() {
+		ocall_api.store_sidechain_justifications(justifications)?;
+	}
+	Ok(())
+}
+
 /// Executes aura for the given `slot`.
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn exec_aura_on_slot<
@@ -270,17 +366,17 @@ pub(crate) fn exec_aura_on_slot<
 	OCallApi,
 	PEnvironment,
 	IntegriteeBlockImportTrigger,
-	TargetABlockImportTrigger,
-	TargetBBlockImportTrigger,
+	TargetBlockImportTrigger,
 >(
 	slot: SlotInfo<ParentchainBlock>,
 	authority: Authority,
 	ocall_api: Arc<OCallApi>,
 	integritee_block_import_trigger: Arc<IntegriteeBlockImportTrigger>,
-	maybe_target_a_block_import_trigger: Option<Arc<TargetABlockImportTrigger>>,
-	maybe_target_b_block_import_trigger: Option<Arc<TargetBBlockImportTrigger>>,
+	target_block_import_triggers: BTreeMap<TargetParentchainIndex, Arc<TargetBlockImportTrigger>>,
 	proposer_environment: PEnvironment,
 	shards: Vec<ShardIdentifierFor<SignedSidechainBlock>>,
+	slot_claim_config: SlotClaimConfig,
+	last_block: Option<(BlockNumber, Slot)>,
 ) -> Result<(Vec<SignedSidechainBlock>, Vec<ParentchainCall>)>
 where
 	ParentchainBlock: BlockTrait<Hash = H256>,
@@ -298,23 +394,20 @@ where
 		Environment<ParentchainBlock, SignedSidechainBlock, Error = ConsensusError> + Send + Sync,
 	IntegriteeBlockImportTrigger:
 		TriggerParentchainBlockImport<SignedBlockType = SignedParentchainBlock<ParentchainBlock>>,
-	TargetABlockImportTrigger:
-		TriggerParentchainBlockImport<SignedBlockType = SignedParentchainBlock<ParentchainBlock>>,
-	TargetBBlockImportTrigger:
+	TargetBlockImportTrigger:
 		TriggerParentchainBlockImport<SignedBlockType = SignedParentchainBlock<ParentchainBlock>>,
 {
 	debug!("[Aura] Executing aura for slot: {:?}", slot);
 
-	let mut aura =
-		Aura::<_, ParentchainBlock, SignedSidechainBlock, PEnvironment, _, _, _, _>::new(
-			authority,
-			ocall_api.as_ref().clone(),
-			integritee_block_import_trigger,
-			maybe_target_a_block_import_trigger,
-			maybe_target_b_block_import_trigger,
-			proposer_environment,
-		)
-		.with_claim_strategy(SlotClaimStrategy::RoundRobin);
+	let mut aura = Aura::<_, ParentchainBlock, SignedSidechainBlock, PEnvironment, _, _, _>::new(
+		authority,
+		ocall_api.as_ref().clone(),
+		integritee_block_import_trigger,
+		target_block_import_triggers,
+		proposer_environment,
+	)
+	.with_claim_strategy(SlotClaimStrategy::RoundRobin)
+	.with_slot_claim_config(slot_claim_config, last_block);
 
 	let (blocks, pxts): (Vec<_>, Vec<_>) =
 		PerShardSlotWorkerScheduler::on_slot(&mut aura, slot, shards)
@@ -341,37 +434,25 @@ where
 	debug!("Proposing {} sidechain block(s) (broadcasting to peers)", blocks.len());
 	ocall_api.propose_sidechain_blocks(blocks)?;
 
-	let calls: Vec<(OpaqueCall, GenericMortality)> = parentchain_calls
-		.iter()
-		.filter_map(|parentchain_call| parentchain_call.as_integritee())
-		.collect();
-	debug!("Enclave wants to send {} extrinsics to Integritee Parentchain", calls.len());
-	if !calls.is_empty() {
-		let extrinsics_factory = get_extrinsic_factory_from_integritee_solo_or_parachain()?;
-		let xts = extrinsics_factory.create_extrinsics(calls.as_slice(), None)?;
-		let validator_access = get_validator_accessor_from_integritee_solo_or_parachain()?;
-		validator_access.execute_mut_on_validator(|v| v.send_extrinsics(xts))?;
-	}
-	let calls: Vec<(OpaqueCall, GenericMortality)> = parentchain_calls
-		.iter()
-		.filter_map(|parentchain_call| parentchain_call.as_target_a())
-		.collect();
-	debug!("Enclave wants to send {} extrinsics to TargetA Parentchain", calls.len());
-	if !calls.is_empty() {
-		let extrinsics_factory = get_extrinsic_factory_from_target_a_solo_or_parachain()?;
-		let xts = extrinsics_factory.create_extrinsics(calls.as_slice(), None)?;
-		let validator_access = get_validator_accessor_from_target_a_solo_or_parachain()?;
-		validator_access.execute_mut_on_validator(|v| v.send_extrinsics(xts))?;
-	}
-	let calls: Vec<(OpaqueCall, GenericMortality)> = parentchain_calls
-		.iter()
-		.filter_map(|parentchain_call| parentchain_call.as_target_b())
-		.collect();
-	debug!("Enclave wants to send {} extrinsics to TargetB Parentchain", calls.len());
-	if !calls.is_empty() {
-		let extrinsics_factory = get_extrinsic_factory_from_target_b_solo_or_parachain()?;
+	let parentchain_ids: BTreeSet<ParentchainId> =
+		parentchain_calls.iter().map(|call| call.parentchain_id()).collect();
+	for parentchain_id in parentchain_ids {
+		let extrinsics_factory = get_extrinsic_factory_from_solo_or_parachain(parentchain_id)?;
+		let calls: Vec<(OpaqueCall, GenericMortality)> = if BATCH_VAULT_WITHDRAWALS {
+			extrinsics_factory.batch_vault_withdrawals(&parentchain_calls, parentchain_id)?
+		} else {
+			parentchain_calls
+				.iter()
+				.filter_map(|parentchain_call| parentchain_call.as_call_for(parentchain_id))
+				.collect()
+		};
+		debug!(
+			"Enclave wants to send {} extrinsics to {:?} Parentchain",
+			calls.len(),
+			parentchain_id
+		);
 		let xts = extrinsics_factory.create_extrinsics(calls.as_slice(), None)?;
-		let validator_access = get_validator_accessor_from_target_b_solo_or_parachain()?;
+		let validator_access = get_validator_accessor_from_solo_or_parachain(parentchain_id)?;
 		validator_access.execute_mut_on_validator(|v| v.send_extrinsics(xts))?;
 	}
 
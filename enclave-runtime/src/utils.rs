@@ -16,29 +16,34 @@
 */
 use crate::{
 	error::{Error, Result},
-	initialization::global_components::{
-		EnclaveExtrinsicsFactory, EnclaveNodeMetadataRepository, EnclaveStfEnclaveSigner,
-		EnclaveStfExecutor, EnclaveValidatorAccessor,
-		IntegriteeParentchainTriggeredBlockImportDispatcher,
-		TargetAParentchainTriggeredBlockImportDispatcher,
-		TargetBParentchainTriggeredBlockImportDispatcher,
-		GLOBAL_INTEGRITEE_PARACHAIN_HANDLER_COMPONENT, GLOBAL_INTEGRITEE_PARENTCHAIN_NONCE_CACHE,
-		GLOBAL_INTEGRITEE_SOLOCHAIN_HANDLER_COMPONENT, GLOBAL_OCALL_API_COMPONENT,
-		GLOBAL_TARGET_A_PARACHAIN_HANDLER_COMPONENT, GLOBAL_TARGET_A_PARENTCHAIN_NONCE_CACHE,
-		GLOBAL_TARGET_A_SOLOCHAIN_HANDLER_COMPONENT, GLOBAL_TARGET_B_PARACHAIN_HANDLER_COMPONENT,
-		GLOBAL_TARGET_B_PARENTCHAIN_NONCE_CACHE, GLOBAL_TARGET_B_SOLOCHAIN_HANDLER_COMPONENT,
+	initialization::{
+		global_components::{
+			EnclaveExtrinsicsFactory, EnclaveNodeMetadataRepository, EnclaveStfEnclaveSigner,
+			EnclaveStfExecutor, EnclaveValidatorAccessor,
+			IntegriteeParentchainTriggeredBlockImportDispatcher,
+			TargetParentchainBlockImportDispatcher,
+			TargetParentchainTriggeredBlockImportDispatcher,
+			GLOBAL_INTEGRITEE_PARACHAIN_HANDLER_COMPONENT,
+			GLOBAL_INTEGRITEE_PARENTCHAIN_NONCE_CACHE,
+			GLOBAL_INTEGRITEE_SOLOCHAIN_HANDLER_COMPONENT, GLOBAL_OCALL_API_COMPONENT,
+			GLOBAL_TARGET_PARACHAIN_HANDLER_COMPONENTS, GLOBAL_TARGET_PARENTCHAIN_NONCE_CACHES,
+			GLOBAL_TARGET_SOLOCHAIN_HANDLER_COMPONENTS,
+		},
+		parentchain::{
+			target_parachain::TargetParachainHandler, target_solochain::TargetSolochainHandler,
+		},
 	},
 	ocall::OcallApi,
 };
-use alloc::vec::Vec;
+use alloc::{collections::BTreeSet, vec::Vec};
 use codec::{Decode, Input};
 use ita_stf::ParentchainHeader;
 use itc_parentchain_block_import_dispatcher::BlockImportDispatcher;
 use itp_component_container::ComponentGetter;
-use itp_nonce_cache::{MutateNonce, Nonce};
+use itp_nonce_cache::{MutateNonce, Nonce, NonceCache};
 use itp_ocall_api::EnclaveOnChainOCallApi;
 use itp_types::{
-	parentchain::{AccountId, GenericMortality, ParentchainId},
+	parentchain::{AccountId, GenericMortality, ParentchainId, TargetParentchainIndex},
 	WorkerRequest, WorkerResponse,
 };
 use log::*;
@@ -92,29 +97,29 @@ pub(crate) fn get_triggered_dispatcher_from_integritee_solo_or_parachain(
 	Ok(dispatcher)
 }
 
-pub(crate) fn get_triggered_dispatcher_from_target_a_solo_or_parachain(
-) -> Result<Arc<TargetAParentchainTriggeredBlockImportDispatcher>> {
-	let dispatcher =
-		if let Ok(solochain_handler) = GLOBAL_TARGET_A_SOLOCHAIN_HANDLER_COMPONENT.get() {
-			get_triggered_dispatcher(solochain_handler.import_dispatcher.clone())?
-		} else if let Ok(parachain_handler) = GLOBAL_TARGET_A_PARACHAIN_HANDLER_COMPONENT.get() {
-			get_triggered_dispatcher(parachain_handler.import_dispatcher.clone())?
-		} else {
-			return Err(Error::NoTargetAParentchainAssigned)
-		};
+pub(crate) fn get_triggered_dispatcher_from_target_solo_or_parachain(
+	index: TargetParentchainIndex,
+) -> Result<Arc<TargetParentchainTriggeredBlockImportDispatcher>> {
+	let dispatcher = if let Some(solochain_handler) = get_target_solochain_handler(index)? {
+		get_triggered_dispatcher(solochain_handler.import_dispatcher.clone())?
+	} else if let Some(parachain_handler) = get_target_parachain_handler(index)? {
+		get_triggered_dispatcher(parachain_handler.import_dispatcher.clone())?
+	} else {
+		return Err(Error::NoTargetParentchainAssigned(index))
+	};
 	Ok(dispatcher)
 }
 
-pub(crate) fn get_triggered_dispatcher_from_target_b_solo_or_parachain(
-) -> Result<Arc<TargetBParentchainTriggeredBlockImportDispatcher>> {
-	let dispatcher =
-		if let Ok(solochain_handler) = GLOBAL_TARGET_B_SOLOCHAIN_HANDLER_COMPONENT.get() {
-			get_triggered_dispatcher(solochain_handler.import_dispatcher.clone())?
-		} else if let Ok(parachain_handler) = GLOBAL_TARGET_B_PARACHAIN_HANDLER_COMPONENT.get() {
-			get_triggered_dispatcher(parachain_handler.import_dispatcher.clone())?
-		} else {
-			return Err(Error::NoTargetBParentchainAssigned)
-		};
+pub(crate) fn get_import_dispatcher_from_target_solo_or_parachain(
+	index: TargetParentchainIndex,
+) -> Result<Arc<TargetParentchainBlockImportDispatcher>> {
+	let dispatcher = if let Some(solochain_handler) = get_target_solochain_handler(index)? {
+		solochain_handler.import_dispatcher.clone()
+	} else if let Some(parachain_handler) = get_target_parachain_handler(index)? {
+		parachain_handler.import_dispatcher.clone()
+	} else {
+		return Err(Error::NoTargetParentchainAssigned(index))
+	};
 	Ok(dispatcher)
 }
 
@@ -140,29 +145,16 @@ pub(crate) fn get_validator_accessor_from_integritee_solo_or_parachain(
 	Ok(validator_accessor)
 }
 
-pub(crate) fn get_validator_accessor_from_target_a_solo_or_parachain(
+pub(crate) fn get_validator_accessor_from_target_solo_or_parachain(
+	index: TargetParentchainIndex,
 ) -> Result<Arc<EnclaveValidatorAccessor>> {
-	let validator_accessor =
-		if let Ok(solochain_handler) = GLOBAL_TARGET_A_SOLOCHAIN_HANDLER_COMPONENT.get() {
-			solochain_handler.validator_accessor.clone()
-		} else if let Ok(parachain_handler) = GLOBAL_TARGET_A_PARACHAIN_HANDLER_COMPONENT.get() {
-			parachain_handler.validator_accessor.clone()
-		} else {
-			return Err(Error::NoTargetAParentchainAssigned)
-		};
-	Ok(validator_accessor)
-}
-
-pub(crate) fn get_validator_accessor_from_target_b_solo_or_parachain(
-) -> Result<Arc<EnclaveValidatorAccessor>> {
-	let validator_accessor =
-		if let Ok(solochain_handler) = GLOBAL_TARGET_B_SOLOCHAIN_HANDLER_COMPONENT.get() {
-			solochain_handler.validator_accessor.clone()
-		} else if let Ok(parachain_handler) = GLOBAL_TARGET_B_PARACHAIN_HANDLER_COMPONENT.get() {
-			parachain_handler.validator_accessor.clone()
-		} else {
-			return Err(Error::NoTargetBParentchainAssigned)
-		};
+	let validator_accessor = if let Some(solochain_handler) = get_target_solochain_handler(index)? {
+		solochain_handler.validator_accessor.clone()
+	} else if let Some(parachain_handler) = get_target_parachain_handler(index)? {
+		parachain_handler.validator_accessor.clone()
+	} else {
+		return Err(Error::NoTargetParentchainAssigned(index))
+	};
 	Ok(validator_accessor)
 }
 
@@ -179,29 +171,17 @@ pub(crate) fn get_node_metadata_repository_from_integritee_solo_or_parachain(
 	Ok(metadata_repository)
 }
 
-pub(crate) fn get_node_metadata_repository_from_target_a_solo_or_parachain(
-) -> Result<Arc<EnclaveNodeMetadataRepository>> {
-	let metadata_repository =
-		if let Ok(solochain_handler) = GLOBAL_TARGET_A_SOLOCHAIN_HANDLER_COMPONENT.get() {
-			solochain_handler.node_metadata_repository.clone()
-		} else if let Ok(parachain_handler) = GLOBAL_TARGET_A_PARACHAIN_HANDLER_COMPONENT.get() {
-			parachain_handler.node_metadata_repository.clone()
-		} else {
-			return Err(Error::NoTargetAParentchainAssigned)
-		};
-	Ok(metadata_repository)
-}
-
-pub(crate) fn get_node_metadata_repository_from_target_b_solo_or_parachain(
+pub(crate) fn get_node_metadata_repository_from_target_solo_or_parachain(
+	index: TargetParentchainIndex,
 ) -> Result<Arc<EnclaveNodeMetadataRepository>> {
-	let metadata_repository =
-		if let Ok(solochain_handler) = GLOBAL_TARGET_B_SOLOCHAIN_HANDLER_COMPONENT.get() {
-			solochain_handler.node_metadata_repository.clone()
-		} else if let Ok(parachain_handler) = GLOBAL_TARGET_B_PARACHAIN_HANDLER_COMPONENT.get() {
-			parachain_handler.node_metadata_repository.clone()
-		} else {
-			return Err(Error::NoTargetBParentchainAssigned)
-		};
+	let metadata_repository = if let Some(solochain_handler) = get_target_solochain_handler(index)?
+	{
+		solochain_handler.node_metadata_repository.clone()
+	} else if let Some(parachain_handler) = get_target_parachain_handler(index)? {
+		parachain_handler.node_metadata_repository.clone()
+	} else {
+		return Err(Error::NoTargetParentchainAssigned(index))
+	};
 	Ok(metadata_repository)
 }
 
@@ -218,29 +198,16 @@ pub(crate) fn get_extrinsic_factory_from_integritee_solo_or_parachain(
 	Ok(extrinsics_factory)
 }
 
-pub(crate) fn get_extrinsic_factory_from_target_a_solo_or_parachain(
-) -> Result<Arc<EnclaveExtrinsicsFactory>> {
-	let extrinsics_factory =
-		if let Ok(solochain_handler) = GLOBAL_TARGET_A_SOLOCHAIN_HANDLER_COMPONENT.get() {
-			solochain_handler.extrinsics_factory.clone()
-		} else if let Ok(parachain_handler) = GLOBAL_TARGET_A_PARACHAIN_HANDLER_COMPONENT.get() {
-			parachain_handler.extrinsics_factory.clone()
-		} else {
-			return Err(Error::NoTargetAParentchainAssigned)
-		};
-	Ok(extrinsics_factory)
-}
-
-pub(crate) fn get_extrinsic_factory_from_target_b_solo_or_parachain(
+pub(crate) fn get_extrinsic_factory_from_target_solo_or_parachain(
+	index: TargetParentchainIndex,
 ) -> Result<Arc<EnclaveExtrinsicsFactory>> {
-	let extrinsics_factory =
-		if let Ok(solochain_handler) = GLOBAL_TARGET_B_SOLOCHAIN_HANDLER_COMPONENT.get() {
-			solochain_handler.extrinsics_factory.clone()
-		} else if let Ok(parachain_handler) = GLOBAL_TARGET_B_PARACHAIN_HANDLER_COMPONENT.get() {
-			parachain_handler.extrinsics_factory.clone()
-		} else {
-			return Err(Error::NoTargetBParentchainAssigned)
-		};
+	let extrinsics_factory = if let Some(solochain_handler) = get_target_solochain_handler(index)? {
+		solochain_handler.extrinsics_factory.clone()
+	} else if let Some(parachain_handler) = get_target_parachain_handler(index)? {
+		parachain_handler.extrinsics_factory.clone()
+	} else {
+		return Err(Error::NoTargetParentchainAssigned(index))
+	};
 	Ok(extrinsics_factory)
 }
 
@@ -257,30 +224,95 @@ pub(crate) fn get_stf_executor_from_integritee_solo_or_parachain() -> Result<Arc
 	Ok(stf_executor)
 }
 
-pub(crate) fn get_stf_executor_from_target_a_solo_or_parachain() -> Result<Arc<EnclaveStfExecutor>>
-{
-	let stf_executor =
-		if let Ok(solochain_handler) = GLOBAL_TARGET_A_SOLOCHAIN_HANDLER_COMPONENT.get() {
-			solochain_handler.stf_executor.clone()
-		} else if let Ok(parachain_handler) = GLOBAL_TARGET_A_PARACHAIN_HANDLER_COMPONENT.get() {
-			parachain_handler.stf_executor.clone()
-		} else {
-			return Err(Error::NoTargetAParentchainAssigned)
-		};
+pub(crate) fn get_stf_executor_from_target_solo_or_parachain(
+	index: TargetParentchainIndex,
+) -> Result<Arc<EnclaveStfExecutor>> {
+	let stf_executor = if let Some(solochain_handler) = get_target_solochain_handler(index)? {
+		solochain_handler.stf_executor.clone()
+	} else if let Some(parachain_handler) = get_target_parachain_handler(index)? {
+		parachain_handler.stf_executor.clone()
+	} else {
+		return Err(Error::NoTargetParentchainAssigned(index))
+	};
 	Ok(stf_executor)
 }
 
-pub(crate) fn get_stf_executor_from_target_b_solo_or_parachain() -> Result<Arc<EnclaveStfExecutor>>
-{
-	let stf_executor =
-		if let Ok(solochain_handler) = GLOBAL_TARGET_B_SOLOCHAIN_HANDLER_COMPONENT.get() {
-			solochain_handler.stf_executor.clone()
-		} else if let Ok(parachain_handler) = GLOBAL_TARGET_B_PARACHAIN_HANDLER_COMPONENT.get() {
-			parachain_handler.stf_executor.clone()
-		} else {
-			return Err(Error::NoTargetBParentchainAssigned)
-		};
-	Ok(stf_executor)
+/// Returns the indices of all target parentchains that have been initialized.
+pub(crate) fn get_target_parentchain_indices() -> Result<Vec<TargetParentchainIndex>> {
+	let mut indices: BTreeSet<TargetParentchainIndex> = GLOBAL_TARGET_SOLOCHAIN_HANDLER_COMPONENTS
+		.read()
+		.map_err(|_| Error::MutexAccess)?
+		.keys()
+		.copied()
+		.collect();
+	indices.extend(
+		GLOBAL_TARGET_PARACHAIN_HANDLER_COMPONENTS
+			.read()
+			.map_err(|_| Error::MutexAccess)?
+			.keys(),
+	);
+	Ok(indices.into_iter().collect())
+}
+
+fn get_target_solochain_handler(
+	index: TargetParentchainIndex,
+) -> Result<Option<Arc<TargetSolochainHandler>>> {
+	Ok(GLOBAL_TARGET_SOLOCHAIN_HANDLER_COMPONENTS
+		.read()
+		.map_err(|_| Error::MutexAccess)?
+		.get(&index)
+		.cloned())
+}
+
+fn get_target_parachain_handler(
+	index: TargetParentchainIndex,
+) -> Result<Option<Arc<TargetParachainHandler>>> {
+	Ok(GLOBAL_TARGET_PARACHAIN_HANDLER_COMPONENTS
+		.read()
+		.map_err(|_| Error::MutexAccess)?
+		.get(&index)
+		.cloned())
+}
+
+/// Returns the nonce cache of a target parentchain, which is created on first access.
+pub(crate) fn get_target_parentchain_nonce_cache(
+	index: TargetParentchainIndex,
+) -> Result<Arc<NonceCache>> {
+	Ok(GLOBAL_TARGET_PARENTCHAIN_NONCE_CACHES
+		.write()
+		.map_err(|_| Error::MutexAccess)?
+		.entry(index)
+		.or_default()
+		.clone())
+}
+
+pub(crate) fn get_validator_accessor_from_solo_or_parachain(
+	parentchain_id: ParentchainId,
+) -> Result<Arc<EnclaveValidatorAccessor>> {
+	match parentchain_id {
+		ParentchainId::Integritee => get_validator_accessor_from_integritee_solo_or_parachain(),
+		ParentchainId::Target(index) => get_validator_accessor_from_target_solo_or_parachain(index),
+	}
+}
+
+pub(crate) fn get_node_metadata_repository_from_solo_or_parachain(
+	parentchain_id: ParentchainId,
+) -> Result<Arc<EnclaveNodeMetadataRepository>> {
+	match parentchain_id {
+		ParentchainId::Integritee =>
+			get_node_metadata_repository_from_integritee_solo_or_parachain(),
+		ParentchainId::Target(index) =>
+			get_node_metadata_repository_from_target_solo_or_parachain(index),
+	}
+}
+
+pub(crate) fn get_extrinsic_factory_from_solo_or_parachain(
+	parentchain_id: ParentchainId,
+) -> Result<Arc<EnclaveExtrinsicsFactory>> {
+	match parentchain_id {
+		ParentchainId::Integritee => get_extrinsic_factory_from_integritee_solo_or_parachain(),
+		ParentchainId::Target(index) => get_extrinsic_factory_from_target_solo_or_parachain(index),
+	}
 }
 
 pub(crate) fn get_stf_enclave_signer_from_solo_or_parachain() -> Result<Arc<EnclaveStfEnclaveSigner>>
@@ -335,12 +367,13 @@ pub(crate) fn update_nonce_cache(
 	parentchain_id: ParentchainId,
 ) -> Result<()> {
 	let ocall_api = GLOBAL_OCALL_API_COMPONENT.get()?;
-	let mut nonce_lock = match parentchain_id {
-		ParentchainId::Integritee => GLOBAL_INTEGRITEE_PARENTCHAIN_NONCE_CACHE.load_for_mutation(),
-		ParentchainId::TargetA => GLOBAL_TARGET_A_PARENTCHAIN_NONCE_CACHE.load_for_mutation(),
-		ParentchainId::TargetB => GLOBAL_TARGET_B_PARENTCHAIN_NONCE_CACHE.load_for_mutation(),
-	}
-	.map_err(|_| Error::NonceUpdateFailed(parentchain_id))?;
+	let nonce_cache = match parentchain_id {
+		ParentchainId::Integritee => GLOBAL_INTEGRITEE_PARENTCHAIN_NONCE_CACHE.clone(),
+		ParentchainId::Target(index) => get_target_parentchain_nonce_cache(index)?,
+	};
+	let mut nonce_lock = nonce_cache
+		.load_for_mutation()
+		.map_err(|_| Error::NonceUpdateFailed(parentchain_id))?;
 
 	if let WorkerResponse::NextNonce(Some(nonce)) = ocall_api
 		.worker_request::<ParentchainHeader, Vec<u8>>(
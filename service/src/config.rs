@@ -18,7 +18,8 @@
 use clap::ArgMatches;
 use itc_rest_client::rest_client::Url;
 use itp_settings::teeracle::{DEFAULT_MARKET_DATA_UPDATE_INTERVAL, ONE_DAY, THIRTY_MINUTES};
-use itp_types::parentchain::ParentchainId;
+use itp_types::parentchain::{ParentchainId, TargetParentchainIndex};
+use its_primitives::types::{block::BlockNumber, slot_claims::SlotClaimConfig};
 use parse_duration::parse;
 use serde::{Deserialize, Serialize};
 use std::{
@@ -34,15 +35,16 @@ static DEFAULT_UNTRUSTED_PORT: &str = "2001";
 static DEFAULT_MU_RA_PORT: &str = "3443";
 static DEFAULT_METRICS_PORT: &str = "8787";
 static DEFAULT_UNTRUSTED_HTTP_PORT: &str = "4545";
+static DEFAULT_SLOT_FALLBACK_DELAY: u64 = 3;
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Config {
 	integritee_rpc_url: String,
 	integritee_rpc_port: String,
-	target_a_parentchain_rpc_url: Option<String>,
-	target_a_parentchain_rpc_port: Option<String>,
-	target_b_parentchain_rpc_url: Option<String>,
-	target_b_parentchain_rpc_port: Option<String>,
+	/// Target parentchain RPC urls, the position in the list is the target parentchain index.
+	target_parentchain_rpc_urls: Vec<String>,
+	/// Target parentchain RPC ports, one for each entry in `target_parentchain_rpc_urls`.
+	target_parentchain_rpc_ports: Vec<String>,
 	worker_ip: String,
 	/// Trusted worker address that will be advertised on the parentchain.
 	trusted_external_worker_address: Option<String>,
@@ -73,10 +75,8 @@ impl Config {
 	pub fn new(
 		integritee_rpc_url: String,
 		integritee_rpc_port: String,
-		target_a_parentchain_rpc_url: Option<String>,
-		target_a_parentchain_rpc_port: Option<String>,
-		target_b_parentchain_rpc_url: Option<String>,
-		target_b_parentchain_rpc_port: Option<String>,
+		target_parentchain_rpc_urls: Vec<String>,
+		target_parentchain_rpc_ports: Vec<String>,
 		worker_ip: String,
 		trusted_external_worker_address: Option<String>,
 		trusted_worker_port: String,
@@ -93,10 +93,8 @@ impl Config {
 		Self {
 			integritee_rpc_url,
 			integritee_rpc_port,
-			target_a_parentchain_rpc_url,
-			target_a_parentchain_rpc_port,
-			target_b_parentchain_rpc_url,
-			target_b_parentchain_rpc_port,
+			target_parentchain_rpc_urls,
+			target_parentchain_rpc_ports,
 			worker_ip,
 			trusted_external_worker_address,
 			trusted_worker_port,
@@ -117,34 +115,16 @@ impl Config {
 		format!("{}:{}", self.integritee_rpc_url, self.integritee_rpc_port)
 	}
 
-	pub fn target_a_parentchain_rpc_endpoint(&self) -> Option<String> {
-		if self.target_a_parentchain_rpc_url.is_some()
-			&& self.target_a_parentchain_rpc_port.is_some()
-		{
-			return Some(format!(
-				"{}:{}",
-				// Can be done better, but this code is obsolete anyhow with clap v4.
-				self.target_a_parentchain_rpc_url.clone().unwrap(),
-				self.target_a_parentchain_rpc_port.clone().unwrap()
-			))
-		};
-
-		None
-	}
-
-	pub fn target_b_parentchain_rpc_endpoint(&self) -> Option<String> {
-		if self.target_b_parentchain_rpc_url.is_some()
-			&& self.target_b_parentchain_rpc_port.is_some()
-		{
-			return Some(format!(
-				"{}:{}",
-				// Can be done better, but this code is obsolete anyhow with clap v4.
-				self.target_b_parentchain_rpc_url.clone().unwrap(),
-				self.target_b_parentchain_rpc_port.clone().unwrap()
-			))
-		};
-
-		None
+	/// Target parentchain RPC endpoints (including ws://), indexed by their target parentchain index.
+	pub fn target_parentchain_rpc_endpoints(&self) -> Vec<(TargetParentchainIndex, String)> {
+		self.target_parentchain_rpc_urls
+			.iter()
+			.zip(self.target_parentchain_rpc_ports.iter())
+			.enumerate()
+			.map(|(index, (url, port))| {
+				(index as TargetParentchainIndex, format!("{}:{}", url, port))
+			})
+			.collect()
 	}
 
 	pub fn trusted_worker_url_internal(&self) -> String {
@@ -234,13 +214,27 @@ impl From<&ArgMatches<'_>> for Config {
 
 		let run_config = m.subcommand_matches("run").map(RunConfig::from);
 
+		let target_parentchain_rpc_urls: Vec<String> = m
+			.values_of("target-parentchain-rpc-url")
+			.map(|urls| urls.map(Into::into).collect())
+			.unwrap_or_default();
+		let target_parentchain_rpc_ports: Vec<String> = m
+			.values_of("target-parentchain-rpc-port")
+			.map(|ports| ports.map(Into::into).collect())
+			.unwrap_or_default();
+		if target_parentchain_rpc_urls.len() != target_parentchain_rpc_ports.len() {
+			panic!(
+				"target-parentchain-rpc-url was given {} times, but target-parentchain-rpc-port {} times",
+				target_parentchain_rpc_urls.len(),
+				target_parentchain_rpc_ports.len()
+			);
+		}
+
 		Self::new(
 			m.value_of("integritee-rpc-url").unwrap_or(DEFAULT_INTEGRITEE_RPC_URL).into(),
 			m.value_of("integritee-rpc-port").unwrap_or(DEFAULT_INTEGRITEE_RPC_PORT).into(),
-			m.value_of("target-a-parentchain-rpc-url").map(Into::into),
-			m.value_of("target-a-parentchain-rpc-port").map(Into::into),
-			m.value_of("target-b-parentchain-rpc-url").map(Into::into),
-			m.value_of("target-b-parentchain-rpc-port").map(Into::into),
+			target_parentchain_rpc_urls,
+			target_parentchain_rpc_ports,
 			if m.is_present("ws-external") { "0.0.0.0".into() } else { "127.0.0.1".into() },
 			m.value_of("trusted-external-address")
 				.map(|url| add_port_if_necessary(url, trusted_port)),
@@ -276,6 +270,14 @@ pub struct RunConfig {
 	marblerun_base_url: Option<String>,
 	/// parentchain which should be used for shielding/unshielding the stf's native token
 	pub shielding_target: Option<ParentchainId>,
+	/// Rotate the shielding key and the state key before the enclave is registered.
+	rotate_keys: bool,
+	/// Sidechain block from which on the slots are assigned in proportion to the validateers' stake.
+	weighted_slots_from: Option<BlockNumber>,
+	/// Sidechain block from which on fallback authors may claim the slot of an offline author.
+	fallback_authors_from: Option<BlockNumber>,
+	/// Number of slots without a sidechain block, after which the slot passes on to a fallback author.
+	fallback_delay: Option<u64>,
 }
 
 impl RunConfig {
@@ -287,6 +289,10 @@ impl RunConfig {
 		self.dev
 	}
 
+	pub fn rotate_keys(&self) -> bool {
+		self.rotate_keys
+	}
+
 	pub fn shard(&self) -> Option<&str> {
 		self.shard.as_deref()
 	}
@@ -304,6 +310,15 @@ impl RunConfig {
 		self.reregister_teeracle_interval.unwrap_or(ONE_DAY - THIRTY_MINUTES)
 	}
 
+	/// Slot claim rules of the sidechain. They must be the same for all validateers of a shard.
+	pub fn slot_claim_config(&self) -> SlotClaimConfig {
+		SlotClaimConfig {
+			weighted_from: self.weighted_slots_from,
+			fallback_authors_from: self.fallback_authors_from,
+			fallback_delay: self.fallback_delay.unwrap_or(DEFAULT_SLOT_FALLBACK_DELAY),
+		}
+	}
+
 	pub fn marblerun_base_url(&self) -> &str {
 		// This conflicts with the default port of a substrate node, but it is indeed the
 		// default port of marblerun too:
@@ -316,6 +331,7 @@ impl From<&ArgMatches<'_>> for RunConfig {
 	fn from(m: &ArgMatches<'_>) -> Self {
 		let skip_ra = m.is_present("skip-ra");
 		let dev = m.is_present("dev");
+		let rotate_keys = m.is_present("rotate-keys");
 		let shard = m.value_of("shard").map(|s| s.to_string());
 		let teeracle_update_interval = m.value_of("teeracle-interval").map(|i| {
 			parse(i).unwrap_or_else(|e| panic!("teeracle-interval parsing error {:?}", e))
@@ -330,14 +346,25 @@ impl From<&ArgMatches<'_>> for RunConfig {
 				.to_string()
 		});
 
-		let shielding_target = m.value_of("shielding-target").map(|i| match i {
-			"integritee" => ParentchainId::Integritee,
-			"target_a" => ParentchainId::TargetA,
-			"target_b" => ParentchainId::TargetB,
-			_ => panic!(
-				"failed to parse shielding-target: {} must be one of integritee|target_a|target_b",
-				i
-			),
+		let weighted_slots_from = m.value_of("weighted-slots-from").map(|b| {
+			b.parse()
+				.unwrap_or_else(|e| panic!("weighted-slots-from parsing error {:?}", e))
+		});
+		let fallback_authors_from = m.value_of("fallback-authors-from").map(|b| {
+			b.parse()
+				.unwrap_or_else(|e| panic!("fallback-authors-from parsing error {:?}", e))
+		});
+		let fallback_delay = m
+			.value_of("fallback-delay")
+			.map(|d| d.parse().unwrap_or_else(|e| panic!("fallback-delay parsing error {:?}", e)));
+
+		let shielding_target = m.value_of("shielding-target").map(|i| {
+			parse_parentchain_id(i).unwrap_or_else(|| {
+				panic!(
+					"failed to parse shielding-target: {} must be one of integritee|target_<index>",
+					i
+				)
+			})
 		});
 		Self {
 			skip_ra,
@@ -347,10 +374,28 @@ impl From<&ArgMatches<'_>> for RunConfig {
 			reregister_teeracle_interval,
 			marblerun_base_url,
 			shielding_target,
+			rotate_keys,
+			weighted_slots_from,
+			fallback_authors_from,
+			fallback_delay,
 		}
 	}
 }
 
+/// Parses `integritee` or `target_<index>`. `target_a` and `target_b` are accepted as aliases
+/// for the first two target parentchains.
+fn parse_parentchain_id(s: &str) -> Option<ParentchainId> {
+	match s {
+		"integritee" => Some(ParentchainId::Integritee),
+		"target_a" => Some(ParentchainId::Target(0)),
+		"target_b" => Some(ParentchainId::Target(1)),
+		_ => s
+			.strip_prefix("target_")
+			.and_then(|index| index.parse().ok())
+			.map(ParentchainId::Target),
+	}
+}
+
 fn add_port_if_necessary(url: &str, port: &str) -> String {
 	// [Option("ws(s)"), ip, Option(port)]
 	match url.split(':').count() {
@@ -386,10 +431,8 @@ mod test {
 
 		assert_eq!(config.integritee_rpc_url, DEFAULT_INTEGRITEE_RPC_URL);
 		assert_eq!(config.integritee_rpc_port, DEFAULT_INTEGRITEE_RPC_PORT);
-		assert_eq!(config.target_a_parentchain_rpc_url, None);
-		assert_eq!(config.target_a_parentchain_rpc_port, None);
-		assert_eq!(config.target_b_parentchain_rpc_url, None);
-		assert_eq!(config.target_b_parentchain_rpc_port, None);
+		assert!(config.target_parentchain_rpc_urls.is_empty());
+		assert!(config.target_parentchain_rpc_ports.is_empty());
 		assert_eq!(config.trusted_worker_port, DEFAULT_TRUSTED_PORT);
 		assert_eq!(config.untrusted_worker_port, DEFAULT_UNTRUSTED_PORT);
 		assert_eq!(config.mu_ra_port, DEFAULT_MU_RA_PORT);
@@ -473,6 +516,8 @@ mod test {
 		assert_eq!(run_config.skip_ra, false);
 		assert!(run_config.shard.is_none());
 		assert!(run_config.teeracle_update_interval.is_none());
+		assert_eq!(run_config.slot_claim_config().weighted_from, None);
+		assert_eq!(run_config.slot_claim_config().fallback_authors_from, None);
 	}
 
 	#[test]
@@ -485,10 +530,12 @@ mod test {
 			("skip-ra", Default::default()),
 			("shard", Default::default()),
 			("teeracle-interval", Default::default()),
+			("fallback-authors-from", Default::default()),
 		]);
 		// Workaround because MatchedArg is private.
 		args.args.get_mut("shard").unwrap().vals = vec![shard_identifier.into()];
 		args.args.get_mut("teeracle-interval").unwrap().vals = vec!["42s".into()];
+		args.args.get_mut("fallback-authors-from").unwrap().vals = vec!["1000".into()];
 
 		let run_config = RunConfig::from(&args);
 
@@ -496,6 +543,14 @@ mod test {
 		assert_eq!(run_config.skip_ra, true);
 		assert_eq!(run_config.shard.unwrap(), shard_identifier.to_string());
 		assert_eq!(run_config.teeracle_update_interval.unwrap(), Duration::from_secs(42));
+		assert_eq!(
+			run_config.slot_claim_config(),
+			SlotClaimConfig {
+				weighted_from: None,
+				fallback_authors_from: Some(1000),
+				fallback_delay: DEFAULT_SLOT_FALLBACK_DELAY,
+			}
+		);
 	}
 
 	#[test]
@@ -604,4 +659,26 @@ mod test {
 
 		assert_eq!(resulting_url, format!("{}:{}", url, port));
 	}
+
+	#[test]
+	fn parse_parentchain_id_works() {
+		assert_eq!(parse_parentchain_id("integritee"), Some(ParentchainId::Integritee));
+		assert_eq!(parse_parentchain_id("target_a"), Some(ParentchainId::Target(0)));
+		assert_eq!(parse_parentchain_id("target_b"), Some(ParentchainId::Target(1)));
+		assert_eq!(parse_parentchain_id("target_7"), Some(ParentchainId::Target(7)));
+		assert_eq!(parse_parentchain_id("target_"), None);
+		assert_eq!(parse_parentchain_id("foo"), None);
+	}
+
+	#[test]
+	fn target_parentchain_rpc_endpoints_are_indexed_in_order() {
+		let mut config = Config::from(&ArgMatches::default());
+		config.target_parentchain_rpc_urls = vec!["ws://a".into(), "ws://b".into()];
+		config.target_parentchain_rpc_ports = vec!["9966".into(), "9988".into()];
+
+		assert_eq!(
+			config.target_parentchain_rpc_endpoints(),
+			vec![(0, "ws://a:9966".to_string()), (1, "ws://b:9988".to_string())]
+		);
+	}
 }
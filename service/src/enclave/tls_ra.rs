@@ -80,26 +80,31 @@ pub fn enclave_request_state_provisioning<E: TlsRemoteAttestation + RemoteAttest
 	info!("[MU-RA-Client] Requesting key provisioning from {}", addr);
 
 	let stream = TcpStream::connect(addr).map_err(|e| Error::Other(Box::new(e)))?;
+	let (quoting_enclave_target_info, quote_size) = quoting_enclave_info(enclave_api, skip_ra)?;
 
-	let quoting_enclave_target_info = if !skip_ra {
-		match enclave_api.qe_get_target_info() {
-			Ok(quote_size) => Some(quote_size),
-			Err(e) => return Err(e),
-		}
-	} else {
-		None
-	};
+	enclave_api.request_state_provisioning(
+		stream.as_raw_fd(),
+		sign_type,
+		quoting_enclave_target_info.as_ref(),
+		quote_size.as_ref(),
+		shard,
+		skip_ra,
+	)
+}
 
-	let quote_size = if !skip_ra {
-		match enclave_api.qe_get_quote_size() {
-			Ok(quote_size) => Some(quote_size),
-			Err(e) => return Err(e),
-		}
-	} else {
-		None
-	};
+pub fn enclave_request_rotated_keys<E: TlsRemoteAttestation + RemoteAttestation>(
+	enclave_api: &E,
+	sign_type: sgx_quote_sign_type_t,
+	addr: &str,
+	shard: &ShardIdentifier,
+	skip_ra: bool,
+) -> EnclaveResult<()> {
+	info!("[MU-RA-Client] Requesting rotated keys from {}", addr);
 
-	enclave_api.request_state_provisioning(
+	let stream = TcpStream::connect(addr).map_err(|e| Error::Other(Box::new(e)))?;
+	let (quoting_enclave_target_info, quote_size) = quoting_enclave_info(enclave_api, skip_ra)?;
+
+	enclave_api.request_rotated_keys(
 		stream.as_raw_fd(),
 		sign_type,
 		quoting_enclave_target_info.as_ref(),
@@ -108,3 +113,35 @@ pub fn enclave_request_state_provisioning<E: TlsRemoteAttestation + RemoteAttest
 		skip_ra,
 	)
 }
+
+pub fn enclave_request_shard_provisioning<E: TlsRemoteAttestation + RemoteAttestation>(
+	enclave_api: &E,
+	sign_type: sgx_quote_sign_type_t,
+	addr: &str,
+	shard: &ShardIdentifier,
+	skip_ra: bool,
+) -> EnclaveResult<()> {
+	info!("[MU-RA-Client] Requesting state of shard {:?} from {}", shard, addr);
+
+	let stream = TcpStream::connect(addr).map_err(|e| Error::Other(Box::new(e)))?;
+	let (quoting_enclave_target_info, quote_size) = quoting_enclave_info(enclave_api, skip_ra)?;
+
+	enclave_api.request_shard_provisioning(
+		stream.as_raw_fd(),
+		sign_type,
+		quoting_enclave_target_info.as_ref(),
+		quote_size.as_ref(),
+		shard,
+		skip_ra,
+	)
+}
+
+fn quoting_enclave_info<E: RemoteAttestation>(
+	enclave_api: &E,
+	skip_ra: bool,
+) -> EnclaveResult<(Option<sgx_target_info_t>, Option<u32>)> {
+	if skip_ra {
+		return Ok((None, None))
+	}
+	Ok((Some(enclave_api.qe_get_target_info()?), Some(enclave_api.qe_get_quote_size()?)))
+}
@@ -56,6 +56,8 @@ pub enum Error {
 	MissingLastFinalizedBlock,
 	#[error("Could not find block in parentchain")]
 	UnknownBlockHeader(Hash),
+	#[error("Sidechain storage error: {0}")]
+	SidechainStorage(#[from] its_storage::Error),
 	#[error("Enclave has not enough funds to send extrinsic")]
 	LowEnclaveBalance,
 	#[error("{0}")]
@@ -16,21 +16,26 @@
 */
 
 //! Service to determine if the integritee services is initialized and registered on the node,
-//! hosted on a http server.
+//! hosted on a http server. The same server accepts shard provisioning requests of the operator.
 
-use crate::error::ServiceResult;
+use crate::{
+	error::ServiceResult,
+	shard_provisioning::{provision_shard_route, ProvisionShard},
+};
 use itp_settings::worker_mode::{ProvideWorkerMode, WorkerMode};
 use log::*;
 use parking_lot::RwLock;
 use std::{default::Default, marker::PhantomData, net::SocketAddr, sync::Arc};
 use warp::Filter;
 
-pub async fn start_is_initialized_server<Handler>(
+pub async fn start_is_initialized_server<Handler, Provisioner>(
 	initialization_handler: Arc<Handler>,
+	shard_provisioner: Arc<Provisioner>,
 	port: u16,
 ) -> ServiceResult<()>
 where
 	Handler: IsInitialized + Send + Sync + 'static,
+	Provisioner: ProvisionShard + Send + Sync + 'static,
 {
 	let is_initialized_route = warp::path!("is_initialized").and_then(move || {
 		let handler_clone = initialization_handler.clone();
@@ -45,8 +50,10 @@ where
 
 	let socket_addr: SocketAddr = ([0, 0, 0, 0], port).into();
 
+	let routes = is_initialized_route.or(provision_shard_route(shard_provisioner));
+
 	info!("Running initialized server on: {:?}", socket_addr);
-	warp::serve(is_initialized_route).run(socket_addr).await;
+	warp::serve(routes).run(socket_addr).await;
 
 	info!("Initialized server shut down");
 	Ok(())
@@ -28,6 +28,8 @@ mod ocall_bridge;
 mod parentchain_handler;
 mod prometheus_metrics;
 mod setup;
+mod shard_provisioning;
+mod sidechain_archive;
 mod sidechain_setup;
 mod sync_block_broadcaster;
 mod sync_state;
@@ -20,7 +20,7 @@ use crate::{
 	},
 	parentchain_handler::{HandleParentchain, ParentchainHandler},
 	prometheus_metrics::{start_metrics_server, EnclaveMetricsReceiver, MetricsHandler},
-	setup,
+	setup, shard_provisioning::ShardProvisioner, sidechain_archive,
 	sidechain_setup::{sidechain_init_block_production, sidechain_start_untrusted_rpc_server},
 	sync_block_broadcaster::SyncBlockBroadcaster,
 	sync_state, tests,
@@ -48,7 +48,10 @@ use its_peer_fetch::{
 	block_fetch_client::BlockFetcher, untrusted_peer_fetch::UntrustedPeerFetcher,
 };
 use its_primitives::types::block::SignedBlock as SignedSidechainBlock;
-use its_storage::{interface::FetchBlocks, BlockPruner, SidechainStorageLock};
+use its_storage::{
+	interface::{FetchBlocks, QueryBlocks},
+	BlockPruner, SidechainStorageLock,
+};
 use log::*;
 use regex::Regex;
 use sgx_types::*;
@@ -73,13 +76,14 @@ use crate::{
 };
 use enclave_bridge_primitives::ShardIdentifier;
 use itc_parentchain::primitives::ParentchainId;
-use itp_types::parentchain::{AccountId, Balance};
+use itp_types::parentchain::{AccountId, Balance, TargetParentchainIndex};
 use sp_core::crypto::{AccountId32, Ss58Codec};
 use sp_keyring::AccountKeyring;
 use sp_runtime::MultiSigner;
 use std::{
+	collections::BTreeMap,
 	fmt::Debug,
-	path::PathBuf,
+	path::{Path, PathBuf},
 	str,
 	str::Utf8Error,
 	sync::{
@@ -161,19 +165,18 @@ pub(crate) fn main() {
 		Arc::new(BlockFetcher::<SignedSidechainBlock, _>::new(untrusted_peer_fetcher));
 	let enclave_metrics_receiver = Arc::new(EnclaveMetricsReceiver {});
 
-	let maybe_target_a_parentchain_api_factory = config
-		.target_a_parentchain_rpc_endpoint()
-		.map(|url| Arc::new(NodeApiFactory::new(url, AccountKeyring::Alice.pair())));
-
-	let maybe_target_b_parentchain_api_factory = config
-		.target_b_parentchain_rpc_endpoint()
-		.map(|url| Arc::new(NodeApiFactory::new(url, AccountKeyring::Alice.pair())));
+	let target_parentchain_api_factories = config
+		.target_parentchain_rpc_endpoints()
+		.into_iter()
+		.map(|(index, url)| {
+			(index, Arc::new(NodeApiFactory::new(url, AccountKeyring::Alice.pair())))
+		})
+		.collect();
 
 	// initialize o-call bridge with a concrete factory implementation
 	OCallBridge::initialize(Arc::new(OCallBridgeComponentFactory::new(
 		node_api_factory.clone(),
-		maybe_target_a_parentchain_api_factory,
-		maybe_target_b_parentchain_api_factory,
+		target_parentchain_api_factories,
 		sync_block_broadcaster,
 		enclave.clone(),
 		sidechain_blockstorage.clone(),
@@ -232,6 +235,18 @@ pub(crate) fn main() {
 			enclave.as_ref(),
 			smatches.is_present("skip-ra"),
 		);
+	} else if let Some(smatches) = matches.subcommand_matches("request-rotated-keys") {
+		println!("*** Requesting rotated keys from a fellow validateer \n");
+		let node_api =
+			node_api_factory.create_api().expect("Failed to create parentchain node API");
+		sync_state::sync_rotated_keys(
+			&node_api,
+			&extract_shard(smatches.value_of("shard"), enclave.as_ref()),
+			enclave.as_ref(),
+			smatches.value_of("provider"),
+			smatches.is_present("skip-ra"),
+		);
+		setup::generate_shielding_key_file(enclave.as_ref());
 	} else if matches.is_present("shielding-key") {
 		setup::generate_shielding_key_file(enclave.as_ref());
 	} else if matches.is_present("signing-key") {
@@ -255,6 +270,17 @@ pub(crate) fn main() {
 			enclave.as_ref(),
 			&extract_shard(sub_matches.value_of("shard"), enclave.as_ref()),
 		);
+	} else if let Some(sub_matches) = matches.subcommand_matches("export-sidechain-blocks") {
+		sidechain_archive::export_sidechain_blocks(
+			sidechain_blockstorage.as_ref(),
+			&extract_shard(sub_matches.value_of("shard"), enclave.as_ref()),
+			Path::new(sub_matches.value_of("file").unwrap()),
+		);
+	} else if let Some(sub_matches) = matches.subcommand_matches("import-sidechain-blocks") {
+		sidechain_archive::import_sidechain_blocks(
+			sidechain_blockstorage.as_ref(),
+			Path::new(sub_matches.value_of("file").unwrap()),
+		);
 	} else if let Some(sub_matches) = matches.subcommand_matches("test") {
 		if sub_matches.is_present("provisioning-server") {
 			println!("*** Running Enclave MU-RA TLS server\n");
@@ -302,12 +328,18 @@ fn start_worker<E, T, D, InitializationHandler, WorkerModeProvider>(
 ) where
 	T: GetTokioHandle,
 	E: EnclaveBase + Sidechain + RemoteAttestation + TlsRemoteAttestation + TeeracleApi + Clone,
-	D: BlockPruner + FetchBlocks<SignedSidechainBlock> + Sync + Send + 'static,
+	D: BlockPruner
+		+ FetchBlocks<SignedSidechainBlock>
+		+ QueryBlocks<SignedSidechainBlock>
+		+ Sync
+		+ Send
+		+ 'static,
 	InitializationHandler: TrackInitialization + IsInitialized + Sync + Send + 'static,
 	WorkerModeProvider: ProvideWorkerMode,
 {
 	let run_config = config.run_config().clone().expect("Run config missing");
 	let skip_ra = run_config.skip_ra();
+	let rotate_keys = run_config.rotate_keys();
 
 	#[cfg(feature = "teeracle")]
 	let flavor_str = "teeracle";
@@ -377,10 +409,15 @@ fn start_worker<E, T, D, InitializationHandler, WorkerModeProvider>(
 		.try_parse_untrusted_http_server_port()
 		.expect("untrusted http server port to be a valid port number");
 	let initialization_handler_clone = initialization_handler.clone();
+	let shard_provisioner =
+		Arc::new(ShardProvisioner::new(enclave.clone(), integritee_rpc_api.clone(), skip_ra));
 	tokio_handle.spawn(async move {
-		if let Err(e) =
-			start_is_initialized_server(initialization_handler_clone, untrusted_http_server_port)
-				.await
+		if let Err(e) = start_is_initialized_server(
+			initialization_handler_clone,
+			shard_provisioner,
+			untrusted_http_server_port,
+		)
+		.await
 		{
 			error!("Unexpected error in `is_initialized` server: {:?}", e);
 		}
@@ -444,6 +481,14 @@ fn start_worker<E, T, D, InitializationHandler, WorkerModeProvider>(
 		run_config.marblerun_base_url().to_string(),
 	);
 
+	// ------------------------------------------------------------------------
+	// Start a key rotation. The new keys are activated at the sidechain block at which the
+	// enclave account of the shard is rotated, on all validateers at the same time.
+	if rotate_keys {
+		println!("[+] Starting a rotation of the shielding key and the state key");
+		enclave.rotate_keys().unwrap();
+	}
+
 	// ------------------------------------------------------------------------
 	// Perform a remote attestation and get an unchecked extrinsic back.
 
@@ -650,44 +695,26 @@ fn start_worker<E, T, D, InitializationHandler, WorkerModeProvider>(
 		},
 	}
 
-	let maybe_target_a_rpc_api = if let Some(url) = config.target_a_parentchain_rpc_endpoint() {
-		let (api, mut handles) = init_target_parentchain(
-			&enclave,
-			&tee_accountid,
-			url,
-			shard,
-			ParentchainId::TargetA,
-			is_development_mode,
-			shutdown_flag.clone(),
-		);
-		sensitive_threads.append(&mut handles);
-		Some(api)
-	} else {
-		None
-	};
-
-	let maybe_target_b_rpc_api = if let Some(url) = config.target_b_parentchain_rpc_endpoint() {
+	let mut target_rpc_apis = BTreeMap::new();
+	for (index, url) in config.target_parentchain_rpc_endpoints() {
 		let (api, mut handles) = init_target_parentchain(
 			&enclave,
 			&tee_accountid,
 			url,
 			shard,
-			ParentchainId::TargetB,
+			ParentchainId::Target(index),
 			is_development_mode,
 			shutdown_flag.clone(),
 		);
 		sensitive_threads.append(&mut handles);
-		Some(api)
-	} else {
-		None
-	};
+		target_rpc_apis.insert(index, api);
+	}
 
 	init_provided_shard_vault(
 		shard,
 		&enclave,
 		integritee_rpc_api.clone(),
-		maybe_target_a_rpc_api.clone(),
-		maybe_target_b_rpc_api.clone(),
+		&target_rpc_apis,
 		run_config.shielding_target,
 		we_are_primary_validateer,
 	);
@@ -703,8 +730,7 @@ fn start_worker<E, T, D, InitializationHandler, WorkerModeProvider>(
 			&tee_accountid,
 			shard,
 			integritee_rpc_api.clone(),
-			maybe_target_a_rpc_api.clone(),
-			maybe_target_b_rpc_api.clone(),
+			target_rpc_apis.clone(),
 			run_config.shielding_target,
 			&tokio_handle,
 			metrics_server_port,
@@ -716,6 +742,7 @@ fn start_worker<E, T, D, InitializationHandler, WorkerModeProvider>(
 		let mut handles = sidechain_init_block_production(
 			enclave.clone(),
 			sidechain_storage,
+			run_config.slot_claim_config(),
 			shutdown_flag.clone(),
 		)
 		.unwrap();
@@ -742,18 +769,19 @@ fn init_provided_shard_vault<E: EnclaveBase>(
 	shard: &ShardIdentifier,
 	enclave: &Arc<E>,
 	integritee_rpc_api: ParentchainApi,
-	maybe_target_a_rpc_api: Option<ParentchainApi>,
-	maybe_target_b_rpc_api: Option<ParentchainApi>,
+	target_rpc_apis: &BTreeMap<TargetParentchainIndex, ParentchainApi>,
 	shielding_target: Option<ParentchainId>,
 	we_are_primary_validateer: bool,
 ) {
 	let shielding_target = shielding_target.unwrap_or_default();
 	let rpc_api = match shielding_target {
 		ParentchainId::Integritee => integritee_rpc_api,
-		ParentchainId::TargetA => maybe_target_a_rpc_api
-			.expect("target A must be initialized to be used as shielding target"),
-		ParentchainId::TargetB => maybe_target_b_rpc_api
-			.expect("target B must be initialized to be used as shielding target"),
+		ParentchainId::Target(index) => target_rpc_apis
+			.get(&index)
+			.cloned()
+			.unwrap_or_else(|| {
+				panic!("target {} must be initialized to be used as shielding target", index)
+			}),
 	};
 	let funding_balance = shard_vault_initial_funds(&rpc_api).unwrap();
 	if let Ok(shard_vault) = enclave.get_ecc_vault_pubkey(shard) {
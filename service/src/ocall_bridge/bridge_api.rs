@@ -17,6 +17,7 @@
 */
 
 use itp_enclave_api::remote_attestation::QveReport;
+use itp_types::parentchain::TargetParentchainIndex;
 use lazy_static::lazy_static;
 use log::*;
 use parking_lot::RwLock;
@@ -129,6 +130,10 @@ pub enum OCallBridgeError {
 	UpdateMetric(String),
 	#[error("Propose sidechain block failed: {0}")]
 	ProposeSidechainBlock(String),
+	#[error("Propose finality votes failed: {0}")]
+	ProposeFinalityVotes(String),
+	#[error("Failed to fetch finalized sidechain head: {0}")]
+	FetchSidechainFinalizedHead(String),
 	#[error("Failed to fetch sidechain blocks from peer: {0}")]
 	FetchSidechainBlocksFromPeer(String),
 	#[error("Sending extrinsics to parentchain failed: {0}")]
@@ -141,10 +146,8 @@ pub enum OCallBridgeError {
 	Codec(#[from] codec::Error),
 	#[error("Node API factory error: {0}")]
 	NodeApiFactory(#[from] itp_node_api::node_api_factory::NodeApiFactoryError),
-	#[error("Target A parentchain not initialized")]
-	TargetAParentchainNotInitialized,
-	#[error("Target B parentchain not initialized")]
-	TargetBParentchainNotInitialized,
+	#[error("Target parentchain {0} not initialized")]
+	TargetParentchainNotInitialized(TargetParentchainIndex),
 	#[error("An error with the substrate node api")]
 	NodeApiError,
 }
@@ -184,6 +187,9 @@ pub trait RemoteAttestationBridge {
 	/// retrieve the quote from dcap server
 	fn get_dcap_quote(&self, report: sgx_report_t, quote_size: u32) -> OCallBridgeResult<Vec<u8>>;
 
+	/// retrieve the encoded quote verification collateral from the PCCS
+	fn get_dcap_collateral(&self, fmspc: [u8; 6]) -> OCallBridgeResult<Vec<u8>>;
+
 	// Retrieve verification of quote
 	fn get_qve_report_on_quote(
 		&self,
@@ -232,6 +238,18 @@ pub trait SidechainBridge {
 
 	fn store_sidechain_blocks(&self, signed_blocks_encoded: Vec<u8>) -> OCallBridgeResult<()>;
 
+	fn propose_finality_votes(&self, votes_encoded: Vec<u8>) -> OCallBridgeResult<()>;
+
+	fn store_sidechain_justifications(
+		&self,
+		justifications_encoded: Vec<u8>,
+	) -> OCallBridgeResult<()>;
+
+	fn fetch_sidechain_finalized_head(
+		&self,
+		shard_identifier_encoded: Vec<u8>,
+	) -> OCallBridgeResult<Vec<u8>>;
+
 	fn fetch_sidechain_blocks_from_peer(
 		&self,
 		last_imported_block_hash_encoded: Vec<u8>,
@@ -34,10 +34,11 @@ use crate::{
 };
 use itp_enclave_api::remote_attestation::RemoteAttestationCallBacks;
 use itp_node_api::node_api_factory::CreateNodeApi;
+use itp_types::parentchain::TargetParentchainIndex;
 use its_peer_fetch::FetchBlocksFromPeer;
 use its_primitives::types::block::SignedBlock as SignedSidechainBlock;
-use its_storage::BlockStorage;
-use std::{path::Path, sync::Arc};
+use its_storage::{BlockStorage, JustificationStorage};
+use std::{collections::BTreeMap, path::Path, sync::Arc};
 
 /// Concrete implementation, should be moved out of the OCall Bridge, into the worker
 /// since the OCall bridge itself should not know any concrete types to ensure
@@ -53,8 +54,7 @@ pub struct OCallBridgeComponentFactory<
 	MetricsReceiver,
 > {
 	integritee_rpc_api_factory: Arc<NodeApi>,
-	target_a_parentchain_rpc_api_factory: Option<Arc<NodeApi>>,
-	target_b_parentchain_rpc_api_factory: Option<Arc<NodeApi>>,
+	target_parentchain_rpc_api_factories: BTreeMap<TargetParentchainIndex, Arc<NodeApi>>,
 	block_broadcaster: Arc<Broadcaster>,
 	enclave_api: Arc<EnclaveApi>,
 	block_storage: Arc<Storage>,
@@ -89,8 +89,7 @@ impl<
 	#[allow(clippy::too_many_arguments)]
 	pub fn new(
 		integritee_rpc_api_factory: Arc<NodeApi>,
-		target_a_parentchain_rpc_api_factory: Option<Arc<NodeApi>>,
-		target_b_parentchain_rpc_api_factory: Option<Arc<NodeApi>>,
+		target_parentchain_rpc_api_factories: BTreeMap<TargetParentchainIndex, Arc<NodeApi>>,
 		block_broadcaster: Arc<Broadcaster>,
 		enclave_api: Arc<EnclaveApi>,
 		block_storage: Arc<Storage>,
@@ -102,8 +101,7 @@ impl<
 	) -> Self {
 		OCallBridgeComponentFactory {
 			integritee_rpc_api_factory,
-			target_a_parentchain_rpc_api_factory,
-			target_b_parentchain_rpc_api_factory,
+			target_parentchain_rpc_api_factories,
 			block_broadcaster,
 			enclave_api,
 			block_storage,
@@ -139,7 +137,7 @@ impl<
 	NodeApi: CreateNodeApi + 'static,
 	Broadcaster: BroadcastBlocks + 'static,
 	EnclaveApi: RemoteAttestationCallBacks + 'static,
-	Storage: BlockStorage<SignedSidechainBlock> + 'static,
+	Storage: BlockStorage<SignedSidechainBlock> + JustificationStorage + 'static,
 	PeerUpdater: UpdateWorkerPeers + 'static,
 	PeerBlockFetcher: FetchBlocksFromPeer<SignedBlockType = SignedSidechainBlock> + 'static,
 	TokioHandle: GetTokioHandle + 'static,
@@ -162,8 +160,7 @@ impl<
 	fn get_oc_api(&self) -> Arc<dyn WorkerOnChainBridge> {
 		Arc::new(WorkerOnChainOCall::new(
 			self.integritee_rpc_api_factory.clone(),
-			self.target_a_parentchain_rpc_api_factory.clone(),
-			self.target_b_parentchain_rpc_api_factory.clone(),
+			self.target_parentchain_rpc_api_factories.clone(),
 			self.log_dir.clone(),
 		))
 	}
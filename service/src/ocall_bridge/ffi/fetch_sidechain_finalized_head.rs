@@ -0,0 +1,100 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+use crate::ocall_bridge::bridge_api::{Bridge, SidechainBridge};
+use itp_utils::write_slice_and_whitespace_pad;
+use log::*;
+use sgx_types::sgx_status_t;
+use std::{slice, sync::Arc};
+
+/// # Safety
+///
+/// FFI are always unsafe
+#[no_mangle]
+pub unsafe extern "C" fn ocall_fetch_sidechain_finalized_head(
+	shard_identifier_ptr: *const u8,
+	shard_identifier_size: u32,
+	justification_ptr: *mut u8,
+	justification_size: u32,
+) -> sgx_status_t {
+	fetch_sidechain_finalized_head(
+		shard_identifier_ptr,
+		shard_identifier_size,
+		justification_ptr,
+		justification_size,
+		Bridge::get_sidechain_api(),
+	)
+}
+
+fn fetch_sidechain_finalized_head(
+	shard_identifier_ptr: *const u8,
+	shard_identifier_size: u32,
+	justification_ptr: *mut u8,
+	justification_size: u32,
+	sidechain_api: Arc<dyn SidechainBridge>,
+) -> sgx_status_t {
+	let shard_identifier_encoded = unsafe {
+		Vec::from(slice::from_raw_parts(shard_identifier_ptr, shard_identifier_size as usize))
+	};
+
+	let justification_encoded =
+		match sidechain_api.fetch_sidechain_finalized_head(shard_identifier_encoded) {
+			Ok(r) => r,
+			Err(e) => {
+				error!("fetch finalized sidechain head failed: {:?}", e);
+				return sgx_status_t::SGX_ERROR_UNEXPECTED
+			},
+		};
+
+	let justification_encoded_slice =
+		unsafe { slice::from_raw_parts_mut(justification_ptr, justification_size as usize) };
+	if let Err(e) =
+		write_slice_and_whitespace_pad(justification_encoded_slice, justification_encoded)
+	{
+		error!("Failed to transfer encoded justification to o-call buffer: {:?}", e);
+		return sgx_status_t::SGX_ERROR_UNEXPECTED
+	}
+
+	sgx_status_t::SGX_SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::ocall_bridge::test::mocks::sidechain_bridge_mock::SidechainBridgeMock;
+	use codec::{Decode, Encode};
+	use its_primitives::types::justification::Justification;
+	use primitive_types::H256;
+
+	#[test]
+	fn fetch_sidechain_finalized_head_works() {
+		let shard_identifier_encoded = H256::random().encode();
+		let mut buffer = vec![0; 1024];
+
+		let result = fetch_sidechain_finalized_head(
+			shard_identifier_encoded.as_ptr(),
+			shard_identifier_encoded.len() as u32,
+			buffer.as_mut_ptr(),
+			buffer.len() as u32,
+			Arc::new(SidechainBridgeMock::default()),
+		);
+
+		let decoded: Option<Justification> = Decode::decode(&mut buffer.as_slice()).unwrap();
+		assert_eq!(result, sgx_status_t::SGX_SUCCESS);
+		assert_eq!(decoded, None);
+	}
+}
@@ -0,0 +1,155 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+use crate::ocall_bridge::bridge_api::{Bridge, RemoteAttestationBridge};
+use log::*;
+use sgx_types::sgx_status_t;
+use std::{convert::TryInto, slice, sync::Arc};
+
+/// p_collateral must be a pre-allocated memory region of size `collateral_max_size`
+#[no_mangle]
+pub unsafe extern "C" fn ocall_get_dcap_collateral(
+	p_fmspc: *const u8,
+	fmspc_size: u32,
+	p_collateral: *mut u8,
+	collateral_max_size: u32,
+	p_collateral_size: *mut u32,
+) -> sgx_status_t {
+	get_dcap_collateral(
+		p_fmspc,
+		fmspc_size,
+		p_collateral,
+		collateral_max_size,
+		p_collateral_size,
+		Bridge::get_ra_api(), // inject the RA API (global state)
+	)
+}
+
+fn get_dcap_collateral(
+	p_fmspc: *const u8,
+	fmspc_size: u32,
+	p_collateral: *mut u8,
+	collateral_max_size: u32,
+	p_collateral_size: *mut u32,
+	ra_api: Arc<dyn RemoteAttestationBridge>,
+) -> sgx_status_t {
+	debug!("    Entering ocall_get_dcap_collateral");
+
+	let fmspc: [u8; 6] =
+		match unsafe { slice::from_raw_parts(p_fmspc, fmspc_size as usize) }.try_into() {
+			Ok(f) => f,
+			Err(_) => return sgx_status_t::SGX_ERROR_INVALID_PARAMETER,
+		};
+
+	let collateral = match ra_api.get_dcap_collateral(fmspc) {
+		Ok(c) => c,
+		Err(e) => {
+			error!("[-]  Failed to get dcap collateral: {:?}", e);
+			return e.into()
+		},
+	};
+
+	if collateral.len() as u32 > collateral_max_size {
+		return sgx_status_t::SGX_ERROR_FAAS_BUFFER_TOO_SHORT
+	}
+
+	let collateral_slice = unsafe { slice::from_raw_parts_mut(p_collateral, collateral.len()) };
+	collateral_slice.clone_from_slice(collateral.as_slice());
+
+	unsafe {
+		*p_collateral_size = collateral.len() as u32;
+	}
+
+	sgx_status_t::SGX_SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::ocall_bridge::bridge_api::{MockRemoteAttestationBridge, OCallBridgeError};
+
+	const FMSPC: [u8; 6] = [0x00, 0x90, 0x6e, 0xd5, 0x00, 0x00];
+
+	#[test]
+	fn collateral_is_copied_to_buffer() {
+		let mut ra_ocall_api_mock = MockRemoteAttestationBridge::new();
+		ra_ocall_api_mock
+			.expect_get_dcap_collateral()
+			.withf(|fmspc| *fmspc == FMSPC)
+			.times(1)
+			.returning(|_| Ok(vec![1, 2, 3]));
+
+		let mut collateral = [0u8; 8];
+		let mut collateral_size = 0u32;
+		let ret_status = get_dcap_collateral(
+			FMSPC.as_ptr(),
+			FMSPC.len() as u32,
+			collateral.as_mut_ptr(),
+			collateral.len() as u32,
+			&mut collateral_size as *mut u32,
+			Arc::new(ra_ocall_api_mock),
+		);
+
+		assert_eq!(ret_status, sgx_status_t::SGX_SUCCESS);
+		assert_eq!(&collateral[..collateral_size as usize], &[1, 2, 3]);
+	}
+
+	#[test]
+	fn too_large_collateral_is_rejected() {
+		let mut ra_ocall_api_mock = MockRemoteAttestationBridge::new();
+		ra_ocall_api_mock
+			.expect_get_dcap_collateral()
+			.times(1)
+			.returning(|_| Ok(vec![0u8; 16]));
+
+		let mut collateral = [0u8; 8];
+		let mut collateral_size = 0u32;
+		let ret_status = get_dcap_collateral(
+			FMSPC.as_ptr(),
+			FMSPC.len() as u32,
+			collateral.as_mut_ptr(),
+			collateral.len() as u32,
+			&mut collateral_size as *mut u32,
+			Arc::new(ra_ocall_api_mock),
+		);
+
+		assert_eq!(ret_status, sgx_status_t::SGX_ERROR_FAAS_BUFFER_TOO_SHORT);
+		assert_eq!(collateral_size, 0);
+	}
+
+	#[test]
+	fn given_error_from_ocall_impl_then_return_sgx_error() {
+		let mut ra_ocall_api_mock = MockRemoteAttestationBridge::new();
+		ra_ocall_api_mock
+			.expect_get_dcap_collateral()
+			.times(1)
+			.returning(|_| Err(OCallBridgeError::GetQuote(sgx_status_t::SGX_ERROR_BUSY)));
+
+		let mut collateral = [0u8; 8];
+		let mut collateral_size = 0u32;
+		let ret_status = get_dcap_collateral(
+			FMSPC.as_ptr(),
+			FMSPC.len() as u32,
+			collateral.as_mut_ptr(),
+			collateral.len() as u32,
+			&mut collateral_size as *mut u32,
+			Arc::new(ra_ocall_api_mock),
+		);
+
+		assert_eq!(ret_status, sgx_status_t::SGX_ERROR_BUSY);
+	}
+}
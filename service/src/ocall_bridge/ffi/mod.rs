@@ -22,14 +22,18 @@
 //! actual implementation of the OCalls (using the traits defined in the bridge_api).
 
 pub mod fetch_sidechain_blocks_from_peer;
+pub mod fetch_sidechain_finalized_head;
+pub mod get_dcap_collateral;
 pub mod get_ias_socket;
 pub mod get_quote;
 pub mod get_qve_report_on_quote;
 pub mod get_update_info;
 pub mod init_quote;
 pub mod ipfs;
+pub mod propose_finality_votes;
 pub mod propose_sidechain_blocks;
 pub mod send_to_parentchain;
 pub mod store_sidechain_blocks;
+pub mod store_sidechain_justifications;
 pub mod update_metrics;
 pub mod worker_request;
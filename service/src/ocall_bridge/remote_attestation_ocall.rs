@@ -90,6 +90,13 @@ where
 		})
 	}
 
+	fn get_dcap_collateral(&self, fmspc: [u8; 6]) -> OCallBridgeResult<Vec<u8>> {
+		self.enclave_api.get_encoded_dcap_collateral(fmspc).map_err(|e| match e {
+			itp_enclave_api::error::Error::Sgx(s) => OCallBridgeError::GetQuote(s),
+			_ => OCallBridgeError::GetQuote(sgx_status_t::SGX_ERROR_UNEXPECTED),
+		})
+	}
+
 	fn get_qve_report_on_quote(
 		&self,
 		quote: Vec<u8>,
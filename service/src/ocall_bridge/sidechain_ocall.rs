@@ -28,9 +28,12 @@ use itp_types::{BlockHash, ShardIdentifier};
 use its_peer_fetch::FetchBlocksFromPeer;
 use its_primitives::{
 	traits::{Block, Header},
-	types::SignedBlock as SignedSidechainBlock,
+	types::{
+		justification::{Justification, SignedFinalityVote},
+		SignedBlock as SignedSidechainBlock,
+	},
 };
-use its_storage::BlockStorage;
+use its_storage::{BlockStorage, JustificationStorage};
 use log::*;
 use std::{collections::HashSet, sync::Arc};
 
@@ -62,11 +65,35 @@ impl<BlockBroadcaster, Storage, PeerUpdater, PeerBlockFetcher, TokioHandle>
 	}
 }
 
+impl<BlockBroadcaster, Storage, PeerUpdater, PeerBlockFetcher, TokioHandle>
+	SidechainOCall<BlockBroadcaster, Storage, PeerUpdater, PeerBlockFetcher, TokioHandle>
+where
+	PeerUpdater: UpdateWorkerPeers,
+{
+	// FIXME: When & where should peers be updated?
+	fn update_peers(&self, shard: ShardIdentifier) {
+		trace!("Updating peers..");
+		let peer_count = self
+			.peer_updater
+			.update_peers(shard)
+			.map(|peer_count| {
+				debug!("successfully updated {} peers", peer_count);
+				peer_count
+			})
+			.map_err(|e| {
+				error!("Error updating peers: {:?}", e);
+				e
+			})
+			.unwrap_or_default();
+		set_sidechain_peer_count_metric(peer_count);
+	}
+}
+
 impl<BlockBroadcaster, Storage, PeerUpdater, PeerBlockFetcher, TokioHandle> SidechainBridge
 	for SidechainOCall<BlockBroadcaster, Storage, PeerUpdater, PeerBlockFetcher, TokioHandle>
 where
 	BlockBroadcaster: BroadcastBlocks,
-	Storage: BlockStorage<SignedSidechainBlock>,
+	Storage: BlockStorage<SignedSidechainBlock> + JustificationStorage,
 	PeerUpdater: UpdateWorkerPeers,
 	PeerBlockFetcher: FetchBlocksFromPeer<SignedBlockType = SignedSidechainBlock>,
 	TokioHandle: GetTokioHandle,
@@ -112,21 +139,7 @@ where
 		}
 		let shard = shards[0];
 
-		// FIXME: When & where should peers be updated?
-		trace!("Updating peers..");
-		let peer_count = self
-			.peer_updater
-			.update_peers(shard)
-			.map(|peer_count| {
-				debug!("successfully updated {} peers", peer_count);
-				peer_count
-			})
-			.map_err(|e| {
-				error!("Error updating peers: {:?}", e);
-				e
-			})
-			.unwrap_or_default();
-		set_sidechain_peer_count_metric(peer_count);
+		self.update_peers(shard);
 
 		trace!("Broadcasting sidechain blocks ...");
 		self.block_broadcaster
@@ -163,6 +176,60 @@ where
 		status
 	}
 
+	fn propose_finality_votes(&self, votes_encoded: Vec<u8>) -> OCallBridgeResult<()> {
+		let votes: Vec<SignedFinalityVote> = Decode::decode(&mut votes_encoded.as_slice())
+			.map_err(|_| {
+				OCallBridgeError::ProposeFinalityVotes(
+					"Could not decode finality votes".to_string(),
+				)
+			})?;
+
+		let shard = match votes.first() {
+			Some(vote) => vote.vote.shard,
+			None => return Ok(()),
+		};
+		self.update_peers(shard);
+
+		trace!("Broadcasting {} finality vote(s) ...", votes.len());
+		if let Err(e) = self.block_broadcaster.broadcast_finality_votes(votes) {
+			error!("Error broadcasting finality votes: {:?}", e);
+		}
+		Ok(())
+	}
+
+	fn store_sidechain_justifications(
+		&self,
+		justifications_encoded: Vec<u8>,
+	) -> OCallBridgeResult<()> {
+		let justifications: Vec<Justification> =
+			Decode::decode(&mut justifications_encoded.as_slice())?;
+
+		if let Err(e) = self.block_storage.store_justifications(justifications) {
+			error!("Error storing justifications: {:?}", e);
+		}
+		Ok(())
+	}
+
+	fn fetch_sidechain_finalized_head(
+		&self,
+		shard_identifier_encoded: Vec<u8>,
+	) -> OCallBridgeResult<Vec<u8>> {
+		let shard_identifier: ShardIdentifier =
+			Decode::decode(&mut shard_identifier_encoded.as_slice()).map_err(|_| {
+				OCallBridgeError::FetchSidechainFinalizedHead(
+					"Failed to decode shard identifier".to_string(),
+				)
+			})?;
+
+		let justification = self.block_storage.finalized_head(&shard_identifier).map_err(|e| {
+			OCallBridgeError::FetchSidechainFinalizedHead(format!(
+				"Failed to read finalized head from storage: {:?}",
+				e
+			))
+		})?;
+		Ok(justification.encode())
+	}
+
 	fn fetch_sidechain_blocks_from_peer(
 		&self,
 		last_imported_block_hash_encoded: Vec<u8>,
@@ -225,8 +292,13 @@ mod tests {
 	};
 	use codec::Decode;
 	use its_peer_fetch::mocks::fetch_blocks_from_peer_mock::FetchBlocksFromPeerMock;
-	use its_primitives::types::block::SignedBlock as SignedSidechainBlock;
-	use its_storage::{interface::BlockStorage, Result as StorageResult};
+	use its_primitives::types::{
+		block::SignedBlock as SignedSidechainBlock, justification::Justification,
+	};
+	use its_storage::{
+		interface::{BlockStorage, JustificationStorage},
+		Result as StorageResult,
+	};
 	use its_test::sidechain_block_builder::{SidechainBlockBuilder, SidechainBlockBuilderTrait};
 	use primitive_types::H256;
 	use std::{collections::HashMap, vec::Vec};
@@ -237,6 +309,15 @@ mod tests {
 			Ok(())
 		}
 	}
+	impl JustificationStorage for BlockStorageMock {
+		fn store_justifications(&self, _justifications: Vec<Justification>) -> StorageResult<()> {
+			Ok(())
+		}
+
+		fn finalized_head(&self, _shard: &ShardIdentifier) -> StorageResult<Option<Justification>> {
+			Ok(None)
+		}
+	}
 
 	type TestSidechainOCall = SidechainOCall<
 		BroadcastBlocksMock,
@@ -17,6 +17,7 @@
 */
 
 use crate::ocall_bridge::bridge_api::{OCallBridgeResult, SidechainBridge};
+use codec::Encode;
 
 #[derive(Default)]
 pub struct SidechainBridgeMock {
@@ -39,6 +40,24 @@ impl SidechainBridge for SidechainBridgeMock {
 		Ok(())
 	}
 
+	fn propose_finality_votes(&self, _votes_encoded: Vec<u8>) -> OCallBridgeResult<()> {
+		Ok(())
+	}
+
+	fn store_sidechain_justifications(
+		&self,
+		_justifications_encoded: Vec<u8>,
+	) -> OCallBridgeResult<()> {
+		Ok(())
+	}
+
+	fn fetch_sidechain_finalized_head(
+		&self,
+		_shard_identifier_encoded: Vec<u8>,
+	) -> OCallBridgeResult<Vec<u8>> {
+		Ok(None::<()>.encode())
+	}
+
 	fn fetch_sidechain_blocks_from_peer(
 		&self,
 		_last_imported_block_hash_encoded: Vec<u8>,
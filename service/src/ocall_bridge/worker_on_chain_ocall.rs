@@ -20,15 +20,19 @@ use crate::ocall_bridge::bridge_api::{OCallBridgeError, OCallBridgeResult, Worke
 use chrono::Local;
 use codec::{Decode, Encode};
 use itp_api_client_types::ParentchainApi;
-use itp_node_api::{api_client::AccountApi, node_api_factory::CreateNodeApi};
+use itp_node_api::{
+	api_client::{AccountApi, ChainApi},
+	node_api_factory::CreateNodeApi,
+};
 use itp_types::{
-	parentchain::{Header as ParentchainHeader, ParentchainId},
+	parentchain::{Header as ParentchainHeader, ParentchainId, TargetParentchainIndex},
 	DigestItem, WorkerRequest, WorkerResponse,
 };
 use log::*;
 use sp_core::blake2_256;
 use sp_runtime::{Digest, OpaqueExtrinsic};
 use std::{
+	collections::BTreeMap,
 	fs::{create_dir_all, File},
 	io::{self, Write},
 	path::Path,
@@ -43,24 +47,17 @@ use substrate_api_client::{
 
 pub struct WorkerOnChainOCall<F> {
 	integritee_api_factory: Arc<F>,
-	target_a_parentchain_api_factory: Option<Arc<F>>,
-	target_b_parentchain_api_factory: Option<Arc<F>>,
+	target_parentchain_api_factories: BTreeMap<TargetParentchainIndex, Arc<F>>,
 	log_dir: Arc<Path>,
 }
 
 impl<F> WorkerOnChainOCall<F> {
 	pub fn new(
 		integritee_api_factory: Arc<F>,
-		target_a_parentchain_api_factory: Option<Arc<F>>,
-		target_b_parentchain_api_factory: Option<Arc<F>>,
+		target_parentchain_api_factories: BTreeMap<TargetParentchainIndex, Arc<F>>,
 		log_dir: Arc<Path>,
 	) -> Self {
-		WorkerOnChainOCall {
-			integritee_api_factory,
-			target_a_parentchain_api_factory,
-			target_b_parentchain_api_factory,
-			log_dir,
-		}
+		WorkerOnChainOCall { integritee_api_factory, target_parentchain_api_factories, log_dir }
 	}
 }
 
@@ -68,15 +65,10 @@ impl<F: CreateNodeApi> WorkerOnChainOCall<F> {
 	pub fn create_api(&self, parentchain_id: ParentchainId) -> OCallBridgeResult<ParentchainApi> {
 		Ok(match parentchain_id {
 			ParentchainId::Integritee => self.integritee_api_factory.create_api()?,
-			ParentchainId::TargetA => self
-				.target_a_parentchain_api_factory
-				.as_ref()
-				.ok_or(OCallBridgeError::TargetAParentchainNotInitialized)
-				.and_then(|f| f.create_api().map_err(Into::into))?,
-			ParentchainId::TargetB => self
-				.target_b_parentchain_api_factory
-				.as_ref()
-				.ok_or(OCallBridgeError::TargetBParentchainNotInitialized)
+			ParentchainId::Target(index) => self
+				.target_parentchain_api_factories
+				.get(&index)
+				.ok_or(OCallBridgeError::TargetParentchainNotInitialized(index))
 				.and_then(|f| f.create_api().map_err(Into::into))?,
 		})
 	}
@@ -133,6 +125,22 @@ where
 					let nonce = api.get_system_account_next_index(account).ok();
 					WorkerResponse::NextNonce(nonce)
 				},
+				WorkerRequest::ChainBlock(hash) => {
+					let block =
+						api.get_signed_block(Some(hash)).ok().flatten().and_then(|signed_block| {
+							let raw_events = api.get_events_for_block(Some(hash)).ok()?;
+							Some((signed_block.encode(), raw_events))
+						});
+					WorkerResponse::ChainBlock(block)
+				},
+				WorkerRequest::ChainBlockJustifications(hash) => {
+					let justifications = api
+						.get_signed_block(Some(hash))
+						.ok()
+						.flatten()
+						.and_then(|signed_block| signed_block.justifications);
+					WorkerResponse::ChainBlockJustifications(justifications)
+				},
 			})
 			.collect();
 
@@ -243,8 +251,11 @@ mod tests {
 
 		let mock_node_api_factory = Arc::new(MockNodeApiFactory::new());
 		let temp_dir = TempDir::new().unwrap();
-		let on_chain_ocall =
-			WorkerOnChainOCall::new(mock_node_api_factory, None, None, temp_dir.path().into());
+		let on_chain_ocall = WorkerOnChainOCall::new(
+			mock_node_api_factory,
+			Default::default(),
+			temp_dir.path().into(),
+		);
 
 		let response = on_chain_ocall
 			.worker_request(Vec::<u8>::new().encode(), ParentchainId::Integritee.encode())
@@ -43,7 +43,10 @@ use itc_rest_client::{
 use itp_api_client_types::ParentchainApi;
 use itp_enclave_api::{enclave_base::EnclaveBase, sidechain::Sidechain};
 use itp_enclave_metrics::EnclaveMetric;
-use itp_types::{parentchain::ParentchainId, EnclaveFingerprint};
+use itp_types::{
+	parentchain::{ParentchainId, TargetParentchainIndex},
+	EnclaveFingerprint,
+};
 use lazy_static::lazy_static;
 use log::*;
 use prometheus::{
@@ -54,7 +57,7 @@ use prometheus::{
 };
 use serde::{Deserialize, Serialize};
 use sp_core::crypto::AccountId32;
-use std::{fmt::Debug, net::SocketAddr, sync::Arc};
+use std::{collections::BTreeMap, fmt::Debug, net::SocketAddr, sync::Arc};
 use tokio::runtime::Handle;
 use warp::{Filter, Rejection, Reply};
 
@@ -355,8 +358,7 @@ pub fn start_prometheus_metrics_server<E>(
 	tee_account_id: &AccountId32,
 	shard: &ShardIdentifier,
 	integritee_rpc_api: ParentchainApi,
-	maybe_target_a_rpc_api: Option<ParentchainApi>,
-	maybe_target_b_rpc_api: Option<ParentchainApi>,
+	target_rpc_apis: BTreeMap<TargetParentchainIndex, ParentchainApi>,
 	shielding_target: Option<ParentchainId>,
 	tokio_handle: &Handle,
 	metrics_server_port: u16,
@@ -376,12 +378,10 @@ pub fn start_prometheus_metrics_server<E>(
 		shielding_target,
 		match shielding_target {
 			ParentchainId::Integritee => integritee_rpc_api.clone(),
-			ParentchainId::TargetA => maybe_target_a_rpc_api
-				.clone()
-				.expect("target A must be initialized to be used as shielding target"),
-			ParentchainId::TargetB => maybe_target_b_rpc_api
-				.clone()
-				.expect("target B must be initialized to be used as shielding target"),
+			ParentchainId::Target(index) =>
+				target_rpc_apis.get(&index).cloned().unwrap_or_else(|| {
+					panic!("target {} must be initialized to be used as shielding target", index)
+				}),
 		},
 		AccountAndRole::ShardVault(
 			enclave
@@ -390,20 +390,13 @@ pub fn start_prometheus_metrics_server<E>(
 				.into(),
 		),
 	)));
-	if let Some(api) = maybe_target_a_rpc_api {
-		account_info_providers.push(Arc::new(ParentchainAccountInfoProvider::new(
-			ParentchainId::TargetA,
-			api,
-			AccountAndRole::EnclaveSigner(tee_account_id.clone()),
-		)))
-	};
-	if let Some(api) = maybe_target_b_rpc_api {
+	for (index, api) in target_rpc_apis {
 		account_info_providers.push(Arc::new(ParentchainAccountInfoProvider::new(
-			ParentchainId::TargetB,
+			ParentchainId::Target(index),
 			api,
 			AccountAndRole::EnclaveSigner(tee_account_id.clone()),
 		)))
-	};
+	}
 	let sidechain_info_provider =
 		Arc::new(ParentchainIntegriteeSidechainInfoProvider::new(integritee_rpc_api, *shard));
 
@@ -20,6 +20,7 @@ use crate::error::{Error, ServiceResult};
 use itp_settings::files::{
 	INTEGRITEE_PARENTCHAIN_LIGHT_CLIENT_DB_PATH, SHARDS_PATH, SIDECHAIN_STORAGE_PATH,
 	TARGET_A_PARENTCHAIN_LIGHT_CLIENT_DB_PATH, TARGET_B_PARENTCHAIN_LIGHT_CLIENT_DB_PATH,
+	TARGET_PARENTCHAIN_LIGHT_CLIENT_DB_PATH_PREFIX,
 };
 use std::{fs, path::Path};
 
@@ -121,10 +122,24 @@ fn purge_files(root_directory: &Path) -> ServiceResult<()> {
 	remove_dir_if_it_exists(root_directory, INTEGRITEE_PARENTCHAIN_LIGHT_CLIENT_DB_PATH)?;
 	remove_dir_if_it_exists(root_directory, TARGET_A_PARENTCHAIN_LIGHT_CLIENT_DB_PATH)?;
 	remove_dir_if_it_exists(root_directory, TARGET_B_PARENTCHAIN_LIGHT_CLIENT_DB_PATH)?;
+	remove_dirs_with_prefix(root_directory, TARGET_PARENTCHAIN_LIGHT_CLIENT_DB_PATH_PREFIX)?;
 
 	Ok(())
 }
 
+fn remove_dirs_with_prefix(root_directory: &Path, prefix: &str) -> ServiceResult<()> {
+	if !root_directory.exists() {
+		return Ok(())
+	}
+	for entry in fs::read_dir(root_directory).map_err(|e| Error::Custom(e.into()))? {
+		let entry = entry.map_err(|e| Error::Custom(e.into()))?;
+		if entry.file_name().to_string_lossy().starts_with(prefix) && entry.path().is_dir() {
+			fs::remove_dir_all(entry.path()).map_err(|e| Error::Custom(e.into()))?;
+		}
+	}
+	Ok(())
+}
+
 fn remove_dir_if_it_exists(root_directory: &Path, dir_name: &str) -> ServiceResult<()> {
 	let directory_path = root_directory.join(dir_name);
 	if directory_path.exists() {
@@ -162,6 +177,9 @@ mod tests {
 			.unwrap();
 		fs::create_dir_all(&root_directory.join(TARGET_B_PARENTCHAIN_LIGHT_CLIENT_DB_PATH))
 			.unwrap();
+		let target_2_lcdb_path =
+			root_directory.join(format!("{}2", TARGET_PARENTCHAIN_LIGHT_CLIENT_DB_PATH_PREFIX));
+		fs::create_dir_all(&target_2_lcdb_path).unwrap();
 
 		purge_files(&root_directory).unwrap();
 
@@ -170,6 +188,7 @@ mod tests {
 		assert!(!root_directory.join(INTEGRITEE_PARENTCHAIN_LIGHT_CLIENT_DB_PATH).exists());
 		assert!(!root_directory.join(TARGET_A_PARENTCHAIN_LIGHT_CLIENT_DB_PATH).exists());
 		assert!(!root_directory.join(TARGET_B_PARENTCHAIN_LIGHT_CLIENT_DB_PATH).exists());
+		assert!(!target_2_lcdb_path.exists());
 	}
 
 	#[test]
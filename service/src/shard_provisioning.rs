@@ -0,0 +1,149 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Provisioning of additional shards to a running worker, triggered by the operator
+//! through the untrusted http server.
+
+use crate::{
+	error::{Error, ServiceResult},
+	sync_state::sync_shard,
+};
+use base58::FromBase58;
+use itp_enclave_api::{
+	enclave_base::EnclaveBase,
+	remote_attestation::{RemoteAttestation, TlsRemoteAttestation},
+};
+use itp_node_api::api_client::PalletTeerexApi;
+use itp_types::ShardIdentifier;
+use log::*;
+use std::{net::SocketAddr, sync::Arc};
+use warp::{http::StatusCode, Filter};
+
+/// Trait to provision the state of an additional shard.
+pub trait ProvisionShard {
+	fn provision_shard(&self, shard: &ShardIdentifier) -> ServiceResult<()>;
+}
+
+/// Requests the state of a shard from the last active validateer of that shard.
+pub struct ShardProvisioner<EnclaveApi, NodeApi> {
+	enclave_api: Arc<EnclaveApi>,
+	node_api: NodeApi,
+	skip_ra: bool,
+}
+
+impl<EnclaveApi, NodeApi> ShardProvisioner<EnclaveApi, NodeApi> {
+	pub fn new(enclave_api: Arc<EnclaveApi>, node_api: NodeApi, skip_ra: bool) -> Self {
+		Self { enclave_api, node_api, skip_ra }
+	}
+}
+
+impl<EnclaveApi, NodeApi> ProvisionShard for ShardProvisioner<EnclaveApi, NodeApi>
+where
+	EnclaveApi: TlsRemoteAttestation + EnclaveBase + RemoteAttestation,
+	NodeApi: PalletTeerexApi,
+{
+	fn provision_shard(&self, shard: &ShardIdentifier) -> ServiceResult<()> {
+		sync_shard(&self.node_api, shard, self.enclave_api.as_ref(), self.skip_ra)
+	}
+}
+
+/// `POST /provision_shard/<base58 encoded shard>`
+///
+/// Only accepted from localhost, since it is meant to be used by the operator of the worker.
+pub fn provision_shard_route<Provisioner>(
+	provisioner: Arc<Provisioner>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone
+where
+	Provisioner: ProvisionShard + Send + Sync + 'static,
+{
+	warp::post()
+		.and(warp::path!("provision_shard" / String))
+		.and(warp::addr::remote())
+		.and_then(move |shard: String, remote_addr: Option<SocketAddr>| {
+			let provisioner = provisioner.clone();
+			async move {
+				if !remote_addr.map(|addr| addr.ip().is_loopback()).unwrap_or(false) {
+					return Ok::<_, warp::Rejection>(warp::reply::with_status(
+						String::from("Shard provisioning is only accepted from localhost"),
+						StatusCode::FORBIDDEN,
+					))
+				}
+				let shard = match decode_shard(&shard) {
+					Ok(shard) => shard,
+					Err(e) =>
+						return Ok(warp::reply::with_status(
+							format!("{:?}", e),
+							StatusCode::BAD_REQUEST,
+						)),
+				};
+
+				// The provisioning blocks until the state has been received.
+				let result =
+					tokio::task::spawn_blocking(move || provisioner.provision_shard(&shard)).await;
+				Ok(match result {
+					Ok(Ok(())) => warp::reply::with_status(
+						format!("Shard {:?} provisioned", shard),
+						StatusCode::OK,
+					),
+					Ok(Err(e)) => {
+						error!("Failed to provision shard {:?}: {:?}", shard, e);
+						warp::reply::with_status(
+							format!("{:?}", e),
+							StatusCode::INTERNAL_SERVER_ERROR,
+						)
+					},
+					Err(e) => warp::reply::with_status(
+						format!("{:?}", e),
+						StatusCode::INTERNAL_SERVER_ERROR,
+					),
+				})
+			}
+		})
+}
+
+fn decode_shard(encoded_shard: &str) -> ServiceResult<ShardIdentifier> {
+	let shard = encoded_shard
+		.from_base58()
+		.map_err(|e| Error::Custom(format!("Shard is not base58 encoded: {:?}", e).into()))?;
+	if shard.len() != 32 {
+		return Err(Error::Custom("Shard must be 32 bytes long".into()))
+	}
+	Ok(ShardIdentifier::from_slice(&shard))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use base58::ToBase58;
+
+	#[test]
+	fn decode_shard_works() {
+		let shard = ShardIdentifier::repeat_byte(7);
+
+		assert_eq!(decode_shard(&shard.as_bytes().to_base58()).unwrap(), shard);
+	}
+
+	#[test]
+	fn decode_shard_fails_for_invalid_length() {
+		assert!(decode_shard(&[1u8, 2, 3].to_base58()).is_err());
+	}
+
+	#[test]
+	fn decode_shard_fails_for_non_base58_input() {
+		assert!(decode_shard("0OIl").is_err());
+	}
+}
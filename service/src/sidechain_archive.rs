@@ -0,0 +1,76 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+*/
+
+//! Export and import of the sidechain blocks of a shard to and from a portable archive file.
+
+use crate::error::ServiceResult;
+use base58::ToBase58;
+use itp_types::ShardIdentifier;
+use its_primitives::types::block::SignedBlock as SignedSidechainBlock;
+use its_storage::{interface::ArchiveBlocks, SidechainBlockArchive};
+use std::path::Path;
+
+pub(crate) fn export_sidechain_blocks<Storage: ArchiveBlocks<SignedSidechainBlock>>(
+	storage: &Storage,
+	shard: &ShardIdentifier,
+	path: &Path,
+) {
+	match export_archive(storage, shard, path) {
+		Ok(exported) => println!(
+			"Exported {} sidechain blocks of shard {} to {}",
+			exported,
+			shard.0.to_base58(),
+			path.display()
+		),
+		Err(e) =>
+			println!("Failed to export sidechain blocks of shard {}: {}", shard.0.to_base58(), e),
+	}
+}
+
+pub(crate) fn import_sidechain_blocks<Storage: ArchiveBlocks<SignedSidechainBlock>>(
+	storage: &Storage,
+	path: &Path,
+) {
+	match import_archive(storage, path) {
+		Ok((shard, imported)) => println!(
+			"Imported {} sidechain blocks of shard {} from {}",
+			imported,
+			shard.0.to_base58(),
+			path.display()
+		),
+		Err(e) => println!("Failed to import sidechain blocks from {}: {}", path.display(), e),
+	}
+}
+
+fn export_archive<Storage: ArchiveBlocks<SignedSidechainBlock>>(
+	storage: &Storage,
+	shard: &ShardIdentifier,
+	path: &Path,
+) -> ServiceResult<usize> {
+	let archive = storage.export_archive(shard)?;
+	archive.write_to_file(path)?;
+	Ok(archive.blocks.len())
+}
+
+fn import_archive<Storage: ArchiveBlocks<SignedSidechainBlock>>(
+	storage: &Storage,
+	path: &Path,
+) -> ServiceResult<(ShardIdentifier, usize)> {
+	let archive =
+		SidechainBlockArchive::<ShardIdentifier, SignedSidechainBlock>::read_from_file(path)?;
+	let shard = archive.shard;
+	Ok((shard, storage.import_archive(archive)?))
+}
@@ -34,8 +34,13 @@ use itp_types::{
 	Header, ShardIdentifier, SidechainBlockNumber,
 };
 use its_consensus_slots::start_slot_worker;
-use its_primitives::types::block::SignedBlock as SignedSidechainBlock;
-use its_storage::{interface::FetchBlocks, start_sidechain_pruning_loop, BlockPruner};
+use its_primitives::types::{
+	block::SignedBlock as SignedSidechainBlock, slot_claims::SlotClaimConfig,
+};
+use its_storage::{
+	interface::{FetchBlocks, QueryBlocks},
+	start_sidechain_pruning_loop, BlockPruner,
+};
 use log::*;
 use sp_runtime::{traits::IdentifyAccount, MultiSigner};
 use std::{
@@ -101,7 +106,12 @@ pub(crate) fn sidechain_start_untrusted_rpc_server<SidechainStorage>(
 	sidechain_storage: Arc<SidechainStorage>,
 	tokio_handle: &Handle,
 ) where
-	SidechainStorage: BlockPruner + FetchBlocks<SignedSidechainBlock> + Sync + Send + 'static,
+	SidechainStorage: BlockPruner
+		+ FetchBlocks<SignedSidechainBlock>
+		+ QueryBlocks<SignedSidechainBlock>
+		+ Sync
+		+ Send
+		+ 'static,
 {
 	let untrusted_url = config.untrusted_worker_url();
 	debug!(
@@ -116,6 +126,7 @@ pub(crate) fn sidechain_start_untrusted_rpc_server<SidechainStorage>(
 pub(crate) fn sidechain_init_block_production<Enclave, SidechainStorage>(
 	enclave: Arc<Enclave>,
 	sidechain_storage: Arc<SidechainStorage>,
+	slot_claim_config: SlotClaimConfig,
 	shutdown_flag: Arc<AtomicBool>,
 ) -> ServiceResult<Vec<thread::JoinHandle<()>>>
 where
@@ -124,7 +135,7 @@ where
 {
 	// ------------------------------------------------------------------------
 	// Initialize sidechain components (has to be AFTER init_parentchain_components()
-	enclave.init_enclave_sidechain_components().unwrap();
+	enclave.init_enclave_sidechain_components(slot_claim_config).unwrap();
 
 	// ------------------------------------------------------------------------
 	// Start interval sidechain block production (execution of trusted calls, sidechain block production).
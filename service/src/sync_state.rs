@@ -17,7 +17,10 @@
 //! Request state keys from a fellow validateer.
 
 use crate::{
-	enclave::tls_ra::enclave_request_state_provisioning,
+	enclave::tls_ra::{
+		enclave_request_rotated_keys, enclave_request_shard_provisioning,
+		enclave_request_state_provisioning,
+	},
 	error::{Error, ServiceResult as Result},
 };
 use futures::executor;
@@ -67,6 +70,64 @@ pub(crate) fn sync_state<
 	println!("[+] State provisioning successfully performed.");
 }
 
+/// Requests the rotated shielding key and state key from the validateer at `provider_url`,
+/// or from the last active validateer of the shard if no url is given.
+pub(crate) fn sync_rotated_keys<
+	E: TlsRemoteAttestation + EnclaveBase + RemoteAttestation,
+	NodeApi: PalletTeerexApi,
+>(
+	node_api: &NodeApi,
+	shard: &ShardIdentifier,
+	enclave_api: &E,
+	provider_url: Option<&str>,
+	skip_ra: bool,
+) {
+	let provider_url = match provider_url {
+		Some(url) => String::from(url),
+		None => executor::block_on(get_enclave_url_of_last_active(node_api, enclave_api, shard))
+			.expect("author of most recent shard update not found"),
+	};
+
+	println!("Requesting rotated keys from worker at {}", &provider_url);
+
+	enclave_request_rotated_keys(
+		enclave_api,
+		sgx_quote_sign_type_t::SGX_UNLINKABLE_SIGNATURE,
+		&provider_url,
+		shard,
+		skip_ra,
+	)
+	.unwrap();
+	println!("[+] Rotated keys successfully obtained.");
+}
+
+/// Requests the state of an additional shard from the last active validateer of that shard,
+/// while the worker is running.
+pub(crate) fn sync_shard<
+	E: TlsRemoteAttestation + EnclaveBase + RemoteAttestation,
+	NodeApi: PalletTeerexApi,
+>(
+	node_api: &NodeApi,
+	shard: &ShardIdentifier,
+	enclave_api: &E,
+	skip_ra: bool,
+) -> Result<()> {
+	let provider_url =
+		executor::block_on(get_enclave_url_of_last_active(node_api, enclave_api, shard))?;
+
+	info!("Requesting state of shard {:?} from worker at {}", shard, &provider_url);
+
+	enclave_request_shard_provisioning(
+		enclave_api,
+		sgx_quote_sign_type_t::SGX_UNLINKABLE_SIGNATURE,
+		&provider_url,
+		shard,
+		skip_ra,
+	)?;
+	info!("[+] Shard {:?} successfully provisioned.", shard);
+	Ok(())
+}
+
 /// Returns the url of the last sidechain block author that has been stored
 /// in the parentchain state as "worker for shard".
 ///
@@ -16,7 +16,9 @@
 */
 
 use crate::{sync_block_broadcaster::BroadcastBlocks, worker::WorkerResult};
-use its_primitives::types::block::SignedBlock as SignedSidechainBlock;
+use its_primitives::types::{
+	block::SignedBlock as SignedSidechainBlock, justification::SignedFinalityVote,
+};
 use std::vec::Vec;
 
 pub struct BroadcastBlocksMock;
@@ -25,4 +27,8 @@ impl BroadcastBlocks for BroadcastBlocksMock {
 	fn broadcast_blocks(&self, _blocks: Vec<SignedSidechainBlock>) -> WorkerResult<()> {
 		Ok(())
 	}
+
+	fn broadcast_finality_votes(&self, _votes: Vec<SignedFinalityVote>) -> WorkerResult<()> {
+		Ok(())
+	}
 }
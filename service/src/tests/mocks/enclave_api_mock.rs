@@ -30,6 +30,7 @@ use itp_types::{
 	parentchain::{Balance, Header},
 	ShardIdentifier,
 };
+use its_primitives::types::slot_claims::SlotClaimConfig;
 use sgx_crypto_helper::rsa3072::Rsa3072PubKey;
 use sp_core::ed25519;
 
@@ -41,7 +42,10 @@ impl EnclaveBase for EnclaveMock {
 		Ok(())
 	}
 
-	fn init_enclave_sidechain_components(&self) -> EnclaveResult<()> {
+	fn init_enclave_sidechain_components(
+		&self,
+		_slot_claim_config: SlotClaimConfig,
+	) -> EnclaveResult<()> {
 		Ok(())
 	}
 
@@ -110,6 +114,10 @@ impl EnclaveBase for EnclaveMock {
 	fn get_fingerprint(&self) -> EnclaveResult<EnclaveFingerprint> {
 		Ok([1u8; MR_ENCLAVE_SIZE].into())
 	}
+
+	fn rotate_keys(&self) -> EnclaveResult<()> {
+		unimplemented!()
+	}
 }
 
 impl Sidechain for EnclaveMock {
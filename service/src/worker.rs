@@ -26,8 +26,12 @@ use itc_rpc_client::direct_client::{DirectApi, DirectClient as DirectWorkerApi};
 use itp_node_api::{api_client::PalletTeerexApi, node_api_factory::CreateNodeApi};
 use itp_types::ShardIdentifier;
 use itp_utils::ToHexPrefixed;
-use its_primitives::types::SignedBlock as SignedSidechainBlock;
-use its_rpc_handler::constants::RPC_METHOD_NAME_IMPORT_BLOCKS;
+use its_primitives::types::{
+	justification::SignedFinalityVote, SignedBlock as SignedSidechainBlock,
+};
+use its_rpc_handler::constants::{
+	RPC_METHOD_NAME_IMPORT_BLOCKS, RPC_METHOD_NAME_IMPORT_FINALITY_VOTES,
+};
 use jsonrpsee::{
 	types::{to_json_value, traits::Client},
 	ws_client::WsClientBuilder,
@@ -69,9 +73,11 @@ impl<Config, NodeApiFactory, Enclave, InitializationHandler>
 }
 
 #[async_trait]
-/// Broadcast Sidechain blocks to peers.
+/// Broadcast Sidechain blocks and finality votes to peers.
 pub trait AsyncBlockBroadcaster {
 	async fn broadcast_blocks(&self, blocks: Vec<SignedSidechainBlock>) -> WorkerResult<()>;
+
+	async fn broadcast_finality_votes(&self, votes: Vec<SignedFinalityVote>) -> WorkerResult<()>;
 }
 
 #[async_trait]
@@ -120,6 +126,36 @@ where
 		info!("broadcast {} block(s) to {} peers", nr_blocks, nr_peers);
 		Ok(())
 	}
+
+	async fn broadcast_finality_votes(&self, votes: Vec<SignedFinalityVote>) -> WorkerResult<()> {
+		if votes.is_empty() {
+			return Ok(())
+		}
+		let encoded_votes = votes.to_hex();
+
+		let peers = self
+			.peers
+			.read()
+			.map_err(|e| {
+				Error::Custom(format!("Encountered poisoned lock for peers: {:?}", e).into())
+			})
+			.map(|l| l.clone())?;
+
+		for url in peers {
+			let encoded_votes_cloned = encoded_votes.clone();
+			tokio::spawn(async move {
+				let direct_client = DirectWorkerApi::new(url.clone());
+				if let Err(e) = direct_client.import_finality_votes(encoded_votes_cloned) {
+					error!(
+						"Broadcast finality vote request ({}) to {} failed: {:?}",
+						RPC_METHOD_NAME_IMPORT_FINALITY_VOTES, url, e
+					);
+				}
+			});
+		}
+		debug!("broadcast {} finality vote(s)", votes.len());
+		Ok(())
+	}
 }
 
 /// Looks for new peers and updates them.
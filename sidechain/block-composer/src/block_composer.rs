@@ -20,6 +20,7 @@ use codec::Encode;
 use itp_settings::worker::BLOCK_NUMBER_FINALIZATION_DIFF;
 use itp_sgx_crypto::{key_repository::AccessKey, StateCrypto};
 use itp_sgx_externalities::{SgxExternalitiesTrait, StateHash};
+use itp_stf_executor::state_commitment::{StateCommitment, StateCommitmentCache};
 use itp_stf_primitives::types::StatePayload;
 use itp_time_utils::now_as_millis;
 use itp_types::{ShardIdentifier, H256};
@@ -55,6 +56,7 @@ pub trait ComposeBlock<Externalities, ParentchainBlock: ParentchainBlockTrait> {
 pub struct BlockComposer<ParentchainBlock, SignedSidechainBlock, Signer, StateKeyRepository> {
 	signer: Signer,
 	state_key_repository: Arc<StateKeyRepository>,
+	state_commitment_cache: Arc<StateCommitmentCache>,
 	_phantom: PhantomData<(ParentchainBlock, SignedSidechainBlock)>,
 }
 
@@ -73,8 +75,17 @@ where
 	StateKeyRepository: AccessKey,
 	<StateKeyRepository as AccessKey>::KeyType: StateCrypto,
 {
-	pub fn new(signer: Signer, state_key_repository: Arc<StateKeyRepository>) -> Self {
-		BlockComposer { signer, state_key_repository, _phantom: Default::default() }
+	pub fn new(
+		signer: Signer,
+		state_key_repository: Arc<StateKeyRepository>,
+		state_commitment_cache: Arc<StateCommitmentCache>,
+	) -> Self {
+		BlockComposer {
+			signer,
+			state_key_repository,
+			state_commitment_cache,
+			_phantom: Default::default(),
+		}
 	}
 }
 
@@ -97,6 +108,7 @@ where
 		+ SidechainState
 		+ SidechainSystemExt
 		+ StateHash
+		+ StateCommitment
 		+ LastBlockExt<SignedSidechainBlock::Block>
 		+ Encode,
 	<Externalities as SgxExternalitiesTrait>::SgxExternalitiesType: Encode,
@@ -119,6 +131,10 @@ where
 		let author_public = self.signer.public();
 
 		let state_hash_new = aposteriori_state.hash();
+		let state_root = self
+			.state_commitment_cache
+			.commit(&shard, aposteriori_state)
+			.map_err(|e| Error::Other(format!("Failed to compute state root: {:?}", e).into()))?;
 
 		let (block_number, parent_hash, next_finalization_block_number) =
 			match aposteriori_state.get_last_block() {
@@ -137,7 +153,8 @@ where
 			return Err(Error::Other("[Sidechain] BlockNumber is not LastBlock's Number + 1".into()))
 		}
 
-		// create encrypted payload
+		// create encrypted payload. The ciphertext header carries the format version, so importers
+		// can tell it apart from payloads in the legacy format.
 		let mut payload: Vec<u8> =
 			StatePayload::new(state_hash_apriori, state_hash_new, aposteriori_state.state_diff())
 				.encode();
@@ -171,6 +188,7 @@ where
 			parent_hash,
 			shard,
 			block_data.hash(),
+			state_root,
 			finalization_candidate,
 		);
 
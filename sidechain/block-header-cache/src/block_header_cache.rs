@@ -83,6 +83,7 @@ pub mod tests {
 			Default::default(),
 			Default::default(),
 			Default::default(),
+			Default::default(),
 			53,
 		);
 
@@ -105,6 +106,7 @@ pub mod tests {
 			Default::default(),
 			Default::default(),
 			Default::default(),
+			Default::default(),
 			53,
 		);
 		// spawn a new thread that reads the header
@@ -27,7 +27,7 @@ compile_error!("feature \"std\" and feature \"sgx\" cannot be disabled at the sa
 #[macro_use]
 extern crate sgx_tstd as std;
 
-use crate::slot::{slot_author, slot_from_timestamp_and_duration};
+use crate::slot::{slot_author_with_fallback, slot_from_timestamp_and_duration};
 use error::Error as ConsensusError;
 use frame_support::ensure;
 use itp_utils::stringify::public_to_string;
@@ -45,7 +45,7 @@ use sp_runtime::{
 	app_crypto::Pair,
 	traits::{Block as ParentchainBlockTrait, Header as ParentchainHeaderTrait},
 };
-use std::{fmt::Debug, time::Duration};
+use std::{fmt::Debug, time::Duration, vec::Vec};
 
 pub mod error;
 pub mod slot;
@@ -59,6 +59,35 @@ pub fn verify_sidechain_block<AuthorityPair, ParentchainBlock, SignedSidechainBl
 	parentchain_header: &ParentchainBlock::Header,
 	authorities: &[AuthorityId<AuthorityPair>],
 ) -> Result<SignedSidechainBlock, ConsensusError>
+where
+	AuthorityPair: Pair,
+	AuthorityPair::Public: Debug,
+	ParentchainBlock: ParentchainBlockTrait<Hash = BlockHash>,
+	SignedSidechainBlock: 'static + SignedSidechainBlockTrait<Public = AuthorityPair::Public>,
+	SignedSidechainBlock::Block: SidechainBlockTrait,
+{
+	let authorities: Vec<_> = authorities.iter().map(|a| (a.clone(), 1)).collect();
+	verify_weighted_sidechain_block::<AuthorityPair, ParentchainBlock, SignedSidechainBlock>(
+		signed_block,
+		slot_duration,
+		last_block,
+		parentchain_header,
+		&authorities,
+		0,
+	)
+}
+
+/// Verify a sidechain block, whose slot author is determined by the authorities' weights.
+///
+/// Blocks of fallback authors are accepted after `fallback_delay` slots without a block.
+pub fn verify_weighted_sidechain_block<AuthorityPair, ParentchainBlock, SignedSidechainBlock>(
+	signed_block: SignedSidechainBlock,
+	slot_duration: Duration,
+	last_block: &Option<<SignedSidechainBlock as SignedBlock>::Block>,
+	parentchain_header: &ParentchainBlock::Header,
+	authorities: &[(AuthorityId<AuthorityPair>, u64)],
+	fallback_delay: u64,
+) -> Result<SignedSidechainBlock, ConsensusError>
 where
 	AuthorityPair: Pair,
 	AuthorityPair::Public: Debug,
@@ -84,11 +113,20 @@ where
 		None => ensure_first_block(signed_block.block())?,
 	}
 
+	let last_block_slot = last_block.as_ref().map(|b| {
+		slot_from_timestamp_and_duration(
+			Duration::from_millis(b.block_data().timestamp()),
+			slot_duration,
+		)
+	});
+
 	if let Err(e) = verify_author::<AuthorityPair, ParentchainBlock::Header, SignedSidechainBlock>(
 		&slot,
+		last_block_slot,
 		signed_block.block(),
 		parentchain_header,
 		authorities,
+		fallback_delay,
 	) {
 		error!(
 			"Author verification for block (number: {}) failed, block will be discarded",
@@ -103,9 +141,11 @@ where
 /// Verify that the `blocks` author is the expected author when comparing with onchain data.
 fn verify_author<AuthorityPair, ParentchainHeader, SignedSidechainBlock>(
 	slot: &Slot,
+	last_block_slot: Option<Slot>,
 	block: &SignedSidechainBlock::Block,
 	parentchain_head: &ParentchainHeader,
-	authorities: &[AuthorityId<AuthorityPair>],
+	authorities: &[(AuthorityId<AuthorityPair>, u64)],
+	fallback_delay: u64,
 ) -> Result<(), ConsensusError>
 where
 	AuthorityPair: Pair,
@@ -121,8 +161,13 @@ where
 		)
 	);
 
-	let expected_author = slot_author::<AuthorityPair>(*slot, authorities)
-		.ok_or_else(|| ConsensusError::CouldNotGetAuthorities("No authorities found".into()))?;
+	let expected_author = slot_author_with_fallback::<AuthorityPair>(
+		*slot,
+		last_block_slot,
+		fallback_delay,
+		authorities,
+	)
+	.ok_or_else(|| ConsensusError::CouldNotGetAuthorities("No authorities found".into()))?;
 
 	ensure!(
 		expected_author == block.block_data().block_author(),
@@ -237,6 +282,21 @@ mod tests {
 			.build_signed()
 	}
 
+	fn block_at_slot(signer: Keyring, header: Header, slot: u64) -> SignedBlock {
+		let parentchain_header = ParentchainHeaderBuilder::default().build();
+		let block_data = SidechainBlockDataBuilder::default()
+			.with_signer(signer.pair())
+			.with_timestamp(slot * SLOT_DURATION.as_millis() as u64)
+			.with_layer_one_head(parentchain_header.hash())
+			.build();
+
+		SidechainBlockBuilder::default()
+			.with_header(header)
+			.with_block_data(block_data)
+			.with_signer(signer.pair())
+			.build_signed()
+	}
+
 	fn block1(signer: Keyring) -> SignedBlock {
 		let header = SidechainHeaderBuilder::default().with_block_number(1).build();
 
@@ -380,6 +440,41 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn verify_weighted_accepts_fallback_author_after_missed_slots() {
+		let authorities = [(Keyring::Bob.public(), 1), (Keyring::Alice.public(), 1)];
+
+		let parentchain_header = ParentchainHeaderBuilder::default().build();
+		let last_block = block_at_slot(Keyring::Bob, SidechainHeaderBuilder::default().build(), 0);
+		let header = SidechainHeaderBuilder::default()
+			.with_parent_hash(last_block.block().hash())
+			.with_block_number(2)
+			.build();
+
+		// Slot 2 belongs to Bob, but slot 1 has been missed.
+		assert_ok!(verify_weighted_sidechain_block::<Pair, ParentchainBlock, _>(
+			block_at_slot(Keyring::Alice, header.clone(), 2),
+			SLOT_DURATION,
+			&Some(last_block.block().clone()),
+			&parentchain_header,
+			&authorities,
+			1,
+		));
+
+		assert_matches!(
+			verify_weighted_sidechain_block::<Pair, ParentchainBlock, _>(
+				block_at_slot(Keyring::Bob, header, 2),
+				SLOT_DURATION,
+				&Some(last_block.block().clone()),
+				&parentchain_header,
+				&authorities,
+				1,
+			)
+			.unwrap_err(),
+			ConsensusError::InvalidAuthority(_)
+		);
+	}
+
 	#[test]
 	fn verify_errs_on_invalid_ancestry() {
 		let signer = Keyring::Alice;
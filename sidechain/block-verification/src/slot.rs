@@ -40,6 +40,150 @@ pub fn slot_author<P: Pair>(slot: Slot, authorities: &[AuthorityId<P>]) -> Optio
 	Some(current_author)
 }
 
+/// Get the slot author, where each authority is assigned a share of the slots that is
+/// proportional to its weight. With equal weights, this is the same as [`slot_author`].
+pub fn weighted_slot_author<P: Pair>(
+	slot: Slot,
+	authorities: &[(AuthorityId<P>, u64)],
+) -> Option<&AuthorityId<P>> {
+	weighted_author_index::<P>(slot, authorities).map(|idx| &authorities[idx].0)
+}
+
+/// Get the weighted slot author, taking into account that the slot author might be offline.
+///
+/// For every `fallback_delay` slots that passed since the slot of the last block without a new
+/// block, the right to author the slot passes on to the next authority in the list. A
+/// `fallback_delay` of zero disables fallback authors.
+pub fn slot_author_with_fallback<P: Pair>(
+	slot: Slot,
+	last_block_slot: Option<Slot>,
+	fallback_delay: u64,
+	authorities: &[(AuthorityId<P>, u64)],
+) -> Option<&AuthorityId<P>> {
+	let primary_idx = weighted_author_index::<P>(slot, authorities)?;
+
+	let missed_slots = last_block_slot
+		.map(|last_slot| (*slot).saturating_sub(*last_slot).saturating_sub(1))
+		.unwrap_or_default();
+	let fallback_rank = missed_slots.checked_div(fallback_delay).unwrap_or_default();
+	if fallback_rank > 0 {
+		log::debug!("Slot {} passed on to fallback author {}", *slot, fallback_rank);
+	}
+
+	let idx =
+		(primary_idx as u64 + fallback_rank % authorities.len() as u64) % authorities.len() as u64;
+	authorities.get(idx as usize).map(|(author, _)| author)
+}
+
+fn weighted_author_index<P: Pair>(
+	slot: Slot,
+	authorities: &[(AuthorityId<P>, u64)],
+) -> Option<usize> {
+	let total_weight = authorities
+		.iter()
+		.fold(0u64, |total, (_, weight)| total.saturating_add(*weight));
+	if total_weight == 0 {
+		log::warn!("Total weight of the authorities is zero, cannot determine slot author");
+		return None
+	}
+
+	let mut position = *slot % total_weight;
+	for (idx, (_, weight)) in authorities.iter().enumerate() {
+		if position < *weight {
+			return Some(idx)
+		}
+		position -= weight;
+	}
+	None
+}
+
 pub fn slot_from_timestamp_and_duration(timestamp: Duration, duration: Duration) -> Slot {
 	((timestamp.as_millis() / duration.as_millis()) as u64).into()
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::ed25519::Pair;
+	use sp_keyring::ed25519::Keyring;
+
+	fn weighted(weights: &[u64]) -> Vec<(AuthorityId<Pair>, u64)> {
+		[Keyring::Alice, Keyring::Bob, Keyring::Charlie]
+			.iter()
+			.zip(weights)
+			.map(|(k, w)| (k.public(), *w))
+			.collect()
+	}
+
+	fn authors(slots: u64, authorities: &[(AuthorityId<Pair>, u64)]) -> Vec<AuthorityId<Pair>> {
+		(0..slots)
+			.map(|s| *weighted_slot_author::<Pair>(s.into(), authorities).unwrap())
+			.collect()
+	}
+
+	#[test]
+	fn weighted_slot_author_with_equal_weights_is_round_robin() {
+		let authorities = weighted(&[1, 1, 1]);
+		let publics: Vec<_> = authorities.iter().map(|(a, _)| *a).collect();
+
+		for slot in 0..6u64 {
+			assert_eq!(
+				weighted_slot_author::<Pair>(slot.into(), &authorities),
+				slot_author::<Pair>(slot.into(), &publics)
+			);
+		}
+	}
+
+	#[test]
+	fn weighted_slot_author_assigns_slots_proportional_to_weight() {
+		let authorities = weighted(&[3, 0, 1]);
+		let alice = Keyring::Alice.public();
+		let charlie = Keyring::Charlie.public();
+
+		assert_eq!(
+			authors(8, &authorities),
+			vec![alice, alice, alice, charlie, alice, alice, alice, charlie]
+		);
+	}
+
+	#[test]
+	fn weighted_slot_author_is_none_for_zero_total_weight() {
+		assert!(weighted_slot_author::<Pair>(1.into(), &weighted(&[0, 0])).is_none());
+		assert!(weighted_slot_author::<Pair>(1.into(), &[]).is_none());
+	}
+
+	#[test]
+	fn slot_passes_on_to_fallback_author_after_delay() {
+		let authorities = weighted(&[1, 1, 1]);
+		let author = |slot: u64, last_block_slot: u64| {
+			*slot_author_with_fallback::<Pair>(
+				slot.into(),
+				Some(last_block_slot.into()),
+				2,
+				&authorities,
+			)
+			.unwrap()
+		};
+
+		// Slot 9 belongs to Alice.
+		assert_eq!(author(9, 8), Keyring::Alice.public());
+		assert_eq!(author(9, 7), Keyring::Alice.public());
+		assert_eq!(author(9, 6), Keyring::Bob.public());
+		assert_eq!(author(9, 4), Keyring::Charlie.public());
+		assert_eq!(author(9, 2), Keyring::Alice.public());
+	}
+
+	#[test]
+	fn fallback_authors_are_disabled_with_zero_delay() {
+		let authorities = weighted(&[1, 1, 1]);
+
+		assert_eq!(
+			slot_author_with_fallback::<Pair>(6.into(), Some(0.into()), 0, &authorities),
+			Some(&Keyring::Alice.public())
+		);
+		assert_eq!(
+			slot_author_with_fallback::<Pair>(6.into(), None, 2, &authorities),
+			Some(&Keyring::Alice.public())
+		);
+	}
+}
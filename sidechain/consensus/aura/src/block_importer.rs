@@ -25,8 +25,12 @@ use itc_parentchain_block_import_dispatcher::triggered_dispatcher::TriggerParent
 use itp_enclave_metrics::EnclaveMetric;
 use itp_ocall_api::{EnclaveMetricsOCallApi, EnclaveSidechainOCallApi};
 use itp_settings::sidechain::SLOT_DURATION;
-use itp_sgx_crypto::{key_repository::AccessKey, StateCrypto};
+use itp_sgx_crypto::{
+	key_repository::{AccessKey, MutateKey},
+	StateCrypto,
+};
 use itp_sgx_externalities::SgxExternalities;
+use itp_stf_executor::state_commitment::StateCommitmentCache;
 use itp_stf_primitives::{traits::TrustedCallVerification, types::TrustedOperationOrHash};
 use itp_stf_state_handler::handle_state::HandleState;
 use itp_top_pool_author::traits::{AuthorApi, OnBlockImported};
@@ -35,9 +39,13 @@ use its_block_header_cache::{
 	CachedSidechainBlockHeader, MutateSidechainBlockHeader, SidechainBlockHeaderCache,
 };
 pub use its_consensus_common::BlockImport;
-use its_consensus_common::Error as ConsensusError;
-use its_primitives::traits::{
-	Block, BlockData, Header as HeaderTrait, ShardIdentifierFor, SignedBlock as SignedBlockTrait,
+use its_consensus_common::{Error as ConsensusError, ObserveImportedState};
+use its_primitives::{
+	traits::{
+		Block, BlockData, Header as HeaderTrait, ShardIdentifierFor,
+		SignedBlock as SignedBlockTrait,
+	},
+	types::slot_claims::SlotClaimConfig,
 };
 use its_validateer_fetch::ValidateerFetch;
 use log::*;
@@ -46,7 +54,7 @@ use sp_runtime::{
 	generic::SignedBlock as SignedParentchainBlock,
 	traits::{Block as ParentchainBlockTrait, Header},
 };
-use std::{marker::PhantomData, sync::Arc};
+use std::{marker::PhantomData, sync::Arc, vec::Vec};
 
 /// Implements `BlockImport`.
 #[derive(Clone)]
@@ -72,6 +80,9 @@ pub struct BlockImporter<
 			<<SignedSidechainBlock as SignedBlockTrait>::Block as Block>::HeaderType,
 		>,
 	>,
+	state_commitment_cache: Arc<StateCommitmentCache>,
+	imported_state_observers: Vec<Arc<dyn ObserveImportedState<SgxExternalities>>>,
+	slot_claim_config: SlotClaimConfig,
 	_phantom: PhantomData<(Authority, ParentchainBlock, SignedSidechainBlock, TCS, G)>,
 }
 
@@ -112,7 +123,7 @@ impl<
 		+ Send
 		+ Sync,
 	StateHandler: HandleState<StateT = SgxExternalities>,
-	StateKeyRepository: AccessKey,
+	StateKeyRepository: AccessKey + MutateKey<<StateKeyRepository as AccessKey>::KeyType>,
 	<StateKeyRepository as AccessKey>::KeyType: StateCrypto,
 	TopPoolAuthor: AuthorApi<H256, H256, TCS, G> + OnBlockImported<Hash = H256>,
 	ParentchainBlockImporter: TriggerParentchainBlockImport<SignedBlockType = SignedParentchainBlock<ParentchainBlock>>
@@ -132,6 +143,7 @@ impl<
 				<<SignedSidechainBlock as SignedBlockTrait>::Block as Block>::HeaderType,
 			>,
 		>,
+		state_commitment_cache: Arc<StateCommitmentCache>,
 	) -> Self {
 		Self {
 			state_handler,
@@ -140,10 +152,43 @@ impl<
 			parentchain_block_importer,
 			ocall_api,
 			header_cache,
+			state_commitment_cache,
+			imported_state_observers: Vec::new(),
+			slot_claim_config: Default::default(),
 			_phantom: Default::default(),
 		}
 	}
 
+	/// Hand the state of every imported block to the observer, after the ones added before.
+	pub fn with_imported_state_observer(
+		mut self,
+		imported_state_observer: Arc<dyn ObserveImportedState<SgxExternalities>>,
+	) -> Self {
+		self.imported_state_observers.push(imported_state_observer);
+		self
+	}
+
+	/// Verify the authors of imported blocks according to the slot claim rules of the shard.
+	pub fn with_slot_claim_config(mut self, slot_claim_config: SlotClaimConfig) -> Self {
+		self.slot_claim_config = slot_claim_config;
+		self
+	}
+
+	fn notify_imported_state(&self, sidechain_block: &SignedSidechainBlock::Block) {
+		if self.imported_state_observers.is_empty() {
+			return
+		}
+
+		let shard = sidechain_block.header().shard_id();
+		if let Err(e) = self.state_handler.execute_on_current(&shard, |state, _| {
+			for observer in &self.imported_state_observers {
+				observer.on_state_imported(shard, sidechain_block.hash(), state)
+			}
+		}) {
+			warn!("Failed to notify observer about imported state: {:?}", e);
+		}
+	}
+
 	fn update_top_pool(&self, sidechain_block: &SignedSidechainBlock::Block) {
 		// Notify pool about imported block for status updates of the calls.
 		self.top_pool_author.on_block_imported(
@@ -209,7 +254,7 @@ impl<
 		+ Send
 		+ Sync,
 	StateHandler: HandleState<StateT = SgxExternalities>,
-	StateKeyRepository: AccessKey,
+	StateKeyRepository: AccessKey + MutateKey<<StateKeyRepository as AccessKey>::KeyType>,
 	<StateKeyRepository as AccessKey>::KeyType: StateCrypto,
 	TopPoolAuthor: AuthorApi<H256, H256, TCS, G> + OnBlockImported<Hash = H256>,
 	ParentchainBlockImporter: TriggerParentchainBlockImport<SignedBlockType = SignedParentchainBlock<ParentchainBlock>>
@@ -231,6 +276,7 @@ impl<
 			SLOT_DURATION,
 			maybe_last_sidechain_block,
 		)
+		.with_slot_claim_config(self.slot_claim_config)
 	}
 
 	fn update_latest_sidechain_header(
@@ -288,6 +334,35 @@ impl<
 			.map_err(|e| ConsensusError::Other(format!("{:?}", e).into()))
 	}
 
+	fn complete_state_encryption_migration(
+		&self,
+		state_key: Self::StateCrypto,
+	) -> Result<(), ConsensusError> {
+		self.state_key_repository
+			.update_key(state_key)
+			.map_err(|e| ConsensusError::Other(format!("{:?}", e).into()))
+	}
+
+	fn state_root(
+		&self,
+		shard: &ShardIdentifierFor<SignedSidechainBlock>,
+		state: &Self::SidechainState,
+	) -> Result<H256, ConsensusError> {
+		self.state_commitment_cache
+			.prepare(shard, state)
+			.map_err(|e| ConsensusError::Other(format!("{:?}", e).into()))
+	}
+
+	fn commit_state_root(
+		&self,
+		shard: &ShardIdentifierFor<SignedSidechainBlock>,
+	) -> Result<(), ConsensusError> {
+		// The trie is kept for the proofs of getters against the state of this block.
+		self.state_commitment_cache
+			.commit_prepared(shard)
+			.map_err(|e| ConsensusError::Other(format!("{:?}", e).into()))
+	}
+
 	fn get_context(&self) -> &Self::Context {
 		&self.ocall_api
 	}
@@ -365,6 +440,8 @@ impl<
 		// Remove all successfully applied trusted calls from the top pool.
 		self.update_top_pool(sidechain_block);
 
+		self.notify_imported_state(sidechain_block);
+
 		// Send metric about sidechain block height (i.e. block number)
 		let block_height_metric =
 			EnclaveMetric::SetSidechainBlockHeight(sidechain_block.header().block_number());
@@ -34,23 +34,27 @@ use core::marker::PhantomData;
 use itc_parentchain_block_import_dispatcher::triggered_dispatcher::TriggerParentchainBlockImport;
 use itp_ocall_api::EnclaveOnChainOCallApi;
 use itp_time_utils::duration_now;
+use itp_types::parentchain::TargetParentchainIndex;
 
 use itp_utils::hex::hex_encode;
-use its_block_verification::slot::slot_author;
+use its_block_verification::slot::slot_author_with_fallback;
 use its_consensus_common::{Environment, Error as ConsensusError, Proposer};
 use its_consensus_slots::{SimpleSlotWorker, Slot, SlotInfo};
 use its_primitives::{
 	traits::{Block as SidechainBlockTrait, Header as HeaderTrait, SignedBlock},
-	types::block::BlockHash,
+	types::{
+		block::{BlockHash, BlockNumber},
+		slot_claims::SlotClaimConfig,
+	},
 };
-use its_validateer_fetch::ValidateerFetch;
+use its_validateer_fetch::{ValidateerFetch, DEFAULT_VALIDATEER_WEIGHT};
 use sp_core::crypto::UncheckedFrom;
 use sp_runtime::{
 	app_crypto::{sp_core::H256, Pair},
 	generic::SignedBlock as SignedParentchainBlock,
 	traits::{Block as ParentchainBlockTrait, Header as ParentchainHeaderTrait},
 };
-use std::{string::ToString, sync::Arc, time::Duration, vec::Vec};
+use std::{collections::BTreeMap, string::ToString, sync::Arc, time::Duration, vec::Vec};
 
 pub mod block_importer;
 pub mod proposer_factory;
@@ -70,16 +74,16 @@ pub struct Aura<
 	Environment,
 	OcallApi,
 	IntegriteeImportTrigger,
-	TargetAImportTrigger,
-	TargetBImportTrigger,
+	TargetImportTrigger,
 > {
 	authority_pair: AuthorityPair,
 	ocall_api: OcallApi,
 	parentchain_integritee_import_trigger: Arc<IntegriteeImportTrigger>,
-	maybe_parentchain_target_a_import_trigger: Option<Arc<TargetAImportTrigger>>,
-	maybe_parentchain_target_b_import_trigger: Option<Arc<TargetBImportTrigger>>,
+	parentchain_target_import_triggers: BTreeMap<TargetParentchainIndex, Arc<TargetImportTrigger>>,
 	environment: Environment,
 	claim_strategy: SlotClaimStrategy,
+	slot_claim_config: SlotClaimConfig,
+	last_block: Option<(BlockNumber, Slot)>,
 	_phantom: PhantomData<(AuthorityPair, ParentchainBlock, SidechainBlock)>,
 }
 
@@ -90,8 +94,7 @@ impl<
 		Environment,
 		OcallApi,
 		IntegriteeImportTrigger,
-		TargetAImportTrigger,
-		TargetBImportTrigger,
+		TargetImportTrigger,
 	>
 	Aura<
 		AuthorityPair,
@@ -100,26 +103,28 @@ impl<
 		Environment,
 		OcallApi,
 		IntegriteeImportTrigger,
-		TargetAImportTrigger,
-		TargetBImportTrigger,
+		TargetImportTrigger,
 	>
 {
 	pub fn new(
 		authority_pair: AuthorityPair,
 		ocall_api: OcallApi,
 		parentchain_integritee_import_trigger: Arc<IntegriteeImportTrigger>,
-		maybe_parentchain_target_a_import_trigger: Option<Arc<TargetAImportTrigger>>,
-		maybe_parentchain_target_b_import_trigger: Option<Arc<TargetBImportTrigger>>,
+		parentchain_target_import_triggers: BTreeMap<
+			TargetParentchainIndex,
+			Arc<TargetImportTrigger>,
+		>,
 		environment: Environment,
 	) -> Self {
 		Self {
 			authority_pair,
 			ocall_api,
 			parentchain_integritee_import_trigger,
-			maybe_parentchain_target_a_import_trigger,
-			maybe_parentchain_target_b_import_trigger,
+			parentchain_target_import_triggers,
 			environment,
 			claim_strategy: SlotClaimStrategy::RoundRobin,
+			slot_claim_config: Default::default(),
+			last_block: None,
 			_phantom: Default::default(),
 		}
 	}
@@ -129,18 +134,49 @@ impl<
 
 		self
 	}
+
+	/// Claim slots according to the rules of `slot_claim_config` that are active for the block
+	/// following `last_block`, given by its number and slot.
+	///
+	/// A validateer only produces blocks for the shard it is assigned to, `last_block` is the
+	/// latest block of that shard.
+	pub fn with_slot_claim_config(
+		mut self,
+		slot_claim_config: SlotClaimConfig,
+		last_block: Option<(BlockNumber, Slot)>,
+	) -> Self {
+		self.slot_claim_config = slot_claim_config;
+		self.last_block = last_block;
+
+		self
+	}
+
+	fn next_block_number(&self) -> BlockNumber {
+		self.last_block.map_or(1, |(number, _)| number + 1)
+	}
+
+	fn target_import_trigger(
+		&self,
+		index: TargetParentchainIndex,
+	) -> Result<Arc<TargetImportTrigger>, ConsensusError> {
+		self.parentchain_target_import_triggers
+			.get(&index)
+			.cloned()
+			.ok_or_else(|| ConsensusError::Other(format!("no target {} assigned", index).into()))
+	}
 }
 
 /// The fraction of total block time we are allowed to be producing the block. So that we have
 /// enough time send create and send the block to fellow validateers.
 pub const BLOCK_PROPOSAL_SLOT_PORTION: f32 = 0.7;
 
-#[derive(PartialEq, Eq, Debug)]
+#[derive(PartialEq, Eq, Debug, Default, Clone, Copy)]
 pub enum SlotClaimStrategy {
 	/// try to produce a block always even if it's not the authors slot
 	/// Intended for first phase to see if aura production works
 	Always,
 	/// Proper Aura strategy: Only produce blocks, when it's the authors slot.
+	#[default]
 	RoundRobin,
 }
 
@@ -155,8 +191,7 @@ impl<
 		E,
 		OcallApi,
 		IntegriteeImportTrigger,
-		TargetAImportTrigger,
-		TargetBImportTrigger,
+		TargetImportTrigger,
 	> SimpleSlotWorker<ParentchainBlock>
 	for Aura<
 		AuthorityPair,
@@ -165,9 +200,9 @@ impl<
 		E,
 		OcallApi,
 		IntegriteeImportTrigger,
-		TargetAImportTrigger,
-		TargetBImportTrigger,
-	> where
+		TargetImportTrigger,
+	>
+where
 	AuthorityPair: Pair,
 	AuthorityPair::Public: UncheckedFrom<[u8; 32]>,
 	// todo: Relax hash trait bound, but this needs a change to some other parts in the code.
@@ -178,14 +213,12 @@ impl<
 	OcallApi: ValidateerFetch + EnclaveOnChainOCallApi + Send + 'static,
 	IntegriteeImportTrigger:
 		TriggerParentchainBlockImport<SignedBlockType = SignedParentchainBlock<ParentchainBlock>>,
-	TargetAImportTrigger:
-		TriggerParentchainBlockImport<SignedBlockType = SignedParentchainBlock<ParentchainBlock>>,
-	TargetBImportTrigger:
+	TargetImportTrigger:
 		TriggerParentchainBlockImport<SignedBlockType = SignedParentchainBlock<ParentchainBlock>>,
 {
 	type Proposer = E::Proposer;
 	type Claim = AuthorityPair::Public;
-	type EpochData = Vec<AuthorityId<AuthorityPair>>;
+	type EpochData = Vec<(AuthorityId<AuthorityPair>, u64)>;
 	type Output = SignedSidechainBlock;
 
 	fn logging_target(&self) -> &'static str {
@@ -198,10 +231,11 @@ impl<
 		shard: ShardIdentifierFor<Self::Output>,
 		_slot: Slot,
 	) -> Result<Self::EpochData, ConsensusError> {
-		authorities::<_, AuthorityPair, SignedSidechainBlock, ParentchainBlock::Header>(
+		slot_authorities::<_, AuthorityPair, SignedSidechainBlock, ParentchainBlock::Header>(
 			&self.ocall_api,
 			header,
 			shard,
+			self.slot_claim_config.is_weighted(self.next_block_number()),
 		)
 	}
 
@@ -216,7 +250,12 @@ impl<
 		slot: Slot,
 		epoch_data: &Self::EpochData,
 	) -> Option<Self::Claim> {
-		let expected_author = slot_author::<AuthorityPair>(slot, epoch_data)?;
+		let expected_author = slot_author_with_fallback::<AuthorityPair>(
+			slot,
+			self.last_block.map(|(_, slot)| slot),
+			self.slot_claim_config.fallback_delay(self.next_block_number()),
+			epoch_data,
+		)?;
 
 		if expected_author == &self.authority_pair.public() {
 			log::info!(target: self.logging_target(), "Claiming slot ({})", *slot);
@@ -265,32 +304,14 @@ impl<
 		Ok(maybe_parentchain_block.map(|b| b.block.header().clone()))
 	}
 
-	fn import_target_a_parentchain_blocks_until(
+	fn import_target_parentchain_blocks_until(
 		&self,
+		index: TargetParentchainIndex,
 		parentchain_header_hash: &<ParentchainBlock::Header as ParentchainHeaderTrait>::Hash,
 	) -> Result<Option<ParentchainBlock::Header>, ConsensusError> {
-		log::trace!(target: self.logging_target(), "import TargetA blocks until {}", hex_encode(parentchain_header_hash.encode().as_ref()));
+		log::trace!(target: self.logging_target(), "import Target({}) blocks until {}", index, hex_encode(parentchain_header_hash.encode().as_ref()));
 		let maybe_parentchain_block = self
-			.maybe_parentchain_target_a_import_trigger
-			.clone()
-			.ok_or_else(|| ConsensusError::Other("no target_a assigned".into()))?
-			.import_until(|parentchain_block| {
-				parentchain_block.block.hash() == *parentchain_header_hash
-			})
-			.map_err(|e| ConsensusError::Other(e.into()))?;
-
-		Ok(maybe_parentchain_block.map(|b| b.block.header().clone()))
-	}
-
-	fn import_target_b_parentchain_blocks_until(
-		&self,
-		parentchain_header_hash: &<ParentchainBlock::Header as ParentchainHeaderTrait>::Hash,
-	) -> Result<Option<ParentchainBlock::Header>, ConsensusError> {
-		log::trace!(target: self.logging_target(), "import TargetB blocks until {}", hex_encode(parentchain_header_hash.encode().as_ref()));
-		let maybe_parentchain_block = self
-			.maybe_parentchain_target_b_import_trigger
-			.clone()
-			.ok_or_else(|| ConsensusError::Other("no target_b assigned".into()))?
+			.target_import_trigger(index)?
 			.import_until(|parentchain_block| {
 				parentchain_block.block.hash() == *parentchain_header_hash
 			})
@@ -310,30 +331,20 @@ impl<
 		Ok(maybe_parentchain_block.map(|b| b.block.header().clone()))
 	}
 
-	fn peek_latest_target_a_parentchain_header(
+	fn peek_latest_target_parentchain_header(
 		&self,
+		index: TargetParentchainIndex,
 	) -> Result<Option<ParentchainBlock::Header>, ConsensusError> {
 		let maybe_parentchain_block = self
-			.maybe_parentchain_target_a_import_trigger
-			.clone()
-			.ok_or_else(|| ConsensusError::Other("no target_a assigned".into()))?
+			.target_import_trigger(index)?
 			.peek_latest()
 			.map_err(|e| ConsensusError::Other(format!("{:?}", e).into()))?;
 
 		Ok(maybe_parentchain_block.map(|b| b.block.header().clone()))
 	}
 
-	fn peek_latest_target_b_parentchain_header(
-		&self,
-	) -> Result<Option<ParentchainBlock::Header>, ConsensusError> {
-		let maybe_parentchain_block = self
-			.maybe_parentchain_target_b_import_trigger
-			.clone()
-			.ok_or_else(|| ConsensusError::Other("no target_b assigned".into()))?
-			.peek_latest()
-			.map_err(|e| ConsensusError::Other(format!("{:?}", e).into()))?;
-
-		Ok(maybe_parentchain_block.map(|b| b.block.header().clone()))
+	fn target_parentchain_indices(&self) -> Vec<TargetParentchainIndex> {
+		self.parentchain_target_import_triggers.keys().copied().collect()
 	}
 }
 
@@ -375,10 +386,40 @@ where
 		.collect())
 }
 
-pub enum AnyImportTrigger<Integritee, TargetA, TargetB> {
+/// Authorities together with their weight for the slot assignment.
+///
+/// All authorities have the same weight, unless the slots are `weighted` by stake.
+fn slot_authorities<ValidateerFetcher, P, SignedSidechainBlock, ParentchainHeader>(
+	ocall_api: &ValidateerFetcher,
+	header: &ParentchainHeader,
+	shard: ShardIdentifierFor<SignedSidechainBlock>,
+	weighted: bool,
+) -> Result<Vec<(AuthorityId<P>, u64)>, ConsensusError>
+where
+	ValidateerFetcher: ValidateerFetch + EnclaveOnChainOCallApi,
+	P: Pair,
+	P::Public: UncheckedFrom<[u8; 32]>,
+	ParentchainHeader: ParentchainHeaderTrait<Hash = H256>,
+	SignedSidechainBlock: its_primitives::traits::SignedBlock,
+{
+	if !weighted {
+		return Ok(authorities::<_, P, SignedSidechainBlock, _>(ocall_api, header, shard)?
+			.into_iter()
+			.map(|authority| (authority, DEFAULT_VALIDATEER_WEIGHT))
+			.collect())
+	}
+
+	Ok(ocall_api
+		.current_weighted_validateers::<ParentchainHeader, SignedSidechainBlock>(header, shard)
+		.map_err(|e| ConsensusError::CouldNotGetAuthorities(e.to_string()))?
+		.iter()
+		.map(|(account, weight)| (P::Public::unchecked_from(*account.as_ref()), *weight))
+		.collect())
+}
+
+pub enum AnyImportTrigger<Integritee, Target> {
 	Integritee(Integritee),
-	TargetA(TargetA),
-	TargetB(TargetB),
+	Target(Target),
 }
 
 #[cfg(test)]
@@ -407,8 +448,7 @@ mod tests {
 			Keyring::Alice.pair(),
 			onchain_mock,
 			trigger_parentchain_import,
-			None,
-			None,
+			Default::default(),
 			EnvironmentMock,
 		)
 	}
@@ -425,8 +465,7 @@ mod tests {
 			duration: SLOT_DURATION,
 			ends_at: now + SLOT_DURATION,
 			last_imported_integritee_parentchain_head: header.clone(),
-			maybe_last_imported_target_a_parentchain_head: None,
-			maybe_last_imported_target_b_parentchain_head: None,
+			last_imported_target_parentchain_heads: Default::default(),
 		}
 	}
 
@@ -467,10 +506,17 @@ mod tests {
 		)
 	}
 
+	fn unweighted(authorities: Vec<Public>) -> Vec<(Public, u64)> {
+		authorities.into_iter().map(|a| (a, 1)).collect()
+	}
+
 	#[test]
 	fn current_authority_should_claim_its_slot() {
-		let authorities =
-			vec![Keyring::Bob.public(), Keyring::Charlie.public(), Keyring::Alice.public()];
+		let authorities = unweighted(vec![
+			Keyring::Bob.public(),
+			Keyring::Charlie.public(),
+			Keyring::Alice.public(),
+		]);
 		let aura = get_default_aura();
 		let header = ParentchainHeaderBuilder::default().build();
 
@@ -488,7 +534,7 @@ mod tests {
 	#[test]
 	fn current_authority_should_claim_all_slots() {
 		let header = ParentchainHeaderBuilder::default().build();
-		let authorities = default_authorities();
+		let authorities = unweighted(default_authorities());
 		let aura = get_default_aura().with_claim_strategy(SlotClaimStrategy::Always);
 
 		assert!(aura.claim_slot(&header, 0.into(), &authorities).is_some());
@@ -498,6 +544,49 @@ mod tests {
 		assert!(aura.claim_slot(&header, 3.into(), &authorities).is_some());
 	}
 
+	#[test]
+	fn current_authority_should_claim_slots_proportional_to_weight() {
+		let authorities = vec![(Keyring::Bob.public(), 1), (Keyring::Alice.public(), 2)];
+		let aura = get_default_aura();
+		let header = ParentchainHeaderBuilder::default().build();
+
+		assert!(aura.claim_slot(&header, 0.into(), &authorities).is_none());
+		assert!(aura.claim_slot(&header, 1.into(), &authorities).is_some());
+		assert!(aura.claim_slot(&header, 2.into(), &authorities).is_some());
+		assert!(aura.claim_slot(&header, 3.into(), &authorities).is_none());
+	}
+
+	#[test]
+	fn fallback_authority_should_claim_slot_of_offline_author() {
+		let authorities = unweighted(vec![
+			Keyring::Bob.public(),
+			Keyring::Alice.public(),
+			Keyring::Charlie.public(),
+		]);
+		let header = ParentchainHeaderBuilder::default().build();
+
+		let config = SlotClaimConfig {
+			fallback_authors_from: Some(5),
+			fallback_delay: 2,
+			..Default::default()
+		};
+
+		// Slot 3 belongs to Bob and Alice is the next in line.
+		let aura = get_default_aura().with_slot_claim_config(config, Some((4, 2.into())));
+		assert!(aura.claim_slot(&header, 3.into(), &authorities).is_none());
+
+		let aura = get_default_aura().with_slot_claim_config(config, Some((4, 0.into())));
+		assert!(aura.claim_slot(&header, 3.into(), &authorities).is_some());
+
+		// Fallback authors are not active yet.
+		let aura = get_default_aura().with_slot_claim_config(config, Some((3, 0.into())));
+		assert!(aura.claim_slot(&header, 3.into(), &authorities).is_none());
+
+		let aura = get_default_aura()
+			.with_slot_claim_config(SlotClaimConfig::default(), Some((4, 0.into())));
+		assert!(aura.claim_slot(&header, 3.into(), &authorities).is_none());
+	}
+
 	#[test]
 	fn on_slot_returns_block() {
 		let _ = env_logger::builder().is_test(true).try_init();
@@ -543,8 +632,7 @@ mod tests {
 			duration: nano_dur,
 			ends_at: now + nano_dur,
 			last_imported_integritee_parentchain_head: ParentchainHeaderBuilder::default().build(),
-			maybe_last_imported_target_a_parentchain_head: None,
-			maybe_last_imported_target_b_parentchain_head: None,
+			last_imported_target_parentchain_heads: Default::default(),
 		};
 
 		let result = PerShardSlotWorkerScheduler::on_slot(
@@ -19,12 +19,12 @@ use crate::slot_proposer::{ExternalitiesFor, SlotProposer};
 use codec::Encode;
 use finality_grandpa::BlockNumberOps;
 use ita_stf::{Getter, TrustedCallSigned};
-use itp_sgx_externalities::{SgxExternalitiesTrait, StateHash};
+use itp_sgx_externalities::{SgxExternalities, SgxExternalitiesTrait, StateHash};
 use itp_stf_executor::traits::StateUpdateProposer;
 use itp_top_pool_author::traits::AuthorApi;
 use itp_types::H256;
 use its_block_composer::ComposeBlock;
-use its_consensus_common::{Environment, Error as ConsensusError};
+use its_consensus_common::{Environment, Error as ConsensusError, ObserveImportedState};
 use its_primitives::traits::{
 	Block as SidechainBlockTrait, Header as HeaderTrait, ShardIdentifierFor,
 	SignedBlock as SignedSidechainBlockTrait,
@@ -34,7 +34,7 @@ use sp_runtime::{
 	traits::{Block, NumberFor},
 	MultiSignature,
 };
-use std::{marker::PhantomData, sync::Arc};
+use std::{marker::PhantomData, sync::Arc, vec::Vec};
 
 ///! `ProposerFactory` instance containing all the data to create the `SlotProposer` for the
 /// next `Slot`.
@@ -42,6 +42,7 @@ pub struct ProposerFactory<ParentchainBlock: Block, TopPoolAuthor, StfExecutor,
 	top_pool_author: Arc<TopPoolAuthor>,
 	stf_executor: Arc<StfExecutor>,
 	block_composer: Arc<BlockComposer>,
+	proposed_state_observers: Vec<Arc<dyn ObserveImportedState<SgxExternalities>>>,
 	_phantom: PhantomData<ParentchainBlock>,
 }
 
@@ -57,9 +58,19 @@ impl<ParentchainBlock: Block, TopPoolAuthor, StfExecutor, BlockComposer>
 			top_pool_author: top_pool_executor,
 			stf_executor,
 			block_composer,
+			proposed_state_observers: Vec::new(),
 			_phantom: Default::default(),
 		}
 	}
+
+	/// Hand the state of every proposed block to the observer, after the ones added before.
+	pub fn with_proposed_state_observer(
+		mut self,
+		proposed_state_observer: Arc<dyn ObserveImportedState<SgxExternalities>>,
+	) -> Self {
+		self.proposed_state_observers.push(proposed_state_observer);
+		self
+	}
 }
 
 impl<
@@ -79,7 +90,10 @@ where
 		HeaderTrait<ShardIdentifier = H256>,
 	TopPoolAuthor:
 		AuthorApi<H256, ParentchainBlock::Hash, TrustedCallSigned, Getter> + Send + Sync + 'static,
-	StfExecutor: StateUpdateProposer<TrustedCallSigned, Getter> + Send + Sync + 'static,
+	StfExecutor: StateUpdateProposer<TrustedCallSigned, Getter, Externalities = SgxExternalities>
+		+ Send
+		+ Sync
+		+ 'static,
 	ExternalitiesFor<StfExecutor>:
 		SgxExternalitiesTrait + SidechainState + SidechainSystemExt + StateHash,
 	<ExternalitiesFor<StfExecutor> as SgxExternalitiesTrait>::SgxExternalitiesType: Encode,
@@ -111,6 +125,7 @@ where
 			block_composer: self.block_composer.clone(),
 			parentchain_header: parent_header,
 			shard,
+			proposed_state_observers: self.proposed_state_observers.clone(),
 			_phantom: PhantomData,
 		})
 	}
@@ -18,13 +18,13 @@
 use codec::Encode;
 use finality_grandpa::BlockNumberOps;
 use ita_stf::{Getter, TrustedCallSigned};
-use itp_sgx_externalities::{SgxExternalitiesTrait, StateHash};
+use itp_sgx_externalities::{SgxExternalities, SgxExternalitiesTrait, StateHash};
 use itp_stf_executor::traits::StateUpdateProposer;
 
 use itp_top_pool_author::traits::AuthorApi;
 use itp_types::H256;
 use its_block_composer::ComposeBlock;
-use its_consensus_common::{Error as ConsensusError, Proposal, Proposer};
+use its_consensus_common::{Error as ConsensusError, ObserveImportedState, Proposal, Proposer};
 use its_primitives::traits::{
 	Block as SidechainBlockTrait, Header as HeaderTrait, ShardIdentifierFor,
 	SignedBlock as SignedSidechainBlockTrait,
@@ -51,6 +51,7 @@ pub struct SlotProposer<
 	pub(crate) block_composer: Arc<BlockComposer>,
 	pub(crate) parentchain_header: ParentchainBlock::Header,
 	pub(crate) shard: ShardIdentifierFor<SignedSidechainBlock>,
+	pub(crate) proposed_state_observers: Vec<Arc<dyn ObserveImportedState<SgxExternalities>>>,
 	pub(crate) _phantom: PhantomData<ParentchainBlock>,
 }
 
@@ -65,7 +66,7 @@ where
 	SignedSidechainBlock::Block: SidechainBlockTrait<Public = sp_core::ed25519::Public>,
 	<<SignedSidechainBlock as SignedSidechainBlockTrait>::Block as SidechainBlockTrait>::HeaderType:
 		HeaderTrait<ShardIdentifier = H256>,
-	StfExecutor: StateUpdateProposer<TrustedCallSigned, Getter>,
+	StfExecutor: StateUpdateProposer<TrustedCallSigned, Getter, Externalities = SgxExternalities>,
 	ExternalitiesFor<StfExecutor>:
 		SgxExternalitiesTrait + SidechainState + SidechainSystemExt + StateHash,
 	<ExternalitiesFor<StfExecutor> as SgxExternalitiesTrait>::SgxExternalitiesType: Encode,
@@ -85,6 +86,7 @@ where
 	/// 1) Retrieve all trusted calls from the top pool.
 	/// 2) Calculate a new state that will be proposed in the sidechain block.
 	/// 3) Compose the sidechain block and the parentchain confirmation.
+	/// 4) Hand the state of the block to the observer, like the state of an imported block.
 	fn propose(
 		&self,
 		max_duration: Duration,
@@ -147,6 +149,15 @@ where
 			)
 			.map_err(|e| ConsensusError::Other(e.to_string().into()))?;
 
+		// 4) Our own blocks are not imported, so the observers are notified here.
+		for observer in &self.proposed_state_observers {
+			observer.on_state_imported(
+				self.shard,
+				sidechain_block.block().hash(),
+				&batch_execution_result.state_after_execution,
+			);
+		}
+
 		println!(
             "[Sidechain] propose block {} summary: executed {}, failed {}, from {} in queue in {}ms",
             sidechain_block.block().header().block_number(),
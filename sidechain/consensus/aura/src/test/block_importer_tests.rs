@@ -22,6 +22,7 @@ use itc_parentchain_block_import_dispatcher::trigger_parentchain_block_import_mo
 use itc_parentchain_test::{ParentchainBlockBuilder, ParentchainHeaderBuilder};
 use itp_sgx_crypto::{aes::Aes, mocks::KeyRepositoryMock, StateCrypto};
 use itp_sgx_externalities::SgxExternalitiesDiffType;
+use itp_stf_executor::state_commitment::StateCommitment;
 use itp_stf_state_handler::handle_state::HandleState;
 use itp_test::mock::{
 	handle_state_mock::HandleStateMock,
@@ -97,6 +98,7 @@ fn test_fixtures(
 		parentchain_block_import_trigger,
 		ocall_api,
 		SidechainBlockHeaderCache::default().into(),
+		Default::default(),
 	);
 
 	(block_importer, state_handler, top_pool_author)
@@ -117,16 +119,32 @@ fn empty_encrypted_state_update(state_handler: &HandleStateMock) -> Vec<u8> {
 	state_update
 }
 
+fn current_state_root(state_handler: &HandleStateMock) -> H256 {
+	let (state, _) = state_handler.load_cloned(&shard()).unwrap();
+	state.state_root().unwrap()
+}
+
 fn signed_block(
 	parentchain_header: &ParentchainHeader,
 	state_handler: &HandleStateMock,
 	signer: Pair,
+) -> SignedSidechainBlock {
+	let state_root = current_state_root(state_handler);
+	signed_block_with_state_root(parentchain_header, state_handler, signer, state_root)
+}
+
+fn signed_block_with_state_root(
+	parentchain_header: &ParentchainHeader,
+	state_handler: &HandleStateMock,
+	signer: Pair,
+	state_root: H256,
 ) -> SignedSidechainBlock {
 	let state_update = empty_encrypted_state_update(state_handler);
 
 	let header = SidechainHeaderBuilder::default()
 		.with_parent_hash(H256::default())
 		.with_shard(shard())
+		.with_state_root(state_root)
 		.build();
 
 	let block_data = SidechainBlockDataBuilder::default()
@@ -174,6 +192,7 @@ fn block_import_with_invalid_signature_fails() {
 	let header = SidechainHeaderBuilder::default()
 		.with_parent_hash(H256::default())
 		.with_shard(shard())
+		.with_state_root(current_state_root(state_handler.as_ref()))
 		.build();
 
 	let block_data = SidechainBlockDataBuilder::default()
@@ -198,6 +217,55 @@ fn block_import_with_invalid_signature_fails() {
 		.is_err());
 }
 
+#[test]
+fn block_import_with_wrong_state_root_fails() {
+	let parentchain_header = ParentchainHeaderBuilder::default().build();
+	let (block_importer, state_handler, _) =
+		test_fixtures_with_default_import_trigger(&parentchain_header);
+	let signed_sidechain_block = signed_block_with_state_root(
+		&parentchain_header,
+		state_handler.as_ref(),
+		default_authority(),
+		H256::random(),
+	);
+
+	assert_matches!(
+		block_importer.import_block(signed_sidechain_block, &parentchain_header),
+		Err(ConsensusError::BadSidechainBlock(..))
+	);
+}
+
+#[test]
+fn block_import_of_header_version_0_without_state_root_works() {
+	let parentchain_header = ParentchainHeaderBuilder::default().build();
+	let (block_importer, state_handler, _) =
+		test_fixtures_with_default_import_trigger(&parentchain_header);
+	let state_update = empty_encrypted_state_update(state_handler.as_ref());
+
+	let header = SidechainHeaderBuilder::default()
+		.with_version(0)
+		.with_parent_hash(H256::default())
+		.with_shard(shard())
+		.build();
+
+	let block_data = SidechainBlockDataBuilder::default()
+		.with_timestamp(now_as_millis())
+		.with_layer_one_head(parentchain_header.hash())
+		.with_signer(default_authority())
+		.with_payload(state_update)
+		.build();
+
+	let signed_sidechain_block = SidechainBlockBuilder::default()
+		.with_header(header)
+		.with_block_data(block_data)
+		.with_signer(default_authority())
+		.build_signed();
+
+	block_importer
+		.import_block(signed_sidechain_block, &parentchain_header)
+		.unwrap();
+}
+
 #[test]
 fn block_import_with_invalid_parentchain_block_fails() {
 	let parentchain_header_invalid = ParentchainHeaderBuilder::default().with_number(2).build();
@@ -41,5 +41,4 @@ pub type TestAura = Aura<
 	OnchainMock,
 	TriggerParentchainBlockImportMock<SignedBlock<ParentchainBlock>>,
 	TriggerParentchainBlockImportMock<SignedBlock<ParentchainBlock>>,
-	TriggerParentchainBlockImportMock<SignedBlock<ParentchainBlock>>,
 >;
@@ -15,13 +15,16 @@
 
 */
 
-use crate::{authorities, EnclaveOnChainOCallApi, ShardIdentifierFor};
+use crate::{slot_authorities, EnclaveOnChainOCallApi, ShardIdentifierFor};
 use core::marker::PhantomData;
-use its_block_verification::verify_sidechain_block;
+use its_block_verification::verify_weighted_sidechain_block;
 use its_consensus_common::{Error as ConsensusError, Verifier};
 use its_primitives::{
-	traits::{Block as SidechainBlockTrait, SignedBlock as SignedSidechainBlockTrait},
-	types::block::BlockHash,
+	traits::{
+		Block as SidechainBlockTrait, Header as HeaderTrait,
+		SignedBlock as SignedSidechainBlockTrait,
+	},
+	types::{block::BlockHash, slot_claims::SlotClaimConfig},
 };
 use its_validateer_fetch::ValidateerFetch;
 use sp_core::crypto::UncheckedFrom;
@@ -36,6 +39,7 @@ where
 {
 	slot_duration: Duration,
 	last_sidechain_block: Option<SignedSidechainBlock::Block>,
+	slot_claim_config: SlotClaimConfig,
 	_phantom: PhantomData<(AuthorityPair, ParentchainBlock, Context)>,
 }
 
@@ -49,7 +53,18 @@ where
 		slot_duration: Duration,
 		last_sidechain_block: Option<SignedSidechainBlock::Block>,
 	) -> Self {
-		Self { slot_duration, last_sidechain_block, _phantom: Default::default() }
+		Self {
+			slot_duration,
+			last_sidechain_block,
+			slot_claim_config: Default::default(),
+			_phantom: Default::default(),
+		}
+	}
+
+	/// Verify the block authors according to the slot claim rules that are active for the block.
+	pub fn with_slot_claim_config(mut self, slot_claim_config: SlotClaimConfig) -> Self {
+		self.slot_claim_config = slot_claim_config;
+		self
 	}
 }
 
@@ -76,19 +91,24 @@ where
 		shard: ShardIdentifierFor<SignedSidechainBlock>,
 		ctx: &Self::Context,
 	) -> Result<Self::BlockImportParams, ConsensusError> {
-		let authorities = authorities::<
-			_,
-			AuthorityPair,
-			SignedSidechainBlock,
-			ParentchainBlock::Header,
-		>(ctx, parentchain_header, shard)?;
+		let block_number = signed_block.block().header().block_number();
+		let authorities =
+			slot_authorities::<_, AuthorityPair, SignedSidechainBlock, ParentchainBlock::Header>(
+				ctx,
+				parentchain_header,
+				shard,
+				self.slot_claim_config.is_weighted(block_number),
+			)?;
 
-		Ok(verify_sidechain_block::<AuthorityPair, ParentchainBlock, SignedSidechainBlock>(
-			signed_block,
-			self.slot_duration,
-			&self.last_sidechain_block,
-			parentchain_header,
-			&authorities,
-		)?)
+		Ok(
+			verify_weighted_sidechain_block::<AuthorityPair, ParentchainBlock, SignedSidechainBlock>(
+				signed_block,
+				self.slot_duration,
+				&self.last_sidechain_block,
+				parentchain_header,
+				&authorities,
+				self.slot_claim_config.fallback_delay(block_number),
+			)?,
+		)
 	}
 }
@@ -21,6 +21,7 @@ use crate::{Error, Verifier};
 use codec::Decode;
 use itp_ocall_api::EnclaveSidechainOCallApi;
 use itp_sgx_crypto::StateCrypto;
+use itp_types::H256;
 use its_primitives::traits::{
 	Block as SidechainBlockTrait, BlockData, Header as HeaderTrait, ShardIdentifierFor,
 	SignedBlock as SignedSidechainBlockTrait,
@@ -86,6 +87,31 @@ where
 	/// Key that is used for state encryption.
 	fn state_key(&self) -> Result<Self::StateCrypto, Error>;
 
+	/// Replace the state key by `state_key`, which no longer accepts legacy state diffs.
+	///
+	/// Called once a block with a state diff in the current format has been imported. From then
+	/// on, the shard's validateers have completed the migration, and a legacy state diff can only
+	/// be a downgrade attempt.
+	fn complete_state_encryption_migration(
+		&self,
+		state_key: Self::StateCrypto,
+	) -> Result<(), Error>;
+
+	/// Root of the state trie, as it is committed in the header of a sidechain block.
+	///
+	/// The state is only committed with `commit_state_root`, once the block has been imported.
+	fn state_root(
+		&self,
+		shard: &ShardIdentifierFor<SignedSidechainBlock>,
+		state: &Self::SidechainState,
+	) -> Result<H256, Error>;
+
+	/// Commit the state of the shard whose root was last computed by `state_root`.
+	fn commit_state_root(
+		&self,
+		shard: &ShardIdentifierFor<SignedSidechainBlock>,
+	) -> Result<(), Error>;
+
 	/// Getter for the context.
 	fn get_context(&self) -> &Self::Context;
 
@@ -152,6 +178,13 @@ where
 			self.import_parentchain_block(&sidechain_block, parentchain_header)?;
 
 		let state_key = self.state_key()?;
+		let migrated_state_key = if state_key
+			.is_legacy_ciphertext(block_import_params.block().block_data().encrypted_state_diff())
+		{
+			None
+		} else {
+			state_key.without_legacy_fallback()
+		};
 
 		let state_update_start_time = Instant::now();
 		self.apply_state_update(&shard, |mut state| {
@@ -168,10 +201,39 @@ where
 
 			state.apply_state_update(&update).map_err(|e| Error::Other(e.into()))?;
 
+			let header = block_import_params.block().header();
+			let state_root = self.state_root(&shard, &state)?;
+			if header.version() == 0 {
+				// Headers of version 0 don't commit to the state, and are only accepted until the
+				// first block with a versioned header has been imported.
+				if state.get_last_block().map_or(false, |b| b.header().version() > 0) {
+					return Err(Error::BadSidechainBlock(
+						block_import_params.block().hash(),
+						"Header of version 0 after a versioned header".into(),
+					))
+				}
+			} else if state_root != header.state_root() {
+				return Err(Error::BadSidechainBlock(
+					block_import_params.block().hash(),
+					format!(
+						"State root {:?} does not match the one in the header {:?}",
+						state_root,
+						header.state_root()
+					),
+				))
+			}
+
 			state.set_last_block(block_import_params.block());
 
 			Ok(state)
 		})?;
+		self.commit_state_root(&shard)?;
+		if let Some(state_key) = migrated_state_key {
+			info!(
+				"Imported first block with an authenticated state diff, dropping legacy state key"
+			);
+			self.complete_state_encryption_migration(state_key)?;
+		}
 		info!(
 			"Applying state update from block {} took {} ms",
 			block_number,
@@ -197,7 +259,11 @@ fn state_update_from_encrypted<Key: StateCrypto, StateUpdate: Decode>(
 	key: Key,
 ) -> Result<StateUpdate, Error> {
 	let mut payload: Vec<u8> = encrypted.to_vec();
-	key.decrypt(&mut payload).map_err(|e| Error::Other(format!("{:?}", e).into()))?;
+	// The block signature is already verified, so it's safe to accept the payloads of
+	// validateers that still use the legacy (unauthenticated) state encryption, until the
+	// migration has been completed.
+	key.decrypt_legacy_compatible(&mut payload)
+		.map_err(|e| Error::Other(format!("{:?}", e).into()))?;
 
 	Ok(Decode::decode(&mut payload.as_slice())?)
 }
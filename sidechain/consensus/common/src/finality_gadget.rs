@@ -0,0 +1,574 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Finality of sidechain blocks among the validateers of a shard.
+//!
+//! Every validateer signs a finality vote for the latest sidechain block it has imported and
+//! gossips it to its peers. As soon as a supermajority of the validateers voted for the very same
+//! block, the block and all of its ancestors are final, without having to wait for the
+//! confirmation on the parentchain. The collected votes form the justification of the block.
+//!
+//! Votes for different blocks are never combined, a vote for a newer block does not prove that
+//! the voter imported the same chain. The height of our own latest vote is sealed before the vote
+//! is handed out, such that we never sign two blocks of the same height, not even after a restart.
+
+#[cfg(feature = "sgx")]
+use std::sync::SgxRwLock as RwLock;
+
+#[cfg(feature = "std")]
+use std::sync::RwLock;
+
+use crate::error::{Error, Result};
+use itp_sgx_io::SealedIO;
+use its_primitives::types::{
+	justification::{supermajority_threshold, FinalityVote, Justification, SignedFinalityVote},
+	BlockHash, BlockNumber, ShardIdentifier, Signature,
+};
+use log::*;
+use sp_core::{ed25519, Pair};
+use std::{
+	collections::{BTreeMap, BTreeSet},
+	vec::Vec,
+};
+
+#[cfg(feature = "sgx")]
+pub use sgx::*;
+
+/// Max number of not yet finalized blocks we keep the votes of, per shard.
+pub const MAX_PENDING_BLOCKS: usize = 256;
+
+/// File name of the sealed heights of our own latest finality votes.
+pub const SEALED_OWN_FINALITY_VOTES_FILE: &str = "finality_votes_sealed.bin";
+
+/// Height of our own latest finality vote, per shard.
+pub type OwnFinalityVotes = BTreeMap<ShardIdentifier, BlockNumber>;
+
+/// Trait to vote on and finalize sidechain blocks.
+pub trait FinalizeBlocks {
+	/// Sign a finality vote for an imported sidechain block.
+	///
+	/// Returns `None` if we already voted for this block or a newer one of the shard.
+	fn vote<Signer>(
+		&self,
+		signer: &Signer,
+		shard: ShardIdentifier,
+		block_number: BlockNumber,
+		block_hash: BlockHash,
+	) -> Result<Option<SignedFinalityVote>>
+	where
+		Signer: Pair<Public = ed25519::Public>,
+		Signature: From<Signer::Signature>;
+
+	/// Count the votes of the given validateers, ignoring any others.
+	///
+	/// Returns the justifications of the blocks that are newly finalized by the votes.
+	fn import_votes(
+		&self,
+		votes: Vec<SignedFinalityVote>,
+		validateers: &[ed25519::Public],
+	) -> Result<Vec<Justification>>;
+
+	/// Resume from an earlier justification, e.g. the one of the finalized head in the storage.
+	///
+	/// Returns `false` if the justification does not verify or is not newer than our finalized head.
+	fn import_justification(
+		&self,
+		justification: Justification,
+		validateers: &[ed25519::Public],
+	) -> Result<bool>;
+
+	/// Justification of the latest finalized block of a shard.
+	fn finalized_head(&self, shard: &ShardIdentifier) -> Result<Option<Justification>>;
+}
+
+#[derive(Default)]
+struct ShardFinality {
+	finalized: Option<Justification>,
+	last_own_vote: Option<BlockNumber>,
+	/// Votes for the blocks which are not finalized yet, by block and signer.
+	votes: BTreeMap<(BlockNumber, BlockHash), BTreeMap<ed25519::Public, SignedFinalityVote>>,
+}
+
+impl ShardFinality {
+	fn finalized_number(&self) -> Option<BlockNumber> {
+		self.finalized.as_ref().map(|j| j.block_number())
+	}
+
+	fn is_finalized(&self, block_number: BlockNumber) -> bool {
+		self.finalized_number().map_or(false, |n| n >= block_number)
+	}
+
+	/// Returns `false` if the signer already voted for this block.
+	fn add_vote(&mut self, signed_vote: SignedFinalityVote) -> bool {
+		let block = (signed_vote.vote.block_number, signed_vote.vote.block_hash);
+		let is_new = self
+			.votes
+			.entry(block)
+			.or_default()
+			.insert(signed_vote.signer, signed_vote)
+			.is_none();
+
+		while self.votes.len() > MAX_PENDING_BLOCKS {
+			if let Some(oldest) = self.votes.keys().next().copied() {
+				self.votes.remove(&oldest);
+			}
+		}
+		is_new
+	}
+
+	/// Finalize the newest block, which a supermajority of the validateers voted for.
+	fn finalize(
+		&mut self,
+		shard: ShardIdentifier,
+		validateers: &[ed25519::Public],
+	) -> Option<Justification> {
+		let threshold = supermajority_threshold(validateers.len());
+
+		// The validateer set might have changed since earlier votes were counted.
+		let (block_number, block_hash, votes) =
+			self.votes.iter().rev().find_map(|((block_number, block_hash), votes)| {
+				let votes: Vec<_> = votes
+					.iter()
+					.filter(|(signer, _)| validateers.contains(signer))
+					.map(|(_, vote)| vote.clone())
+					.collect();
+				(votes.len() >= threshold).then_some((*block_number, *block_hash, votes))
+			})?;
+
+		let justification =
+			Justification { vote: FinalityVote { shard, block_number, block_hash }, votes };
+		self.set_finalized(justification.clone());
+		Some(justification)
+	}
+
+	fn set_finalized(&mut self, justification: Justification) {
+		let block_number = justification.block_number();
+		self.votes.retain(|(n, _), _| *n > block_number);
+		self.finalized = Some(justification);
+	}
+}
+
+/// Keeps track of the finality votes of all shards.
+pub struct FinalityGadget<OwnVotesSeal> {
+	own_votes_seal: OwnVotesSeal,
+	shards: RwLock<BTreeMap<ShardIdentifier, ShardFinality>>,
+}
+
+impl<OwnVotesSeal> FinalityGadget<OwnVotesSeal>
+where
+	OwnVotesSeal: SealedIO<Unsealed = OwnFinalityVotes>,
+	Error: From<OwnVotesSeal::Error>,
+{
+	/// Restores the heights of our own latest votes from the seal.
+	///
+	/// Fails if they exist but can't be unsealed, as we could sign a second block of the same
+	/// height otherwise.
+	pub fn new(own_votes_seal: OwnVotesSeal) -> Result<Self> {
+		let own_votes = own_votes_seal.unseal()?;
+		let shards = own_votes
+			.into_iter()
+			.map(|(shard, block_number)| {
+				(shard, ShardFinality { last_own_vote: Some(block_number), ..Default::default() })
+			})
+			.collect();
+		Ok(FinalityGadget { own_votes_seal, shards: RwLock::new(shards) })
+	}
+}
+
+impl<OwnVotesSeal> FinalizeBlocks for FinalityGadget<OwnVotesSeal>
+where
+	OwnVotesSeal: SealedIO<Unsealed = OwnFinalityVotes>,
+	Error: From<OwnVotesSeal::Error>,
+{
+	fn vote<Signer>(
+		&self,
+		signer: &Signer,
+		shard: ShardIdentifier,
+		block_number: BlockNumber,
+		block_hash: BlockHash,
+	) -> Result<Option<SignedFinalityVote>>
+	where
+		Signer: Pair<Public = ed25519::Public>,
+		Signature: From<Signer::Signature>,
+	{
+		let mut shards = self.shards.write().map_err(|_| Error::LockPoisoning)?;
+
+		// Never sign two different blocks of the same height.
+		let last_own_vote = shards.get(&shard).and_then(|s| s.last_own_vote);
+		if last_own_vote.map_or(false, |n| n >= block_number) {
+			return Ok(None)
+		}
+
+		// The vote must not leave the enclave before its height is sealed.
+		let mut own_votes: OwnFinalityVotes = shards
+			.iter()
+			.filter_map(|(shard, s)| s.last_own_vote.map(|n| (*shard, n)))
+			.collect();
+		own_votes.insert(shard, block_number);
+		self.own_votes_seal.seal(&own_votes)?;
+		shards.entry(shard).or_default().last_own_vote = Some(block_number);
+
+		Ok(Some(FinalityVote { shard, block_number, block_hash }.sign(signer)))
+	}
+
+	fn import_votes(
+		&self,
+		votes: Vec<SignedFinalityVote>,
+		validateers: &[ed25519::Public],
+	) -> Result<Vec<Justification>> {
+		let mut shards = self.shards.write().map_err(|_| Error::LockPoisoning)?;
+		let mut voted_shards = BTreeSet::new();
+
+		for signed_vote in votes {
+			if !validateers.contains(&signed_vote.signer) {
+				debug!("Ignoring finality vote of non-validateer {:?}", signed_vote.signer);
+				continue
+			}
+			if !signed_vote.verify_signature() {
+				warn!("Ignoring finality vote with invalid signature: {:?}", signed_vote.vote);
+				continue
+			}
+
+			let shard = signed_vote.vote.shard;
+			let shard_finality = shards.entry(shard).or_default();
+			if shard_finality.is_finalized(signed_vote.vote.block_number) {
+				continue
+			}
+			if shard_finality.add_vote(signed_vote) {
+				voted_shards.insert(shard);
+			}
+		}
+
+		let mut justifications = Vec::new();
+		for shard in voted_shards {
+			let shard_finality = shards.entry(shard).or_default();
+			if let Some(justification) = shard_finality.finalize(shard, validateers) {
+				info!(
+					"Finalized sidechain block {} ({:?}) with {} of {} validateer votes",
+					justification.block_number(),
+					justification.block_hash(),
+					justification.votes.len(),
+					validateers.len()
+				);
+				justifications.push(justification);
+			}
+		}
+
+		Ok(justifications)
+	}
+
+	fn import_justification(
+		&self,
+		justification: Justification,
+		validateers: &[ed25519::Public],
+	) -> Result<bool> {
+		if !justification.verify(validateers) {
+			warn!("Ignoring justification which does not verify: {:?}", justification.vote);
+			return Ok(false)
+		}
+
+		let mut shards = self.shards.write().map_err(|_| Error::LockPoisoning)?;
+		let shard_finality = shards.entry(justification.vote.shard).or_default();
+		if shard_finality.is_finalized(justification.block_number()) {
+			return Ok(false)
+		}
+		shard_finality.set_finalized(justification);
+		Ok(true)
+	}
+
+	fn finalized_head(&self, shard: &ShardIdentifier) -> Result<Option<Justification>> {
+		let shards = self.shards.read().map_err(|_| Error::LockPoisoning)?;
+		Ok(shards.get(shard).and_then(|s| s.finalized.clone()))
+	}
+}
+
+#[cfg(feature = "sgx")]
+pub mod sgx {
+	use super::{OwnFinalityVotes, SEALED_OWN_FINALITY_VOTES_FILE};
+	use crate::error::{Error, Result};
+	use codec::{Decode, Encode};
+	use itp_sgx_io::{seal, unseal, SealedIO};
+	use log::*;
+	use std::{
+		fs,
+		path::{Path, PathBuf},
+	};
+
+	#[derive(Clone, Debug)]
+	pub struct OwnFinalityVotesSeal {
+		base_path: PathBuf,
+	}
+
+	impl OwnFinalityVotesSeal {
+		pub fn new(base_path: PathBuf) -> Self {
+			Self { base_path }
+		}
+
+		pub fn path(&self) -> PathBuf {
+			self.base_path.join(SEALED_OWN_FINALITY_VOTES_FILE)
+		}
+
+		/// Copy which is sealed before the votes file itself is overwritten, see the top pool seal.
+		pub fn staged_path(&self) -> PathBuf {
+			self.base_path.join(format!("{}.staged", SEALED_OWN_FINALITY_VOTES_FILE))
+		}
+	}
+
+	fn unseal_votes(path: &Path) -> Result<OwnFinalityVotes> {
+		Ok(unseal(path).map(|b| Decode::decode(&mut b.as_slice()))??)
+	}
+
+	impl SealedIO for OwnFinalityVotesSeal {
+		type Error = Error;
+		type Unsealed = OwnFinalityVotes;
+
+		fn unseal(&self) -> Result<Self::Unsealed> {
+			if !self.path().exists() && !self.staged_path().exists() {
+				info!("No sealed finality votes found, we have not voted yet");
+				return Ok(OwnFinalityVotes::default())
+			}
+			match unseal_votes(&self.path()) {
+				Ok(votes) => Ok(votes),
+				Err(e) if self.staged_path().exists() => {
+					warn!(
+						"Failed to unseal the finality votes ({:?}), sealing has been interrupted, restoring the staged copy",
+						e
+					);
+					unseal_votes(&self.staged_path())
+				},
+				Err(e) => Err(e),
+			}
+		}
+
+		fn seal(&self, unsealed: &Self::Unsealed) -> Result<()> {
+			unsealed.using_encoded(|bytes| seal(bytes, self.staged_path()))?;
+			unsealed.using_encoded(|bytes| seal(bytes, self.path()))?;
+			Ok(fs::remove_file(self.staged_path())?)
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::test::mocks::own_finality_votes_seal_mock::OwnFinalityVotesSealMock;
+	use sp_core::{ed25519::Pair as Ed25519Pair, H256};
+
+	fn gadget() -> FinalityGadget<OwnFinalityVotesSealMock> {
+		FinalityGadget::new(OwnFinalityVotesSealMock::default()).unwrap()
+	}
+
+	fn validateers(count: u8) -> Vec<Ed25519Pair> {
+		(0..count).map(|i| Ed25519Pair::from_seed(&[i; 32])).collect()
+	}
+
+	fn publics(validateers: &[Ed25519Pair]) -> Vec<ed25519::Public> {
+		validateers.iter().map(|v| v.public()).collect()
+	}
+
+	fn votes_of(
+		validateers: &[Ed25519Pair],
+		shard: ShardIdentifier,
+		block_number: BlockNumber,
+		block_hash: BlockHash,
+	) -> Vec<SignedFinalityVote> {
+		validateers
+			.iter()
+			.map(|v| FinalityVote { shard, block_number, block_hash }.sign(v))
+			.collect()
+	}
+
+	#[test]
+	fn vote_is_only_signed_once_per_block_height() {
+		let gadget = gadget();
+		let signer = Ed25519Pair::from_seed(&[1u8; 32]);
+		let shard = H256::random();
+
+		let vote = gadget.vote(&signer, shard, 2, H256::random()).unwrap().unwrap();
+		assert!(vote.verify_signature());
+		assert_eq!(vote.signer, signer.public());
+
+		assert!(gadget.vote(&signer, shard, 2, H256::random()).unwrap().is_none());
+		assert!(gadget.vote(&signer, shard, 1, H256::random()).unwrap().is_none());
+		assert!(gadget.vote(&signer, shard, 3, H256::random()).unwrap().is_some());
+	}
+
+	#[test]
+	fn own_votes_are_restored_from_the_seal() {
+		let seal = OwnFinalityVotesSealMock::default();
+		let signer = Ed25519Pair::from_seed(&[1u8; 32]);
+		let shard = H256::random();
+
+		let gadget = FinalityGadget::new(seal.clone()).unwrap();
+		assert!(gadget.vote(&signer, shard, 2, H256::random()).unwrap().is_some());
+
+		let restarted = FinalityGadget::new(seal).unwrap();
+		assert!(restarted.vote(&signer, shard, 2, H256::random()).unwrap().is_none());
+		assert!(restarted.vote(&signer, shard, 3, H256::random()).unwrap().is_some());
+	}
+
+	#[test]
+	fn block_is_finalized_by_supermajority() {
+		let gadget = gadget();
+		let validateers = validateers(4);
+		let shard = H256::random();
+		let block_hash = H256::random();
+		let votes = votes_of(&validateers, shard, 7, block_hash);
+
+		let justifications =
+			gadget.import_votes(votes[..2].to_vec(), &publics(&validateers)).unwrap();
+		assert!(justifications.is_empty());
+		assert!(gadget.finalized_head(&shard).unwrap().is_none());
+
+		let justifications =
+			gadget.import_votes(votes[2..3].to_vec(), &publics(&validateers)).unwrap();
+		assert_eq!(justifications.len(), 1);
+		assert_eq!(justifications[0].block_hash(), block_hash);
+		assert!(justifications[0].verify(&publics(&validateers)));
+		assert_eq!(gadget.finalized_head(&shard).unwrap(), Some(justifications[0].clone()));
+
+		// Late votes for an already finalized block do not finalize it again.
+		let justifications =
+			gadget.import_votes(votes[3..].to_vec(), &publics(&validateers)).unwrap();
+		assert!(justifications.is_empty());
+	}
+
+	#[test]
+	fn votes_of_non_validateers_and_invalid_votes_are_ignored() {
+		let gadget = gadget();
+		let validateers = validateers(3);
+		let shard = H256::random();
+		let block_hash = H256::random();
+
+		let mut votes = votes_of(&validateers, shard, 1, block_hash);
+		votes[0].vote.block_number = 2;
+
+		let justifications = gadget.import_votes(votes, &publics(&validateers[1..])).unwrap();
+		assert!(justifications.is_empty());
+	}
+
+	#[test]
+	fn duplicate_votes_are_counted_once() {
+		let gadget = gadget();
+		let validateers = validateers(2);
+		let shard = H256::random();
+		let votes = votes_of(&validateers[..1], shard, 1, H256::random());
+
+		let justifications = gadget
+			.import_votes(vec![votes[0].clone(), votes[0].clone()], &publics(&validateers))
+			.unwrap();
+		assert!(justifications.is_empty());
+	}
+
+	#[test]
+	fn finalizing_a_block_discards_votes_for_older_blocks() {
+		let gadget = gadget();
+		let validateers = validateers(1);
+		let shard = H256::random();
+
+		gadget
+			.import_votes(votes_of(&validateers, shard, 5, H256::random()), &publics(&validateers))
+			.unwrap();
+		let justifications = gadget
+			.import_votes(votes_of(&validateers, shard, 4, H256::random()), &publics(&validateers))
+			.unwrap();
+
+		assert!(justifications.is_empty());
+		assert_eq!(gadget.finalized_head(&shard).unwrap().unwrap().block_number(), 5);
+	}
+
+	#[test]
+	fn votes_for_different_blocks_are_not_combined() {
+		let gadget = gadget();
+		let validateers = validateers(4);
+		let shard = H256::random();
+		let hashes = [H256::random(), H256::random(), H256::random()];
+
+		let votes = vec![
+			votes_of(&validateers[..1], shard, 3, hashes[2]).remove(0),
+			votes_of(&validateers[1..2], shard, 2, hashes[1]).remove(0),
+			votes_of(&validateers[2..3], shard, 1, hashes[0]).remove(0),
+		];
+		let justifications = gadget.import_votes(votes, &publics(&validateers)).unwrap();
+
+		assert!(justifications.is_empty());
+		assert!(gadget.finalized_head(&shard).unwrap().is_none());
+	}
+
+	#[test]
+	fn newest_block_with_supermajority_is_finalized() {
+		let gadget = gadget();
+		let validateers = validateers(4);
+		let shard = H256::random();
+		let hashes = [H256::random(), H256::random()];
+
+		let mut votes = votes_of(&validateers[..3], shard, 1, hashes[0]);
+		votes.extend(votes_of(&validateers[..2], shard, 2, hashes[1]));
+		let justifications = gadget.import_votes(votes, &publics(&validateers)).unwrap();
+
+		assert_eq!(justifications.len(), 1);
+		assert_eq!(justifications[0].block_hash(), hashes[0]);
+
+		// The last validateer catching up finalizes the newest block.
+		let justifications = gadget
+			.import_votes(votes_of(&validateers[3..], shard, 2, hashes[1]), &publics(&validateers))
+			.unwrap();
+		assert_eq!(justifications.len(), 1);
+		assert_eq!(justifications[0].block_hash(), hashes[1]);
+		assert!(justifications[0].verify(&publics(&validateers)));
+	}
+
+	#[test]
+	fn votes_for_forks_are_not_counted_for_each_other() {
+		let gadget = gadget();
+		let validateers = validateers(3);
+		let shard = H256::random();
+
+		let mut votes = votes_of(&validateers[..2], shard, 2, H256::random());
+		votes.extend(votes_of(&validateers[2..], shard, 2, H256::random()));
+		let justifications = gadget.import_votes(votes, &publics(&validateers)).unwrap();
+
+		assert!(justifications.is_empty());
+	}
+
+	#[test]
+	fn justification_is_only_imported_if_it_verifies_and_is_newer() {
+		let gadget = gadget();
+		let validateers = validateers(3);
+		let shard = H256::random();
+		let justification = |block_number| {
+			let votes = votes_of(&validateers, shard, block_number, H256::random());
+			Justification { vote: votes[0].vote, votes }
+		};
+
+		let mut unsigned = justification(5);
+		unsigned.votes.pop();
+		assert!(!gadget.import_justification(unsigned, &publics(&validateers)).unwrap());
+		assert!(gadget.finalized_head(&shard).unwrap().is_none());
+
+		let finalized = justification(5);
+		assert!(gadget.import_justification(finalized.clone(), &publics(&validateers)).unwrap());
+		assert!(!gadget.import_justification(justification(4), &publics(&validateers)).unwrap());
+		assert_eq!(gadget.finalized_head(&shard).unwrap(), Some(finalized));
+
+		// Votes for blocks which are already final are ignored.
+		let justifications = gadget
+			.import_votes(votes_of(&validateers, shard, 5, H256::random()), &publics(&validateers))
+			.unwrap();
+		assert!(justifications.is_empty());
+	}
+}
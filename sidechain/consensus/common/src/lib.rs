@@ -28,7 +28,10 @@ compile_error!("feature \"std\" and feature \"sgx\" cannot be enabled at the sam
 extern crate sgx_tstd as std;
 extern crate alloc;
 
-use its_primitives::traits::{ShardIdentifierFor, SignedBlock as SignedSidechainBlockTrait};
+use its_primitives::{
+	traits::{ShardIdentifierFor, SignedBlock as SignedSidechainBlockTrait},
+	types::{BlockHash, ShardIdentifier},
+};
 use sp_runtime::traits::Block as ParentchainBlockTrait;
 use std::{time::Duration, vec::Vec};
 
@@ -36,6 +39,7 @@ mod block_import;
 mod block_import_confirmation_handler;
 mod block_import_queue_worker;
 mod error;
+mod finality_gadget;
 mod header_db;
 mod peer_block_sync;
 
@@ -50,6 +54,7 @@ pub use block_import::*;
 pub use block_import_confirmation_handler::*;
 pub use block_import_queue_worker::*;
 pub use error::*;
+pub use finality_gadget::*;
 use itp_types::parentchain::ParentchainCall;
 pub use peer_block_sync::*;
 
@@ -74,6 +79,12 @@ where
 	) -> Result<Self::BlockImportParams>;
 }
 
+/// Observes the state of a shard right after a sidechain block has been imported or proposed,
+/// e.g. to notify clients about the events that were emitted in the block.
+pub trait ObserveImportedState<State>: Send + Sync {
+	fn on_state_imported(&self, shard: ShardIdentifier, block_hash: BlockHash, state: &State);
+}
+
 /// Environment for a Consensus instance.
 ///
 /// Creates proposer instance.
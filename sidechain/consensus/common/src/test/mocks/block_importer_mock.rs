@@ -139,6 +139,22 @@ where
 		todo!()
 	}
 
+	fn complete_state_encryption_migration(&self, _state_key: Self::StateCrypto) -> Result<()> {
+		todo!()
+	}
+
+	fn state_root(
+		&self,
+		_shard: &ShardIdentifierFor<SignedSidechainBlock>,
+		_state: &Self::SidechainState,
+	) -> Result<H256> {
+		todo!()
+	}
+
+	fn commit_state_root(&self, _shard: &ShardIdentifierFor<SignedSidechainBlock>) -> Result<()> {
+		todo!()
+	}
+
 	fn get_context(&self) -> &Self::Context {
 		todo!()
 	}
@@ -18,4 +18,5 @@
 pub mod block_import_queue_worker_mock;
 pub mod block_importer_mock;
 pub mod confirm_block_import_mock;
+pub mod own_finality_votes_seal_mock;
 pub mod verifier_mock;
@@ -0,0 +1,39 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+use crate::{error::Result, OwnFinalityVotes};
+use itp_sgx_io::SealedIO;
+use std::sync::{Arc, RwLock};
+
+/// Seals the own finality votes in memory. Clones share the sealed votes.
+#[derive(Clone, Default)]
+pub struct OwnFinalityVotesSealMock {
+	sealed: Arc<RwLock<OwnFinalityVotes>>,
+}
+
+impl SealedIO for OwnFinalityVotesSealMock {
+	type Error = crate::error::Error;
+	type Unsealed = OwnFinalityVotes;
+
+	fn unseal(&self) -> Result<Self::Unsealed> {
+		Ok(self.sealed.read().unwrap().clone())
+	}
+
+	fn seal(&self, unsealed: &Self::Unsealed) -> Result<()> {
+		*self.sealed.write().unwrap() = unsealed.clone();
+		Ok(())
+	}
+}
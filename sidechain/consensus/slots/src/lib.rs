@@ -43,7 +43,7 @@ use its_primitives::traits::{
 use log::*;
 pub use slots::*;
 use sp_runtime::traits::{Block as ParentchainBlockTrait, Header as ParentchainHeaderTrait};
-use std::{fmt::Debug, time::Duration, vec::Vec};
+use std::{collections::BTreeMap, fmt::Debug, time::Duration, vec::Vec};
 
 #[cfg(feature = "std")]
 mod slot_stream;
@@ -55,7 +55,7 @@ mod mocks;
 #[cfg(test)]
 mod per_shard_slot_worker_tests;
 
-use itp_types::parentchain::ParentchainCall;
+use itp_types::parentchain::{ParentchainCall, TargetParentchainIndex};
 #[cfg(feature = "std")]
 pub use slot_stream::*;
 pub use slots::*;
@@ -173,13 +173,13 @@ pub trait SimpleSlotWorker<ParentchainBlock: ParentchainBlockTrait> {
 		last_imported_parentchain_header: &<ParentchainBlock::Header as ParentchainHeaderTrait>::Hash,
 	) -> Result<Option<ParentchainBlock::Header>, ConsensusError>;
 
-	fn import_target_a_parentchain_blocks_until(
-		&self,
-		last_imported_parentchain_header: &<ParentchainBlock::Header as ParentchainHeaderTrait>::Hash,
-	) -> Result<Option<ParentchainBlock::Header>, ConsensusError>;
-
-	fn import_target_b_parentchain_blocks_until(
+	/// Trigger the import of the given target parentchain block.
+	///
+	/// Returns the header of the latest imported block, analogous to
+	/// [`Self::import_integritee_parentchain_blocks_until`].
+	fn import_target_parentchain_blocks_until(
 		&self,
+		index: TargetParentchainIndex,
 		last_imported_parentchain_header: &<ParentchainBlock::Header as ParentchainHeaderTrait>::Hash,
 	) -> Result<Option<ParentchainBlock::Header>, ConsensusError>;
 
@@ -189,13 +189,13 @@ pub trait SimpleSlotWorker<ParentchainBlock: ParentchainBlockTrait> {
 		&self,
 	) -> Result<Option<ParentchainBlock::Header>, ConsensusError>;
 
-	fn peek_latest_target_a_parentchain_header(
+	fn peek_latest_target_parentchain_header(
 		&self,
+		index: TargetParentchainIndex,
 	) -> Result<Option<ParentchainBlock::Header>, ConsensusError>;
 
-	fn peek_latest_target_b_parentchain_header(
-		&self,
-	) -> Result<Option<ParentchainBlock::Header>, ConsensusError>;
+	/// The target parentchains this worker imports blocks for.
+	fn target_parentchain_indices(&self) -> Vec<TargetParentchainIndex>;
 
 	/// Implements [`SlotWorker::on_slot`]. This is an adaption from
 	/// substrate's sc-consensus-slots implementation. There, the slot worker handles all the
@@ -231,34 +231,29 @@ pub trait SimpleSlotWorker<ParentchainBlock: ParentchainBlockTrait> {
 			"on_slot: a priori latest Integritee block number: {:?}",
 			latest_integritee_parentchain_header.number()
 		);
-		// fixme: we need proper error handling here. we just assume there is no target_a if there is an error here, which is very brittle
-		let maybe_latest_target_a_parentchain_header =
-			match self.peek_latest_target_a_parentchain_header() {
-				Ok(Some(peeked_header)) => Some(peeked_header),
-				Ok(None) => slot_info.maybe_last_imported_target_a_parentchain_head.clone(),
-				Err(e) => {
-					debug!("Failed to peek latest target_a_parentchain block header: {:?}", e);
-					None
-				},
-			};
-		trace!(
-			"on_slot: a priori latest TargetA block number: {:?}",
-			maybe_latest_target_a_parentchain_header.clone().map(|h| *h.number())
-		);
-
-		let maybe_latest_target_b_parentchain_header =
-			match self.peek_latest_target_b_parentchain_header() {
+		// fixme: we need proper error handling here. we just assume there is no target parentchain if there is an error here, which is very brittle
+		let mut latest_target_parentchain_headers = BTreeMap::new();
+		for index in self.target_parentchain_indices() {
+			let maybe_header = match self.peek_latest_target_parentchain_header(index) {
 				Ok(Some(peeked_header)) => Some(peeked_header),
-				Ok(None) => slot_info.maybe_last_imported_target_b_parentchain_head.clone(),
+				Ok(None) => slot_info.last_imported_target_parentchain_heads.get(&index).cloned(),
 				Err(e) => {
-					debug!("Failed to peek latest target_a_parentchain block header: {:?}", e);
+					debug!(
+						"Failed to peek latest target parentchain {} block header: {:?}",
+						index, e
+					);
 					None
 				},
 			};
-		trace!(
-			"on_slot: a priori latest TargetB block number: {:?}",
-			maybe_latest_target_b_parentchain_header.clone().map(|h| *h.number())
-		);
+			trace!(
+				"on_slot: a priori latest Target({}) block number: {:?}",
+				index,
+				maybe_header.as_ref().map(|h| *h.number())
+			);
+			if let Some(header) = maybe_header {
+				latest_target_parentchain_headers.insert(index, header);
+			}
+		}
 
 		let epoch_data = match self.epoch_data(&latest_integritee_parentchain_header, shard, slot) {
 			Ok(epoch_data) => epoch_data,
@@ -300,50 +295,26 @@ pub trait SimpleSlotWorker<ParentchainBlock: ParentchainBlockTrait> {
 			last_imported_integritee_header.clone().map(|h| *h.number())
 		);
 
-		let maybe_last_imported_target_a_header =
-			if let Some(ref header) = maybe_latest_target_a_parentchain_header {
-				match self.import_target_a_parentchain_blocks_until(&header.hash()) {
-					Ok(Some(h)) => Some(h),
-					Ok(None) => None,
-					Err(e) => {
-						debug!(
-							"Failed to import TargetA blocks until nr{:?}: {:?}",
-							header.number(),
-							e
-						);
-						None
-					},
-				}
-			} else {
-				None
-			};
-		trace!(
-			"on_slot: a posteriori latest TargetA block number: {:?}",
-			maybe_last_imported_target_a_header.map(|h| *h.number())
-		);
-
-		let maybe_last_imported_target_b_header =
-			if let Some(ref header) = maybe_latest_target_b_parentchain_header {
-				match self.import_target_b_parentchain_blocks_until(&header.hash()) {
-					Ok(Some(h)) => Some(h),
-					Ok(None) => None,
+		for (index, header) in latest_target_parentchain_headers.iter() {
+			let maybe_last_imported_header =
+				match self.import_target_parentchain_blocks_until(*index, &header.hash()) {
+					Ok(h) => h,
 					Err(e) => {
 						debug!(
-							"Failed to import TargetB blocks until nr{:?}: {:?}",
+							"Failed to import Target({}) blocks until nr{:?}: {:?}",
+							index,
 							header.number(),
 							e
 						);
 						None
 					},
-				}
-			} else {
-				None
-			};
-
-		trace!(
-			"on_slot: a posteriori latest TargetB block number: {:?}",
-			maybe_last_imported_target_b_header.map(|h| *h.number())
-		);
+				};
+			trace!(
+				"on_slot: a posteriori latest Target({}) block number: {:?}",
+				index,
+				maybe_last_imported_header.map(|h| *h.number())
+			);
+		}
 
 		let proposer = match self.proposer(latest_integritee_parentchain_header.clone(), shard) {
 			Ok(p) => p,
@@ -374,10 +345,12 @@ pub trait SimpleSlotWorker<ParentchainBlock: ParentchainBlockTrait> {
 
 		if last_imported_integritee_header.is_some() {
 			println!(
-				"Syncing Parentchains: Integritee: {:?} TargetA: {:?}, TargetB: {:?}, Sidechain: {:?}",
+				"Syncing Parentchains: Integritee: {:?} Targets: {:?}, Sidechain: {:?}",
 				latest_integritee_parentchain_header.number(),
-				maybe_latest_target_a_parentchain_header.map(|h| *h.number()),
-				maybe_latest_target_b_parentchain_header.map(|h| *h.number()),
+				latest_target_parentchain_headers
+					.iter()
+					.map(|(index, h)| (*index, *h.number()))
+					.collect::<Vec<_>>(),
 				proposing.block.block().header().block_number()
 			);
 		}
@@ -444,8 +417,7 @@ impl<ParentchainBlock: ParentchainBlockTrait, T: SimpleSlotWorker<ParentchainBlo
 				shard_remaining_duration,
 				shard_slot_ends_at,
 				slot_info.last_imported_integritee_parentchain_head.clone(),
-				slot_info.maybe_last_imported_target_a_parentchain_head.clone(),
-				slot_info.maybe_last_imported_target_b_parentchain_head.clone(),
+				slot_info.last_imported_target_parentchain_heads.clone(),
 			);
 
 			match SimpleSlotWorker::on_slot(self, shard_slot.clone(), shard) {
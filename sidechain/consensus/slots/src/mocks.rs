@@ -16,6 +16,7 @@
 */
 
 use crate::{slots::Slot, SimpleSlotWorker, SlotInfo, SlotResult};
+use itp_types::parentchain::TargetParentchainIndex;
 use its_consensus_common::{Proposal, Proposer, Result};
 use its_primitives::{traits::ShardIdentifierFor, types::SignedBlock as SignedSidechainBlock};
 use sp_runtime::traits::{Block as ParentchainBlockTrait, Header as ParentchainHeaderTrait};
@@ -105,25 +106,22 @@ where
 		todo!()
 	}
 
-	fn import_target_a_parentchain_blocks_until(
+	fn import_target_parentchain_blocks_until(
 		&self,
+		_index: TargetParentchainIndex,
 		_last_imported_parentchain_header: &<B::Header as ParentchainHeaderTrait>::Hash,
 	) -> Result<Option<B::Header>> {
 		todo!()
 	}
 
-	fn peek_latest_target_a_parentchain_header(&self) -> Result<Option<B::Header>> {
-		todo!()
-	}
-
-	fn import_target_b_parentchain_blocks_until(
+	fn peek_latest_target_parentchain_header(
 		&self,
-		_last_imported_parentchain_header: &<B::Header as ParentchainHeaderTrait>::Hash,
+		_index: TargetParentchainIndex,
 	) -> Result<Option<B::Header>> {
 		todo!()
 	}
 
-	fn peek_latest_target_b_parentchain_header(&self) -> Result<Option<B::Header>> {
+	fn target_parentchain_indices(&self) -> Vec<TargetParentchainIndex> {
 		todo!()
 	}
 
@@ -20,6 +20,7 @@
 //! This is used instead of `futures_timer::Interval` because it was unreliable.
 
 use itp_time_utils::duration_now;
+use itp_types::parentchain::TargetParentchainIndex;
 use its_block_verification::slot::slot_from_timestamp_and_duration;
 use its_consensus_common::Error as ConsensusError;
 use its_primitives::traits::{
@@ -28,7 +29,7 @@ use its_primitives::traits::{
 use lazy_static::lazy_static;
 use log::warn;
 use sp_runtime::traits::Block as ParentchainBlockTrait;
-use std::time::Duration;
+use std::{collections::BTreeMap, time::Duration};
 
 #[cfg(all(not(feature = "std"), feature = "sgx"))]
 use std::sync::SgxRwLock as RwLock;
@@ -65,10 +66,9 @@ pub struct SlotInfo<ParentchainBlock: ParentchainBlockTrait> {
 	pub ends_at: Duration,
 	/// Last imported parentchain header, potentially outdated.
 	pub last_imported_integritee_parentchain_head: ParentchainBlock::Header,
-	/// Last imported parentchain header, potentially outdated.
-	pub maybe_last_imported_target_a_parentchain_head: Option<ParentchainBlock::Header>,
-	/// Last imported parentchain header, potentially outdated.
-	pub maybe_last_imported_target_b_parentchain_head: Option<ParentchainBlock::Header>,
+	/// Last imported header of each assigned target parentchain, potentially outdated.
+	pub last_imported_target_parentchain_heads:
+		BTreeMap<TargetParentchainIndex, ParentchainBlock::Header>,
 }
 
 impl<ParentchainBlock: ParentchainBlockTrait> SlotInfo<ParentchainBlock> {
@@ -81,8 +81,10 @@ impl<ParentchainBlock: ParentchainBlockTrait> SlotInfo<ParentchainBlock> {
 		duration: Duration,
 		ends_at: Duration,
 		last_imported_integritee_parentchain_head: ParentchainBlock::Header,
-		maybe_last_imported_target_a_parentchain_head: Option<ParentchainBlock::Header>,
-		maybe_last_imported_target_b_parentchain_head: Option<ParentchainBlock::Header>,
+		last_imported_target_parentchain_heads: BTreeMap<
+			TargetParentchainIndex,
+			ParentchainBlock::Header,
+		>,
 	) -> Self {
 		Self {
 			slot,
@@ -90,8 +92,7 @@ impl<ParentchainBlock: ParentchainBlockTrait> SlotInfo<ParentchainBlock> {
 			duration,
 			ends_at,
 			last_imported_integritee_parentchain_head,
-			maybe_last_imported_target_a_parentchain_head,
-			maybe_last_imported_target_b_parentchain_head,
+			last_imported_target_parentchain_heads,
 		}
 	}
 
@@ -140,8 +141,7 @@ pub fn yield_next_slot<SlotGetter, ParentchainBlock>(
 	timestamp: Duration,
 	duration: Duration,
 	integritee_header: ParentchainBlock::Header,
-	maybe_target_a_header: Option<ParentchainBlock::Header>,
-	maybe_target_b_header: Option<ParentchainBlock::Header>,
+	target_headers: BTreeMap<TargetParentchainIndex, ParentchainBlock::Header>,
 	last_slot_getter: &mut SlotGetter,
 ) -> Result<Option<SlotInfo<ParentchainBlock>>, ConsensusError>
 where
@@ -168,8 +168,7 @@ where
 		duration,
 		slot_ends_time,
 		integritee_header,
-		maybe_target_a_header,
-		maybe_target_b_header,
+		target_headers,
 	)))
 }
 
@@ -232,8 +231,7 @@ mod tests {
 			duration: SLOT_DURATION,
 			ends_at: duration_now() + SLOT_DURATION,
 			last_imported_integritee_parentchain_head: ParentchainHeaderBuilder::default().build(),
-			maybe_last_imported_target_a_parentchain_head: None,
-			maybe_last_imported_target_b_parentchain_head: None,
+			last_imported_target_parentchain_heads: Default::default(),
 		}
 	}
 
@@ -304,8 +302,7 @@ mod tests {
 			duration: SLOT_DURATION,
 			ends_at: duration_now() + SLOT_DURATION - Duration::from_secs(5),
 			last_imported_integritee_parentchain_head: ParentchainHeaderBuilder::default().build(),
-			maybe_last_imported_target_a_parentchain_head: None,
-			maybe_last_imported_target_b_parentchain_head: None,
+			last_imported_target_parentchain_heads: Default::default(),
 		};
 		assert!(slot.duration_remaining().is_none());
 	}
@@ -318,8 +315,7 @@ mod tests {
 			duration: SLOT_DURATION,
 			ends_at: duration_now() + Duration::from_secs(60),
 			last_imported_integritee_parentchain_head: ParentchainHeaderBuilder::default().build(),
-			maybe_last_imported_target_a_parentchain_head: None,
-			maybe_last_imported_target_b_parentchain_head: None,
+			last_imported_target_parentchain_heads: Default::default(),
 		};
 		let maybe_duration_remaining = slot.duration_remaining();
 		assert!(maybe_duration_remaining.is_some());
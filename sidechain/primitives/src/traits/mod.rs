@@ -29,6 +29,8 @@ pub trait Header: Encode + Decode + Clone {
 	/// Identifier for the shards.
 	type ShardIdentifier: Encode + Decode + sp_std::hash::Hash + Copy + Member;
 
+	/// Get the version of the header encoding.
+	fn version(&self) -> u8;
 	/// Get block number.
 	fn block_number(&self) -> u64;
 	/// get parent hash of block
@@ -37,6 +39,8 @@ pub trait Header: Encode + Decode + Clone {
 	fn shard_id(&self) -> Self::ShardIdentifier;
 	/// get hash of the block's payload
 	fn block_data_hash(&self) -> H256;
+	/// get the state root after executing the block
+	fn state_root(&self) -> H256;
 
 	/// get the `blake2_256` hash of the header.
 	fn hash(&self) -> H256 {
@@ -50,6 +54,7 @@ pub trait Header: Encode + Decode + Clone {
 		parent_hash: H256,
 		shard: Self::ShardIdentifier,
 		block_data_hash: H256,
+		state_root: H256,
 		next_finalization_block_number: u64,
 	) -> Self;
 }
@@ -17,7 +17,7 @@
 
 //!Primitives for the sidechain
 use crate::traits::Header as HeaderTrait;
-use codec::{Decode, Encode};
+use codec::{Decode, Encode, EncodeLike, Error, Input, Output};
 use scale_info::TypeInfo;
 use sp_core::H256;
 use sp_runtime::traits::{BlakeTwo256, Hash};
@@ -28,9 +28,25 @@ use serde::{Deserialize, Serialize};
 
 pub use itp_types::ShardIdentifier;
 
-#[derive(PartialEq, Eq, Clone, Encode, Decode, Debug, Copy, Default, TypeInfo)]
+/// Version of the headers produced by this worker.
+///
+/// Version 0 are the headers of the blocks that were produced before the state root was
+/// committed. They are still decoded, so that stored blocks and blocks of peers that are not
+/// yet updated remain readable.
+pub const SIDECHAIN_HEADER_VERSION: u8 = 1;
+
+/// Prefix of the encoding of headers with a version greater than 0.
+///
+/// Headers of version 0 are encoded without it, so their encoding and hash stay unchanged.
+pub const VERSIONED_HEADER_MAGIC: [u8; 4] = *b"itsh";
+
+/// Sidechain header, see [`SIDECHAIN_HEADER_VERSION`] for the encodings.
+#[derive(PartialEq, Eq, Clone, Debug, Copy, TypeInfo)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub struct SidechainHeader {
+	/// The version of the header encoding.
+	pub version: u8,
+
 	/// The parent hash.
 	pub parent_hash: H256,
 
@@ -43,6 +59,9 @@ pub struct SidechainHeader {
 	/// The payload hash.
 	pub block_data_hash: H256,
 
+	/// The root of the state trie after executing the block. Zero for headers of version 0.
+	pub state_root: H256,
+
 	/// The latest finalized block number
 	pub next_finalization_block_number: u64,
 }
@@ -54,9 +73,81 @@ impl SidechainHeader {
 	}
 }
 
+impl Default for SidechainHeader {
+	fn default() -> Self {
+		SidechainHeader {
+			version: SIDECHAIN_HEADER_VERSION,
+			parent_hash: Default::default(),
+			block_number: Default::default(),
+			shard_id: Default::default(),
+			block_data_hash: Default::default(),
+			state_root: Default::default(),
+			next_finalization_block_number: Default::default(),
+		}
+	}
+}
+
+impl Encode for SidechainHeader {
+	fn encode_to<T: Output + ?Sized>(&self, dest: &mut T) {
+		if self.version > 0 {
+			VERSIONED_HEADER_MAGIC.encode_to(dest);
+			self.version.encode_to(dest);
+		}
+		self.parent_hash.encode_to(dest);
+		self.block_number.encode_to(dest);
+		self.shard_id.encode_to(dest);
+		self.block_data_hash.encode_to(dest);
+		if self.version > 0 {
+			self.state_root.encode_to(dest);
+		}
+		self.next_finalization_block_number.encode_to(dest);
+	}
+}
+
+impl EncodeLike for SidechainHeader {}
+
+impl Decode for SidechainHeader {
+	fn decode<I: Input>(input: &mut I) -> Result<Self, Error> {
+		// The encoding of a header of version 0 starts with the parent hash.
+		let mut prefix = [0u8; 32];
+		input.read(&mut prefix[..VERSIONED_HEADER_MAGIC.len()])?;
+
+		let (version, parent_hash) =
+			if prefix[..VERSIONED_HEADER_MAGIC.len()] == VERSIONED_HEADER_MAGIC {
+				let version = u8::decode(input)?;
+				if version != SIDECHAIN_HEADER_VERSION {
+					return Err("Unsupported sidechain header version".into())
+				}
+				(version, H256::decode(input)?)
+			} else {
+				input.read(&mut prefix[VERSIONED_HEADER_MAGIC.len()..])?;
+				(0, H256::from(prefix))
+			};
+
+		let block_number = u64::decode(input)?;
+		let shard_id = ShardIdentifier::decode(input)?;
+		let block_data_hash = H256::decode(input)?;
+		let state_root = if version > 0 { H256::decode(input)? } else { H256::zero() };
+		let next_finalization_block_number = u64::decode(input)?;
+
+		Ok(SidechainHeader {
+			version,
+			parent_hash,
+			block_number,
+			shard_id,
+			block_data_hash,
+			state_root,
+			next_finalization_block_number,
+		})
+	}
+}
+
 impl HeaderTrait for SidechainHeader {
 	type ShardIdentifier = H256;
 
+	fn version(&self) -> u8 {
+		self.version
+	}
 	fn block_number(&self) -> u64 {
 		self.block_number
 	}
@@ -69,6 +160,9 @@ impl HeaderTrait for SidechainHeader {
 	fn block_data_hash(&self) -> H256 {
 		self.block_data_hash
 	}
+	fn state_root(&self) -> H256 {
+		self.state_root
+	}
 	fn next_finalization_block_number(&self) -> u64 {
 		self.next_finalization_block_number
 	}
@@ -78,14 +172,84 @@ impl HeaderTrait for SidechainHeader {
 		parent_hash: H256,
 		shard: Self::ShardIdentifier,
 		block_data_hash: H256,
+		state_root: H256,
 		next_finalization_block_number: u64,
 	) -> SidechainHeader {
 		SidechainHeader {
+			version: SIDECHAIN_HEADER_VERSION,
 			block_number,
 			parent_hash,
 			shard_id: shard,
 			block_data_hash,
+			state_root,
 			next_finalization_block_number,
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Encoding of the headers of version 0, before the state root was added.
+	#[derive(Encode)]
+	struct HeaderV0 {
+		parent_hash: H256,
+		block_number: u64,
+		shard_id: ShardIdentifier,
+		block_data_hash: H256,
+		next_finalization_block_number: u64,
+	}
+
+	fn header_v0() -> HeaderV0 {
+		HeaderV0 {
+			parent_hash: H256::random(),
+			block_number: 7,
+			shard_id: H256::random(),
+			block_data_hash: H256::random(),
+			next_finalization_block_number: 9,
+		}
+	}
+
+	#[test]
+	fn header_of_version_0_is_decoded_and_keeps_its_hash() {
+		let legacy_header = header_v0();
+		let encoded = legacy_header.encode();
+
+		let header = SidechainHeader::decode(&mut encoded.as_slice()).unwrap();
+
+		assert_eq!(header.version, 0);
+		assert_eq!(header.parent_hash, legacy_header.parent_hash);
+		assert_eq!(header.block_number, legacy_header.block_number);
+		assert_eq!(header.shard_id, legacy_header.shard_id);
+		assert_eq!(header.block_data_hash, legacy_header.block_data_hash);
+		assert_eq!(header.state_root, H256::zero());
+		assert_eq!(header.next_finalization_block_number, 9);
+		assert_eq!(header.encode(), encoded);
+		assert_eq!(header.hash(), BlakeTwo256::hash(&encoded));
+	}
+
+	#[test]
+	fn versioned_header_encoding_roundtrips() {
+		let header = SidechainHeader::new(
+			7,
+			H256::random(),
+			H256::random(),
+			H256::random(),
+			H256::random(),
+			9,
+		);
+		let encoded = header.encode();
+
+		assert!(encoded.starts_with(&VERSIONED_HEADER_MAGIC));
+		assert_eq!(SidechainHeader::decode(&mut encoded.as_slice()).unwrap(), header);
+	}
+
+	#[test]
+	fn header_of_unknown_version_is_not_decoded() {
+		let mut encoded = SidechainHeader::default().encode();
+		encoded[VERSIONED_HEADER_MAGIC.len()] = SIDECHAIN_HEADER_VERSION + 1;
+
+		assert!(SidechainHeader::decode(&mut encoded.as_slice()).is_err());
+	}
+}
@@ -0,0 +1,192 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Finality votes of the validateers and the justifications of finalized sidechain blocks.
+
+use crate::types::block::{BlockHash, BlockNumber, ShardIdentifier, Signature};
+use codec::{Decode, Encode};
+use sp_core::ed25519;
+use sp_runtime::traits::Verify;
+use sp_std::{collections::btree_set::BTreeSet, vec::Vec};
+
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+/// Minimal number of validateer signatures needed to finalize a block, i.e. more than two thirds
+/// of the validateers of a shard.
+pub fn supermajority_threshold(validateer_count: usize) -> usize {
+	validateer_count * 2 / 3 + 1
+}
+
+/// Statement of a validateer, that it has imported a sidechain block and considers it final.
+///
+/// A vote for a block implicitly is a vote for all of its ancestors.
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, Debug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct FinalityVote {
+	pub shard: ShardIdentifier,
+	pub block_number: BlockNumber,
+	pub block_hash: BlockHash,
+}
+
+/// Finality vote, signed by the enclave signing key of a validateer.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, Debug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct SignedFinalityVote {
+	pub vote: FinalityVote,
+	pub signer: ed25519::Public,
+	pub signature: Signature,
+}
+
+impl SignedFinalityVote {
+	/// Verifies the signature of the vote.
+	pub fn verify_signature(&self) -> bool {
+		self.vote.using_encoded(|v| self.signature.verify(v, &self.signer.into()))
+	}
+}
+
+#[cfg(feature = "full_crypto")]
+impl FinalityVote {
+	/// Sign the vote with the enclave signing key.
+	pub fn sign<P: sp_core::Pair<Public = ed25519::Public>>(self, signer: &P) -> SignedFinalityVote
+	where
+		Signature: From<P::Signature>,
+	{
+		let signature = self.using_encoded(|v| signer.sign(v)).into();
+		SignedFinalityVote { vote: self, signer: signer.public(), signature }
+	}
+}
+
+/// Proof that a sidechain block is final: The finality votes of a supermajority of the validateers
+/// for exactly this block.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, Debug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct Justification {
+	/// The finalized block.
+	pub vote: FinalityVote,
+	pub votes: Vec<SignedFinalityVote>,
+}
+
+impl Justification {
+	pub fn block_number(&self) -> BlockNumber {
+		self.vote.block_number
+	}
+
+	pub fn block_hash(&self) -> BlockHash {
+		self.vote.block_hash
+	}
+
+	/// Verifies that the justification is signed by a supermajority of the given validateers.
+	///
+	/// Only votes for the justified block itself are accepted. A vote for a newer block does not
+	/// prove anything about the justified one, it might be on a different fork.
+	pub fn verify(&self, validateers: &[ed25519::Public]) -> bool {
+		let mut signers = BTreeSet::new();
+		for signed_vote in self.votes.iter() {
+			if !validateers.contains(&signed_vote.signer) || !signers.insert(signed_vote.signer) {
+				return false
+			}
+			if !self.is_supported_by(&signed_vote.vote) || !signed_vote.verify_signature() {
+				return false
+			}
+		}
+		signers.len() >= supermajority_threshold(validateers.len())
+	}
+
+	fn is_supported_by(&self, vote: &FinalityVote) -> bool {
+		*vote == self.vote
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::{ed25519::Pair as Ed25519Pair, Pair, H256};
+
+	fn vote() -> FinalityVote {
+		FinalityVote { shard: H256::random(), block_number: 5, block_hash: H256::random() }
+	}
+
+	fn signers(count: u8) -> Vec<Ed25519Pair> {
+		(0..count).map(|i| Ed25519Pair::from_seed(&[i; 32])).collect()
+	}
+
+	fn justification(vote: FinalityVote, signers: &[Ed25519Pair]) -> Justification {
+		Justification { vote, votes: signers.iter().map(|s| vote.sign(s)).collect() }
+	}
+
+	#[test]
+	fn supermajority_threshold_is_more_than_two_thirds() {
+		assert_eq!(supermajority_threshold(1), 1);
+		assert_eq!(supermajority_threshold(3), 3);
+		assert_eq!(supermajority_threshold(4), 3);
+		assert_eq!(supermajority_threshold(6), 5);
+		assert_eq!(supermajority_threshold(7), 5);
+	}
+
+	#[test]
+	fn signed_vote_verifies_and_tampered_vote_does_not() {
+		let signer = Ed25519Pair::from_seed(&[1u8; 32]);
+		let mut signed_vote = vote().sign(&signer);
+		assert!(signed_vote.verify_signature());
+
+		signed_vote.vote.block_number += 1;
+		assert!(!signed_vote.verify_signature());
+	}
+
+	#[test]
+	fn justification_of_supermajority_verifies() {
+		let validateers = signers(4);
+		let publics: Vec<_> = validateers.iter().map(|v| v.public()).collect();
+
+		assert!(justification(vote(), &validateers[..3]).verify(&publics));
+		assert!(!justification(vote(), &validateers[..2]).verify(&publics));
+	}
+
+	#[test]
+	fn justification_with_duplicate_or_unknown_signer_does_not_verify() {
+		let validateers = signers(4);
+		let publics: Vec<_> = validateers[..3].iter().map(|v| v.public()).collect();
+		let vote = vote();
+
+		let mut duplicate = justification(vote, &validateers[..2]);
+		duplicate.votes.push(duplicate.votes[0].clone());
+		assert!(!duplicate.verify(&publics));
+
+		assert!(!justification(vote, &validateers[1..]).verify(&publics));
+	}
+
+	#[test]
+	fn votes_for_other_blocks_do_not_count_for_the_justified_block() {
+		let validateers = signers(3);
+		let publics: Vec<_> = validateers.iter().map(|v| v.public()).collect();
+		let vote = vote();
+		let newer = FinalityVote { block_number: vote.block_number + 2, ..vote };
+
+		let mut justification = justification(vote, &validateers[..2]);
+		justification.votes.push(newer.sign(&validateers[2]));
+		assert!(!justification.verify(&publics));
+
+		let older = FinalityVote { block_number: vote.block_number - 1, ..vote };
+		justification.votes[2] = older.sign(&validateers[2]);
+		assert!(!justification.verify(&publics));
+
+		let fork = FinalityVote { block_hash: H256::random(), ..vote };
+		justification.votes[2] = fork.sign(&validateers[2]);
+		assert!(!justification.verify(&publics));
+	}
+}
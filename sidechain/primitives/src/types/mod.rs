@@ -18,5 +18,7 @@
 pub mod block;
 pub mod block_data;
 pub mod header;
+pub mod justification;
+pub mod slot_claims;
 
 pub use block::*;
@@ -0,0 +1,79 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Configuration of the rules by which validateers claim sidechain slots.
+
+use crate::types::block::BlockNumber;
+use codec::{Decode, Encode};
+
+/// Slot claim rules of a shard.
+///
+/// Changing these rules is a consensus change: all validateers of a shard must run with the same
+/// configuration. Rules are therefore activated at a sidechain block number, so that validateers
+/// can be upgraded before the activation block is reached. The default configuration assigns the
+/// slots round robin and disables fallback authors.
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, Debug, Default)]
+pub struct SlotClaimConfig {
+	/// First block whose slot is assigned in proportion to the validateers' stake.
+	pub weighted_from: Option<BlockNumber>,
+	/// First block that may be authored by a fallback author.
+	pub fallback_authors_from: Option<BlockNumber>,
+	/// Number of slots without a block after which the slot passes on to a fallback author.
+	pub fallback_delay: u64,
+}
+
+impl SlotClaimConfig {
+	/// Whether the slot of the block `block_number` is assigned by weight.
+	pub fn is_weighted(&self, block_number: BlockNumber) -> bool {
+		self.weighted_from.map_or(false, |from| block_number >= from)
+	}
+
+	/// Fallback delay that applies to the block `block_number`. Zero disables fallback authors.
+	pub fn fallback_delay(&self, block_number: BlockNumber) -> u64 {
+		match self.fallback_authors_from {
+			Some(from) if block_number >= from => self.fallback_delay,
+			_ => 0,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn default_config_keeps_round_robin_without_fallback_authors() {
+		let config = SlotClaimConfig::default();
+
+		assert!(!config.is_weighted(1_000));
+		assert_eq!(config.fallback_delay(1_000), 0);
+	}
+
+	#[test]
+	fn rules_apply_from_their_activation_block() {
+		let config = SlotClaimConfig {
+			weighted_from: Some(10),
+			fallback_authors_from: Some(20),
+			fallback_delay: 3,
+		};
+
+		assert!(!config.is_weighted(9));
+		assert!(config.is_weighted(10));
+		assert_eq!(config.fallback_delay(19), 0);
+		assert_eq!(config.fallback_delay(20), 3);
+	}
+}
@@ -19,4 +19,15 @@
 
 // RPC method names.
 pub const RPC_METHOD_NAME_IMPORT_BLOCKS: &str = "sidechain_importBlock";
+pub const RPC_METHOD_NAME_IMPORT_FINALITY_VOTES: &str = "sidechain_importFinalityVotes";
 pub const RPC_METHOD_NAME_FETCH_BLOCKS_FROM_PEER: &str = "sidechain_fetchBlocksFromPeer";
+pub const RPC_METHOD_NAME_GET_SHARDS: &str = "sidechain_getShards";
+pub const RPC_METHOD_NAME_GET_BLOCK_BY_HASH: &str = "sidechain_getBlockByHash";
+pub const RPC_METHOD_NAME_GET_BLOCK_BY_NUMBER: &str = "sidechain_getBlockByNumber";
+pub const RPC_METHOD_NAME_GET_LATEST_BLOCKS: &str = "sidechain_getLatestBlocks";
+pub const RPC_METHOD_NAME_GET_BLOCK_BY_SIGNED_TOP: &str = "sidechain_getBlockBySignedTop";
+pub const RPC_METHOD_NAME_GET_SHARD_STATISTICS: &str = "sidechain_getShardStatistics";
+pub const RPC_METHOD_NAME_GET_JUSTIFICATION: &str = "sidechain_getJustification";
+pub const RPC_METHOD_NAME_GET_FINALIZED_HEAD: &str = "sidechain_getFinalizedHead";
+pub const RPC_METHOD_NAME_SUBSCRIBE_NEW_BLOCKS: &str = "sidechain_subscribeNewBlocks";
+pub const RPC_METHOD_NAME_UNSUBSCRIBE_NEW_BLOCKS: &str = "sidechain_unsubscribeNewBlocks";
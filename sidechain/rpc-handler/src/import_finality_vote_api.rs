@@ -0,0 +1,93 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+#[cfg(all(not(feature = "std"), feature = "sgx"))]
+use crate::sgx_reexport_prelude::*;
+
+use crate::constants::RPC_METHOD_NAME_IMPORT_FINALITY_VOTES;
+use itp_utils::FromHexPrefixed;
+use its_primitives::types::justification::SignedFinalityVote;
+use jsonrpc_core::{IoHandler, Params, Value};
+use log::*;
+use std::{borrow::ToOwned, fmt::Debug, string::String, vec::Vec};
+
+pub fn add_import_finality_votes_rpc_method<ImportFn, Error>(
+	import_fn: ImportFn,
+	io_handler: &mut IoHandler,
+) where
+	ImportFn: Fn(SignedFinalityVote) -> Result<(), Error> + Sync + Send + 'static,
+	Error: Debug,
+{
+	io_handler.add_sync_method(RPC_METHOD_NAME_IMPORT_FINALITY_VOTES, move |params: Params| {
+		debug!("{} rpc. Params: {:?}", RPC_METHOD_NAME_IMPORT_FINALITY_VOTES, params);
+
+		let hex_encoded_votes: Vec<String> = params.parse()?;
+
+		let votes = hex_encoded_votes
+			.first()
+			.and_then(|hex| Vec::<SignedFinalityVote>::from_hex(hex).ok())
+			.ok_or_else(|| {
+				jsonrpc_core::error::Error::invalid_params_with_details(
+					"Could not decode Vec<SignedFinalityVote>",
+					hex_encoded_votes.clone(),
+				)
+			})?;
+
+		for vote in votes {
+			trace!("Add finality vote for block {} to import queue", vote.vote.block_number);
+			if let Err(e) = import_fn(vote) {
+				warn!("Dropping finality votes, failed to import: {:?}", e);
+				break
+			}
+		}
+
+		Ok(Value::String("ok".to_owned()))
+	});
+}
+
+#[cfg(test)]
+pub mod tests {
+
+	use super::*;
+
+	fn io_handler() -> IoHandler {
+		let mut io_handler = IoHandler::new();
+		add_import_finality_votes_rpc_method::<_, String>(|_| Ok(()), &mut io_handler);
+		io_handler
+	}
+
+	#[test]
+	pub fn import_empty_finality_votes_is_ok() {
+		let io = io_handler();
+		let enclave_req = r#"{"jsonrpc":"2.0","method":"sidechain_importFinalityVotes","params":["0x00"],"id":1}"#;
+
+		let response_string = io.handle_request_sync(enclave_req).unwrap();
+
+		assert_eq!(response_string, r#"{"jsonrpc":"2.0","result":"ok","id":1}"#);
+	}
+
+	#[test]
+	pub fn import_finality_votes_returns_decode_err() {
+		let io = io_handler();
+		let enclave_req = r#"{"jsonrpc":"2.0","method":"sidechain_importFinalityVotes","params":["0x11"],"id":1}"#;
+
+		let response_string = io.handle_request_sync(enclave_req).unwrap();
+
+		let err_msg = r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Invalid parameters: Could not decode Vec<SignedFinalityVote>","data":"[\"0x11\"]"},"id":1}"#;
+		assert_eq!(response_string, err_msg);
+	}
+}
@@ -34,19 +34,28 @@ pub mod sgx_reexport_prelude {
 #[cfg(all(not(feature = "std"), feature = "sgx"))]
 use crate::sgx_reexport_prelude::*;
 
-use crate::import_block_api::add_import_block_rpc_method;
+use crate::{
+	import_block_api::add_import_block_rpc_method,
+	import_finality_vote_api::add_import_finality_votes_rpc_method,
+};
 use itp_import_queue::{ImportQueue, PushToQueue};
-use its_primitives::types::SignedBlock;
+use its_primitives::types::{justification::SignedFinalityVote, SignedBlock};
 use jsonrpc_core::IoHandler;
 use std::sync::Arc;
 
 pub mod constants;
 pub mod direct_top_pool_api;
 mod import_block_api;
+mod import_finality_vote_api;
 
 pub fn add_sidechain_api(
 	io_handler: &mut IoHandler,
 	sidechain_import_queue: Arc<ImportQueue<SignedBlock>>,
+	finality_vote_queue: Arc<ImportQueue<SignedFinalityVote>>,
 ) {
 	add_import_block_rpc_method(move |block| sidechain_import_queue.push_single(block), io_handler);
+	add_import_finality_votes_rpc_method(
+		move |vote| finality_vote_queue.push_single(vote),
+		io_handler,
+	);
 }
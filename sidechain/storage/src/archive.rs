@@ -0,0 +1,133 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Portable archive of the sidechain blocks of a shard.
+//!
+//! An archive file consists of [`ARCHIVE_MAGIC`], followed by the SCALE encoded archive version
+//! and the SCALE encoded [`SidechainBlockArchive`].
+
+use super::{Error, Result};
+use codec::{Decode, DecodeAll, Encode};
+use std::{fs, path::Path};
+
+/// Magic bytes at the start of every archive file.
+pub const ARCHIVE_MAGIC: [u8; 8] = *b"itsblkar";
+
+/// Version of the archive format. Needs to be increased whenever the encoding changes.
+pub const ARCHIVE_VERSION: u16 = 1;
+
+/// All exported blocks of a shard.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, Debug)]
+pub struct SidechainBlockArchive<ShardIdentifier, SignedBlock> {
+	/// shard the blocks belong to
+	pub shard: ShardIdentifier,
+	/// blocks of the shard, oldest first
+	pub blocks: Vec<SignedBlock>,
+}
+
+impl<ShardIdentifier, SignedBlock> SidechainBlockArchive<ShardIdentifier, SignedBlock>
+where
+	ShardIdentifier: Encode + Decode,
+	SignedBlock: Encode + Decode,
+{
+	pub fn new(shard: ShardIdentifier, blocks: Vec<SignedBlock>) -> Self {
+		SidechainBlockArchive { shard, blocks }
+	}
+
+	/// Encodes the archive including magic bytes and version.
+	pub fn to_bytes(&self) -> Vec<u8> {
+		(ARCHIVE_MAGIC, ARCHIVE_VERSION, self).encode()
+	}
+
+	/// Decodes an archive, which was encoded with [`Self::to_bytes`].
+	pub fn from_bytes(mut bytes: &[u8]) -> Result<Self> {
+		let magic = <[u8; 8]>::decode(&mut bytes)?;
+		if magic != ARCHIVE_MAGIC {
+			return Err(Error::InvalidArchive)
+		}
+		let version = u16::decode(&mut bytes)?;
+		if version != ARCHIVE_VERSION {
+			return Err(Error::UnsupportedArchiveVersion(version))
+		}
+		Ok(Self::decode_all(&mut bytes)?)
+	}
+
+	pub fn write_to_file(&self, path: &Path) -> Result<()> {
+		Ok(fs::write(path, self.to_bytes())?)
+	}
+
+	pub fn read_from_file(path: &Path) -> Result<Self> {
+		Self::from_bytes(&fs::read(path)?)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::test_utils::{create_signed_block_with_parenthash, create_temp_dir, default_shard};
+	use itp_types::ShardIdentifier;
+	use its_primitives::{
+		traits::SignedBlock as SignedBlockT,
+		types::{BlockHash, SignedBlock},
+	};
+	use std::assert_matches::assert_matches;
+
+	type TestArchive = SidechainBlockArchive<ShardIdentifier, SignedBlock>;
+
+	fn test_archive() -> TestArchive {
+		let block_1 = create_signed_block_with_parenthash(1, BlockHash::default());
+		let block_2 = create_signed_block_with_parenthash(2, block_1.hash());
+		TestArchive::new(default_shard(), vec![block_1, block_2])
+	}
+
+	#[test]
+	fn archive_encoding_roundtrip_works() {
+		let archive = test_archive();
+
+		assert_eq!(TestArchive::from_bytes(&archive.to_bytes()).unwrap(), archive);
+	}
+
+	#[test]
+	fn archive_file_roundtrip_works() {
+		let temp_dir = create_temp_dir();
+		let path = temp_dir.path().join("blocks.bin");
+		let archive = test_archive();
+
+		archive.write_to_file(&path).unwrap();
+
+		assert_eq!(TestArchive::read_from_file(&path).unwrap(), archive);
+	}
+
+	#[test]
+	fn decoding_archive_with_wrong_magic_fails() {
+		let mut bytes = test_archive().to_bytes();
+		bytes[0] = b'x';
+
+		assert_matches!(TestArchive::from_bytes(&bytes), Err(Error::InvalidArchive));
+	}
+
+	#[test]
+	fn decoding_archive_with_unknown_version_fails() {
+		let archive = test_archive();
+		let bytes = (ARCHIVE_MAGIC, ARCHIVE_VERSION + 1, &archive).encode();
+
+		assert_matches!(
+			TestArchive::from_bytes(&bytes),
+			Err(Error::UnsupportedArchiveVersion(v)) if v == ARCHIVE_VERSION + 1
+		);
+	}
+}
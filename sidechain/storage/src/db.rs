@@ -25,6 +25,10 @@ use std::path::PathBuf;
 /// (LAST_BLOCK_KEY, Shard) -> (Blockhash, BlockNr) (look up current blockchain state)
 /// (Shard , Block number) -> Blockhash (needed for block pruning)
 /// Blockhash -> Signed Block (actual block storage)
+/// (SIGNED_TOP_KEY, Signed TOP hash) -> Blockhash (look up the block including a trusted operation)
+/// (SHARD_STATISTICS_KEY, Shard) -> ShardStatistics
+/// (JUSTIFICATION_KEY, Blockhash) -> Justification (proof of finality of a block)
+/// (FINALIZED_HEAD_KEY, Shard) -> Justification (look up the latest finalized block)
 
 /// Interface struct to rocks DB
 pub struct SidechainDB {
@@ -31,4 +31,12 @@ pub enum Error {
 	Decode(#[from] codec::Error),
 	#[error("Given block is not a successor of the last known block")]
 	HeaderAncestryMismatch,
+	#[error("Block of shard {0} does not belong to the imported shard")]
+	ShardMismatch(String),
+	#[error("Could not access archive file: {0:?}")]
+	Io(#[from] std::io::Error),
+	#[error("Not a sidechain block archive")]
+	InvalidArchive,
+	#[error("Unsupported sidechain block archive version: {0}")]
+	UnsupportedArchiveVersion(u16),
 }
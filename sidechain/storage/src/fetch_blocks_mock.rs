@@ -15,15 +15,29 @@
 
 */
 
-use crate::{error::Result, interface::FetchBlocks};
+use crate::{
+	error::Result,
+	interface::{
+		notify_new_block_subscribers, FetchBlocks, NewBlockCallback, QueryBlocks,
+		SubscribeNewBlocks,
+	},
+	storage::ShardStatistics,
+};
 use its_primitives::{
-	traits::ShardIdentifierFor,
-	types::{BlockHash, SignedBlock},
+	traits::{
+		Block as BlockTrait, BlockData as BlockDataTrait, Header as HeaderTrait,
+		ShardIdentifierFor, SignedBlock as SignedBlockTrait,
+	},
+	types::{justification::Justification, BlockHash, BlockNumber, SignedBlock},
 };
+use parking_lot::Mutex;
+use sp_core::H256;
 
 #[derive(Default)]
 pub struct FetchBlocksMock {
 	blocks_to_be_fetched: Vec<SignedBlock>,
+	justifications: Vec<Justification>,
+	new_block_subscribers: Mutex<Vec<NewBlockCallback<SignedBlock>>>,
 }
 
 impl FetchBlocksMock {
@@ -31,6 +45,26 @@ impl FetchBlocksMock {
 		self.blocks_to_be_fetched = blocks;
 		self
 	}
+
+	pub fn with_justifications(mut self, justifications: Vec<Justification>) -> Self {
+		self.justifications = justifications;
+		self
+	}
+
+	/// Hands the blocks to the subscribers, as if they were stored.
+	pub fn notify_new_blocks(&self, blocks: &[SignedBlock]) {
+		notify_new_block_subscribers(&mut self.new_block_subscribers.lock(), blocks);
+	}
+
+	pub fn number_of_new_block_subscribers(&self) -> usize {
+		self.new_block_subscribers.lock().len()
+	}
+}
+
+impl SubscribeNewBlocks<SignedBlock> for FetchBlocksMock {
+	fn subscribe_new_blocks(&self, callback: NewBlockCallback<SignedBlock>) {
+		self.new_block_subscribers.lock().push(callback);
+	}
 }
 
 impl FetchBlocks<SignedBlock> for FetchBlocksMock {
@@ -51,3 +85,80 @@ impl FetchBlocks<SignedBlock> for FetchBlocksMock {
 		Ok(self.blocks_to_be_fetched.clone())
 	}
 }
+
+impl QueryBlocks<SignedBlock> for FetchBlocksMock {
+	fn shards(&self) -> Vec<ShardIdentifierFor<SignedBlock>> {
+		let mut shards: Vec<_> = self
+			.blocks_to_be_fetched
+			.iter()
+			.map(|b| b.block().header().shard_id())
+			.collect();
+		shards.dedup();
+		shards
+	}
+
+	fn fetch_block(&self, block_hash: &BlockHash) -> Result<Option<SignedBlock>> {
+		Ok(self.blocks_to_be_fetched.iter().find(|b| &b.hash() == block_hash).cloned())
+	}
+
+	fn fetch_block_by_number(
+		&self,
+		shard_identifier: &ShardIdentifierFor<SignedBlock>,
+		block_number: BlockNumber,
+	) -> Result<Option<SignedBlock>> {
+		Ok(self
+			.blocks_to_be_fetched
+			.iter()
+			.find(|b| {
+				&b.block().header().shard_id() == shard_identifier
+					&& b.block().header().block_number() == block_number
+			})
+			.cloned())
+	}
+
+	fn fetch_latest_blocks(
+		&self,
+		shard_identifier: &ShardIdentifierFor<SignedBlock>,
+		max: usize,
+	) -> Result<Vec<SignedBlock>> {
+		Ok(self
+			.blocks_to_be_fetched
+			.iter()
+			.rev()
+			.filter(|b| &b.block().header().shard_id() == shard_identifier)
+			.take(max)
+			.cloned()
+			.collect())
+	}
+
+	fn fetch_block_by_signed_top(&self, top_hash: &H256) -> Result<Option<SignedBlock>> {
+		Ok(self
+			.blocks_to_be_fetched
+			.iter()
+			.find(|b| b.block().block_data().signed_top_hashes().contains(top_hash))
+			.cloned())
+	}
+
+	fn shard_statistics(
+		&self,
+		_shard_identifier: &ShardIdentifierFor<SignedBlock>,
+	) -> Option<ShardStatistics> {
+		None
+	}
+
+	fn fetch_justification(&self, block_hash: &BlockHash) -> Result<Option<Justification>> {
+		Ok(self.justifications.iter().find(|j| &j.block_hash() == block_hash).cloned())
+	}
+
+	fn fetch_finalized_head(
+		&self,
+		shard_identifier: &ShardIdentifierFor<SignedBlock>,
+	) -> Result<Option<Justification>> {
+		Ok(self
+			.justifications
+			.iter()
+			.filter(|j| &j.vote.shard == shard_identifier)
+			.max_by_key(|j| j.block_number())
+			.cloned())
+	}
+}
@@ -20,27 +20,45 @@ use mockall::predicate::*;
 #[cfg(test)]
 use mockall::*;
 
-use super::{storage::SidechainStorage, Result};
+use super::{
+	archive::SidechainBlockArchive,
+	storage::{ShardStatistics, SidechainStorage},
+	Result,
+};
 use its_primitives::{
 	traits::{ShardIdentifierFor, SignedBlock as SignedBlockT},
-	types::{BlockHash, BlockNumber},
+	types::{justification::Justification, BlockHash, BlockNumber, ShardIdentifier},
 };
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
+use sp_core::H256;
 use std::path::PathBuf;
 
+/// Called with every newly stored block. Returns `false` if it doesn't want any further blocks.
+pub type NewBlockCallback<SignedBlock> = Box<dyn Fn(&SignedBlock) -> bool + Send + Sync>;
+
 /// Lock wrapper around sidechain storage
 pub struct SidechainStorageLock<SignedBlock: SignedBlockT> {
 	storage: RwLock<SidechainStorage<SignedBlock>>,
+	new_block_subscribers: Mutex<Vec<NewBlockCallback<SignedBlock>>>,
 }
 
 impl<SignedBlock: SignedBlockT> SidechainStorageLock<SignedBlock> {
 	pub fn from_base_path(path: PathBuf) -> Result<SidechainStorageLock<SignedBlock>> {
 		Ok(SidechainStorageLock {
 			storage: RwLock::new(SidechainStorage::<SignedBlock>::load_from_base_path(path)?),
+			new_block_subscribers: Default::default(),
 		})
 	}
 }
 
+/// Hands the blocks to all subscribers, and removes the ones that are not interested anymore.
+pub fn notify_new_block_subscribers<SignedBlock>(
+	subscribers: &mut Vec<NewBlockCallback<SignedBlock>>,
+	blocks: &[SignedBlock],
+) {
+	subscribers.retain(|subscriber| blocks.iter().all(|block| subscriber(block)));
+}
+
 /// Storage interface Trait
 #[cfg_attr(test, automock)]
 pub trait BlockStorage<SignedBlock: SignedBlockT> {
@@ -50,6 +68,22 @@ pub trait BlockStorage<SignedBlock: SignedBlockT> {
 	fn store_blocks(&self, blocks: Vec<SignedBlock>) -> Result<()>;
 }
 
+/// Storage of the justifications of finalized blocks.
+#[cfg_attr(test, automock)]
+pub trait JustificationStorage {
+	fn store_justifications(&self, justifications: Vec<Justification>) -> Result<()>;
+
+	/// The justification of the latest finalized block of a shard.
+	fn finalized_head(&self, shard: &ShardIdentifier) -> Result<Option<Justification>>;
+}
+
+/// Subscriptions to newly stored blocks, e.g. for a block explorer.
+pub trait SubscribeNewBlocks<SignedBlock: SignedBlockT> {
+	/// Registers a callback, which is called with every block stored from now on,
+	/// until it returns `false`.
+	fn subscribe_new_blocks(&self, callback: NewBlockCallback<SignedBlock>);
+}
+
 pub trait BlockPruner {
 	/// Prune all blocks except the newest n, where n = `number_of_blocks_to_keep`.
 	fn prune_blocks_except(&self, number_of_blocks_to_keep: u64);
@@ -79,9 +113,86 @@ pub trait FetchBlocks<SignedBlock: SignedBlockT> {
 	) -> Result<Vec<SignedBlock>>;
 }
 
+/// Read access to single blocks, e.g. for a block explorer.
+pub trait QueryBlocks<SignedBlock: SignedBlockT> {
+	/// All shards with blocks in storage.
+	fn shards(&self) -> Vec<ShardIdentifierFor<SignedBlock>>;
+
+	fn fetch_block(&self, block_hash: &BlockHash) -> Result<Option<SignedBlock>>;
+
+	fn fetch_block_by_number(
+		&self,
+		shard_identifier: &ShardIdentifierFor<SignedBlock>,
+		block_number: BlockNumber,
+	) -> Result<Option<SignedBlock>>;
+
+	/// Fetch up to `max` of the most recent blocks of a shard, newest first.
+	fn fetch_latest_blocks(
+		&self,
+		shard_identifier: &ShardIdentifierFor<SignedBlock>,
+		max: usize,
+	) -> Result<Vec<SignedBlock>>;
+
+	/// Fetch the block which includes the signed trusted operation with the given hash.
+	fn fetch_block_by_signed_top(&self, top_hash: &H256) -> Result<Option<SignedBlock>>;
+
+	fn shard_statistics(
+		&self,
+		shard_identifier: &ShardIdentifierFor<SignedBlock>,
+	) -> Option<ShardStatistics>;
+
+	/// Fetch the justification of a finalized block.
+	fn fetch_justification(&self, block_hash: &BlockHash) -> Result<Option<Justification>>;
+
+	/// Fetch the justification of the latest finalized block of a shard.
+	fn fetch_finalized_head(
+		&self,
+		shard_identifier: &ShardIdentifierFor<SignedBlock>,
+	) -> Result<Option<Justification>>;
+}
+
+/// Export and import of all blocks of a shard, e.g. to move a shard to another worker.
+pub trait ArchiveBlocks<SignedBlock: SignedBlockT> {
+	/// Archive all stored blocks of a shard.
+	fn export_archive(
+		&self,
+		shard_identifier: &ShardIdentifierFor<SignedBlock>,
+	) -> Result<SidechainBlockArchive<ShardIdentifierFor<SignedBlock>, SignedBlock>>;
+
+	/// Import all blocks of an archive, which are not stored yet. Returns the number of imported blocks.
+	fn import_archive(
+		&self,
+		archive: SidechainBlockArchive<ShardIdentifierFor<SignedBlock>, SignedBlock>,
+	) -> Result<usize>;
+}
+
 impl<SignedBlock: SignedBlockT> BlockStorage<SignedBlock> for SidechainStorageLock<SignedBlock> {
 	fn store_blocks(&self, blocks: Vec<SignedBlock>) -> Result<()> {
-		self.storage.write().store_blocks(blocks)
+		let mut subscribers = self.new_block_subscribers.lock();
+		if subscribers.is_empty() {
+			return self.storage.write().store_blocks(blocks)
+		}
+		self.storage.write().store_blocks(blocks.clone())?;
+		notify_new_block_subscribers(&mut subscribers, &blocks);
+		Ok(())
+	}
+}
+
+impl<SignedBlock: SignedBlockT> SubscribeNewBlocks<SignedBlock>
+	for SidechainStorageLock<SignedBlock>
+{
+	fn subscribe_new_blocks(&self, callback: NewBlockCallback<SignedBlock>) {
+		self.new_block_subscribers.lock().push(callback);
+	}
+}
+
+impl<SignedBlock: SignedBlockT> JustificationStorage for SidechainStorageLock<SignedBlock> {
+	fn store_justifications(&self, justifications: Vec<Justification>) -> Result<()> {
+		self.storage.write().store_justifications(justifications)
+	}
+
+	fn finalized_head(&self, shard: &ShardIdentifier) -> Result<Option<Justification>> {
+		self.storage.read().get_finalized_head(shard)
 	}
 }
 
@@ -111,3 +222,72 @@ impl<SignedBlock: SignedBlockT> FetchBlocks<SignedBlock> for SidechainStorageLoc
 			.get_blocks_in_range(block_hash_from, block_hash_until, shard_identifier)
 	}
 }
+
+impl<SignedBlock: SignedBlockT> QueryBlocks<SignedBlock> for SidechainStorageLock<SignedBlock> {
+	fn shards(&self) -> Vec<ShardIdentifierFor<SignedBlock>> {
+		self.storage.read().shards().clone()
+	}
+
+	fn fetch_block(&self, block_hash: &BlockHash) -> Result<Option<SignedBlock>> {
+		self.storage.read().get_block(block_hash)
+	}
+
+	fn fetch_block_by_number(
+		&self,
+		shard_identifier: &ShardIdentifierFor<SignedBlock>,
+		block_number: BlockNumber,
+	) -> Result<Option<SignedBlock>> {
+		self.storage.read().get_block_by_number(shard_identifier, block_number)
+	}
+
+	fn fetch_latest_blocks(
+		&self,
+		shard_identifier: &ShardIdentifierFor<SignedBlock>,
+		max: usize,
+	) -> Result<Vec<SignedBlock>> {
+		self.storage.read().get_latest_blocks(shard_identifier, max)
+	}
+
+	fn fetch_block_by_signed_top(&self, top_hash: &H256) -> Result<Option<SignedBlock>> {
+		let storage = self.storage.read();
+		match storage.get_block_hash_by_signed_top(top_hash)? {
+			Some(block_hash) => storage.get_block(&block_hash),
+			None => Ok(None),
+		}
+	}
+
+	fn shard_statistics(
+		&self,
+		shard_identifier: &ShardIdentifierFor<SignedBlock>,
+	) -> Option<ShardStatistics> {
+		self.storage.read().shard_statistics(shard_identifier).copied()
+	}
+
+	fn fetch_justification(&self, block_hash: &BlockHash) -> Result<Option<Justification>> {
+		self.storage.read().get_justification(block_hash)
+	}
+
+	fn fetch_finalized_head(
+		&self,
+		shard_identifier: &ShardIdentifierFor<SignedBlock>,
+	) -> Result<Option<Justification>> {
+		self.storage.read().get_finalized_head(shard_identifier)
+	}
+}
+
+impl<SignedBlock: SignedBlockT> ArchiveBlocks<SignedBlock> for SidechainStorageLock<SignedBlock> {
+	fn export_archive(
+		&self,
+		shard_identifier: &ShardIdentifierFor<SignedBlock>,
+	) -> Result<SidechainBlockArchive<ShardIdentifierFor<SignedBlock>, SignedBlock>> {
+		let blocks = self.storage.read().export_shard(shard_identifier)?;
+		Ok(SidechainBlockArchive::new(*shard_identifier, blocks))
+	}
+
+	fn import_archive(
+		&self,
+		archive: SidechainBlockArchive<ShardIdentifierFor<SignedBlock>, SignedBlock>,
+	) -> Result<usize> {
+		self.storage.write().import_blocks(&archive.shard, archive.blocks)
+	}
+}
@@ -27,6 +27,7 @@ use std::{
 	time::{Duration, SystemTime},
 };
 
+pub mod archive;
 mod db;
 mod error;
 pub mod interface;
@@ -38,14 +39,28 @@ mod storage_tests_get_blocks_after;
 #[cfg(test)]
 mod storage_tests_get_blocks_in_range;
 
+#[cfg(test)]
+mod storage_tests_get_latest_blocks;
+
+#[cfg(test)]
+mod storage_tests_import_blocks;
+
+#[cfg(test)]
+mod storage_tests_justifications;
+
 #[cfg(test)]
 mod test_utils;
 
 #[cfg(feature = "mocks")]
 pub mod fetch_blocks_mock;
 
+pub use archive::SidechainBlockArchive;
 pub use error::{Error, Result};
-pub use interface::{BlockPruner, BlockStorage, SidechainStorageLock};
+pub use interface::{
+	ArchiveBlocks, BlockPruner, BlockStorage, JustificationStorage, NewBlockCallback, QueryBlocks,
+	SidechainStorageLock, SubscribeNewBlocks,
+};
+pub use storage::ShardStatistics;
 
 pub fn start_sidechain_pruning_loop<D>(
 	storage: &Arc<D>,
@@ -19,11 +19,15 @@ use super::{db::SidechainDB, Error, Result};
 use codec::{Decode, Encode};
 use itp_settings::files::SIDECHAIN_STORAGE_PATH;
 use its_primitives::{
-	traits::{Block as BlockTrait, Header as HeaderTrait, SignedBlock as SignedBlockT},
-	types::{BlockHash, BlockNumber},
+	traits::{
+		Block as BlockTrait, BlockData as BlockDataTrait, Header as HeaderTrait,
+		SignedBlock as SignedBlockT,
+	},
+	types::{justification::Justification, BlockHash, BlockNumber},
 };
 use log::*;
 use rocksdb::WriteBatch;
+use serde::{Deserialize, Serialize};
 use sp_core::H256;
 use std::{collections::HashMap, fmt::Debug, path::PathBuf};
 
@@ -31,6 +35,14 @@ use std::{collections::HashMap, fmt::Debug, path::PathBuf};
 const LAST_BLOCK_KEY: &[u8] = b"last_sidechainblock";
 /// key value of the stored shards vector
 const STORED_SHARDS_KEY: &[u8] = b"stored_shards";
+/// key prefix of the statistics of every shard
+const SHARD_STATISTICS_KEY: &[u8] = b"shard_statistics";
+/// key prefix of the index from signed trusted operation hash to including block hash
+const SIGNED_TOP_KEY: &[u8] = b"signed_top";
+/// key prefix of the justifications of finalized blocks
+const JUSTIFICATION_KEY: &[u8] = b"justification";
+/// key prefix of the justification of the latest finalized block of every shard
+const FINALIZED_HEAD_KEY: &[u8] = b"finalized_head";
 
 /// ShardIdentifier type
 type ShardIdentifierFor<B> =
@@ -46,6 +58,20 @@ pub struct LastSidechainBlock {
 	pub number: BlockNumber,
 }
 
+/// Helper struct, contains statistics over all blocks ever stored for a shard.
+/// Pruning blocks does not change the statistics.
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, Debug, Default, Serialize, Deserialize)]
+pub struct ShardStatistics {
+	/// number of stored blocks
+	pub block_count: u64,
+	/// number of trusted operations included in the stored blocks
+	pub signed_top_count: u64,
+	/// timestamp of the first stored block
+	pub first_block_timestamp: u64,
+	/// timestamp of the last stored block
+	pub last_block_timestamp: u64,
+}
+
 /// Struct used to insert newly produced sidechainblocks
 /// into the database
 pub struct SidechainStorage<SignedBlock: SignedBlockT> {
@@ -55,6 +81,8 @@ pub struct SidechainStorage<SignedBlock: SignedBlockT> {
 	shards: Vec<ShardIdentifierFor<SignedBlock>>,
 	/// map to last sidechain block of every shard
 	last_blocks: HashMap<ShardIdentifierFor<SignedBlock>, LastSidechainBlock>,
+	/// map to the statistics of every shard
+	statistics: HashMap<ShardIdentifierFor<SignedBlock>, ShardStatistics>,
 }
 
 impl<SignedBlock: SignedBlockT> SidechainStorage<SignedBlock> {
@@ -64,7 +92,12 @@ impl<SignedBlock: SignedBlockT> SidechainStorage<SignedBlock> {
 	pub fn load_from_base_path(base_path: PathBuf) -> Result<SidechainStorage<SignedBlock>> {
 		// load db
 		let db = SidechainDB::open_default(base_path.join(SIDECHAIN_STORAGE_PATH))?;
-		let mut storage = SidechainStorage { db, shards: vec![], last_blocks: HashMap::new() };
+		let mut storage = SidechainStorage {
+			db,
+			shards: vec![],
+			last_blocks: HashMap::new(),
+			statistics: HashMap::new(),
+		};
 		storage.shards = storage.load_shards_from_db()?;
 		// get last block of each shard
 		for shard in storage.shards.iter() {
@@ -74,6 +107,9 @@ impl<SignedBlock: SignedBlockT> SidechainStorage<SignedBlock> {
 				// an empty shard sidechain storage should not exist. Consider deleting this shard from the shards list.
 				error!("Sidechain storage of shard {:?} is empty", shard);
 			}
+			if let Some(statistics) = storage.db.get((SHARD_STATISTICS_KEY, *shard))? {
+				storage.statistics.insert(*shard, statistics);
+			}
 		}
 		Ok(storage)
 	}
@@ -91,6 +127,32 @@ impl<SignedBlock: SignedBlockT> SidechainStorage<SignedBlock> {
 		self.last_blocks.get(shard)
 	}
 
+	/// gets the statistics of the given shard, if it has any blocks
+	pub fn shard_statistics(
+		&self,
+		shard: &ShardIdentifierFor<SignedBlock>,
+	) -> Option<&ShardStatistics> {
+		self.statistics.get(shard)
+	}
+
+	/// gets the hash of the block which includes the given signed trusted operation, if there is such a block
+	pub fn get_block_hash_by_signed_top(&self, top_hash: &H256) -> Result<Option<BlockHash>> {
+		self.db.get((SIGNED_TOP_KEY, top_hash))
+	}
+
+	/// gets the justification of the given block hash, if the block has been finalized
+	pub fn get_justification(&self, block_hash: &BlockHash) -> Result<Option<Justification>> {
+		self.db.get((JUSTIFICATION_KEY, block_hash))
+	}
+
+	/// gets the justification of the latest finalized block of the given shard, if there is one
+	pub fn get_finalized_head<Shard: Encode>(
+		&self,
+		shard: &Shard,
+	) -> Result<Option<Justification>> {
+		self.db.get((FINALIZED_HEAD_KEY, shard))
+	}
+
 	/// gets the block hash of the sidechain block of the given shard and block number, if there is such a block
 	pub fn get_block_hash(
 		&self,
@@ -101,11 +163,61 @@ impl<SignedBlock: SignedBlockT> SidechainStorage<SignedBlock> {
 	}
 
 	/// gets the block of the given blockhash, if there is such a block
-	#[allow(unused)]
 	pub fn get_block(&self, block_hash: &BlockHash) -> Result<Option<SignedBlock>> {
 		self.db.get(block_hash)
 	}
 
+	/// gets the block of the given shard and block number, if there is such a block
+	pub fn get_block_by_number(
+		&self,
+		shard: &ShardIdentifierFor<SignedBlock>,
+		block_number: BlockNumber,
+	) -> Result<Option<SignedBlock>> {
+		match self.get_block_hash(shard, block_number)? {
+			Some(block_hash) => self.get_block(&block_hash),
+			None => Ok(None),
+		}
+	}
+
+	/// Get up to `max` of the most recent blocks of a shard, newest first.
+	///
+	/// Stops early at the genesis block or at a pruned block.
+	pub fn get_latest_blocks(
+		&self,
+		shard: &ShardIdentifierFor<SignedBlock>,
+		max: usize,
+	) -> Result<Vec<SignedBlock>> {
+		let mut blocks = Vec::new();
+		let mut next_block_hash = match self.last_block_of_shard(shard) {
+			Some(last_block) => last_block.hash,
+			None => return Ok(blocks),
+		};
+		while blocks.len() < max {
+			let block = match self.get_block(&next_block_hash)? {
+				Some(block) => block,
+				None => break,
+			};
+			next_block_hash = block.block().header().parent_hash();
+			blocks.push(block);
+			if next_block_hash == BlockHash::default() {
+				break
+			}
+		}
+		Ok(blocks)
+	}
+
+	/// Get all stored blocks of a shard, oldest first.
+	///
+	/// Blocks that have already been pruned are not included.
+	pub fn export_shard(
+		&self,
+		shard: &ShardIdentifierFor<SignedBlock>,
+	) -> Result<Vec<SignedBlock>> {
+		let mut blocks = self.get_latest_blocks(shard, usize::MAX)?;
+		blocks.reverse();
+		Ok(blocks)
+	}
+
 	/// Get all blocks after (i.e. children of) a specified block.
 	pub fn get_blocks_after(
 		&self,
@@ -184,6 +296,72 @@ impl<SignedBlock: SignedBlockT> SidechainStorage<SignedBlock> {
 		self.db.write(batch)
 	}
 
+	/// Store the justifications of finalized blocks.
+	///
+	/// The finalized head of a shard is only updated by justifications of newer blocks.
+	pub fn store_justifications(&mut self, justifications: Vec<Justification>) -> Result<()> {
+		let mut batch = WriteBatch::default();
+		let mut finalized_heads = HashMap::new();
+		for justification in justifications.into_iter() {
+			let shard = justification.vote.shard;
+			let finalized_number = match finalized_heads.get(&shard) {
+				Some(number) => Some(*number),
+				None => self
+					.db
+					.get::<_, Justification>((FINALIZED_HEAD_KEY, shard))?
+					.map(|j| j.block_number()),
+			};
+			if finalized_number.map_or(true, |n| n < justification.block_number()) {
+				finalized_heads.insert(shard, justification.block_number());
+				SidechainDB::add_to_batch(&mut batch, (FINALIZED_HEAD_KEY, shard), &justification);
+			}
+			SidechainDB::add_to_batch(
+				&mut batch,
+				(JUSTIFICATION_KEY, justification.block_hash()),
+				&justification,
+			);
+		}
+		self.db.write(batch)
+	}
+
+	/// Import blocks of a single shard, oldest first, e.g. from an archive.
+	///
+	/// In contrast to `store_blocks`, either all blocks are imported or none. Blocks which are
+	/// already stored are skipped, all others must be a direct successor of the last block.
+	/// Returns the number of imported blocks.
+	pub fn import_blocks(
+		&mut self,
+		shard: &ShardIdentifierFor<SignedBlock>,
+		blocks_to_import: Vec<SignedBlock>,
+	) -> Result<usize> {
+		let mut batch = WriteBatch::default();
+		let mut new_shard = false;
+		// Keep the in-memory state, such that it can be restored if a block is rejected.
+		let shards = self.shards.clone();
+		let last_blocks = self.last_blocks.clone();
+		let statistics = self.statistics.clone();
+
+		let imported = match self.add_imported_blocks_to_batch(
+			shard,
+			blocks_to_import,
+			&mut new_shard,
+			&mut batch,
+		) {
+			Ok(imported) => imported,
+			Err(e) => {
+				self.shards = shards;
+				self.last_blocks = last_blocks;
+				self.statistics = statistics;
+				return Err(e)
+			},
+		};
+		if new_shard {
+			SidechainDB::add_to_batch(&mut batch, STORED_SHARDS_KEY, self.shards().clone());
+		}
+		self.db.write(batch)?;
+		Ok(imported)
+	}
+
 	/// purges a shard and its block from the db storage
 	pub fn purge_shard(&mut self, shard: &ShardIdentifierFor<SignedBlock>) -> Result<()> {
 		// get last block of shard
@@ -199,6 +377,10 @@ impl<SignedBlock: SignedBlockT> SidechainStorage<SignedBlock> {
 			current_block_number = previous_block.number;
 			self.delete_block(&mut batch, &previous_block.hash, &current_block_number, shard);
 		}
+		// Remove statistics and finalized head of shard.
+		SidechainDB::delete_to_batch(&mut batch, (SHARD_STATISTICS_KEY, *shard));
+		SidechainDB::delete_to_batch(&mut batch, (FINALIZED_HEAD_KEY, *shard));
+		self.statistics.remove(shard);
 		// Remove shard from list.
 		// STORED_SHARDS_KEY -> Vec<(Shard)>
 		self.shards.retain(|&x| x != *shard);
@@ -271,9 +453,39 @@ impl<SignedBlock: SignedBlockT> SidechainStorage<SignedBlock> {
 		}
 		// Add block to DB batch.
 		self.add_last_block(batch, signed_block);
+		self.add_block_indexes(batch, signed_block);
 		Ok(())
 	}
 
+	fn add_imported_blocks_to_batch(
+		&mut self,
+		shard: &ShardIdentifierFor<SignedBlock>,
+		blocks_to_import: Vec<SignedBlock>,
+		new_shard: &mut bool,
+		batch: &mut WriteBatch,
+	) -> Result<usize> {
+		let mut imported = 0;
+		for block in blocks_to_import.into_iter() {
+			let header = block.block().header();
+			if header.shard_id() != *shard {
+				return Err(Error::ShardMismatch(format!("{:?}", header.shard_id())))
+			}
+			if self.get_block(&block.hash())?.is_some() {
+				debug!("[Sidechain DB] Skipping import of already stored block {:?}", block.hash());
+				continue
+			}
+			// `verify_block_ancestry` only checks the block number, an import needs to be linked by hash too.
+			if let Some(last_block) = self.last_block_of_shard(shard) {
+				if header.parent_hash() != last_block.hash {
+					return Err(Error::HeaderAncestryMismatch)
+				}
+			}
+			self.add_block_to_batch(&block, new_shard, batch)?;
+			imported += 1;
+		}
+		Ok(imported)
+	}
+
 	fn verify_block_ancestry(&self, block: &<SignedBlock as SignedBlockT>::Block) -> bool {
 		let shard = &block.header().shard_id();
 		let current_block_nr = block.header().block_number();
@@ -347,6 +559,28 @@ impl<SignedBlock: SignedBlockT> SidechainStorage<SignedBlock> {
 		SidechainDB::add_to_batch(batch, (LAST_BLOCK_KEY, shard), last_block);
 	}
 
+	/// Adds the secondary indexes of the block to the WriteBatch and updates the shard statistics.
+	fn add_block_indexes(&mut self, batch: &mut WriteBatch, block: &SignedBlock) {
+		let hash = block.hash();
+		let shard = block.block().header().shard_id();
+		let block_data = block.block().block_data();
+
+		// (SIGNED_TOP_KEY, Signed TOP hash) -> Blockhash.
+		for top_hash in block_data.signed_top_hashes() {
+			SidechainDB::add_to_batch(batch, (SIGNED_TOP_KEY, top_hash), hash);
+		}
+
+		// (SHARD_STATISTICS_KEY, shard) -> ShardStatistics.
+		let statistics = self.statistics.entry(shard).or_default();
+		if statistics.block_count == 0 {
+			statistics.first_block_timestamp = block_data.timestamp();
+		}
+		statistics.block_count += 1;
+		statistics.signed_top_count += block_data.signed_top_hashes().len() as u64;
+		statistics.last_block_timestamp = block_data.timestamp();
+		SidechainDB::add_to_batch(batch, (SHARD_STATISTICS_KEY, shard), *statistics);
+	}
+
 	/// Add delete block to the WriteBatch.
 	fn delete_block(
 		&self,
@@ -355,6 +589,18 @@ impl<SignedBlock: SignedBlockT> SidechainStorage<SignedBlock> {
 		block_number: &BlockNumber,
 		shard: &ShardIdentifierFor<SignedBlock>,
 	) {
+		// (SIGNED_TOP_KEY, Signed TOP hash) -> Blockhash, unless a newer block included the same TOP.
+		if let Ok(Some(block)) = self.get_block(block_hash) {
+			for top_hash in block.block().block_data().signed_top_hashes() {
+				if self.get_block_hash_by_signed_top(top_hash).ok().flatten().as_ref()
+					== Some(block_hash)
+				{
+					SidechainDB::delete_to_batch(batch, (SIGNED_TOP_KEY, top_hash));
+				}
+			}
+		}
+		// (JUSTIFICATION_KEY, Block hash) -> Justification.
+		SidechainDB::delete_to_batch(batch, (JUSTIFICATION_KEY, block_hash));
 		// Block hash -> Signed Block.
 		SidechainDB::delete_to_batch(batch, block_hash);
 		// (Shard, Block number) -> Blockhash (for block pruning).
@@ -393,7 +639,8 @@ impl<SignedBlock: SignedBlockT> SidechainStorage<SignedBlock> {
 mod test {
 	use super::*;
 	use crate::test_utils::{
-		create_signed_block_with_shard as create_signed_block, create_temp_dir, get_storage,
+		create_signed_block_with_shard as create_signed_block,
+		create_signed_block_with_signed_tops, create_temp_dir, get_storage,
 	};
 	use itp_types::ShardIdentifier;
 	use its_primitives::{traits::SignedBlock as SignedBlockT, types::SignedBlock};
@@ -813,6 +1060,78 @@ mod test {
 		}
 	}
 
+	#[test]
+	fn store_blocks_updates_indexes_and_statistics() {
+		let temp_dir = create_temp_dir();
+		let shard = H256::from_low_u64_be(1);
+		let top_one = H256::from_low_u64_be(11);
+		let top_two = H256::from_low_u64_be(12);
+		let top_three = H256::from_low_u64_be(13);
+		let block_one = create_signed_block_with_signed_tops(1, shard, 1000, vec![top_one]);
+		let block_two =
+			create_signed_block_with_signed_tops(2, shard, 2000, vec![top_two, top_three]);
+
+		{
+			let mut sidechain_db = get_storage(temp_dir.path().to_path_buf());
+			sidechain_db.store_blocks(vec![block_one.clone()]).unwrap();
+			sidechain_db.store_blocks(vec![block_two.clone()]).unwrap();
+		}
+
+		{
+			// open new DB of same path:
+			let updated_sidechain_db = get_storage(temp_dir.path().to_path_buf());
+			assert_eq!(
+				updated_sidechain_db.get_block_hash_by_signed_top(&top_one).unwrap(),
+				Some(block_one.hash())
+			);
+			assert_eq!(
+				updated_sidechain_db.get_block_hash_by_signed_top(&top_three).unwrap(),
+				Some(block_two.hash())
+			);
+			assert_eq!(
+				updated_sidechain_db
+					.get_block_hash_by_signed_top(&H256::from_low_u64_be(14))
+					.unwrap(),
+				None
+			);
+			assert_eq!(
+				updated_sidechain_db.shard_statistics(&shard),
+				Some(&ShardStatistics {
+					block_count: 2,
+					signed_top_count: 3,
+					first_block_timestamp: 1000,
+					last_block_timestamp: 2000,
+				})
+			);
+		}
+	}
+
+	#[test]
+	fn pruning_removes_signed_top_index_but_keeps_statistics() {
+		let temp_dir = create_temp_dir();
+		let shard = H256::from_low_u64_be(1);
+		let top_one = H256::from_low_u64_be(11);
+		let top_two = H256::from_low_u64_be(12);
+		let block_one = create_signed_block_with_signed_tops(1, shard, 1000, vec![top_one]);
+		let block_two = create_signed_block_with_signed_tops(2, shard, 2000, vec![top_two]);
+
+		{
+			let mut sidechain_db = get_storage(temp_dir.path().to_path_buf());
+			sidechain_db.store_blocks(vec![block_one, block_two.clone()]).unwrap();
+			sidechain_db.prune_shards(1);
+		}
+
+		{
+			let updated_sidechain_db = get_storage(temp_dir.path().to_path_buf());
+			assert_eq!(updated_sidechain_db.get_block_hash_by_signed_top(&top_one).unwrap(), None);
+			assert_eq!(
+				updated_sidechain_db.get_block_hash_by_signed_top(&top_two).unwrap(),
+				Some(block_two.hash())
+			);
+			assert_eq!(updated_sidechain_db.shard_statistics(&shard).unwrap().block_count, 2);
+		}
+	}
+
 	#[test]
 	fn store_blocks_on_multi_sharding_works() {
 		let temp_dir = create_temp_dir();
@@ -990,6 +1309,7 @@ mod test {
 			// test if local storage has been cleansed
 			assert!(!sidechain_db.shards.contains(&shard));
 			assert!(sidechain_db.last_blocks.get(&shard).is_none());
+			assert!(sidechain_db.shard_statistics(&shard).is_none());
 		}
 
 		{
@@ -0,0 +1,79 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+use crate::test_utils::{
+	create_signed_block_with_parenthash as create_signed_block, default_shard,
+	fill_storage_with_blocks, get_storage,
+};
+use its_primitives::{traits::SignedBlock, types::BlockHash};
+use sp_core::H256;
+
+#[test]
+fn get_latest_blocks_returns_newest_first() {
+	let block_1 = create_signed_block(1, BlockHash::default());
+	let block_2 = create_signed_block(2, block_1.hash());
+	let block_3 = create_signed_block(3, block_2.hash());
+
+	let temp_dir =
+		fill_storage_with_blocks(vec![block_1.clone(), block_2.clone(), block_3.clone()]);
+
+	{
+		let updated_sidechain_db = get_storage(temp_dir.path().to_path_buf());
+
+		assert_eq!(
+			updated_sidechain_db.get_latest_blocks(&default_shard(), 2).unwrap(),
+			vec![block_3.clone(), block_2.clone()]
+		);
+		assert_eq!(
+			updated_sidechain_db.get_latest_blocks(&default_shard(), 10).unwrap(),
+			vec![block_3, block_2, block_1]
+		);
+	}
+}
+
+#[test]
+fn get_latest_blocks_returns_empty_vec_for_unknown_shard() {
+	let block_1 = create_signed_block(1, BlockHash::default());
+
+	let temp_dir = fill_storage_with_blocks(vec![block_1]);
+
+	{
+		let updated_sidechain_db = get_storage(temp_dir.path().to_path_buf());
+
+		assert!(updated_sidechain_db
+			.get_latest_blocks(&H256::from_low_u64_be(42), 10)
+			.unwrap()
+			.is_empty());
+	}
+}
+
+#[test]
+fn get_block_by_number_works() {
+	let block_1 = create_signed_block(1, BlockHash::default());
+	let block_2 = create_signed_block(2, block_1.hash());
+
+	let temp_dir = fill_storage_with_blocks(vec![block_1.clone(), block_2.clone()]);
+
+	{
+		let updated_sidechain_db = get_storage(temp_dir.path().to_path_buf());
+
+		assert_eq!(
+			updated_sidechain_db.get_block_by_number(&default_shard(), 2).unwrap(),
+			Some(block_2)
+		);
+		assert_eq!(updated_sidechain_db.get_block_by_number(&default_shard(), 3).unwrap(), None);
+	}
+}
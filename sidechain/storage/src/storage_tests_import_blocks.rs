@@ -0,0 +1,118 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+use crate::{
+	error::Error,
+	test_utils::{
+		create_signed_block_with_parenthash as create_signed_block, create_temp_dir, default_shard,
+		fill_storage_with_blocks, get_storage,
+	},
+};
+use its_primitives::{traits::SignedBlock, types::BlockHash};
+use sp_core::H256;
+use std::assert_matches::assert_matches;
+
+#[test]
+fn exported_blocks_can_be_imported_into_empty_storage() {
+	let block_1 = create_signed_block(1, BlockHash::default());
+	let block_2 = create_signed_block(2, block_1.hash());
+	let block_3 = create_signed_block(3, block_2.hash());
+
+	let source_dir = fill_storage_with_blocks(vec![block_1.clone(), block_2, block_3]);
+	let exported_blocks = get_storage(source_dir.path().to_path_buf())
+		.export_shard(&default_shard())
+		.unwrap();
+	assert_eq!(exported_blocks.first(), Some(&block_1));
+
+	let target_dir = create_temp_dir();
+	{
+		let mut target_db = get_storage(target_dir.path().to_path_buf());
+		assert_eq!(target_db.import_blocks(&default_shard(), exported_blocks.clone()).unwrap(), 3);
+	}
+
+	let updated_target_db = get_storage(target_dir.path().to_path_buf());
+	assert_eq!(updated_target_db.shards(), &vec![default_shard()]);
+	assert_eq!(updated_target_db.export_shard(&default_shard()).unwrap(), exported_blocks);
+}
+
+#[test]
+fn import_skips_already_stored_blocks() {
+	let block_1 = create_signed_block(1, BlockHash::default());
+	let block_2 = create_signed_block(2, block_1.hash());
+	let block_3 = create_signed_block(3, block_2.hash());
+
+	let temp_dir = fill_storage_with_blocks(vec![block_1.clone(), block_2.clone()]);
+	let mut sidechain_db = get_storage(temp_dir.path().to_path_buf());
+
+	assert_eq!(
+		sidechain_db
+			.import_blocks(&default_shard(), vec![block_1, block_2, block_3.clone()])
+			.unwrap(),
+		1
+	);
+	assert_eq!(sidechain_db.last_block_of_shard(&default_shard()).unwrap().hash, block_3.hash());
+}
+
+#[test]
+fn import_is_rejected_entirely_if_a_block_is_not_linked_to_its_parent() {
+	let block_1 = create_signed_block(1, BlockHash::default());
+	let block_2 = create_signed_block(2, block_1.hash());
+	let block_3 = create_signed_block(3, H256::random());
+
+	let temp_dir = fill_storage_with_blocks(vec![block_1.clone()]);
+	{
+		let mut sidechain_db = get_storage(temp_dir.path().to_path_buf());
+		assert_matches!(
+			sidechain_db.import_blocks(&default_shard(), vec![block_2.clone(), block_3]),
+			Err(Error::HeaderAncestryMismatch)
+		);
+		assert_eq!(
+			sidechain_db.last_block_of_shard(&default_shard()).unwrap().hash,
+			block_1.hash()
+		);
+	}
+
+	let updated_sidechain_db = get_storage(temp_dir.path().to_path_buf());
+	assert_eq!(updated_sidechain_db.get_block(&block_2.hash()).unwrap(), None);
+}
+
+#[test]
+fn import_is_rejected_if_block_numbers_are_not_consecutive() {
+	let block_1 = create_signed_block(1, BlockHash::default());
+	let block_3 = create_signed_block(3, block_1.hash());
+
+	let temp_dir = create_temp_dir();
+	let mut sidechain_db = get_storage(temp_dir.path().to_path_buf());
+
+	assert_matches!(
+		sidechain_db.import_blocks(&default_shard(), vec![block_1, block_3]),
+		Err(Error::HeaderAncestryMismatch)
+	);
+	assert!(sidechain_db.shards().is_empty());
+}
+
+#[test]
+fn import_is_rejected_if_block_belongs_to_other_shard() {
+	let block_1 = create_signed_block(1, BlockHash::default());
+
+	let temp_dir = create_temp_dir();
+	let mut sidechain_db = get_storage(temp_dir.path().to_path_buf());
+
+	assert_matches!(
+		sidechain_db.import_blocks(&H256::from_low_u64_be(42), vec![block_1]),
+		Err(Error::ShardMismatch(_))
+	);
+}
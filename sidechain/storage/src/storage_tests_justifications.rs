@@ -0,0 +1,103 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+use crate::test_utils::{
+	create_signed_block_with_parenthash as create_signed_block, default_shard,
+	fill_storage_with_blocks, get_storage,
+};
+use its_primitives::{
+	traits::{Block as BlockTrait, Header as HeaderTrait, SignedBlock},
+	types::{
+		justification::{FinalityVote, Justification},
+		BlockHash, SignedBlock as SignedSidechainBlock,
+	},
+};
+use sp_core::{ed25519, Pair};
+
+fn justification_of(block: &SignedSidechainBlock) -> Justification {
+	let signer = ed25519::Pair::from_seed(&[1u8; 32]);
+	let vote = FinalityVote {
+		shard: block.block().header().shard_id(),
+		block_number: block.block().header().block_number(),
+		block_hash: block.hash(),
+	}
+	.sign(&signer);
+	Justification { vote: vote.vote, votes: vec![vote] }
+}
+
+#[test]
+fn stored_justifications_can_be_fetched_by_block_hash() {
+	let block_1 = create_signed_block(1, BlockHash::default());
+	let block_2 = create_signed_block(2, block_1.hash());
+	let temp_dir = fill_storage_with_blocks(vec![block_1.clone(), block_2.clone()]);
+
+	{
+		let mut sidechain_db = get_storage(temp_dir.path().to_path_buf());
+		sidechain_db.store_justifications(vec![justification_of(&block_2)]).unwrap();
+	}
+
+	{
+		let updated_sidechain_db = get_storage(temp_dir.path().to_path_buf());
+		assert_eq!(
+			updated_sidechain_db.get_justification(&block_2.hash()).unwrap(),
+			Some(justification_of(&block_2))
+		);
+		assert_eq!(updated_sidechain_db.get_justification(&block_1.hash()).unwrap(), None);
+	}
+}
+
+#[test]
+fn finalized_head_is_only_updated_by_newer_blocks() {
+	let block_1 = create_signed_block(1, BlockHash::default());
+	let block_2 = create_signed_block(2, block_1.hash());
+	let temp_dir = fill_storage_with_blocks(vec![block_1.clone(), block_2.clone()]);
+
+	{
+		let mut sidechain_db = get_storage(temp_dir.path().to_path_buf());
+		assert_eq!(sidechain_db.get_finalized_head(&default_shard()).unwrap(), None);
+
+		sidechain_db.store_justifications(vec![justification_of(&block_2)]).unwrap();
+		sidechain_db.store_justifications(vec![justification_of(&block_1)]).unwrap();
+	}
+
+	{
+		let updated_sidechain_db = get_storage(temp_dir.path().to_path_buf());
+		assert_eq!(
+			updated_sidechain_db.get_finalized_head(&default_shard()).unwrap(),
+			Some(justification_of(&block_2))
+		);
+		assert!(updated_sidechain_db.get_justification(&block_1.hash()).unwrap().is_some());
+	}
+}
+
+#[test]
+fn purging_shard_removes_justifications() {
+	let block_1 = create_signed_block(1, BlockHash::default());
+	let temp_dir = fill_storage_with_blocks(vec![block_1.clone()]);
+
+	{
+		let mut sidechain_db = get_storage(temp_dir.path().to_path_buf());
+		sidechain_db.store_justifications(vec![justification_of(&block_1)]).unwrap();
+		sidechain_db.purge_shard(&default_shard()).unwrap();
+	}
+
+	{
+		let updated_sidechain_db = get_storage(temp_dir.path().to_path_buf());
+		assert_eq!(updated_sidechain_db.get_justification(&block_1.hash()).unwrap(), None);
+		assert_eq!(updated_sidechain_db.get_finalized_head(&default_shard()).unwrap(), None);
+	}
+}
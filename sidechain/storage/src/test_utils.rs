@@ -81,6 +81,28 @@ pub fn create_signed_block_with_shard(
 		.build_signed()
 }
 
+pub fn create_signed_block_with_signed_tops(
+	block_number: u64,
+	shard: ShardIdentifier,
+	timestamp: u64,
+	signed_top_hashes: Vec<H256>,
+) -> SignedSidechainBlock {
+	let header = default_header_builder()
+		.with_shard(shard)
+		.with_block_number(block_number)
+		.build();
+
+	let block_data = default_block_data_builder()
+		.with_timestamp(timestamp)
+		.with_signed_top_hashes(signed_top_hashes)
+		.build();
+
+	SidechainBlockBuilder::default()
+		.with_header(header)
+		.with_block_data(block_data)
+		.build_signed()
+}
+
 fn default_header_builder() -> SidechainHeaderBuilder {
 	SidechainHeaderBuilder::default()
 		.with_parent_hash(H256::random())
@@ -18,24 +18,31 @@
 
 //! Builder pattern for a sidechain header.
 
-use its_primitives::types::{header::SidechainHeader as Header, ShardIdentifier};
+use its_primitives::types::{
+	header::{SidechainHeader as Header, SIDECHAIN_HEADER_VERSION},
+	ShardIdentifier,
+};
 use sp_core::H256;
 
 pub struct SidechainHeaderBuilder {
+	version: u8,
 	parent_hash: H256,
 	block_number: u64,
 	shard_id: ShardIdentifier,
 	block_data_hash: H256,
+	state_root: H256,
 	next_finalization_block_number: u64,
 }
 
 impl Default for SidechainHeaderBuilder {
 	fn default() -> Self {
 		SidechainHeaderBuilder {
+			version: SIDECHAIN_HEADER_VERSION,
 			parent_hash: Default::default(),
 			block_number: 1,
 			shard_id: Default::default(),
 			block_data_hash: Default::default(),
+			state_root: Default::default(),
 			next_finalization_block_number: 1,
 		}
 	}
@@ -44,14 +51,21 @@ impl Default for SidechainHeaderBuilder {
 impl SidechainHeaderBuilder {
 	pub fn random() -> Self {
 		SidechainHeaderBuilder {
+			version: SIDECHAIN_HEADER_VERSION,
 			parent_hash: H256::random(),
 			block_number: 42,
 			shard_id: ShardIdentifier::random(),
 			block_data_hash: H256::random(),
+			state_root: H256::random(),
 			next_finalization_block_number: 1,
 		}
 	}
 
+	pub fn with_version(mut self, version: u8) -> Self {
+		self.version = version;
+		self
+	}
+
 	pub fn with_parent_hash(mut self, parent_hash: H256) -> Self {
 		self.parent_hash = parent_hash;
 		self
@@ -72,6 +86,11 @@ impl SidechainHeaderBuilder {
 		self
 	}
 
+	pub fn with_state_root(mut self, state_root: H256) -> Self {
+		self.state_root = state_root;
+		self
+	}
+
 	pub fn with_next_finalization_block_number(
 		mut self,
 		next_finalization_block_number: u64,
@@ -82,10 +101,12 @@ impl SidechainHeaderBuilder {
 
 	pub fn build(self) -> Header {
 		Header {
+			version: self.version,
 			parent_hash: self.parent_hash,
 			block_number: self.block_number,
 			shard_id: self.shard_id,
 			block_data_hash: self.block_data_hash,
+			state_root: self.state_root,
 			next_finalization_block_number: self.next_finalization_block_number,
 		}
 	}
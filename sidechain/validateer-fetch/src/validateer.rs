@@ -17,9 +17,11 @@
 
 use crate::error::{Error, Result};
 use itp_ocall_api::EnclaveOnChainOCallApi;
-use itp_pallet_storage::{EnclaveBridgeStorage, EnclaveBridgeStorageKeys};
+use itp_pallet_storage::{
+	EnclaveBridgeStorage, EnclaveBridgeStorageKeys, SystemStorage, SystemStorageKeys,
+};
 use itp_types::{
-	parentchain::{AccountId, ParentchainId},
+	parentchain::{AccountId, AccountInfo, Balance, ParentchainId},
 	ShardSignerStatus,
 };
 use its_primitives::traits::{Block as SidechainBlockTrait, Header as HeaderTrait, SignedBlock};
@@ -28,6 +30,13 @@ use sp_core::H256;
 use sp_runtime::traits::Header as HeaderT;
 use sp_std::prelude::Vec;
 
+/// Minimal weight of a validateer, e.g. if its account does not exist on the parentchain or does
+/// not have any stake. Every validateer keeps a share of the slots.
+pub const DEFAULT_VALIDATEER_WEIGHT: u64 = 1;
+
+/// Stake that corresponds to a weight of one, i.e. one TEER.
+pub const VALIDATEER_STAKE_UNIT: Balance = 1_000_000_000_000;
+
 type ShardIdentifierFor<SignedSidechainBlock> =
 <<<SignedSidechainBlock as SignedBlock>::Block as SidechainBlockTrait>::HeaderType as HeaderTrait>::ShardIdentifier;
 
@@ -40,6 +49,20 @@ pub trait ValidateerFetch {
 		latest_header: &Header,
 		shard: ShardIdentifierFor<SignedSidechainBlock>,
 	) -> Result<Vec<AccountId>>;
+	/// Current validateers, together with their weight for the slot assignment.
+	///
+	/// The weight is the reserved balance of the validateer's account on the Integritee
+	/// parentchain, in units of [`VALIDATEER_STAKE_UNIT`], but at least
+	/// [`DEFAULT_VALIDATEER_WEIGHT`]. Unlike the free balance, the reserved balance can't be spent
+	/// on fees or transferred away.
+	fn current_weighted_validateers<
+		Header: HeaderT<Hash = H256>,
+		SignedSidechainBlock: its_primitives::traits::SignedBlock,
+	>(
+		&self,
+		latest_header: &Header,
+		shard: ShardIdentifierFor<SignedSidechainBlock>,
+	) -> Result<Vec<(AccountId, u64)>>;
 	fn validateer_count<
 		Header: HeaderT<Hash = H256>,
 		SignedSidechainBlock: its_primitives::traits::SignedBlock,
@@ -74,6 +97,39 @@ impl<OnchainStorage: EnclaveOnChainOCallApi> ValidateerFetch for OnchainStorage
 		Ok(shard_status.iter().map(|sss: &ShardSignerStatus| sss.signer.clone()).collect())
 	}
 
+	fn current_weighted_validateers<
+		Header: HeaderT<Hash = H256>,
+		SignedSidechainBlock: its_primitives::traits::SignedBlock,
+	>(
+		&self,
+		header: &Header,
+		shard: ShardIdentifierFor<SignedSidechainBlock>,
+	) -> Result<Vec<(AccountId, u64)>> {
+		let validateers =
+			self.current_validateers::<Header, SignedSidechainBlock>(header, shard)?;
+		let accounts: Vec<Option<AccountInfo>> = self
+			.get_multiple_storages_verified(
+				validateers.iter().map(SystemStorage::account).collect(),
+				header,
+				&ParentchainId::Integritee,
+			)?
+			.into_iter()
+			.map(|entry| entry.into_tuple().1)
+			.collect();
+		trace!("fetched {} validateer accounts for shard {:?}", accounts.len(), shard);
+
+		Ok(validateers
+			.into_iter()
+			.zip(accounts)
+			.map(|(validateer, account)| {
+				let stake = account.map_or(0, |account| {
+					(account.data.reserved / VALIDATEER_STAKE_UNIT).try_into().unwrap_or(u64::MAX)
+				});
+				(validateer, stake.max(DEFAULT_VALIDATEER_WEIGHT))
+			})
+			.collect())
+	}
+
 	fn validateer_count<
 		Header: HeaderT<Hash = H256>,
 		SignedSidechainBlock: its_primitives::traits::SignedBlock,
@@ -108,6 +164,68 @@ mod tests {
 		);
 	}
 
+	#[test]
+	pub fn get_weighted_validateers_defaults_to_equal_weights_without_accounts() {
+		let header = ParentchainHeaderBuilder::default().build();
+		let shard = ShardIdentifier::default();
+		let mock = OnchainMock::default().add_validateer_set(&header, shard, None);
+
+		let weighted_validateers = mock
+			.current_weighted_validateers::<itp_types::Header, its_primitives::types::SignedBlock>(
+				&header, shard,
+			)
+			.unwrap();
+
+		assert_eq!(
+			weighted_validateers,
+			validateer_set()
+				.into_iter()
+				.map(|v| (v, DEFAULT_VALIDATEER_WEIGHT))
+				.collect::<Vec<_>>()
+		);
+	}
+
+	#[test]
+	pub fn get_weighted_validateers_weighs_by_reserved_stake() {
+		let header = ParentchainHeaderBuilder::default().build();
+		let shard = ShardIdentifier::default();
+		let validateers = validateer_set();
+		let account_with_stake = |reserved: Balance, free: Balance| {
+			let mut account = AccountInfo::default();
+			account.data.reserved = reserved;
+			account.data.free = free;
+			account
+		};
+		let mock = OnchainMock::default()
+			.add_validateer_set(&header, shard, None)
+			.with_storage_entries_at_header(
+				&header,
+				vec![
+					(
+						SystemStorage::account(&validateers[1]),
+						account_with_stake(5 * VALIDATEER_STAKE_UNIT, 0),
+					),
+					(
+						SystemStorage::account(&validateers[2]),
+						account_with_stake(VALIDATEER_STAKE_UNIT / 2, 0),
+					),
+					(
+						SystemStorage::account(&validateers[3]),
+						account_with_stake(0, 100 * VALIDATEER_STAKE_UNIT),
+					),
+				],
+			);
+
+		let weighted_validateers = mock
+			.current_weighted_validateers::<itp_types::Header, its_primitives::types::SignedBlock>(
+				&header, shard,
+			)
+			.unwrap();
+
+		let weights: Vec<u64> = weighted_validateers.iter().map(|(_, w)| *w).collect();
+		assert_eq!(weights, vec![1, 5, 1, 1]);
+	}
+
 	#[test]
 	pub fn get_validateer_set_works() {
 		let header = ParentchainHeaderBuilder::default().build();